@@ -1,12 +1,15 @@
 use crate::equivalence::EquivalenceResult;
 use clap::ValueEnum;
 use dandy::dfa::parse::DfaParseError;
-use dandy::dfa::Dfa;
+use dandy::dfa::{AlphabetDiff, Dfa};
+use dandy::io::{Automaton, AutomatonType, LoadError};
 use dandy::nfa::parse::NfaParseError;
 use dandy::nfa::Nfa;
-use dandy::parser;
+use dandy::parser::{self, Detected};
 use dandy::regex::Regex;
+use std::fmt::{self, Display, Formatter};
 use std::path::Path;
+use std::rc::Rc;
 use std::{fs, io};
 use thiserror::Error;
 
@@ -14,16 +17,32 @@ use thiserror::Error;
 pub enum Error<'a> {
     #[error("Error loading file {0}: {1}")]
     File(&'a Path, io::Error),
-    #[error("Error parsing DFA: {0}")]
+    #[error("Error parsing DFA: {0}{}", type_hint(parser::suggest_type(.0.input, Detected::Dfa)))]
     DfaParse(nom::error::Error<&'a str>),
     #[error("Error compiling DFA: {0}")]
     DfaCompile(DfaParseError<'a>),
-    #[error("Error parsing DFA: {0}")]
+    #[error("Error parsing NFA: {0}{}", type_hint(parser::suggest_type(.0.input, Detected::Nfa)))]
     NfaParse(nom::error::Error<&'a str>),
-    #[error("Error compiling DFA: {0}")]
+    #[error("Error compiling NFA: {0}")]
     NfaCompile(NfaParseError<'a>),
     #[error("Error parsing Regex: {0}")]
     RegexParse(nom::error::Error<&'a str>),
+    #[error(
+        "The Regex contains elements not in the alphabet declared by its alphabet pragma {0:?}"
+    )]
+    RegexAlphabet(Vec<String>),
+    #[error("Could not detect if the file contains a DFA, NFA or Regex, use --type to specify it")]
+    UnknownType,
+}
+
+/// Gives a hint to add to an error from parsing a table as a DFA or NFA, if the table looks like the other type of
+/// table (`suggested`, see [parser::suggest_type]), or an empty string otherwise
+pub fn type_hint(suggested: Option<Detected>) -> &'static str {
+    match suggested {
+        Some(Detected::Nfa) => "\nhint: this looks like an NFA table — did you mean --type nfa?",
+        Some(Detected::Dfa) => "\nhint: this looks like a DFA table — did you mean --type dfa?",
+        _ => "",
+    }
 }
 
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
@@ -32,9 +51,36 @@ pub enum AutomataType {
     Dfa,
     Nfa,
     Regex,
+    /// Detect the type from the contents of each file
+    Auto,
 }
 
 impl AutomataType {
+    /// Infers the type of a file from its extension, where the extensions `dfa`, `nfa` and `regex` give their
+    /// respective types (ignoring case). The extensions in `overrides` (without leading dots) take precedence over
+    /// those. Returns `None` if the extension isn't known.
+    pub fn from_extension(path: &Path, overrides: &[(String, AutomataType)]) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        if let Some((_, r#type)) = overrides.iter().find(|(ext, _)| *ext == extension) {
+            return Some(*r#type);
+        }
+        match extension.as_str() {
+            "dfa" => Some(AutomataType::Dfa),
+            "nfa" => Some(AutomataType::Nfa),
+            "regex" => Some(AutomataType::Regex),
+            _ => None,
+        }
+    }
+
+    /// Parses a mapping from an extension to a type, such as `rx=regex`, for use with [AutomataType::from_extension]
+    pub fn parse_extension_mapping(s: &str) -> Result<(String, AutomataType), String> {
+        let (extension, r#type) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected EXTENSION=TYPE (such as rx=regex), got '{s}'"))?;
+        let r#type = AutomataType::from_str(r#type, true)?;
+        Ok((extension.trim_start_matches('.').to_lowercase(), r#type))
+    }
+
     pub fn to_string(self, multiple: bool) -> &'static str {
         match (self, multiple) {
             (AutomataType::Dfa, true) => "DFAs",
@@ -43,6 +89,33 @@ impl AutomataType {
             (AutomataType::Nfa, false) => "NFA",
             (AutomataType::Regex, true) => "Regexes",
             (AutomataType::Regex, false) => "Regex",
+            (AutomataType::Auto, true) => "automata",
+            (AutomataType::Auto, false) => "automaton",
+        }
+    }
+
+    /// Gives the type of the given file contents, which is this type unless it is [AutomataType::Auto], in which case
+    /// the type is detected by [parser::detect]. Returns `None` if the type can't be detected.
+    pub fn resolve(self, file: &str) -> Option<Self> {
+        if self != AutomataType::Auto {
+            return Some(self);
+        }
+        match parser::detect(file) {
+            Detected::Dfa => Some(AutomataType::Dfa),
+            Detected::Nfa => Some(AutomataType::Nfa),
+            Detected::Regex => Some(AutomataType::Regex),
+            Detected::Unknown => None,
+        }
+    }
+}
+
+impl From<AutomataType> for AutomatonType {
+    fn from(value: AutomataType) -> Self {
+        match value {
+            AutomataType::Dfa => AutomatonType::Dfa,
+            AutomataType::Nfa => AutomatonType::Nfa,
+            AutomataType::Regex => AutomatonType::Regex,
+            AutomataType::Auto => AutomatonType::Auto,
         }
     }
 }
@@ -51,7 +124,30 @@ impl AutomataType {
 pub enum Automata {
     Dfa(Dfa),
     Nfa(Nfa),
-    Regex(Regex),
+    /// A Regex, along with the alphabet declared by its alphabet pragma, if it has one
+    Regex(Regex, Option<Vec<String>>),
+}
+
+/// Reads a file to a string like [fs::read_to_string], but also accepts UTF-16 files and files starting with a byte
+/// order mark (see [parser::decode]). If the file can't be decoded, the error explains what encoding it seems to
+/// have, rather than just saying that it isn't valid UTF-8.
+pub fn read_file(path: &Path) -> io::Result<String> {
+    let bytes = fs::read(path)?;
+    parser::decode(&bytes)
+        .map(|(text, _)| text.into_owned())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Converts a Regex to a NFA over the given alphabet (such as one declared by an alphabet pragma), or over the
+/// elements appearing in it if there is none. Returns `None` if the Regex contains elements not in the alphabet.
+pub fn regex_to_nfa(regex: Regex, alphabet: Option<&[String]>) -> Option<Nfa> {
+    match alphabet {
+        Some(alphabet) => {
+            let alphabet = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+            regex.to_nfa_with_alphabet(&alphabet).ok()
+        }
+        None => Some(regex.to_nfa()),
+    }
 }
 
 #[allow(dead_code)]
@@ -61,36 +157,52 @@ impl Automata {
         match self {
             Automata::Dfa(_) => AutomataType::Dfa,
             Automata::Nfa(_) => AutomataType::Nfa,
-            Automata::Regex(_) => AutomataType::Regex,
+            Automata::Regex(..) => AutomataType::Regex,
         }
     }
 
-    /// Loads an automata of any type by reading and parsing it from a file.
+    /// Loads an automata of any type by reading and parsing it from a file, see [dandy::io::read_automaton]. Parse
+    /// errors give the path and line of the problem.
     pub fn load_file(path: &Path, r#type: AutomataType) -> Result<Self, String> {
-        let file = fs::read_to_string(path).map_err(|e| Error::File(path, e).to_string());
-        file.and_then(|f| Self::load(&f, r#type).map_err(|e| e.to_string()))
+        let automaton = dandy::io::read_automaton(path, r#type.into()).map_err(|e| match e {
+            LoadError::UnknownType { .. } => Error::UnknownType.to_string(),
+            LoadError::Parse { suggested, .. } => format!("{e}{}", type_hint(suggested)),
+            e => e.to_string(),
+        })?;
+        Ok(match automaton {
+            Automaton::Dfa(dfa) => Automata::Dfa(dfa),
+            Automaton::Nfa(nfa) => Automata::Nfa(nfa),
+            Automaton::Regex(regex, alphabet) => Automata::Regex(regex, alphabet),
+        })
     }
 
     /// Loads an automata of any type by parsing it from a string.
-    pub fn load(file: &str, r#type: AutomataType) -> Result<Self, Error> {
-        match r#type {
+    pub fn load(file: &str, r#type: AutomataType) -> Result<Self, Error<'_>> {
+        match r#type.resolve(file).ok_or(Error::UnknownType)? {
             AutomataType::Dfa => {
-                let dfa: Dfa = parser::dfa(file)
+                let dfa: Dfa = parser::dfa_file(file)
                     .map_err(Error::DfaParse)?
                     .try_into()
                     .map_err(Error::DfaCompile)?;
                 Ok(Self::Dfa(dfa))
             }
             AutomataType::Nfa => {
-                let nfa: Nfa = parser::nfa(file)
+                let nfa: Nfa = parser::nfa_file(file)
                     .map_err(Error::NfaParse)?
                     .try_into()
                     .map_err(Error::NfaCompile)?;
                 Ok(Self::Nfa(nfa))
             }
-            AutomataType::Regex => parser::regex(file)
-                .map(Self::Regex)
-                .map_err(Error::RegexParse),
+            AutomataType::Regex => {
+                let (regex, alphabet) = parser::regex_file(file).map_err(Error::RegexParse)?;
+                if let Some(alphabet) = alphabet {
+                    regex_to_nfa(regex.clone(), Some(&alphabet))
+                        .ok_or_else(|| Error::RegexAlphabet(alphabet.clone()))?;
+                    return Ok(Self::Regex(regex, Some(alphabet)));
+                }
+                Ok(Self::Regex(regex, None))
+            }
+            AutomataType::Auto => unreachable!("The type is resolved"),
         }
     }
 
@@ -124,13 +236,23 @@ impl Automata {
         (Self::Dfa(dfa), converted)
     }
 
+    /// Converts this Automata to an NFA minimized by conversion to a minimized DFA and back (independent of automata
+    /// type), see [Nfa::minimize_via_dfa], wrapped in this Automata enum
+    pub fn into_minimized_nfa_automata(self) -> Self {
+        let (mut nfa, _) = self.into_nfa();
+        nfa.minimize_via_dfa();
+        Self::Nfa(nfa)
+    }
+
     /// Converts this Automata to a DFA (independent of automata type). Returns the DFA and a bool indicating
     /// whether or not a conversion occurred.
     pub fn into_dfa(self) -> (Dfa, bool) {
         match self {
             Automata::Dfa(dfa) => (dfa, false),
             Automata::Nfa(nfa) => (nfa.to_dfa(), true),
-            Automata::Regex(regex) => (regex.to_nfa().to_dfa(), true),
+            Automata::Regex(regex, alphabet) => {
+                (Self::checked_regex_to_nfa(regex, alphabet).to_dfa(), true)
+            }
         }
     }
 
@@ -141,6 +263,13 @@ impl Automata {
         (Self::Dfa(dfa), converted)
     }
 
+    /// Converts a Regex loaded by [Automata::load] to a NFA, over its declared alphabet if it has one (which is
+    /// checked to be possible when loading)
+    fn checked_regex_to_nfa(regex: Regex, alphabet: Option<Vec<String>>) -> Nfa {
+        regex_to_nfa(regex, alphabet.as_deref())
+            .expect("The alphabet of the Regex is checked when it is loaded")
+    }
+
     /// Borrows this Automata as a DFA, accessing the value within
     pub fn borrow_dfa(&self) -> Option<&Dfa> {
         match self {
@@ -155,7 +284,7 @@ impl Automata {
         match self {
             Automata::Dfa(dfa) => (dfa.to_nfa(), true),
             Automata::Nfa(nfa) => (nfa, false),
-            Automata::Regex(regex) => (regex.to_nfa(), true),
+            Automata::Regex(regex, alphabet) => (Self::checked_regex_to_nfa(regex, alphabet), true),
         }
     }
 
@@ -182,13 +311,14 @@ impl Automata {
             AutomataType::Dfa => Some(self.into_dfa_automata()),
             AutomataType::Nfa => Some(self.into_to_nfa_automata()),
             AutomataType::Regex => {
-                if let Self::Regex(regex) = self {
-                    Some((Self::Regex(regex), false))
+                if let Self::Regex(..) = self {
+                    Some((self, false))
                 } else {
                     eprintln!("Cannot convert DFA/NFA to Regex");
                     None
                 }
             }
+            AutomataType::Auto => None,
         }
     }
 
@@ -198,26 +328,17 @@ impl Automata {
     pub fn prepare_to_compare_with(self, other: AutomataType) -> (Self, bool) {
         match other {
             AutomataType::Dfa => self.into_dfa_automata(),
-            AutomataType::Nfa | AutomataType::Regex => self.into_to_nfa_automata(),
+            AutomataType::Nfa | AutomataType::Regex | AutomataType::Auto => {
+                self.into_to_nfa_automata()
+            }
         }
     }
 
     pub fn test_equivalence(&self, other: Self, minimized: bool) -> EquivalenceResult {
-        macro_rules! warn_minimized_check_type {
-            ($m:expr, $t:expr) => {
-                if $t.get_type() == T::Dfa && $m {
-                    eprintln!("Minimized option ignored: make sure the base automata is the correct type.");
-                    eprintln!("This is most likely an internal error; please send a bug report")
-                } else {
-                    warn_minimized!($m);
-                }
-            };
-        }
-
         macro_rules! warn_minimized {
             ($m:expr) => {
                 if $m {
-                    eprintln!("Can only check minimization if the tested type is a DFA")
+                    eprintln!("Can only check minimization if the tested type is a DFA or NFA")
                 }
             };
         }
@@ -228,7 +349,9 @@ impl Automata {
             (T::Dfa, T::Dfa) => {
                 let dfa1 = self.borrow_dfa().unwrap();
                 let dfa2 = other.borrow_dfa().unwrap();
-                if dfa1.equivalent_to(dfa2) {
+                if let Some(mismatch) = AlphabetMismatch::between_dfas(dfa1, dfa2) {
+                    DifferentAlphabets(mismatch.missing_and_unexpected())
+                } else if dfa1.equivalent_to(dfa2) {
                     if minimized && dfa1.states().len() != dfa2.states().len() {
                         NotMinimized
                     } else {
@@ -242,18 +365,26 @@ impl Automata {
                 warn_minimized!(minimized);
                 let dfa1 = self.borrow_dfa().unwrap();
                 let (dfa2, _) = other.into_dfa();
-                if dfa1.equivalent_to(&dfa2) {
+                if let Some(mismatch) = AlphabetMismatch::between_dfas(dfa1, &dfa2) {
+                    DifferentAlphabets(mismatch.missing_and_unexpected())
+                } else if dfa1.equivalent_to(&dfa2) {
                     Equivalent
                 } else {
                     NotEquivalent
                 }
             }
             (T::Nfa, _) => {
-                warn_minimized_check_type!(minimized, other);
                 let nfa1 = self.borrow_nfa().unwrap();
                 let (nfa2, _) = other.into_nfa();
-                if nfa1.equivalent_to(&nfa2) {
-                    Equivalent
+                if let Some(mismatch) = AlphabetMismatch::between_nfas(nfa1, &nfa2) {
+                    DifferentAlphabets(mismatch.missing_and_unexpected())
+                } else if nfa1.equivalent_to(&nfa2) {
+                    // NFAs can't be checked to be truly minimal, so they are compared to the NFA minimized via DFA
+                    if minimized && nfa2.states().len() > nfa1.states().len() {
+                        NotMinimized
+                    } else {
+                        Equivalent
+                    }
                 } else {
                     NotEquivalent
                 }
@@ -264,23 +395,139 @@ impl Automata {
                 warn_minimized!(minimized);
                 let (dfa1, _) = self.clone().into_dfa();
                 let (dfa2, _) = other.into_dfa();
-                if dfa1.equivalent_to(&dfa2) {
+                if let Some(mismatch) = AlphabetMismatch::between_dfas(&dfa1, &dfa2) {
+                    DifferentAlphabets(mismatch.missing_and_unexpected())
+                } else if dfa1.equivalent_to(&dfa2) {
                     Equivalent
                 } else {
                     NotEquivalent
                 }
             }
+            (T::Auto, _) => unreachable!("Loaded automatas have a resolved type"),
         }
     }
 }
 
+/// Lists elements of alphabets in double quotes separated by commas, such as `"a", "b"`
+pub fn quoted_list<T: Display>(elems: impl IntoIterator<Item = T>) -> String {
+    elems
+        .into_iter()
+        .map(|e| format!("\"{e}\""))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Two alphabets which don't contain the same elements, such as the alphabets of the inputs of a binary operation.
+/// Displayed as `Left alphabet: {a, b, c}, Right alphabet: {a, b, d}. Missing from left: {d}. Missing from right: {c}.`
+#[derive(Debug, Clone)]
+pub struct AlphabetMismatch {
+    left: Vec<String>,
+    right: Vec<String>,
+    diff: AlphabetDiff,
+}
+
+impl AlphabetMismatch {
+    /// Compares the alphabets of two DFAs, giving `None` if they contain the same elements
+    pub fn between_dfas(left: &Dfa, right: &Dfa) -> Option<Self> {
+        Self::new(left.alphabet(), right.alphabet(), left.alphabet_diff(right))
+    }
+
+    /// Compares the alphabets of two NFAs, giving `None` if they contain the same elements
+    pub fn between_nfas(left: &Nfa, right: &Nfa) -> Option<Self> {
+        Self::new(left.alphabet(), right.alphabet(), left.alphabet_diff(right))
+    }
+
+    fn new(left: &[Rc<str>], right: &[Rc<str>], diff: AlphabetDiff) -> Option<Self> {
+        let strings = |elems: &[Rc<str>]| elems.iter().map(|e| e.to_string()).collect();
+        (!diff.is_equal()).then(|| Self {
+            left: strings(left),
+            right: strings(right),
+            diff,
+        })
+    }
+
+    /// Describes the elements the right alphabet is missing and the elements it shouldn't have, compared to the left
+    /// alphabet, such as `missing "c", unexpected "d"`
+    pub fn missing_and_unexpected(&self) -> String {
+        let mut parts = vec![];
+        if !self.diff.only_in_self.is_empty() {
+            parts.push(format!("missing {}", quoted_list(&self.diff.only_in_self)));
+        }
+        if !self.diff.only_in_other.is_empty() {
+            parts.push(format!(
+                "unexpected {}",
+                quoted_list(&self.diff.only_in_other)
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+impl Display for AlphabetMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let set = |elems: &mut dyn Iterator<Item = &str>| {
+            format!("{{{}}}", elems.collect::<Vec<_>>().join(", "))
+        };
+        write!(
+            f,
+            "Left alphabet: {}, Right alphabet: {}. Missing from left: {}. Missing from right: {}.",
+            set(&mut self.left.iter().map(String::as_str)),
+            set(&mut self.right.iter().map(String::as_str)),
+            set(&mut self.diff.only_in_other.iter().map(|e| e.as_ref())),
+            set(&mut self.diff.only_in_self.iter().map(|e| e.as_ref())),
+        )
+    }
+}
+
 impl Automata {
+    /// Checks if this automata is equivalent to another automata after renaming the symbols of the other automata by
+    /// `mapping` (pairs of a symbol of the other automata and the symbol it is renamed to), see
+    /// [Dfa::equivalent_to_under]. DFAs are compared to the other automata as a DFA, and other automatas as NFAs.
+    pub fn test_equivalence_under(
+        &self,
+        other: Self,
+        mapping: &[(&str, &str)],
+    ) -> EquivalenceResult {
+        let result = match self {
+            Automata::Dfa(dfa) => dfa.equivalent_to_under(&other.into_dfa().0, mapping),
+            Automata::Nfa(nfa) => nfa.equivalent_to_under(&other.into_nfa().0, mapping),
+            Automata::Regex(..) => {
+                let (nfa, _) = self.clone().into_nfa();
+                nfa.equivalent_to_under(&other.into_nfa().0, mapping)
+            }
+        };
+        match result {
+            Ok(true) => EquivalenceResult::Equivalent,
+            Ok(false) => EquivalenceResult::NotEquivalent,
+            Err(e) => EquivalenceResult::InvalidMapping(e.to_string()),
+        }
+    }
+
+    /// Checks if this automata is isomorphic to another automata of the same type, see [Dfa::isomorphic_to] and
+    /// [Nfa::isomorphic_to]. Automatas of different types, Regexes and NFAs too symmetric to be checked are never
+    /// considered isomorphic.
+    pub fn isomorphic_to(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Automata::Dfa(dfa1), Automata::Dfa(dfa2)) => dfa1.isomorphic_to(dfa2),
+            (Automata::Nfa(nfa1), Automata::Nfa(nfa2)) => nfa1.isomorphic_to(nfa2).unwrap_or(false),
+            _ => false,
+        }
+    }
+
     // TODO: Rewrite this
     pub fn load_test(file: &str, r#type: AutomataType) -> Result<Self, EquivalenceResult> {
+        let r#type = r#type
+            .resolve(file)
+            .ok_or_else(|| EquivalenceResult::FailedToParse(Error::UnknownType.to_string()))?;
         match r#type {
             AutomataType::Dfa => {
-                let dfa = parser::dfa(file)
-                    .map_err(|e| EquivalenceResult::FailedToParse(e.to_string()))?
+                let dfa = parser::dfa_file(file)
+                    .map_err(|e| {
+                        EquivalenceResult::FailedToParse(format!(
+                            "{e}{}",
+                            type_hint(parser::suggest_type(e.input, Detected::Dfa))
+                        ))
+                    })?
                     .try_into()
                     .map_err(|e: DfaParseError| {
                         EquivalenceResult::FailedToValidate(e.to_string())
@@ -288,8 +535,13 @@ impl Automata {
                 Ok(Automata::Dfa(dfa))
             }
             AutomataType::Nfa => {
-                let nfa = parser::nfa(file)
-                    .map_err(|e| EquivalenceResult::FailedToParse(e.to_string()))?
+                let nfa = parser::nfa_file(file)
+                    .map_err(|e| {
+                        EquivalenceResult::FailedToParse(format!(
+                            "{e}{}",
+                            type_hint(parser::suggest_type(e.input, Detected::Nfa))
+                        ))
+                    })?
                     .try_into()
                     .map_err(|e: NfaParseError| {
                         EquivalenceResult::FailedToValidate(e.to_string())
@@ -297,20 +549,26 @@ impl Automata {
                 Ok(Automata::Nfa(nfa))
             }
             AutomataType::Regex => {
-                let regex = parser::regex(file)
+                let (regex, alphabet) = parser::regex_file(file)
                     .map_err(|e| EquivalenceResult::FailedToParse(e.to_string()))?;
-                let nfa = regex.to_nfa();
+                let nfa = regex_to_nfa(regex, alphabet.as_deref()).ok_or_else(|| {
+                    let alphabet = alphabet.unwrap_or_default();
+                    EquivalenceResult::FailedToValidate(Error::RegexAlphabet(alphabet).to_string())
+                })?;
                 Ok(Automata::Nfa(nfa)) // We don't really need to reduce states here as much, since
                                        // base testing with has fewer states
             }
+            AutomataType::Auto => unreachable!("The type is resolved"),
         }
     }
 
-    pub fn table(&self) -> String {
+    /// Gets a description of how the automata was created, or `None` if it was parsed from text (or is a Regex)
+    #[cfg(feature = "provenance")]
+    pub fn provenance(&self) -> Option<&str> {
         match self {
-            Automata::Dfa(dfa) => dfa.to_table(),
-            Automata::Nfa(nfa) => nfa.to_table(),
-            Automata::Regex(regex) => regex.to_string(),
+            Automata::Dfa(dfa) => dfa.provenance(),
+            Automata::Nfa(nfa) => nfa.provenance(),
+            Automata::Regex(..) => None,
         }
     }
 }
@@ -1,14 +1,22 @@
-use crate::automata::Automata;
+use crate::automata::{AlphabetMismatch, Automata, AutomataType};
+use crate::enumerate::{enumerate_words, only_words_note};
 use crate::equivalence::EquivalenceResult;
-use crate::{BinaryOpArgs, BinaryOperation, DandyArgs};
+use crate::stats::{Phase, Stats};
+use crate::{BinaryOpArgs, BinaryOperation, DandyArgs, Outcome};
+use dandy::dfa::{Dfa, ProductOperation, ProductTooLarge};
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::Path;
 use thiserror::Error;
 
 pub fn binary_op(
     main_args: &DandyArgs,
     args: &BinaryOpArgs,
     op: BinaryOperation,
+    stats: &mut Stats,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
-) -> Result<(), String> {
+) -> Result<Outcome, String> {
     #[allow(unused_variables)]
     let log = |s: &str| {
         if !main_args.no_log {
@@ -16,108 +24,196 @@ pub fn binary_op(
         }
     };
     macro_rules! log {
-        ($($t:tt)*) => (log(&format!($($t)*)))
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
     }
 
     macro_rules! output {
-        ($($t:tt)*) => (output(&format!($($t)*)))
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
     }
 
-    let (mut dfa1, converted1) = Automata::load_file(&args.first, args.r#type)
-        .map_err(|e| Error::InputFile(1, e).to_string())?
-        .into_minimized_dfa_if_not_dfa();
+    let (mut dfa1, converted1) = load_dfa(&args.first, args.r#type, stats)
+        .map_err(|e| Error::InputFile(1, e).to_string())?;
     let (mut dfa2, converted2) =
-        Automata::load_file(&args.second, args.second_type.unwrap_or(args.r#type))
-            .map_err(|e| Error::InputFile(2, e).to_string())?
-            .into_minimized_dfa_if_not_dfa();
+        load_dfa(&args.second, args.second_type.unwrap_or(args.r#type), stats)
+            .map_err(|e| Error::InputFile(2, e).to_string())?;
 
     if converted1 {
         log!("Input file 1 was converted to a minimized DFA to proceed, since it wasn't a DFA to start with");
     } else if args.minimized {
-        dfa1.minimize();
+        stats.time(Phase::Minimize, || dfa1.minimize());
         log!("Minimized DFA 1 before doing product construction");
     }
 
     if converted2 {
         log!("Input file 2 was converted to a minimized DFA to proceed, since it wasn't a DFA to start with");
     } else if args.minimized {
-        dfa2.minimize();
+        stats.time(Phase::Minimize, || dfa2.minimize());
         log!("Minimized DFA 2 before doing product construction");
     }
 
-    let combined = match op {
-        BinaryOperation::Union => dfa1.union(&dfa2),
-        BinaryOperation::Intersection => dfa1.intersection(&dfa2),
-        BinaryOperation::Difference => dfa1.difference(&dfa2),
-        BinaryOperation::SymmetricDifference => dfa1.symmetric_difference(&dfa2),
+    // The concatenation isn't a product construction, and is always minimized
+    let product = match op {
+        BinaryOperation::Union => Some(ProductOperation::Union),
+        BinaryOperation::Intersection => Some(ProductOperation::Intersection),
+        BinaryOperation::Difference => Some(ProductOperation::Difference),
+        BinaryOperation::SymmetricDifference => Some(ProductOperation::SymmetricDifference),
+        BinaryOperation::Concatenation => None,
     };
+    if let Some(mismatch) = AlphabetMismatch::between_dfas(&dfa1, &dfa2) {
+        return Err(Error::DifferentAlphabets(mismatch).to_string());
+    }
+    let max_states = args.max_states.unwrap_or(usize::MAX);
+    let combined = stats.time(Phase::Construct, || match product {
+        Some(product) => dfa1.product_operation_bounded(&dfa2, product, max_states, None),
+        None => dfa1.concatenate(&dfa2).map(Ok),
+    });
 
-    let Some(mut combined) = combined else {
-        return Err(Error::DifferentAlphabets.to_string());
+    let mut combined = match combined {
+        Some(Ok(combined)) => combined,
+        Some(Err(e)) => return Err(Error::TooLarge(op.as_str_lower(), e).to_string()),
+        None => unreachable!("The alphabets are equal"),
     };
 
-    if args.minimized {
-        combined.minimize();
+    stats.peak(combined.states().len());
+
+    #[cfg(feature = "provenance")]
+    if let Some(provenance) = combined.provenance() {
+        log!("Created by {provenance}");
+    }
+
+    if args.minimized || product.is_none() {
+        stats.time(Phase::Minimize, || combined.minimize());
         log!(
             "Minimized DFA ({} of the two provided {}):",
             op.as_str(),
             args.r#type.to_string(true)
         );
-        output!("{}", combined.to_table());
+        output(&main_args.style.dfa_table(&combined));
     } else {
         log!(
             "{} of {} (not minimized, add --minimized to minimize):",
             op.as_str(),
             args.r#type.to_string(true)
         );
-        output!("{}", combined.to_table());
+        output(&main_args.style.dfa_table(&combined));
     }
+    stats.output(&combined);
 
     if let Some(n) = args.enumerate {
-        output!(
+        let mut words_file = args
+            .enumerate_out
+            .as_ref()
+            .map(|path| File::create(path).map_err(|e| Error::EnumerateOut(e).to_string()))
+            .transpose()?;
+        log!(
             "First {n} words in the language of the {}:",
             op.as_str_lower()
         );
-        let mut x = 0;
-        combined.clone().to_nfa().words().take(n).for_each(|word| {
-            if word.is_empty() {
-                output!("(empty word)");
-            } else {
-                output!("{word}");
+        // Converting to a NFA consumes the DFA, and the combined DFA is still needed for the
+        // comparison below, so the words are enumerated from a clone of it
+        let nfa = combined.clone().to_nfa();
+        let x = enumerate_words(&nfa, main_args, n, args.max_word_length, |word| {
+            match words_file.as_mut() {
+                Some(f) => writeln!(f, "{word}").map_err(|e| Error::EnumerateOut(e).to_string())?,
+                None => output!("{word}"),
             }
-            x += 1;
-        });
+            Ok(())
+        })?;
         if x != n {
-            output!("(only {x} words exists in the {})", op.as_str_lower());
+            log!(
+                "{}",
+                only_words_note(x, args.max_word_length, op.as_str_lower())
+            );
         }
     }
 
+    let mut outcome = Outcome::Passed;
     if let Some(path) = &args.compare_against {
         // We load the other DFA and then check equivalence to this DFA
-        let compare_to = Automata::load_file(path, args.compared_type)
+        let compare_to = stats
+            .time(Phase::Parse, || {
+                Automata::load_file(path, args.compared_type)
+            })
             .map_err(|e| Error::CompareTo(e).to_string())?;
-        let result = match Automata::Dfa(combined).test_equivalence(compare_to, false) {
-            EquivalenceResult::Equivalent => "EQUIVALENT",
-            _ => "NOT EQUIVALENT",
-        };
+        let equivalent = stats.time(Phase::Compare, || {
+            Automata::Dfa(combined).test_equivalence(compare_to, false)
+        }) == EquivalenceResult::Equivalent;
+        outcome = Outcome::passed_if(equivalent);
         output!(
             "{} of the two provided {} is {} to the third {}",
             op,
             args.r#type.to_string(true),
-            result,
+            if equivalent {
+                "EQUIVALENT"
+            } else {
+                "NOT EQUIVALENT"
+            },
             args.compared_type.to_string(false)
         );
     }
 
-    Ok(())
+    Ok(outcome)
+}
+
+/// Loads an operand and converts it to a minimized DFA if it isn't a DFA, like
+/// [Automata::into_minimized_dfa_if_not_dfa], recording its size and the durations in `stats`
+fn load_dfa(path: &Path, r#type: AutomataType, stats: &mut Stats) -> Result<(Dfa, bool), String> {
+    let automata = stats.time(Phase::Parse, || Automata::load_file(path, r#type))?;
+    stats.input(path, &automata);
+    let (mut dfa, converted) = stats.time(Phase::Convert, || automata.into_dfa());
+    if converted {
+        stats.peak(dfa.states().len());
+        stats.time(Phase::Minimize, || dfa.minimize());
+    }
+    Ok((dfa, converted))
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Different alphabets in input DFAs, can't do product construction")]
-    DifferentAlphabets,
+    #[error("Different alphabets in input DFAs, can't do product construction. {0}")]
+    DifferentAlphabets(AlphabetMismatch),
     #[error("Error comparing with automata: {0}")]
     CompareTo(String),
     #[error("Error reading {0}: {1}")]
     InputFile(usize, String),
+    #[error("Error writing enumerated words: {0}")]
+    EnumerateOut(io::Error),
+    #[error(
+        "The {0} has more than {max} states (after exploring {explored} pairs of states{}), raise --max-states to \
+         compute it anyway",
+        theoretical_max(.1),
+        max = .1.max_states,
+        explored = .1.explored
+    )]
+    TooLarge(&'static str, ProductTooLarge),
+}
+
+fn theoretical_max(e: &ProductTooLarge) -> String {
+    e.theoretical_max
+        .map(|max| format!(", out of at most {max}"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dandy::parser;
+
+    #[test]
+    fn different_alphabets_error_lists_alphabets() {
+        let left: Dfa = parser::dfa("a b c\n-> * s s s s")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let right: Dfa = parser::dfa("a b d\n-> * s s s s")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert!(left.union(&right).is_none());
+        assert_eq!(
+            Error::DifferentAlphabets(AlphabetMismatch::between_dfas(&left, &right).unwrap()).to_string(),
+            "Different alphabets in input DFAs, can't do product construction. Left alphabet: {a, b, c}, \
+             Right alphabet: {a, b, d}. Missing from left: {d}. Missing from right: {c}."
+        );
+    }
 }
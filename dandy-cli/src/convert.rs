@@ -0,0 +1,146 @@
+use crate::automata::{Automata, AutomataType};
+use crate::{ConvertArgs, ConvertTarget, DandyArgs, OutputFormat};
+use dandy::dfa::Dfa;
+use dandy::nfa::Nfa;
+
+pub fn convert(
+    main_args: &DandyArgs,
+    args: &ConvertArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    let style = main_args.style;
+    let dfa_output = |dfa: &Dfa| match args.format {
+        OutputFormat::Table => style.dfa_table(dfa),
+        OutputFormat::Edges => dfa.to_edge_list(),
+    };
+    let nfa_output = |nfa: &Nfa| match args.format {
+        OutputFormat::Table => style.nfa_table(nfa),
+        OutputFormat::Edges => nfa.to_edge_list(),
+    };
+
+    if args.merged_names && !(args.minimize && args.to == ConvertTarget::Dfa) {
+        return Err("--merged-names can only be used when minimizing DFAs".to_string());
+    }
+    if args.remove_epsilon_moves && args.to != ConvertTarget::Grammar {
+        return Err(
+            "--remove-epsilon-moves can only be used when converting to grammars".to_string(),
+        );
+    }
+    if args.format != OutputFormat::Table && args.to == ConvertTarget::Grammar {
+        return Err("--format can only be used when converting to DFAs or NFAs".to_string());
+    }
+
+    let automata = Automata::load_file(&args.file, args.r#type)?;
+
+    if args.debug_nfa {
+        let Automata::Regex(regex, _) = automata else {
+            return Err("--debug-nfa can only be used when converting a Regex".to_string());
+        };
+        let (nfa, debug) = regex.to_nfa_debug();
+        log!("NFA converted from the Regex:");
+        output(&nfa_output(&nfa));
+        log!("Part of the Regex each state of the NFA corresponds to:");
+        nfa.states()
+            .iter()
+            .zip(debug.roles())
+            .for_each(|(state, role)| output(&format!("{}: {role}", state.name())));
+        if args.to == ConvertTarget::Dfa {
+            log!("DFA converted from the NFA:");
+            output(&dfa_output(&nfa.to_dfa()));
+        }
+        return Ok(());
+    }
+
+    let from = automata.get_type();
+    let to = match args.to {
+        ConvertTarget::Dfa => AutomataType::Dfa,
+        ConvertTarget::Nfa => AutomataType::Nfa,
+        ConvertTarget::Grammar => {
+            let (mut nfa, _) = automata.into_nfa();
+            if args.minimize {
+                let before = nfa.states().len();
+                nfa.minimize_via_dfa();
+                log!(
+                    "Minimized the NFA from {before} to {} states",
+                    nfa.states().len()
+                );
+            }
+            if args.remove_epsilon_moves {
+                nfa.remove_epsilon_moves();
+            }
+            log!(
+                "{} converted to right-linear grammar:",
+                from.to_string(false)
+            );
+            output!("{}", nfa.to_right_linear_grammar());
+            return Ok(());
+        }
+    };
+    // The merged states aren't known when a Regex is converted to a minimized DFA directly
+    let direct = args.minimize && !args.merged_names;
+    if let (Automata::Regex(regex, None), AutomataType::Dfa, true) = (&automata, to, direct) {
+        let (dfa, report) = regex.to_min_dfa_with_report();
+        if args.verbose {
+            log!("{report}");
+        }
+        log!("Regex converted to minimized DFA:");
+        output(&dfa_output(&dfa));
+        return Ok(());
+    }
+
+    let (mut converted, _) = automata
+        .convert_to(to)
+        .ok_or_else(|| "Only conversion to DFA or NFA is supported".to_string())?;
+    if args.minimize {
+        let (before, after) = match &mut converted {
+            Automata::Dfa(dfa) => {
+                let before = dfa.states().len();
+                if args.merged_names {
+                    for (kept, merged) in dfa.minimize_named() {
+                        merged
+                            .iter()
+                            .for_each(|name| log!("{name} was merged into {kept}"));
+                    }
+                } else {
+                    dfa.minimize();
+                }
+                (before, dfa.states().len())
+            }
+            Automata::Nfa(nfa) => {
+                let before = nfa.states().len();
+                nfa.minimize_via_dfa();
+                (before, nfa.states().len())
+            }
+            Automata::Regex(..) => unreachable!("Conversion is always to DFA or NFA"),
+        };
+        log!(
+            "Minimized the {} from {before} to {after} states",
+            to.to_string(false)
+        );
+    }
+    log!(
+        "{} converted to {}:",
+        from.to_string(false),
+        to.to_string(false)
+    );
+    match &converted {
+        Automata::Dfa(dfa) => output(&dfa_output(dfa)),
+        Automata::Nfa(nfa) => output(&nfa_output(nfa)),
+        Automata::Regex(..) => unreachable!("Conversion is always to DFA or NFA"),
+    }
+    Ok(())
+}
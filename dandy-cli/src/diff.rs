@@ -0,0 +1,79 @@
+use crate::automata::{AlphabetMismatch, Automata};
+use crate::{DandyArgs, DiffArgs};
+use thiserror::Error;
+
+pub fn diff(
+    main_args: &DandyArgs,
+    args: &DiffArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    let (dfa1, converted1) = Automata::load_file(&args.first, args.r#type)
+        .map_err(|e| Error::InputFile(1, e).to_string())?
+        .into_dfa();
+    let (dfa2, converted2) =
+        Automata::load_file(&args.second, args.second_type.unwrap_or(args.r#type))
+            .map_err(|e| Error::InputFile(2, e).to_string())?
+            .into_dfa();
+
+    if converted1 {
+        log!("Input file 1 was converted to a DFA to proceed, since it wasn't a DFA to start with");
+    }
+    if converted2 {
+        log!("Input file 2 was converted to a DFA to proceed, since it wasn't a DFA to start with");
+    }
+
+    if args.structural {
+        log!("Structural diff of the two DFAs:");
+        output(dfa1.structural_diff(&dfa2).to_string().trim_end());
+        return Ok(());
+    }
+
+    if let Some(mismatch) = AlphabetMismatch::between_dfas(&dfa1, &dfa2) {
+        return Err(Error::DifferentAlphabets(mismatch).to_string());
+    }
+    let difference = dfa1
+        .symmetric_difference(&dfa2)
+        .expect("The alphabets are equal");
+    match difference.to_nfa().word_components().next() {
+        None => output!("The languages are equal"),
+        Some(word) => {
+            let elems = word.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+            let accepted_by = if dfa1.accepts(&elems) {
+                "first"
+            } else {
+                "second"
+            };
+            let word = if word.is_empty() {
+                "(empty word)".to_string()
+            } else {
+                elems.concat()
+            };
+            output!("The languages differ, the shortest word in only one of them is {word} (accepted by the {accepted_by})");
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(
+        "Different alphabets in input DFAs, can't compare their languages (use --structural to diff them). {0}"
+    )]
+    DifferentAlphabets(AlphabetMismatch),
+    #[error("Error reading {0}: {1}")]
+    InputFile(usize, String),
+}
@@ -3,6 +3,9 @@ use crate::{DandyArgs, EnumerateFileArgs, EnumerateRegexArgs};
 use dandy::nfa::Nfa;
 use dandy::parser;
 
+/// How often the progress of an enumeration is written to stderr, in words
+const PROGRESS_INTERVAL: usize = 1000;
+
 pub fn enumerate_regex(
     main_args: &DandyArgs,
     args: &EnumerateRegexArgs,
@@ -10,8 +13,7 @@ pub fn enumerate_regex(
 ) -> Result<(), String> {
     let regex = parser::regex(&args.regex).map_err(|e| e.to_string())?;
     let nfa = regex.to_nfa();
-    enumerate_nfa(nfa, main_args, args.amount, output);
-    Ok(())
+    enumerate_nfa(nfa, main_args, args.amount, args.max_word_length, output)
 }
 
 pub fn enumerate_file(
@@ -21,16 +23,49 @@ pub fn enumerate_file(
 ) -> Result<(), String> {
     let file = Automata::load_file(&args.file, args.r#type)?;
     let (nfa, _) = file.into_nfa();
-    enumerate_nfa(nfa, main_args, args.amount, output);
-    Ok(())
+    enumerate_nfa(nfa, main_args, args.amount, args.max_word_length, output)
+}
+
+/// Enumerates at most `n` words of the language of a NFA without ε-moves, of at most `max_len` elements if given,
+/// calling `word` with each of them (where the empty word is given as "(empty word)"). This gives the number of words
+/// enumerated. Since enumerating many words of a large automaton may take a while, the progress is written to stderr
+/// every [PROGRESS_INTERVAL] words, unless the logs are disabled.
+pub fn enumerate_words(
+    nfa: &Nfa,
+    main_args: &DandyArgs,
+    n: usize,
+    max_len: Option<usize>,
+    mut word: impl FnMut(&str) -> Result<(), String>,
+) -> Result<usize, String> {
+    let words = nfa.words_up_to_length(max_len.unwrap_or(usize::MAX));
+    let mut x = 0;
+    for w in words.take(n) {
+        word(if w.is_empty() { "(empty word)" } else { &w })?;
+        x += 1;
+        if x % PROGRESS_INTERVAL == 0 && x != n && !main_args.no_log {
+            eprintln!("({x} of {n} words enumerated)");
+        }
+    }
+    Ok(x)
+}
+
+/// The note logged when fewer than the requested number of words were enumerated from the language of `what`
+pub fn only_words_note(x: usize, max_len: Option<usize>, what: &str) -> String {
+    match max_len {
+        Some(max_len) => {
+            format!("(only {x} words of length at most {max_len} exists in the {what})")
+        }
+        None => format!("(only {x} words exists in the {what})"),
+    }
 }
 
 fn enumerate_nfa(
     mut nfa: Nfa,
     main_args: &DandyArgs,
     n: usize,
+    max_len: Option<usize>,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
-) {
+) -> Result<(), String> {
     #[allow(unused_variables)]
     let log = |s: &str| {
         if !main_args.no_log {
@@ -38,26 +73,22 @@ fn enumerate_nfa(
         }
     };
     macro_rules! log {
-        ($($t:tt)*) => (log(&format!($($t)*)))
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
     }
 
     macro_rules! output {
-        ($($t:tt)*) => (output(&format!($($t)*)))
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
     }
 
     nfa.remove_epsilon_moves();
 
     log!("First {n} words of the language of the regex:");
-    let mut x = 0;
-    nfa.words().take(n).for_each(|word| {
-        if word.is_empty() {
-            output!("(empty word)");
-        } else {
-            output!("{word}");
-        }
-        x += 1;
-    });
+    let x = enumerate_words(&nfa, main_args, n, max_len, |word| {
+        output!("{word}");
+        Ok(())
+    })?;
     if x != n {
-        log!("(only {x} words exists in the language of the regex)");
+        log!("{}", only_words_note(x, max_len, "language of the regex"));
     }
+    Ok(())
 }
@@ -1,12 +1,19 @@
-use crate::automata::AutomataType;
-use crate::{automata::Automata, DandyArgs, EquivalenceArgs};
+use crate::automata::{self, AutomataType};
+use crate::stats::{Phase, Stats};
+use crate::{automata::Automata, DandyArgs, EquivalenceArgs, Outcome};
+use dandy::batch;
 use dandy::dfa::parse::DfaParseError;
+use dandy::dfa::DfaState;
 use dandy::nfa::parse::NfaParseError;
-use dandy::parser;
+use dandy::parser::{self, Detected};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::fmt::Display;
-use std::path::Path;
-use std::time::SystemTime;
-use std::{fs, io};
+use std::io;
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -14,9 +21,24 @@ pub enum EquivalenceResult {
     FailedToRead(String),
     FailedToParse(String),
     FailedToValidate(String),
+    InvalidMapping(String),
+    DifferentAlphabets(String),
     NotEquivalent,
     NotMinimized,
     Equivalent,
+    EquivalentButDifferentStructure,
+    Isomorphic,
+}
+
+impl EquivalenceResult {
+    /// Whether the tested automata is accepted as equivalent, which with `--structural` also requires it to be
+    /// isomorphic
+    pub fn is_equivalent(&self) -> bool {
+        matches!(
+            self,
+            EquivalenceResult::Equivalent | EquivalenceResult::Isomorphic
+        )
+    }
 }
 
 impl Display for EquivalenceResult {
@@ -26,9 +48,13 @@ impl Display for EquivalenceResult {
             FailedToRead(s) => write!(f, "Failed to read ({s})"),
             FailedToParse(s) => write!(f, "Failed to parse ({s})"),
             FailedToValidate(s) => write!(f, "Failed to validate ({s})"),
+            InvalidMapping(s) => write!(f, "Invalid symbol mapping ({s})"),
+            DifferentAlphabets(s) => write!(f, "Not equivalent due to alphabets ({s})"),
             NotEquivalent => write!(f, "Not Equivalent"),
             NotMinimized => write!(f, "Equivalent but not minimized"),
             Equivalent => write!(f, "Equivalent"),
+            EquivalentButDifferentStructure => write!(f, "Equivalent but different structure"),
+            Isomorphic => write!(f, "Isomorphic"),
         }
     }
 }
@@ -36,69 +62,189 @@ impl Display for EquivalenceResult {
 pub fn equivalence(
     main_args: &DandyArgs,
     args: &EquivalenceArgs,
+    stats: &mut Stats,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
-) -> Result<(), String> {
-    let file = fs::read_to_string(&args.automata).map_err(|e| Error::InputFile(e).to_string())?;
+) -> Result<Outcome, String> {
+    let file = automata::read_file(&args.automata).map_err(|e| Error::InputFile(e).to_string())?;
 
-    let tester = DandyTester::new(&file, args).map_err(|e| e.to_string())?;
+    let tester = DandyTester::new(&file, args, stats).map_err(|e| e.to_string())?;
     #[allow(unused_variables)]
     let log = |s: &str| {
-        if !main_args.no_log {
+        if !main_args.no_log && !args.summary_only {
             println!("{s}")
         }
     };
     macro_rules! log {
-        ($($t:tt)*) => (log(&format!($($t)*)))
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
     }
     macro_rules! output {
-        ($($t:tt)*) => (output(&format!($($t)*)))
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
     }
 
+    for (conversion, duration) in tester.conversions() {
+        log!("{conversion} in {}ms", duration.as_millis());
+    }
     log!("Input loaded:");
-    log!("{}", tester.input_automata().table());
+    log(&main_args.style.table(tester.input_automata()));
+    #[cfg(feature = "provenance")]
+    if let Some(provenance) = tester.input_automata().provenance() {
+        log!("Created by {provenance}");
+    }
+
+    if args.explain {
+        let path = &args.files[0];
+        let equivalent = stats.time(Phase::Compare, || {
+            tester.explain(path, tester.file_type(path), |s: &str| {
+                output(&main_args.style.text(s))
+            })
+        })?;
+        return Ok(Outcome::passed_if(equivalent));
+    }
+
+    let files = match args.sample {
+        Some(sample) => {
+            let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+            let files = sample_files(&args.files, sample, seed);
+            log!(
+                "Sampled {} of {} files (seed {seed})",
+                files.len(),
+                args.files.len()
+            );
+            files
+        }
+        None => args.files.iter().collect(),
+    };
 
     let start = SystemTime::now();
-    let results = args
-        .files
-        .iter()
-        .map(|path| (path, tester.test_equivalence(path)))
-        .collect::<Vec<_>>();
+    let test = |tester: &DandyTester, path| {
+        let r#type = tester.file_type(path);
+        (path, r#type, tester.test_equivalence(path, r#type))
+    };
+    let results = if args.jobs == Some(1) || files.len() <= 1 {
+        files.iter().map(|path| test(&tester, path)).collect()
+    } else {
+        batch::map_with_state(
+            files.clone(),
+            args.jobs.unwrap_or(0),
+            // Automata can't be shared between threads, so each thread loads its own tester
+            || DandyTester::new(&file, args, &mut Stats::default()).expect("loaded before"),
+            |tester, path| test(tester, path),
+        )
+    };
     let duration = SystemTime::now().duration_since(start).unwrap_or_default();
+    stats.add(Phase::Compare, duration);
 
     log!(
         "Testing of {} files done in {}ms. Results:",
-        args.files.len(),
+        files.len(),
         duration.as_millis()
     );
 
-    let successes = results.into_iter().fold(0usize, |acc, (path, result)| {
-        let res = if args.bool {
-            format!("{}", result == EquivalenceResult::Equivalent)
+    let successes = results
+        .into_iter()
+        .fold(0usize, |acc, (path, r#type, result)| {
+            let res = if args.bool {
+                format!("{}", result.is_equivalent())
+            } else if args.infer_type {
+                format!("{result} (as {})", r#type.to_string(false))
+            } else {
+                result.to_string()
+            };
+            if args.summary_only {
+                // Only the summary below is printed
+            } else if let Some(prefix) = crate::last_n_components(path, args.path_length) {
+                output!("{prefix}: {res}");
+            } else {
+                output!("{res}");
+            }
+
+            if result.is_equivalent() {
+                acc + 1
+            } else {
+                acc
+            }
+        });
+
+    let summary = if args.sample.is_some() {
+        let percentage = if files.is_empty() {
+            0.0
         } else {
-            result.to_string()
+            100.0 * successes as f64 / files.len() as f64
         };
-        if let Some(prefix) = crate::last_n_components(path, args.path_length) {
-            output!("{prefix}: {res}");
-        } else {
-            output!("{res}");
-        }
+        format!(
+            "{}/{} sampled files passed ({percentage:.1}%)",
+            successes,
+            files.len()
+        )
+    } else {
+        format!("{}/{} files passed", successes, files.len())
+    };
+    if args.summary_only {
+        output!("{summary}");
+    } else {
+        log!("{summary}");
+    }
 
-        if result == EquivalenceResult::Equivalent {
-            acc + 1
-        } else {
-            acc
-        }
-    });
+    Ok(Outcome::passed_if(successes == files.len()))
+}
+
+/// Randomly selects `sample` of the files (or all of them if there are fewer), using the given seed. The selected
+/// files are kept in the order they were given in, so that the output is easy to compare to a full run.
+fn sample_files(files: &[PathBuf], sample: usize, seed: u64) -> Vec<&PathBuf> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut indices = (0..files.len())
+        .collect::<Vec<_>>()
+        .choose_multiple(&mut rng, sample)
+        .copied()
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices.into_iter().map(|idx| &files[idx]).collect()
+}
 
-    log!("{}/{} files passed", successes, args.files.len());
+/// A conversion done by [DandyTester] when preparing the input automata for testing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Conversion {
+    RegexToNfa,
+    OptimizeNfa,
+    NfaToDfa,
+    DfaToNfa,
+    MinimizeDfa,
+    MinimizeNfa,
+}
 
-    Ok(())
+impl Conversion {
+    /// The phase of `--stats` the conversion is counted as
+    fn phase(self) -> Phase {
+        match self {
+            Conversion::MinimizeDfa | Conversion::MinimizeNfa => Phase::Minimize,
+            _ => Phase::Convert,
+        }
+    }
+}
+
+impl Display for Conversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Conversion::*;
+        match self {
+            RegexToNfa => write!(f, "Converted Regex to NFA"),
+            OptimizeNfa => write!(f, "Removed unreachable states and epsilon moves of NFA"),
+            NfaToDfa => write!(f, "Converted NFA to DFA"),
+            DfaToNfa => write!(f, "Converted DFA to NFA"),
+            MinimizeDfa => write!(f, "Minimized DFA"),
+            MinimizeNfa => write!(f, "Minimized NFA by conversion to a minimized DFA and back"),
+        }
+    }
 }
 
 struct DandyTester {
     input: Automata,
     minimized: bool,
+    structural: bool,
     test_type: AutomataType,
+    infer_type: bool,
+    ext_map: Vec<(String, AutomataType)>,
+    map: Option<SymbolMapping>,
+    conversions: Vec<(Conversion, Duration)>,
 }
 
 impl DandyTester {
@@ -106,75 +252,383 @@ impl DandyTester {
         &self.input
     }
 
-    fn new<'a>(file: &'a str, args: &EquivalenceArgs) -> Result<DandyTester, Error<'a>> {
-        let mut input = match args.in_type.unwrap_or(args.r#type) {
-            AutomataType::Dfa => {
-                let dfa = parser::dfa(file)
-                    .map_err(Error::DfaParse)?
-                    .try_into()
-                    .map_err(Error::Dfa)?;
-                Automata::Dfa(dfa)
-            }
-            AutomataType::Nfa => {
-                let nfa = parser::nfa(file)
-                    .map_err(Error::NfaParse)?
-                    .try_into()
-                    .map_err(Error::Nfa)?;
-                Automata::Nfa(nfa)
+    /// The conversions done to the input automata when preparing it for testing, in order, along with the time
+    /// each of them took
+    fn conversions(&self) -> &[(Conversion, Duration)] {
+        &self.conversions
+    }
+
+    /// Loads and prepares the input automata, recording the sizes and durations in `stats`
+    fn new<'a>(
+        file: &'a str,
+        args: &EquivalenceArgs,
+        stats: &mut Stats,
+    ) -> Result<DandyTester, Error<'a>> {
+        let mut conversions = vec![];
+
+        let in_type = args.in_type.unwrap_or_else(|| {
+            infer_type(&args.automata, args.infer_type, &args.ext_map).unwrap_or(args.r#type)
+        });
+        let in_type = in_type.resolve(file).ok_or(Error::UnknownType)?;
+        let loaded = stats.time(Phase::Parse, || -> Result<_, Error<'a>> {
+            match in_type {
+                AutomataType::Dfa => {
+                    let dfa = parser::dfa_file(file)
+                        .map_err(Error::DfaParse)?
+                        .try_into()
+                        .map_err(Error::Dfa)?;
+                    Ok(Automata::Dfa(dfa))
+                }
+                AutomataType::Nfa => {
+                    let nfa = parser::nfa_file(file)
+                        .map_err(Error::NfaParse)?
+                        .try_into()
+                        .map_err(Error::Nfa)?;
+                    Ok(Automata::Nfa(nfa))
+                }
+                AutomataType::Regex => {
+                    let (regex, alphabet) = parser::regex_file(file).map_err(Error::RegexParse)?;
+                    Ok(Automata::Regex(regex, alphabet))
+                }
+                AutomataType::Auto => unreachable!("The type is resolved"),
             }
-            AutomataType::Regex => {
-                let regex = parser::regex(file).map_err(Error::RegexParse)?;
-                let nfa = regex.to_nfa();
-                let dfa = nfa.to_dfa(); // To reduce states, regex->nfa can produce MANY states
-                Automata::Dfa(dfa)
+        })?;
+        stats.input(&args.automata, &loaded);
+        let mut input = match loaded {
+            Automata::Regex(regex, alphabet) => {
+                let mut nfa = timed(&mut conversions, stats, Conversion::RegexToNfa, || {
+                    automata::regex_to_nfa(regex, alphabet.as_deref())
+                })
+                .ok_or_else(|| Error::RegexAlphabet(alphabet.unwrap_or_default()))?;
+                if args.r#type == AutomataType::Dfa {
+                    // regex->nfa can produce MANY states, so the subset construction may explode
+                    let dfa = timed(&mut conversions, stats, Conversion::NfaToDfa, || {
+                        nfa.to_dfa_bounded(args.max_dfa_states)
+                    });
+                    let dfa = dfa.ok_or(Error::DfaTooLarge(args.max_dfa_states))?;
+                    stats.peak(dfa.states().len());
+                    Automata::Dfa(dfa)
+                } else {
+                    // Test files are compared as NFAs, so there is no need to determinize
+                    timed(&mut conversions, stats, Conversion::OptimizeNfa, || {
+                        nfa.optimize()
+                    });
+                    Automata::Nfa(nfa)
+                }
             }
+            automata => automata,
         };
 
+        // With `--type auto`, the type of each tested file is only known once it is loaded, so the options are
+        // checked against the type of the main automata instead
+        let test_type = match args.r#type {
+            AutomataType::Auto => in_type,
+            r#type => r#type,
+        };
         let minimized = if args.minimized {
-            if args.r#type == AutomataType::Dfa {
-                (input, _) = input.into_minimized_dfa_automata();
-                true
-            } else {
-                return Err(Error::InvalidMinimizedConfig);
+            match test_type {
+                AutomataType::Dfa => {
+                    input = timed(&mut conversions, stats, Conversion::MinimizeDfa, || {
+                        input.into_minimized_dfa_automata().0
+                    });
+                }
+                AutomataType::Nfa => {
+                    input = timed(&mut conversions, stats, Conversion::MinimizeNfa, || {
+                        input.into_minimized_nfa_automata()
+                    });
+                }
+                _ => return Err(Error::InvalidMinimizedConfig),
             }
+            true
         } else {
             false
         };
 
-        (input, _) = input.prepare_to_compare_with(args.r#type);
+        if args.explain && args.files.len() != 1 {
+            return Err(Error::InvalidExplainConfig);
+        }
+        if args.structural
+            && (args.minimized || test_type == AutomataType::Regex || in_type != test_type)
+        {
+            return Err(Error::InvalidStructuralConfig);
+        }
+
+        let conversion = match (input.get_type(), args.r#type) {
+            (AutomataType::Nfa, AutomataType::Dfa) => Some(Conversion::NfaToDfa),
+            (AutomataType::Dfa, AutomataType::Nfa | AutomataType::Regex) => {
+                Some(Conversion::DfaToNfa)
+            }
+            _ => None,
+        };
+        if let Some(conversion) = conversion {
+            input = timed(&mut conversions, stats, conversion, || {
+                input.prepare_to_compare_with(args.r#type).0
+            });
+            if let (Conversion::NfaToDfa, Some(dfa)) = (conversion, input.borrow_dfa()) {
+                stats.peak(dfa.states().len());
+            }
+        }
 
         Ok(Self {
             input,
             minimized,
+            structural: args.structural,
             test_type: args.r#type,
+            infer_type: args.infer_type,
+            ext_map: args.ext_map.clone(),
+            map: args.map.clone(),
+            conversions,
         })
     }
 
-    fn test_equivalence(&self, file: &Path) -> EquivalenceResult {
-        match fs::read_to_string(file) {
+    /// The type to load a file to test as, which is the test type unless it can be inferred from the extension of the
+    /// file (with `--infer-type`)
+    fn file_type(&self, file: &Path) -> AutomataType {
+        infer_type(file, self.infer_type, &self.ext_map).unwrap_or(self.test_type)
+    }
+
+    fn test_equivalence(&self, file: &Path, r#type: AutomataType) -> EquivalenceResult {
+        match automata::read_file(file) {
             Err(e) => EquivalenceResult::FailedToRead(e.to_string()),
-            Ok(f) => match Automata::load_test(&f, self.test_type) {
-                Ok(automata) => self.input.test_equivalence(automata, self.minimized),
+            Ok(f) => match Automata::load_test(&f, r#type) {
+                Ok(automata) if self.map.is_some() => {
+                    let map = self.map.as_ref().expect("checked above");
+                    self.input.test_equivalence_under(automata, &map.pairs())
+                }
+                Ok(automata) => {
+                    let isomorphic = self.structural.then(|| self.input.isomorphic_to(&automata));
+                    match (
+                        self.input.test_equivalence(automata, self.minimized),
+                        isomorphic,
+                    ) {
+                        (EquivalenceResult::Equivalent, Some(true)) => {
+                            EquivalenceResult::Isomorphic
+                        }
+                        (EquivalenceResult::Equivalent, Some(false)) => {
+                            EquivalenceResult::EquivalentButDifferentStructure
+                        }
+                        (result, _) => result,
+                    }
+                }
                 Err(res) => res,
             },
         }
     }
+
+    /// Explains why the automata in the file is (or isn't) equivalent to the input automata, by outputting each pair
+    /// of states explored when comparing them as DFAs, along with the word leading to it, until the first mismatch.
+    /// This gives whether they are equivalent.
+    fn explain(
+        &self,
+        file: &Path,
+        r#type: AutomataType,
+        mut output: impl FnMut(&str),
+    ) -> Result<bool, String> {
+        let file = automata::read_file(file)
+            .map_err(|e| EquivalenceResult::FailedToRead(e.to_string()).to_string())?;
+        let candidate = Automata::load_test(&file, r#type).map_err(|e| e.to_string())?;
+        let (reference, _) = self.input.clone().into_dfa();
+        let (candidate, _) = candidate.into_dfa();
+
+        let describe = |state: &DfaState| {
+            if state.is_accepting() {
+                "accepting"
+            } else {
+                "rejecting"
+            }
+        };
+        let mut pairs = 0;
+        let result = reference.equivalence_trace(&candidate, |word, s1, s2| {
+            pairs += 1;
+            let word = if word.is_empty() {
+                "ε".to_string()
+            } else {
+                word.concat()
+            };
+            let mismatch = if s1.is_accepting() != s2.is_accepting() {
+                " ← MISMATCH"
+            } else {
+                ""
+            };
+            output(&format!(
+                "on word {word}: ref in state {} ({}), student in state {} ({}){mismatch}",
+                s1.name(),
+                describe(s1),
+                s2.name(),
+                describe(s2)
+            ));
+            ControlFlow::Continue(())
+        });
+        if result == Some(true) {
+            output(&format!("explored {pairs} pairs, no mismatch → equivalent"));
+        } else if pairs == 0 {
+            output("alphabets differ → not equivalent");
+        }
+        Ok(result == Some(true))
+    }
+}
+
+/// A renaming of the symbols of the tested files given by `--map`, as pairs of a symbol of the tested files and the
+/// symbol of the main automata it is renamed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolMapping(Vec<(String, String)>);
+
+impl SymbolMapping {
+    /// Parses a mapping such as `0=a,1=b`, where whitespace around the symbols is ignored
+    pub fn parse(s: &str) -> Result<Self, String> {
+        s.split(',')
+            .map(|pair| {
+                let (from, to) = pair
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected FROM=TO (such as 0=a), got '{pair}'"))?;
+                Ok((from.trim().to_string(), to.trim().to_string()))
+            })
+            .collect::<Result<_, _>>()
+            .map(SymbolMapping)
+    }
+
+    /// The pairs of symbols as expected by [Dfa::equivalent_to_under](dandy::dfa::Dfa::equivalent_to_under)
+    pub fn pairs(&self) -> Vec<(&str, &str)> {
+        self.0
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .collect()
+    }
+}
+
+/// Infers the type of a file from its extension if `infer` is set, see [AutomataType::from_extension]
+fn infer_type(
+    file: &Path,
+    infer: bool,
+    ext_map: &[(String, AutomataType)],
+) -> Option<AutomataType> {
+    infer
+        .then(|| AutomataType::from_extension(file, ext_map))
+        .flatten()
+}
+
+/// Runs `f`, recording how long it took as the given conversion, and adding it to the phase of the conversion in
+/// `stats`
+fn timed<T>(
+    conversions: &mut Vec<(Conversion, Duration)>,
+    stats: &mut Stats,
+    conversion: Conversion,
+    f: impl FnOnce() -> T,
+) -> T {
+    let start = SystemTime::now();
+    let result = f();
+    let duration = SystemTime::now().duration_since(start).unwrap_or_default();
+    conversions.push((conversion, duration));
+    stats.add(conversion.phase(), duration);
+    result
 }
 
 #[derive(Error, Debug)]
 pub enum Error<'a> {
-    #[error("Error parsing DFA: {0:?}")]
+    #[error("Error parsing DFA: {0:?}{}", automata::type_hint(parser::suggest_type(.0.input, Detected::Dfa)))]
     DfaParse(nom::error::Error<&'a str>),
     #[error("Error compiling DFA: {0}")]
     Dfa(DfaParseError<'a>),
-    #[error("Error parsing NFA: {0:?}")]
+    #[error("Error parsing NFA: {0:?}{}", automata::type_hint(parser::suggest_type(.0.input, Detected::Nfa)))]
     NfaParse(nom::error::Error<&'a str>),
     #[error("Error compiling NFA: {0}")]
     Nfa(NfaParseError<'a>),
     #[error("Error parsing regular expression: {0:?}")]
     RegexParse(nom::error::Error<&'a str>),
-    #[error("--minimized option can only be used when testing DFAs")]
+    #[error(
+        "The Regex contains elements not in the alphabet declared by its alphabet pragma {0:?}"
+    )]
+    RegexAlphabet(Vec<String>),
+    #[error("Converting the Regex to a DFA gives more than {0} states, try testing NFAs instead")]
+    DfaTooLarge(usize),
+    #[error(
+        "Could not detect if the main automata is a DFA, NFA or Regex, use --in-type to specify it"
+    )]
+    UnknownType,
+    #[error("--minimized option can only be used when testing DFAs or NFAs")]
     InvalidMinimizedConfig,
+    #[error("--structural option can only be used when testing DFAs or NFAs against automata of the same type")]
+    InvalidStructuralConfig,
+    #[error("--explain option can only be used with exactly one file to compare to")]
+    InvalidExplainConfig,
     #[error("Error reading input file: {0}")]
     InputFile(#[from] io::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(in_type: AutomataType, r#type: AutomataType) -> EquivalenceArgs {
+        EquivalenceArgs {
+            in_type: Some(in_type),
+            r#type,
+            minimized: false,
+            structural: false,
+            r#bool: false,
+            path_length: None,
+            max_dfa_states: 100_000,
+            sample: None,
+            seed: None,
+            summary_only: false,
+            infer_type: false,
+            ext_map: vec![],
+            explain: false,
+            map: None,
+            jobs: None,
+            automata: PathBuf::new(),
+            files: vec![],
+        }
+    }
+
+    fn conversions(tester: &DandyTester) -> Vec<Conversion> {
+        tester.conversions().iter().map(|(c, _)| *c).collect()
+    }
+
+    #[test]
+    fn regex_compared_to_nfas_is_not_determinized() {
+        let args = args(AutomataType::Regex, AutomataType::Nfa);
+        let tester = DandyTester::new("(a|b)*abb", &args, &mut Stats::default()).unwrap();
+        assert_eq!(
+            conversions(&tester),
+            [Conversion::RegexToNfa, Conversion::OptimizeNfa]
+        );
+        let nfa = tester.input_automata().borrow_nfa().unwrap();
+        assert!(!nfa.has_epsilon_moves());
+    }
+
+    #[test]
+    fn regex_compared_to_dfas_is_determinized() {
+        let args = args(AutomataType::Regex, AutomataType::Dfa);
+        let tester = DandyTester::new("(a|b)*abb", &args, &mut Stats::default()).unwrap();
+        assert_eq!(
+            conversions(&tester),
+            [Conversion::RegexToNfa, Conversion::NfaToDfa]
+        );
+        assert!(tester.input_automata().borrow_dfa().is_some());
+    }
+
+    #[test]
+    fn sampling_is_seeded_and_keeps_order() {
+        let files = (0..20)
+            .map(|idx| PathBuf::from(format!("{idx}.dfa")))
+            .collect::<Vec<_>>();
+        let sample = sample_files(&files, 5, 42);
+        assert_eq!(sample.len(), 5);
+        assert_eq!(sample, sample_files(&files, 5, 42));
+        let positions = sample
+            .iter()
+            .map(|path| files.iter().position(|file| file == *path).unwrap())
+            .collect::<Vec<_>>();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(sample_files(&files, 50, 42).len(), files.len());
+    }
+
+    #[test]
+    fn regex_to_dfa_is_bounded() {
+        let mut args = args(AutomataType::Regex, AutomataType::Dfa);
+        args.max_dfa_states = 4;
+        let result = DandyTester::new("(a|b)*a(a|b)(a|b)(a|b)", &args, &mut Stats::default());
+        assert!(matches!(result, Err(Error::DfaTooLarge(4))));
+    }
+}
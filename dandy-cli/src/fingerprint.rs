@@ -0,0 +1,47 @@
+use crate::automata::Automata;
+use crate::{DandyArgs, FingerprintArgs};
+
+pub fn fingerprint(
+    main_args: &DandyArgs,
+    args: &FingerprintArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    log!(
+        "Language fingerprints of {} {} (equal fingerprints mean equal languages, barring hash collisions):",
+        args.files.len(),
+        args.r#type.to_string(args.files.len() != 1)
+    );
+    let mut failed = 0;
+    for file in &args.files {
+        match Automata::load_file(file, args.r#type) {
+            Ok(automata) => {
+                let (dfa, _) = automata.into_dfa();
+                output!("{:016x}  {}", dfa.language_fingerprint(), file.display());
+            }
+            Err(e) => {
+                // Errors don't go to the output, so that it only consists of fingerprints
+                eprintln!("{}: {}", file.display(), main_args.style.text(e.trim_end()));
+                failed += 1;
+            }
+        }
+    }
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(format!("{failed} of the files couldn't be loaded"))
+    }
+}
@@ -0,0 +1,73 @@
+use crate::automata::Automata;
+use crate::{DandyArgs, GradeArgs, Outcome};
+use dandy::grading::{self, LanguageRelation};
+use thiserror::Error;
+
+/// The kinds of relations counted in the summary, in the order of the variants of [LanguageRelation]
+const SUMMARY_KINDS: [&str; 4] = ["equal", "subset", "superset", "incomparable"];
+
+pub fn grade(
+    main_args: &DandyArgs,
+    args: &GradeArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<Outcome, String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    let (spec, _) = Automata::load_file(&args.spec, args.spec_type)
+        .map_err(|e| Error::Spec(e).to_string())?
+        .into_minimized_dfa();
+    log!(
+        "Grading {} {} against a specification with {} states",
+        args.files.len(),
+        args.r#type.to_string(args.files.len() != 1),
+        spec.states().len()
+    );
+
+    let mut counts = [0usize; 4];
+    let mut errors = 0;
+    for file in &args.files {
+        let name = file.display();
+        let relation = match Automata::load_file(file, args.r#type) {
+            Ok(automata) => grading::compare_language(&automata.into_dfa().0, &spec),
+            Err(e) => {
+                errors += 1;
+                output!("{name}: {}", e.trim_end());
+                continue;
+            }
+        };
+        counts[match relation {
+            LanguageRelation::Equal => 0,
+            LanguageRelation::Subset { .. } => 1,
+            LanguageRelation::Superset { .. } => 2,
+            LanguageRelation::Incomparable { .. } => 3,
+        }] += 1;
+        output!("{name}: {relation}");
+    }
+
+    let mut summary = SUMMARY_KINDS
+        .iter()
+        .zip(counts)
+        .map(|(kind, count)| format!("{count} {kind}"))
+        .collect::<Vec<_>>();
+    summary.push(format!("{errors} failed to load"));
+    output!("{}", summary.join(", "));
+    Ok(Outcome::passed_if(counts[0] == args.files.len()))
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading the specification: {0}")]
+    Spec(String),
+}
@@ -1,25 +1,44 @@
 mod automata;
 mod binary_op;
+mod convert;
+mod diff;
 mod enumerate;
 mod equivalence;
+mod fingerprint;
+mod grade;
+mod sample;
+mod stats;
+mod style;
 mod test_files;
+mod unary_op;
+mod validate;
 
 use automata::AutomataType;
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use dandy::dfa::Dfa;
 use dandy::parser;
+use equivalence::SymbolMapping;
+use stats::Stats;
 use std::fmt;
 use std::fmt::Formatter;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
+use style::OutputStyle;
 use thiserror::Error;
 
 /// A cli tool for parsing and checking DFAs, NFAs and Regexes.
 // Example usage: dandy-cli equivalence tests/dfa1.dfa tests/example_tree/**/*.dfa
 //                dandy-cli equivalence --in-type nfa --minimized tests/nfa1.nfa tests/example_tree/**/*.dfa
 #[derive(Parser, Debug)]
-#[command(version, author = "Jonathan Widén", about)]
+#[command(
+    version,
+    author = "Jonathan Widén",
+    about,
+    after_help = "Exit codes: 0 if every check passed, 1 if some check failed (such as a non-equivalent file or a \
+                  rejected line) and 2 if an error occurred (such as a missing file or a parse error)"
+)]
 struct DandyArgs {
     #[arg(
         short,
@@ -33,6 +52,27 @@ struct DandyArgs {
         default_value_t
     )]
     no_log: bool,
+    #[arg(
+        long,
+        help = "Prints metrics of the run (the sizes of the automatas and the durations of each phase) as one line of \
+                JSON to stderr, see the documentation of stats.rs for the fields"
+    )]
+    stats: bool,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Writes the metrics of --stats to a file instead of stderr (implies --stats)"
+    )]
+    stats_out: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Writes all output in plain ASCII, with -> and eps instead of the unicode arrow and epsilon (also \
+                enabled by the environment variable DANDY_ASCII=1)"
+    )]
+    ascii: bool,
+    /// The style of the output, given by `ascii`
+    #[arg(skip)]
+    style: OutputStyle,
     #[command(subcommand)]
     command: Operation,
 }
@@ -59,6 +99,14 @@ enum Operation {
         about = "Computes the symmetric difference of two automatas or regexes by conversion to DFA and product construction"
     )]
     SymmetricDifference(BinaryOpArgs),
+    #[command(
+        about = "Computes the concatenation of two automatas or regexes as a minimized DFA, by the subset construction"
+    )]
+    Concatenate(BinaryOpArgs),
+    #[command(
+        about = "Computes the Kleene star of an automata or regex as a minimized DFA, by the subset construction"
+    )]
+    Star(UnaryOpArgs),
     #[command(
         about = "Enumerate strings in the language of a Regex provided as an argument (not from a file)"
     )]
@@ -67,6 +115,69 @@ enum Operation {
     EnumerateFile(EnumerateFileArgs),
     #[command(about = "Tests a list of files against an automata or regex")]
     TestFile(TestFileArgs),
+    #[command(about = "Converts an automata or regex to a DFA or NFA")]
+    Convert(ConvertArgs),
+    #[command(
+        about = "Shows the difference between two automatas or regexes, in language or in structure"
+    )]
+    Diff(DiffArgs),
+    #[command(
+        about = "Checks that files parse and are valid automatas or regexes, without doing anything else"
+    )]
+    Validate(ValidateArgs),
+    #[command(
+        about = "Samples words accepted and words rejected by an automata or regex, such as examples for an exercise"
+    )]
+    Sample(SampleArgs),
+    #[command(
+        about = "Lists a fingerprint of the language of each automata or regex, which is equal for equivalent files"
+    )]
+    Fingerprint(FingerprintArgs),
+    #[command(
+        about = "Grades automatas against a specification, telling if each accepts exactly its language, a subset, \
+                 a superset or neither, with words showing the differences"
+    )]
+    Grade(GradeArgs),
+}
+
+#[derive(Debug, Args)]
+struct GradeArgs {
+    #[arg(
+        long,
+        help = "The path to the specification the files are graded against"
+    )]
+    spec: PathBuf,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = AutomataType::Regex,
+        help = "The type of the specification"
+    )]
+    spec_type: AutomataType,
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of the automatas or regexes to grade"
+    )]
+    r#type: AutomataType,
+    #[arg(help = "The files to grade")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct FingerprintArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Auto,
+        help = "The type of the automatas or regexes to fingerprint"
+    )]
+    r#type: AutomataType,
+    #[arg(help = "The files to fingerprint")]
+    files: Vec<PathBuf>,
 }
 
 #[derive(Debug, Args)]
@@ -78,6 +189,11 @@ struct EnumerateRegexArgs {
         help = "The amount of strings to generate"
     )]
     amount: usize,
+    #[arg(
+        long,
+        help = "Only enumerates words of at most this many elements of the alphabet"
+    )]
+    max_word_length: Option<usize>,
     regex: String,
 }
 
@@ -97,6 +213,11 @@ struct EnumerateFileArgs {
         help = "The amount of strings to enumerate"
     )]
     amount: usize,
+    #[arg(
+        long,
+        help = "Only enumerates words of at most this many elements of the alphabet"
+    )]
+    max_word_length: Option<usize>,
     file: PathBuf,
 }
 
@@ -119,12 +240,232 @@ struct TestFileArgs {
         `files` to accept each file depending if all lines match"
     )]
     test_type: TestType,
+    #[arg(
+        long,
+        help = "Suggest the closest accepted word (in edit distance) for every rejected line"
+    )]
+    suggest: bool,
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "The maximum number of edits to consider when suggesting words with --suggest"
+    )]
+    max_edits: usize,
+    #[arg(
+        long,
+        help = "Only print the final line with the number of passed files, and no results per file or line"
+    )]
+    summary_only: bool,
+    #[arg(
+        long,
+        help = "Print the number of accepted and rejected lines of each length, and the symbols outside the \
+        alphabet, over all files"
+    )]
+    report: bool,
+    #[arg(
+        long,
+        help = "Print the smallest, largest and mean number of active states (as an NFA) after each number of \
+        steps, and how many lines died, over all files"
+    )]
+    profile: bool,
+    #[arg(
+        long,
+        help = "Print how many states (as a DFA) were never visited and how many transitions were never taken, over \
+        all files"
+    )]
+    coverage: bool,
+    #[arg(
+        long,
+        requires = "coverage",
+        help = "With --coverage, also print the table of the DFA with the number of visits of each state, and the \
+        transitions never taken from it, as comments"
+    )]
+    annotate_coverage: bool,
+    #[arg(
+        short,
+        long,
+        help = "The number of threads testing lines in parallel (by default one per CPU), where 1 tests sequentially"
+    )]
+    jobs: Option<usize>,
+    #[arg(
+        long,
+        value_name = "CORPUS",
+        help = "Before testing, check that every grapheme in the lines of this file is in the alphabet, failing \
+        otherwise"
+    )]
+    check_alphabet: Option<PathBuf>,
     #[arg(help = "The path to the automata or regex to test")]
     automata: PathBuf,
     #[arg(help = "The files to test")]
     files: Vec<PathBuf>,
 }
 
+#[derive(Debug, Args)]
+struct ConvertArgs {
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = AutomataType::Regex,
+        help = "The type of the automata/regex to convert"
+    )]
+    r#type: AutomataType,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ConvertTarget::Nfa,
+        help = "The type to convert to (DFA, NFA or a right-linear grammar)"
+    )]
+    to: ConvertTarget,
+    #[arg(
+        long,
+        help = "(Only for converting Regexes): Print which part of the Regex each NFA state corresponds to"
+    )]
+    debug_nfa: bool,
+    #[arg(
+        long,
+        help = "Minimize the result (NFAs are minimized by conversion to a minimized DFA and back, which isn't a true NFA minimization)"
+    )]
+    minimize: bool,
+    #[arg(
+        long,
+        help = "(Only for minimizing DFAs): Name each state of the minimized DFA after all states merged into it, and \
+                print which states were merged"
+    )]
+    merged_names: bool,
+    #[arg(
+        short,
+        long,
+        help = "(Only for converting Regexes to minimized DFAs): Print the number of states after each step of the conversion"
+    )]
+    verbose: bool,
+    #[arg(
+        long,
+        help = "(Only for converting to grammars): Remove the ε-moves of the NFA first, so that the grammar has no \
+                unit productions"
+    )]
+    remove_epsilon_moves: bool,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "The format to print the DFA or NFA in (edges prints one line per state and one line per transition, \
+                which is more compact for sparse NFAs)"
+    )]
+    format: OutputFormat,
+    #[arg(help = "The path to the automata or regex to convert")]
+    file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct DiffArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of the automatas to diff"
+    )]
+    r#type: AutomataType,
+    #[arg(
+        short,
+        long,
+        help = "The type of the second automata to diff (if different to the first automata)"
+    )]
+    second_type: Option<AutomataType>,
+    #[arg(
+        long,
+        help = "Diff the transition tables (which states correspond and which transitions differ) instead of the languages"
+    )]
+    structural: bool,
+    #[arg(help = "The first automata or regex to diff")]
+    first: PathBuf,
+    #[arg(help = "The second automata or regex to diff")]
+    second: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct ValidateArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of the automatas or regexes to validate"
+    )]
+    r#type: AutomataType,
+    #[arg(
+        long,
+        help = "Also warn about unreachable states and symbols which aren't part of any accepted word (DFAs and NFAs only)"
+    )]
+    lint: bool,
+    #[arg(
+        long,
+        help = "Also require the DFAs to be minimal (DFAs only, with '--type auto' files which aren't DFAs fail)"
+    )]
+    minimal: bool,
+    #[arg(
+        long,
+        help = "Require the alphabet to be exactly these symbols, separated by whitespace (such as \"a b c\")"
+    )]
+    alphabet: Option<String>,
+    #[arg(
+        long,
+        value_name = "CORPUS",
+        help = "Require every grapheme in the lines of this file to be in the alphabet, to find symbols left out of \
+        the alphabet"
+    )]
+    check_alphabet: Option<PathBuf>,
+    #[arg(help = "The files to validate")]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct SampleArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of the automata or regex to sample words from"
+    )]
+    r#type: AutomataType,
+    #[arg(long, default_value_t = 5, help = "The amount of accepted words to sample")]
+    accept: usize,
+    #[arg(long, default_value_t = 5, help = "The amount of rejected words to sample")]
+    reject: usize,
+    #[arg(
+        long,
+        default_value_t = 8,
+        help = "The maximum length of the sampled words, in elements of the alphabet"
+    )]
+    max_len: usize,
+    #[arg(
+        long,
+        help = "The seed to use when sampling, to get the same words every time (defaults to a random seed)"
+    )]
+    seed: Option<u64>,
+    #[arg(
+        help = "The path to the automata to sample words from, or the regex itself (not a file) for --type regex"
+    )]
+    automata: String,
+}
+
+#[derive(Default, Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+enum ConvertTarget {
+    Dfa,
+    #[default]
+    Nfa,
+    /// A right-linear grammar, converted from the NFA
+    Grammar,
+}
+
+#[derive(Default, Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+enum OutputFormat {
+    #[default]
+    Table,
+    Edges,
+}
+
 #[derive(Default, Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
 enum TestType {
     #[default]
@@ -140,6 +481,14 @@ impl Operation {
             Operation::Intersection(_) => Some(Intersection),
             Operation::Difference(_) => Some(Difference),
             Operation::SymmetricDifference(_) => Some(SymmetricDifference),
+            Operation::Concatenate(_) => Some(Concatenation),
+            _ => None,
+        }
+    }
+
+    fn unary_operation(&self) -> Option<UnaryOperation> {
+        match self {
+            Operation::Star(_) => Some(UnaryOperation::Star),
             _ => None,
         }
     }
@@ -151,6 +500,7 @@ pub enum BinaryOperation {
     Intersection,
     Difference,
     SymmetricDifference,
+    Concatenation,
 }
 
 impl BinaryOperation {
@@ -160,6 +510,7 @@ impl BinaryOperation {
             BinaryOperation::Intersection => "Intersection",
             BinaryOperation::Difference => "Difference",
             BinaryOperation::SymmetricDifference => "Symmetric difference",
+            BinaryOperation::Concatenation => "Concatenation",
         }
     }
 
@@ -169,6 +520,7 @@ impl BinaryOperation {
             BinaryOperation::Intersection => "intersection",
             BinaryOperation::Difference => "difference",
             BinaryOperation::SymmetricDifference => "symmetric difference",
+            BinaryOperation::Concatenation => "concatenation",
         }
     }
 }
@@ -179,6 +531,25 @@ impl fmt::Display for BinaryOperation {
     }
 }
 
+#[derive(Debug, Copy, Clone)]
+pub enum UnaryOperation {
+    Star,
+}
+
+impl UnaryOperation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UnaryOperation::Star => "Kleene star",
+        }
+    }
+}
+
+impl fmt::Display for UnaryOperation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 #[derive(Debug, Args)]
 struct EquivalenceArgs {
     #[arg(
@@ -212,9 +583,16 @@ struct EquivalenceArgs {
         short,
         long,
         default_value_t,
-        help = "(Only for testing 'DFA's): Requires the DFAs to be minimized"
+        help = "(Only for testing 'DFA's or 'NFA's): Requires the DFAs to be minimized, or the NFAs to have at most as \
+                many states as the main automata converted to a minimized DFA and back"
     )]
     minimized: bool,
+    #[arg(
+        long,
+        default_value_t,
+        help = "Also check if the automata are isomorphic, that is, identical up to renaming of states (only for testing DFAs against DFAs or NFAs against NFAs)"
+    )]
+    structural: bool,
     #[arg(
         short,
         long,
@@ -224,6 +602,61 @@ struct EquivalenceArgs {
     r#bool: bool,
     #[arg(short, long, help = "How many path components to print (0 to disable)")]
     path_length: Option<usize>,
+    #[arg(
+        long,
+        default_value_t = 100_000,
+        help = "The maximum amount of states allowed when converting a Regex to a DFA"
+    )]
+    max_dfa_states: usize,
+    #[arg(
+        long,
+        help = "Only test a random sample of this many of the files, for a quick check before testing all of them"
+    )]
+    sample: Option<usize>,
+    #[arg(
+        long,
+        requires = "sample",
+        help = "The seed to use when sampling files, to get the same sample every time (defaults to a random seed)"
+    )]
+    seed: Option<u64>,
+    #[arg(
+        long,
+        help = "Only print the final line with the number of passed files, and no results per file"
+    )]
+    summary_only: bool,
+    #[arg(
+        long,
+        help = "Infer the type of each file from its extension (dfa, nfa or regex), using --type for other extensions \
+                (and for the main automata, unless --in-type is given)"
+    )]
+    infer_type: bool,
+    #[arg(
+        long,
+        requires = "infer_type",
+        value_parser = AutomataType::parse_extension_mapping,
+        help = "Additional extensions to infer types from with --infer-type, such as rx=regex (may be repeated)"
+    )]
+    ext_map: Vec<(String, AutomataType)>,
+    #[arg(
+        long,
+        help = "Explain the result by printing each pair of states explored (comparing the automata as DFAs) until \
+                the first mismatch, which requires exactly one file to compare to"
+    )]
+    explain: bool,
+    #[arg(
+        long,
+        value_parser = SymbolMapping::parse,
+        conflicts_with_all = ["structural", "explain", "minimized"],
+        help = "Rename the symbols of the other files before comparing them to the main automata, such as \"0=a,1=b\" \
+                for files over 0 and 1 compared to an automata over a and b (every symbol needs to be renamed)"
+    )]
+    map: Option<SymbolMapping>,
+    #[arg(
+        short,
+        long,
+        help = "The number of threads testing files in parallel (by default one per CPU), where 1 tests sequentially"
+    )]
+    jobs: Option<usize>,
     #[arg(help = "The main automata to compare the other automatas to")]
     automata: PathBuf,
     #[arg(help = "Other files containing automata to compare to the main automata")]
@@ -266,6 +699,22 @@ struct BinaryOpArgs {
         help = "Enumerates `n` strings of the resulting product construction"
     )]
     enumerate: Option<usize>,
+    #[arg(
+        long,
+        alias = "generate-out",
+        help = "Writes the enumerated strings to this file instead of the output"
+    )]
+    enumerate_out: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Only enumerates words of at most this many elements of the alphabet"
+    )]
+    max_word_length: Option<usize>,
+    #[arg(
+        long,
+        help = "Gives up if the product construction gets more than this many states (not for concatenate)"
+    )]
+    max_states: Option<usize>,
     #[arg(help = "The first automata or regex to do the operation on")]
     first: PathBuf,
     #[arg(help = "The second automata or regex to do the operation on")]
@@ -274,6 +723,31 @@ struct BinaryOpArgs {
     compare_against: Option<PathBuf>,
 }
 
+#[derive(Debug, Args)]
+struct UnaryOpArgs {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value_t = AutomataType::Dfa,
+        help = "The type of the automata to operate on"
+    )]
+    r#type: AutomataType,
+    #[arg(
+        short,
+        long,
+        help = "Enumerates `n` strings of the result of the operation"
+    )]
+    enumerate: Option<usize>,
+    #[arg(
+        long,
+        help = "Only enumerates words of at most this many elements of the alphabet"
+    )]
+    max_word_length: Option<usize>,
+    #[arg(help = "The automata or regex to do the operation on")]
+    automata: PathBuf,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum OpType {
     Equivalence,
@@ -281,7 +755,11 @@ enum OpType {
 }
 
 fn main() {
-    let args = DandyArgs::parse();
+    let matches = DandyArgs::command().get_matches();
+    let mut args = DandyArgs::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    args.style = OutputStyle::new(args.ascii);
+    let style = args.style;
+    let operation = matches.subcommand_name().unwrap_or_default();
 
     let mut out_file = args
         .out_file
@@ -302,31 +780,114 @@ fn main() {
         };
     };
 
+    let mut stats = Stats::default();
+    let start = Instant::now();
     let result = match &args.command {
         Operation::Equivalence(eq_args) => {
-            equivalence::equivalence(&args, eq_args, &mut sink).map_err(Error::Equivalence)
+            equivalence::equivalence(&args, eq_args, &mut stats, &mut sink)
+                .map_err(Error::Equivalence)
         }
         Operation::Union(bin_args)
         | Operation::Intersection(bin_args)
         | Operation::Difference(bin_args)
-        | Operation::SymmetricDifference(bin_args) => {
+        | Operation::SymmetricDifference(bin_args)
+        | Operation::Concatenate(bin_args) => {
             let operation = args.command.binary_operation().unwrap();
-            binary_op::binary_op(&args, bin_args, operation, &mut sink)
+            binary_op::binary_op(&args, bin_args, operation, &mut stats, &mut sink)
                 .map_err(|e| Error::Binary(operation, e))
         }
+        Operation::Star(unary_args) => {
+            let operation = args.command.unary_operation().unwrap();
+            unary_op::unary_op(&args, unary_args, operation, &mut sink)
+                .map(|()| Outcome::Passed)
+                .map_err(|e| Error::Unary(operation, e))
+        }
         Operation::TestFile(test_args) => {
             test_files::test_files(&args, test_args, &mut sink).map_err(Error::TestFile)
         }
         Operation::EnumerateRegex(regex_args) => {
-            enumerate::enumerate_regex(&args, regex_args, &mut sink).map_err(Error::EnumerateRegex)
+            enumerate::enumerate_regex(&args, regex_args, &mut sink)
+                .map(|()| Outcome::Passed)
+                .map_err(Error::EnumerateRegex)
         }
         Operation::EnumerateFile(file_args) => {
-            enumerate::enumerate_file(&args, file_args, &mut sink).map_err(Error::EnumerateFile)
+            enumerate::enumerate_file(&args, file_args, &mut sink)
+                .map(|()| Outcome::Passed)
+                .map_err(Error::EnumerateFile)
+        }
+        Operation::Convert(convert_args) => convert::convert(&args, convert_args, &mut sink)
+            .map(|()| Outcome::Passed)
+            .map_err(Error::Convert),
+        Operation::Diff(diff_args) => diff::diff(&args, diff_args, &mut sink)
+            .map(|()| Outcome::Passed)
+            .map_err(Error::Diff),
+        Operation::Validate(validate_args) => {
+            validate::validate(&args, validate_args, &mut sink).map_err(Error::Validate)
+        }
+        Operation::Sample(sample_args) => sample::sample(&args, sample_args, &mut sink)
+            .map(|()| Outcome::Passed)
+            .map_err(Error::Sample),
+        Operation::Fingerprint(fingerprint_args) => {
+            fingerprint::fingerprint(&args, fingerprint_args, &mut sink)
+                .map(|()| Outcome::Passed)
+                .map_err(Error::Fingerprint)
+        }
+        Operation::Grade(grade_args) => {
+            grade::grade(&args, grade_args, &mut sink).map_err(Error::Grade)
         }
     };
 
-    if let Err(e) = result {
-        eprintln!("{e}");
+    if args.stats || args.stats_out.is_some() {
+        let record = stats.to_json(operation, start.elapsed());
+        match &args.stats_out {
+            Some(path) => {
+                if let Err(e) = std::fs::write(path, format!("{record}\n")) {
+                    eprintln!("Error writing stats to {}: {e}", path.display());
+                }
+            }
+            None => eprintln!("{record}"),
+        }
+    }
+
+    let code = match result {
+        Ok(outcome) => outcome.exit_code(),
+        Err(e) => {
+            eprintln!("{}", style.text(&e.to_string()));
+            EXIT_ERROR
+        }
+    };
+    std::process::exit(code);
+}
+
+/// The exit code when a command fails with an error, such as an unreadable file or a main automaton which can't be
+/// parsed. This is the same exit code as clap uses for usage errors.
+const EXIT_ERROR: i32 = 2;
+
+/// The outcome of a command which completed without errors, which decides the exit code of the program. Commands
+/// which don't check anything always pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Every check passed, exiting with code 0
+    Passed,
+    /// Some check failed, such as a file not being equivalent or a line being rejected, exiting with code 1
+    Failed,
+}
+
+impl Outcome {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Passed => 0,
+            Outcome::Failed => 1,
+        }
+    }
+
+    /// Gives [Outcome::Passed] if `passed` is true and [Outcome::Failed] otherwise
+    pub fn passed_if(passed: bool) -> Self {
+        if passed {
+            Outcome::Passed
+        } else {
+            Outcome::Failed
+        }
     }
 }
 
@@ -336,12 +897,26 @@ enum Error {
     Equivalence(String),
     #[error("Error in {0}: {1}")]
     Binary(BinaryOperation, String),
+    #[error("Error in {0}: {1}")]
+    Unary(UnaryOperation, String),
     #[error("Error in testing file: {0}")]
     TestFile(String),
     #[error("Error in enumerating regex: {0}")]
     EnumerateRegex(String),
     #[error("Error in enumerating file: {0}")]
     EnumerateFile(String),
+    #[error("Error in converting: {0}")]
+    Convert(String),
+    #[error("Error in diffing: {0}")]
+    Diff(String),
+    #[error("Error in validating: {0}")]
+    Validate(String),
+    #[error("Error in sampling: {0}")]
+    Sample(String),
+    #[error("Error in fingerprinting: {0}")]
+    Fingerprint(String),
+    #[error("Error in grading: {0}")]
+    Grade(String),
 }
 
 pub fn last_n_components(path: &Path, n: Option<usize>) -> Option<String> {
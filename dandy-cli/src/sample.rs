@@ -0,0 +1,102 @@
+use crate::automata::{Automata, AutomataType};
+use crate::{DandyArgs, SampleArgs};
+use dandy::dfa::Dfa;
+use dandy::parser;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// The maximum number of words to choose the sampled words from, on each side. The candidates are the shortest
+/// words, so with a large language and a large `--max-len`, the longest words are never sampled.
+const MAX_CANDIDATES: usize = 10_000;
+
+pub fn sample(
+    main_args: &DandyArgs,
+    args: &SampleArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    let automata = if args.r#type == AutomataType::Regex {
+        parser::regex(&args.automata)
+            .map(|regex| Automata::Regex(regex, None))
+            .map_err(|e| e.to_string())?
+    } else {
+        Automata::load_file(args.automata.as_ref(), args.r#type)?
+    };
+    let (accepting, _) = automata.into_minimized_dfa();
+    let mut rejecting = accepting.clone();
+    rejecting.invert();
+
+    let seed = args.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    log!(
+        "Sampling words of length up to {} (seed {seed})",
+        args.max_len
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    for (dfa, amount, kind) in [
+        (accepting, args.accept, "accepted"),
+        (rejecting, args.reject, "rejected"),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let words = sample_words(dfa, amount, args.max_len, &mut rng);
+        if words.is_empty() {
+            output!("no {kind} words exist up to length {}", args.max_len);
+            continue;
+        }
+        for word in &words {
+            if word.is_empty() {
+                output!("{kind}: (empty word)");
+            } else {
+                output!("{kind}: {word}");
+            }
+        }
+        if words.len() < amount {
+            log!(
+                "(only {} {kind} words exist up to length {})",
+                words.len(),
+                args.max_len
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Randomly selects `amount` of the words accepted by the DFA with at most `max_len` elements, among the
+/// [MAX_CANDIDATES] shortest ones. The selected words are given in the order they are enumerated, that is, by
+/// length and then lexicographically.
+fn sample_words(dfa: Dfa, amount: usize, max_len: usize, rng: &mut StdRng) -> Vec<String> {
+    let lengths = (0..=max_len)
+        .filter(|&len| dfa.count_words_of_length(len) != 0)
+        .collect::<Vec<_>>();
+    let nfa = dfa.to_nfa();
+    let candidates = lengths
+        .into_iter()
+        .flat_map(|len| nfa.words_of_length(len))
+        .take(MAX_CANDIDATES)
+        .collect::<Vec<_>>();
+    let mut indices = (0..candidates.len())
+        .collect::<Vec<_>>()
+        .choose_multiple(rng, amount)
+        .copied()
+        .collect::<Vec<_>>();
+    indices.sort_unstable();
+    indices
+        .into_iter()
+        .map(|idx| candidates[idx].clone())
+        .collect()
+}
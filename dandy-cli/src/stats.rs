@@ -0,0 +1,189 @@
+//! Metrics of a run, printed as one line of JSON (to stderr, or to the file given by `--stats-out`) when the program
+//! is run with `--stats`. The record is meant to be collected across runs for tracking performance, so its fields are
+//! kept stable (new fields may be added, and `version` is increased if existing ones change):
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "operation": "equivalence",
+//!   "inputs": [{"path": "spec.nfa", "type": "nfa", "states": 6, "alphabet": 2, "epsilon_cycles": false,
+//!     "ambiguous": true, "max_branching": 2, "nondeterministic_states": 1, "minimal_dfa_states": 4}],
+//!   "output": {"path": null, "type": "dfa", "states": 4, "alphabet": 2, "epsilon_cycles": false,
+//!     "ambiguous": false, "max_branching": 1, "nondeterministic_states": 0, "minimal_dfa_states": 4},
+//!   "peak_states": 5,
+//!   "durations_ms": {"parse": 0.1, "convert": 0.3, "construct": 0.0, "minimize": 0.1, "compare": 2.5, "total": 3.2}
+//! }
+//! ```
+//!
+//! - `operation` is the name of the subcommand
+//! - `inputs` are the automata the subcommand loaded as its main inputs (the specification for `equivalence`, both
+//!   operands of binary operations), with the path it was loaded from, its type (`dfa`, `nfa` or `regex`), and its
+//!   number of states and size of its alphabet, as well as how nondeterministic it is: whether it contains epsilon
+//!   cycles (see [Nfa::epsilon_cycles](dandy::nfa::Nfa::epsilon_cycles)), whether it is
+//!   [ambiguous](dandy::nfa::Nfa::is_ambiguous), its [largest transition set](dandy::nfa::Nfa::max_branching) and its
+//!   number of [nondeterministic states](dandy::nfa::Nfa::nondeterministic_states) (for a DFA, these are the ones of
+//!   the DFA converted to an NFA). It also has the number of states of its minimal DFA (see
+//!   [Dfa::minimal_size](dandy::dfa::Dfa::minimal_size)), which is `null` for an NFA whose subset construction
+//!   exceeds 10 000 states. All of these are `null` for regexes.
+//! - `output` is the automaton the subcommand printed, if any, on the same form (where `path` is always `null`)
+//! - `peak_states` is the largest number of states of an intermediate DFA, such as a DFA from the subset construction
+//!   or a product construction before it is minimized, or `null` if no DFA was constructed
+//! - `durations_ms` are the wall-clock times of the phases of the subcommand in milliseconds, which are `0` for
+//!   phases the subcommand didn't run: parsing the inputs (`parse`), converting them between types (`convert`),
+//!   constructing a new automaton from them, such as a product (`construct`), minimizing DFAs (`minimize`), and
+//!   testing other automata against the inputs (`compare`), as well as the whole subcommand (`total`)
+//!
+//! Only `equivalence` and the binary operations measure their phases and sizes, other subcommands only report their
+//! operation and total duration.
+
+use crate::automata::Automata;
+use dandy::dfa::Dfa;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The version of the record, increased when existing fields change
+const VERSION: u32 = 1;
+
+/// The largest number of states of the subset construction when computing the size of the minimal DFA of an NFA
+const MAX_SUBSET_STATES: usize = 10_000;
+
+/// The phases of a subcommand which are timed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Convert,
+    Construct,
+    Minimize,
+    Compare,
+}
+
+impl Phase {
+    const ALL: [Phase; 5] = [
+        Phase::Parse,
+        Phase::Convert,
+        Phase::Construct,
+        Phase::Minimize,
+        Phase::Compare,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Convert => "convert",
+            Phase::Construct => "construct",
+            Phase::Minimize => "minimize",
+            Phase::Compare => "compare",
+        }
+    }
+}
+
+/// The metrics collected while running a subcommand
+#[derive(Debug, Default)]
+pub struct Stats {
+    inputs: Vec<Value>,
+    output: Option<Value>,
+    peak_states: Option<usize>,
+    durations: [Duration; Phase::ALL.len()],
+}
+
+impl Stats {
+    /// Runs `f`, adding the time it took to the phase
+    pub fn time<T>(&mut self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.add(phase, start.elapsed());
+        result
+    }
+
+    /// Adds a duration measured elsewhere to the phase
+    pub fn add(&mut self, phase: Phase, duration: Duration) {
+        self.durations[phase as usize] += duration;
+    }
+
+    /// Records the size of an automaton loaded as an input from `path`
+    pub fn input(&mut self, path: &Path, automata: &Automata) {
+        self.inputs.push(size(path, automata));
+    }
+
+    /// Records the size of the automaton the subcommand printed
+    pub fn output(&mut self, dfa: &Dfa) {
+        self.output = Some(dfa_size(dfa));
+    }
+
+    /// Records the number of states of an intermediate DFA, keeping the largest one
+    pub fn peak(&mut self, states: usize) {
+        self.peak_states = Some(self.peak_states.map_or(states, |peak| peak.max(states)));
+    }
+
+    /// Gives the record as one line of JSON
+    pub fn to_json(&self, operation: &str, total: Duration) -> String {
+        let mut durations = Phase::ALL
+            .iter()
+            .map(|&phase| {
+                (
+                    phase.name().to_string(),
+                    millis(self.durations[phase as usize]),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        durations.insert("total".to_string(), millis(total));
+        json!({
+            "version": VERSION,
+            "operation": operation,
+            "inputs": self.inputs,
+            "output": self.output,
+            "peak_states": self.peak_states,
+            "durations_ms": durations,
+        })
+        .to_string()
+    }
+}
+
+fn size(path: &Path, automata: &Automata) -> Value {
+    let mut size = match automata {
+        Automata::Dfa(dfa) => dfa_size(dfa),
+        Automata::Nfa(nfa) => json!({
+            "type": "nfa",
+            "states": nfa.states().len(),
+            "alphabet": nfa.alphabet().len(),
+            "epsilon_cycles": nfa.has_epsilon_cycles(),
+            "ambiguous": nfa.is_ambiguous(),
+            "max_branching": nfa.max_branching(),
+            "nondeterministic_states": nfa.nondeterministic_states().len(),
+            "minimal_dfa_states": nfa.minimal_dfa_size_bounded(MAX_SUBSET_STATES).ok(),
+        }),
+        Automata::Regex(..) => json!({
+            "type": "regex",
+            "states": null,
+            "alphabet": null,
+            "epsilon_cycles": null,
+            "ambiguous": null,
+            "max_branching": null,
+            "nondeterministic_states": null,
+            "minimal_dfa_states": null,
+        }),
+    };
+    size["path"] = json!(path.display().to_string());
+    size
+}
+
+/// The size of a DFA, where the metrics of nondeterminism are the ones of the DFA converted to an NFA
+fn dfa_size(dfa: &Dfa) -> Value {
+    let has_transitions = !dfa.states().is_empty() && !dfa.alphabet().is_empty();
+    json!({
+        "path": null,
+        "type": "dfa",
+        "states": dfa.states().len(),
+        "alphabet": dfa.alphabet().len(),
+        "epsilon_cycles": false,
+        "ambiguous": false,
+        "max_branching": usize::from(has_transitions),
+        "nondeterministic_states": 0,
+        "minimal_dfa_states": dfa.minimal_size(),
+    })
+}
+
+fn millis(duration: Duration) -> Value {
+    json!(duration.as_secs_f64() * 1000.0)
+}
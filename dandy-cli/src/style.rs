@@ -0,0 +1,78 @@
+use crate::automata::Automata;
+use dandy::dfa::Dfa;
+use dandy::nfa::Nfa;
+use std::borrow::Cow;
+use std::env;
+
+/// The environment variable which enables `--ascii` when set to `1`
+const ASCII_ENV: &str = "DANDY_ASCII";
+
+/// The symbols written by dandy which aren't ASCII, and what they are replaced by with `--ascii`
+const ASCII_REPLACEMENTS: [(char, &str); 6] = [
+    ('ε', "eps"),
+    ('→', "->"),
+    ('←', "<-"),
+    ('∅', "{}"),
+    ('—', "-"),
+    ('…', "..."),
+];
+
+/// How the output of the CLI is written. With `--ascii` (or `DANDY_ASCII=1`), tables are written with `->` and `eps`
+/// (like [Dfa::ascii_table]), and ε, → and the other symbols dandy writes are replaced by ASCII in all other output,
+/// for terminals and logs which garble unicode. Names of states and elements are written as they are in the tables,
+/// since replacing symbols in them could make the tables ambiguous, and regexes are written as they are, since `eps`
+/// and `{}` would be read as symbols rather than as ε and ∅.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OutputStyle {
+    ascii: bool,
+}
+
+impl OutputStyle {
+    /// The style given by the `--ascii` flag, which is also set if the environment variable `DANDY_ASCII` is `1`
+    pub fn new(ascii: bool) -> Self {
+        Self {
+            ascii: ascii || env::var(ASCII_ENV).is_ok_and(|value| value == "1"),
+        }
+    }
+
+    pub fn dfa_table(self, dfa: &Dfa) -> String {
+        if self.ascii {
+            dfa.ascii_table()
+        } else {
+            dfa.to_table()
+        }
+    }
+
+    pub fn nfa_table(self, nfa: &Nfa) -> String {
+        if self.ascii {
+            nfa.ascii_table()
+        } else {
+            nfa.to_table()
+        }
+    }
+
+    /// The table of a DFA or NFA, or the regex itself
+    pub fn table(self, automata: &Automata) -> String {
+        match automata {
+            Automata::Dfa(dfa) => self.dfa_table(dfa),
+            Automata::Nfa(nfa) => self.nfa_table(nfa),
+            Automata::Regex(regex, _) => regex.to_string(),
+        }
+    }
+
+    /// Replaces the non-ASCII symbols in text written by dandy, such as ε in words and error messages, if the output
+    /// should be ASCII
+    pub fn text(self, s: &str) -> Cow<'_, str> {
+        if !self.ascii || s.is_ascii() {
+            return Cow::Borrowed(s);
+        }
+        let mut replaced = String::with_capacity(s.len());
+        for c in s.chars() {
+            match ASCII_REPLACEMENTS.iter().find(|(symbol, _)| *symbol == c) {
+                Some((_, replacement)) => replaced.push_str(replacement),
+                None => replaced.push(c),
+            }
+        }
+        Cow::Owned(replaced)
+    }
+}
@@ -1,62 +1,311 @@
-use crate::automata::Automata;
-use crate::{DandyArgs, TestFileArgs, TestType};
-use std::fs;
+use crate::automata::{self, Automata};
+use crate::{DandyArgs, Outcome, TestFileArgs, TestType};
+use dandy::analysis::{ActiveProfile, CorpusReport, Coverage};
+use dandy::batch::{self, DfaParts, NfaParts};
+use dandy::dfa::Dfa;
+use dandy::nfa::Nfa;
+
+/// The minimum number of lines in a file for the lines to be tested in parallel, since every thread has to put the
+/// automaton together again
+const PARALLEL_THRESHOLD: usize = 1024;
+
+/// The number of lines tested in a row by each thread, after putting the automaton together
+const CHUNK_LEN: usize = 4096;
 
 pub fn test_files(
     main_args: &DandyArgs,
     args: &TestFileArgs,
     #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
-) -> Result<(), String> {
+) -> Result<Outcome, String> {
     #[allow(unused_variables)]
     let log = |s: &str| {
-        if !main_args.no_log {
+        if !main_args.no_log && !args.summary_only {
             println!("{s}")
         }
     };
     macro_rules! log {
-        ($($t:tt)*) => (log(&format!($($t)*)))
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
     }
 
     macro_rules! output {
-        ($($t:tt)*) => (output(&format!($($t)*)))
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
     }
 
-    let automata = Automata::load_file(&args.automata, args.r#type)?;
-    let (nfa, _) = automata.into_nfa();
-    log!("Loaded NFA:\n{}", nfa.to_table());
+    // With --summary-only, only the final number of passed files is printed
+    macro_rules! result {
+        ($($t:tt)*) => (if !args.summary_only { output!($($t)*) })
+    }
 
+    let tester = Tester::load(args)?;
+    log!("Loaded NFA:");
+    log(&main_args.style.nfa_table(&tester.nfa));
+    let mut missing_symbols = false;
+    if let Some(path) = &args.check_alphabet {
+        let corpus =
+            automata::read_file(path).map_err(|e| automata::Error::File(path, e).to_string())?;
+        let missing = tester.nfa.missing_symbols_for(corpus.lines());
+        if !missing.is_empty() {
+            missing_symbols = true;
+            output!(
+                "Symbols in {} missing from the alphabet: {}",
+                path.display(),
+                automata::quoted_list(&missing)
+            );
+        }
+    }
+    let mut report = args.report.then(CorpusReport::default);
+    let mut profile = args.profile.then(ActiveProfile::default);
+    let mut coverage = tester.coverage.as_ref().map(Coverage::new);
+    let mut passed_files = 0;
+    // Automata can't be shared between threads, so each thread puts its own tester together from these parts
+    let parts = TesterParts::new(&tester);
     for file in &args.files {
-        let loaded_file = fs::read_to_string(file).map_err(|e| e.to_string())?;
+        let loaded_file = automata::read_file(file).map_err(|e| e.to_string())?;
+        let lines = loaded_file.lines().collect::<Vec<_>>();
+        // With the files test type, only the first rejected line of each file gets a suggestion
+        let suggest = args.test_type == TestType::Lines;
+        let chunks = if args.jobs == Some(1) || lines.len() < PARALLEL_THRESHOLD {
+            vec![tester.test_chunk(&lines, suggest)]
+        } else {
+            batch::map_with_state(
+                lines.chunks(CHUNK_LEN).collect(),
+                args.jobs.unwrap_or(0),
+                || parts.clone().into_tester(),
+                |tester, chunk| tester.test_chunk(chunk, suggest),
+            )
+        };
+        let mut results = Vec::with_capacity(lines.len());
+        for chunk in chunks {
+            results.extend(chunk.rejections);
+            if let (Some(report), Some(chunk)) = (&mut report, &chunk.report) {
+                report.merge(chunk);
+            }
+            if let (Some(profile), Some(chunk)) = (&mut profile, &chunk.profile) {
+                profile.merge(chunk);
+            }
+            if let (Some(coverage), Some(dfa), Some(chunk)) =
+                (&mut coverage, &tester.coverage, chunk.coverage)
+            {
+                coverage.merge(&chunk.into_coverage(dfa));
+            }
+        }
         if args.test_type == TestType::Lines {
-            output!("Testing file {}:", file.display());
-            let mut n = 0;
+            result!("Testing file {}:", file.display());
             let mut a = 0;
-            for line in loaded_file.lines() {
-                n += 1;
-                let accepted = nfa.accepts_graphemes(line);
-                let ok = if accepted {
-                    a += 1;
-                    "[ OK ]"
-                } else {
-                    "[FAIL]"
-                };
-                output!("{ok} {line}");
+            for (line, rejection) in lines.iter().zip(&results) {
+                match rejection {
+                    None => {
+                        a += 1;
+                        result!("[ OK ] {line}");
+                    }
+                    Some(suggestion) => result!("[FAIL] {line}{suggestion}"),
+                }
+            }
+            let n = lines.len();
+            result!("{a}/{n} lines passed in file {}:", file.display());
+            if a == n {
+                passed_files += 1;
             }
-            output!("{a}/{n} lines passed in file {}:", file.display());
         } else {
-            let counterexample = loaded_file
-                .lines()
-                .find(|line| !nfa.accepts_graphemes(line));
-            match counterexample {
+            match lines
+                .iter()
+                .zip(&results)
+                .find(|(_, rejection)| rejection.is_some())
+            {
                 None => {
-                    output!("[ OK ] {}", file.display())
+                    passed_files += 1;
+                    result!("[ OK ] {}", file.display())
                 }
-                Some(c) => {
-                    output!("[FAIL] {} failed on {c}", file.display())
+                Some((c, _)) => {
+                    let suggestion = tester.suggestion(c);
+                    result!("[FAIL] {} failed on {c}{suggestion}", file.display())
                 }
             }
         }
     }
 
-    Ok(())
+    if let Some(report) = report {
+        output!("{report}");
+    }
+    if let Some(profile) = profile {
+        output!("{profile}");
+    }
+    if let (Some(coverage), Some(dfa)) = (coverage, &tester.coverage) {
+        output!("{coverage}");
+        if args.annotate_coverage {
+            output(&coverage.annotate_table(&main_args.style.dfa_table(dfa)));
+        }
+    }
+
+    let summary = format!("{passed_files}/{} files passed", args.files.len());
+    if args.summary_only {
+        output!("{summary}");
+    } else {
+        log!("{summary}");
+    }
+
+    Ok(Outcome::passed_if(
+        passed_files == args.files.len() && !missing_symbols,
+    ))
+}
+
+/// The automaton to test lines against, along with the DFA used to suggest words if `--suggest` is given and which
+/// statistics to collect about the lines
+struct Tester {
+    nfa: Nfa,
+    dfa: Option<Dfa>,
+    max_edits: usize,
+    report: bool,
+    profile: bool,
+    /// The DFA to count the coverage of with `--coverage`, which is the DFA itself if a DFA is tested, and otherwise
+    /// the DFA from the subset construction
+    coverage: Option<Dfa>,
+}
+
+/// A [Tester] taken apart (see [DfaParts]), which can be sent between threads
+#[derive(Clone)]
+struct TesterParts {
+    nfa: NfaParts,
+    dfa: Option<DfaParts>,
+    max_edits: usize,
+    report: bool,
+    profile: bool,
+    coverage: Option<DfaParts>,
+}
+
+impl TesterParts {
+    fn new(tester: &Tester) -> Self {
+        Self {
+            nfa: NfaParts::new(&tester.nfa),
+            dfa: tester.dfa.as_ref().map(DfaParts::new),
+            max_edits: tester.max_edits,
+            report: tester.report,
+            profile: tester.profile,
+            coverage: tester.coverage.as_ref().map(DfaParts::new),
+        }
+    }
+
+    fn into_tester(self) -> Tester {
+        Tester {
+            nfa: self.nfa.into_nfa(),
+            dfa: self.dfa.map(DfaParts::into_dfa),
+            max_edits: self.max_edits,
+            report: self.report,
+            profile: self.profile,
+            coverage: self.coverage.map(DfaParts::into_dfa),
+        }
+    }
+}
+
+/// The results of testing a chunk of lines with [Tester::test_chunk], along with the statistics of those lines which
+/// were asked for
+struct ChunkResults {
+    /// For each line, `None` if it was accepted and otherwise the suggestion to print after it
+    rejections: Vec<Option<String>>,
+    report: Option<CorpusReport>,
+    profile: Option<ActiveProfile>,
+    coverage: Option<CoverageCounts>,
+}
+
+/// The counts of a [Coverage], which (unlike the coverage, which shares the names of the DFA) can be sent between
+/// threads
+struct CoverageCounts {
+    state_visits: Vec<usize>,
+    transition_counts: Vec<Vec<usize>>,
+    inputs: usize,
+}
+
+impl CoverageCounts {
+    fn new(coverage: Coverage) -> Self {
+        Self {
+            state_visits: coverage.state_visits,
+            transition_counts: coverage.transition_counts,
+            inputs: coverage.inputs,
+        }
+    }
+
+    /// The coverage with these counts, where `dfa` is the DFA the counts were counted on
+    fn into_coverage(self, dfa: &Dfa) -> Coverage {
+        let mut coverage = Coverage::new(dfa);
+        coverage.state_visits = self.state_visits;
+        coverage.transition_counts = self.transition_counts;
+        coverage.inputs = self.inputs;
+        coverage
+    }
+}
+
+impl Tester {
+    fn load(args: &TestFileArgs) -> Result<Self, String> {
+        let automata = Automata::load_file(&args.automata, args.r#type)?;
+        let tested_dfa = match &automata {
+            Automata::Dfa(dfa) if args.coverage => Some(dfa.clone()),
+            _ => None,
+        };
+        let (nfa, _) = automata.into_nfa();
+        let dfa = args.suggest.then(|| nfa.to_dfa());
+        let coverage = args
+            .coverage
+            .then(|| tested_dfa.unwrap_or_else(|| nfa.to_dfa()));
+        Ok(Self {
+            nfa,
+            dfa,
+            max_edits: args.max_edits,
+            report: args.report,
+            profile: args.profile,
+            coverage,
+        })
+    }
+
+    /// Tests every line of a chunk (see [Tester::test]) and collects the statistics of them in the same pass
+    fn test_chunk(&self, lines: &[&str], suggest: bool) -> ChunkResults {
+        let mut report = self.report.then(CorpusReport::default);
+        let mut profile = self.profile.then(ActiveProfile::default);
+        let mut coverage = self.coverage.as_ref().map(Coverage::new);
+        let rejections = lines
+            .iter()
+            .map(|line| {
+                if let Some(report) = &mut report {
+                    report.record(&self.nfa, line);
+                }
+                if let Some(profile) = &mut profile {
+                    profile.record(&self.nfa, line);
+                }
+                if let (Some(coverage), Some(dfa)) = (&mut coverage, &self.coverage) {
+                    coverage.record(dfa, line);
+                }
+                self.test(line, suggest)
+            })
+            .collect();
+        ChunkResults {
+            rejections,
+            report,
+            profile,
+            coverage: coverage.map(CoverageCounts::new),
+        }
+    }
+
+    /// Tests a line, giving `None` if it is accepted and otherwise the suggestion to print after it (which is empty
+    /// without `--suggest`, or if `suggest` is false)
+    fn test(&self, line: &str, suggest: bool) -> Option<String> {
+        if self.nfa.accepts_graphemes(line) {
+            None
+        } else if suggest {
+            Some(self.suggestion(line))
+        } else {
+            Some(String::new())
+        }
+    }
+
+    /// The suggestion to print after a rejected line, which is empty without `--suggest`
+    fn suggestion(&self, line: &str) -> String {
+        match &self.dfa {
+            None => String::new(),
+            Some(dfa) => match dfa.closest_accepted_graphemes(line, self.max_edits) {
+                Some((word, distance)) => {
+                    format!(" (did you mean \"{word}\"? {distance} edit(s) away)")
+                }
+                None => format!(" (no accepted word within {} edit(s))", self.max_edits),
+            },
+        }
+    }
 }
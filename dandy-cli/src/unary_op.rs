@@ -0,0 +1,67 @@
+use crate::automata::Automata;
+use crate::enumerate::{enumerate_words, only_words_note};
+use crate::{DandyArgs, UnaryOpArgs, UnaryOperation};
+
+pub fn unary_op(
+    main_args: &DandyArgs,
+    args: &UnaryOpArgs,
+    op: UnaryOperation,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<(), String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    let (dfa, converted) =
+        Automata::load_file(&args.automata, args.r#type)?.into_minimized_dfa_if_not_dfa();
+    if converted {
+        log!("The input file was converted to a minimized DFA to proceed, since it wasn't a DFA to start with");
+    }
+
+    let result = match op {
+        UnaryOperation::Star => dfa.star(),
+    };
+
+    #[cfg(feature = "provenance")]
+    if let Some(provenance) = result.provenance() {
+        log!("Created by {provenance}");
+    }
+
+    log!(
+        "Minimized DFA ({} of the provided {}):",
+        op,
+        args.r#type.to_string(false)
+    );
+    output(&main_args.style.dfa_table(&result));
+
+    if let Some(n) = args.enumerate {
+        log!("First {n} words in the language of the {}:", op);
+        let x = enumerate_words(
+            &result.to_nfa(),
+            main_args,
+            n,
+            args.max_word_length,
+            |word| {
+                output!("{word}");
+                Ok(())
+            },
+        )?;
+        if x != n {
+            log!(
+                "{}",
+                only_words_note(x, args.max_word_length, &op.to_string())
+            );
+        }
+    }
+    Ok(())
+}
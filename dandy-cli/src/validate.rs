@@ -0,0 +1,182 @@
+use crate::automata::{self, Automata, AutomataType};
+use crate::{DandyArgs, Outcome, ValidateArgs};
+use std::collections::BTreeSet;
+use std::path::Path;
+use thiserror::Error;
+
+pub fn validate(
+    main_args: &DandyArgs,
+    args: &ValidateArgs,
+    #[allow(unused_variables, unused_mut)] mut output: impl FnMut(&str),
+) -> Result<Outcome, String> {
+    #[allow(unused_variables)]
+    let log = |s: &str| {
+        if !main_args.no_log {
+            println!("{s}")
+        }
+    };
+    macro_rules! log {
+        ($($t:tt)*) => (log(&main_args.style.text(&format!($($t)*))))
+    }
+
+    macro_rules! output {
+        ($($t:tt)*) => (output(&main_args.style.text(&format!($($t)*))))
+    }
+
+    // With `--type auto`, the type of each file is only known once it is loaded, so the options are checked for
+    // each file instead
+    if args.minimal && !matches!(args.r#type, AutomataType::Dfa | AutomataType::Auto) {
+        return Err(Error::MinimalNotDfa.to_string());
+    }
+    if args.lint && args.r#type == AutomataType::Regex {
+        return Err(Error::LintRegex.to_string());
+    }
+    let alphabet = args
+        .alphabet
+        .as_ref()
+        .map(|alphabet| alphabet.split_whitespace().collect::<BTreeSet<_>>());
+    let corpus = match &args.check_alphabet {
+        Some(path) => Some(
+            automata::read_file(path).map_err(|e| automata::Error::File(path, e).to_string())?,
+        ),
+        None => None,
+    };
+
+    log!(
+        "Validating {} {}",
+        args.files.len(),
+        args.r#type.to_string(args.files.len() != 1)
+    );
+    let mut passed = 0;
+    for file in &args.files {
+        let automata = match Automata::load_file(file, args.r#type) {
+            Ok(automata) => automata,
+            Err(e) => {
+                output!("{}", Error::InFile(file, e.trim_end().to_string()));
+                continue;
+            }
+        };
+
+        if args.lint {
+            for warning in lint(&automata) {
+                output!("{}", Error::InFile(file, format!("warning: {warning}")));
+            }
+        }
+        let problem = alphabet
+            .as_ref()
+            .and_then(|alphabet| wrong_alphabet(&automata, alphabet))
+            .or_else(|| missing_symbols(&automata, corpus.as_deref()?))
+            .or_else(|| {
+                if !args.minimal {
+                    return None;
+                }
+                let problem = match automata.borrow_dfa() {
+                    Some(dfa) => (!dfa.is_minimal()).then_some(Error::NotMinimal)?,
+                    None => Error::MinimalOfType(automata.get_type().to_string(false)),
+                };
+                Some(problem.to_string())
+            });
+        match problem {
+            Some(problem) => output!("{}", Error::InFile(file, problem)),
+            None => {
+                passed += 1;
+                output!("{}: OK", file.display());
+            }
+        }
+    }
+
+    output!("{passed}/{} files valid", args.files.len());
+    Ok(Outcome::passed_if(passed == args.files.len()))
+}
+
+/// Describes how the alphabet of the automata differs from the required alphabet, or gives `None` if they are the
+/// same (not considering ordering)
+fn wrong_alphabet(automata: &Automata, required: &BTreeSet<&str>) -> Option<String> {
+    let alphabet = match automata {
+        Automata::Dfa(dfa) => dfa.alphabet().to_vec(),
+        Automata::Nfa(nfa) => nfa.alphabet().to_vec(),
+        Automata::Regex(..) => automata.clone().into_nfa().0.alphabet().to_vec(),
+    };
+    let alphabet = alphabet.iter().map(|e| e.as_ref()).collect::<BTreeSet<_>>();
+    if &alphabet == required {
+        return None;
+    }
+    let missing = automata::quoted_list(required.difference(&alphabet));
+    let extra = automata::quoted_list(alphabet.difference(required));
+    let mut parts = vec![];
+    if !missing.is_empty() {
+        parts.push(format!("missing {missing}"));
+    }
+    if !extra.is_empty() {
+        parts.push(format!("unexpected {extra}"));
+    }
+    Some(Error::WrongAlphabet(parts.join(", ")).to_string())
+}
+
+/// Describes the symbols in the lines of a corpus which aren't in the alphabet of the automata, or gives `None` if
+/// there are none
+fn missing_symbols(automata: &Automata, corpus: &str) -> Option<String> {
+    let missing = match automata {
+        Automata::Dfa(dfa) => dfa.missing_symbols_for(corpus.lines()),
+        Automata::Nfa(nfa) => nfa.missing_symbols_for(corpus.lines()),
+        Automata::Regex(..) => automata
+            .clone()
+            .into_nfa()
+            .0
+            .missing_symbols_for(corpus.lines()),
+    };
+    if missing.is_empty() {
+        return None;
+    }
+    Some(Error::MissingSymbols(automata::quoted_list(&missing)).to_string())
+}
+
+/// Finds problems with an automata which don't make it invalid, but which are most likely mistakes: states which
+/// can't be reached from the initial state, and symbols which aren't part of any accepted word
+fn lint(automata: &Automata) -> Vec<String> {
+    let nfa = match automata {
+        Automata::Dfa(dfa) => dfa.clone().to_nfa(),
+        Automata::Nfa(nfa) => nfa.clone(),
+        Automata::Regex(..) => return vec![],
+    };
+    let mut warnings = vec![];
+    let unreachable = nfa.unreachable_states();
+    if !unreachable.is_empty() {
+        let names = unreachable
+            .iter()
+            .map(|s| s.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+        warnings.push(format!("unreachable states: {names}"));
+    }
+    // The symbols in the accepted words of a NFA are the same as those of its DFA
+    let dead = match automata {
+        Automata::Dfa(dfa) => dfa.unused_symbols(),
+        _ => nfa.to_dfa().unused_symbols(),
+    };
+    if !dead.is_empty() {
+        warnings.push(format!(
+            "symbols not in any accepted word: {}",
+            dead.join(", ")
+        ));
+    }
+    warnings
+}
+
+#[derive(Debug, Error)]
+pub enum Error<'a> {
+    #[error("{}: {1}", .0.display())]
+    InFile(&'a Path, String),
+    #[error("Can only check minimality of DFAs (use --type dfa)")]
+    MinimalNotDfa,
+    #[error("Can only lint DFAs and NFAs")]
+    LintRegex,
+    #[error("not minimal")]
+    NotMinimal,
+    #[error("can only check minimality of DFAs, but this is a {0}")]
+    MinimalOfType(&'static str),
+    #[error("wrong alphabet ({0})")]
+    WrongAlphabet(String),
+    #[error("symbols of the corpus missing from the alphabet ({0})")]
+    MissingSymbols(String),
+}
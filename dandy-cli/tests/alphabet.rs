@@ -0,0 +1,77 @@
+mod common;
+
+use common::{dandy, stdout};
+
+#[test]
+fn equivalence_lists_alphabet_differences() {
+    let output = stdout(
+        dandy().args([
+            "--less-logs",
+            "equivalence",
+            "tests/validate/good.dfa",
+            "tests/validate/wrong_alphabet.dfa",
+        ]),
+        1,
+    );
+    assert!(
+        output.contains("Not equivalent due to alphabets (missing \"c\")"),
+        "{output}"
+    );
+}
+
+#[test]
+fn validate_checks_alphabet_against_corpus() {
+    let output = stdout(
+        dandy().args([
+            "--less-logs",
+            "validate",
+            "--check-alphabet",
+            "tests/validate/corpus.txt",
+            "tests/validate/good.dfa",
+            "tests/validate/wrong_alphabet.dfa",
+        ]),
+        1,
+    );
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "tests/validate/good.dfa: symbols of the corpus missing from the alphabet (\"d\", \"x\")",
+            "tests/validate/wrong_alphabet.dfa: symbols of the corpus missing from the alphabet (\"c\", \"d\", \"x\")",
+            "0/2 files valid",
+        ]
+    );
+}
+
+#[test]
+fn test_file_checks_alphabet_against_corpus() {
+    let output = stdout(
+        dandy().args([
+            "--less-logs",
+            "test-file",
+            "--check-alphabet",
+            "tests/validate/corpus.txt",
+            "tests/example.dfa",
+            "tests/dfa1_test.txt",
+        ]),
+        1,
+    );
+    assert!(
+        output.starts_with(
+            "Symbols in tests/validate/corpus.txt missing from the alphabet: \"d\", \"x\"\n"
+        ),
+        "{output}"
+    );
+
+    dandy()
+        .args([
+            "--less-logs",
+            "test-file",
+            "--summary-only",
+            "--check-alphabet",
+            "tests/dfa1_test.txt",
+            "tests/example.dfa",
+        ])
+        .assert()
+        .code(0);
+}
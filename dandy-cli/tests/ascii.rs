@@ -0,0 +1,64 @@
+mod common;
+
+use assert_cmd::Command;
+use common::stdout;
+
+fn dandy() -> Command {
+    let mut cmd = common::dandy();
+    cmd.env_remove("DANDY_ASCII");
+    cmd
+}
+
+#[test]
+fn ascii_flag_replaces_symbols_in_tables_and_words() {
+    let explain = [
+        "equivalence",
+        "--in-type",
+        "regex",
+        "--type",
+        "regex",
+        "--explain",
+        "tests/ascii/optional_a.regex",
+        "tests/ascii/optional_a.regex",
+    ];
+    let unicode = stdout(dandy().args(explain), 0);
+    assert!(unicode.contains('ε') && unicode.contains('→'), "{unicode}");
+
+    let ascii = stdout(dandy().arg("--ascii").args(explain), 0);
+    assert!(ascii.is_ascii(), "{ascii}");
+    assert!(ascii.contains("eps"), "{ascii}");
+    assert!(ascii.contains("on word eps: ref in state 0"), "{ascii}");
+    assert!(ascii.contains("no mismatch -> equivalent"), "{ascii}");
+}
+
+#[test]
+fn ascii_environment_variable_gives_ascii_tables() {
+    let convert = ["convert", "--to", "nfa", "tests/ascii/optional_a.regex"];
+    let ascii = stdout(dandy().env("DANDY_ASCII", "1").args(convert), 0);
+    assert!(ascii.is_ascii(), "{ascii}");
+    assert!(ascii.contains("->"), "{ascii}");
+
+    // The table is parsed again by the other subcommands
+    let table = ascii.lines().skip(1).collect::<Vec<_>>().join("\n");
+    let nfa: Result<dandy::nfa::Nfa, _> = dandy::parser::nfa(&table).unwrap().try_into();
+    assert!(nfa.is_ok());
+
+    let unicode = stdout(dandy().env("DANDY_ASCII", "0").args(convert), 0);
+    assert!(!unicode.is_ascii(), "{unicode}");
+}
+
+#[test]
+fn ascii_flag_keeps_names_in_tables() {
+    let convert = [
+        "--ascii",
+        "convert",
+        "--type",
+        "dfa",
+        "--to",
+        "nfa",
+        "tests/ascii/ellipsis_names.dfa",
+    ];
+    let ascii = stdout(dandy().args(convert), 0);
+    assert!(ascii.contains("eps"), "{ascii}");
+    assert!(ascii.contains("-> * s… {}  {t…} {s…}"), "{ascii}");
+}
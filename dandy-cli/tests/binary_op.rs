@@ -0,0 +1,149 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+fn run(args: &[&str]) {
+    dandy().args(args).assert().success();
+}
+
+#[test]
+fn union_enumerates_words_into_out_file() {
+    let out = temp_file("result.txt");
+    run(&[
+        "--out",
+        out.to_str().unwrap(),
+        "union",
+        "--enumerate",
+        "5",
+        "tests/init_b.dfa",
+        "tests/term_a.dfa",
+    ]);
+    let result = fs::read_to_string(&out).unwrap();
+    fs::remove_file(&out).unwrap();
+    // The output is written to the file as it is, without separating lines
+    assert!(result.ends_with("abaababb"), "{result}");
+}
+
+#[test]
+fn union_enumerates_words_into_separate_file() {
+    let out = temp_file("result2.txt");
+    let words = temp_file("words.txt");
+    run(&[
+        "--out",
+        out.to_str().unwrap(),
+        "union",
+        "--enumerate",
+        "5",
+        "--generate-out",
+        words.to_str().unwrap(),
+        "tests/init_b.dfa",
+        "tests/term_a.dfa",
+    ]);
+    let result = fs::read_to_string(&out).unwrap();
+    let words_content = fs::read_to_string(&words).unwrap();
+    fs::remove_file(&out).unwrap();
+    fs::remove_file(&words).unwrap();
+    assert_eq!(
+        words_content.lines().collect::<Vec<_>>(),
+        ["a", "b", "aa", "ba", "bb"]
+    );
+    assert!(!result.lines().any(|line| line == "aa"));
+}
+
+#[test]
+fn max_states_bounds_the_product() {
+    let output = dandy()
+        .args([
+            "union",
+            "--max-states",
+            "2",
+            "tests/init_b.dfa",
+            "tests/term_a.dfa",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("The union has more than 2 states"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("out of at most 6"), "{stderr}");
+
+    run(&[
+        "union",
+        "--max-states",
+        "6",
+        "tests/init_b.dfa",
+        "tests/term_a.dfa",
+    ]);
+}
+
+#[test]
+fn concatenate_and_star_give_minimized_dfas() {
+    let stdout = |args: &[&str]| {
+        let output = dandy().arg("--less-logs").args(args).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let result = stdout(&[
+        "concatenate",
+        "--enumerate",
+        "3",
+        "tests/init_b.dfa",
+        "tests/term_a.dfa",
+    ]);
+    // b(a|b|c)*a, which has an initial, a dead, and two more states
+    let lines = result.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 5 + 3, "{result}");
+    assert!(lines.ends_with(&["ba", "baa", "bba"]), "{result}");
+
+    let result = stdout(&["star", "--enumerate", "3", "tests/term_a.dfa"]);
+    let lines = result.lines().collect::<Vec<_>>();
+    assert!(lines.ends_with(&["(empty word)", "a", "aa"]), "{result}");
+}
+
+#[test]
+fn enumeration_reports_progress_and_stops_at_max_word_length() {
+    let enumerate = |args: &[&str]| {
+        let words = temp_file(&format!("progress-{}.txt", args.len()));
+        let output = dandy()
+            .args(args)
+            .args(["--generate-out", words.to_str().unwrap()])
+            .args(["tests/init_b.dfa", "tests/term_a.dfa"])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let words_content = fs::read_to_string(&words).unwrap();
+        fs::remove_file(&words).unwrap();
+        (
+            String::from_utf8(output.stdout).unwrap(),
+            String::from_utf8(output.stderr).unwrap(),
+            words_content,
+        )
+    };
+
+    let (_, stderr, words) = enumerate(&["union", "--enumerate", "2500"]);
+    assert_eq!(words.lines().count(), 2500);
+    assert!(
+        stderr.contains("(1000 of 2500 words enumerated)"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("(2000 of 2500 words enumerated)"),
+        "{stderr}"
+    );
+    let (_, stderr, _) = enumerate(&["--less-logs", "union", "--enumerate", "2500"]);
+    assert!(!stderr.contains("words enumerated"), "{stderr}");
+
+    let (stdout, _, words) = enumerate(&["union", "--enumerate", "100", "--max-word-length", "2"]);
+    assert_eq!(
+        words.lines().collect::<Vec<_>>(),
+        ["a", "b", "aa", "ba", "bb", "bc", "ca"]
+    );
+    assert!(
+        stdout.contains("(only 7 words of length at most 2 exists in the union)"),
+        "{stdout}"
+    );
+}
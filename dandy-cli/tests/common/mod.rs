@@ -0,0 +1,32 @@
+// Each test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+/// Creates a command running the CLI in the directory of the crate, so that the test files are found at `tests/...`
+pub fn dandy() -> Command {
+    let mut cmd = Command::cargo_bin("dandy-cli").unwrap();
+    cmd.current_dir(env!("CARGO_MANIFEST_DIR"));
+    cmd
+}
+
+/// Runs a command, asserting that it exits with the given code, and gives what it printed to stdout
+pub fn stdout(cmd: &mut Command, code: i32) -> String {
+    let output = cmd.assert().code(code).get_output().stdout.clone();
+    String::from_utf8(output).unwrap()
+}
+
+/// Splits the output of a command into its lines
+pub fn stdout_lines(output: Vec<u8>) -> Vec<String> {
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Gives a path in the temporary directory which is unique to this test process
+pub fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dandy-cli-{}-{name}", std::process::id()))
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+#[test]
+fn verbose_minimized_regex_conversion_prints_report() {
+    let regex = temp_file("regex.txt");
+    fs::write(&regex, "(a|b)*abb").unwrap();
+    let output = dandy()
+        .args(["convert", "--to", "dfa", "--minimize", "--verbose"])
+        .arg(&regex)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_file(&regex).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.starts_with("Compiled to 14 NFA states, 5 DFA states and 4 minimal DFA states\n"),
+        "{output}"
+    );
+}
+
+#[test]
+fn edge_list_format_prints_transitions() {
+    let output = dandy()
+        .args([
+            "convert", "--type", "nfa", "--to", "nfa", "--format", "edges",
+        ])
+        .arg("tests/nfa1.nfa")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let edges = output.lines().skip(1).collect::<Vec<_>>();
+    assert_eq!(
+        edges[..5],
+        [
+            "alphabet: a b",
+            "state ok initial accepting",
+            "state fail",
+            "state other",
+            "state inf"
+        ]
+    );
+    assert!(edges.contains(&"ok -ε-> other"), "{output}");
+    assert!(edges.contains(&"fail -a-> ok"), "{output}");
+    assert_eq!(edges.len(), 5 + 11);
+}
+
+#[test]
+fn conversion_to_grammar_prints_productions() {
+    let convert = |extra: &[&str]| {
+        let output = dandy()
+            .args(["--less-logs", "convert", "--type", "nfa", "--to", "grammar"])
+            .args(extra)
+            .arg("tests/nfa1.nfa")
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+    let grammar = convert(&[]);
+    assert_eq!(
+        grammar.lines().next(),
+        Some("ok -> other | a fail | a other | b ok | b | ε"),
+        "{grammar}"
+    );
+    assert_eq!(grammar.lines().count(), 4, "{grammar}");
+    let without_units = convert(&["--remove-epsilon-moves"]);
+    let alternatives = without_units
+        .lines()
+        .flat_map(|line| line.split_once(" -> ").unwrap().1.split(" | "))
+        .collect::<Vec<_>>();
+    assert!(
+        alternatives
+            .iter()
+            .all(|rhs| *rhs == "ε" || rhs.starts_with("a") || rhs.starts_with("b")),
+        "{without_units}"
+    );
+}
+
+#[test]
+fn flags_for_other_targets_are_rejected() {
+    let convert = |to: &str, extra: &str| {
+        dandy()
+            .args(["convert", "--type", "nfa", "--to", to, extra])
+            .arg("tests/nfa1.nfa")
+            .assert()
+    };
+    let stderr = |to: &str, extra: &str| {
+        let output = convert(to, extra).failure().get_output().stderr.clone();
+        String::from_utf8(output).unwrap()
+    };
+    let format = stderr("grammar", "--format=edges");
+    assert!(format.contains("--format can only be used"), "{format}");
+    let remove = stderr("dfa", "--remove-epsilon-moves");
+    assert!(
+        remove.contains("--remove-epsilon-moves can only be used"),
+        "{remove}"
+    );
+    convert("nfa", "--remove-epsilon-moves").failure();
+    convert("dfa", "--format=edges").success();
+}
+
+#[test]
+fn merged_names_tell_which_states_were_merged() {
+    let dfa = temp_file("odd_as.dfa");
+    fs::write(
+        &dfa,
+        "      a  b\n-> s1 s2 s1\n * s2 s3 s2\n   s3 s4 s3\n * s4 s1 s4\n",
+    )
+    .unwrap();
+    let convert = |extra: &[&str]| {
+        dandy()
+            .args(["convert", "--type", "dfa", "--to", "dfa"])
+            .args(extra)
+            .arg(&dfa)
+            .assert()
+    };
+    let output = convert(&["--minimize", "--merged-names"])
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.starts_with("s3 was merged into s1\ns4 was merged into s2\n"),
+        "{output}"
+    );
+    assert!(
+        output.contains("s1=s3 s2=s4 s1=s3 \n  * s2=s4 s1=s3 s2=s4"),
+        "{output}"
+    );
+    convert(&["--merged-names"]).failure();
+    fs::remove_file(&dfa).unwrap();
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+#[test]
+fn auto_type_detects_bundled_examples() {
+    let files = [
+        ("tests/eq_example2_nfa.dfa", "DFA"),
+        ("tests/example.dfa", "DFA"),
+        ("tests/example2.dfa", "DFA"),
+        ("tests/init_b.dfa", "DFA"),
+        ("tests/term_a.dfa", "DFA"),
+        ("tests/explain/has_b.dfa", "DFA"),
+        ("tests/validate/good.dfa", "DFA"),
+        ("tests/example.nfa", "NFA"),
+        ("tests/example2.nfa", "NFA"),
+        ("tests/example3.nfa", "NFA"),
+        ("tests/nfa1.nfa", "NFA"),
+        ("tests/validate/pragma.regex", "Regex"),
+        ("tests/example_tree/user1/same.regex", "Regex"),
+    ];
+    for (file, r#type) in files {
+        // Converting to a NFA logs the type it was converted from
+        let output = dandy()
+            .args(["convert", "--type", "auto", "--to", "nfa", file])
+            .assert()
+            .code(0)
+            .get_output()
+            .stdout
+            .clone();
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.starts_with(&format!("{type} converted to NFA:")),
+            "{file}: {output}"
+        );
+    }
+}
+
+#[test]
+fn mismatched_type_gives_hint() {
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "validate",
+            "--type",
+            "dfa",
+            "tests/example.nfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("hint: this looks like an NFA table — did you mean --type nfa?"));
+
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "validate",
+            "--type",
+            "nfa",
+            "tests/example.dfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("hint: this looks like a DFA table — did you mean --type dfa?"));
+}
+
+#[test]
+fn auto_type_fails_on_undetectable_files() {
+    let file = temp_file("detect.txt");
+    fs::write(&file, "# nothing here\n").unwrap();
+    let output = dandy()
+        .args(["--less-logs", "validate", "--type", "auto"])
+        .arg(&file)
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_file(&file).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("Could not detect"), "{output}");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn equivalence_accepts_utf16_files() {
+    let source =
+        fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/init_b.dfa"))
+            .unwrap();
+    let utf16 = temp_file("init_b_utf16.dfa");
+    let bytes = [0xFF, 0xFE]
+        .into_iter()
+        .chain(source.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect::<Vec<u8>>();
+    fs::write(&utf16, bytes).unwrap();
+
+    let output = dandy()
+        .args(["--less-logs", "equivalence", "-p", "0", "tests/init_b.dfa"])
+        .arg(&utf16)
+        .output()
+        .unwrap();
+    fs::remove_file(&utf16).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "Equivalent");
+}
+
+#[test]
+fn truncated_files_give_the_path_and_line() {
+    let truncated = temp_file("truncated.dfa");
+    fs::write(
+        &truncated,
+        "     a b c\r\n-> i n y n\r\n   n n n n\r\n * y y",
+    )
+    .unwrap();
+    let output = dandy()
+        .args(["--less-logs", "validate"])
+        .arg(&truncated)
+        .output()
+        .unwrap();
+    fs::remove_file(&truncated).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(&format!("on line 4 of {}", truncated.display())),
+        "{stdout}"
+    );
+}
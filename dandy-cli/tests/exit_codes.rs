@@ -0,0 +1,135 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+#[test]
+fn equivalent_files_exit_with_zero() {
+    dandy()
+        .args(["equivalence", "tests/init_b.dfa", "tests/init_b.dfa"])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn non_equivalent_files_exit_with_one() {
+    dandy()
+        .args(["equivalence", "tests/init_b.dfa", "tests/term_a.dfa"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn minimized_nfas_are_compared_to_nfa_minimized_via_dfa() {
+    // nfa1.nfa has four states, but its minimized DFA has two
+    let output = dandy()
+        .args([
+            "equivalence",
+            "--type",
+            "nfa",
+            "--minimized",
+            "tests/nfa1.nfa",
+            "tests/nfa1.nfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains("Equivalent but not minimized"));
+    // example.nfa has five states, but its minimized DFA has ten
+    dandy()
+        .args([
+            "equivalence",
+            "--type",
+            "nfa",
+            "--minimized",
+            "tests/example.nfa",
+            "tests/example.nfa",
+        ])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn missing_file_exits_with_two() {
+    dandy()
+        .args([
+            "equivalence",
+            "tests/does_not_exist.dfa",
+            "tests/init_b.dfa",
+        ])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn summary_only_prints_only_counts() {
+    let output = dandy()
+        .args([
+            "equivalence",
+            "--summary-only",
+            "tests/init_b.dfa",
+            "tests/init_b.dfa",
+            "tests/term_a.dfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(String::from_utf8(output).unwrap(), "1/2 files passed\n");
+}
+
+#[test]
+fn test_file_exit_code_follows_rejected_lines() {
+    let lines = temp_file("lines.txt");
+    fs::write(&lines, "b\nba\nbab\n").unwrap();
+    dandy()
+        .args(["test-file", "tests/init_b.dfa"])
+        .arg(&lines)
+        .assert()
+        .code(0);
+    dandy()
+        .args(["test-file", "tests/term_a.dfa"])
+        .arg(&lines)
+        .assert()
+        .code(1);
+    fs::remove_file(&lines).unwrap();
+}
+
+#[test]
+fn parallel_equivalence_keeps_file_order() {
+    let files = [
+        "tests/example.dfa",
+        "tests/example2.dfa",
+        "tests/init_b.dfa",
+        "tests/term_a.dfa",
+        "tests/eq_example2_nfa.dfa",
+    ]
+    .repeat(5);
+    let run = |jobs: &str| {
+        let output = dandy()
+            .args([
+                "--less-logs",
+                "equivalence",
+                "--jobs",
+                jobs,
+                "tests/example2.dfa",
+            ])
+            .args(&files)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let sequential = run("1");
+    assert_eq!(sequential.lines().count(), files.len());
+    assert!(sequential.contains("Not Equivalent"));
+    assert_eq!(
+        sequential.lines().nth(1),
+        Some("tests/example2.dfa: Equivalent")
+    );
+    assert_eq!(run("4"), sequential);
+}
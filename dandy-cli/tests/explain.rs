@@ -0,0 +1,67 @@
+mod common;
+
+use common::dandy;
+
+fn explain(reference: &str, candidate: &str, code: i32) -> String {
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "equivalence",
+            "--explain",
+            reference,
+            candidate,
+        ])
+        .assert()
+        .code(code)
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn explain_equivalent() {
+    assert_eq!(
+        explain(
+            "tests/explain/has_b.dfa",
+            "tests/explain/has_b_large.dfa",
+            0
+        ),
+        "\
+on word ε: ref in state x (rejecting), student in state p (rejecting)
+on word b: ref in state y (accepting), student in state q (accepting)
+on word ba: ref in state y (accepting), student in state r (accepting)
+explored 3 pairs, no mismatch → equivalent
+"
+    );
+}
+
+#[test]
+fn explain_not_equivalent() {
+    assert_eq!(
+        explain(
+            "tests/explain/ends_with_b.dfa",
+            "tests/explain/has_b.dfa",
+            1
+        ),
+        "\
+on word ε: ref in state s (rejecting), student in state x (rejecting)
+on word b: ref in state t (accepting), student in state y (accepting)
+on word ba: ref in state s (rejecting), student in state y (accepting) ← MISMATCH
+"
+    );
+}
+
+#[test]
+fn explain_requires_one_file() {
+    dandy()
+        .args([
+            "equivalence",
+            "--explain",
+            "tests/explain/has_b.dfa",
+            "tests/explain/has_b.dfa",
+            "tests/explain/has_b_large.dfa",
+        ])
+        .assert()
+        .code(2);
+}
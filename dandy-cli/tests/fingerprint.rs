@@ -0,0 +1,76 @@
+mod common;
+
+use common::dandy;
+use std::collections::HashMap;
+
+/// Runs the fingerprint command on the files and maps each file to its fingerprint
+fn fingerprints(files: &[&str]) -> HashMap<String, String> {
+    let output = dandy()
+        .args(["--less-logs", "fingerprint"])
+        .args(files)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    output
+        .lines()
+        .map(|line| {
+            let (fingerprint, path) = line.split_once("  ").unwrap();
+            assert_eq!(fingerprint.len(), 16, "{line}");
+            (path.to_string(), fingerprint.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn equivalent_files_have_equal_fingerprints() {
+    let fingerprints = fingerprints(&[
+        "tests/fingerprint/ab_star_a.regex",
+        "tests/fingerprint/a_ba_star.regex",
+        "tests/fingerprint/ab_star.regex",
+        "tests/example.dfa",
+        "tests/example2.dfa",
+        "tests/init_b.dfa",
+    ]);
+    assert_eq!(fingerprints.len(), 6);
+    assert_eq!(
+        fingerprints["tests/fingerprint/ab_star_a.regex"],
+        fingerprints["tests/fingerprint/a_ba_star.regex"]
+    );
+    assert_ne!(
+        fingerprints["tests/fingerprint/ab_star_a.regex"],
+        fingerprints["tests/fingerprint/ab_star.regex"]
+    );
+    assert_eq!(
+        fingerprints["tests/example.dfa"],
+        fingerprints["tests/example2.dfa"]
+    );
+    assert_ne!(
+        fingerprints["tests/example.dfa"],
+        fingerprints["tests/init_b.dfa"]
+    );
+}
+
+#[test]
+fn unloadable_files_are_errors() {
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "fingerprint",
+            "tests/init_b.dfa",
+            "tests/validate/syntax_error.dfa",
+        ])
+        .assert()
+        .code(2)
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.ends_with("  tests/init_b.dfa\n"), "{stdout}");
+    assert!(
+        stderr.contains("tests/validate/syntax_error.dfa:"),
+        "{stderr}"
+    );
+}
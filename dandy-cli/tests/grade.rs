@@ -0,0 +1,74 @@
+mod common;
+
+use common::{dandy, stdout_lines};
+
+#[test]
+fn grade_reports_relation_and_witnesses() {
+    let output = dandy()
+        .args(["--less-logs", "grade", "--spec", "tests/grade/spec.regex"])
+        .args([
+            "tests/grade/equal.dfa",
+            "tests/grade/subset.dfa",
+            "tests/grade/superset.dfa",
+            "tests/grade/incomparable.dfa",
+            "tests/validate/syntax_error.dfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let lines = stdout_lines(output);
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], "tests/grade/equal.dfa: equal");
+    assert_eq!(
+        lines[1],
+        "tests/grade/subset.dfa: subset (too restrictive), rejects \"a\""
+    );
+    assert_eq!(
+        lines[2],
+        "tests/grade/superset.dfa: superset (too permissive), accepts \"ba\""
+    );
+    assert_eq!(
+        lines[3],
+        "tests/grade/incomparable.dfa: incomparable, rejects \"ab\", accepts \"ba\""
+    );
+    assert!(lines[4].starts_with("tests/validate/syntax_error.dfa: Error parsing DFA"));
+    assert_eq!(
+        lines[5],
+        "1 equal, 1 subset, 1 superset, 1 incomparable, 1 failed to load"
+    );
+}
+
+#[test]
+fn grade_passes_if_all_equal() {
+    let output = dandy()
+        .args(["--less-logs", "grade", "--spec", "tests/grade/spec.regex"])
+        .args(["tests/grade/equal.dfa"])
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        stdout_lines(output).last().unwrap(),
+        "1 equal, 0 subset, 0 superset, 0 incomparable, 0 failed to load"
+    );
+}
+
+#[test]
+fn unreadable_spec_is_an_error() {
+    let output = dandy()
+        .args(["grade", "--spec", "tests/grade/missing.regex"])
+        .args(["tests/grade/equal.dfa"])
+        .assert()
+        .code(2)
+        .get_output()
+        .stderr
+        .clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert!(
+        stderr.contains("Error in grading: Error reading the specification"),
+        "{stderr}"
+    );
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+#[test]
+fn infer_type_loads_each_file_by_extension() {
+    let dir = temp_file("infer-type");
+    fs::create_dir_all(&dir).unwrap();
+    // All of these accept the words starting with b, just like tests/init_b.dfa
+    fs::write(
+        dir.join("candidate.dfa"),
+        "    a b c\n-> s t u t\n   t t t t\n * u u u u\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("candidate.nfa"),
+        "    a   b   c\n-> s {}  {t} {}\n * t {t} {t} {t}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("candidate.rx"), "b(a|b|c)*").unwrap();
+
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "equivalence",
+            "--infer-type",
+            "--ext-map",
+            "rx=regex",
+            "tests/init_b.dfa",
+        ])
+        .args(["candidate.dfa", "candidate.nfa", "candidate.rx"].map(|file| dir.join(file)))
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_dir_all(&dir).unwrap();
+    let lines = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.rsplit(": ").next().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "Equivalent (as DFA)",
+            "Equivalent (as NFA)",
+            "Equivalent (as Regex)"
+        ]
+    );
+}
+
+#[test]
+fn ext_map_requires_infer_type() {
+    dandy()
+        .args([
+            "equivalence",
+            "--ext-map",
+            "rx=regex",
+            "tests/init_b.dfa",
+            "tests/init_b.dfa",
+        ])
+        .assert()
+        .code(2);
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::dandy;
+
+/// Runs the equivalence command with the mapping and the given arguments, expecting the exit code, and gives the
+/// lines of the output
+fn equivalence_under(map: &str, args: &[&str], code: i32) -> Vec<String> {
+    let output = dandy()
+        .args(["--less-logs", "equivalence", "--map", map])
+        .args(args)
+        .args(["tests/mapping/odd_binary.dfa"])
+        .args([
+            "tests/mapping/ends_with_b.dfa",
+            "tests/mapping/ends_with_a.dfa",
+        ])
+        .assert()
+        .code(code)
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn renamed_symbols_are_compared() {
+    // Automatically detected types are compared as NFAs
+    for args in [&[][..], &["--type", "auto"]] {
+        let lines = equivalence_under("a=0, b=1", args, 1);
+        assert_eq!(
+            lines,
+            [
+                "tests/mapping/ends_with_b.dfa: Equivalent",
+                "tests/mapping/ends_with_a.dfa: Not Equivalent"
+            ]
+        );
+    }
+    let lines = equivalence_under("a=1,b=0", &[], 1);
+    assert_eq!(lines[0], "tests/mapping/ends_with_b.dfa: Not Equivalent");
+    assert_eq!(lines[1], "tests/mapping/ends_with_a.dfa: Equivalent");
+}
+
+#[test]
+fn invalid_mapping_lists_unmapped_symbols() {
+    let lines = equivalence_under("a=0", &[], 1);
+    assert_eq!(
+        lines[0],
+        "tests/mapping/ends_with_b.dfa: Invalid symbol mapping (the symbols [\"b\"] are unmapped)"
+    );
+}
+
+#[test]
+fn malformed_mapping_is_a_usage_error() {
+    dandy()
+        .args(["equivalence", "--map", "a0"])
+        .args([
+            "tests/mapping/odd_binary.dfa",
+            "tests/mapping/ends_with_b.dfa",
+        ])
+        .assert()
+        .code(2);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::dandy;
+
+fn sample(args: &[&str]) -> String {
+    let output = dandy()
+        .args(["--less-logs", "sample"])
+        .args(args)
+        .assert()
+        .code(0)
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn sample_is_deterministic_with_seed() {
+    let args = [
+        "--type",
+        "regex",
+        "--accept",
+        "2",
+        "--reject",
+        "3",
+        "--max-len",
+        "5",
+        "--seed",
+        "7",
+        "--",
+        "(ab)+c",
+    ];
+    let output = sample(&args);
+    assert_eq!(
+        output,
+        "accepted: abc\n\
+         accepted: ababc\n\
+         rejected: abba\n\
+         rejected: accbc\n\
+         rejected: ccccb\n"
+    );
+    assert_eq!(output, sample(&args));
+}
+
+#[test]
+fn sample_degrades_gracefully_without_words() {
+    let output = sample(&["--type", "regex", "--seed", "1", "--max-len", "3", "(a|b)*"]);
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[5], "no rejected words exist up to length 3");
+
+    // Accepts the words starting with b, so the only rejected words of length 1 are a and c
+    let output = sample(&["--seed", "1", "--max-len", "1", "tests/init_b.dfa"]);
+    assert_eq!(
+        output,
+        "accepted: b\nrejected: (empty word)\nrejected: a\nrejected: c\n"
+    );
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::{dandy, temp_file};
+use serde_json::Value;
+use std::fs;
+
+fn duration(record: &Value, phase: &str) -> f64 {
+    record["durations_ms"][phase]
+        .as_f64()
+        .unwrap_or_else(|| panic!("no duration of {phase} in {record}"))
+}
+
+#[test]
+fn equivalence_stats_are_written_to_file() {
+    let path = temp_file("stats.json");
+    dandy()
+        .args(["--less-logs", "--stats-out"])
+        .arg(&path)
+        .args([
+            "equivalence",
+            "--in-type",
+            "regex",
+            "tests/grade/spec.regex",
+        ])
+        .args(["tests/grade/equal.dfa", "tests/grade/subset.dfa"])
+        .assert()
+        .code(1)
+        .stderr("");
+    let record: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(record["version"], 1);
+    assert_eq!(record["operation"], "equivalence");
+    let inputs = record["inputs"].as_array().unwrap();
+    assert_eq!(inputs.len(), 1);
+    assert_eq!(inputs[0]["path"], "tests/grade/spec.regex");
+    assert_eq!(inputs[0]["type"], "regex");
+    assert!(inputs[0]["states"].is_null());
+    assert!(inputs[0]["epsilon_cycles"].is_null());
+    assert!(record["output"].is_null());
+    assert!(record["peak_states"].as_u64().unwrap() >= 1);
+
+    let total = duration(&record, "total");
+    for phase in ["parse", "convert", "construct", "minimize", "compare"] {
+        let phase = duration(&record, phase);
+        assert!((0.0..=total).contains(&phase), "{record}");
+    }
+    assert_eq!(duration(&record, "construct"), 0.0);
+    assert!(duration(&record, "compare") > 0.0);
+}
+
+#[test]
+fn binary_operation_stats_are_printed_to_stderr() {
+    let output = dandy()
+        .args(["--less-logs", "--stats", "union"])
+        .args(["tests/example.dfa", "tests/example.dfa"])
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+    let record: Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(record["operation"], "union");
+    let inputs = record["inputs"].as_array().unwrap();
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[0]["type"], "dfa");
+    let states = inputs[0]["states"].as_u64().unwrap();
+    let alphabet = inputs[0]["alphabet"].as_u64().unwrap();
+    assert_eq!(inputs[0]["epsilon_cycles"], false);
+    assert_eq!(inputs[0]["ambiguous"], false);
+    assert_eq!(record["output"]["nondeterministic_states"], 0);
+    assert_eq!(
+        inputs[0]["minimal_dfa_states"],
+        record["output"]["minimal_dfa_states"]
+    );
+    // The product of a DFA with itself only reaches the pairs of equal states
+    assert_eq!(record["peak_states"].as_u64(), Some(states));
+    assert_eq!(record["output"]["states"].as_u64(), Some(states));
+    assert_eq!(record["output"]["alphabet"].as_u64(), Some(alphabet));
+    assert!(duration(&record, "construct") > 0.0);
+}
+
+#[test]
+fn nfa_input_stats_describe_nondeterminism() {
+    let output = dandy()
+        .args(["--less-logs", "--stats", "equivalence"])
+        .args(["--in-type", "nfa", "--type", "nfa"])
+        .args(["tests/example.nfa", "tests/example.nfa"])
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+    let record: Value = serde_json::from_slice(&output).unwrap();
+    let input = &record["inputs"][0];
+    assert_eq!(input["type"], "nfa");
+    assert_eq!(input["epsilon_cycles"], false);
+    assert_eq!(input["max_branching"], 2);
+    // Only s₀ has two transitions upon the same element
+    assert_eq!(input["nondeterministic_states"], 1);
+    // "cbba" has one accepting run staying in s₀ upon the c, and one going to s₂
+    assert_eq!(input["ambiguous"], true);
+    assert!(input["minimal_dfa_states"].as_u64().unwrap() >= 1);
+}
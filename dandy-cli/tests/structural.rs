@@ -0,0 +1,74 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+#[test]
+fn structural_equivalence_distinguishes_renamed_and_unminimized_dfas() {
+    let renamed = temp_file("renamed.dfa");
+    let unminimized = temp_file("unminimized.dfa");
+    fs::write(
+        &renamed,
+        "    c  b  a\n * q0 q0 q0 q0\n   q1 q1 q1 q1\n-> q2 q1 q0 q1\n",
+    )
+    .unwrap();
+    fs::write(
+        &unminimized,
+        "    a  b  c\n-> i  n  y  m\n   n  n  n  m\n   m  m  m  n\n * y  y  y  y\n",
+    )
+    .unwrap();
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "equivalence",
+            "--structural",
+            "tests/init_b.dfa",
+        ])
+        .args([&renamed, &unminimized])
+        .arg("tests/term_a.dfa")
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_file(&renamed).unwrap();
+    fs::remove_file(&unminimized).unwrap();
+    let lines = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.rsplit(": ").next().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "Isomorphic",
+            "Equivalent but different structure",
+            "Not Equivalent"
+        ]
+    );
+}
+
+#[test]
+fn structural_equivalence_rejects_minimized() {
+    dandy()
+        .args([
+            "equivalence",
+            "--structural",
+            "--minimized",
+            "tests/init_b.dfa",
+            "tests/init_b.dfa",
+        ])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn structural_equivalence_of_detected_types() {
+    dandy()
+        .args(["--less-logs", "equivalence", "--structural"])
+        .args(["--in-type", "auto", "--type", "auto"])
+        .args(["tests/example.dfa", "tests/example.dfa"])
+        .assert()
+        .code(0)
+        .stdout("tests/example.dfa: Isomorphic\n");
+}
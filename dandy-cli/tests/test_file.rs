@@ -0,0 +1,111 @@
+mod common;
+
+use common::{dandy, temp_file};
+use std::fs;
+
+/// Runs test-file with the given number of jobs on the lines file, giving the output
+fn test_lines(jobs: &str, extra: &[&str], lines: &std::path::Path) -> String {
+    let output = dandy()
+        .args(["--less-logs", "test-file", "--jobs", jobs])
+        .args(extra)
+        .arg("tests/init_b.dfa")
+        .arg(lines)
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn parallel_output_matches_sequential_output() {
+    let lines = temp_file("corpus.txt");
+    // A deterministic corpus of 10k words over {a, b, c}, about a third of which start with b
+    let mut state = 12345u32;
+    let corpus = (0..10_000)
+        .map(|_| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            let len = 1 + (state >> 16) % 7;
+            (0..len)
+                .map(|i| ["a", "b", "c"][((state >> (i * 2)) % 3) as usize])
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(&lines, corpus).unwrap();
+
+    let sequential = test_lines("1", &[], &lines);
+    assert_eq!(sequential.lines().count(), 10_002);
+    assert!(sequential.contains("[ OK ] b"));
+    assert!(sequential.contains("[FAIL] a"));
+    assert_eq!(test_lines("4", &[], &lines), sequential);
+
+    let sequential = test_lines("1", &["--suggest"], &lines);
+    assert_eq!(test_lines("4", &["--suggest"], &lines), sequential);
+
+    // The statistics are collected in the same pass as the lines are tested, on every thread
+    let statistics = ["--report", "--profile", "--coverage"];
+    let sequential = test_lines("1", &statistics, &lines);
+    assert!(sequential.contains("total  "), "{sequential}");
+    assert!(sequential.contains("over 10000 input(s)"), "{sequential}");
+    assert_eq!(test_lines("4", &statistics, &lines), sequential);
+
+    let sequential = test_lines("1", &["--test-type", "files"], &lines);
+    assert_eq!(
+        test_lines("4", &["--test-type", "files"], &lines),
+        sequential
+    );
+    fs::remove_file(&lines).unwrap();
+}
+
+#[test]
+fn report_is_printed_after_the_lines() {
+    let lines = temp_file("report.txt");
+    fs::write(&lines, "b\nab\nba\n\nbx\nbcx").unwrap();
+    let output = test_lines("1", &["--report"], &lines);
+    fs::remove_file(&lines).unwrap();
+    let report = "length accepted rejected\n\
+                  0      0        1\n\
+                  1      1        0\n\
+                  2      1        2\n\
+                  3      0        1\n\
+                  total  2        4\n\
+                  empty lines: 1\n\
+                  unknown symbols: 2 occurrence(s) in 2 line(s), most common: \"x\" (2)\n";
+    assert!(output.starts_with("Testing file"));
+    assert!(output.contains("[FAIL] bcx\n2/6 lines passed"));
+    assert!(output.ends_with(report), "{output}");
+}
+
+#[test]
+fn profile_is_printed_after_the_lines() {
+    let lines = temp_file("profile.txt");
+    fs::write(&lines, "b\nab\nx").unwrap();
+    let output = test_lines("1", &["--profile"], &lines);
+    fs::remove_file(&lines).unwrap();
+    // A DFA always has one active state, until an element outside the alphabet kills the line
+    let profile = "step    inputs min max mean\n\
+                   0       3      1   1   1.00\n\
+                   1       3      0   1   0.67\n\
+                   2       1      1   1   1.00\n\
+                   overall 7      0   1   0.86\n\
+                   died: 1 of 3 input(s), after 1.00 step(s) on average\n";
+    assert!(output.ends_with(profile), "{output}");
+}
+
+#[test]
+fn coverage_is_printed_after_the_lines() {
+    let lines = temp_file("coverage.txt");
+    fs::write(&lines, "b\nab\nx").unwrap();
+    let output = test_lines("1", &["--coverage"], &lines);
+    // "b" visits i and y, "ab" visits i and n twice, and "x" only visits i before stopping
+    let coverage = "visited 3 of 3 state(s) and took 3 of 9 transition(s) over 3 input(s)\n\
+                    untaken transitions from visited states: i -c->, n -a->, n -c->, y -a->, y -b->, y -c->\n";
+    assert!(output.ends_with(coverage), "{output}");
+
+    let output = test_lines("1", &["--coverage", "--annotate-coverage"], &lines);
+    fs::remove_file(&lines).unwrap();
+    let table = "      a b c\n\
+                 →   i n y n # 3 visit(s), untaken: c\n    \
+                     n n n n # 2 visit(s), untaken: a c\n  \
+                   * y y y y # 1 visit(s), untaken: a b c\n";
+    assert!(output.ends_with(&format!("{coverage}{table}")), "{output}");
+}
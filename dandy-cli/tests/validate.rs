@@ -0,0 +1,139 @@
+mod common;
+
+use common::{dandy, stdout_lines, temp_file};
+use std::fs;
+
+#[test]
+fn validate_reports_each_file() {
+    let output = dandy()
+        .args(["--less-logs", "validate", "--alphabet", "c b a"])
+        .args([
+            "tests/validate/good.dfa",
+            "tests/validate/syntax_error.dfa",
+            "tests/validate/wrong_alphabet.dfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let lines = stdout_lines(output);
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "tests/validate/good.dfa: OK");
+    assert!(lines[1].starts_with("tests/validate/syntax_error.dfa: Error parsing DFA"));
+    assert_eq!(
+        lines[2],
+        "tests/validate/wrong_alphabet.dfa: wrong alphabet (missing \"c\")"
+    );
+    assert_eq!(lines[3], "1/3 files valid");
+}
+
+#[test]
+fn validate_passes_valid_files() {
+    dandy()
+        .args([
+            "validate",
+            "tests/validate/good.dfa",
+            "tests/validate/wrong_alphabet.dfa",
+        ])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn validate_lints_and_checks_minimality() {
+    let file = temp_file("lint.dfa");
+    fs::write(
+        &file,
+        "     a b c\n-> i i y n\n   n n n n\n   m n n n\n * y y y n\n",
+    )
+    .unwrap();
+    let output = dandy()
+        .args(["--less-logs", "validate", "--lint", "--minimal"])
+        .arg(&file)
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    fs::remove_file(&file).unwrap();
+    let name = file.display();
+    assert_eq!(
+        stdout_lines(output),
+        [
+            format!("{name}: warning: unreachable states: m"),
+            format!("{name}: warning: symbols not in any accepted word: c"),
+            format!("{name}: not minimal"),
+            "0/1 files valid".to_string()
+        ]
+    );
+}
+
+#[test]
+fn validate_rejects_minimal_for_nfas() {
+    dandy()
+        .args([
+            "validate",
+            "--type",
+            "nfa",
+            "--minimal",
+            "tests/example.nfa",
+        ])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn validate_honors_alphabet_pragmas() {
+    // The pragma adds c to the alphabet, even though it isn't in the regex
+    dandy()
+        .args([
+            "--less-logs",
+            "validate",
+            "--type",
+            "regex",
+            "--alphabet",
+            "a b c",
+        ])
+        .arg("tests/validate/pragma.regex")
+        .assert()
+        .code(0);
+
+    let output = dandy()
+        .args([
+            "--less-logs",
+            "validate",
+            "tests/validate/pragma_mismatch.dfa",
+        ])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let lines = stdout_lines(output);
+    assert_eq!(
+        lines[0],
+        "tests/validate/pragma_mismatch.dfa: Error compiling DFA: The alphabet pragma declares the alphabet \
+         [\"a\", \"b\", \"c\"], but the table has the alphabet [\"a\", \"b\"]"
+    );
+}
+
+#[test]
+fn validate_checks_minimality_of_detected_dfas() {
+    let output = dandy()
+        .args(["--less-logs", "validate", "--type", "auto", "--minimal"])
+        .args(["tests/validate/good.dfa", "tests/example.nfa"])
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        stdout_lines(output),
+        [
+            "tests/validate/good.dfa: OK",
+            "tests/example.nfa: can only check minimality of DFAs, but this is a NFA",
+            "1/2 files valid"
+        ]
+    );
+}
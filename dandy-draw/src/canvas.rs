@@ -3,46 +3,206 @@ use crate::Drawer;
 use std::f64::consts::PI;
 use web_sys::{wasm_bindgen::JsCast, CanvasRenderingContext2d, HtmlCanvasElement};
 
+/// A drawer drawing on a HTML canvas. Every drawing starts by clearing the canvas and sizing its backing store to
+/// its CSS size times the device pixel ratio, so that drawings are sharp on HiDPI screens, while coordinates are
+/// still given in CSS pixels.
 pub struct CanvasDrawer {
-    context: CanvasRenderingContext2d,
+    context: Box<dyn Context2d>,
+    pixel_ratio: f64,
 }
 
 impl CanvasDrawer {
     pub fn new(context: CanvasRenderingContext2d) -> Self {
-        context.set_text_align("center");
-        context.set_text_baseline("middle");
-        Self { context }
+        let pixel_ratio = web_sys::window().map_or(1.0, |window| window.device_pixel_ratio());
+        Self::with_context(Box::new(context), pixel_ratio)
     }
 
     pub fn from_element(canvas: HtmlCanvasElement) -> Option<Self> {
         let context = canvas.get_context("2d").ok()??.dyn_into().ok()?;
-        Some(Self { context })
+        Some(Self::new(context))
+    }
+
+    /// Uses the given device pixel ratio instead of the one of the window
+    pub fn with_pixel_ratio(mut self, pixel_ratio: f64) -> Self {
+        self.pixel_ratio = pixel_ratio;
+        self
+    }
+
+    fn with_context(context: Box<dyn Context2d>, pixel_ratio: f64) -> Self {
+        context.set_text_align("center");
+        context.set_text_baseline("middle");
+        Self {
+            context,
+            pixel_ratio,
+        }
+    }
+}
+
+/// The methods of [CanvasRenderingContext2d] used by [CanvasDrawer], so that what it draws can be tested without a
+/// browser
+trait Context2d {
+    fn canvas(&self) -> Option<HtmlCanvasElement>;
+    fn set_text_align(&self, value: &str);
+    fn set_text_baseline(&self, value: &str);
+    fn save(&self);
+    fn restore(&self);
+    fn reset_transform(&self);
+    fn clear_rect(&self, x: f64, y: f64, w: f64, h: f64);
+    fn scale(&self, x: f64, y: f64);
+    fn begin_path(&self);
+    fn close_path(&self);
+    fn stroke(&self);
+    fn set_line_width(&self, value: f64);
+    fn set_color(&self, color: &str);
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64);
+    fn move_to(&self, x: f64, y: f64);
+    fn line_to(&self, x: f64, y: f64);
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64);
+    fn fill_text(&self, text: &str, x: f64, y: f64);
+    fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64);
+}
+
+impl Context2d for CanvasRenderingContext2d {
+    fn canvas(&self) -> Option<HtmlCanvasElement> {
+        CanvasRenderingContext2d::canvas(self)
+    }
+
+    fn set_text_align(&self, value: &str) {
+        CanvasRenderingContext2d::set_text_align(self, value)
+    }
+
+    fn set_text_baseline(&self, value: &str) {
+        CanvasRenderingContext2d::set_text_baseline(self, value)
+    }
+
+    fn save(&self) {
+        CanvasRenderingContext2d::save(self)
+    }
+
+    fn restore(&self) {
+        CanvasRenderingContext2d::restore(self)
+    }
+
+    fn reset_transform(&self) {
+        let _ = self.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+    }
+
+    fn clear_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+        CanvasRenderingContext2d::clear_rect(self, x, y, w, h)
+    }
+
+    fn scale(&self, x: f64, y: f64) {
+        let _ = CanvasRenderingContext2d::scale(self, x, y);
+    }
+
+    fn begin_path(&self) {
+        CanvasRenderingContext2d::begin_path(self)
+    }
+
+    fn close_path(&self) {
+        CanvasRenderingContext2d::close_path(self)
+    }
+
+    fn stroke(&self) {
+        CanvasRenderingContext2d::stroke(self)
+    }
+
+    fn set_line_width(&self, value: f64) {
+        CanvasRenderingContext2d::set_line_width(self, value)
+    }
+
+    fn set_color(&self, color: &str) {
+        self.set_stroke_style(&color.into());
+        self.set_fill_style(&color.into())
+    }
+
+    fn arc(&self, x: f64, y: f64, radius: f64, start_angle: f64, end_angle: f64) {
+        CanvasRenderingContext2d::arc(self, x, y, radius, start_angle, end_angle).unwrap();
+    }
+
+    fn move_to(&self, x: f64, y: f64) {
+        CanvasRenderingContext2d::move_to(self, x, y)
+    }
+
+    fn line_to(&self, x: f64, y: f64) {
+        CanvasRenderingContext2d::line_to(self, x, y)
+    }
+
+    fn bezier_curve_to(&self, cp1x: f64, cp1y: f64, cp2x: f64, cp2y: f64, x: f64, y: f64) {
+        CanvasRenderingContext2d::bezier_curve_to(self, cp1x, cp1y, cp2x, cp2y, x, y)
+    }
+
+    fn fill_text(&self, text: &str, x: f64, y: f64) {
+        CanvasRenderingContext2d::fill_text(self, text, x, y).unwrap();
+    }
+
+    fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+        CanvasRenderingContext2d::fill_rect(self, x, y, w, h)
     }
 }
 
+/// The size of the canvas in CSS pixels. This is the size set by a previous drawing if there is one, and otherwise
+/// the size of the canvas on the page, or the size of its backing store if it isn't laid out.
+fn css_size(canvas: &HtmlCanvasElement) -> (f64, f64) {
+    let style = canvas.style();
+    let inline = |property| {
+        let value = style.get_property_value(property).ok()?;
+        value.strip_suffix("px")?.parse::<f64>().ok()
+    };
+    let laid_out = |client: i32, backing: u32| {
+        if client > 0 {
+            client as f64
+        } else {
+            backing as f64
+        }
+    };
+    let width = inline("width").unwrap_or_else(|| laid_out(canvas.client_width(), canvas.width()));
+    let height =
+        inline("height").unwrap_or_else(|| laid_out(canvas.client_height(), canvas.height()));
+    (width, height)
+}
+
 impl Drawer for CanvasDrawer {
     fn start_drawing(&mut self) {
+        if let Some(canvas) = self.context.canvas() {
+            let (width, height) = css_size(&canvas);
+            canvas.set_width((width * self.pixel_ratio).round() as u32);
+            canvas.set_height((height * self.pixel_ratio).round() as u32);
+            let style = canvas.style();
+            let _ = style.set_property("width", &format!("{width}px"));
+            let _ = style.set_property("height", &format!("{height}px"));
+            // Resizing the canvas resets the context
+            self.context.set_text_align("center");
+            self.context.set_text_baseline("middle");
+        }
+
+        self.context.save();
+        self.context.reset_transform();
+        if let Some(canvas) = self.context.canvas() {
+            self.context
+                .clear_rect(0.0, 0.0, canvas.width() as f64, canvas.height() as f64);
+        }
+        self.context.scale(self.pixel_ratio, self.pixel_ratio);
         self.context.begin_path();
     }
 
     fn finish_drawing(&mut self) {
-        self.context.stroke();
+        // Every primitive strokes its own path, and stroking the last path again here would redraw it in whatever
+        // color was set last. Only the transform set when starting to draw is restored.
+        self.context.restore();
     }
 
     fn draw_circle(&mut self, pos: Pos2, radius: f32, thickness: f32) {
         self.context.begin_path();
         self.context.set_line_width(thickness as f64);
         self.context
-            .arc(pos.x as f64, pos.y as f64, radius as f64, 0.0, 2.0 * PI)
-            .unwrap();
+            .arc(pos.x as f64, pos.y as f64, radius as f64, 0.0, 2.0 * PI);
         self.context.stroke();
         self.context.close_path();
     }
 
     fn draw_centered_text(&mut self, pos: Pos2, text: &str) {
-        self.context
-            .fill_text(text, pos.x as f64, pos.y as f64)
-            .unwrap();
+        self.context.fill_text(text, pos.x as f64, pos.y as f64);
     }
 
     fn draw_rect(&mut self, upper_left: Pos2, size: Pos2) {
@@ -63,11 +223,129 @@ impl Drawer for CanvasDrawer {
         self.context.close_path();
     }
 
+    fn draw_bezier_curve(
+        &mut self,
+        from: Pos2,
+        control1: Pos2,
+        control2: Pos2,
+        to: Pos2,
+        thickness: f32,
+    ) {
+        self.context.begin_path();
+        self.context.set_line_width(thickness as f64);
+        self.context.move_to(from.x as f64, from.y as f64);
+        self.context.bezier_curve_to(
+            control1.x as f64,
+            control1.y as f64,
+            control2.x as f64,
+            control2.y as f64,
+            to.x as f64,
+            to.y as f64,
+        );
+        self.context.stroke();
+        self.context.close_path();
+    }
+
     fn set_color(&mut self, rgb: [u8; 3]) {
         let [r, g, b] = rgb;
-        self.context
-            .set_stroke_style(&format!("rgb({r} {g} {b})").into());
-        self.context
-            .set_fill_style(&format!("rgb({r} {g} {b})").into())
+        self.context.set_color(&format!("rgb({r} {g} {b})"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{draw_dfa_with_opts, DrawOptions};
+    use dandy::dfa::Dfa;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A context which records which color each path was stroked with, and how many times
+    #[derive(Default)]
+    struct RecordingContext {
+        color: RefCell<String>,
+        /// The colors every path has been stroked with, the last one being the current path
+        paths: Rc<RefCell<Vec<Vec<String>>>>,
+    }
+
+    impl Context2d for RecordingContext {
+        fn canvas(&self) -> Option<HtmlCanvasElement> {
+            None
+        }
+
+        fn set_text_align(&self, _value: &str) {}
+
+        fn set_text_baseline(&self, _value: &str) {}
+
+        fn save(&self) {}
+
+        fn restore(&self) {}
+
+        fn reset_transform(&self) {}
+
+        fn clear_rect(&self, _x: f64, _y: f64, _w: f64, _h: f64) {}
+
+        fn scale(&self, _x: f64, _y: f64) {}
+
+        fn begin_path(&self) {
+            self.paths.borrow_mut().push(vec![]);
+        }
+
+        fn close_path(&self) {}
+
+        fn stroke(&self) {
+            let mut paths = self.paths.borrow_mut();
+            let color = self.color.borrow().clone();
+            match paths.last_mut() {
+                Some(path) => path.push(color),
+                None => paths.push(vec![color]),
+            }
+        }
+
+        fn set_line_width(&self, _value: f64) {}
+
+        fn set_color(&self, color: &str) {
+            *self.color.borrow_mut() = color.to_string();
+        }
+
+        fn arc(&self, _x: f64, _y: f64, _radius: f64, _start_angle: f64, _end_angle: f64) {}
+
+        fn move_to(&self, _x: f64, _y: f64) {}
+
+        fn line_to(&self, _x: f64, _y: f64) {}
+
+        fn bezier_curve_to(&self, _: f64, _: f64, _: f64, _: f64, _x: f64, _y: f64) {}
+
+        fn fill_text(&self, _text: &str, _x: f64, _y: f64) {}
+
+        fn fill_rect(&self, _x: f64, _y: f64, _w: f64, _h: f64) {}
+    }
+
+    #[test]
+    fn every_path_is_stroked_once_in_its_own_color() {
+        let dfa = "
+               a  b
+            * s0 s1 s2
+           -> s1 s2 s0
+            * s2 s0 s1
+        ";
+        let dfa: Dfa = dandy::parser::dfa(dfa).unwrap().try_into().unwrap();
+        let opts = DrawOptions::default()
+            .with_text_color([1, 0, 0])
+            .with_circle_color([0, 1, 0])
+            .with_line_color([0, 0, 1]);
+
+        let context = RecordingContext::default();
+        let paths = context.paths.clone();
+        let mut drawer = CanvasDrawer::with_context(Box::new(context), 1.0);
+        draw_dfa_with_opts(&dfa, &mut drawer, opts);
+
+        let paths = paths.take();
+        assert!(paths.iter().any(|strokes| !strokes.is_empty()));
+        for strokes in paths.into_iter().filter(|strokes| !strokes.is_empty()) {
+            // Stroking a path again when finishing the drawing would redraw it in the color of the text
+            assert_eq!(strokes.len(), 1, "Path stroked more than once: {strokes:?}");
+            assert_ne!(strokes[0], "rgb(1 0 0)");
+        }
     }
 }
@@ -7,6 +7,7 @@ pub mod pos2;
 use crate::pos2::{pos2, Pos2};
 use dandy::dfa::{Dfa, DfaState};
 use dandy::nfa::{Nfa, NfaState};
+use dandy::regex::RegexProvenance;
 use paste::paste;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
@@ -21,6 +22,30 @@ pub trait Drawer {
     fn draw_rect(&mut self, upper_left: Pos2, size: Pos2);
     fn draw_line(&mut self, from: Pos2, to: Pos2, thickness: f32);
     fn set_color(&mut self, _rgb: [u8; 3]) {}
+
+    /// Draws a cubic Bézier curve from `from` to `to` with the given control points. By default, the curve is
+    /// approximated by straight lines, for drawers which can't draw curves.
+    fn draw_bezier_curve(
+        &mut self,
+        from: Pos2,
+        control1: Pos2,
+        control2: Pos2,
+        to: Pos2,
+        thickness: f32,
+    ) {
+        const SEGMENTS: usize = 16;
+        let point = |t: f32| {
+            let u = 1.0 - t;
+            from * (u * u * u)
+                + control1 * (3.0 * u * u * t)
+                + control2 * (3.0 * u * t * t)
+                + to * (t * t * t)
+        };
+        for i in 0..SEGMENTS {
+            let (t0, t1) = (i as f32 / SEGMENTS as f32, (i + 1) as f32 / SEGMENTS as f32);
+            self.draw_line(point(t0), point(t1), thickness);
+        }
+    }
 }
 
 struct OffsetScaleDrawer<'a, T> {
@@ -67,6 +92,49 @@ impl<'a, T: Drawer> Drawer for OffsetScaleDrawer<'a, T> {
     fn set_color(&mut self, rgb: [u8; 3]) {
         self.drawer.set_color(rgb)
     }
+
+    fn draw_bezier_curve(
+        &mut self,
+        from: Pos2,
+        control1: Pos2,
+        control2: Pos2,
+        to: Pos2,
+        thickness: f32,
+    ) {
+        self.drawer.draw_bezier_curve(
+            (from + self.offset) * self.scale,
+            (control1 + self.offset) * self.scale,
+            (control2 + self.offset) * self.scale,
+            (to + self.offset) * self.scale,
+            thickness,
+        )
+    }
+}
+
+impl<T: Drawer> OffsetScaleDrawer<'_, T> {
+    // Each primitive sets its own color right before it is drawn, since some drawers (such as the canvas) keep the
+    // color as global state, which would otherwise leak into the next primitive drawn
+
+    fn colored_circle(&mut self, rgb: [u8; 3], pos: Pos2, radius: f32, thickness: f32) {
+        self.set_color(rgb);
+        self.draw_circle(pos, radius, thickness);
+    }
+
+    fn colored_text(&mut self, rgb: [u8; 3], pos: Pos2, text: &str) {
+        self.set_color(rgb);
+        self.draw_centered_text(pos, text);
+    }
+
+    fn colored_line(&mut self, rgb: [u8; 3], from: Pos2, to: Pos2, thickness: f32) {
+        self.set_color(rgb);
+        self.draw_line(from, to, thickness);
+    }
+
+    fn colored_curve(&mut self, rgb: [u8; 3], points: [Pos2; 4], thickness: f32) {
+        self.set_color(rgb);
+        let [from, control1, control2, to] = points;
+        self.draw_bezier_curve(from, control1, control2, to, thickness);
+    }
 }
 
 macro_rules! define_draw_options {
@@ -78,6 +146,7 @@ macro_rules! define_draw_options {
         }
 
         impl $name {
+            #[allow(clippy::too_many_arguments)]
             pub fn new($($field: $ty,)*) -> Self {
                 Self {
                     $($field,)*
@@ -130,6 +199,88 @@ define_draw_options! {
         text_color: [u8; 3] = [255, 255, 255],
         circle_color: [u8; 3] = [150, 255, 255],
         line_color: [u8; 3] = [0, 255, 255],
+        curve_bidirectional: bool = false,
+        state_colors: Vec<Option<[u8; 3]>> = Vec::new(),
+    }
+}
+
+impl DrawOptions {
+    /// Draws the circles of the state with the given index in the given color instead of the circle color, for
+    /// example to highlight the current state when stepping through an automaton. The indices are the same as the
+    /// indices of [Dfa::states] and [Nfa::states].
+    pub fn with_state_color(mut self, state: usize, rgb: [u8; 3]) -> Self {
+        if self.state_colors.len() <= state {
+            self.state_colors.resize(state + 1, None);
+        }
+        self.state_colors[state] = Some(rgb);
+        self
+    }
+
+    /// Colors the states of a NFA converted from a regex by which part of the Thompson construction they are, as
+    /// given by [Regex::to_nfa_annotated](dandy::regex::Regex::to_nfa_annotated), see [regex_provenance_color]
+    pub fn with_regex_provenance(self, provenance: &[RegexProvenance]) -> Self {
+        provenance
+            .iter()
+            .enumerate()
+            .fold(self, |opts, (state, provenance)| {
+                opts.with_state_color(state, regex_provenance_color(provenance))
+            })
+    }
+
+    /// Creates options suitable for drawing the given DFA, see [DrawOptions::for_nfa] for how they are picked
+    pub fn for_dfa(dfa: &Dfa) -> Self {
+        let states = dfa.states().iter().map(Into::into).collect::<Vec<State>>();
+        Self::for_automaton(&states, dfa_to_arrows(dfa))
+    }
+
+    /// Creates options suitable for drawing the given NFA. Starting from the default options, the circles get
+    /// smaller the more states there are (down to half of the default size), the floors get higher the more levels
+    /// of arrows there are (which is especially common for NFAs with many epsilon transitions), and the margin of the
+    /// text grows with the length of the longest state name.
+    pub fn for_nfa(nfa: &Nfa) -> Self {
+        let states = nfa.states().iter().map(Into::into).collect::<Vec<State>>();
+        Self::for_automaton(&states, nfa_to_arrows(nfa))
+    }
+
+    fn for_automaton(states: &[State], arrows: Vec<Arrow>) -> Self {
+        let default = Self::default();
+        let (_, levels) = place_arrows(group_arrows(arrows));
+        let longest_name = states
+            .iter()
+            .map(|s| s.name.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        // Up to 8 states are drawn in full size, after which the circles shrink
+        let circle_scale = (8.0 / states.len().max(1) as f32).sqrt().clamp(0.5, 1.0);
+        // Up to 4 levels of arrows fit within the default floor height
+        let floor_scale = (1.0 + 0.1 * levels.saturating_sub(4) as f32).min(2.0);
+        // State names of up to 3 characters fit within the default margin
+        let text_margin = default.text_margin + 2.0 * longest_name.saturating_sub(3).min(10) as f32;
+
+        Self {
+            circle_radius: default.circle_radius * circle_scale,
+            accepting_circle_radius: default.accepting_circle_radius * circle_scale,
+            floor_height: default.floor_height * floor_scale,
+            text_margin,
+            ..default
+        }
+    }
+}
+
+/// The color of states of the given [RegexProvenance] used by [DrawOptions::with_regex_provenance]. The parts of
+/// sequences are blue, alternations orange, repetitions purple and characters yellow, while the initial and
+/// accepting states are green and red. States of NFAs compiled from extended regexes keep the default circle color.
+pub fn regex_provenance_color(provenance: &RegexProvenance) -> [u8; 3] {
+    match provenance {
+        RegexProvenance::Initial => [120, 255, 120],
+        RegexProvenance::Accepting => [255, 120, 120],
+        RegexProvenance::SequenceEntry | RegexProvenance::SequenceJoint { .. } => [150, 170, 255],
+        RegexProvenance::AltEntry | RegexProvenance::AltBranch { .. } => [255, 190, 100],
+        RegexProvenance::RepeatEntry => [220, 140, 255],
+        RegexProvenance::CharIncoming { .. } => [255, 255, 130],
+        RegexProvenance::EpsilonIncoming | RegexProvenance::EmptyIncoming => [180, 180, 180],
+        RegexProvenance::Compiled => DrawOptions::default().circle_color,
     }
 }
 
@@ -168,6 +319,24 @@ fn draw<'a>(
     };
 
     let arrows = group_arrows(arrows);
+    // With curve_bidirectional, arrows between two states going both ways are drawn as curves instead of being
+    // placed on the floors above the states
+    let (curved, arrows): (Vec<_>, Vec<_>) = if opts.curve_bidirectional {
+        let present = arrows
+            .iter()
+            .map(|arrow| (arrow.left, arrow.right, arrow.direction))
+            .collect::<HashSet<_>>();
+        arrows.into_iter().partition(|arrow| {
+            let opposite = match arrow.direction {
+                Direction::Left => Direction::Right,
+                Direction::Right => Direction::Left,
+                Direction::Spot => return false,
+            };
+            present.contains(&(arrow.left, arrow.right, opposite))
+        })
+    } else {
+        (vec![], arrows)
+    };
     let (arrows, levels) = place_arrows(arrows);
 
     let x_pos = |idx: usize| -> f32 {
@@ -188,15 +357,21 @@ fn draw<'a>(
     // draw arrow
     // FIXME: This assumes first is initial, which isn't always the case
     {
-        drawer.set_color(opts.line_color);
         let arrow_base = pos2(opts.init_arrow_length, circle_center);
-        drawer.draw_line(pos2(0.0, circle_center), arrow_base, opts.init_arrow_width);
-        drawer.draw_line(
+        drawer.colored_line(
+            opts.line_color,
+            pos2(0.0, circle_center),
+            arrow_base,
+            opts.init_arrow_width,
+        );
+        drawer.colored_line(
+            opts.line_color,
             pos2(opts.init_arrow_length, circle_center),
             arrow_base - pos2(opts.init_arrow_arms_length, opts.init_arrow_arms_length),
             opts.init_arrow_width,
         );
-        drawer.draw_line(
+        drawer.colored_line(
+            opts.line_color,
             pos2(opts.init_arrow_length, circle_center),
             arrow_base - pos2(opts.init_arrow_arms_length, -opts.init_arrow_arms_length),
             opts.init_arrow_width,
@@ -206,54 +381,103 @@ fn draw<'a>(
     // draw states
     for (idx, state) in states.iter().enumerate() {
         let cc = pos2(x_pos(idx), circle_center);
-        drawer.set_color(opts.circle_color);
-        drawer.draw_circle(cc, opts.circle_radius, opts.circle_width);
+        let circle_color = opts
+            .state_colors
+            .get(idx)
+            .copied()
+            .flatten()
+            .unwrap_or(opts.circle_color);
+        drawer.colored_circle(circle_color, cc, opts.circle_radius, opts.circle_width);
         if state.accepting {
-            drawer.draw_circle(
+            drawer.colored_circle(
+                circle_color,
                 cc,
                 opts.accepting_circle_radius,
                 opts.accepting_circle_width,
             );
         }
         if state.initial {
-            drawer.set_color(opts.line_color);
             let start = cc + Pos2::y(opts.circle_radius + opts.center_line_padding);
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 start,
                 start + Pos2::y(opts.init_arrow_length),
                 opts.init_arrow_width,
             );
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 start,
                 start + pos2(opts.init_arrow_arms_length, opts.init_arrow_arms_length),
                 opts.init_arrow_width,
             );
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 start,
                 start + pos2(-opts.init_arrow_arms_length, opts.init_arrow_arms_length),
                 opts.init_arrow_width,
             );
         }
 
-        drawer.set_color(opts.text_color);
-        drawer.draw_centered_text(cc, state.name);
+        drawer.colored_text(opts.text_color, cc, state.name);
+    }
+
+    for arrow in curved {
+        // Arrows to the right curve above the states, and arrows to the left curve below them
+        let (side, from, to) = match arrow.direction {
+            Direction::Right => (-1.0, arrow.left, arrow.right),
+            _ => (1.0, arrow.right, arrow.left),
+        };
+        let from = pos2(x_pos(from), circle_center + side * opts.circle_radius);
+        let to = pos2(x_pos(to), circle_center + side * opts.circle_radius);
+        let bulge = Pos2::y(side * (opts.floor_height + 0.25 * (to.x - from.x).abs()));
+        drawer.colored_curve(
+            opts.line_color,
+            [from, from + bulge, to + bulge, to],
+            opts.trans_line_width,
+        );
+
+        if opts.end_arrow {
+            let arms = opts.trans_arrow_arms_length;
+            drawer.colored_line(
+                opts.line_color,
+                to,
+                to + pos2(arms, side * arms),
+                opts.trans_line_width,
+            );
+            drawer.colored_line(
+                opts.line_color,
+                to,
+                to + pos2(-arms, side * arms),
+                opts.trans_line_width,
+            );
+        }
+
+        // The middle of a cubic Bézier curve is at 1/8 of each end point and 3/8 of each control point
+        let middle = (from + to) * 0.5 + bulge * 0.75;
+        drawer.colored_text(
+            opts.text_color,
+            middle + Pos2::y(side * opts.text_margin),
+            &arrow.label(),
+        );
     }
 
     for arrow in arrows {
-        drawer.set_color(opts.line_color);
         let line_height = opts.floor_height * (levels - arrow.level) as f32;
 
-        drawer.draw_line(
+        drawer.colored_line(
+            opts.line_color,
             pos2(x_from_pos(arrow.arrow.left), line_baseline),
             pos2(x_from_pos(arrow.arrow.left), line_height),
             opts.trans_line_width,
         );
-        drawer.draw_line(
+        drawer.colored_line(
+            opts.line_color,
             pos2(x_to_pos(arrow.arrow.right), line_baseline),
             pos2(x_to_pos(arrow.arrow.right), line_height),
             opts.trans_line_width,
         );
-        drawer.draw_line(
+        drawer.colored_line(
+            opts.line_color,
             pos2(x_from_pos(arrow.arrow.left), line_height),
             pos2(x_to_pos(arrow.arrow.right), line_height),
             opts.trans_line_width,
@@ -267,7 +491,8 @@ fn draw<'a>(
                 -1.0
             };
             let middle = middle - opts.trans_arrow_arms_length * mul * 0.5;
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 pos2(middle, line_height),
                 pos2(
                     middle + mul * opts.trans_arrow_arms_length,
@@ -275,7 +500,8 @@ fn draw<'a>(
                 ),
                 opts.trans_line_width,
             );
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 pos2(middle, line_height),
                 pos2(
                     middle + mul * opts.trans_arrow_arms_length,
@@ -292,20 +518,22 @@ fn draw<'a>(
                 (x_from_pos(arrow.arrow.left), line_baseline)
             };
             let base = pos2(x, y);
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 base,
                 base - pos2(opts.trans_arrow_arms_length, opts.trans_arrow_arms_length),
                 opts.trans_line_width,
             );
-            drawer.draw_line(
+            drawer.colored_line(
+                opts.line_color,
                 pos2(x, y),
                 base - pos2(-opts.trans_arrow_arms_length, opts.trans_arrow_arms_length),
                 opts.trans_line_width,
             );
         }
 
-        drawer.set_color(opts.text_color);
-        drawer.draw_centered_text(
+        drawer.colored_text(
+            opts.text_color,
             pos2(middle, line_height - opts.text_margin),
             &arrow.arrow.label(),
         );
@@ -663,3 +891,262 @@ impl<'a> From<&'a NfaState> for State<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Command {
+        Circle,
+        Text(String),
+        Rect,
+        Line,
+        Curve,
+    }
+
+    /// A drawer which records every primitive drawn, together with the color in effect when it was drawn
+    #[derive(Default)]
+    struct RecordingDrawer {
+        color: Option<[u8; 3]>,
+        commands: Vec<(Command, Option<[u8; 3]>)>,
+    }
+
+    impl Drawer for RecordingDrawer {
+        fn start_drawing(&mut self) {}
+
+        fn finish_drawing(&mut self) {}
+
+        fn draw_circle(&mut self, _pos: Pos2, _radius: f32, _thickness: f32) {
+            self.commands.push((Command::Circle, self.color));
+        }
+
+        fn draw_centered_text(&mut self, _pos: Pos2, text: &str) {
+            self.commands
+                .push((Command::Text(text.to_string()), self.color));
+        }
+
+        fn draw_rect(&mut self, _upper_left: Pos2, _size: Pos2) {
+            self.commands.push((Command::Rect, self.color));
+        }
+
+        fn draw_line(&mut self, _from: Pos2, _to: Pos2, _thickness: f32) {
+            self.commands.push((Command::Line, self.color));
+        }
+
+        fn set_color(&mut self, rgb: [u8; 3]) {
+            self.color = Some(rgb);
+        }
+
+        fn draw_bezier_curve(
+            &mut self,
+            _from: Pos2,
+            _control1: Pos2,
+            _control2: Pos2,
+            _to: Pos2,
+            _thickness: f32,
+        ) {
+            self.commands.push((Command::Curve, self.color));
+        }
+    }
+
+    #[test]
+    fn every_primitive_has_its_own_color() {
+        // Initial state in the middle, with accepting states on both sides
+        let dfa = "
+               a  b
+            * s0 s1 s2
+           -> s1 s2 s0
+            * s2 s0 s1
+        ";
+        let dfa: Dfa = dandy::parser::dfa(dfa).unwrap().try_into().unwrap();
+
+        let text = [1, 0, 0];
+        let circle = [0, 1, 0];
+        let line = [0, 0, 1];
+        let opts = DrawOptions::default()
+            .with_text_color(text)
+            .with_circle_color(circle)
+            .with_line_color(line);
+
+        let mut drawer = RecordingDrawer::default();
+        draw_dfa_with_opts(&dfa, &mut drawer, opts);
+
+        assert_eq!(
+            drawer
+                .commands
+                .iter()
+                .filter(|(c, _)| *c == Command::Circle)
+                .count(),
+            5
+        );
+        let texts = drawer
+            .commands
+            .iter()
+            .filter(|(c, _)| matches!(c, Command::Text(_)))
+            .count();
+        assert_eq!(texts, 3 + group_arrows(dfa_to_arrows(&dfa)).len());
+
+        for (command, color) in drawer.commands {
+            let expected = match command {
+                Command::Circle => circle,
+                Command::Text(_) => text,
+                Command::Line | Command::Curve => line,
+                Command::Rect => unreachable!("No rectangles are drawn"),
+            };
+            assert_eq!(color, Some(expected), "Wrong color for {command:?}");
+        }
+    }
+
+    #[test]
+    fn state_color_overrides_circle_color() {
+        let dfa = "
+               a  b
+           -> s0 s1 s0
+            * s1 s1 s2
+              s2 s2 s2
+        ";
+        let dfa: Dfa = dandy::parser::dfa(dfa).unwrap().try_into().unwrap();
+
+        let circle = [0, 1, 0];
+        let highlight = [1, 1, 1];
+        let opts = DrawOptions::default()
+            .with_circle_color(circle)
+            .with_state_color(1, highlight);
+
+        let mut drawer = RecordingDrawer::default();
+        draw_dfa_with_opts(&dfa, &mut drawer, opts);
+
+        // s0, both circles of the accepting s1, and s2
+        let circles = drawer
+            .commands
+            .iter()
+            .filter(|(c, _)| *c == Command::Circle)
+            .map(|(_, color)| color.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(circles, [circle, highlight, highlight, circle]);
+    }
+
+    #[test]
+    fn curved_bidirectional_arrows() {
+        // s0 and s1 have transitions both ways, while s1 and s2 only have a transition one way
+        let dfa = "
+               a  b
+           -> s0 s1 s0
+            * s1 s0 s2
+              s2 s2 s2
+        ";
+        let dfa: Dfa = dandy::parser::dfa(dfa).unwrap().try_into().unwrap();
+        let count = |opts: DrawOptions, command: Command| {
+            let mut drawer = RecordingDrawer::default();
+            draw_dfa_with_opts(&dfa, &mut drawer, opts);
+            drawer
+                .commands
+                .iter()
+                .filter(|(c, _)| *c == command)
+                .count()
+        };
+
+        assert_eq!(count(DrawOptions::default(), Command::Curve), 0);
+        let curved = DrawOptions::default().with_curve_bidirectional(true);
+        assert_eq!(count(curved, Command::Curve), 2);
+
+        // Each curve replaces the three lines of a placed arrow and the two lines of its middle arrow
+        let lines = count(DrawOptions::default(), Command::Line);
+        let curved = DrawOptions::default().with_curve_bidirectional(true);
+        assert_eq!(count(curved, Command::Line), lines - 2 * 5);
+    }
+
+    /// A drawer which only draws lines, to check the default implementation of curves
+    #[derive(Default)]
+    struct LineDrawer {
+        lines: Vec<(Pos2, Pos2)>,
+    }
+
+    impl Drawer for LineDrawer {
+        fn start_drawing(&mut self) {}
+
+        fn finish_drawing(&mut self) {}
+
+        fn draw_circle(&mut self, _pos: Pos2, _radius: f32, _thickness: f32) {}
+
+        fn draw_centered_text(&mut self, _pos: Pos2, _text: &str) {}
+
+        fn draw_rect(&mut self, _upper_left: Pos2, _size: Pos2) {}
+
+        fn draw_line(&mut self, from: Pos2, to: Pos2, _thickness: f32) {
+            self.lines.push((from, to));
+        }
+    }
+
+    #[test]
+    fn default_bezier_curve_is_connected_lines() {
+        let mut drawer = LineDrawer::default();
+        let (from, to) = (pos2(0.0, 0.0), pos2(30.0, 0.0));
+        drawer.draw_bezier_curve(from, pos2(0.0, -20.0), pos2(30.0, -20.0), to, 1.0);
+        assert_eq!(drawer.lines.first().unwrap().0, from);
+        assert_eq!(drawer.lines.last().unwrap().1, to);
+        assert!(drawer.lines.windows(2).all(|w| w[0].1 == w[1].0));
+        // The middle of the curve is 3/4 of the way to the control points
+        let middle = drawer.lines[drawer.lines.len() / 2].0;
+        assert_eq!(middle, pos2(15.0, -15.0));
+    }
+
+    #[test]
+    fn states_colored_by_regex_provenance() {
+        let regex = dandy::parser::regex("a(b|c)*").unwrap();
+        let (nfa, provenance) = regex.to_nfa_annotated();
+        let opts = DrawOptions::default().with_regex_provenance(&provenance);
+        assert_eq!(opts.state_colors.len(), nfa.states().len());
+        let mut drawer = RecordingDrawer::default();
+        draw_nfa_with_opts(&nfa, &mut drawer, opts);
+        // The accepting state is drawn with two circles, and the other states with one
+        let circle_colors = drawer
+            .commands
+            .iter()
+            .filter(|(command, _)| *command == Command::Circle)
+            .map(|(_, color)| color.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(circle_colors.len(), nfa.states().len() + 1);
+        for provenance in &provenance {
+            assert!(circle_colors.contains(&regex_provenance_color(provenance)));
+        }
+        let initial = circle_colors
+            .iter()
+            .filter(|&&color| color == regex_provenance_color(&RegexProvenance::Initial))
+            .count();
+        assert_eq!(initial, 1);
+    }
+
+    #[test]
+    fn options_for_automata() {
+        let small = "
+               a  b
+           -> s0 s1 s0
+            * s1 s1 s0
+        ";
+        let small: Dfa = dandy::parser::dfa(small).unwrap().try_into().unwrap();
+        let opts = DrawOptions::for_dfa(&small);
+        let default = DrawOptions::default();
+        assert_eq!(opts.circle_radius, default.circle_radius);
+        assert_eq!(opts.floor_height, default.floor_height);
+        assert_eq!(opts.text_margin, default.text_margin);
+
+        // 16 states, where every state has epsilon transitions to every other state
+        let names = (0..16)
+            .map(|i| format!("long_name_{i}"))
+            .collect::<Vec<_>>();
+        let mut large = String::from("  ε a\n");
+        for (idx, name) in names.iter().enumerate() {
+            let prefix = if idx == 0 { "->" } else { "  " };
+            large.push_str(&format!("{prefix} {name} {{{}}} {{}}\n", names.join(" ")));
+        }
+        let large: Nfa = dandy::parser::nfa(&large).unwrap().try_into().unwrap();
+        let opts = DrawOptions::for_nfa(&large);
+        assert!(opts.circle_radius < default.circle_radius);
+        assert!(opts.circle_radius >= default.circle_radius / 2.0);
+        assert!(opts.accepting_circle_radius < opts.circle_radius);
+        assert!(opts.floor_height > default.floor_height);
+        assert!(opts.text_margin > default.text_margin);
+    }
+}
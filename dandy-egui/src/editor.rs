@@ -0,0 +1,186 @@
+//! # The live editor
+//! [DfaEditorState] parses the table being edited into a [Dfa], but only when the text changed since the last time
+//! it was given, so that the editor can be updated every frame without parsing the table on every repaint. The
+//! result is kept keyed by a hash of the text.
+//!
+//! ```
+//! use dandy_egui::editor::DfaEditorState;
+//!
+//! let mut editor = DfaEditorState::default();
+//! assert!(editor.update("   a\n→ * s s").is_ok());
+//! let error = editor.update("   a\n→ * s s\n  t").as_ref().unwrap_err();
+//! assert_eq!((error.line, error.column), (Some(3), Some(3)));
+//! assert_eq!((editor.misses(), editor.hits()), (2, 0));
+//! ```
+
+use dandy::dfa::Dfa;
+use dandy::io;
+use dandy_draw::egui::EguiDrawer;
+use dandy_draw::DrawOptions;
+use egui::{Color32, FontSelection, TextStyle, Ui};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+/// Why the text in the editor couldn't be turned into a DFA, located in the text if possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseErrorDisplay {
+    pub message: String,
+    /// The line (starting from 1) the parser stopped at if the text couldn't be parsed as a table at all, and
+    /// otherwise the line of the state or element the error is about, if there is one
+    pub line: Option<usize>,
+    /// The column (in characters, starting from 1) on [line](ParseErrorDisplay::line) the parser stopped at, if the
+    /// text couldn't be parsed as a table at all
+    pub column: Option<usize>,
+}
+
+impl ParseErrorDisplay {
+    /// Locates a parse error, where `remaining` is the part of `text` which the parser couldn't make sense of, at the
+    /// first non-whitespace character of the remaining input (or the end of the last line which isn't empty), see
+    /// [io::parse_error_offset]
+    fn parse_error(text: &str, remaining: &str) -> Self {
+        let offset = io::parse_error_offset(text, remaining);
+        let line_start = text[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let content = text[offset..].lines().next().unwrap_or_default().trim();
+        Self {
+            message: if content.is_empty() {
+                "unexpected end of table".to_string()
+            } else {
+                format!("couldn't parse \"{content}\"")
+            },
+            line: io::line_of(text, &text[offset..]),
+            column: Some(text[line_start..offset].chars().count() + 1),
+        }
+    }
+}
+
+impl Display for ParseErrorDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            (Some(line), None) => write!(f, "line {line}: {}", self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// The state of a live DFA editor: the DFA parsed from the text last given to [DfaEditorState::update], together
+/// with how often the text had to be parsed
+#[derive(Debug, Default)]
+pub struct DfaEditorState {
+    parsed: Option<(u64, Result<Dfa, ParseErrorDisplay>)>,
+    hits: usize,
+    misses: usize,
+}
+
+impl DfaEditorState {
+    /// Gives the DFA parsed from `text`, which is only parsed again if it differs from the text given last time
+    pub fn update(&mut self, text: &str) -> &Result<Dfa, ParseErrorDisplay> {
+        let hash = {
+            let mut hasher = DefaultHasher::new();
+            text.hash(&mut hasher);
+            hasher.finish()
+        };
+        match &mut self.parsed {
+            Some((cached, _)) if *cached == hash => self.hits += 1,
+            parsed => {
+                self.misses += 1;
+                *parsed = Some((hash, parse(text)));
+            }
+        }
+        &self.parsed.as_ref().expect("the text was just parsed").1
+    }
+
+    /// The result of the last [update](DfaEditorState::update), if there has been one
+    pub fn current(&self) -> Option<&Result<Dfa, ParseErrorDisplay>> {
+        self.parsed.as_ref().map(|(_, result)| result)
+    }
+
+    /// The number of updates where the text was unchanged, so the cached DFA was used
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// The number of updates where the text changed and was parsed again
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Shows an editor for `text` followed by the DFA parsed from it (drawn with `opts`), or the error explaining why
+    /// it couldn't be parsed
+    pub fn show(&mut self, ui: &mut Ui, text: &mut String, opts: DrawOptions) {
+        ui.add(egui::TextEdit::multiline(text).font(FontSelection::Style(TextStyle::Monospace)));
+        match self.update(text) {
+            Ok(dfa) => {
+                egui::Area::new("DFA").show(ui.ctx(), |ui| {
+                    let mut drawer = EguiDrawer::new(ui.painter());
+                    dandy_draw::draw_dfa_with_opts(dfa, &mut drawer, opts);
+                });
+            }
+            Err(error) => {
+                ui.colored_label(Color32::RED, error.to_string());
+            }
+        }
+    }
+}
+
+/// Parses a table into a DFA, describing why it failed if it does
+fn parse(text: &str) -> Result<Dfa, ParseErrorDisplay> {
+    let parsed = dandy::parser::dfa(text)
+        .map_err(|error| ParseErrorDisplay::parse_error(text, error.input))?;
+    Dfa::try_from(parsed).map_err(|error| ParseErrorDisplay {
+        message: error.to_string(),
+        line: io::dfa_error_slice(&error).and_then(|slice| io::line_of(text, slice)),
+        column: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "   a  b\n→ s  t  s\n* t  t  s\n";
+
+    #[test]
+    fn parses_only_changed_text() {
+        let mut editor = DfaEditorState::default();
+        assert!(editor.current().is_none());
+        assert!(editor.update(TABLE).is_ok());
+        assert!(editor.update(TABLE).is_ok());
+        assert!(editor.update(TABLE).is_ok());
+        assert_eq!((editor.misses(), editor.hits()), (1, 2));
+
+        let changed = TABLE.replace("t  t  s", "t  t  t");
+        let dfa = editor.update(&changed).as_ref().unwrap();
+        assert!(dfa.accepts_graphemes("ab"));
+        assert_eq!((editor.misses(), editor.hits()), (2, 2));
+        editor.update(TABLE);
+        assert_eq!((editor.misses(), editor.hits()), (3, 2));
+    }
+
+    #[test]
+    fn locates_parse_errors() {
+        let mut editor = DfaEditorState::default();
+        let error = editor
+            .update("   a  b\n→ s  t  s\n* t  t  s\n  u →\n")
+            .clone()
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (Some(4), Some(3)));
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 3: couldn't parse \"u →\""
+        );
+        assert_eq!(editor.current().unwrap(), &Err(error));
+
+        let error = editor
+            .update("   a  b\n→ s  t  s\n* t  t  u\n")
+            .as_ref()
+            .unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), None));
+        assert!(error.to_string().starts_with("line 3: "));
+        assert!(editor.update(TABLE).is_ok());
+        assert_eq!((editor.misses(), editor.hits()), (3, 0));
+    }
+}
@@ -0,0 +1,4 @@
+//! Live editing of DFAs with egui. [DfaEditorState](editor::DfaEditorState) keeps the automaton parsed from the text
+//! being edited, and draws it (or the reason it couldn't be parsed) in a panel.
+
+pub mod editor;
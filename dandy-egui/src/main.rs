@@ -1,9 +1,8 @@
 use dandy::dfa::Dfa;
 use dandy::nfa::Nfa;
-use dandy_draw::egui::EguiDrawer;
 use dandy_draw::DrawOptions;
+use dandy_egui::editor::DfaEditorState;
 use eframe::egui;
-use egui::{FontSelection, TextStyle};
 
 fn example_dfa() -> Dfa {
     dandy::parser::dfa(include_str!("../../dandy-cli/tests/example.dfa"))
@@ -33,32 +32,14 @@ fn main() -> Result<(), eframe::Error> {
     };
 
     let mut dfa = example_dfa().to_table();
-    let mut dfa_to_render = dfa.clone();
+    let mut editor = DfaEditorState::default();
 
     eframe::run_simple_native("Display DFAs", options, move |ctx, _frame| {
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add(
-                egui::TextEdit::multiline(&mut dfa)
-                    .font(FontSelection::Style(TextStyle::Monospace)),
-            );
-
-            if ui.button("Render").clicked() {
-                dfa_to_render = dfa.clone();
-            }
-
-            egui::Area::new("DFA").show(ui.ctx(), |ui| {
-                let painter = ui.painter();
-                let mut drawer = EguiDrawer::new(painter);
-                if let Some(Ok(dfa)) = dandy::parser::dfa(&dfa_to_render)
-                    .ok()
-                    .map(TryInto::try_into)
-                {
-                    let opts = DrawOptions::default()
-                        .with_x_offset(20.0)
-                        .with_y_offset(150.0);
-                    dandy_draw::draw_dfa_with_opts(&dfa, &mut drawer, opts);
-                }
-            });
+            let opts = DrawOptions::default()
+                .with_x_offset(20.0)
+                .with_y_offset(150.0);
+            editor.show(ui, &mut dfa, opts);
         });
     })
 }
@@ -0,0 +1,62 @@
+#ifndef DANDY_H
+#define DANDY_H
+
+/* Generated by cbindgen from src/lib.rs, edit the documentation there instead */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ * Threads: every function may be called from any thread, and a DFA loaded on one thread may be used, minimized and
+ * deleted on any other. Calls are serialized by a lock on the loaded DFAs, so a call may block while another thread's
+ * call runs. The message of dandy_last_error() is kept per thread, and only reports failures on the calling thread.
+ * A panic in the library never unwinds into the caller, but makes the call fail with the error value it documents.
+ */
+
+/**
+ * Checks if two DFAs accept the same language, giving `1` if they do, `0` if they don't and `-1` if either handle
+ * doesn't refer to a loaded DFA. DFAs with different alphabets are never equivalent.
+ */
+int dandy_check_eq(uintptr_t dfa1, uintptr_t dfa2);
+
+/**
+ * Deletes the DFA with the given handle, giving `0` if it was deleted and `-1` if there is no such DFA
+ */
+int dandy_delete_dfa(uintptr_t dfa);
+
+/**
+ * Frees a string returned by one of these functions. Passing `NULL` does nothing.
+ *
+ * # Safety
+ * `s` must be `NULL` or a string returned by one of these functions which hasn't been freed already.
+ */
+void dandy_free_string(char *s);
+
+/**
+ * Gives the message of the last error on the calling thread, or `NULL` if no call on this thread has failed. The
+ * message must be freed with dandy_free_string().
+ */
+char *dandy_last_error(void);
+
+/**
+ * Parses a DFA from its table and loads it, giving its handle, or `0` if the table couldn't be parsed.
+ *
+ * # Safety
+ * `table` must be `NULL` or point to a NUL-terminated string which is valid for the duration of the call.
+ */
+uintptr_t dandy_load_dfa(const char *table);
+
+/**
+ * Minimizes the DFA with the given handle in place, giving `0` if it was minimized and `-1` if there is no such DFA
+ */
+int dandy_minimize(uintptr_t dfa);
+
+/**
+ * Gives the table of the DFA with the given handle, which may be parsed again by dandy_load_dfa(), or `NULL` if
+ * there is no such DFA. The table must be freed with dandy_free_string().
+ */
+char *dandy_to_table(uintptr_t dfa);
+
+#endif  /* DANDY_H */
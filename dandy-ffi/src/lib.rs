@@ -0,0 +1,240 @@
+//! C bindings for the dandy library, for embedding it in other languages (such as Python through `ctypes`) rather
+//! than running dandy-cli and reading its output. The functions are declared in `include/dandy.h`, which is generated
+//! from this file by cbindgen (see `cbindgen.toml`).
+//!
+//! Like the wasm bindings, the DFAs are not exposed to the caller. dandy_load_dfa() parses a table and stores the DFA
+//! in a global registry, and returns a handle which is used to reference the DFA later on. The DFA is kept loaded
+//! until dandy_delete_dfa() is called. Handles are never `0`, which is returned when loading fails. When a DFA is
+//! deleted, its handle is recycled (the smallest recycled handle first), so a handle kept after deleting its DFA may
+//! later refer to another DFA. Example usage from Python:
+//! ```python
+//! import ctypes
+//! dandy = ctypes.CDLL("target/release/libdandy_ffi.so")
+//! dandy.dandy_load_dfa.restype = ctypes.c_size_t
+//! dandy.dandy_to_table.restype = ctypes.c_void_p
+//! dandy.dandy_last_error.restype = ctypes.c_void_p
+//!
+//! def take_string(ptr):
+//!     text = ctypes.string_at(ptr).decode("utf-8")
+//!     dandy.dandy_free_string(ctypes.c_void_p(ptr))
+//!     return text
+//!
+//! dfa = dandy.dandy_load_dfa("   a\n→ s t\n* t s".encode("utf-8"))
+//! if dfa == 0:
+//!     raise ValueError(take_string(dandy.dandy_last_error()))
+//! dandy.dandy_minimize(ctypes.c_size_t(dfa))
+//! print(take_string(dandy.dandy_to_table(ctypes.c_size_t(dfa))))
+//! dandy.dandy_delete_dfa(ctypes.c_size_t(dfa))
+//! ```
+//!
+//! ## Strings
+//! All strings passed to and returned from these functions are UTF-8 and terminated by a NUL byte. Strings passed to
+//! the functions are only borrowed for the duration of the call. Strings returned by the functions are owned by the
+//! caller, and must be freed by passing them to dandy_free_string() exactly once (and not by `free()`, since they are
+//! allocated by Rust).
+//!
+//! ## Errors
+//! Functions report failure by their return value (`0` for handles, `-1` for integers and `NULL` for strings). The
+//! message of the last error on the calling thread is then given by dandy_last_error(). Successful calls don't clear
+//! the last error. A panic never unwinds into the caller, but is reported as an error like any other.
+//!
+//! ## Threads
+//! The functions may be called from any thread. The registry is protected by a mutex, so calls are serialized, and a
+//! DFA loaded on one thread can be used on any other. Since DFAs can't be sent between threads, the registry stores
+//! them taken apart (see [DfaParts]), and every call puts a copy of the DFAs it uses together on the calling thread.
+
+use dandy::batch::DfaParts;
+use dandy::dfa::Dfa;
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry::new());
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// The loaded DFAs, by their handles
+struct Registry {
+    dfas: BTreeMap<usize, DfaParts>,
+    next: Option<usize>,
+    free: BTreeSet<usize>,
+}
+
+impl Registry {
+    const fn new() -> Self {
+        Self {
+            dfas: BTreeMap::new(),
+            next: Some(1),
+            free: BTreeSet::new(),
+        }
+    }
+
+    /// Stores a DFA under a new handle, which is a recycled handle if there is one
+    fn push(&mut self, dfa: &Dfa) -> Result<usize, String> {
+        let handle = match self.free.pop_first() {
+            Some(handle) => handle,
+            None => {
+                let handle = self.next.ok_or(
+                    "No more handles available, delete some DFAs before loading new ones"
+                        .to_string(),
+                )?;
+                self.next = handle.checked_add(1);
+                handle
+            }
+        };
+        self.dfas.insert(handle, DfaParts::new(dfa));
+        Ok(handle)
+    }
+
+    /// Puts a copy of the DFA with the given handle together
+    fn get(&self, handle: usize) -> Result<Dfa, String> {
+        self.dfas
+            .get(&handle)
+            .map(|parts| parts.clone().into_dfa())
+            .ok_or_else(|| no_such_dfa(handle))
+    }
+
+    /// Replaces the DFA with the given handle, which must be loaded
+    fn set(&mut self, handle: usize, dfa: &Dfa) {
+        self.dfas.insert(handle, DfaParts::new(dfa));
+    }
+}
+
+fn no_such_dfa(handle: usize) -> String {
+    format!("No DFA is loaded with handle {handle}")
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // The registry is consistent even if a call panicked while holding the lock
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs the body of a function and gives its value, or records its error as the last error and gives `on_error`. A
+/// panic in the body is caught (since unwinding into the caller is undefined behavior) and recorded as an error.
+fn call<T>(on_error: T, body: impl FnOnce() -> Result<T, String>) -> T {
+    panic::catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|payload| Err(panic_message(payload)))
+        .unwrap_or_else(|e| {
+            LAST_ERROR.with_borrow_mut(|last| *last = Some(e));
+            on_error
+        })
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Internal error: {message}")
+}
+
+/// Borrows a string passed by the caller as a `&str`
+///
+/// # Safety
+/// `s` must be `NULL` or point to a NUL-terminated string which is valid for the duration of the call
+unsafe fn borrow_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("The {what} is NULL"));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| format!("The {what} is not valid UTF-8: {e}"))
+}
+
+/// Hands a string over to the caller, who frees it with dandy_free_string()
+fn give_string(s: String) -> Result<*mut c_char, String> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|_| "The string contains a NUL byte".to_string())
+}
+
+/// Parses a DFA from its table and loads it, giving its handle, or `0` if the table couldn't be parsed.
+///
+/// # Safety
+/// `table` must be `NULL` or point to a NUL-terminated string which is valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn dandy_load_dfa(table: *const c_char) -> usize {
+    call(0, || {
+        let table = borrow_str(table, "table")?;
+        let dfa: Dfa = dandy::parser::dfa(table)
+            .map_err(|e| format!("Error parsing DFA: {e:?}"))?
+            .try_into()
+            .map_err(|e: dandy::dfa::DfaParseError| e.to_string())?;
+        registry().push(&dfa)
+    })
+}
+
+/// Deletes the DFA with the given handle, giving `0` if it was deleted and `-1` if there is no such DFA
+#[no_mangle]
+pub extern "C" fn dandy_delete_dfa(dfa: usize) -> c_int {
+    call(-1, || {
+        let mut registry = registry();
+        registry.dfas.remove(&dfa).ok_or_else(|| no_such_dfa(dfa))?;
+        registry.free.insert(dfa);
+        Ok(0)
+    })
+}
+
+/// Checks if two DFAs accept the same language, giving `1` if they do, `0` if they don't and `-1` if either handle
+/// doesn't refer to a loaded DFA. DFAs with different alphabets are never equivalent.
+#[no_mangle]
+pub extern "C" fn dandy_check_eq(dfa1: usize, dfa2: usize) -> c_int {
+    call(-1, || {
+        let registry = registry();
+        let (dfa1, dfa2) = (registry.get(dfa1)?, registry.get(dfa2)?);
+        Ok(c_int::from(dfa1.equivalent_to(&dfa2)))
+    })
+}
+
+/// Minimizes the DFA with the given handle in place, giving `0` if it was minimized and `-1` if there is no such DFA
+#[no_mangle]
+pub extern "C" fn dandy_minimize(dfa: usize) -> c_int {
+    call(-1, || {
+        let mut registry = registry();
+        let mut minimized = registry.get(dfa)?;
+        minimized.minimize();
+        registry.set(dfa, &minimized);
+        Ok(0)
+    })
+}
+
+/// Gives the table of the DFA with the given handle, which may be parsed again by dandy_load_dfa(), or `NULL` if
+/// there is no such DFA. The table must be freed with dandy_free_string().
+#[no_mangle]
+pub extern "C" fn dandy_to_table(dfa: usize) -> *mut c_char {
+    call(std::ptr::null_mut(), || {
+        give_string(registry().get(dfa)?.to_table())
+    })
+}
+
+/// Gives the message of the last error on the calling thread, or `NULL` if no call on this thread has failed. The
+/// message must be freed with dandy_free_string().
+#[no_mangle]
+pub extern "C" fn dandy_last_error() -> *mut c_char {
+    call(std::ptr::null_mut(), || {
+        Ok(LAST_ERROR
+            .with_borrow(Clone::clone)
+            .and_then(|e| give_string(e).ok())
+            .unwrap_or(std::ptr::null_mut()))
+    })
+}
+
+/// Frees a string returned by one of these functions. Passing `NULL` does nothing.
+///
+/// # Safety
+/// `s` must be `NULL` or a string returned by one of these functions which hasn't been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn dandy_free_string(s: *mut c_char) {
+    call((), || {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,131 @@
+//! Drives the C ABI through the raw externs, as a C program (or Python through `ctypes`) would
+
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::thread;
+
+// Links the library, whose functions are then called through the declarations below rather than as Rust functions
+extern crate dandy_ffi;
+
+extern "C" {
+    fn dandy_load_dfa(table: *const c_char) -> usize;
+    fn dandy_delete_dfa(dfa: usize) -> c_int;
+    fn dandy_check_eq(dfa1: usize, dfa2: usize) -> c_int;
+    fn dandy_minimize(dfa: usize) -> c_int;
+    fn dandy_to_table(dfa: usize) -> *mut c_char;
+    fn dandy_last_error() -> *mut c_char;
+    fn dandy_free_string(s: *mut c_char);
+}
+
+const ODD_AS: &str = "
+       a b
+-> e   o e
+ * o   e o";
+
+const ODD_AS_REDUNDANT: &str = "
+       a  b
+-> e1  o1 e2
+   e2  o2 e1
+ * o1  e1 o1
+ * o2  e2 o2";
+
+fn load(table: &str) -> usize {
+    let table = CString::new(table).unwrap();
+    unsafe { dandy_load_dfa(table.as_ptr()) }
+}
+
+/// Takes ownership of a string returned by the library, freeing it
+fn take_string(s: *mut c_char) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    let string = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+    unsafe { dandy_free_string(s) };
+    Some(string)
+}
+
+fn last_error() -> String {
+    take_string(unsafe { dandy_last_error() }).expect("some call failed")
+}
+
+#[test]
+fn load_minimize_and_compare() {
+    let odd = load(ODD_AS);
+    let redundant = load(ODD_AS_REDUNDANT);
+    assert_ne!(odd, 0);
+    assert_ne!(redundant, 0);
+    unsafe {
+        assert_eq!(dandy_check_eq(odd, redundant), 1);
+        assert_eq!(dandy_minimize(redundant), 0);
+        assert_eq!(dandy_check_eq(odd, redundant), 1);
+    }
+
+    let table = take_string(unsafe { dandy_to_table(redundant) }).unwrap();
+    assert_eq!(table.lines().count(), 3);
+    let reloaded = load(&table);
+    let even = load("   a b\n→ * e o e\n    o e o");
+    unsafe {
+        assert_eq!(dandy_check_eq(reloaded, redundant), 1);
+        assert_eq!(dandy_check_eq(even, odd), 0);
+        for dfa in [odd, redundant, reloaded, even] {
+            assert_eq!(dandy_delete_dfa(dfa), 0);
+        }
+    }
+}
+
+#[test]
+fn errors_are_reported() {
+    assert_eq!(load("   a b\n-> e o e\n  u →"), 0);
+    assert!(
+        last_error().starts_with("Error parsing DFA"),
+        "{}",
+        last_error()
+    );
+    assert_eq!(load("   a b\n-> e o"), 0);
+    assert!(
+        last_error().contains("transitions for state 'e'"),
+        "{}",
+        last_error()
+    );
+    assert_eq!(unsafe { dandy_load_dfa(std::ptr::null()) }, 0);
+    assert_eq!(last_error(), "The table is NULL");
+    let invalid = b"   a\n-> \xff s\0";
+    assert_eq!(unsafe { dandy_load_dfa(invalid.as_ptr().cast()) }, 0);
+    assert!(last_error().starts_with("The table is not valid UTF-8"));
+
+    let dfa = load(ODD_AS);
+    unsafe {
+        assert_eq!(dandy_delete_dfa(dfa), 0);
+        assert_eq!(dandy_delete_dfa(dfa), -1);
+        assert_eq!(last_error(), format!("No DFA is loaded with handle {dfa}"));
+        assert_eq!(dandy_minimize(dfa), -1);
+        assert_eq!(dandy_check_eq(dfa, dfa), -1);
+        assert!(dandy_to_table(dfa).is_null());
+        dandy_free_string(std::ptr::null_mut());
+    }
+}
+
+#[test]
+fn handles_are_shared_between_threads() {
+    let dfa = load(ODD_AS);
+    let loaded = thread::scope(|scope| {
+        let workers = (0..4)
+            .map(|_| scope.spawn(|| load(ODD_AS_REDUNDANT)))
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    for other in loaded {
+        unsafe {
+            assert_eq!(dandy_check_eq(dfa, other), 1);
+            assert_eq!(dandy_delete_dfa(other), 0);
+        }
+    }
+    // The error of another thread isn't the error of this one
+    thread::spawn(|| assert_eq!(load("   a b\n-> e o"), 0))
+        .join()
+        .unwrap();
+    assert!(unsafe { dandy_last_error() }.is_null());
+    assert_eq!(unsafe { dandy_delete_dfa(dfa) }, 0);
+}
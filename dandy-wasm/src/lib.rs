@@ -16,25 +16,149 @@
 //!     return table;
 //! }
 //! ```
+//!
+//! When many automata are to be loaded or checked at once, load_many() and batch_check_equivalence() can be used to
+//! do so in a single call, which avoids crossing the boundary between JavaScript and wasm for every automaton:
+//! ```js
+//! import init, { load_dfa, batch_check_equivalence, delete_dfa } from './web_bindings/dandy_wasm.js';
+//! function grade(reference, submissions) {
+//!     let key = load_dfa(reference);
+//!     // One object per submission: {index, status, message}, where status is one of
+//!     // "equivalent", "not_equivalent", "parse_error" and "invalid"
+//!     let results = batch_check_equivalence(key, submissions, "dfa");
+//!     delete_dfa(key);
+//!     return results;
+//! }
+//! ```
+//!
+//! A DFA can be stepped through one element at a time using an evaluator, which is stored like the automata and
+//! referenced by its own key. draw_dfa_with_evaluator() draws the DFA with the current state of the evaluator
+//! highlighted, which allows for an interactive visualization of the evaluation:
+//! ```js
+//! import init, {
+//!     load_dfa, dfa_evaluator, evaluator_step, draw_dfa_with_evaluator
+//! } from './web_bindings/dandy_wasm.js';
+//! let dfa = load_dfa(input);
+//! let evaluator = dfa_evaluator(dfa);
+//! draw_dfa_with_evaluator(dfa, evaluator, "canvas");
+//! function step(elem) {
+//!     let accepting = evaluator_step(evaluator, elem);
+//!     draw_dfa_with_evaluator(dfa, evaluator, "canvas");
+//!     return accepting;
+//! }
+//! ```
+//!
+//! An evaluator belongs to the DFA as it was when the evaluator was created. If the DFA is modified afterwards (such
+//! as by minimize_dfa()), evaluator_step() throws an error instead of stepping through the modified DFA, and a new
+//! evaluator has to be created.
+//!
+//! The draw functions clear the canvas before drawing, and scale its backing store by `window.devicePixelRatio`
+//! so that drawings are sharp on HiDPI screens. The CSS size of the canvas is kept, and is set as an inline style the
+//! first time the canvas is drawn on.
+//!
+//! The words accepted by an automaton or regex can be enumerated a page at a time using a words session, which
+//! keeps its own copy of the automaton, so that it isn't affected by later changes to it:
+//! ```js
+//! import init, { load_regex, words_start, words_next, words_delete } from './web_bindings/dandy_wasm.js';
+//! let regex = load_regex("a*b");
+//! let session = words_start(regex, "regex");
+//! let first = words_next(session, 20); // ["b", "ab", "aab", ...]
+//! let more = words_next(session, 20); // the next 20 words, or fewer (possibly none) when exhausted
+//! words_delete(session);
+//! ```
+//!
+//! To render the table of an automaton in some other way than as text (such as in an aligned HTML table),
+//! dfa_table_cells() and nfa_table_cells() give the cells of the table given by dfa_to_table() and nfa_to_table():
+//! ```js
+//! let rows = dfa_table_cells(dfa); // [["", "", "", "a", "b"], ["→", "", "s0", "s1", "s0"], ...]
+//! ```
+//!
+//! ## Keys
+//! Keys are shared between DFAs, NFAs, regexes, evaluators and words sessions, and are never `0`. When an automaton or
+//! regex is deleted (or consumed, as with regex_to_nfa()), its key is recycled, and the smallest recycled key is always
+//! handed out before any new key. This means that keys are only unique among the *currently loaded* automata: a key
+//! kept after calling delete_dfa()/delete_nfa()/delete_regex() may later refer to another automaton. Deleting a DFA
+//! deletes all evaluators of it as well, recycling their keys. Since keys are reused, the number of keys in use is
+//! bounded by the number of simultaneously loaded automata, and the key space (which is 32 bits when compiled for
+//! wasm32) can only be exhausted if more than `usize::MAX - 1` automata are loaded at the same time. In that case, the
+//! function creating the automaton throws a JavaScript error instead of returning a key.
+//!
+//! ## Persistence
+//! export_registry() serializes all loaded DFAs, NFAs and regexes together with their keys to a JSON string, which
+//! can be stored (such as in `localStorage`) and passed to import_registry() after a page reload to restore them
+//! under the same keys:
+//! ```js
+//! import init, { export_registry, import_registry } from './web_bindings/dandy_wasm.js';
+//! window.addEventListener("beforeunload", () => localStorage.setItem("registry", export_registry()));
+//! let saved = localStorage.getItem("registry");
+//! if (saved !== null) import_registry(saved);
+//! ```
+//!
+//! DFAs and NFAs are stored as their tables, starting with an [alphabet pragma](dandy::parser#alphabet-pragma), and
+//! regexes as their string representations, and are parsed again when imported. The priorities of the states of DFAs
+//! are stored next to their tables. Exporting throws an error if an automaton doesn't read back the same from its
+//! table, which is the case if a name contains a line break or an element of the alphabet contains whitespace.
+//! Evaluators and words sessions are not exported. Importing is atomic: if any entry can't be parsed, an error is
+//! thrown and nothing is changed, and otherwise the imported automata *replace* everything that is loaded (including
+//! evaluators and words sessions). Keys handed out after an import never collide with the imported ones.
 
 use dandy::dfa::parse::DfaParseError;
-use dandy::dfa::Dfa;
+use dandy::dfa::{Dfa, DfaEvaluator, DfaState};
 use dandy::nfa::parse::NfaParseError;
+use dandy::nfa::words::WordComponentIndices;
 use dandy::nfa::Nfa;
 use dandy::regex::Regex;
 use dandy_draw::canvas::CanvasDrawer;
+use dandy_draw::DrawOptions;
+use js_sys::{Array, Object, Reflect};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::ops::RangeFrom;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
 use wasm_bindgen::prelude::wasm_bindgen;
-use wasm_bindgen::JsCast;
+use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
 
+/// The key and version of the DFA an evaluator steps through, together with the state key of the evaluator
+type EvaluatorEntry = (usize, u64, Option<usize>);
+
 thread_local! {
     static DFA_MAP: RefCell<HashMap<usize, Dfa>> = RefCell::default();
     static NFA_MAP: RefCell<HashMap<usize, Nfa>> = RefCell::default();
     static REGEX_MAP: RefCell<HashMap<usize, Regex>> = RefCell::default();
-    static KEYGEN: RefCell<RangeFrom<usize>> = RefCell::from(1usize..);
+    static EVALUATOR_MAP: RefCell<HashMap<usize, EvaluatorEntry>> = RefCell::default();
+    static WORDS_MAP: RefCell<HashMap<usize, WordsSession>> = RefCell::default();
+    static KEYGEN: RefCell<KeyGen> = RefCell::new(KeyGen::new());
+}
+
+/// Generator of the keys used to reference loaded automata. Keys that have been freed are reused (smallest
+/// first) before any new key is generated.
+struct KeyGen {
+    next: Option<usize>,
+    free: BTreeSet<usize>,
+}
+
+impl KeyGen {
+    fn new() -> Self {
+        Self {
+            next: Some(1),
+            free: BTreeSet::new(),
+        }
+    }
+
+    fn gen(&mut self) -> Option<usize> {
+        if let Some(key) = self.free.pop_first() {
+            return Some(key);
+        }
+        let key = self.next?;
+        self.next = key.checked_add(1);
+        Some(key)
+    }
+
+    fn free(&mut self, key: usize) {
+        self.free.insert(key);
+    }
 }
 
 #[wasm_bindgen]
@@ -49,10 +173,19 @@ macro_rules! console_log {
 }
 
 #[wasm_bindgen]
-pub fn regex_to_nfa(regex: usize) -> Option<usize> {
-    REGEX_MAP
-        .with_borrow_mut(|map| map.remove(&regex))
-        .map(|regex| push_nfa(regex.to_nfa()))
+pub fn regex_to_nfa(regex: usize) -> Result<Option<usize>, String> {
+    let Some(removed) = REGEX_MAP.with_borrow_mut(|map| map.remove(&regex)) else {
+        return Ok(None);
+    };
+    free_key(regex);
+    push_nfa(removed.to_nfa()).map(Some)
+}
+
+/// Gives the [language fingerprint](Regex::language_fingerprint) of a regex (as a `BigInt`), which is equal for
+/// regexes matching the same words and stable across runs and platforms
+#[wasm_bindgen]
+pub fn regex_fingerprint(regex: usize) -> Option<u64> {
+    REGEX_MAP.with_borrow(|map| map.get(&regex).map(Regex::language_fingerprint))
 }
 
 #[wasm_bindgen]
@@ -60,30 +193,71 @@ pub fn minimize_dfa(dfa: usize) -> bool {
     DFA_MAP.with_borrow_mut(|map| map.get_mut(&dfa).map(|dfa| dfa.minimize()).is_some())
 }
 
+#[wasm_bindgen]
+pub fn dfa_state_count(dfa: usize) -> Option<usize> {
+    DFA_MAP.with_borrow(|map| map.get(&dfa).map(Dfa::states_count))
+}
+
+#[wasm_bindgen]
+pub fn dfa_alphabet_size(dfa: usize) -> Option<usize> {
+    DFA_MAP.with_borrow(|map| map.get(&dfa).map(Dfa::alphabet_size))
+}
+
+#[wasm_bindgen]
+pub fn nfa_has_epsilon_cycles(nfa: usize) -> Option<bool> {
+    NFA_MAP.with_borrow(|map| map.get(&nfa).map(Nfa::has_epsilon_cycles))
+}
+
+#[wasm_bindgen]
+pub fn minimize_nfa_via_dfa(nfa: usize) -> bool {
+    NFA_MAP.with_borrow_mut(|map| {
+        map.get_mut(&nfa)
+            .map(|nfa| nfa.minimize_via_dfa())
+            .is_some()
+    })
+}
+
+/// The color of the current state when drawing a DFA with draw_dfa_with_evaluator()
+const CURRENT_STATE_COLOR: [u8; 3] = [255, 200, 0];
+
 #[wasm_bindgen]
 pub fn draw_dfa(dfa: usize, canvas_id: &str) -> bool {
     let Some(dfa) = DFA_MAP.with_borrow(|map| map.get(&dfa).cloned()) else {
         return false;
     };
-
-    let document = web_sys::window().unwrap().document().unwrap();
-    let Some(canvas) = document.get_element_by_id(canvas_id) else {
+    let Some(mut drawer) = canvas_drawer(canvas_id) else {
         return false;
     };
+    dandy_draw::draw_dfa(&dfa, &mut drawer);
 
-    let canvas: Result<HtmlCanvasElement, _> = canvas.dyn_into();
-    let Ok(canvas) = canvas else {
+    true
+}
+
+/// Draws the DFA with the given key, with the current state of the given evaluator highlighted. If the evaluator
+/// has seen an element not in the alphabet, no state is highlighted. Returns false if there is no such DFA, no such
+/// evaluator, if the evaluator doesn't step through the DFA or if there is no canvas with the given id.
+#[wasm_bindgen]
+pub fn draw_dfa_with_evaluator(dfa_key: usize, eval_key: usize, canvas_id: &str) -> bool {
+    let Some(dfa) = DFA_MAP.with_borrow(|map| map.get(&dfa_key).cloned()) else {
+        return false;
+    };
+    let Some((evaluated, version, state)) =
+        EVALUATOR_MAP.with_borrow(|map| map.get(&eval_key).cloned())
+    else {
+        return false;
+    };
+    if evaluated != dfa_key || dfa.version() != version || dfa.evaluator_at(state).is_none() {
+        return false;
+    }
+    let Some(mut drawer) = canvas_drawer(canvas_id) else {
         return false;
     };
 
-    let context: CanvasRenderingContext2d = canvas
-        .get_context("2d")
-        .unwrap()
-        .unwrap()
-        .dyn_into()
-        .unwrap();
-    let mut drawer = CanvasDrawer::new(context);
-    dandy_draw::draw_dfa(&dfa, &mut drawer);
+    let mut opts = DrawOptions::for_dfa(&dfa);
+    if let Some(state) = state {
+        opts = opts.with_state_color(state, CURRENT_STATE_COLOR);
+    }
+    dandy_draw::draw_dfa_with_opts(&dfa, &mut drawer, opts);
 
     true
 }
@@ -93,27 +267,114 @@ pub fn draw_nfa(nfa: usize, canvas_id: &str) -> bool {
     let Some(nfa) = NFA_MAP.with_borrow(|map| map.get(&nfa).cloned()) else {
         return false;
     };
-
-    let document = web_sys::window().unwrap().document().unwrap();
-    let Some(canvas) = document.get_element_by_id(canvas_id) else {
+    let Some(mut drawer) = canvas_drawer(canvas_id) else {
         return false;
     };
+    dandy_draw::draw_nfa(&nfa, &mut drawer);
 
-    let canvas: Result<HtmlCanvasElement, _> = canvas.dyn_into();
-    let Ok(canvas) = canvas else {
-        return false;
-    };
+    true
+}
 
+fn canvas_drawer(canvas_id: &str) -> Option<CanvasDrawer> {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas: HtmlCanvasElement = document.get_element_by_id(canvas_id)?.dyn_into().ok()?;
     let context: CanvasRenderingContext2d = canvas
         .get_context("2d")
         .unwrap()
         .unwrap()
         .dyn_into()
         .unwrap();
-    let mut drawer = CanvasDrawer::new(context);
-    dandy_draw::draw_nfa(&nfa, &mut drawer);
+    Some(CanvasDrawer::new(context))
+}
 
-    true
+/// Creates an evaluator for the DFA with the given key, starting in the initial state. The evaluator is stored
+/// until delete_evaluator() is called, and its key is returned (or null if there is no such DFA).
+#[wasm_bindgen]
+pub fn dfa_evaluator(dfa: usize) -> Result<Option<usize>, String> {
+    let Some((version, state)) = DFA_MAP.with_borrow(|map| {
+        map.get(&dfa)
+            .map(|d| (d.version(), d.evaluator().state_key()))
+    }) else {
+        return Ok(None);
+    };
+    let key = gen_key()?;
+    EVALUATOR_MAP.with_borrow_mut(|map| {
+        map.insert(key, (dfa, version, state));
+    });
+    Ok(Some(key))
+}
+
+/// The error thrown when using an evaluator whose DFA has been modified (or replaced) since the evaluator was created
+const STALE_EVALUATOR: &str =
+    "Stale evaluator: the DFA has been modified since the evaluator was created";
+
+/// Resumes the evaluator with the given key and gives the result of `f` on it, or `Ok(None)` if there is no such
+/// evaluator, which is also the case once its DFA has been deleted. Gives the [STALE_EVALUATOR] error if the version of
+/// the DFA has changed since the evaluator was created, or if the key of the DFA now refers to another DFA.
+fn with_evaluator<T>(
+    evaluator: usize,
+    f: impl FnOnce(&mut DfaEvaluator) -> T,
+) -> Result<Option<T>, String> {
+    let Some((dfa, version, state)) = EVALUATOR_MAP.with_borrow(|map| map.get(&evaluator).cloned())
+    else {
+        return Ok(None);
+    };
+    DFA_MAP.with_borrow(|map| {
+        let Some(dfa) = map.get(&dfa) else {
+            return Ok(None);
+        };
+        if dfa.version() != version {
+            return Err(STALE_EVALUATOR.to_string());
+        }
+        // The version ensures that the state key was recorded for this DFA
+        let mut evaluator = dfa
+            .evaluator_at(state)
+            .ok_or_else(|| STALE_EVALUATOR.to_string())?;
+        Ok(Some(f(&mut evaluator)))
+    })
+}
+
+/// Steps the evaluator with the given key on the given element, and returns if the evaluator is in an accepting
+/// state afterwards. Returns null if there is no such evaluator or if its DFA has been deleted, and throws an error
+/// if its DFA has been modified (for example by minimize_dfa()) since the evaluator was created, rather than
+/// stepping through the modified DFA.
+#[wasm_bindgen]
+pub fn evaluator_step(evaluator: usize, elem: &str) -> Result<Option<bool>, String> {
+    let Some((state, accepting)) = with_evaluator(evaluator, |eval| {
+        eval.step(elem);
+        (eval.state_key(), eval.is_accepting())
+    })?
+    else {
+        return Ok(None);
+    };
+    EVALUATOR_MAP.with_borrow_mut(|map| {
+        if let Some((_, _, old)) = map.get_mut(&evaluator) {
+            *old = state;
+        }
+    });
+    Ok(Some(accepting))
+}
+
+/// Gets the name of the current state of the evaluator with the given key, or null if there is no such evaluator,
+/// if it has seen an element not in the alphabet or if its DFA has been deleted or modified since the evaluator was
+/// created
+#[wasm_bindgen]
+pub fn evaluator_current_state(evaluator: usize) -> Option<String> {
+    with_evaluator(evaluator, |eval| {
+        eval.current_state().map(|s| s.name().to_string())
+    })
+    .ok()
+    .flatten()
+    .flatten()
+}
+
+#[wasm_bindgen]
+pub fn delete_evaluator(evaluator: usize) -> bool {
+    let removed = EVALUATOR_MAP.with_borrow_mut(|map| map.remove(&evaluator).is_some());
+    if removed {
+        free_key(evaluator);
+    }
+    removed
 }
 
 #[wasm_bindgen]
@@ -131,17 +392,21 @@ pub fn check_nfa_eq(nfa1: usize, nfa2: usize) -> Option<bool> {
 }
 
 #[wasm_bindgen]
-pub fn dfa_to_nfa(dfa: usize) -> Option<usize> {
-    let dfa = DFA_MAP.with_borrow(|map| map.get(&dfa).cloned())?;
+pub fn dfa_to_nfa(dfa: usize) -> Result<Option<usize>, String> {
+    let Some(dfa) = DFA_MAP.with_borrow(|map| map.get(&dfa).cloned()) else {
+        return Ok(None);
+    };
     let nfa = dfa.to_nfa();
-    Some(push_nfa(nfa))
+    push_nfa(nfa).map(Some)
 }
 
 #[wasm_bindgen]
-pub fn nfa_to_dfa(nfa: usize) -> Option<usize> {
-    let nfa = NFA_MAP.with_borrow(|map| map.get(&nfa).cloned())?;
+pub fn nfa_to_dfa(nfa: usize) -> Result<Option<usize>, String> {
+    let Some(nfa) = NFA_MAP.with_borrow(|map| map.get(&nfa).cloned()) else {
+        return Ok(None);
+    };
     let dfa = nfa.to_dfa();
-    Some(push_dfa(dfa))
+    push_dfa(dfa).map(Some)
 }
 
 #[wasm_bindgen]
@@ -154,29 +419,82 @@ pub fn nfa_to_table(nfa: usize) -> Option<String> {
     NFA_MAP.with_borrow(|map| map.get(&nfa).map(Nfa::to_table))
 }
 
+/// Gets the cells of the table of the DFA (see dfa_to_table()) as an array of rows, each of which is an array of
+/// strings without any padding, so that the table can be rendered (and compared cell by cell) by the caller. Returns
+/// undefined if there is no DFA with the given key.
+#[wasm_bindgen]
+pub fn dfa_table_cells(dfa: usize) -> JsValue {
+    DFA_MAP
+        .with_borrow(|map| map.get(&dfa).map(Dfa::to_table_rows))
+        .map_or(JsValue::UNDEFINED, |rows| js_rows(&rows))
+}
+
+/// Gets the cells of the table of the NFA (see nfa_to_table()) as an array of rows, each of which is an array of
+/// strings without any padding, so that the table can be rendered (and compared cell by cell) by the caller. Returns
+/// undefined if there is no NFA with the given key.
+#[wasm_bindgen]
+pub fn nfa_table_cells(nfa: usize) -> JsValue {
+    NFA_MAP
+        .with_borrow(|map| map.get(&nfa).map(Nfa::to_table_rows))
+        .map_or(JsValue::UNDEFINED, |rows| js_rows(&rows))
+}
+
+fn js_rows(rows: &[Vec<String>]) -> JsValue {
+    rows.iter()
+        .map(|row| row.iter().map(JsValue::from).collect::<Array>())
+        .collect::<Array>()
+        .into()
+}
+
 #[wasm_bindgen]
 pub fn delete_regex(regex: usize) -> bool {
-    REGEX_MAP.with_borrow_mut(|map| map.remove(&regex).is_some())
+    let removed = REGEX_MAP.with_borrow_mut(|map| map.remove(&regex).is_some());
+    if removed {
+        free_key(regex);
+    }
+    removed
 }
 
 #[wasm_bindgen]
 pub fn load_regex(input: &str) -> Result<usize, String> {
     let regex: Regex =
         dandy::parser::regex(input).map_err(|e| format!("Error parsing Regex: {e:?}"))?;
-    Ok(push_regex(regex))
+    push_regex(regex)
 }
 
-fn push_regex(regex: Regex) -> usize {
-    let key = gen_key();
+/// Like [load_regex], but takes the raw bytes of a file, which may be encoded as UTF-8 or UTF-16 (see
+/// [dandy::parser::decode]). Throws an error naming the detected encoding if the file can't be decoded.
+#[wasm_bindgen]
+pub fn load_regex_bytes(bytes: &[u8]) -> Result<usize, String> {
+    load_regex(&decode(bytes)?)
+}
+
+fn push_regex(regex: Regex) -> Result<usize, String> {
+    let key = gen_key()?;
     REGEX_MAP.with_borrow_mut(|map| {
         map.insert(key, regex);
     });
-    key
+    Ok(key)
 }
 
+/// Deletes the DFA with the given key together with all evaluators of it, and returns if there was such a DFA
 #[wasm_bindgen]
 pub fn delete_dfa(dfa: usize) -> bool {
-    DFA_MAP.with_borrow_mut(|map| map.remove(&dfa).is_some())
+    let removed = DFA_MAP.with_borrow_mut(|map| map.remove(&dfa).is_some());
+    if removed {
+        free_key(dfa);
+        let mut evaluators = vec![];
+        EVALUATOR_MAP.with_borrow_mut(|map| {
+            map.retain(|&key, (evaluated, _, _)| {
+                if *evaluated == dfa {
+                    evaluators.push(key);
+                }
+                *evaluated != dfa
+            })
+        });
+        evaluators.into_iter().for_each(free_key);
+    }
+    removed
 }
 
 #[wasm_bindgen]
@@ -185,20 +503,31 @@ pub fn load_dfa(input: &str) -> Result<usize, String> {
         .map_err(|e| format!("Error parsing DFA: {e:?}"))?
         .try_into()
         .map_err(|e: DfaParseError| e.to_string())?;
-    Ok(push_dfa(dfa))
+    push_dfa(dfa)
+}
+
+/// Like [load_dfa], but takes the raw bytes of a file, which may be encoded as UTF-8 or UTF-16 (see
+/// [dandy::parser::decode]). Throws an error naming the detected encoding if the file can't be decoded.
+#[wasm_bindgen]
+pub fn load_dfa_bytes(bytes: &[u8]) -> Result<usize, String> {
+    load_dfa(&decode(bytes)?)
 }
 
-fn push_dfa(dfa: Dfa) -> usize {
-    let key = gen_key();
+fn push_dfa(dfa: Dfa) -> Result<usize, String> {
+    let key = gen_key()?;
     DFA_MAP.with_borrow_mut(|map| {
         map.insert(key, dfa);
     });
-    key
+    Ok(key)
 }
 
 #[wasm_bindgen]
 pub fn delete_nfa(nfa: usize) -> bool {
-    NFA_MAP.with_borrow_mut(|map| map.remove(&nfa).is_some())
+    let removed = NFA_MAP.with_borrow_mut(|map| map.remove(&nfa).is_some());
+    if removed {
+        free_key(nfa);
+    }
+    removed
 }
 
 #[wasm_bindgen]
@@ -207,17 +536,767 @@ pub fn load_nfa(input: &str) -> Result<usize, String> {
         .map_err(|e| format!("Error parsing NFA: {e:?}"))?
         .try_into()
         .map_err(|e: NfaParseError| e.to_string())?;
-    Ok(push_nfa(nfa))
+    push_nfa(nfa)
 }
 
-fn push_nfa(nfa: Nfa) -> usize {
-    let key = gen_key();
+/// Like [load_nfa], but takes the raw bytes of a file, which may be encoded as UTF-8 or UTF-16 (see
+/// [dandy::parser::decode]). Throws an error naming the detected encoding if the file can't be decoded.
+#[wasm_bindgen]
+pub fn load_nfa_bytes(bytes: &[u8]) -> Result<usize, String> {
+    load_nfa(&decode(bytes)?)
+}
+
+fn push_nfa(nfa: Nfa) -> Result<usize, String> {
+    let key = gen_key()?;
     NFA_MAP.with_borrow_mut(|map| {
         map.insert(key, nfa);
     });
-    key
+    Ok(key)
+}
+
+/// Starts enumerating the words accepted by the automaton or regex with the given key and kind (`"dfa"`, `"nfa"` or
+/// `"regex"`), and returns the key of the new words session. The words are visited in length-lexicographic order
+/// by words_next(), and the session is kept until words_delete() is called. Throws an error if the kind is unknown
+/// or if there is no automaton or regex of that kind with the given key.
+#[wasm_bindgen]
+pub fn words_start(key: usize, kind: &str) -> Result<usize, String> {
+    let nfa = match Kind::parse(kind)? {
+        Kind::Dfa => DFA_MAP.with_borrow(|map| map.get(&key).map(|dfa| dfa.clone().to_nfa())),
+        Kind::Nfa => NFA_MAP.with_borrow(|map| map.get(&key).cloned()),
+        Kind::Regex => {
+            REGEX_MAP.with_borrow(|map| map.get(&key).map(|regex| regex.clone().to_nfa()))
+        }
+    }
+    .ok_or_else(|| format!("No {kind} with key {key}"))?;
+    let session = gen_key()?;
+    WORDS_MAP.with_borrow_mut(|map| map.insert(session, WordsSession::new(nfa)));
+    Ok(session)
+}
+
+/// Gets the next `count` words of the words session with the given key, as an array of strings. The array is
+/// shorter than `count` (possibly empty) once all words have been visited. Throws an error if there is no words
+/// session with the given key.
+#[wasm_bindgen]
+pub fn words_next(session: usize, count: usize) -> Result<JsValue, String> {
+    let words = WORDS_MAP
+        .with_borrow_mut(|map| map.get_mut(&session).map(|session| session.next(count)))
+        .ok_or_else(|| format!("No words session with key {session}"))?;
+    Ok(words
+        .into_iter()
+        .map(JsValue::from)
+        .collect::<Array>()
+        .into())
+}
+
+#[wasm_bindgen]
+pub fn words_delete(session: usize) -> bool {
+    let removed = WORDS_MAP.with_borrow_mut(|map| map.remove(&session).is_some());
+    if removed {
+        free_key(session);
+    }
+    removed
+}
+
+/// The state of a words session. The word iterators borrow the NFA, so instead of keeping an iterator around, only
+/// the last word visited is kept, and the enumeration is resumed from it on every call.
+struct WordsSession {
+    nfa: Nfa,
+    last_word: Option<Vec<usize>>,
+    exhausted: bool,
+}
+
+impl WordsSession {
+    fn new(mut nfa: Nfa) -> Self {
+        nfa.remove_epsilon_moves();
+        Self {
+            nfa,
+            last_word: None,
+            exhausted: false,
+        }
+    }
+
+    fn next(&mut self, count: usize) -> Vec<String> {
+        if self.exhausted || count == 0 {
+            return vec![];
+        }
+        let indices = match &self.last_word {
+            None => WordComponentIndices::new(&self.nfa),
+            Some(last) => WordComponentIndices::resume_from(&self.nfa, last)
+                .expect("the last word should be accepted by the NFA"),
+        };
+        let words = indices.take(count).collect::<Vec<_>>();
+        if words.len() < count {
+            self.exhausted = true;
+        }
+        if let Some(last) = words.last() {
+            self.last_word = Some(last.clone());
+        }
+        let alphabet = self.nfa.alphabet();
+        words
+            .iter()
+            .map(|word| word.iter().map(|&idx| alphabet[idx].as_ref()).collect())
+            .collect()
+    }
+}
+
+/// Parses each of the given inputs (an array of strings) as an automaton or regex of the given kind (`"dfa"`,
+/// `"nfa"` or `"regex"`) and checks if it is equivalent to the automaton or regex with the given key. The parsed
+/// inputs are not stored. Returns an array with one object `{index, status, message}` per input, where `status` is
+/// `"equivalent"`, `"not_equivalent"`, `"parse_error"` or `"invalid"` (if the input could be parsed, but isn't a
+/// valid automaton), and `message` describes the error (or is `null` if there is none). Throws an error if there is
+/// no automaton or regex with the given key, if the kind is unknown or if the inputs aren't an array of strings.
+#[wasm_bindgen]
+pub fn batch_check_equivalence(
+    reference_key: usize,
+    inputs: JsValue,
+    kind: &str,
+) -> Result<JsValue, String> {
+    let kind = Kind::parse(kind)?;
+    let inputs = strings_from_js(inputs)?;
+    let reference = Reference::load(reference_key)
+        .ok_or_else(|| format!("No automaton or regex with key {reference_key}"))?;
+    let results = Array::new();
+    for (index, result) in reference.check_all(&inputs, kind).into_iter().enumerate() {
+        let (status, message) = match result {
+            Ok(true) => ("equivalent", None),
+            Ok(false) => ("not_equivalent", None),
+            Err(BatchError::Parse(message)) => ("parse_error", Some(message)),
+            Err(BatchError::Invalid(message)) => ("invalid", Some(message)),
+        };
+        let message = message.map_or(JsValue::NULL, JsValue::from);
+        results.push(&js_object(&[
+            ("index", JsValue::from(index)),
+            ("status", JsValue::from(status)),
+            ("message", message),
+        ]));
+    }
+    Ok(results.into())
+}
+
+/// Loads all the given inputs (an array of strings) as automata or regexes of the given kind (`"dfa"`, `"nfa"` or
+/// `"regex"`), just like load_dfa(), load_nfa() and load_regex() does. Returns an array with one object per input,
+/// either `{index, key}` if the input was loaded, or `{index, error}` if it wasn't. Throws an error if the kind is
+/// unknown or if the inputs aren't an array of strings.
+#[wasm_bindgen]
+pub fn load_many(inputs: JsValue, kind: &str) -> Result<JsValue, String> {
+    let kind = Kind::parse(kind)?;
+    let inputs = strings_from_js(inputs)?;
+    let results = Array::new();
+    for (index, result) in load_all(&inputs, kind).into_iter().enumerate() {
+        let entry = match result {
+            Ok(key) => ("key", JsValue::from(key)),
+            Err(error) => ("error", JsValue::from(error)),
+        };
+        results.push(&js_object(&[("index", JsValue::from(index)), entry]));
+    }
+    Ok(results.into())
+}
+
+fn load_all(inputs: &[String], kind: Kind) -> Vec<Result<usize, String>> {
+    inputs
+        .iter()
+        .map(|input| match kind {
+            Kind::Dfa => load_dfa(input),
+            Kind::Nfa => load_nfa(input),
+            Kind::Regex => load_regex(input),
+        })
+        .collect()
+}
+
+fn strings_from_js(inputs: JsValue) -> Result<Vec<String>, String> {
+    if !Array::is_array(&inputs) {
+        return Err("Inputs should be an array of strings".to_string());
+    }
+    Array::from(&inputs)
+        .iter()
+        .map(|input| {
+            input
+                .as_string()
+                .ok_or_else(|| "Inputs should be an array of strings".to_string())
+        })
+        .collect()
 }
 
-fn gen_key() -> usize {
-    KEYGEN.with_borrow_mut(|gen| gen.next().unwrap())
+fn js_object(entries: &[(&str, JsValue)]) -> JsValue {
+    let object = Object::new();
+    for (key, value) in entries {
+        Reflect::set(&object, &JsValue::from(*key), value).unwrap();
+    }
+    object.into()
+}
+
+/// The kind of the inputs to the batch functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Dfa,
+    Nfa,
+    Regex,
+}
+
+impl Kind {
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind {
+            "dfa" => Ok(Kind::Dfa),
+            "nfa" => Ok(Kind::Nfa),
+            "regex" => Ok(Kind::Regex),
+            _ => Err(format!(
+                "Unknown kind '{kind}', expected 'dfa', 'nfa' or 'regex'"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BatchError {
+    /// The input could not be parsed
+    Parse(String),
+    /// The input could be parsed, but isn't a valid automaton
+    Invalid(String),
+}
+
+/// An input parsed by the batch functions
+enum Parsed {
+    Dfa(Dfa),
+    Nfa(Nfa),
+}
+
+impl Parsed {
+    fn parse(input: &str, kind: Kind) -> Result<Self, BatchError> {
+        let parse_error = |e: nom::error::Error<&str>| BatchError::Parse(format!("{e:?}"));
+        match kind {
+            Kind::Dfa => dandy::parser::dfa(input)
+                .map_err(parse_error)?
+                .try_into()
+                .map(Parsed::Dfa)
+                .map_err(|e: DfaParseError| BatchError::Invalid(e.to_string())),
+            Kind::Nfa => dandy::parser::nfa(input)
+                .map_err(parse_error)?
+                .try_into()
+                .map(Parsed::Nfa)
+                .map_err(|e: NfaParseError| BatchError::Invalid(e.to_string())),
+            Kind::Regex => dandy::parser::regex(input)
+                .map(|regex| Parsed::Nfa(regex.to_nfa()))
+                .map_err(parse_error),
+        }
+    }
+}
+
+/// The automaton to compare to in batch_check_equivalence(). DFAs are compared to DFAs directly, while everything
+/// else is compared as NFAs, so the reference is converted to a NFA once instead of once per input.
+struct Reference {
+    dfa: Option<Dfa>,
+    nfa: Nfa,
+}
+
+impl Reference {
+    fn load(key: usize) -> Option<Self> {
+        if let Some(dfa) = DFA_MAP.with_borrow(|map| map.get(&key).cloned()) {
+            return Some(Self::from_dfa(dfa));
+        }
+        NFA_MAP
+            .with_borrow(|map| map.get(&key).cloned())
+            .or_else(|| REGEX_MAP.with_borrow(|map| map.get(&key).cloned().map(Regex::to_nfa)))
+            .map(|nfa| Self { dfa: None, nfa })
+    }
+
+    fn from_dfa(dfa: Dfa) -> Self {
+        Self {
+            nfa: dfa.clone().to_nfa(),
+            dfa: Some(dfa),
+        }
+    }
+
+    fn check_all(&self, inputs: &[String], kind: Kind) -> Vec<Result<bool, BatchError>> {
+        inputs
+            .iter()
+            .map(|input| {
+                Parsed::parse(input, kind).map(|parsed| match (&self.dfa, parsed) {
+                    (Some(reference), Parsed::Dfa(dfa)) => reference.equivalent_to(&dfa),
+                    (_, Parsed::Dfa(dfa)) => self.nfa.equivalent_to(&dfa.to_nfa()),
+                    (_, Parsed::Nfa(nfa)) => self.nfa.equivalent_to(&nfa),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The JSON representation of the loaded automata given by export_registry(). `next` and `free` is the state of the
+/// [KeyGen], where the keys of evaluators and words sessions (which aren't exported) are counted as free. When
+/// importing, the free keys are recomputed from `next` and the keys of the entries.
+#[derive(Debug, Serialize, Deserialize)]
+struct RegistryDump {
+    next: Option<usize>,
+    free: BTreeSet<usize>,
+    dfas: Vec<RegistryEntry>,
+    nfas: Vec<RegistryEntry>,
+    regexes: Vec<RegistryEntry>,
+}
+
+/// A loaded automaton or regex in a [RegistryDump], as its table with an alphabet pragma (for DFAs and NFAs) or
+/// string representation (for regexes). `priorities` has the priority of every state of a DFA, and is left out if no
+/// state has a priority.
+#[derive(Debug, Serialize, Deserialize)]
+struct RegistryEntry {
+    key: usize,
+    source: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    priorities: Vec<Option<u32>>,
+}
+
+/// Serializes all loaded DFAs, NFAs and regexes, together with their keys, to a JSON string which can be given to
+/// import_registry() to restore them (see the module documentation). The entries are ordered by key, so the same
+/// registry always gives the same string. Throws an error if an automaton or regex doesn't read back the same from
+/// its table or string representation.
+#[wasm_bindgen]
+pub fn export_registry() -> Result<String, String> {
+    fn entries<T>(
+        map: &HashMap<usize, T>,
+        entry: impl Fn(usize, &T) -> Result<RegistryEntry, String>,
+    ) -> Result<Vec<RegistryEntry>, String> {
+        let mut entries = map
+            .iter()
+            .map(|(&key, value)| entry(key, value))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_unstable_by_key(|entry| entry.key);
+        Ok(entries)
+    }
+
+    fn with_pragma(alphabet: &[Rc<str>], table: String) -> String {
+        let pragma = alphabet
+            .iter()
+            .map(|elem| format!(" {elem}"))
+            .collect::<String>();
+        format!("#! alphabet{pragma}\n{table}")
+    }
+
+    fn unreadable(kind: &str, key: usize) -> String {
+        format!(
+            "Error exporting registry: the {kind} with key {key} doesn't read back the same from its table (names \
+             can't contain line breaks, and elements of the alphabet can't contain whitespace)"
+        )
+    }
+
+    let dfa_entry = |key, dfa: &Dfa| {
+        let table = dfa.to_table();
+        let source = with_pragma(dfa.alphabet(), table.clone());
+        match parse_dfa_entry(&source) {
+            Ok(parsed) if parsed.to_table() == table => {}
+            _ => return Err(unreadable("DFA", key)),
+        }
+        let priorities = if dfa.states().iter().any(|state| state.priority().is_some()) {
+            dfa.states().iter().map(DfaState::priority).collect()
+        } else {
+            vec![]
+        };
+        Ok(RegistryEntry {
+            key,
+            source,
+            priorities,
+        })
+    };
+    let nfa_entry = |key, nfa: &Nfa| {
+        let table = nfa.to_table();
+        let source = with_pragma(nfa.alphabet(), table.clone());
+        match parse_nfa_entry(&source) {
+            Ok(parsed) if parsed.to_table() == table => {}
+            _ => return Err(unreadable("NFA", key)),
+        }
+        Ok(RegistryEntry {
+            key,
+            source,
+            priorities: vec![],
+        })
+    };
+    let regex_entry = |key, regex: &Regex| {
+        let source = regex.to_string();
+        match dandy::parser::regex(&source) {
+            Ok(parsed) if parsed.to_string() == source => {}
+            _ => return Err(unreadable("Regex", key)),
+        }
+        Ok(RegistryEntry {
+            key,
+            source,
+            priorities: vec![],
+        })
+    };
+
+    let (next, mut free) = KEYGEN.with_borrow(|gen| (gen.next, gen.free.clone()));
+    free.extend(EVALUATOR_MAP.with_borrow(|map| map.keys().copied().collect::<Vec<_>>()));
+    free.extend(WORDS_MAP.with_borrow(|map| map.keys().copied().collect::<Vec<_>>()));
+    let dump = RegistryDump {
+        next,
+        free,
+        dfas: DFA_MAP.with_borrow(|map| entries(map, dfa_entry))?,
+        nfas: NFA_MAP.with_borrow(|map| entries(map, nfa_entry))?,
+        regexes: REGEX_MAP.with_borrow(|map| entries(map, regex_entry))?,
+    };
+    Ok(serde_json::to_string(&dump).expect("the registry should be serializable"))
+}
+
+/// Parses the table of a DFA in a [RegistryEntry], which starts with an alphabet pragma
+fn parse_dfa_entry(source: &str) -> Result<Dfa, String> {
+    dandy::parser::dfa_file(source)
+        .map_err(|e| format!("{e:?}"))?
+        .try_into()
+        .map_err(|e: DfaParseError| e.to_string())
+}
+
+/// Parses the table of a NFA in a [RegistryEntry], which starts with an alphabet pragma
+fn parse_nfa_entry(source: &str) -> Result<Nfa, String> {
+    dandy::parser::nfa_file(source)
+        .map_err(|e| format!("{e:?}"))?
+        .try_into()
+        .map_err(|e: NfaParseError| e.to_string())
+}
+
+/// Restores the DFAs, NFAs and regexes serialized by export_registry() under the keys they had when exported. This
+/// replaces everything that is currently loaded, including evaluators and words sessions. Throws an error (without
+/// changing anything) if the JSON is malformed, if a key is used more than once or if any entry can't be parsed.
+#[wasm_bindgen]
+pub fn import_registry(json: &str) -> Result<(), String> {
+    let dump: RegistryDump =
+        serde_json::from_str(json).map_err(|e| format!("Error reading registry: {e}"))?;
+
+    // Every key has to be unique (among the entries and the free keys) and handed out before `next`
+    let mut used = BTreeSet::new();
+    let mut check_key = |key: usize| {
+        if key == 0 || dump.next.is_some_and(|next| key >= next) || !used.insert(key) {
+            Err(format!(
+                "Error importing registry: invalid or duplicate key {key}"
+            ))
+        } else {
+            Ok(())
+        }
+    };
+    for &key in &dump.free {
+        check_key(key)?;
+    }
+    let mut dfas = HashMap::new();
+    for entry in &dump.dfas {
+        check_key(entry.key)?;
+        let mut dfa = parse_dfa_entry(&entry.source)
+            .map_err(|e| format!("Error in DFA with key {}: {e}", entry.key))?;
+        if !entry.priorities.is_empty() {
+            if entry.priorities.len() != dfa.states().len() {
+                return Err(format!(
+                    "Error in DFA with key {}: {} priorities are given for {} states",
+                    entry.key,
+                    entry.priorities.len(),
+                    dfa.states().len()
+                ));
+            }
+            for (state, &priority) in entry.priorities.iter().enumerate() {
+                dfa.set_state_priority(state, priority);
+            }
+        }
+        dfas.insert(entry.key, dfa);
+    }
+    let mut nfas = HashMap::new();
+    for entry in &dump.nfas {
+        check_key(entry.key)?;
+        let nfa = parse_nfa_entry(&entry.source)
+            .map_err(|e| format!("Error in NFA with key {}: {e}", entry.key))?;
+        nfas.insert(entry.key, nfa);
+    }
+    let mut regexes = HashMap::new();
+    for entry in &dump.regexes {
+        check_key(entry.key)?;
+        let regex = dandy::parser::regex(&entry.source)
+            .map_err(|e| format!("Error parsing Regex with key {}: {e:?}", entry.key))?;
+        regexes.insert(entry.key, regex);
+    }
+
+    // Every key handed out before `next` which isn't used by an entry is free, even if it is missing from `free`
+    let free = (1..=usize::MAX)
+        .take_while(|&key| dump.next.is_none_or(|next| key < next))
+        .filter(|key| {
+            !dfas.contains_key(key) && !nfas.contains_key(key) && !regexes.contains_key(key)
+        })
+        .collect();
+    clear_all();
+    DFA_MAP.set(dfas);
+    NFA_MAP.set(nfas);
+    REGEX_MAP.set(regexes);
+    KEYGEN.set(KeyGen {
+        next: dump.next,
+        free,
+    });
+    Ok(())
+}
+
+/// Deletes all loaded automata, regexes, evaluators and words sessions, and starts handing out keys from `1` again
+#[wasm_bindgen]
+pub fn clear_all() {
+    DFA_MAP.take();
+    NFA_MAP.take();
+    REGEX_MAP.take();
+    EVALUATOR_MAP.take();
+    WORDS_MAP.take();
+    KEYGEN.set(KeyGen::new());
+}
+
+/// Generates a key not used by any loaded automaton, or returns an error if all keys are in use
+fn gen_key() -> Result<usize, String> {
+    KEYGEN.with_borrow_mut(KeyGen::gen).ok_or_else(|| {
+        "No more keys available, delete some automata before loading new ones".to_string()
+    })
+}
+
+fn decode(bytes: &[u8]) -> Result<Cow<'_, str>, String> {
+    dandy::parser::decode(bytes)
+        .map(|(text, _)| text)
+        .map_err(|e| e.to_string())
+}
+
+fn free_key(key: usize) {
+    KEYGEN.with_borrow_mut(|gen| gen.free(key));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ODD_AS: &str = "
+           a b
+    -> e   o e
+     * o   e o";
+
+    fn strings(inputs: &[&str]) -> Vec<String> {
+        inputs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn batch_check_with_invalid_entry() {
+        let reference =
+            Reference::from_dfa(dandy::parser::dfa(ODD_AS).unwrap().try_into().unwrap());
+        let inputs = strings(&[
+            "   a b\n-> * x y x\n     y x y", // even number of a:s
+            ODD_AS,
+            "   a b",                  // no states
+            "   a b\n* x y x\n y x y", // no initial state
+        ]);
+        let results = reference.check_all(&inputs, Kind::Dfa);
+        assert_eq!(results[0], Ok(false));
+        assert_eq!(results[1], Ok(true));
+        assert!(matches!(results[2], Err(BatchError::Parse(_))));
+        assert!(matches!(results[3], Err(BatchError::Invalid(_))));
+
+        // Regexes are compared as NFAs
+        let inputs = strings(&["b*a(b|ab*a)*", "(a|b)*", "(a"]);
+        let results = reference.check_all(&inputs, Kind::Regex);
+        assert_eq!(results[0], Ok(true));
+        assert_eq!(results[1], Ok(false));
+        assert!(matches!(results[2], Err(BatchError::Parse(_))));
+    }
+
+    #[test]
+    fn load_many_mixed_batch() {
+        let inputs = strings(&[ODD_AS, "   a b\n* x y x\n y x y", ODD_AS]);
+        let results = load_all(&inputs, Kind::Dfa);
+        assert!(results[1].is_err());
+        let (first, second) = (results[0].clone().unwrap(), results[2].clone().unwrap());
+        assert_ne!(first, second);
+        assert_eq!(check_dfa_eq(first, second), Some(true));
+        assert!(delete_dfa(first));
+        assert!(delete_dfa(second));
+    }
+
+    #[test]
+    fn load_dfa_bytes_in_each_encoding() {
+        let utf16 = |bom: &[u8], le: bool| {
+            let units = ODD_AS.encode_utf16().flat_map(move |unit| {
+                if le {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                }
+            });
+            bom.iter().copied().chain(units).collect::<Vec<u8>>()
+        };
+        let inputs = [
+            ODD_AS.as_bytes().to_vec(),
+            [&[0xEF, 0xBB, 0xBF], ODD_AS.as_bytes()].concat(),
+            utf16(&[0xFF, 0xFE], true),
+            utf16(&[0xFE, 0xFF], false),
+            utf16(&[], true),
+        ];
+        let reference = load_dfa(ODD_AS).unwrap();
+        for input in inputs {
+            let key = load_dfa_bytes(&input).unwrap();
+            assert_eq!(check_dfa_eq(reference, key), Some(true));
+            assert!(delete_dfa(key));
+        }
+        assert!(delete_dfa(reference));
+
+        // 'ö' in Latin-1 is not valid UTF-8
+        let error = load_dfa_bytes(b"   a\n-> * s\xF6 s\xF6").unwrap_err();
+        assert!(error.contains("Latin-1"), "{error}");
+    }
+
+    #[test]
+    fn evaluator_steps_through_dfa() {
+        let dfa = load_dfa(ODD_AS).unwrap();
+        let evaluator = dfa_evaluator(dfa).unwrap().unwrap();
+        assert_eq!(evaluator_current_state(evaluator).as_deref(), Some("e"));
+        assert_eq!(evaluator_step(evaluator, "a"), Ok(Some(true)));
+        assert_eq!(evaluator_step(evaluator, "b"), Ok(Some(true)));
+        assert_eq!(evaluator_current_state(evaluator).as_deref(), Some("o"));
+        assert_eq!(evaluator_step(evaluator, "c"), Ok(Some(false)));
+        assert_eq!(evaluator_current_state(evaluator), None);
+
+        assert!(delete_evaluator(evaluator));
+        assert!(!delete_evaluator(evaluator));
+
+        // Deleting the DFA deletes its evaluators as well
+        let other = dfa_evaluator(dfa).unwrap().unwrap();
+        assert!(delete_dfa(dfa));
+        assert_eq!(evaluator_step(other, "a"), Ok(None));
+        assert_eq!(dfa_evaluator(dfa), Ok(None));
+        assert!(!delete_evaluator(other));
+    }
+
+    #[test]
+    fn evaluator_of_modified_dfa_is_stale() {
+        let dfa = load_dfa("   a\n-> e o\n * o e\n   u u").unwrap();
+        let evaluator = dfa_evaluator(dfa).unwrap().unwrap();
+        assert_eq!(evaluator_step(evaluator, "a"), Ok(Some(true)));
+        assert!(minimize_dfa(dfa));
+        assert_eq!(
+            evaluator_step(evaluator, "a"),
+            Err(STALE_EVALUATOR.to_string())
+        );
+        assert_eq!(evaluator_current_state(evaluator), None);
+        // A new evaluator steps through the modified DFA
+        let fresh = dfa_evaluator(dfa).unwrap().unwrap();
+        assert_eq!(evaluator_step(fresh, "a"), Ok(Some(true)));
+
+        // The evaluators are deleted with the DFA, so they never step through another DFA getting the same key
+        assert!(delete_dfa(dfa));
+        let other = load_dfa("   a b\n-> * s s s").unwrap();
+        assert_eq!(other, dfa);
+        assert_eq!(evaluator_step(fresh, "a"), Ok(None));
+        assert!(delete_dfa(other));
+        assert!(!delete_evaluator(evaluator));
+        assert!(!delete_evaluator(fresh));
+    }
+
+    #[test]
+    fn words_session_pages() {
+        let regex = load_regex("(a|b)*c").unwrap();
+        let nfa = regex_to_nfa(regex).unwrap().unwrap();
+        let all = WordsSession::new(NFA_MAP.with_borrow(|map| map[&nfa].clone())).next(30);
+        assert_eq!(all.len(), 30);
+        assert_eq!(&all[..4], ["c", "ac", "bc", "aac"]);
+
+        let mut session = WordsSession::new(NFA_MAP.with_borrow(|map| map[&nfa].clone()));
+        let paged = (0..3).flat_map(|_| session.next(10)).collect::<Vec<_>>();
+        assert_eq!(paged, all);
+        assert!(delete_nfa(nfa));
+
+        // A finite language is exhausted after the last page
+        let dfa = load_dfa("   a b\n-> s t u\n * t u u\n   u u u").unwrap();
+        let key = words_start(dfa, "dfa").unwrap();
+        let mut session = WORDS_MAP.with_borrow_mut(|map| map.remove(&key)).unwrap();
+        assert_eq!(session.next(10), ["a"]);
+        assert!(session.next(10).is_empty());
+        free_key(key);
+        assert!(delete_dfa(dfa));
+
+        assert!(words_start(dfa, "dfa").is_err());
+        assert!(words_start(dfa, "automaton").is_err());
+    }
+
+    #[test]
+    fn registry_survives_export_and_import() {
+        let first = load_dfa(ODD_AS).unwrap();
+        let nfa = load_nfa("   a b\n-> s {s t} {s}\n * t {} {}").unwrap();
+        let second = load_dfa("   a b\n-> * x y x\n     y x y").unwrap();
+        let regex = load_regex("a*(b|ε)").unwrap();
+        let evaluator = dfa_evaluator(first).unwrap().unwrap();
+        assert!(delete_nfa(nfa));
+        let minimized = load_dfa(&dfa_to_table(first).unwrap()).unwrap();
+        assert_eq!(minimized, nfa);
+        assert!(minimize_dfa(minimized));
+        DFA_MAP.with_borrow_mut(|map| map.get_mut(&second).unwrap().set_state_priority(0, Some(2)));
+
+        let json = export_registry().unwrap();
+        clear_all();
+        assert_eq!(check_dfa_eq(first, minimized), None);
+        import_registry(&json).unwrap();
+        assert_eq!(export_registry(), Ok(json.clone()));
+        let priorities = DFA_MAP.with_borrow(|map| {
+            let states = map[&second].states();
+            states.iter().map(DfaState::priority).collect::<Vec<_>>()
+        });
+        assert_eq!(priorities, [Some(2), None]);
+
+        assert_eq!(check_dfa_eq(first, minimized), Some(true));
+        assert_eq!(check_dfa_eq(first, second), Some(false));
+        assert_eq!(
+            regex_fingerprint(regex),
+            dandy::parser::regex("a*(ε|b)")
+                .ok()
+                .as_ref()
+                .map(Regex::language_fingerprint)
+        );
+        // Evaluators aren't exported, so their keys are free to be handed out again
+        assert_eq!(dfa_evaluator(first), Ok(Some(evaluator)));
+        assert_eq!(load_regex("a").unwrap(), 6);
+
+        // A failing import doesn't change anything
+        let mut broken: RegistryDump = serde_json::from_str(&json).unwrap();
+        broken.regexes[0].source.push('(');
+        let broken = serde_json::to_string(&broken).unwrap();
+        assert!(import_registry(&broken).is_err());
+        let duplicate = json.replace(&format!("\"key\":{second}"), &format!("\"key\":{first}"));
+        assert!(import_registry(&duplicate).is_err());
+        assert!(import_registry("{}").is_err());
+        assert_eq!(check_dfa_eq(first, minimized), Some(true));
+        assert_eq!(dfa_to_table(6), None);
+
+        // Keys missing from the free keys are still handed out again
+        let mut leaky: RegistryDump = serde_json::from_str(&json).unwrap();
+        leaky.free.clear();
+        import_registry(&serde_json::to_string(&leaky).unwrap()).unwrap();
+        assert_eq!(export_registry(), Ok(json));
+    }
+
+    #[test]
+    fn export_fails_for_elements_which_cant_be_in_a_pragma() {
+        let dfa = load_dfa("   \"a b\"\n-> * s s").unwrap();
+        assert_eq!(
+            export_registry(),
+            Err(format!(
+                "Error exporting registry: the DFA with key {dfa} doesn't read back the same from its table (names \
+                 can't contain line breaks, and elements of the alphabet can't contain whitespace)"
+            ))
+        );
+        assert!(delete_dfa(dfa));
+        assert!(export_registry().is_ok());
+    }
+
+    #[test]
+    fn keygen_reuses_smallest_freed_key() {
+        let mut keygen = KeyGen::new();
+        assert_eq!(
+            (1..=4).map(|_| keygen.gen()).collect::<Vec<_>>(),
+            [Some(1), Some(2), Some(3), Some(4)]
+        );
+        keygen.free(3);
+        keygen.free(2);
+        assert_eq!(keygen.gen(), Some(2));
+        assert_eq!(keygen.gen(), Some(3));
+        assert_eq!(keygen.gen(), Some(5));
+    }
+
+    #[test]
+    fn keygen_does_not_overflow() {
+        let mut keygen = KeyGen::new();
+        keygen.next = Some(usize::MAX);
+        assert_eq!(keygen.gen(), Some(usize::MAX));
+        assert_eq!(keygen.gen(), None);
+        keygen.free(7);
+        assert_eq!(keygen.gen(), Some(7));
+        assert_eq!(keygen.gen(), None);
+    }
 }
@@ -0,0 +1,94 @@
+//! Tests running in a browser, with `wasm-pack test --headless --firefox` (or `--chrome`)
+#![cfg(target_arch = "wasm32")]
+
+use dandy_draw::canvas::CanvasDrawer;
+use dandy_wasm::{
+    delete_dfa, delete_regex, dfa_table_cells, dfa_to_table, draw_dfa, load_dfa, load_regex,
+    regex_fingerprint,
+};
+use js_sys::Array;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlCanvasElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const DFA: &str = "
+       a  b
+-> s0 s1 s0
+ * s1 s1 s0";
+
+/// Adds a canvas of the given size to the page
+fn canvas(id: &str, width: u32, height: u32) -> HtmlCanvasElement {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas: HtmlCanvasElement = document
+        .create_element("canvas")
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    canvas.set_id(id);
+    canvas.set_width(width);
+    canvas.set_height(height);
+    document.body().unwrap().append_child(&canvas).unwrap();
+    canvas
+}
+
+#[wasm_bindgen_test]
+fn draw_dfa_scales_canvas_by_pixel_ratio() {
+    let canvas = canvas("draw-dfa", 200, 100);
+    let ratio = web_sys::window().unwrap().device_pixel_ratio();
+    let dfa = load_dfa(DFA).unwrap();
+    assert!(draw_dfa(dfa, "draw-dfa"));
+    assert_eq!(canvas.width(), (200.0 * ratio).round() as u32);
+    assert_eq!(canvas.height(), (100.0 * ratio).round() as u32);
+    // Drawing again keeps the size, rather than scaling the already scaled canvas
+    assert!(draw_dfa(dfa, "draw-dfa"));
+    assert_eq!(canvas.width(), (200.0 * ratio).round() as u32);
+    assert!(delete_dfa(dfa));
+}
+
+#[wasm_bindgen_test]
+fn canvas_drawer_with_pixel_ratio_two() {
+    let canvas = canvas("pixel-ratio-two", 200, 100);
+    let dfa = dandy::parser::dfa(DFA).unwrap().try_into().unwrap();
+    let mut drawer = CanvasDrawer::from_element(canvas.clone())
+        .unwrap()
+        .with_pixel_ratio(2.0);
+    dandy_draw::draw_dfa(&dfa, &mut drawer);
+    dandy_draw::draw_dfa(&dfa, &mut drawer);
+    assert_eq!((canvas.width(), canvas.height()), (400, 200));
+    let style = canvas.style();
+    assert_eq!(style.get_property_value("width").unwrap(), "200px");
+    assert_eq!(style.get_property_value("height").unwrap(), "100px");
+}
+
+#[wasm_bindgen_test]
+fn dfa_table_cells_are_the_cells_of_the_table() {
+    let dfa = load_dfa(DFA).unwrap();
+    let table = dfa_to_table(dfa).unwrap();
+    let rows = Array::from(&dfa_table_cells(dfa));
+    assert_eq!(rows.length() as usize, table.lines().count());
+    for (line, row) in table.lines().zip(rows.iter()) {
+        let cells = Array::from(&row)
+            .iter()
+            .map(|cell| cell.as_string().unwrap())
+            .filter(|cell| !cell.is_empty())
+            .collect::<Vec<_>>();
+        assert_eq!(cells, line.split_whitespace().collect::<Vec<_>>());
+    }
+    assert!(dfa_table_cells(dfa + 1).is_undefined());
+    assert!(delete_dfa(dfa));
+}
+
+#[wasm_bindgen_test]
+fn equivalent_regexes_have_equal_fingerprints() {
+    let regex1 = load_regex("(ab)*a").unwrap();
+    let regex2 = load_regex("a(ba)*").unwrap();
+    let regex3 = load_regex("(ab)*").unwrap();
+    assert_eq!(regex_fingerprint(regex1), regex_fingerprint(regex2));
+    assert_ne!(regex_fingerprint(regex1), regex_fingerprint(regex3));
+    for regex in [regex1, regex2, regex3] {
+        assert!(delete_regex(regex));
+    }
+    assert_eq!(regex_fingerprint(regex1), None);
+}
@@ -0,0 +1,62 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use dandy::batch;
+use dandy::dfa::Dfa;
+use dandy::parser;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Generates a random DFA with `n` states over 3 symbols, like a student submission (though larger)
+fn random_dfa(rng: &mut StdRng, n: usize) -> Dfa {
+    let mut table = String::from("a b c\n");
+    for i in 0..n {
+        let prefix = match (i, rng.gen_bool(0.5)) {
+            (0, true) => "-> *",
+            (0, false) => "->",
+            (_, true) => "*",
+            (_, false) => "",
+        };
+        table += &format!("{prefix} q{i}");
+        for _ in 0..3 {
+            table += &format!(" q{}", rng.gen_range(0..n));
+        }
+        table += "\n";
+    }
+    parser::dfa(&table).unwrap().try_into().unwrap()
+}
+
+/// Minimizes and compares 500 random DFAs with 200 states each on 1 and 4 threads, where half of the compared DFAs
+/// are equivalent to the reference
+pub fn batch_minimize_and_compare(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(1213);
+    let dfas = (0..500)
+        .map(|_| random_dfa(&mut rng, 200))
+        .collect::<Vec<_>>();
+    let reference = random_dfa(&mut rng, 200);
+    // Comparing equivalent DFAs explores all pairs of states, while others are usually told apart quickly
+    let candidates = dfas
+        .iter()
+        .enumerate()
+        .map(|(i, dfa)| {
+            if i % 2 == 0 {
+                reference.clone()
+            } else {
+                dfa.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    for jobs in [1, 4] {
+        c.bench_function(&format!("minimize 500 dfas ({jobs} threads)"), |b| {
+            b.iter_batched(
+                || dfas.clone(),
+                |dfas| batch::minimize_all_with_jobs(dfas, jobs),
+                BatchSize::LargeInput,
+            )
+        });
+        c.bench_function(&format!("compare 500 dfas ({jobs} threads)"), |b| {
+            b.iter(|| batch::equivalence_all_with_jobs(&reference, &candidates, jobs))
+        });
+    }
+}
+
+criterion_group!(benches, batch_minimize_and_compare);
+criterion_main!(benches);
@@ -1,38 +1,41 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use dandy::dfa::Dfa;
+use dandy::io::{self, Automaton, AutomatonType};
+use dandy::nfa::Nfa;
 use dandy::parser;
 use lazy_static::lazy_static;
 use proptest::prelude::*;
 use proptest::strategy::ValueTree;
 use proptest::test_runner::TestRunner;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use regex::Regex as LibRegex;
-use std::fs;
-use std::path::Path;
+use unicode_segmentation::UnicodeSegmentation;
 
 lazy_static! {
-    static ref DFAS: Box<[String]> = {
-        (0..10)
-            .map(|num| {
-                let filename = format!("benches/example_dfas/dfa{num}.txt");
-                let path = Path::new(&filename);
-                fs::read_to_string(path).unwrap()
-            })
-            .collect()
-    };
     static ref REGEXES: Box<[String]> = {
         (0..10)
             .map(|num| {
                 let filename = format!("benches/example_regexes/regex{num}.txt");
-                let path = Path::new(&filename);
-                fs::read_to_string(path).unwrap()
+                io::read_text(filename).unwrap_or_else(|e| panic!("{e}"))
             })
             .collect()
     };
 }
 
+/// Reads one of the example DFAs, panicking with the path and line of the problem if it can't be read
+fn example_dfa(num: usize) -> Dfa {
+    let filename = format!("benches/example_dfas/dfa{num}.txt");
+    match io::read_automaton(filename, AutomatonType::Dfa) {
+        Ok(Automaton::Dfa(dfa)) => dfa,
+        Ok(_) => unreachable!("Read as a DFA"),
+        Err(e) => panic!("{e}"),
+    }
+}
+
 pub fn powerset(c: &mut Criterion) {
-    let dfa1: Dfa = parser::dfa(&DFAS[0]).unwrap().try_into().unwrap();
-    let dfa2: Dfa = parser::dfa(&DFAS[1]).unwrap().try_into().unwrap();
+    let dfa1 = example_dfa(0);
+    let dfa2 = example_dfa(1);
     c.bench_function("union", |b| b.iter(|| dfa1.union(black_box(&dfa2))));
     c.bench_function("intersection", |b| {
         b.iter(|| dfa1.intersection(black_box(&dfa2)))
@@ -46,8 +49,8 @@ pub fn powerset(c: &mut Criterion) {
 }
 
 pub fn equivalence_check(c: &mut Criterion) {
-    let dfa1: Dfa = parser::dfa(&DFAS[0]).unwrap().try_into().unwrap();
-    let dfa2: Dfa = parser::dfa(&DFAS[1]).unwrap().try_into().unwrap();
+    let dfa1 = example_dfa(0);
+    let dfa2 = example_dfa(1);
     c.bench_function("equivalence check", |b| {
         b.iter(|| dfa1.equivalent_to(black_box(&dfa2)))
     });
@@ -64,6 +67,24 @@ pub fn regex_compile(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("dandy regex compile (minimal)", |b| {
+        b.iter(|| {
+            let input = black_box(&REGEXES[6]);
+            let regex = parser::regex(input).unwrap();
+            regex.to_min_dfa()
+        })
+    });
+
+    c.bench_function("dandy regex compile (naive minimal)", |b| {
+        b.iter(|| {
+            let input = black_box(&REGEXES[6]);
+            let regex = parser::regex(input).unwrap();
+            let mut dfa = regex.to_nfa().to_dfa();
+            dfa.minimize();
+            dfa
+        })
+    });
+
     c.bench_function("library regex compile", |b| {
         b.iter(|| {
             let input = black_box(&REGEXES[6]);
@@ -91,11 +112,222 @@ pub fn regex_check(c: &mut Criterion) {
     });
 }
 
+/// Finds the first words of a 100-state NFA whose shortest word is 99 symbols long, where most of the time is spent
+/// computing powers of the adjacency matrix. Run this with and without `--features fast-matrix` to compare the
+/// bitpacked matrices with the `nalgebra` ones.
+pub fn nfa_words(c: &mut Criterion) {
+    let n = 100;
+    let mut table = String::from("a b\n");
+    for i in 0..n {
+        let prefix = match i {
+            0 => "->",
+            _ if i == n - 1 => "*",
+            _ => "",
+        };
+        let next = (i + 1).min(n - 1);
+        table += &format!("{prefix} q{i} {{q{next}}} {{q{i} q{next}}}\n");
+    }
+    let nfa: Nfa = parser::nfa(&table).unwrap().try_into().unwrap();
+    c.bench_function("nfa words (100 states)", |b| {
+        b.iter(|| black_box(&nfa).words().take(10).count())
+    });
+}
+
+/// Compares [Nfa::equivalent_to] with [Nfa::equivalent_to_antichain] on the NFAs for `(a|b)*a(a|b)^n` ("the n:th
+/// last symbol is an a"), whose DFAs have 2^(n+1) states, against the same language written differently
+pub fn nfa_equivalence(c: &mut Criterion) {
+    let n = 10;
+    let nfa1 = parser::regex(&format!("(a|b)*a{}", "(a|b)".repeat(n)))
+        .unwrap()
+        .to_nfa();
+    let nfa2 = parser::regex(&format!("(b|a)*a{}", "(b|a)".repeat(n)))
+        .unwrap()
+        .to_nfa();
+    c.bench_function("nfa equivalence (classic)", |b| {
+        b.iter(|| nfa1.equivalent_to(black_box(&nfa2)))
+    });
+    c.bench_function("nfa equivalence (antichain)", |b| {
+        b.iter(|| nfa1.equivalent_to_antichain(black_box(&nfa2)))
+    });
+}
+
+/// Removes the epsilon moves of a NFA with 1000 states, whose initial state needs to be replaced by a new state.
+/// The states are named such that the new state gets the first free number as its name, which is the 1000th number
+/// tried.
+pub fn nfa_remove_epsilon_moves(c: &mut Criterion) {
+    let n = 1000;
+    let mut table = String::from("ε a\n-> s_new {0} {s_new}\n");
+    for i in 0..n - 1 {
+        let prefix = if i == n - 2 { "*" } else { "" };
+        let next = (i + 1).min(n - 2);
+        table += &format!("{prefix} {i} {{}} {{{next}}}\n");
+    }
+    let nfa: Nfa = parser::nfa(&table).unwrap().try_into().unwrap();
+    c.bench_function("nfa remove epsilon moves (1000 states)", |b| {
+        b.iter_batched(
+            || nfa.clone(),
+            |mut nfa| nfa.remove_epsilon_moves(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Constructs the union of 50 small NFAs (for the words `a^i b`) at once with [Nfa::union_all], compared to folding
+/// [Nfa::union], which grows the states of the union and renames them when the names of the states collide
+pub fn nfa_union_all(c: &mut Criterion) {
+    let nfas = (0..50)
+        .map(|i| {
+            parser::regex(&format!("{}b", "a".repeat(i)))
+                .unwrap()
+                .to_nfa_with_alphabet(&["a", "b"])
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    c.bench_function("nfa union all (50 nfas)", |b| {
+        b.iter_batched(|| nfas.clone(), Nfa::union_all, BatchSize::SmallInput)
+    });
+    c.bench_function("nfa union fold (50 nfas)", |b| {
+        b.iter_batched(
+            || nfas.clone(),
+            |nfas| {
+                nfas.into_iter()
+                    .reduce(|union, nfa| union.union(nfa).unwrap())
+                    .unwrap()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Intersects the DFAs for `(a|b)*a(a|b)^6` and `(a|b)*b(a|b)^6` over an alphabet with 10 more symbols which always
+/// lead to the dead state, before and after [pruning](Dfa::prune_symbols) those symbols. Without pruning, the product
+/// also explores the pairs of the dead state and every state of the other DFA.
+pub fn product_after_pruning(c: &mut Criterion) {
+    let alphabet = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+    let dfa = |regex: &str| {
+        parser::regex(regex)
+            .unwrap()
+            .to_nfa_with_alphabet(&alphabet)
+            .unwrap()
+            .to_dfa()
+    };
+    let dfa1 = dfa(&format!("(a|b)*a{}", "(a|b)".repeat(6)));
+    let dfa2 = dfa(&format!("(a|b)*b{}", "(a|b)".repeat(6)));
+    let (mut pruned1, mut pruned2) = (dfa1.clone(), dfa2.clone());
+    pruned1.prune_symbols();
+    pruned2.prune_symbols();
+    c.bench_function("product with unused symbols", |b| {
+        b.iter(|| dfa1.intersection(black_box(&dfa2)))
+    });
+    c.bench_function("product after pruning", |b| {
+        b.iter(|| pruned1.intersection(black_box(&pruned2)))
+    });
+}
+
+/// Checks a 100 000 grapheme long input against a DFA, stepping the graphemes as they are segmented compared to
+/// collecting them into a `Vec` first (which is what [Dfa::accepts_graphemes] used to do)
+pub fn accepts_graphemes_long_input(c: &mut Criterion) {
+    let dfa: Dfa = parser::dfa("a b\n-> * s0 s1 s0\n s1 s0 s1\n")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let input = "ab".repeat(50_000);
+    c.bench_function("accepts_graphemes long input", |b| {
+        b.iter(|| dfa.accepts_graphemes(black_box(&input)))
+    });
+    c.bench_function("accepts_graphemes long input (collected)", |b| {
+        b.iter(|| {
+            let graphemes = black_box(&input).graphemes(true).collect::<Vec<_>>();
+            dfa.accepts(&graphemes)
+        })
+    });
+}
+
+/// Minimizes a random DFA with 2000 states over 4 symbols, where most of the time is spent in Hopcroft's
+/// algorithm finding the pre-images of the splitters
+pub fn minimize_large_random_dfa(c: &mut Criterion) {
+    let n = 2000;
+    let mut rng = StdRng::seed_from_u64(1204);
+    let mut table = String::from("a b c d\n");
+    for i in 0..n {
+        let prefix = match (i, rng.gen_bool(0.5)) {
+            (0, true) => "-> *",
+            (0, false) => "->",
+            (_, true) => "*",
+            (_, false) => "",
+        };
+        table += &format!("{prefix} q{i}");
+        for _ in 0..4 {
+            table += &format!(" q{}", rng.gen_range(0..n));
+        }
+        table += "\n";
+    }
+    let dfa: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+    c.bench_function("minimize random dfa (2000 states)", |b| {
+        b.iter_batched(
+            || dfa.clone(),
+            |mut dfa| dfa.minimize(),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+/// Checks 1000 random words of length 30 against the NFA for `(a|b)*a(a|b)^10` ("the 11th last symbol is an a"),
+/// whose DFA has 2^11 states, with the NFA evaluator, the lazily determinized evaluator (reused between words, so
+/// that it caches the sets of states reached) and the DFA (converted up front, which isn't measured)
+pub fn lazy_dfa_evaluation(c: &mut Criterion) {
+    let nfa = parser::regex(&format!("(a|b)*a{}", "(a|b)".repeat(10)))
+        .unwrap()
+        .to_nfa();
+    let mut rng = StdRng::seed_from_u64(1221);
+    let corpus = (0..1000)
+        .map(|_| {
+            (0..30)
+                .map(|_| if rng.gen_bool(0.5) { 'a' } else { 'b' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    c.bench_function("nfa evaluator (1000 words)", |b| {
+        b.iter(|| {
+            corpus
+                .iter()
+                .filter(|word| nfa.accepts_graphemes(black_box(word)))
+                .count()
+        })
+    });
+    let mut lazy = nfa.lazy_dfa_evaluator();
+    c.bench_function("lazy dfa evaluator (1000 words)", |b| {
+        b.iter(|| {
+            corpus
+                .iter()
+                .filter(|word| lazy.accepts_graphemes(black_box(word)))
+                .count()
+        })
+    });
+    let dfa = nfa.to_dfa();
+    c.bench_function("precomputed dfa (1000 words)", |b| {
+        b.iter(|| {
+            corpus
+                .iter()
+                .filter(|word| dfa.accepts_graphemes(black_box(word)))
+                .count()
+        })
+    });
+}
+
 criterion_group!(
     benches,
     equivalence_check,
     powerset,
     regex_compile,
-    regex_check
+    regex_check,
+    nfa_words,
+    nfa_equivalence,
+    nfa_remove_epsilon_moves,
+    nfa_union_all,
+    product_after_pruning,
+    accepts_graphemes_long_input,
+    minimize_large_random_dfa,
+    lazy_dfa_evaluation
 );
 criterion_main!(benches);
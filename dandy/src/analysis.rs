@@ -0,0 +1,572 @@
+//! # Analysis
+//! Aggregate statistics over a corpus of words tested against an automaton. [corpus_report] tests every line of a
+//! corpus (where every grapheme is an element of the alphabet, like [Dfa::accepts_graphemes]) and collects how many
+//! words of each length were accepted and rejected, together with the graphemes which aren't in the alphabet of the
+//! automaton at all, which often hints at encoding problems in the corpus rather than at words being rejected:
+//! ```
+//! use dandy::analysis;
+//! use dandy::dfa::Dfa;
+//!
+//! let dfa: Dfa = dandy::parser::dfa("
+//!       a b
+//!   → s t s
+//!   * t t s
+//! ").unwrap().try_into().unwrap();
+//! let report = analysis::corpus_report(&dfa, "a\nba\nab\n\nåa".lines());
+//! assert_eq!(report.length_counts(2).accepted, 1);
+//! assert_eq!(report.length_counts(2).rejected, 2);
+//! assert_eq!(report.empty_lines, 1);
+//! assert_eq!(report.top_unknown_symbols(3), vec![("å", 1)]);
+//! ```
+//!
+//! [Nfa::active_state_profile] instead tells how many states of an NFA are active while reading the words, which
+//! shows how "confusable" it is: the more states are active at once, the more the NFA has to keep track of. See
+//! [ActiveProfile].
+//!
+//! [Dfa::coverage] counts how often each state of a DFA is visited and each transition is taken while reading the
+//! words, which shows the parts of a hand-written DFA which real inputs never use. See [Coverage].
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::table::Table;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The number of unknown symbols listed when displaying a [CorpusReport]
+const DISPLAYED_UNKNOWN_SYMBOLS: usize = 5;
+
+/// Automata which the words of a corpus can be tested against in [corpus_report]
+pub trait Recognizer {
+    /// Checks if the given element is part of the alphabet of this automaton
+    fn in_alphabet(&self, symbol: &str) -> bool;
+
+    /// Checks if this automaton accepts the given string, where every grapheme is an element of the alphabet
+    fn accepts_graphemes(&self, word: &str) -> bool;
+}
+
+impl Recognizer for Dfa {
+    fn in_alphabet(&self, symbol: &str) -> bool {
+        self.alphabet_index(symbol).is_some()
+    }
+
+    fn accepts_graphemes(&self, word: &str) -> bool {
+        let mut eval = self.evaluator();
+        eval.step_str(word)
+            .is_some_and(|state| state.is_accepting())
+    }
+}
+
+impl Recognizer for Nfa {
+    fn in_alphabet(&self, symbol: &str) -> bool {
+        self.alphabet_index(symbol).is_some()
+    }
+
+    fn accepts_graphemes(&self, word: &str) -> bool {
+        let mut eval = self.evaluator();
+        eval.step_str(word).is_some() && eval.is_accepting()
+    }
+}
+
+/// The number of accepted and rejected words of some length, see [CorpusReport::length_counts]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LengthCounts {
+    pub accepted: usize,
+    pub rejected: usize,
+}
+
+/// Statistics over the lines of a corpus tested against an automaton, given by [corpus_report]. More lines can be
+/// added with [CorpusReport::record], for example to get one report for several files. Displaying the report gives a
+/// table of the number of accepted and rejected words of each length, followed by the number of empty lines and the
+/// most common unknown symbols.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CorpusReport {
+    /// The number of accepted and rejected words of each length (in graphemes) occurring in the corpus. Empty lines
+    /// are counted as words of length 0.
+    pub lengths: BTreeMap<usize, LengthCounts>,
+    /// The number of empty lines
+    pub empty_lines: usize,
+    /// The number of lines containing at least one grapheme which isn't in the alphabet
+    pub lines_with_unknown_symbols: usize,
+    /// The number of occurrences of every grapheme which isn't in the alphabet
+    pub unknown_symbols: BTreeMap<String, usize>,
+}
+
+/// Tests every line against the automaton, where every grapheme is an element of the alphabet, and collects the
+/// statistics into a [CorpusReport]. Lines containing graphemes which aren't in the alphabet are rejected.
+pub fn corpus_report<'a, A: Recognizer + ?Sized>(
+    automaton: &A,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> CorpusReport {
+    let mut report = CorpusReport::default();
+    lines
+        .into_iter()
+        .for_each(|line| report.record(automaton, line));
+    report
+}
+
+impl CorpusReport {
+    /// Tests one more line against the automaton and adds it to this report
+    pub fn record<A: Recognizer + ?Sized>(&mut self, automaton: &A, line: &str) {
+        let mut length = 0;
+        let mut unknown = false;
+        for grapheme in line.graphemes(true) {
+            length += 1;
+            if !automaton.in_alphabet(grapheme) {
+                unknown = true;
+                *self
+                    .unknown_symbols
+                    .entry(grapheme.to_string())
+                    .or_default() += 1;
+            }
+        }
+        let counts = self.lengths.entry(length).or_default();
+        if !unknown && automaton.accepts_graphemes(line) {
+            counts.accepted += 1;
+        } else {
+            counts.rejected += 1;
+        }
+        if line.is_empty() {
+            self.empty_lines += 1;
+        }
+        if unknown {
+            self.lines_with_unknown_symbols += 1;
+        }
+    }
+
+    /// Adds the counts of another report to this one, which gives the same report as recording the lines of both
+    /// with one report (such as when parts of a corpus are tested on different threads)
+    pub fn merge(&mut self, other: &CorpusReport) {
+        for (&length, counts) in &other.lengths {
+            let total = self.lengths.entry(length).or_default();
+            total.accepted += counts.accepted;
+            total.rejected += counts.rejected;
+        }
+        self.empty_lines += other.empty_lines;
+        self.lines_with_unknown_symbols += other.lines_with_unknown_symbols;
+        for (symbol, &count) in &other.unknown_symbols {
+            *self.unknown_symbols.entry(symbol.clone()).or_default() += count;
+        }
+    }
+
+    /// The number of accepted and rejected words of the given length, which is zero for both if there are no words
+    /// of that length in the corpus
+    pub fn length_counts(&self, length: usize) -> LengthCounts {
+        self.lengths.get(&length).copied().unwrap_or_default()
+    }
+
+    /// The total number of accepted and rejected words
+    pub fn total(&self) -> LengthCounts {
+        self.lengths
+            .values()
+            .fold(LengthCounts::default(), |total, counts| LengthCounts {
+                accepted: total.accepted + counts.accepted,
+                rejected: total.rejected + counts.rejected,
+            })
+    }
+
+    /// The total number of occurrences of graphemes which aren't in the alphabet
+    pub fn unknown_symbol_occurrences(&self) -> usize {
+        self.unknown_symbols.values().sum()
+    }
+
+    /// The (at most) `n` graphemes not in the alphabet which occur most often, together with their number of
+    /// occurrences, most common first (and in lexicographic order among equally common ones)
+    pub fn top_unknown_symbols(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut symbols = self
+            .unknown_symbols
+            .iter()
+            .map(|(symbol, &count)| (symbol.as_str(), count))
+            .collect::<Vec<_>>();
+        symbols.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        symbols.truncate(n);
+        symbols
+    }
+}
+
+impl Display for CorpusReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .lengths
+            .iter()
+            .map(|(length, counts)| (length.to_string(), *counts))
+            .chain([("total".to_string(), self.total())])
+            .map(|(length, counts)| {
+                [
+                    length,
+                    counts.accepted.to_string(),
+                    counts.rejected.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut table = Table::default();
+        table.push_row(vec!["length", "accepted", "rejected"]);
+        rows.iter()
+            .for_each(|row| table.push_row(row.iter().map(String::as_str).collect()));
+        for line in table.to_string(" ").lines() {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        writeln!(f, "empty lines: {}", self.empty_lines)?;
+        write!(
+            f,
+            "unknown symbols: {} occurrence(s) in {} line(s)",
+            self.unknown_symbol_occurrences(),
+            self.lines_with_unknown_symbols
+        )?;
+        let top = self.top_unknown_symbols(DISPLAYED_UNKNOWN_SYMBOLS);
+        if !top.is_empty() {
+            let top = top
+                .iter()
+                .map(|(symbol, count)| format!("{symbol:?} ({count})"))
+                .collect::<Vec<_>>();
+            write!(f, ", most common: {}", top.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The smallest, largest and total size of a number of active state sets, see [ActiveProfile]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SizeStats {
+    pub min: usize,
+    pub max: usize,
+    pub total: usize,
+    /// The number of sets
+    pub count: usize,
+}
+
+impl SizeStats {
+    /// Adds the size of another set
+    fn record(&mut self, size: usize) {
+        if self.count == 0 {
+            self.min = size;
+            self.max = size;
+        } else {
+            self.min = self.min.min(size);
+            self.max = self.max.max(size);
+        }
+        self.total += size;
+        self.count += 1;
+    }
+
+    /// Adds the sizes of other sets
+    fn merge(&mut self, other: &SizeStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.total += other.total;
+        self.count += other.count;
+    }
+
+    /// The mean size of the sets, which is 0 if there are none
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total as f64 / self.count as f64
+        }
+    }
+}
+
+/// How many states of an NFA are active while reading a number of inputs, given by [Nfa::active_state_profile].
+/// Only the aggregated sizes are kept, so the memory used only depends on the length of the longest input.
+///
+/// An input *dies* when no state is active anymore (or when it contains a grapheme which isn't in the alphabet), at
+/// which point the rest of it is rejected no matter what it is. The empty set is recorded at the step the input
+/// died, but no steps after it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ActiveProfile {
+    /// The sizes of the active state sets after each number of steps, where index 0 is the set before reading
+    /// anything (the ε-closure of the initial state)
+    pub steps: Vec<SizeStats>,
+    /// The sizes of the active state sets over all steps
+    pub overall: SizeStats,
+    /// The number of inputs
+    pub inputs: usize,
+    /// The number of inputs which died
+    pub died: usize,
+    /// The sum of the number of elements read by every input which died, when it died
+    pub death_depth_total: usize,
+}
+
+impl ActiveProfile {
+    /// Runs the evaluator of the NFA on one more input (where every grapheme is an element of the alphabet) and adds
+    /// the sizes of its active state sets to this profile
+    pub fn record(&mut self, nfa: &Nfa, input: &str) {
+        self.inputs += 1;
+        let mut eval = nfa.evaluator();
+        self.record_size(0, eval.current_states_idx().len());
+        for (idx, grapheme) in input.graphemes(true).enumerate() {
+            let depth = idx + 1;
+            let size = match eval.step(grapheme) {
+                Some(()) => eval.current_states_idx().len(),
+                None => 0,
+            };
+            self.record_size(depth, size);
+            if size == 0 {
+                self.died += 1;
+                self.death_depth_total += depth;
+                return;
+            }
+        }
+    }
+
+    fn record_size(&mut self, step: usize, size: usize) {
+        if self.steps.len() <= step {
+            self.steps.resize(step + 1, SizeStats::default());
+        }
+        self.steps[step].record(size);
+        self.overall.record(size);
+    }
+
+    /// Adds the sizes of another profile of the same NFA to this one, which gives the same profile as recording the
+    /// inputs of both with one profile (such as when parts of a corpus are evaluated on different threads)
+    pub fn merge(&mut self, other: &ActiveProfile) {
+        if self.steps.len() < other.steps.len() {
+            self.steps.resize(other.steps.len(), SizeStats::default());
+        }
+        self.steps
+            .iter_mut()
+            .zip(&other.steps)
+            .for_each(|(stats, other)| stats.merge(other));
+        self.overall.merge(&other.overall);
+        self.inputs += other.inputs;
+        self.died += other.died;
+        self.death_depth_total += other.death_depth_total;
+    }
+
+    /// The mean number of elements read by the inputs which died before they died, if any input died
+    pub fn mean_death_depth(&self) -> Option<f64> {
+        (self.died > 0).then(|| self.death_depth_total as f64 / self.died as f64)
+    }
+}
+
+impl Display for ActiveProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(step, stats)| (step.to_string(), stats))
+            .chain([("overall".to_string(), &self.overall)])
+            .map(|(step, stats)| {
+                [
+                    step,
+                    stats.count.to_string(),
+                    stats.min.to_string(),
+                    stats.max.to_string(),
+                    format!("{:.2}", stats.mean()),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let mut table = Table::default();
+        table.push_row(vec!["step", "inputs", "min", "max", "mean"]);
+        rows.iter()
+            .for_each(|row| table.push_row(row.iter().map(String::as_str).collect()));
+        for line in table.to_string(" ").lines() {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        write!(f, "died: {} of {} input(s)", self.died, self.inputs)?;
+        if let Some(depth) = self.mean_death_depth() {
+            write!(f, ", after {depth:.2} step(s) on average")?;
+        }
+        Ok(())
+    }
+}
+
+/// How often each state of a DFA is visited and each transition is taken while evaluating a number of inputs, given
+/// by [Dfa::coverage]. Every input visits the initial state, and then takes one transition and visits its target for
+/// every grapheme it reads. An input stops at the first grapheme which isn't in the alphabet, but the states and
+/// transitions it used before that are still counted.
+///
+/// Coverages of the same DFA (such as the coverages of parts of a corpus evaluated on different threads) can be
+/// combined with [Coverage::merge]. Displaying a coverage gives the number of visited states and taken transitions,
+/// followed by the unvisited states and the untaken transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    state_names: Vec<Rc<str>>,
+    alphabet: Rc<[Rc<str>]>,
+    /// The number of times each state was visited, by the index of the state
+    pub state_visits: Vec<usize>,
+    /// The number of times each transition was taken, by the index of the state it is from and then by the index of
+    /// its element of the alphabet
+    pub transition_counts: Vec<Vec<usize>>,
+    /// The number of inputs
+    pub inputs: usize,
+}
+
+impl Coverage {
+    /// Creates a coverage of the DFA where no state has been visited yet
+    pub fn new(dfa: &Dfa) -> Self {
+        Self {
+            state_names: dfa.states.iter().map(|state| state.name.clone()).collect(),
+            alphabet: dfa.alphabet.clone(),
+            state_visits: vec![0; dfa.states.len()],
+            transition_counts: vec![vec![0; dfa.alphabet.len()]; dfa.states.len()],
+            inputs: 0,
+        }
+    }
+
+    /// Evaluates one more input (where every grapheme is an element of the alphabet) with the DFA and adds the states
+    /// and transitions it used to this coverage. The DFA must be the DFA this coverage was created for.
+    pub fn record(&mut self, dfa: &Dfa, input: &str) {
+        self.inputs += 1;
+        let mut current = dfa.initial_state;
+        self.state_visits[current] += 1;
+        for grapheme in input.graphemes(true) {
+            let Some(elem) = dfa.alphabet_index(grapheme) else {
+                return;
+            };
+            self.transition_counts[current][elem] += 1;
+            current = dfa.states[current].transitions[elem];
+            self.state_visits[current] += 1;
+        }
+    }
+
+    /// Adds the counts of another coverage of the same DFA to this one, which gives the same coverage as evaluating
+    /// the inputs of both with one coverage
+    ///
+    /// # Panics
+    /// Panics if the other coverage is of a DFA with other states or another alphabet (in another order)
+    pub fn merge(&mut self, other: &Coverage) {
+        assert!(
+            self.state_names == other.state_names && self.alphabet == other.alphabet,
+            "merged coverages must be of the same DFA"
+        );
+        self.inputs += other.inputs;
+        self.state_visits
+            .iter_mut()
+            .zip(&other.state_visits)
+            .for_each(|(visits, other)| *visits += other);
+        self.transition_counts
+            .iter_mut()
+            .flatten()
+            .zip(other.transition_counts.iter().flatten())
+            .for_each(|(count, other)| *count += other);
+    }
+
+    /// The number of times the state with the given name was visited, or `None` if there is no such state
+    pub fn visits(&self, state: &str) -> Option<usize> {
+        let idx = self.state_names.iter().position(|name| &**name == state)?;
+        Some(self.state_visits[idx])
+    }
+
+    /// The names of the states which were never visited, in the order of the states
+    pub fn unvisited_states(&self) -> Vec<&str> {
+        self.state_names
+            .iter()
+            .zip(&self.state_visits)
+            .filter(|(_, &visits)| visits == 0)
+            .map(|(name, _)| &**name)
+            .collect()
+    }
+
+    /// The transitions which were never taken, as the name of the state they are from and their element of the
+    /// alphabet, in the order of the states and then of the alphabet. This includes all transitions from unvisited
+    /// states.
+    pub fn untaken_transitions(&self) -> Vec<(&str, &str)> {
+        self.state_names
+            .iter()
+            .zip(&self.transition_counts)
+            .flat_map(|(name, counts)| {
+                self.alphabet
+                    .iter()
+                    .zip(counts)
+                    .filter(|(_, &count)| count == 0)
+                    .map(move |(elem, _)| (&**name, &**elem))
+            })
+            .collect()
+    }
+
+    /// Appends the number of visits of each state to its row of a table of the DFA (as given by [Dfa::to_table] or
+    /// [Dfa::ascii_table]) as a comment, together with the elements of its transitions which were never taken from
+    /// visited states. The annotated table may still be parsed, since comments are ignored.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///       a b
+    ///   → s t s
+    ///   * t t s
+    /// ").unwrap().try_into().unwrap();
+    /// let coverage = dfa.coverage(["aa", "b"]);
+    /// assert_eq!(
+    ///     coverage.annotate_table(&dfa.to_table()),
+    ///     "      a b\n→   s t s # 3 visit(s)\n  * t t s # 2 visit(s), untaken: b"
+    /// );
+    /// ```
+    pub fn annotate_table(&self, table: &str) -> String {
+        let mut lines = table.lines();
+        let header = lines.next().unwrap_or_default().trim_end().to_string();
+        let rows = lines
+            .zip(self.state_visits.iter().zip(&self.transition_counts))
+            .map(|(line, (&visits, counts))| {
+                let untaken = self
+                    .alphabet
+                    .iter()
+                    .zip(counts)
+                    .filter(|(_, &count)| count == 0)
+                    .map(|(elem, _)| &**elem)
+                    .collect::<Vec<_>>();
+                let mut comment = format!("{} # {visits} visit(s)", line.trim_end());
+                if visits > 0 && !untaken.is_empty() {
+                    comment.push_str(&format!(", untaken: {}", untaken.join(" ")));
+                }
+                comment
+            });
+        [header]
+            .into_iter()
+            .chain(rows)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Display for Coverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let visited = self
+            .state_visits
+            .iter()
+            .filter(|&&visits| visits > 0)
+            .count();
+        let taken = self
+            .transition_counts
+            .iter()
+            .flatten()
+            .filter(|&&count| count > 0)
+            .count();
+        write!(
+            f,
+            "visited {visited} of {} state(s) and took {taken} of {} transition(s) over {} input(s)",
+            self.state_visits.len(),
+            self.state_visits.len() * self.alphabet.len(),
+            self.inputs
+        )?;
+        let unvisited = self.unvisited_states();
+        if !unvisited.is_empty() {
+            write!(f, "\nunvisited states: {}", unvisited.join(", "))?;
+        }
+        let untaken = self
+            .untaken_transitions()
+            .into_iter()
+            .filter(|(state, _)| !unvisited.contains(state))
+            .map(|(state, elem)| format!("{state} -{elem}->"))
+            .collect::<Vec<_>>();
+        if !untaken.is_empty() {
+            write!(
+                f,
+                "\nuntaken transitions from visited states: {}",
+                untaken.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
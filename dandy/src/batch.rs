@@ -0,0 +1,319 @@
+//! # Batch processing
+//! Minimizing or comparing many DFAs at once (such as all submissions of an assignment) on several threads, with
+//! the `parallel` feature. The results are always in the same order as the inputs, and don't depend on how many
+//! threads are used:
+//! ```
+//! use dandy::batch;
+//! use dandy::dfa::Dfa;
+//!
+//! let parse = |table: &str| -> Dfa { dandy::parser::dfa(table).unwrap().try_into().unwrap() };
+//! let reference = parse("   a\n→ s t\n* t s");
+//! let candidates = vec![
+//!     parse("   a\n→ p q\n* q r\n  r q"),
+//!     parse("   a\n→ * p p"),
+//! ];
+//! assert_eq!(batch::equivalence_all(&reference, &candidates), vec![true, false]);
+//! let minimized = batch::minimize_all(candidates);
+//! assert_eq!(minimized[0].states().len(), 2);
+//! ```
+//!
+//! Automata use [Rc](std::rc::Rc)s internally, so they can't be sent between threads. Instead, every DFA is taken
+//! apart into owned strings and indices ([DfaParts]), which are sent to a worker thread and put together into a DFA
+//! again there. The same approach can be used for other work with [map_with_state], where every thread creates its
+//! own state (such as an automaton put together from [DfaParts] or [NfaParts]) before processing its share of the
+//! items.
+
+use crate::dfa::{Dfa, DfaState};
+use crate::nfa::{Nfa, NfaState};
+use crate::util::{AlphabetIndex, Provenance, ReverseIndex, Version};
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::thread;
+
+/// The number of items a thread takes from the queue at a time
+const CHUNK_LEN: usize = 8;
+
+/// Minimizes every DFA, using one thread per CPU, see [minimize_all_with_jobs]
+pub fn minimize_all(inputs: Vec<Dfa>) -> Vec<Dfa> {
+    minimize_all_with_jobs(inputs, 0)
+}
+
+/// Minimizes every DFA (see [Dfa::minimize]) on `jobs` threads, where 0 uses one thread per CPU and 1 minimizes the
+/// DFAs on the current thread. The minimized DFAs are in the same order as the inputs.
+pub fn minimize_all_with_jobs(inputs: Vec<Dfa>, jobs: usize) -> Vec<Dfa> {
+    if resolve_jobs(jobs) == 1 {
+        return inputs
+            .into_iter()
+            .map(|mut dfa| {
+                dfa.minimize();
+                dfa
+            })
+            .collect();
+    }
+    let parts = inputs.iter().map(DfaParts::new).collect::<Vec<_>>();
+    map_with_state(
+        parts,
+        jobs,
+        || (),
+        |_, parts| {
+            let mut dfa = parts.into_dfa();
+            dfa.minimize();
+            DfaParts::new(&dfa)
+        },
+    )
+    .into_iter()
+    .map(DfaParts::into_dfa)
+    .collect()
+}
+
+/// Checks whether every candidate is equivalent to the reference DFA, using one thread per CPU, see
+/// [equivalence_all_with_jobs]
+pub fn equivalence_all(reference: &Dfa, candidates: &[Dfa]) -> Vec<bool> {
+    equivalence_all_with_jobs(reference, candidates, 0)
+}
+
+/// Checks whether every candidate is equivalent to the reference DFA (see [Dfa::equivalent_to]) on `jobs` threads,
+/// where 0 uses one thread per CPU and 1 compares the DFAs on the current thread. The results are in the same order
+/// as the candidates.
+pub fn equivalence_all_with_jobs(reference: &Dfa, candidates: &[Dfa], jobs: usize) -> Vec<bool> {
+    if resolve_jobs(jobs) == 1 {
+        return candidates
+            .iter()
+            .map(|candidate| reference.equivalent_to(candidate))
+            .collect();
+    }
+    let reference = DfaParts::new(reference);
+    let candidates = candidates.iter().map(DfaParts::new).collect();
+    map_with_state(
+        candidates,
+        jobs,
+        || reference.clone().into_dfa(),
+        |reference, candidate| reference.equivalent_to(&candidate.into_dfa()),
+    )
+}
+
+/// Applies `f` to every item on `jobs` threads, where 0 uses one thread per CPU and 1 applies it on the current
+/// thread. Every thread first calls `init` to create its own state, which is given to `f` together with each item,
+/// so the state doesn't need to be [Send] (which automata aren't). Threads take a few items at a time, so that
+/// threads given quick items aren't left idle. The results are in the same order as the items.
+///
+/// ```
+/// use dandy::batch;
+/// use dandy::dfa::Dfa;
+///
+/// let table = "   a\n→ s t\n* t s";
+/// let words = vec!["", "a", "aa", "aaa"];
+/// let accepted = batch::map_with_state(
+///     words,
+///     4,
+///     || -> Dfa { dandy::parser::dfa(table).unwrap().try_into().unwrap() },
+///     |dfa, word| dfa.accepts_graphemes(word),
+/// );
+/// assert_eq!(accepted, vec![false, true, false, true]);
+/// ```
+pub fn map_with_state<T, S, R>(
+    items: Vec<T>,
+    jobs: usize,
+    init: impl Fn() -> S + Sync,
+    f: impl Fn(&S, T) -> R + Sync,
+) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+{
+    let threads = resolve_jobs(jobs).min(items.len().div_ceil(CHUNK_LEN));
+    if threads <= 1 {
+        let state = init();
+        return items.into_iter().map(|item| f(&state, item)).collect();
+    }
+
+    let len = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let state = init();
+                    let mut results = vec![];
+                    loop {
+                        let chunk = queue
+                            .lock()
+                            .expect("no thread panics while holding the lock")
+                            .by_ref()
+                            .take(CHUNK_LEN)
+                            .collect::<Vec<_>>();
+                        if chunk.is_empty() {
+                            return results;
+                        }
+                        results.extend(chunk.into_iter().map(|(idx, item)| (idx, f(&state, item))));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| match worker.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect::<Vec<_>>()
+    });
+    results.sort_unstable_by_key(|&(idx, _)| idx);
+    debug_assert_eq!(results.len(), len);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The number of threads to use for the given number of jobs, where 0 is one thread per CPU
+fn resolve_jobs(jobs: usize) -> usize {
+    match jobs {
+        0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        jobs => jobs,
+    }
+}
+
+/// A [Dfa] taken apart into owned strings and indices, which can be sent between threads and put together into an
+/// equal DFA again there, without parsing or validating it again
+///
+/// ```
+/// use dandy::batch::DfaParts;
+/// use dandy::dfa::Dfa;
+///
+/// let dfa: Dfa = dandy::parser::dfa("   a\n→ s t\n* t s").unwrap().try_into().unwrap();
+/// let parts = DfaParts::new(&dfa);
+/// let rebuilt = std::thread::spawn(move || parts.into_dfa().to_table()).join().unwrap();
+/// assert_eq!(rebuilt, dfa.to_table());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DfaParts {
+    alphabet: Vec<String>,
+    states: Vec<(String, bool, Option<u32>, Vec<usize>)>,
+    initial_state: usize,
+    #[cfg(feature = "provenance")]
+    provenance: Option<String>,
+}
+
+impl DfaParts {
+    /// Takes a DFA apart, copying its alphabet, states and provenance
+    pub fn new(dfa: &Dfa) -> Self {
+        Self {
+            alphabet: dfa.alphabet.iter().map(|elem| elem.to_string()).collect(),
+            states: dfa
+                .states
+                .iter()
+                .map(|state| {
+                    (
+                        state.name.to_string(),
+                        state.accepting,
+                        state.priority,
+                        state.transitions.clone(),
+                    )
+                })
+                .collect(),
+            initial_state: dfa.initial_state,
+            #[cfg(feature = "provenance")]
+            provenance: dfa.provenance.get().map(str::to_string),
+        }
+    }
+
+    /// Puts the DFA together again
+    pub fn into_dfa(self) -> Dfa {
+        let initial_state = self.initial_state;
+        #[cfg(feature = "provenance")]
+        let provenance = match self.provenance {
+            Some(provenance) => Provenance::new(|| provenance),
+            None => Provenance::none(),
+        };
+        #[cfg(not(feature = "provenance"))]
+        let provenance = Provenance::none();
+        Dfa {
+            alphabet: self.alphabet.into_iter().map(Into::into).collect(),
+            states: self
+                .states
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (name, accepting, priority, transitions))| DfaState {
+                    name: name.into(),
+                    initial: idx == initial_state,
+                    accepting,
+                    priority,
+                    transitions,
+                })
+                .collect(),
+            initial_state,
+            provenance,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+        }
+    }
+}
+
+/// The name, whether it is accepting, the ε-moves and the transitions of a NFA state
+type NfaStateParts = (String, bool, Vec<usize>, Vec<Vec<usize>>);
+
+/// A [Nfa] taken apart into owned strings and indices, which can be sent between threads just as [DfaParts]
+#[derive(Debug, Clone)]
+pub struct NfaParts {
+    alphabet: Vec<String>,
+    states: Vec<NfaStateParts>,
+    initial_state: usize,
+    #[cfg(feature = "provenance")]
+    provenance: Option<String>,
+}
+
+impl NfaParts {
+    /// Takes a NFA apart, copying its alphabet, states and provenance
+    pub fn new(nfa: &Nfa) -> Self {
+        Self {
+            alphabet: nfa.alphabet.iter().map(|elem| elem.to_string()).collect(),
+            states: nfa
+                .states
+                .iter()
+                .map(|state| {
+                    (
+                        state.name.to_string(),
+                        state.accepting,
+                        state.epsilon_transitions.clone(),
+                        state.transitions.clone(),
+                    )
+                })
+                .collect(),
+            initial_state: nfa.initial_state,
+            #[cfg(feature = "provenance")]
+            provenance: nfa.provenance.get().map(str::to_string),
+        }
+    }
+
+    /// Puts the NFA together again
+    pub fn into_nfa(self) -> Nfa {
+        let initial_state = self.initial_state;
+        #[cfg(feature = "provenance")]
+        let provenance = match self.provenance {
+            Some(provenance) => Provenance::new(|| provenance),
+            None => Provenance::none(),
+        };
+        #[cfg(not(feature = "provenance"))]
+        let provenance = Provenance::none();
+        Nfa {
+            alphabet: self.alphabet.into_iter().map(Into::into).collect(),
+            states: self
+                .states
+                .into_iter()
+                .enumerate()
+                .map(
+                    |(idx, (name, accepting, epsilon_transitions, transitions))| NfaState {
+                        name: name.into(),
+                        initial: idx == initial_state,
+                        accepting,
+                        epsilon_transitions,
+                        transitions,
+                    },
+                )
+                .collect(),
+            initial_state,
+            provenance,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::dfa::Dfa;
+use nalgebra::DMatrix;
+use num_traits::{One, Zero};
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+
+/// A number of words, which saturates instead of overflowing if it doesn't fit in a `u64`, as returned by
+/// [Dfa::count_words_of_length_saturating] and [Dfa::count_words_up_to_length_saturating]. A saturated count is larger than `u64::MAX`,
+/// while a count which isn't saturated is exact (even if it is `u64::MAX`).
+///
+/// Since all counts are non-negative, a matrix product computed with these is exact unless a true value doesn't fit in
+/// a `u64`. This implements the num traits since matrix multiplication requires them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SaturatingCount {
+    value: u64,
+    saturated: bool,
+}
+
+impl SaturatingCount {
+    pub(crate) const SATURATED: SaturatingCount = SaturatingCount {
+        value: u64::MAX,
+        saturated: true,
+    };
+
+    pub(crate) fn new(value: u64) -> Self {
+        Self {
+            value,
+            saturated: false,
+        }
+    }
+
+    /// Gets the count, or `None` if it is larger than `u64::MAX`
+    pub fn exact(self) -> Option<u64> {
+        (!self.saturated).then_some(self.value)
+    }
+
+    /// Gets the count, or `u64::MAX` if it is larger than that
+    pub fn saturated_value(self) -> u64 {
+        self.value
+    }
+
+    /// Checks if the count is larger than `u64::MAX`
+    pub fn is_saturated(self) -> bool {
+        self.saturated
+    }
+}
+
+impl Add for SaturatingCount {
+    type Output = SaturatingCount;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        match self.value.checked_add(rhs.value) {
+            Some(value) if !self.saturated && !rhs.saturated => SaturatingCount::new(value),
+            _ => SaturatingCount::SATURATED,
+        }
+    }
+}
+
+impl AddAssign for SaturatingCount {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs
+    }
+}
+
+impl Mul for SaturatingCount {
+    type Output = SaturatingCount;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // Zero times any count is exactly zero, even if the other count is saturated
+        if self.is_zero() || rhs.is_zero() {
+            return SaturatingCount::zero();
+        }
+        match self.value.checked_mul(rhs.value) {
+            Some(value) if !self.saturated && !rhs.saturated => SaturatingCount::new(value),
+            _ => SaturatingCount::SATURATED,
+        }
+    }
+}
+
+impl MulAssign for SaturatingCount {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl One for SaturatingCount {
+    fn one() -> Self {
+        SaturatingCount::new(1)
+    }
+}
+
+impl Zero for SaturatingCount {
+    fn zero() -> Self {
+        SaturatingCount::new(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == SaturatingCount::zero()
+    }
+}
+
+/// Generates the counting adjacency matrix M of the DFA, where M(i, j) is the number of elements of the alphabet
+/// which moves the DFA from the state with index i to the state with index j. If `sink` is true, one extra row and
+/// column is added for a sink state which every accepting state (and the sink itself) has one transition to. This
+/// means that M^(k+1)(i, sink) is the number of words of length at most k accepted from state i.
+pub(crate) fn adjacency_matrix(dfa: &Dfa, sink: bool) -> DMatrix<SaturatingCount> {
+    let n = dfa.states.len();
+    let size = if sink { n + 1 } else { n };
+    let mut matrix = DMatrix::from_element(size, size, SaturatingCount::zero());
+    dfa.states.iter().enumerate().for_each(|(from, state)| {
+        state
+            .transitions
+            .iter()
+            .for_each(|&to| matrix[(from, to)] += SaturatingCount::one());
+        if sink && state.accepting {
+            matrix[(from, n)] = SaturatingCount::one();
+        }
+    });
+    if sink {
+        matrix[(n, n)] = SaturatingCount::one();
+    }
+    matrix
+}
+
+/// Raises a square matrix to the power of `exp` by repeated squaring
+pub(crate) fn matrix_pow(
+    mut base: DMatrix<SaturatingCount>,
+    mut exp: usize,
+) -> DMatrix<SaturatingCount> {
+    let mut result = DMatrix::identity(base.nrows(), base.ncols());
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = &result * &base;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = &base * &base;
+        }
+    }
+    result
+}
@@ -0,0 +1,192 @@
+use crate::dfa::Dfa;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+/// A structural diff between two DFAs, created by [Dfa::structural_diff]. States are referred to by their names, so
+/// that the diff can be displayed without the DFAs at hand. The `Display` implementation renders the diff as text,
+/// with one difference per line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructuralDiff {
+    /// The pairs of matched states, as `(state in self, state in other)`, in the order they were matched
+    pub matched: Vec<(Rc<str>, Rc<str>)>,
+    /// The pairs of matched states where one of them is accepting and the other isn't
+    pub acceptance_mismatches: Vec<(Rc<str>, Rc<str>)>,
+    /// The transitions of matched states that lead to states which aren't matched with each other
+    pub transition_mismatches: Vec<TransitionMismatch>,
+    /// The states of `self` which weren't matched with any state of `other`, which includes all unreachable states
+    pub unmatched_in_self: Vec<Rc<str>>,
+    /// The states of `other` which weren't matched with any state of `self`, which includes all unreachable states
+    pub unmatched_in_other: Vec<Rc<str>>,
+    /// The elements of the alphabet of `self` which aren't in the alphabet of `other`
+    pub alphabet_only_in_self: Vec<Rc<str>>,
+    /// The elements of the alphabet of `other` which aren't in the alphabet of `self`
+    pub alphabet_only_in_other: Vec<Rc<str>>,
+}
+
+/// A transition where two matched states lead to states which aren't matched with each other, that is, a cell of
+/// the transition tables which differs between the two DFAs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransitionMismatch {
+    /// The state in `self` the transition is from
+    pub from_self: Rc<str>,
+    /// The state in `other` the transition is from
+    pub from_other: Rc<str>,
+    /// The element of the alphabet the transition is taken upon
+    pub symbol: Rc<str>,
+    /// The state in `self` the transition leads to
+    pub to_self: Rc<str>,
+    /// The state in `other` the transition leads to
+    pub to_other: Rc<str>,
+    /// Whether exactly one of the states the transition leads to is accepting
+    pub acceptance_differs: bool,
+}
+
+impl StructuralDiff {
+    /// Checks if the two DFAs are identical up to renaming of states, that is, if every state is matched and there
+    /// are no differences in acceptance, transitions or alphabet. Since states are matched by walking from the initial
+    /// states, unreachable states are never matched, so a DFA with unreachable states isn't identical to anything (not
+    /// even to itself). Use [Dfa::remove_unreachable_states] on both DFAs first to compare only their reachable parts.
+    pub fn is_identical(&self) -> bool {
+        self.acceptance_mismatches.is_empty()
+            && self.transition_mismatches.is_empty()
+            && self.unmatched_in_self.is_empty()
+            && self.unmatched_in_other.is_empty()
+            && self.alphabet_only_in_self.is_empty()
+            && self.alphabet_only_in_other.is_empty()
+    }
+}
+
+impl Display for StructuralDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "The DFAs are identical up to renaming of states");
+        }
+        for symbol in &self.alphabet_only_in_self {
+            writeln!(f, "Symbol {symbol} only in the first DFA")?;
+        }
+        for symbol in &self.alphabet_only_in_other {
+            writeln!(f, "Symbol {symbol} only in the second DFA")?;
+        }
+        for (this, other) in &self.matched {
+            writeln!(f, "State {this} matches {other}")?;
+        }
+        for (this, other) in &self.acceptance_mismatches {
+            writeln!(f, "State {this} and {other} differ in acceptance")?;
+        }
+        for mismatch in &self.transition_mismatches {
+            write!(
+                f,
+                "Transition from {}/{} on {} leads to {} and {}",
+                mismatch.from_self,
+                mismatch.from_other,
+                mismatch.symbol,
+                mismatch.to_self,
+                mismatch.to_other
+            )?;
+            if mismatch.acceptance_differs {
+                write!(f, " (which differ in acceptance)")?;
+            }
+            writeln!(f)?;
+        }
+        for state in &self.unmatched_in_self {
+            writeln!(f, "State {state} only in the first DFA")?;
+        }
+        for state in &self.unmatched_in_other {
+            writeln!(f, "State {state} only in the second DFA")?;
+        }
+        Ok(())
+    }
+}
+
+/// Matches the states of `this` and `other` by walking both DFAs in lockstep from their initial states over their
+/// shared alphabet. The first time two states are reached together they are matched, and any later transition of
+/// matched states leading to states which aren't matched with each other is reported as a mismatch.
+pub(crate) fn structural_diff(this: &Dfa, other: &Dfa) -> StructuralDiff {
+    let shared = this
+        .alphabet
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, elem)| Some((idx, other.alphabet_index(elem)?)))
+        .collect::<Vec<_>>();
+    let only_in = |a: &Dfa, b: &Dfa| {
+        a.alphabet
+            .iter()
+            .filter(|elem| b.alphabet_index(elem).is_none())
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    // this_to_other[s] is the state of `other` matched with state `s` of `this`, and vice versa
+    let mut this_to_other: Vec<Option<usize>> = vec![None; this.states.len()];
+    let mut other_to_this: Vec<Option<usize>> = vec![None; other.states.len()];
+    let mut matched = vec![];
+    let mut transition_mismatches = vec![];
+
+    let mut try_match = |s: usize, o: usize, matched: &mut Vec<(usize, usize)>| {
+        if this_to_other[s].is_none() && other_to_this[o].is_none() {
+            this_to_other[s] = Some(o);
+            other_to_this[o] = Some(s);
+            matched.push((s, o));
+            true
+        } else {
+            this_to_other[s] == Some(o)
+        }
+    };
+
+    // `matched` doubles as the queue of the breadth-first walk, since every pair is visited once right after it's
+    // matched
+    try_match(this.initial_state, other.initial_state, &mut matched);
+    let mut next = 0;
+    while next < matched.len() {
+        let (s, o) = matched[next];
+        next += 1;
+        for &(this_idx, other_idx) in &shared {
+            let to_s = this.states[s].transitions[this_idx];
+            let to_o = other.states[o].transitions[other_idx];
+            if !try_match(to_s, to_o, &mut matched) {
+                transition_mismatches.push(TransitionMismatch {
+                    from_self: this.states[s].name.clone(),
+                    from_other: other.states[o].name.clone(),
+                    symbol: this.alphabet[this_idx].clone(),
+                    to_self: this.states[to_s].name.clone(),
+                    to_other: other.states[to_o].name.clone(),
+                    acceptance_differs: this.states[to_s].accepting != other.states[to_o].accepting,
+                });
+            }
+        }
+    }
+
+    let acceptance_mismatches = matched
+        .iter()
+        .filter(|&&(s, o)| this.states[s].accepting != other.states[o].accepting)
+        .map(|&(s, o)| (this.states[s].name.clone(), other.states[o].name.clone()))
+        .collect();
+    let unmatched_in_self = this
+        .states
+        .iter()
+        .zip(&this_to_other)
+        .filter(|(_, o)| o.is_none())
+        .map(|(state, _)| state.name.clone())
+        .collect();
+    let unmatched_in_other = other
+        .states
+        .iter()
+        .zip(&other_to_this)
+        .filter(|(_, s)| s.is_none())
+        .map(|(state, _)| state.name.clone())
+        .collect();
+
+    StructuralDiff {
+        matched: matched
+            .into_iter()
+            .map(|(s, o)| (this.states[s].name.clone(), other.states[o].name.clone()))
+            .collect(),
+        acceptance_mismatches,
+        transition_mismatches,
+        unmatched_in_self,
+        unmatched_in_other,
+        alphabet_only_in_self: only_in(this, other),
+        alphabet_only_in_other: only_in(other, this),
+    }
+}
@@ -0,0 +1,84 @@
+use crate::dfa::Dfa;
+use std::collections::VecDeque;
+
+/// How a node in the edit lattice was reached, used to reconstruct the witness word
+#[derive(Copy, Clone, Debug)]
+struct Step {
+    /// The node (index into the distance table) this step was taken from
+    from: usize,
+    /// The element of the alphabet appended to the witness word by this step, if any (deletions append nothing)
+    elem: Option<usize>,
+}
+
+/// Finds a word accepted by the DFA with minimal edit distance to `word`, where `word` is given as indices into the
+/// alphabet of the DFA (`None` for elements not in the alphabet, which can never be matched). The search is a 0-1 BFS
+/// over the product of the word positions and the DFA states, where a node `(i, q)` means that the first `i`
+/// elements of `word` have been turned into some word taking the DFA to `q`. Matching an element costs nothing,
+/// while substituting, deleting or inserting an element costs one edit. Returns the witness word (as indices into
+/// the alphabet) and its distance, or `None` if no accepted word is within `max_edits` edits.
+pub(crate) fn closest_accepted(
+    dfa: &Dfa,
+    word: &[Option<usize>],
+    max_edits: usize,
+) -> Option<(Vec<usize>, usize)> {
+    let n_states = dfa.states.len();
+    let node = |pos: usize, state: usize| pos * n_states + state;
+    let nodes = (word.len() + 1) * n_states;
+
+    let mut dist = vec![usize::MAX; nodes];
+    let mut prev: Vec<Option<Step>> = vec![None; nodes];
+    let mut queue = VecDeque::new();
+    let start = node(0, dfa.initial_state);
+    dist[start] = 0;
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        let (pos, state) = (current / n_states, current % n_states);
+        let d = dist[current];
+        if pos == word.len() && dfa.states[state].accepting {
+            return Some((witness(&prev, current), d));
+        }
+
+        let mut relax = |to: usize, cost: usize, elem: Option<usize>| {
+            let new_dist = d + cost;
+            if new_dist <= max_edits && new_dist < dist[to] {
+                dist[to] = new_dist;
+                prev[to] = Some(Step {
+                    from: current,
+                    elem,
+                });
+                if cost == 0 {
+                    queue.push_front(to);
+                } else {
+                    queue.push_back(to);
+                }
+            }
+        };
+
+        let transitions = &dfa.states[state].transitions;
+        for (elem, &to) in transitions.iter().enumerate() {
+            // Insertion of elem before word[pos]
+            relax(node(pos, to), 1, Some(elem));
+            if pos < word.len() {
+                // Matching or substituting word[pos] with elem
+                let cost = usize::from(word[pos] != Some(elem));
+                relax(node(pos + 1, to), cost, Some(elem));
+            }
+        }
+        if pos < word.len() {
+            // Deletion of word[pos]
+            relax(node(pos + 1, state), 1, None);
+        }
+    }
+    None
+}
+
+fn witness(prev: &[Option<Step>], mut current: usize) -> Vec<usize> {
+    let mut word = vec![];
+    while let Some(step) = prev[current] {
+        word.extend(step.elem);
+        current = step.from;
+    }
+    word.reverse();
+    word
+}
@@ -1,11 +1,12 @@
-use crate::dfa::{Dfa, DfaState};
-use std::collections::HashMap;
-use std::iter;
+use crate::dfa::{Dfa, DfaState, SymbolMap};
+use std::hash::{Hash, Hasher};
+use std::{iter, ptr};
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Debug, Clone)]
 pub struct DfaEvaluator<'a> {
     dfa: &'a Dfa,
-    rev_map: HashMap<&'a str, usize>,
     current_state: usize,
     unknown_elem_seen: bool,
 }
@@ -28,6 +29,18 @@ impl<'a> DfaEvaluator<'a> {
         self.current_state
     }
 
+    /// Gets the index of the current state, or `None` if an element not in the alphabet has been seen. This is a
+    /// canonical representation of the state of the evaluator, suitable as a key when caching results for states.
+    pub fn state_key(&self) -> Option<usize> {
+        (!self.unknown_elem_seen).then_some(self.current_state)
+    }
+
+    /// Gets the DFA this evaluator steps through, for example to compare its [version](Dfa::version) to the
+    /// version of a DFA the state of this evaluator was recorded for
+    pub fn automaton(&self) -> &'a Dfa {
+        self.dfa
+    }
+
     pub fn step_all(&self) -> Vec<DfaEvaluator<'a>> {
         iter::repeat(self.clone())
             .zip(self.dfa.alphabet())
@@ -43,12 +56,12 @@ impl<'a> DfaEvaluator<'a> {
             return None;
         }
 
-        match self.rev_map.get(elem) {
+        match self.dfa.alphabet_index(elem) {
             None => {
                 self.unknown_elem_seen = true;
                 None
             }
-            Some(&idx) => {
+            Some(idx) => {
                 self.current_state = self.dfa.states[self.current_state].transitions[idx];
                 Some(&self.dfa.states[self.current_state])
             }
@@ -64,19 +77,122 @@ impl<'a> DfaEvaluator<'a> {
             Some(_) => Some(&self.dfa.states[self.current_state]),
         }
     }
+
+    /// Steps this evaluator on an element after translating it with the given [SymbolMap], see
+    /// [Dfa::merge_equivalent_symbols]
+    pub fn step_mapped(&mut self, elem: &str, map: &SymbolMap) -> Option<&DfaState> {
+        self.step(map.translate(elem))
+    }
+
+    /// Steps this evaluator once for every grapheme of `s`, where every grapheme by itself is considered as an
+    /// element of the alphabet (see [Dfa::accepts_graphemes]). The graphemes are stepped as they are segmented, so
+    /// nothing is allocated, and the rest of `s` isn't segmented after a grapheme which isn't in the alphabet.
+    pub fn step_str(&mut self, s: &str) -> Option<&DfaState> {
+        s.graphemes(true)
+            .try_for_each(|g| self.step(g).map(|_| ()))?;
+        self.current_state()
+    }
+
+    /// Saves the current state of this evaluator into a checkpoint, which can later be resumed with
+    /// [Dfa::resume]. The checkpoint doesn't borrow the DFA, but contains a [fingerprint](Dfa::fingerprint) of it
+    /// so that it can't be resumed against another DFA. Note that computing the fingerprint requires going through
+    /// the whole transition table of the DFA.
+    pub fn checkpoint(&self) -> EvaluatorCheckpoint {
+        EvaluatorCheckpoint {
+            state: self.current_state,
+            unknown_elem_seen: self.unknown_elem_seen,
+            fingerprint: self.dfa.fingerprint(),
+        }
+    }
+
+    pub(crate) fn from_checkpoint(
+        dfa: &'a Dfa,
+        checkpoint: &EvaluatorCheckpoint,
+    ) -> Result<Self, CheckpointMismatch> {
+        let fingerprint = dfa.fingerprint();
+        if checkpoint.fingerprint != fingerprint {
+            return Err(CheckpointMismatch::Fingerprint {
+                expected: fingerprint,
+                found: checkpoint.fingerprint,
+            });
+        }
+        if checkpoint.state >= dfa.states.len() {
+            return Err(CheckpointMismatch::StateOutOfRange(checkpoint.state));
+        }
+        let mut evaluator = Self::from(dfa);
+        evaluator.current_state = checkpoint.state;
+        evaluator.unknown_elem_seen = checkpoint.unknown_elem_seen;
+        Ok(evaluator)
+    }
+
+    pub(crate) fn at(dfa: &'a Dfa, state_key: Option<usize>) -> Option<Self> {
+        let mut evaluator = Self::from(dfa);
+        match state_key {
+            Some(state) if state < dfa.states.len() => evaluator.current_state = state,
+            Some(_) => return None,
+            None => evaluator.unknown_elem_seen = true,
+        }
+        Some(evaluator)
+    }
+}
+
+/// Two evaluators are equal if they evaluate the same DFA (the very same instance, not just an equal one) and have the
+/// same [state key](DfaEvaluator::state_key)
+impl PartialEq for DfaEvaluator<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.dfa, other.dfa) && self.state_key() == other.state_key()
+    }
+}
+
+impl Eq for DfaEvaluator<'_> {}
+
+impl Hash for DfaEvaluator<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.dfa, state);
+        self.state_key().hash(state);
+    }
+}
+
+/// A snapshot of the state of a [DfaEvaluator], created by [DfaEvaluator::checkpoint], which can be resumed later
+/// (possibly on another machine) by [Dfa::resume]. With the `serde` feature enabled, checkpoints can be serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvaluatorCheckpoint {
+    state: usize,
+    unknown_elem_seen: bool,
+    fingerprint: u64,
+}
+
+impl EvaluatorCheckpoint {
+    /// Gets the index of the state the evaluator was in
+    pub fn state_idx(&self) -> usize {
+        self.state
+    }
+
+    /// Checks if the evaluator had seen an element not in the alphabet
+    pub fn unknown_elem_seen(&self) -> bool {
+        self.unknown_elem_seen
+    }
+
+    /// Gets the fingerprint of the automaton the evaluator was evaluating
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
+
+/// The error returned when resuming an evaluator from a checkpoint which doesn't belong to the automaton
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum CheckpointMismatch {
+    #[error("Checkpoint is for another automaton (fingerprint {found:#018x}, expected {expected:#018x})")]
+    Fingerprint { expected: u64, found: u64 },
+    #[error("Checkpoint refers to state {0}, which does not exist")]
+    StateOutOfRange(usize),
 }
 
 impl<'a> From<&'a Dfa> for DfaEvaluator<'a> {
     fn from(value: &'a Dfa) -> Self {
-        let map = value
-            .alphabet
-            .iter()
-            .enumerate()
-            .map(|(idx, c)| (c as &str, idx))
-            .collect();
         Self {
             dfa: value,
-            rev_map: map,
             current_state: value.initial_state,
             unknown_elem_seen: false,
         }
@@ -0,0 +1,70 @@
+use crate::dfa::parse::DfaParseErrorOwned;
+use crate::dfa::{Dfa, DfaState};
+use crate::util::{valid_symbol, AlphabetIndex, Provenance, ReverseIndex, Version};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// The serialized fields of a [Dfa], which are validated before being turned into a [Dfa] since the invariants of a
+/// [Dfa] can't be expressed in its serialized form
+#[derive(Deserialize)]
+pub(crate) struct DfaFields {
+    alphabet: Vec<Rc<str>>,
+    states: Vec<DfaState>,
+    initial_state: usize,
+}
+
+impl TryFrom<DfaFields> for Dfa {
+    type Error = DfaParseErrorOwned;
+
+    fn try_from(value: DfaFields) -> Result<Self, Self::Error> {
+        use DfaParseErrorOwned::*;
+        let DfaFields {
+            alphabet,
+            mut states,
+            initial_state,
+        } = value;
+
+        let mut seen = HashSet::new();
+        if let Some(symbol) = alphabet.iter().find(|&symbol| !seen.insert(symbol)) {
+            return Err(DuplicateAlphabetSymbol(symbol.to_string()));
+        }
+        if let Some(symbol) = alphabet.iter().find(|symbol| !valid_symbol(symbol)) {
+            return Err(InvalidAlphabetSymbol(symbol.to_string()));
+        }
+        let mut seen = HashSet::new();
+        if let Some(state) = states.iter().find(|&state| !seen.insert(&state.name)) {
+            return Err(DuplicateStateDefinition(state.name.to_string()));
+        }
+        if initial_state >= states.len() {
+            return Err(MissingInitialState);
+        }
+
+        for state in &states {
+            if state.transitions.len() != alphabet.len() {
+                return Err(WrongNumberOfTransitions(
+                    state.name.to_string(),
+                    state.transitions.len(),
+                    alphabet.len(),
+                ));
+            }
+            if let Some(to) = state.transitions.iter().find(|&&to| to >= states.len()) {
+                return Err(TransitionDoesNotExist(
+                    state.name.to_string(),
+                    to.to_string(),
+                ));
+            }
+        }
+        states[initial_state].initial = true;
+
+        Ok(Dfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state,
+            provenance: Provenance::none(),
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+        })
+    }
+}
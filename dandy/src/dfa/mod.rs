@@ -48,7 +48,8 @@
 //!
 //! ## Syntax
 //! The file format for DFAs is an UTF-8 encoded text file with more or less just a transition table.
-//! The first row of the file should contain all elements of the non-empty alphabet, space-separated. Then,
+//! The first row of the file should contain all elements of the alphabet, space-separated (an empty alphabet is
+//! written as just `state`, the header of the state column, see the [parser](crate::parser)). Then,
 //! there should be one row per state in the DFA (there must be at least one state), where each row contains
 //! these space-separated elements, in order:
 //! * Optionally `->` or `→`, if the state is the initial state
@@ -57,8 +58,8 @@
 //! * For each element of the alphabet specified in the header, in order, what state the Dfa transitions to from the
 //!   given state upon seeing that element
 //!
-//! `ε`, `eps`, `→`, `->` and `*` are reserved and may not be used as elements of the alphabet or names of
-//! states.
+//! `ε`, `eps`, `→`, `->` and `*` are reserved and may not be used as elements of the alphabet or names of states.
+//! Other tokens can be used (and reserved) instead by parsing with a [ParserConfig](crate::parser::ParserConfig).
 //!
 //! Additionally, these rules apply:
 //! * There must be exactly one (1) initial state
@@ -128,7 +129,7 @@
 //! We can convert the DFA to a NFA using [Dfa::to_nfa]. Since every DFA is already a NFA, this is a
 //! cheap and straight-forward operation (but since NFAs has a set of transitions per symbol rather than just a
 //! single transition, a NFA requires `Vec`s and isn't as cheap as a DFA). Note that some features are available
-//! for NFA:s only, such as enumerating words.
+//! for NFA:s only, such as enumerating words (apart from [Dfa::words_with_states], which converts the DFA itself).
 //!
 //! Additionally, a DFA can be minimized by [Dfa::minimize], which firstly removes all unreachable states, and then
 //! merges all non-distinguishable states. The minimization is unique. The algorithm involves multiple steps, some of
@@ -143,6 +144,9 @@
 //!   * Remapping states (not exposed), and
 //!   * Removing states (not exposed)
 //!
+//! To merge the non-distinguishable states while keeping some states (for example, states with a meaning of their
+//! own), use [Dfa::merge_nondistinguishable_states_except].
+//!
 //! We can also invert a DFA by doing [Dfa::invert], which simply makes accepting states non-accepting and non-accepting
 //! states accepting. After inverting a DFA, it will accept all strings not previously accepted, and rejects all strings
 //! it previously accepted (assuming the strings are of the alphabet).
@@ -197,6 +201,12 @@
 //! * [Dfa::symmetric_difference] - Binary `xor`, `A.symmetric_difference(&B)` accepts all strings accepted by
 //!   `A` or by `B` but not by both
 //!
+//! To combine more than two DFAs at once, use [Dfa::product_many], which explores tuples of states directly instead
+//! of building intermediate products, or its convenience functions [Dfa::union_many] and [Dfa::intersection_many].
+//!
+//! Since the product of two large DFAs may be huge, [Dfa::product_construction_bounded] gives up once the product has
+//! more states than a given bound, and can report its progress while exploring.
+//!
 //! ### Checking equivalence
 //! Two DFAs `A` and `B` are equivalent if and only if they have the same alphabet and accept the same language.
 //! There are two ways to check equivalence between two DFAs: either by running [Dfa::equivalent_to] or by
@@ -205,49 +215,192 @@
 //! [Dfa::equivalent_to] leads to a performance penalty of around 3964%, since [Dfa::equivalent_to] doesn't actually
 //! construct any new automatas.
 //!
+//! A stricter check is [Dfa::isomorphic_to], which checks if two DFAs are the same machine with states renamed.
+//!
+//! ### JSON
+//! With the `serde` feature enabled, DFAs can be serialized with any serde format, or to and from JSON directly with
+//! [Dfa::to_json_string] and [Dfa::from_json_str]. A DFA is represented as an object with its `alphabet`, its
+//! `states` and the index of its `initial_state`. Each state has a `name`, is `accepting` or not, and has one
+//! transition per element of the alphabet, given as the index of the state it leads to. This DFA accepting all words
+//! ending with an `a`
+//! ```text
+//!        a  b
+//! -> s0 s1 s0
+//!  * s1 s1 s0
+//! ```
+//! is represented as:
+//! ```json
+//! {
+//!   "alphabet": ["a", "b"],
+//!   "states": [
+//!     { "name": "s0", "accepting": false, "transitions": [1, 0] },
+//!     { "name": "s1", "accepting": true, "transitions": [1, 0] }
+//!   ],
+//!   "initial_state": 0
+//! }
+//! ```
+//!
 //! ### Additional operations
 //! In addition to the above-mentioned operations, you can:
-//! * [Get the alphabet](Dfa::alphabet) of the DFA,
+//! * [Get the alphabet](Dfa::alphabet) of the DFA, and [the index](Dfa::alphabet_index) of an element in it,
 //! * [Get the states](Dfa::states) and [initial state](Dfa::initial_state) of the DFA,
+//! * Get the [transition table](Dfa::transition_table), [accepting states](Dfa::accepting_vector) and
+//!   [initial state](Dfa::initial_state_vector) as plain vectors of indices and booleans,
 //! * [Convert it to a table](Dfa::to_table), possibly [in ascii-only](Dfa::ascii_table), both of which
 //!   can be parsed by Dandy into this very same DFA again,
+//! * [Convert it to a table with a custom column separator](Dfa::to_table_with_separator), such as
+//!   [pipes](Dfa::to_table_pipe_separated) for readability,
 //! * Find all [reachable](Dfa::reachable_states) and [non-reachable](Dfa::unreachable_states) states,
+//! * [Count the accepted words](Dfa::count_words_of_length) of a given length, or
+//!   [up to a given length](Dfa::count_words_up_to_length), without enumerating them,
+//! * [Enumerate the accepted words](Dfa::words_with_states) together with the state each of them ends in,
+//! * Find the [closest accepted word](Dfa::closest_accepted) to a given word, in terms of edit distance,
+//! * Construct the [left](Dfa::left_quotient) and [right](Dfa::right_quotient) quotients of its language by the
+//!   language of another DFA,
+//! * Construct the [concatenation](Dfa::concatenate) with another DFA and the [Kleene star](Dfa::star) as minimized
+//!   DFAs, which is done by the subset construction,
+//! * Find the [classes of equivalent elements](Dfa::symbol_equivalence_classes) of the alphabet, and
+//!   [merge them](Dfa::merge_equivalent_symbols) while still [accepting the same words](Dfa::accepts_with_symbol_map),
+//! * Compute a [structural diff](Dfa::structural_diff) to another DFA, showing which states correspond to each other
+//!   and which transitions differ,
+//! * [Trace the exploration](Dfa::equivalence_trace) of pairs of states done when checking equivalence to another
+//!   DFA, along with the word leading to each pair,
+//! * Compute the [size of the minimal DFA](Dfa::minimal_size) without minimizing it,
+//! * Compute a [fingerprint of its language](Dfa::language_fingerprint), to group DFAs by their languages,
+//! * Step backwards, by finding the [pre-image](Dfa::preimage) of a set of states on an element of the alphabet or
+//!   the [predecessors](Dfa::predecessors) of a state,
+//! * Count the [states](Dfa::states_count), [alphabet elements](Dfa::alphabet_size),
+//!   [transitions](Dfa::transition_count) and [accepting states](Dfa::accepting_state_count), or
+//!   [estimate the memory footprint](Dfa::approx_memory_bytes) of the DFA,
 //! * [Clone](Dfa::clone) it, which isn't super expensive since the alphabet and state names doesn't need new
 //!   allocations to be cloned (no strings at all are actually copied, just some `vec`s with `bool`s and `usize`s)
+use crate::analysis::Coverage;
+use crate::nfa::words::WordsWithStates;
 use crate::nfa::{Nfa, NfaState};
 pub use crate::parser::dfa as parse;
-use crate::table::Table;
-use crate::util::alphabet_equal;
-pub use eval::DfaEvaluator;
-pub use parse::DfaParseError;
-use std::collections::{HashMap, HashSet};
+use crate::parser::{ParsedDfaOwned, ParsedDfaStateOwned, TableStyle};
+use crate::table::{self, quote_cell, Table};
+use crate::util::{self, alphabet_equal, AlphabetIndex, Fnv64, Provenance, ReverseIndex, Version};
+pub use crate::util::{AlphabetDiff, BfsEvent, IndexRemap};
+pub use count::SaturatingCount;
+pub use diff::{StructuralDiff, TransitionMismatch};
+pub use eval::{CheckpointMismatch, DfaEvaluator, EvaluatorCheckpoint};
+#[cfg(feature = "normalization")]
+pub use normalize::{NormalizationError, NormalizationForm};
+pub use parse::{DfaParseError, DfaParseErrorOwned};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::mem;
+use std::ops::ControlFlow;
 use std::rc::Rc;
+pub use symbols::{MappingError, SymbolMap};
+use thiserror::Error;
 use unicode_segmentation::UnicodeSegmentation;
 
+mod count;
+mod diff;
+mod edit;
 pub mod eval;
+#[cfg(feature = "serde")]
+mod json;
+#[cfg(feature = "normalization")]
+mod normalize;
 pub mod parse;
+mod quotient;
+pub(crate) mod symbols;
+
+/// The separator between the names of the states of a class merged by [Dfa::minimize_named]
+const MERGED_NAME_SEPARATOR: char = '=';
+/// The number of characters after which the name of a class merged by [Dfa::minimize_named] is truncated
+const MAX_MERGED_NAME_LEN: usize = 32;
 
 /// A [Deterministic finite automaton](https://en.wikipedia.org/wiki/Deterministic_finite_automaton),
 /// defined by its *alphabet*, a *set of states*, one of the states being its *initial state*, a subset of its states
 /// being *final states*/*accepting states*, and a *transition function* from each state upon seeing each element of
 /// the alphabet to any state. See the [module-level documentation](crate::dfa) for more info.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "json::DfaFields"))]
 pub struct Dfa {
     pub(crate) alphabet: Rc<[Rc<str>]>,
     pub(crate) states: Vec<DfaState>,
     pub(crate) initial_state: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) provenance: Provenance,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) alphabet_lookup: AlphabetIndex,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) version: Version,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) reverse_transitions: ReverseIndex,
 }
 
 /// A state in a DFA automata, which consists of its name, if it is the initial state or not, if it is accepting
-/// or not, and the transition for each element of the alphabet
+/// or not, its priority (if any), and the transition for each element of the alphabet
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DfaState {
     pub(crate) name: Rc<str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) initial: bool,
     pub(crate) accepting: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) priority: Option<u32>,
     pub(crate) transitions: Vec<usize>,
 }
 
+/// An operation on the languages of two DFAs which is computed by a product construction, see
+/// [Dfa::product_operation_bounded]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProductOperation {
+    /// The words accepted by either DFA, see [Dfa::union]
+    Union,
+    /// The words accepted by both DFAs, see [Dfa::intersection]
+    Intersection,
+    /// The words accepted by the first DFA but not by the second, see [Dfa::difference]
+    Difference,
+    /// The words accepted by exactly one of the DFAs, see [Dfa::symmetric_difference]
+    SymmetricDifference,
+}
+
+impl ProductOperation {
+    /// Checks if a pair of states of the two DFAs is accepting in the product, given if each of them is accepting
+    pub fn accepts(self, first: bool, second: bool) -> bool {
+        match self {
+            ProductOperation::Union => first || second,
+            ProductOperation::Intersection => first && second,
+            ProductOperation::Difference => first && !second,
+            ProductOperation::SymmetricDifference => first != second,
+        }
+    }
+
+    fn combinator(self) -> impl FnMut(&DfaState, &DfaState) -> bool {
+        move |s1, s2| self.accepts(s1.accepting, s2.accepting)
+    }
+}
+
+/// How often [Dfa::product_construction_bounded] reports its progress, in explored pairs of states
+pub const PRODUCT_PROGRESS_INTERVAL: usize = 1024;
+
+/// The error given by [Dfa::product_construction_bounded] when the product has more states than allowed
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error(
+    "The product construction exceeded the bound of {max_states} states after exploring {explored} pairs of states"
+)]
+pub struct ProductTooLarge {
+    /// The maximum number of states which was exceeded
+    pub max_states: usize,
+    /// The number of pairs of states which were fully explored before giving up
+    pub explored: usize,
+    /// The number of states of the first DFA times the number of states of the second one, which bounds the size of
+    /// the product (`None` if that overflows)
+    pub theoretical_max: Option<usize>,
+}
+
 impl DfaState {
     /// Gets the name of this state
     pub fn name(&self) -> &str {
@@ -264,18 +417,27 @@ impl DfaState {
         self.accepting
     }
 
+    /// Gets the priority of this state, if it has one. Priorities tell which token an accepting state belongs to
+    /// when DFAs of several tokens are combined, where a lower value is a higher priority. See
+    /// [Dfa::set_priority] and [Dfa::longest_match_with_priority].
+    pub fn priority(&self) -> Option<u32> {
+        self.priority
+    }
+
     /// Gets a list of transitions, as state indices for each element of the alphabet, in the alphabet's ordering
     pub fn transitions(&self) -> &[usize] {
         self.transitions.as_slice()
     }
 }
 
+/// Converts a DFA state to a NFA state with the same transitions, dropping its priority since NFA states have none
 impl From<DfaState> for NfaState {
     fn from(value: DfaState) -> Self {
         let DfaState {
             name,
             initial,
             accepting,
+            priority: _,
             transitions,
         } = value;
         NfaState {
@@ -294,6 +456,13 @@ impl From<Dfa> for Nfa {
     }
 }
 
+impl Display for Dfa {
+    /// Formats the DFA as the table given by [Dfa::to_table]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table())
+    }
+}
+
 impl Dfa {
     /// Inverts this automata, which makes accepting states non-accepting and non-accepting states accepting. This means
     /// that the automata after the inversion accepts all strings not previously accepted, and rejects all strings
@@ -315,11 +484,43 @@ impl Dfa {
     /// assert!(contains_a.accepts_graphemes("bcb"));
     /// ```
     pub fn invert(&mut self) {
+        self.version.bump();
         self.states
             .iter_mut()
             .for_each(|s| s.accepting = !s.accepting)
     }
 
+    /// Sets the priority of all accepting states of this DFA, such as the priority of the token it recognizes. When
+    /// DFAs of several tokens are combined with [Dfa::union], a state accepting for more than one of them gets the
+    /// highest priority (the lowest value) of them, and [Dfa::longest_match_with_priority] tells which token matched.
+    /// Non-accepting states keep their priorities.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let mut dfa: Dfa = parser::dfa("   a\n-> s t\n * t t").unwrap().try_into().unwrap();
+    /// dfa.set_priority(Some(3));
+    /// assert_eq!(dfa.states()[0].priority(), None);
+    /// assert_eq!(dfa.states()[1].priority(), Some(3));
+    /// ```
+    pub fn set_priority(&mut self, priority: Option<u32>) {
+        self.version.bump();
+        self.states
+            .iter_mut()
+            .filter(|s| s.accepting)
+            .for_each(|s| s.priority = priority)
+    }
+
+    /// Sets the priority of the state with the given index, see [Dfa::set_priority].
+    ///
+    /// # Panics
+    /// Panics if there is no state with the given index
+    pub fn set_state_priority(&mut self, state: usize, priority: Option<u32>) {
+        self.version.bump();
+        self.states[state].priority = priority;
+    }
+
     /// Constructs the union of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted by
     /// the first, second or both DFAs. This returns `None` if and only if the alphabets of the two DFAs are unequal
     /// (not considering ordering).
@@ -348,7 +549,35 @@ impl Dfa {
     /// assert!(any.accepts_graphemes("bb"));
     /// ```
     pub fn union(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting || s2.accepting)
+        self.union_with_priority(other, min_priority)
+    }
+
+    /// Constructs the union of two DFAs like [Dfa::union], but resolves the priority of each state with `resolver`
+    /// rather than by taking the highest priority (the lowest value). See [Dfa::product_construction_with_priority]
+    /// for how `resolver` is called.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let mut ends_with_a: Dfa = parser::dfa("   a b\n-> n y n\n * y y n").unwrap().try_into().unwrap();
+    /// let mut ends_with_b: Dfa = parser::dfa("   a b\n-> n n y\n * y n y").unwrap().try_into().unwrap();
+    /// ends_with_a.set_priority(Some(0));
+    /// ends_with_b.set_priority(Some(1));
+    /// // The last one wins
+    /// let any = ends_with_a.union_with_priority(&ends_with_b, |p1, p2| p2.or(p1)).unwrap();
+    /// assert_eq!(any.longest_match_with_priority("b").unwrap().1, 1);
+    /// ```
+    pub fn union_with_priority(
+        &self,
+        other: &Self,
+        resolver: impl FnMut(Option<u32>, Option<u32>) -> Option<u32>,
+    ) -> Option<Self> {
+        self.product_construction_with_priority(
+            other,
+            ProductOperation::Union.combinator(),
+            resolver,
+        )
     }
 
     /// Constructs the intersection of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted
@@ -379,7 +608,7 @@ impl Dfa {
     /// assert!(!both.accepts_graphemes("bb"));
     /// ```
     pub fn intersection(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting && s2.accepting)
+        self.product_construction(other, ProductOperation::Intersection.combinator())
     }
 
     /// Constructs the difference of two DFAs, that is, a new DFA that accepts exactly those strings that are accepted
@@ -410,7 +639,7 @@ impl Dfa {
     /// assert!(!a_not_b.accepts_graphemes("bb"));
     /// ```
     pub fn difference(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting && !s2.accepting)
+        self.product_construction(other, ProductOperation::Difference.combinator())
     }
 
     /// Constructs the symmetric difference of two DFAs, that is, a new DFA that accepts exactly those strings that are
@@ -441,7 +670,231 @@ impl Dfa {
     /// assert!(a_or_b.accepts_graphemes("bb"));
     /// ```
     pub fn symmetric_difference(&self, other: &Self) -> Option<Self> {
-        self.product_construction(other, |s1, s2| s1.accepting != s2.accepting)
+        self.product_construction(other, ProductOperation::SymmetricDifference.combinator())
+    }
+
+    /// Constructs the left quotient of the language of this DFA by the language of another DFA, that is, a NFA
+    /// accepting the words `w` such that `uw` is accepted by this DFA for some word `u` accepted by `other`. This
+    /// returns `None` if and only if the alphabets of the two DFAs are unequal (not considering ordering).
+    ///
+    /// The NFA has the states and transitions of this DFA, and a new initial state with ε-moves to every state some
+    /// word accepted by `other` leads to, which are found by exploring the pairs of states of the two DFAs.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let a_then_b: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s0 s1 s3
+    ///    s1 s1 s2
+    ///  * s2 s3 s2
+    ///    s3 s3 s3").unwrap().try_into().unwrap(); // a+b+
+    /// let aa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> t0 t1 t3
+    ///    t1 t2 t3
+    ///  * t2 t3 t3
+    ///    t3 t3 t3").unwrap().try_into().unwrap(); // aa
+    /// let quotient = a_then_b.left_quotient(&aa).unwrap(); // a*b+
+    /// assert!(quotient.accepts_graphemes("b"));
+    /// assert!(quotient.accepts_graphemes("aab"));
+    /// assert!(!quotient.accepts_graphemes("a"));
+    /// ```
+    pub fn left_quotient(&self, other: &Dfa) -> Option<Nfa> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        let initial_states = quotient::left_quotient_states(self, other);
+
+        let mut nfa = self.clone().to_nfa();
+        let new_initial_state = NfaState {
+            name: nfa.fresh_name("s_new"),
+            initial: true,
+            accepting: false,
+            epsilon_transitions: initial_states,
+            transitions: vec![vec![]; nfa.alphabet.len()],
+        };
+        nfa.states[nfa.initial_state].initial = false;
+        nfa.initial_state = nfa.states.len();
+        nfa.states.push(new_initial_state);
+        nfa.provenance = Provenance::new(|| {
+            format!(
+                "left quotient of ({}, {})",
+                self.provenance.describe("DFA"),
+                other.provenance.describe("DFA")
+            )
+        });
+        Some(nfa)
+    }
+
+    /// Constructs the right quotient of the language of this DFA by the language of another DFA, that is, a DFA
+    /// accepting the words `w` such that `wu` is accepted by this DFA for some word `u` accepted by `other`. This
+    /// returns `None` if and only if the alphabets of the two DFAs are unequal (not considering ordering).
+    ///
+    /// Unlike the [left quotient](Dfa::left_quotient), this is still a DFA: it is this DFA where the accepting states
+    /// are the states from which some word accepted by `other` leads to an accepting state.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let a_then_b: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s0 s1 s3
+    ///    s1 s1 s2
+    ///  * s2 s3 s2
+    ///    s3 s3 s3").unwrap().try_into().unwrap(); // a+b+
+    /// let bb: Dfa = parser::dfa("
+    ///        a  b
+    /// -> t0 t3 t1
+    ///    t1 t3 t2
+    ///  * t2 t3 t3
+    ///    t3 t3 t3").unwrap().try_into().unwrap(); // bb
+    /// let quotient = a_then_b.right_quotient(&bb).unwrap(); // a+b*
+    /// assert!(quotient.accepts_graphemes("a"));
+    /// assert!(quotient.accepts_graphemes("aab"));
+    /// assert!(!quotient.accepts_graphemes("b"));
+    /// ```
+    pub fn right_quotient(&self, other: &Dfa) -> Option<Dfa> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        let accepting = quotient::right_quotient_accepting(self, other);
+
+        let mut dfa = self.clone();
+        dfa.states
+            .iter_mut()
+            .zip(accepting)
+            .for_each(|(state, accepting)| state.accepting = accepting);
+        dfa.provenance = Provenance::new(|| {
+            format!(
+                "right quotient of ({}, {})",
+                self.provenance.describe("DFA"),
+                other.provenance.describe("DFA")
+            )
+        });
+        Some(dfa)
+    }
+
+    /// Constructs the concatenation of the languages of this DFA and another DFA, that is, a DFA accepting the words
+    /// `uw` such that `u` is accepted by this DFA and `w` is accepted by `other`. This returns `None` if and only if
+    /// the alphabets of the two DFAs are unequal (not considering ordering).
+    ///
+    /// This is done by constructing a NFA with the states of both DFAs and ε-moves from the accepting states of this
+    /// DFA to the initial state of `other`, and converting it back to a DFA by the subset construction. The result is
+    /// minimized, with its states named sequentially from 0 in breadth-first order. Note that the subset construction
+    /// may give exponentially many states (in the number of states of `other`) before minimization.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let a_plus: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s0 s1 s2
+    ///  * s1 s1 s2
+    ///    s2 s2 s2").unwrap().try_into().unwrap();
+    /// let b: Dfa = parser::dfa("
+    ///        b  a
+    /// -> t0 t1 t2
+    ///  * t1 t2 t2
+    ///    t2 t2 t2").unwrap().try_into().unwrap();
+    /// let a_plus_b = a_plus.concatenate(&b).unwrap();
+    /// assert!(a_plus_b.accepts_graphemes("ab"));
+    /// assert!(a_plus_b.accepts_graphemes("aaab"));
+    /// assert!(!a_plus_b.accepts_graphemes("b"));
+    /// assert!(!a_plus_b.accepts_graphemes("abb"));
+    /// ```
+    pub fn concatenate(&self, other: &Dfa) -> Option<Dfa> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        // The index in 'other's alphabet of each element of 'self's alphabet
+        let symbols = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet_index(elem).unwrap())
+            .collect::<Vec<_>>();
+        let offset = self.states.len();
+        let other_initial = other.initial_state + offset;
+
+        let mut nfa = self.clone().to_nfa();
+        for state in nfa.states.iter_mut().filter(|s| s.accepting) {
+            state.accepting = false;
+            state.epsilon_transitions.push(other_initial);
+        }
+        nfa.states.extend(other.states.iter().map(|state| {
+            NfaState {
+                name: state.name.clone(),
+                initial: false,
+                accepting: state.accepting,
+                epsilon_transitions: vec![],
+                transitions: symbols
+                    .iter()
+                    .map(|&idx| vec![state.transitions[idx] + offset])
+                    .collect(),
+            }
+        }));
+
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize();
+        dfa.renumber_states();
+        dfa.provenance = Provenance::new(|| {
+            format!(
+                "concatenation of ({}, {})",
+                self.provenance.describe("DFA"),
+                other.provenance.describe("DFA")
+            )
+        });
+        Some(dfa)
+    }
+
+    /// Constructs the Kleene star of the language of this DFA, that is, a DFA accepting the concatenations of any
+    /// number of words accepted by this DFA. The empty word is always accepted.
+    ///
+    /// This is done by constructing a NFA with the states of this DFA, ε-moves from the accepting states to the
+    /// initial state and a new accepting initial state, and converting it back to a DFA by the subset construction.
+    /// The result is minimized, with its states named sequentially from 0 in breadth-first order. Note that the subset
+    /// construction may give exponentially many states before minimization.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ab: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s0 s1 s3
+    ///    s1 s3 s2
+    ///  * s2 s3 s3
+    ///    s3 s3 s3").unwrap().try_into().unwrap();
+    /// let ab_star = ab.star();
+    /// assert!(ab_star.accepts_graphemes(""));
+    /// assert!(ab_star.accepts_graphemes("abab"));
+    /// assert!(!ab_star.accepts_graphemes("aba"));
+    /// ```
+    pub fn star(&self) -> Dfa {
+        let mut nfa = self.clone().to_nfa();
+        let old_initial = nfa.initial_state;
+        for state in nfa.states.iter_mut().filter(|s| s.accepting) {
+            state.epsilon_transitions.push(old_initial);
+        }
+        let new_initial_state = NfaState {
+            name: nfa.fresh_name("s_new"),
+            initial: true,
+            accepting: true,
+            epsilon_transitions: vec![old_initial],
+            transitions: vec![vec![]; nfa.alphabet.len()],
+        };
+        nfa.states[old_initial].initial = false;
+        nfa.initial_state = nfa.states.len();
+        nfa.states.push(new_initial_state);
+
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize();
+        dfa.renumber_states();
+        dfa.provenance = Provenance::new(|| format!("star of {}", self.provenance.describe("DFA")));
+        dfa
     }
 
     /// Constructs a new DFA from two DFAs using the product construction. That is a new DFA with states corresponding
@@ -449,15 +902,108 @@ impl Dfa {
     /// state or not is given by the `combinator` function, combining the state from the first parser and the second
     /// parser. `self.product_construction(other, |s1, s2| s1.is_accepting() && s2.is_accepting())` corresponds to
     /// the intersection between the two.
+    ///
+    /// The alphabet of `self` always wins: the constructed DFA has the alphabet of `self` in the same order, no matter
+    /// the order of the alphabet of `other`. Thus, the column order of its table, and the order in which its words are
+    /// enumerated, follows `self`. This applies to [Dfa::union], [Dfa::intersection], [Dfa::difference] and
+    /// [Dfa::symmetric_difference] as well, since they are all product constructions. To get another order, see
+    /// [Nfa::with_alphabet_order].
+    ///
+    /// Accepting states of the constructed DFA get the highest [priority](DfaState::priority) (the lowest value) of
+    /// the accepting states they consist of, see [Dfa::product_construction_with_priority].
     pub fn product_construction(
         &self,
         other: &Self,
-        mut combinator: impl FnMut(&DfaState, &DfaState) -> bool,
+        combinator: impl FnMut(&DfaState, &DfaState) -> bool,
+    ) -> Option<Self> {
+        self.product_construction_with_priority(other, combinator, min_priority)
+    }
+
+    /// Constructs a new DFA from two DFAs using the product construction like [Dfa::product_construction], but
+    /// resolves the [priority](DfaState::priority) of each accepting state with `resolver`. It is called with the
+    /// priorities of the states of the first and the second DFA, where the priority of a non-accepting state is
+    /// always given as `None`, since only accepting states tell which token matched. Non-accepting states of the
+    /// constructed DFA have no priority.
+    pub fn product_construction_with_priority(
+        &self,
+        other: &Self,
+        combinator: impl FnMut(&DfaState, &DfaState) -> bool,
+        resolver: impl FnMut(Option<u32>, Option<u32>) -> Option<u32>,
     ) -> Option<Self> {
+        self.product_construction_inner(other, combinator, resolver, usize::MAX, None)
+            .map(|result| result.expect("the product can't have more than usize::MAX states"))
+    }
+
+    /// Like [Dfa::product_construction], but gives up with a [ProductTooLarge] error as soon as more than
+    /// `max_states` pairs of states have been found, instead of allocating a possibly huge product. If `progress` is
+    /// given, it is called with the number of pairs explored so far after every [PRODUCT_PROGRESS_INTERVAL] explored
+    /// pairs. As for the unbounded version, this returns `None` if the alphabets of the two DFAs are unequal (not
+    /// considering ordering), which is checked before anything is explored.
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, DfaState};
+    /// use dandy::parser;
+    ///
+    /// let mod_2: Dfa = parser::dfa("  a\n-> * s0 s1\n s1 s0").unwrap().try_into().unwrap();
+    /// let mod_3: Dfa = parser::dfa("  a\n-> * s0 s1\n s1 s2\n s2 s0").unwrap().try_into().unwrap();
+    /// let accepting = |s1: &DfaState, s2: &DfaState| s1.is_accepting() && s2.is_accepting();
+    /// let mod_6 = mod_2.product_construction_bounded(&mod_3, accepting, 6, None).unwrap().unwrap();
+    /// assert_eq!(mod_6.states().len(), 6);
+    ///
+    /// let error = mod_2.product_construction_bounded(&mod_3, accepting, 4, None).unwrap().unwrap_err();
+    /// assert_eq!(error.max_states, 4);
+    /// assert_eq!(error.theoretical_max, Some(6));
+    /// ```
+    pub fn product_construction_bounded(
+        &self,
+        other: &Self,
+        combinator: impl FnMut(&DfaState, &DfaState) -> bool,
+        max_states: usize,
+        progress: Option<&mut dyn FnMut(usize)>,
+    ) -> Option<Result<Self, ProductTooLarge>> {
+        self.product_construction_inner(other, combinator, min_priority, max_states, progress)
+    }
+
+    /// Constructs the union, intersection, difference or symmetric difference of two DFAs (see [ProductOperation]),
+    /// giving up as soon as more than `max_states` pairs of states have been found like
+    /// [Dfa::product_construction_bounded].
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, ProductOperation};
+    /// use dandy::parser;
+    ///
+    /// let mod_2: Dfa = parser::dfa("  a\n-> * s0 s1\n s1 s0").unwrap().try_into().unwrap();
+    /// let mod_3: Dfa = parser::dfa("  a\n-> * s0 s1\n s1 s2\n s2 s0").unwrap().try_into().unwrap();
+    /// let either = mod_2.product_operation_bounded(&mod_3, ProductOperation::Union, 6, None).unwrap().unwrap();
+    /// assert_eq!(Some(either), mod_2.union(&mod_3));
+    /// ```
+    pub fn product_operation_bounded(
+        &self,
+        other: &Self,
+        operation: ProductOperation,
+        max_states: usize,
+        progress: Option<&mut dyn FnMut(usize)>,
+    ) -> Option<Result<Self, ProductTooLarge>> {
+        self.product_construction_bounded(other, operation.combinator(), max_states, progress)
+    }
+
+    fn product_construction_inner(
+        &self,
+        other: &Self,
+        mut combinator: impl FnMut(&DfaState, &DfaState) -> bool,
+        mut resolver: impl FnMut(Option<u32>, Option<u32>) -> Option<u32>,
+        max_states: usize,
+        mut progress: Option<&mut dyn FnMut(usize)>,
+    ) -> Option<Result<Self, ProductTooLarge>> {
         //if the alphabets are different, they aren't equivalent
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
             return None;
         }
+        let too_large = |explored: usize| ProductTooLarge {
+            max_states,
+            explored,
+            theoretical_max: self.states.len().checked_mul(other.states.len()),
+        };
 
         // initially, we explore the (pair of) initial states
         let mut evaluators_to_explore = vec![(self.evaluator(), other.evaluator())];
@@ -466,8 +1012,11 @@ impl Dfa {
         let q2 = other.initial_state;
         let mut explored_states = HashSet::new();
         explored_states.insert((q1, q2));
+        if max_states == 0 {
+            return Some(Err(too_large(0)));
+        }
 
-        // maps (q1, q2) to accepting?
+        // maps (q1, q2) to accepting? and priority
         let mut state_data = vec![];
 
         while let Some((s1, s2)) = evaluators_to_explore.pop() {
@@ -480,41 +1029,46 @@ impl Dfa {
                 let states = (d1.current_state_idx(), d2.current_state_idx());
                 transition_list.push(states);
                 if explored_states.insert(states) {
+                    if explored_states.len() > max_states {
+                        return Some(Err(too_large(state_data.len())));
+                    }
                     evaluators_to_explore.push((d1, d2));
                 }
             }
 
+            let (state1, state2) = (s1.current_state().unwrap(), s2.current_state().unwrap());
+            let accepting = combinator(state1, state2);
+            let priority = accepting
+                .then(|| resolver(accepting_priority(state1), accepting_priority(state2)))
+                .flatten();
             state_data.push((
                 (s1.current_state_idx(), s2.current_state_idx()),
-                combinator(s1.current_state().unwrap(), s2.current_state().unwrap()),
+                (accepting, priority),
                 transition_list,
             ));
+            if state_data.len() % PRODUCT_PROGRESS_INTERVAL == 0 {
+                if let Some(progress) = progress.as_mut() {
+                    progress(state_data.len());
+                }
+            }
         }
 
-        // Try to generate new names for states
-        let names = {
-            let mut hm = HashSet::new();
-            let potential_names = explored_states
-                .iter()
-                .map_while(|(s1, s2)| {
-                    let combined_name: Rc<str> = Rc::from(format!(
-                        "({},{})",
-                        self.states[*s1].name, other.states[*s2].name
-                    ));
-                    hm.insert(combined_name.clone())
-                        .then_some(((*s1, *s2), combined_name))
-                })
-                .collect::<HashMap<_, _>>();
-            if potential_names.len() < state_data.len() {
-                explored_states
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, (s1, s2))| ((*s1, *s2), Rc::from(format!("{idx}"))))
-                    .collect()
-            } else {
-                potential_names
-            }
-        };
+        // Try to generate new names for states, and if the names of some pairs collide, number the states in the
+        // order they were explored instead
+        let mut names = state_data
+            .iter()
+            .map(|((s1, s2), _, _)| {
+                Rc::from(format!(
+                    "({},{})",
+                    self.states[*s1].name, other.states[*s2].name
+                ))
+            })
+            .collect::<Vec<Rc<str>>>();
+        if names.iter().collect::<HashSet<_>>().len() < names.len() {
+            names = (0..names.len())
+                .map(|idx| Rc::from(idx.to_string()))
+                .collect();
+        }
 
         let rev_state_idx_map = state_data
             .iter()
@@ -527,31 +1081,211 @@ impl Dfa {
 
         let states = state_data
             .into_iter()
-            .map(|(states, accepting, transitions)| DfaState {
-                name: names
-                    .get(&states)
-                    .expect("All states should have a name")
-                    .clone(),
-                initial: states == (q1, q2),
-                accepting,
-                transitions: transitions
-                    .into_iter()
-                    .map(|states| {
-                        *rev_state_idx_map
-                            .get(&states)
-                            .expect("Each state pair with transition to it should have a idx")
-                    })
-                    .collect(),
-            })
+            .zip(names)
+            .map(
+                |((states, (accepting, priority), transitions), name)| DfaState {
+                    name,
+                    initial: states == (q1, q2),
+                    accepting,
+                    priority,
+                    transitions: transitions
+                        .into_iter()
+                        .map(|states| {
+                            *rev_state_idx_map
+                                .get(&states)
+                                .expect("Each state pair with transition to it should have a idx")
+                        })
+                        .collect(),
+                },
+            )
             .collect::<Vec<_>>();
-        Some(Dfa {
+        Some(Ok(Dfa {
             alphabet: self.alphabet.clone(),
             states,
             initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+            provenance: Provenance::new(|| {
+                format!(
+                    "product of ({}, {})",
+                    self.provenance.describe("DFA"),
+                    other.provenance.describe("DFA")
+                )
+            }),
+        }))
+    }
+
+    /// Constructs the union of any number of DFAs, that is, a new DFA that accepts exactly those strings that are
+    /// accepted by at least one of the DFAs. This returns `None` if `dfas` is empty or if the alphabets of the DFAs
+    /// are unequal (not considering ordering). See [Dfa::product_many] for details.
+    pub fn union_many(dfas: &[&Dfa]) -> Option<Self> {
+        Self::product_many(dfas, |states| states.iter().any(|s| s.accepting))
+    }
+
+    /// Constructs the intersection of any number of DFAs, that is, a new DFA that accepts exactly those strings that
+    /// are accepted by all of the DFAs. This returns `None` if `dfas` is empty or if the alphabets of the DFAs are
+    /// unequal (not considering ordering). See [Dfa::product_many] for details.
+    pub fn intersection_many(dfas: &[&Dfa]) -> Option<Self> {
+        Self::product_many(dfas, |states| states.iter().all(|s| s.accepting))
+    }
+
+    /// Constructs a new DFA from any number of DFAs using the product construction, like
+    /// [Dfa::product_construction] does for two DFAs. The states of the new DFA correspond to tuples of states the
+    /// DFAs would be in on any given input, and only reachable tuples are explored. If such a state is accepting or
+    /// not is given by the `combinator` function, which gets the states of the DFAs in the same order as `dfas`.
+    ///
+    /// Compared to folding [Dfa::product_construction] pairwise, no intermediate products are built, and the states
+    /// are named `(a,b,c)` rather than `((a,b),c)`. If those names aren't unique, the states are numbered instead.
+    /// The alphabet of the first DFA wins, just like the alphabet of `self` does in [Dfa::product_construction].
+    ///
+    /// This returns `None` if `dfas` is empty or if the alphabets of the DFAs are unequal (not considering
+    /// ordering).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let contains_a = "
+    ///      a b c
+    /// -> n y n n
+    ///  * y y y y";
+    /// let contains_b = "
+    ///      a b c
+    /// -> n n y n
+    ///  * y y y y";
+    /// let contains_c = "
+    ///      a b c
+    /// -> n n n y
+    ///  * y y y y";
+    /// let contains_a: Dfa = parser::dfa(contains_a).unwrap().try_into().unwrap();
+    /// let contains_b: Dfa = parser::dfa(contains_b).unwrap().try_into().unwrap();
+    /// let contains_c: Dfa = parser::dfa(contains_c).unwrap().try_into().unwrap();
+    ///
+    /// // 'two_of_three' accepts strings that contain at least two of a, b and c
+    /// let two_of_three = Dfa::product_many(&[&contains_a, &contains_b, &contains_c], |states| {
+    ///     states.iter().filter(|s| s.is_accepting()).count() >= 2
+    /// })
+    /// .unwrap();
+    /// assert!(!two_of_three.accepts_graphemes("aaa"));
+    /// assert!(two_of_three.accepts_graphemes("ab"));
+    /// assert!(two_of_three.accepts_graphemes("cba"));
+    /// assert_eq!(two_of_three.initial_state().name(), "(n,n,n)");
+    /// ```
+    pub fn product_many(
+        dfas: &[&Dfa],
+        mut combinator: impl FnMut(&[&DfaState]) -> bool,
+    ) -> Option<Self> {
+        let (first, rest) = dfas.split_first()?;
+        if rest
+            .iter()
+            .any(|dfa| !alphabet_equal(&first.alphabet, &dfa.alphabet))
+        {
+            return None;
+        }
+
+        // columns[i][j] is the column of the j:th element of the alphabet of the first DFA in the i:th DFA
+        let columns = dfas
+            .iter()
+            .map(|dfa| {
+                first
+                    .alphabet
+                    .iter()
+                    .map(|elem| dfa.alphabet_index(elem).expect("Alphabets should be equal"))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // tuple_idx maps a tuple of states to the index of the state it becomes, and tuples[idx] is the reverse of
+        // that. The tuples are explored breadth-first, so that index is the order in which they are found.
+        let initial = dfas.iter().map(|dfa| dfa.initial_state).collect::<Vec<_>>();
+        let mut tuple_idx = HashMap::from([(initial.clone(), 0)]);
+        let mut tuples = vec![initial];
+        let mut state_data = vec![];
+        let mut current = Vec::with_capacity(dfas.len());
+
+        while state_data.len() < tuples.len() {
+            let tuple = tuples[state_data.len()].clone();
+            current.clear();
+            current.extend(dfas.iter().zip(&tuple).map(|(dfa, &s)| &dfa.states[s]));
+            let accepting = combinator(&current);
+            let priority = accepting
+                .then(|| {
+                    current
+                        .iter()
+                        .map(|state| accepting_priority(state))
+                        .fold(None, min_priority)
+                })
+                .flatten();
+
+            let mut transitions = Vec::with_capacity(first.alphabet.len());
+            for j in 0..first.alphabet.len() {
+                let next = current
+                    .iter()
+                    .zip(&columns)
+                    .map(|(state, cols)| state.transitions[cols[j]])
+                    .collect::<Vec<_>>();
+                let len = tuples.len();
+                let idx = *tuple_idx.entry(next).or_insert_with_key(|next| {
+                    tuples.push(next.clone());
+                    len
+                });
+                transitions.push(idx);
+            }
+            state_data.push(((accepting, priority), transitions));
+        }
+
+        let mut names = tuples
+            .iter()
+            .map(|tuple| {
+                let parts = dfas
+                    .iter()
+                    .zip(tuple)
+                    .map(|(dfa, &s)| dfa.states[s].name.as_ref())
+                    .collect::<Vec<_>>();
+                Rc::from(format!("({})", parts.join(",")))
+            })
+            .collect::<Vec<Rc<str>>>();
+        if names.iter().collect::<HashSet<_>>().len() < names.len() {
+            names = (0..tuples.len())
+                .map(|idx| Rc::from(idx.to_string()))
+                .collect();
+        }
+
+        let states = state_data
+            .into_iter()
+            .zip(names)
+            .enumerate()
+            .map(
+                |(idx, (((accepting, priority), transitions), name))| DfaState {
+                    name,
+                    initial: idx == 0,
+                    accepting,
+                    priority,
+                    transitions,
+                },
+            )
+            .collect();
+        Some(Dfa {
+            alphabet: first.alphabet.clone(),
+            states,
+            initial_state: 0,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+            provenance: Provenance::new(|| {
+                let parts = dfas
+                    .iter()
+                    .map(|dfa| dfa.provenance.describe("DFA"))
+                    .collect::<Vec<_>>();
+                format!("product of ({})", parts.join(", "))
+            }),
         })
     }
 
-    /// Minimizes this DFA by first removing all unreachable states and then merging non-distinguishable states.
+    /// Minimizes this DFA by first removing all unreachable states and then merging non-distinguishable states. The
+    /// returned [IndexRemap] maps the indices of the states before the call to the indices after it, where merged
+    /// states map to the state they were merged into.
     /// ```
     /// use dandy::parser;
     /// use dandy::dfa::Dfa;
@@ -566,75 +1300,436 @@ impl Dfa {
     ///      d d d c
     /// ";
     /// let mut dfa: Dfa = parser::dfa(accepts_everything).unwrap().try_into().unwrap();
-    /// dfa.minimize();
+    /// let remap = dfa.minimize();
     /// assert_eq!(dfa.states().len(), 1);
+    /// assert_eq!(remap.old_to_new(0), Some(0));
+    /// assert_eq!(remap.old_to_new(1), None); // a is unreachable
     /// ```
-    pub fn minimize(&mut self) {
-        self.remove_unreachable_states();
-        self.merge_nondistinguishable_states();
+    pub fn minimize(&mut self) -> IndexRemap {
+        let reachable = self.remove_unreachable_states();
+        reachable.then(&self.merge_nondistinguishable_states())
     }
 
-    /// Merges the non-distinguishable states of this DFA such that every set of multiple non-distinguishable states
-    /// become just one. Which of multiple non-distinguishable states is left over is non-deterministic
-    pub fn merge_nondistinguishable_states(&mut self) {
-        let mapper = self
+    /// Minimizes this DFA like [Dfa::minimize], but names every state which other states were merged into after all
+    /// states of its class, so that it is clear where the states of the original table went. The name is the sorted
+    /// names of the class joined by `=`, such as `s1=s3`, which the table parser reads as one name. Since names may
+    /// get long, the name is truncated after 32 characters, followed by `=…+n` where `n` is the number of names left
+    /// out. If the name is already taken by another state, `'` is appended until it isn't. States which weren't merged
+    /// with any other state keep their names, and of each class, the first state (in the order of the states) is
+    /// kept, like [Dfa::minimize] does.
+    ///
+    /// This returns the classes which were merged, as the original name of each kept state mapped to the (sorted)
+    /// original names of the states merged into it.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let odd_as = "
+    ///        a  b
+    /// -> s1 s2 s1
+    ///  * s2 s3 s2
+    ///    s3 s4 s3
+    ///  * s4 s1 s4
+    /// ";
+    /// let mut dfa: Dfa = parser::dfa(odd_as).unwrap().try_into().unwrap();
+    /// let merges = dfa.minimize_named();
+    /// assert_eq!(dfa.states()[0].name(), "s1=s3");
+    /// assert_eq!(dfa.states()[1].name(), "s2=s4");
+    /// assert_eq!(merges["s1"], vec!["s3".into()]);
+    /// ```
+    pub fn minimize_named(&mut self) -> BTreeMap<Rc<str>, Vec<Rc<str>>> {
+        self.remove_unreachable_states();
+        self.version.bump();
+        let mut classes = self
             .state_equivalence_classes_idx()
             .into_iter()
-            .flat_map(|set| {
-                debug_assert!(!set.is_empty(), "Should not have empty equivalence classes");
-                let mut iter = set.into_iter();
-                let new = iter.next();
-                // safety: iter.map is lazy, so body is only executed if there are elements,
-                // which means new must be non-optional
-                iter.map(move |old| (old, unsafe { new.unwrap_unchecked() }))
+            .filter(|class| class.len() > 1)
+            .map(|class| {
+                let mut class = class.into_iter().collect::<Vec<_>>();
+                class.sort_unstable();
+                class
             })
-            .collect::<HashMap<_, _>>();
-        let map = |idx| mapper.get(&idx).copied();
-        self.remap_transitions(map);
-        if let Some(new_initial) = map(self.initial_state) {
-            self.initial_state = new_initial;
-            self.states[new_initial].initial = true;
+            .collect::<Vec<_>>();
+        classes.sort_unstable();
+
+        let mut taken = self
+            .states
+            .iter()
+            .map(|state| state.name.clone())
+            .collect::<HashSet<_>>();
+        let mut merges = BTreeMap::new();
+        let mut mapper = HashMap::new();
+        for class in classes {
+            let mut names = class
+                .iter()
+                .map(|&idx| self.states[idx].name.clone())
+                .collect::<Vec<_>>();
+            names.sort_unstable();
+            let mut name = merged_name(&names);
+            while taken.contains(name.as_str()) {
+                name.push('\'');
+            }
+            let name = Rc::<str>::from(name);
+            taken.insert(name.clone());
+
+            let kept = class[0];
+            let kept_name = mem::replace(&mut self.states[kept].name, name);
+            names.retain(|name| *name != kept_name);
+            merges.insert(kept_name, names);
+            mapper.extend(class[1..].iter().map(|&idx| (idx, kept)));
         }
-        let to_remove = mapper.into_keys().collect();
-        self.remove_states(to_remove);
+        self.merge_states(mapper);
+        merges
     }
 
-    /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states
-    pub fn state_equivalence_classes(&self) -> Vec<Vec<&DfaState>> {
-        self.state_equivalence_classes_idx()
+    /// Gives the classes of equivalent elements of the alphabet, where two elements are equivalent if every state
+    /// transitions to the same state on both of them. The classes are ordered by their first element, and the
+    /// elements of each class are in the order of the alphabet.
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, SymbolMap};
+    /// use dandy::parser;
+    ///
+    /// // Accepts numbers without leading zeroes, where all digits except 0 behave the same
+    /// let numbers = "
+    ///          0 1 2 3 4 5 6 7 8 9
+    /// -> s     z n n n n n n n n n
+    ///  * z     e e e e e e e e e e
+    ///  * n     n n n n n n n n n n
+    ///    e     e e e e e e e e e e
+    /// ";
+    /// let mut dfa: Dfa = parser::dfa(numbers).unwrap().try_into().unwrap();
+    /// let classes = dfa.symbol_equivalence_classes();
+    /// assert_eq!(classes.len(), 2);
+    /// assert_eq!(classes[0], vec!["0".into()]);
+    /// assert_eq!(classes[1].len(), 9);
+    ///
+    /// let merges = dfa.merge_equivalent_symbols();
+    /// assert_eq!(dfa.alphabet(), &["0".into(), "1".into()]);
+    /// assert!(merges.contains(&("7".into(), "1".into())));
+    /// let map = SymbolMap::new(&merges);
+    /// assert!(dfa.accepts_with_symbol_map(&["7", "0", "9"], &map));
+    /// assert!(!dfa.accepts_with_symbol_map(&["0", "9"], &map));
+    /// ```
+    pub fn symbol_equivalence_classes(&self) -> Vec<Vec<Rc<str>>> {
+        self.symbol_equivalence_classes_idx()
             .into_iter()
             .map(|class| {
                 class
                     .into_iter()
-                    .map(|state| &self.states[state])
-                    .collect::<Vec<_>>()
+                    .map(|idx| self.alphabet[idx].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn symbol_equivalence_classes_idx(&self) -> Vec<Vec<usize>> {
+        symbols::classes(self.alphabet.len(), |idx| {
+            self.states
+                .iter()
+                .map(|state| state.transitions[idx])
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Merges every [class of equivalent elements](Dfa::symbol_equivalence_classes) of the alphabet into its first
+    /// element, removing the other elements from the alphabet. The words over the remaining alphabet are accepted just
+    /// like before. Returns pairs of every removed element and the element it was merged into, which can be made
+    /// into a [SymbolMap] to evaluate words over the previous alphabet, see [Dfa::accepts_with_symbol_map].
+    pub fn merge_equivalent_symbols(&mut self) -> Vec<(Rc<str>, Rc<str>)> {
+        self.version.bump();
+        let classes = self.symbol_equivalence_classes_idx();
+        let merges = symbols::merges(&self.alphabet, &classes);
+        if merges.is_empty() {
+            return merges;
+        }
+        self.alphabet = classes
+            .iter()
+            .map(|class| self.alphabet[class[0]].clone())
+            .collect();
+        for state in &mut self.states {
+            state.transitions = classes
+                .iter()
+                .map(|class| state.transitions[class[0]])
+                .collect();
+        }
+        self.alphabet_lookup = AlphabetIndex::default();
+        self.reverse_transitions = ReverseIndex::default();
+        merges
+    }
+
+    /// Finds the elements of the alphabet which aren't part of any accepted word, that is, the elements on which
+    /// every transition from a reachable state leads to a state from which no accepting state can be reached. Removing
+    /// these elements from the alphabet with [Dfa::prune_symbols] doesn't change which words over the remaining
+    /// elements are accepted. The elements are given in the order of the alphabet.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // Accepts a followed by any number of b:s, where c always leads to the dead state
+    /// let input = "
+    ///        a b c
+    /// -> s   t d d
+    ///  * t   d t d
+    ///    d   d d d
+    /// ";
+    /// let mut dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.unused_symbols(), vec!["c".into()]);
+    /// assert_eq!(dfa.prune_symbols(), vec!["c".into()]);
+    /// assert_eq!(dfa.alphabet(), &["a".into(), "b".into()]);
+    /// assert!(dfa.accepts(&["a", "b", "b"]));
+    /// ```
+    pub fn unused_symbols(&self) -> Vec<Rc<str>> {
+        self.unused_symbols_idx()
+            .into_iter()
+            .map(|idx| self.alphabet[idx].clone())
+            .collect()
+    }
+
+    fn unused_symbols_idx(&self) -> Vec<usize> {
+        let live = self.live_states();
+        let reachable = self.reachable_state_idx();
+        (0..self.alphabet.len())
+            .filter(|&elem| {
+                !reachable
+                    .iter()
+                    .any(|&idx| live[self.states[idx].transitions[elem]])
+            })
+            .collect()
+    }
+
+    /// Removes the [unused elements](Dfa::unused_symbols) from the alphabet, and the transitions on them from every
+    /// state, returning the removed elements. This makes product constructions with other DFAs cheaper, since pairs
+    /// of states only reached through the unused elements aren't explored.
+    ///
+    /// Note that this changes the alphabet, and DFAs with different alphabets are never
+    /// [equivalent](Dfa::equivalent_to), so the pruned DFA isn't equivalent to the original DFA, nor can product
+    /// constructions be made between them. Only the words over the remaining elements are accepted like before.
+    /// Other automata sharing the alphabet of this DFA keep the alphabet they had.
+    pub fn prune_symbols(&mut self) -> Vec<Rc<str>> {
+        self.version.bump();
+        let unused = self.unused_symbols_idx();
+        if unused.is_empty() {
+            return vec![];
+        }
+        let kept = (0..self.alphabet.len())
+            .filter(|elem| !unused.contains(elem))
+            .collect::<Vec<_>>();
+        let removed = unused
+            .iter()
+            .map(|&idx| self.alphabet[idx].clone())
+            .collect();
+        self.alphabet = kept.iter().map(|&idx| self.alphabet[idx].clone()).collect();
+        for state in &mut self.states {
+            state.transitions = kept.iter().map(|&idx| state.transitions[idx]).collect();
+        }
+        self.alphabet_lookup = AlphabetIndex::default();
+        self.reverse_transitions = ReverseIndex::default();
+        removed
+    }
+
+    /// Finds the live states, that is, the states from which an accepting state can be reached, where `live[idx]`
+    /// tells if the state with index `idx` is live
+    fn live_states(&self) -> Vec<bool> {
+        let reverse = self.reverse_transitions();
+        let mut live = self.accepting_vector();
+        let mut stack = (0..self.states.len())
+            .filter(|&idx| live[idx])
+            .collect::<Vec<_>>();
+        while let Some(idx) = stack.pop() {
+            for &from in reverse[idx].iter().flatten() {
+                if !live[from] {
+                    live[from] = true;
+                    stack.push(from);
+                }
+            }
+        }
+        live
+    }
+
+    /// Checks if this DFA accepts the given string, after translating every element with the given [SymbolMap].
+    /// This accepts the same words as before [merging equivalent elements](Dfa::merge_equivalent_symbols) if the map
+    /// is made from the merges.
+    pub fn accepts_with_symbol_map(&self, string: &[&str], map: &SymbolMap) -> bool {
+        let mut eval = self.evaluator();
+        for elem in string {
+            if eval.step_mapped(elem, map).is_none() {
+                return false;
+            }
+        }
+        eval.is_accepting()
+    }
+
+    /// Checks if this DFA is minimal, that is, if every state is reachable and no two states are non-distinguishable.
+    /// This is the case exactly when [Dfa::minimize] wouldn't remove any states.
+    pub fn is_minimal(&self) -> bool {
+        self.unreachable_state_idx().is_empty()
+            && self.state_equivalence_classes_idx().len() == self.states.len()
+    }
+
+    /// Merges the non-distinguishable states of this DFA such that every set of multiple non-distinguishable states
+    /// become just one. Of multiple non-distinguishable states, the first one (in the order of the states) is left
+    /// over, and the returned [IndexRemap] maps the indices of all states before the call to the state they were
+    /// merged into.
+    pub fn merge_nondistinguishable_states(&mut self) -> IndexRemap {
+        self.version.bump();
+        let mapper = self
+            .state_equivalence_classes_idx()
+            .into_iter()
+            .flat_map(|class| {
+                let kept = class.iter().min().copied();
+                class
+                    .into_iter()
+                    .filter_map(move |old| kept.filter(|&kept| kept != old).map(|kept| (old, kept)))
+            })
+            .collect::<HashMap<_, _>>();
+        self.merge_states(mapper)
+    }
+
+    /// Merges the non-distinguishable states of this DFA like [Dfa::merge_nondistinguishable_states], except that the
+    /// states named in `protected` are never removed. If a set of non-distinguishable states contains protected
+    /// states, the other states of the set are merged into the first protected one (in the order of the states), and
+    /// the protected states are all kept, even though they are non-distinguishable. Otherwise, the states are merged
+    /// into the first state of the set. This returns the merges done, as pairs of the name of each removed state and
+    /// the name of the state it was merged into, in the order of the removed states.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// // s and t are non-distinguishable
+    /// let mut dfa: Dfa = dandy::parser::dfa("
+    ///        a b
+    ///   -> i s t
+    ///    * s s t
+    ///    * t s t
+    /// ").unwrap().try_into().unwrap();
+    /// let mut partially_minimized = dfa.clone();
+    /// let merges = partially_minimized.merge_nondistinguishable_states_except(&["t"]);
+    /// assert_eq!(merges, [("s".into(), "t".into())]);
+    /// assert!(partially_minimized.equivalent_to(&dfa));
+    ///
+    /// assert!(dfa.merge_nondistinguishable_states_except(&["s", "t"]).is_empty());
+    /// assert_eq!(dfa.states().len(), 3);
+    /// ```
+    pub fn merge_nondistinguishable_states_except(
+        &mut self,
+        protected: &[&str],
+    ) -> Vec<(Rc<str>, Rc<str>)> {
+        self.version.bump();
+        let is_protected = |idx: usize| protected.contains(&self.states[idx].name.as_ref());
+        let mut mapper = HashMap::new();
+        for class in self.state_equivalence_classes_idx() {
+            let mut class = class.into_iter().collect::<Vec<_>>();
+            class.sort_unstable();
+            let kept = class
+                .iter()
+                .copied()
+                .find(|&idx| is_protected(idx))
+                .unwrap_or(class[0]);
+            for idx in class {
+                if idx != kept && !is_protected(idx) {
+                    mapper.insert(idx, kept);
+                }
+            }
+        }
+        let mut merges = mapper
+            .iter()
+            .map(|(&removed, &kept)| (removed, kept))
+            .collect::<Vec<_>>();
+        merges.sort_unstable();
+        let merges = merges
+            .into_iter()
+            .map(|(removed, kept)| {
+                let name = |idx: usize| self.states[idx].name.clone();
+                (name(removed), name(kept))
+            })
+            .collect();
+        self.merge_states(mapper);
+        merges
+    }
+
+    /// Merges states into other states, by redirecting every transition to (and the initial state if it is) a key of
+    /// `mapper` to its value, and then removing the keys. The values must not be keys themselves.
+    fn merge_states(&mut self, mapper: HashMap<usize, usize>) -> IndexRemap {
+        let map = |idx| mapper.get(&idx).copied();
+        self.remap_transitions(map);
+        if let Some(new_initial) = map(self.initial_state) {
+            self.initial_state = new_initial;
+            self.states[new_initial].initial = true;
+        }
+        let to_remove = mapper.keys().copied().collect();
+        self.remove_states(to_remove).with_merged(&mapper)
+    }
+
+    /// Computes the number of states the DFA would have after being [minimized](Dfa::minimize), without modifying
+    /// it. This is the number of classes of non-distinguishable states which contain some reachable state.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let odd_as = "
+    ///        a  b
+    /// -> s1 s2 s1
+    ///  * s2 s3 s2
+    ///    s3 s4 s3
+    ///  * s4 s1 s4
+    ///    s5 s5 s5
+    /// ";
+    /// let dfa: Dfa = parser::dfa(odd_as).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.minimal_size(), 2);
+    /// assert_eq!(dfa.states().len(), 5);
+    /// ```
+    pub fn minimal_size(&self) -> usize {
+        let reachable = self.reachable_state_idx();
+        self.state_equivalence_classes_idx()
+            .into_iter()
+            .filter(|class| class.iter().any(|idx| reachable.contains(idx)))
+            .count()
+    }
+
+    /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states. The
+    /// states of each class are in the order of the states, and so are the classes (by their first state).
+    pub fn state_equivalence_classes(&self) -> Vec<Vec<&DfaState>> {
+        let mut classes = self
+            .state_equivalence_classes_idx()
+            .into_iter()
+            .map(|class| {
+                let mut class = class.into_iter().collect::<Vec<_>>();
+                class.sort_unstable();
+                class
             })
+            .collect::<Vec<_>>();
+        classes.sort_unstable();
+        classes
+            .into_iter()
+            .map(|class| class.into_iter().map(|state| &self.states[state]).collect())
             .collect()
     }
 
     /// Gives the equivalence classes of the states of this DFA, which is the sets of non-distinguishable states, by
     /// their indices
     pub fn state_equivalence_classes_idx(&self) -> Vec<HashSet<usize>> {
-        let (finals, nonfinals): (HashSet<usize>, HashSet<usize>) =
-            (0..self.states.len()).partition(|&idx| self.states[idx].accepting);
-        if finals.is_empty() {
-            return vec![nonfinals];
-        } else if nonfinals.is_empty() {
-            return vec![finals];
-        }
-        let mut p = vec![finals, nonfinals];
+        // states which differ in being accepting or in their priority are distinguishable by definition
+        let mut initial = BTreeMap::<_, HashSet<usize>>::new();
+        for (idx, state) in self.states.iter().enumerate() {
+            initial
+                .entry((!state.accepting, state.priority))
+                .or_default()
+                .insert(idx);
+        }
+        if initial.len() <= 1 {
+            return vec![initial.into_values().next().unwrap_or_default()];
+        }
+        let mut p = initial.into_values().collect::<Vec<_>>();
         let mut w = p.clone();
 
         // Hopcroft's algorithm
         while let Some(a) = w.pop() {
             for c in 0..self.alphabet.len() {
-                let x: HashSet<usize> = self
-                    .states
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, s)| a.contains(&s.transitions[c]))
-                    .map(|(i, _)| i)
-                    .collect();
+                let x = self.preimage_idx(&a, c);
                 p = p
                     .into_iter()
                     .map(|y| {
@@ -668,13 +1763,16 @@ impl Dfa {
         p
     }
 
-    /// Removes the unreachable states of this automata, leaving only states actually reaachable by some input
-    pub fn remove_unreachable_states(&mut self) {
-        let states = self.unreachable_state_idx().into_iter().collect();
-        self.remove_states(states);
+    /// Removes the unreachable states of this automata, leaving only states actually reaachable by some input. The
+    /// remaining states keep their relative order, and the returned [IndexRemap] maps the indices of the states
+    /// before the call to the indices after it.
+    pub fn remove_unreachable_states(&mut self) -> IndexRemap {
+        self.version.bump();
+        self.remove_states(self.unreachable_state_idx())
     }
 
-    /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata
+    /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, in the
+    /// order they appear in the automata
     pub fn unreachable_states(&self) -> Vec<&DfaState> {
         self.unreachable_state_idx()
             .into_iter()
@@ -683,8 +1781,8 @@ impl Dfa {
     }
 
     /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, and
-    /// returns them as indices
-    pub fn unreachable_state_idx(&self) -> HashSet<usize> {
+    /// returns them as indices (in increasing order when iterated)
+    pub fn unreachable_state_idx(&self) -> BTreeSet<usize> {
         let reachables = self.reachable_state_idx();
         (0..self.states.len())
             .filter(|x| !reachables.contains(x))
@@ -700,7 +1798,110 @@ impl Dfa {
             .any(|idx| self.states[*idx].accepting)
     }
 
-    /// Finds the reachable states, that is, all states that can be reached by some input to the automata
+    /// Counts the number of words of length exactly `k` which this DFA accepts. This is done by raising the
+    /// adjacency matrix of the DFA (counting the number of transitions between each pair of states) to the power of
+    /// `k`, which is much faster than enumerating the words for large `k`. If the count does not fit in a `u64`,
+    /// `u64::MAX` is returned (see [Dfa::count_words_of_length_saturating] to tell those apart).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let even_as = "
+    ///        a b
+    /// -> * e o e
+    ///      o e o"; // Dfa accepting all strings with an even number of a:s
+    /// let dfa: Dfa = parser::dfa(even_as).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.count_words_of_length(0), 1); // ""
+    /// assert_eq!(dfa.count_words_of_length(1), 1); // "b"
+    /// assert_eq!(dfa.count_words_of_length(2), 2); // "aa", "bb"
+    /// assert_eq!(dfa.count_words_of_length(100), u64::MAX);
+    /// ```
+    pub fn count_words_of_length(&self, k: usize) -> u64 {
+        self.count_words_of_length_saturating(k).saturated_value()
+    }
+
+    /// Counts the number of words of length exactly `k` which this DFA accepts, like [Dfa::count_words_of_length],
+    /// but as a [SaturatingCount] which tells if the count doesn't fit in a `u64`
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("   a b\n-> * s s s").unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.count_words_of_length_saturating(63).exact(), Some(1 << 63));
+    /// assert!(dfa.count_words_of_length_saturating(64).is_saturated());
+    /// ```
+    pub fn count_words_of_length_saturating(&self, k: usize) -> SaturatingCount {
+        let matrix = count::matrix_pow(count::adjacency_matrix(self, false), k);
+        self.states
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.accepting)
+            .fold(SaturatingCount::new(0), |acc, (idx, _)| {
+                acc + matrix[(self.initial_state, idx)]
+            })
+    }
+
+    /// Counts the number of words of length at most `n` which this DFA accepts, or `None` if that number does not
+    /// fit in a `u64`. Like [Dfa::count_words_of_length], this is done by matrix exponentiation, by adding a sink
+    /// state to the adjacency matrix which counts the words accepted so far.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let accepts_everything = "
+    ///        a b
+    /// -> * s s s";
+    /// let dfa: Dfa = parser::dfa(accepts_everything).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.count_words_up_to_length(2), Some(1 + 2 + 4));
+    /// // 2⁰ + 2¹ + … + 2⁶³ is exactly u64::MAX
+    /// assert_eq!(dfa.count_words_up_to_length(63), Some(u64::MAX));
+    /// assert_eq!(dfa.count_words_up_to_length(64), None);
+    /// ```
+    pub fn count_words_up_to_length(&self, n: usize) -> Option<u64> {
+        self.count_words_up_to_length_saturating(n).exact()
+    }
+
+    /// Counts the number of words of length at most `n` which this DFA accepts, like
+    /// [Dfa::count_words_up_to_length], but as a [SaturatingCount]
+    pub fn count_words_up_to_length_saturating(&self, n: usize) -> SaturatingCount {
+        let adjacency = count::adjacency_matrix(self, true);
+        // M^(n+1), without overflowing n + 1
+        let matrix = &count::matrix_pow(adjacency.clone(), n) * &adjacency;
+        matrix[(self.initial_state, self.states.len())]
+    }
+
+    /// Iterate over the words accepted by this DFA in lexicographic order (according to the order of the alphabet),
+    /// together with the name of the accepting state the DFA ends up in on each word. This clones the DFA and
+    /// enumerates the words of it [as a NFA](Nfa::words_with_states), so the iterator doesn't borrow this DFA.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = "
+    ///        a b
+    /// -> s   x y
+    ///  * x   x y
+    ///  * y   x y";
+    /// let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// let words = dfa.words_with_states().take(3).collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     words,
+    ///     vec![("a".to_string(), "x".into()), ("b".to_string(), "y".into()), ("aa".to_string(), "x".into())]
+    /// );
+    /// ```
+    pub fn words_with_states(&self) -> impl Iterator<Item = (String, Rc<str>)> {
+        WordsWithStates::owned(self.clone().to_nfa()).map(|(word, mut states)| {
+            let state = states.pop().expect("A DFA should end in exactly one state");
+            (word, state)
+        })
+    }
+
+    /// Finds the reachable states, that is, all states that can be reached by some input to the automata, in the
+    /// order they appear in the automata
     pub fn reachable_states(&self) -> Vec<&DfaState> {
         self.reachable_state_idx()
             .into_iter()
@@ -709,20 +1910,173 @@ impl Dfa {
     }
 
     /// Finds the reachable states, that is, all states that can be reached by some input to the automata, and
-    /// returns them as indices
-    pub fn reachable_state_idx(&self) -> HashSet<usize> {
-        let mut reachables = HashSet::from([self.initial_state]);
-        let mut new_states = reachables.clone();
-        while !new_states.is_empty() {
-            new_states = new_states
-                .drain()
-                .flat_map(|state| self.states[state].transitions.iter().copied())
-                .filter(|&state| reachables.insert(state))
-                .collect();
-        }
+    /// returns them as indices (in increasing order when iterated)
+    pub fn reachable_state_idx(&self) -> BTreeSet<usize> {
+        let mut reachables = BTreeSet::new();
+        self.bfs(|event| {
+            reachables.insert(event.state);
+            ControlFlow::Continue(())
+        });
         reachables
     }
 
+    /// Walks the reachable states of this DFA breadth-first from the initial state, calling `visit` with each state
+    /// when it is first found, until it returns [ControlFlow::Break] or all reachable states are found. The
+    /// transitions of each state are followed in the order of the alphabet, so the states are always visited in the
+    /// same order, and following the parents of a state back to the initial state gives the shortest word reaching it
+    /// (the first one in the order of the alphabet among equally short words). See [Dfa::bfs_tree] for collecting the
+    /// parents.
+    ///
+    /// ```
+    /// use dandy::dfa::{BfsEvent, Dfa};
+    /// use dandy::parser;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let dfa = "
+    ///        a  b
+    /// -> s0 s1 s2
+    ///    s1 s1 s3
+    ///    s2 s3 s2
+    ///  * s3 s3 s3
+    ///    s4 s4 s4
+    /// ";
+    /// let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// let mut events = vec![];
+    /// dfa.bfs(|event| {
+    ///     events.push(event);
+    ///     ControlFlow::Continue(())
+    /// });
+    /// let states = events.iter().map(|event| event.state).collect::<Vec<_>>();
+    /// assert_eq!(states, vec![0, 1, 2, 3]); // s4 is unreachable
+    /// assert_eq!(events[3], BfsEvent { state: 3, parent: Some(1), symbol: Some(1), depth: 2 });
+    /// ```
+    pub fn bfs(&self, mut visit: impl FnMut(BfsEvent) -> ControlFlow<()>) {
+        let mut depths = vec![None; self.states.len()];
+        depths[self.initial_state] = Some(0);
+        let initial = BfsEvent {
+            state: self.initial_state,
+            parent: None,
+            symbol: None,
+            depth: 0,
+        };
+        if visit(initial).is_break() {
+            return;
+        }
+        let mut queue = VecDeque::from([self.initial_state]);
+        while let Some(parent) = queue.pop_front() {
+            let depth = depths[parent].expect("Queued states have a depth") + 1;
+            for (symbol, &state) in self.states[parent].transitions.iter().enumerate() {
+                if depths[state].is_some() {
+                    continue;
+                }
+                depths[state] = Some(depth);
+                queue.push_back(state);
+                let event = BfsEvent {
+                    state,
+                    parent: Some(parent),
+                    symbol: Some(symbol),
+                    depth,
+                };
+                if visit(event).is_break() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Gives the tree of the breadth-first search of [Dfa::bfs], as the parent of each state and the index of the
+    /// element of the alphabet on which the parent transitions to it, or `None` for the initial state and the
+    /// unreachable states. Following the parents of a state back to the initial state gives the shortest word
+    /// reaching it, in reverse.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("   a b\n-> s0 s1 s0\n * s1 s1 s2\n s2 s2 s2").unwrap().try_into().unwrap();
+    /// let tree = dfa.bfs_tree();
+    /// assert_eq!(tree, vec![None, Some((0, 0)), Some((1, 1))]);
+    ///
+    /// let mut word = vec![];
+    /// let mut state = 2;
+    /// while let Some((parent, symbol)) = tree[state] {
+    ///     word.push(dfa.alphabet()[symbol].as_ref());
+    ///     state = parent;
+    /// }
+    /// word.reverse();
+    /// assert_eq!(word, vec!["a", "b"]);
+    /// ```
+    pub fn bfs_tree(&self) -> Vec<Option<(usize, usize)>> {
+        let mut tree = vec![None; self.states.len()];
+        self.bfs(|event| {
+            tree[event.state] = event.parent.zip(event.symbol);
+            ControlFlow::Continue(())
+        });
+        tree
+    }
+
+    /// Gives the pre-image of a set of states on an element of the alphabet, that is, the indices of all states which
+    /// transition to one of the `targets` on `symbol`. This is empty if `symbol` isn't in the alphabet. The reversed
+    /// transitions are computed the first time this (or [Dfa::predecessors]) is called, and are kept until the DFA is
+    /// modified, so repeated calls only cost as much as the size of the result.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    /// use std::collections::HashSet;
+    ///
+    /// let input = "
+    ///        a  b
+    /// -> s0 s1 s0
+    ///    s1 s2 s0
+    ///  * s2 s2 s2
+    /// ";
+    /// let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.preimage(&HashSet::from([2]), "a"), HashSet::from([1, 2]));
+    /// assert_eq!(dfa.preimage(&HashSet::from([0, 1]), "b"), HashSet::from([0, 1]));
+    /// assert!(dfa.preimage(&HashSet::from([2]), "c").is_empty());
+    /// assert_eq!(dfa.predecessors(0), vec![(0, 1), (1, 1)]);
+    /// ```
+    pub fn preimage(&self, targets: &HashSet<usize>, symbol: &str) -> HashSet<usize> {
+        let Some(elem) = self.alphabet_index(symbol) else {
+            return HashSet::new();
+        };
+        self.preimage_idx(targets, elem)
+    }
+
+    /// Gives the pre-image of the `targets` on the element of the alphabet with the given index, see [Dfa::preimage]
+    fn preimage_idx(&self, targets: &HashSet<usize>, elem: usize) -> HashSet<usize> {
+        let reverse = self.reverse_transitions();
+        targets
+            .iter()
+            .flat_map(|&to| reverse[to][elem].iter().copied())
+            .collect()
+    }
+
+    /// Gives the transitions to the state with the given index, as pairs of the index of the state transitioned from
+    /// and the index of the element of the alphabet transitioned on, ordered by the element and then by the state.
+    /// See [Dfa::preimage] for how this is computed. Panics if there is no state with the given index.
+    pub fn predecessors(&self, state: usize) -> Vec<(usize, usize)> {
+        self.reverse_transitions()[state]
+            .iter()
+            .enumerate()
+            .flat_map(|(elem, sources)| sources.iter().map(move |&from| (from, elem)))
+            .collect()
+    }
+
+    /// Gets the reversed transitions of this DFA, see [ReverseIndex]
+    fn reverse_transitions(&self) -> &[Vec<Vec<usize>>] {
+        self.reverse_transitions.get_or_init(|| {
+            let mut reverse = vec![vec![vec![]; self.alphabet.len()]; self.states.len()];
+            for (from, state) in self.states.iter().enumerate() {
+                for (elem, &to) in state.transitions.iter().enumerate() {
+                    reverse[to][elem].push(from);
+                }
+            }
+            reverse
+        })
+    }
+
     /// Remaps the transitions so that any transition to n gets mapped to mapper(n) (if any, otherwise n is preserved)
     fn remap_transitions(&mut self, mapper: impl Fn(usize) -> Option<usize>) {
         self.states.iter_mut().for_each(|state| {
@@ -730,25 +2084,25 @@ impl Dfa {
                 .transitions
                 .iter_mut()
                 .for_each(|trans| *trans = mapper(*trans).unwrap_or(*trans))
-        })
+        });
+        self.reverse_transitions = ReverseIndex::default();
     }
 
-    /// This function removes the states with indices in the vector from this DFA, changing the transition tables
+    /// This function removes the states with indices in the set from this DFA, changing the transition tables
     /// of the remaining states to the new state indices. There should not be any transitions to any of the states
     /// that are to be removed (except for in any of the states that are to be removed). If there is, transitions may be
     /// undefined after this call. If debug_assertions is enabled, such errors would cause a panic here, otherwise they
     /// would not immediately panic but other operations might panic at a later stage. The initial state cannot be
     /// removed and will cause a panic if attempted to.
-    fn remove_states(&mut self, mut to_remove: Vec<usize>) {
+    fn remove_states(&mut self, to_remove: BTreeSet<usize>) -> IndexRemap {
+        let old_len = self.states.len();
         let mut old_state_idx = (0..self.states.len()).collect::<Vec<_>>();
 
-        to_remove.sort();
-        if let Err(less_than) = to_remove.binary_search(&self.initial_state) {
-            // We removed "less than" states before the initial state: adjust
-            self.initial_state -= less_than;
-        } else {
+        if to_remove.contains(&self.initial_state) {
             panic!("Cannot remove initial state");
         }
+        // We removed some states before the initial state: adjust
+        self.initial_state -= to_remove.range(..self.initial_state).count();
 
         to_remove.iter().rev().for_each(|&idx| {
             self.states.remove(idx);
@@ -764,22 +2118,74 @@ impl Dfa {
             }
         };
         self.remap_transitions(map);
+        IndexRemap::from_kept(old_len, old_state_idx)
+    }
+
+    /// Reorders the states of this DFA in breadth-first order from the initial state (following
+    /// the transitions in alphabet order), with any unreachable states last, and names them
+    /// sequentially from 0. The result only depends on the structure of the DFA, and not on the
+    /// names or order of its states.
+    pub(crate) fn renumber_states(&mut self) {
+        self.version.bump();
+        let mut new_idx = vec![None; self.states.len()];
+        let mut order = vec![];
+        self.bfs(|event| {
+            new_idx[event.state] = Some(order.len());
+            order.push(event.state);
+            ControlFlow::Continue(())
+        });
+        for (idx, new) in new_idx.iter_mut().enumerate() {
+            if new.is_none() {
+                *new = Some(order.len());
+                order.push(idx);
+            }
+        }
+
+        let mut old_states = mem::take(&mut self.states)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        self.states = order
+            .into_iter()
+            .enumerate()
+            .map(|(idx, old)| {
+                let mut state = old_states[old].take().expect("Each state is visited once");
+                state.name = Rc::from(idx.to_string());
+                state
+                    .transitions
+                    .iter_mut()
+                    .for_each(|to| *to = new_idx[*to].expect("Each state gets a new index"));
+                state
+            })
+            .collect();
+        self.initial_state = 0;
+        self.reverse_transitions = ReverseIndex::default();
     }
 
     /// Converts this DFA to a NFA by simply converting each state to a NFA state. All state names
     /// are kept. This is a cheap operation, involving no clones but some vector allocations due to
-    /// the vectors required by NFA.
+    /// the vectors required by NFA. NFA states have no [priorities](DfaState::priority), so those
+    /// are dropped.
     pub fn to_nfa(self) -> Nfa {
         let Dfa {
             alphabet,
             states,
             initial_state,
+            provenance,
+            alphabet_lookup,
+            version: _,
+            reverse_transitions: _,
         } = self;
         let states = states.into_iter().map(|s| s.into()).collect();
         Nfa {
             alphabet,
             states,
             initial_state,
+            alphabet_lookup,
+            version: Version::default(),
+            provenance: Provenance::new(|| {
+                format!("conversion from {}", provenance.describe("DFA"))
+            }),
         }
     }
 
@@ -812,12 +2218,56 @@ impl Dfa {
     /// assert!(dfa.accepts_graphemes("001")); // Equivalent to dfa.accepts(&["0", "0", "1"])
     /// ```
     pub fn accepts_graphemes(&self, string: &str) -> bool {
-        let graphemes = string.graphemes(true).collect::<Vec<_>>();
         let mut eval = self.evaluator();
-        eval.step_multiple(&graphemes);
+        eval.step_str(string);
         eval.is_accepting()
     }
 
+    /// Finds the longest prefix of `input` which this DFA accepts, where every grapheme is considered as an element of
+    /// the alphabet (like [Dfa::accepts_graphemes]), as a lexer would when matching the next token. This gives the
+    /// length of the prefix in bytes, the [priority](DfaState::priority) of the accepting state it leads to and the
+    /// state itself, or `None` if no prefix (not even the empty one) is accepted. The input is only read until an
+    /// element outside of the alphabet is found.
+    ///
+    /// A DFA has only one state for each prefix, so when DFAs of several tokens are combined with [Dfa::union], the
+    /// tie between tokens matching the same prefix is broken by the priorities when the DFAs are combined, and the
+    /// priority given here tells which token won. Accepting states without a priority are given as `u32::MAX`, the
+    /// lowest priority.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let mut a_then_bs: Dfa = parser::dfa("
+    ///        a b
+    /// -> s0 s1 s2
+    ///  * s1 s2 s1
+    ///    s2 s2 s2
+    /// ").unwrap().try_into().unwrap();
+    /// a_then_bs.set_priority(Some(2));
+    /// let (len, priority, state) = a_then_bs.longest_match_with_priority("abba").unwrap();
+    /// assert_eq!((len, priority, state.name()), (3, 2, "s1"));
+    /// assert!(a_then_bs.longest_match_with_priority("ba").is_none());
+    /// ```
+    pub fn longest_match_with_priority(&self, input: &str) -> Option<(usize, u32, &DfaState)> {
+        let matched = |len: usize, state: &DfaState| {
+            state
+                .accepting
+                .then(|| (len, state.priority.unwrap_or(u32::MAX)))
+        };
+        let mut eval = self.evaluator();
+        let mut longest = matched(0, self.initial_state()).map(|m| (m, self.initial_state));
+        for (idx, grapheme) in input.grapheme_indices(true) {
+            let Some(state) = eval.step(grapheme) else {
+                break;
+            };
+            if let Some(m) = matched(idx + grapheme.len(), state) {
+                longest = Some((m, eval.current_state_idx()));
+            }
+        }
+        longest.map(|((len, priority), state)| (len, priority, &self.states[state]))
+    }
+
     /// Checks if the alphabet of this automaton consists of only single graphemes. If it does, one may use
     /// [Dfa::accepts_graphemes] instead of [Dfa::accepts] for improved ergonomics. A grapheme is defined to be
     /// one extended unicode grapheme cluster (which may consist of one or many code points).
@@ -848,47 +2298,593 @@ impl Dfa {
             .all(|str| str.graphemes(true).count() == 1)
     }
 
-    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
-    pub fn evaluator(&self) -> DfaEvaluator<'_> {
-        self.into()
-    }
-
-    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton
-    pub fn to_table(&self) -> String {
-        self.gen_table("→")
+    /// Compares the alphabet of this DFA with the alphabet of another DFA, giving the elements they share and the
+    /// elements only one of them has. DFAs are only [equivalent](Dfa::equivalent_to) if their alphabets are equal, so
+    /// this explains why two DFAs with different alphabets aren't, such as when a symbol has been left out.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let reference = "
+    ///        a b c
+    /// -> * s s s s
+    /// ";
+    /// let attempt = "
+    ///        b a d
+    /// -> * s s s s
+    /// ";
+    /// let reference: Dfa = parser::dfa(reference).unwrap().try_into().unwrap();
+    /// let attempt: Dfa = parser::dfa(attempt).unwrap().try_into().unwrap();
+    /// let diff = reference.alphabet_diff(&attempt);
+    /// assert_eq!(diff.shared, vec!["a".into(), "b".into()]);
+    /// assert_eq!(diff.only_in_self, vec!["c".into()]);
+    /// assert_eq!(diff.only_in_other, vec!["d".into()]);
+    /// assert!(!diff.is_equal());
+    /// ```
+    pub fn alphabet_diff(&self, other: &Dfa) -> AlphabetDiff {
+        AlphabetDiff::new(&self.alphabet, &other.alphabet)
     }
 
-    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton. The arrow for
-    /// the initial state is "->"
+    /// Finds the graphemes appearing in the words of a corpus which aren't elements of the alphabet of this DFA, such
+    /// as symbols which were left out of the alphabet. The words are split into graphemes like in
+    /// [Dfa::accepts_graphemes], so an element consisting of several graphemes never matches, and its graphemes are
+    /// reported unless they are elements themselves.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let input = "
+    ///        a b
+    /// -> * s s s
+    /// ";
+    /// let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    /// let missing = dfa.missing_symbols_for(["abba", "bca", "", "cd"].into_iter());
+    /// assert_eq!(missing.into_iter().collect::<Vec<_>>(), vec!["c", "d"]);
+    /// ```
+    pub fn missing_symbols_for<'a>(
+        &self,
+        corpus: impl Iterator<Item = &'a str>,
+    ) -> BTreeSet<String> {
+        util::missing_graphemes(corpus, |grapheme| self.alphabet_index(grapheme))
+    }
+
+    /// Computes a structural diff between this DFA and `other`, which is useful to see how two near-identical
+    /// transition tables differ, for example when a state has been renamed and one of its transitions changed. This
+    /// is unlike checking [equivalence](Dfa::equivalent_to), since two DFAs accepting the same language may differ
+    /// structurally, and two DFAs differing in a single transition may accept vastly different languages.
+    ///
+    /// States are matched by walking both DFAs in lockstep from their initial states, over the elements of the
+    /// alphabet they share. The first time two states are reached together, they are matched with each other. Any
+    /// transition of two matched states which leads to states that aren't matched with each other (since at least one
+    /// of them has been matched with another state) is reported as a [TransitionMismatch]. The diff also contains
+    /// the matched states which differ in acceptance, the states without a counterpart and the differences of the
+    /// alphabets. See [StructuralDiff] for more info.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_ab = "
+    ///        a  b
+    /// -> s0  s1 s0
+    ///    s1  s1 s2
+    ///  * s2  s1 s0";
+    /// let edited = "
+    ///        a  b
+    /// -> q0  q1 q0
+    ///    q1  q1 q2
+    ///  * q2  q1 q2";
+    /// let ends_with_ab: Dfa = parser::dfa(ends_with_ab).unwrap().try_into().unwrap();
+    /// let edited: Dfa = parser::dfa(edited).unwrap().try_into().unwrap();
+    ///
+    /// let diff = ends_with_ab.structural_diff(&edited);
+    /// assert_eq!(diff.matched.len(), 3);
+    /// assert_eq!(diff.transition_mismatches.len(), 1);
+    /// let mismatch = &diff.transition_mismatches[0];
+    /// assert_eq!((mismatch.from_self.as_ref(), mismatch.symbol.as_ref()), ("s2", "b"));
+    /// assert_eq!((mismatch.to_self.as_ref(), mismatch.to_other.as_ref()), ("s0", "q2"));
+    ///
+    /// // Renaming the states doesn't make a difference
+    /// assert!(ends_with_ab.structural_diff(&ends_with_ab.clone()).is_identical());
+    /// ```
+    pub fn structural_diff(&self, other: &Dfa) -> StructuralDiff {
+        diff::structural_diff(self, other)
+    }
+
+    /// Finds the accepted word closest to the given word, measured in edit distance over elements of the alphabet,
+    /// that is, the minimum number of single-element insertions, deletions and substitutions needed to turn `word`
+    /// into a word accepted by this DFA. Returns one such word along with its distance, or `None` if no accepted word
+    /// is within `max_edits` edits. Elements of `word` which are not in the alphabet can never be matched, so they
+    /// have to be substituted or deleted. This is useful to suggest a correction to a rejected word.
+    ///
+    /// The search is done over the product of the positions in `word` and the states of this DFA, so it takes
+    /// `O(|word| * |states| * |alphabet|)` time in the worst case.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let odd_as = "
+    ///        a b
+    /// -> e   o e
+    ///  * o   e o"; // Dfa accepting all strings with an odd number of a:s
+    /// let dfa: Dfa = parser::dfa(odd_as).unwrap().try_into().unwrap();
+    /// let (word, distance) = dfa.closest_accepted(&["a", "a"], 2).unwrap();
+    /// assert_eq!(distance, 1);
+    /// assert!(dfa.accepts(&word.iter().map(|e| e.as_ref()).collect::<Vec<_>>()));
+    ///
+    /// // "a" is already accepted
+    /// assert_eq!(dfa.closest_accepted(&["a"], 0), Some((vec!["a".into()], 0)));
+    /// // "bb" needs at least one edit
+    /// assert_eq!(dfa.closest_accepted(&["b", "b"], 0), None);
+    /// ```
+    pub fn closest_accepted(
+        &self,
+        word: &[&str],
+        max_edits: usize,
+    ) -> Option<(Vec<Rc<str>>, usize)> {
+        let word = word
+            .iter()
+            .map(|elem| self.alphabet_index(elem))
+            .collect::<Vec<_>>();
+        let (closest, distance) = edit::closest_accepted(self, &word, max_edits)?;
+        let closest = closest
+            .into_iter()
+            .map(|idx| self.alphabet[idx].clone())
+            .collect();
+        Some((closest, distance))
+    }
+
+    /// Finds the accepted word closest to the given string of graphemes, see [Dfa::closest_accepted]. Just as with
+    /// [Dfa::accepts_graphemes], every grapheme by itself is considered as an element of the alphabet. The closest
+    /// word is returned as a `String` by concatenating its elements.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_b = "
+    ///        a b
+    /// -> n   n y
+    ///  * y   n y";
+    /// let dfa: Dfa = parser::dfa(ends_with_b).unwrap().try_into().unwrap();
+    /// let (word, distance) = dfa.closest_accepted_graphemes("aba", 3).unwrap();
+    /// assert_eq!(distance, 1);
+    /// assert!(dfa.accepts_graphemes(&word));
+    /// ```
+    pub fn closest_accepted_graphemes(
+        &self,
+        string: &str,
+        max_edits: usize,
+    ) -> Option<(String, usize)> {
+        let graphemes = string.graphemes(true).collect::<Vec<_>>();
+        let (closest, distance) = self.closest_accepted(&graphemes, max_edits)?;
+        Some((closest.concat(), distance))
+    }
+
+    /// Evaluates every input (where every grapheme is an element of the alphabet) and counts how many times each
+    /// state is visited and each transition is taken, see [Coverage]. The inputs are consumed one at a time and not
+    /// kept.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///       a b
+    ///   → s t s
+    ///   * t t s
+    ///     u u t
+    /// ").unwrap().try_into().unwrap();
+    /// let coverage = dfa.coverage("ab\na\nb".lines());
+    /// assert_eq!(coverage.visits("s"), Some(5));
+    /// assert_eq!(coverage.unvisited_states(), ["u"]);
+    /// assert_eq!(coverage.untaken_transitions(), [("t", "a"), ("u", "a"), ("u", "b")]);
+    /// ```
+    pub fn coverage<'a>(&self, inputs: impl IntoIterator<Item = &'a str>) -> Coverage {
+        let mut coverage = Coverage::new(self);
+        inputs
+            .into_iter()
+            .for_each(|input| coverage.record(self, input));
+        coverage
+    }
+
+    /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
+    pub fn evaluator(&self) -> DfaEvaluator<'_> {
+        self.into()
+    }
+
+    /// Resumes an evaluator from a checkpoint created by [DfaEvaluator::checkpoint]. This fails if the checkpoint
+    /// was created by an evaluator of another DFA, which is detected by comparing [fingerprints](Dfa::fingerprint).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let ends_with_a = "
+    ///      a b
+    /// -> n y n
+    ///  * y y n";
+    /// let dfa: Dfa = parser::dfa(ends_with_a).unwrap().try_into().unwrap();
+    /// let mut evaluator = dfa.evaluator();
+    /// evaluator.step_multiple(&["b", "a"]);
+    /// let checkpoint = evaluator.checkpoint();
+    ///
+    /// // ...later on
+    /// let mut evaluator = dfa.resume(&checkpoint).unwrap();
+    /// assert!(evaluator.is_accepting());
+    /// evaluator.step("b");
+    /// assert!(!evaluator.is_accepting());
+    /// ```
+    pub fn resume(
+        &self,
+        checkpoint: &EvaluatorCheckpoint,
+    ) -> Result<DfaEvaluator<'_>, CheckpointMismatch> {
+        DfaEvaluator::from_checkpoint(self, checkpoint)
+    }
+
+    /// Gets an evaluator in the state with the given [state key](DfaEvaluator::state_key), or `None` if there is no
+    /// such state. Unlike [Dfa::resume], nothing ensures that the key was recorded for this DFA, which has to be known
+    /// some other way (such as by comparing [versions](Dfa::version)), but the transition table isn't gone through.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("   a b\n→ n y n\n* y y n").unwrap().try_into().unwrap();
+    /// let mut evaluator = dfa.evaluator();
+    /// evaluator.step("a");
+    /// let (version, key) = (dfa.version(), evaluator.state_key());
+    ///
+    /// // ...later on
+    /// assert_eq!(dfa.version(), version);
+    /// let evaluator = dfa.evaluator_at(key).unwrap();
+    /// assert!(evaluator.is_accepting());
+    /// assert!(dfa.evaluator_at(Some(2)).is_none());
+    /// ```
+    pub fn evaluator_at(&self, state_key: Option<usize>) -> Option<DfaEvaluator<'_>> {
+        DfaEvaluator::at(self, state_key)
+    }
+
+    /// Gets the version of this DFA, which is 0 for a newly created DFA and is increased by every method modifying it
+    /// (such as [Dfa::minimize] or [Dfa::invert]), whether or not anything changed. Clones keep the version, and the
+    /// version doesn't affect equality. This is useful to detect that a DFA has been modified since some data about it
+    /// was computed.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let mut dfa: Dfa = parser::dfa("  a\n-> s s\n * t t").unwrap().try_into().unwrap();
+    /// let before = dfa.version();
+    /// dfa.remove_unreachable_states();
+    /// assert!(dfa.version() > before);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.version.get()
+    }
+
+    /// Computes a fingerprint of this DFA, which is a hash of its alphabet, its states (but not their names) and its
+    /// transition table. The fingerprint is stable across platforms, so it may be stored or sent to another machine.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv64::new();
+        hasher.write_usize(self.alphabet.len());
+        self.alphabet.iter().for_each(|elem| hasher.write_str(elem));
+        hasher.write_usize(self.states.len());
+        hasher.write_usize(self.initial_state);
+        self.states.iter().for_each(|state| {
+            hasher.write_bool(state.accepting);
+            state
+                .transitions
+                .iter()
+                .for_each(|&to| hasher.write_usize(to));
+        });
+        hasher.finish()
+    }
+
+    /// Computes a fingerprint of the language of this DFA, such that equivalent DFAs get the same fingerprint no
+    /// matter their states, state names or alphabet order. This minimizes a copy of the DFA, see
+    /// [Dfa::fingerprint] for a cheap fingerprint of the DFA itself. Elements of the alphabet which aren't part of any
+    /// accepted word don't affect the fingerprint, so it only depends on the set of accepted words.
+    ///
+    /// Like [Dfa::fingerprint], this is stable across runs and platforms. Since it is a 64-bit hash, two DFAs with
+    /// different languages get the same fingerprint with a tiny probability, so an equal fingerprint should be
+    /// confirmed with [Dfa::equivalent_to] if certainty is needed.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let odd_a: Dfa = parser::dfa("  a b\n-> e o e\n * o e o").unwrap().try_into().unwrap();
+    /// let odd_a_reordered: Dfa = parser::dfa("  b a\n * x x y\n -> y y x").unwrap().try_into().unwrap();
+    /// assert_eq!(odd_a.language_fingerprint(), odd_a_reordered.language_fingerprint());
+    /// ```
+    pub fn language_fingerprint(&self) -> u64 {
+        let mut minimized = self.clone();
+        minimized.minimize();
+        minimized.minimal_language_fingerprint()
+    }
+
+    /// Computes the [language fingerprint](Dfa::language_fingerprint) of this DFA, which must be minimal. The live
+    /// states (those from which an accepting state can be reached) are numbered in the order a breadth-first search
+    /// from the initial state finds them, following only the elements of the alphabet that are part of some accepted
+    /// word, in lexicographic order. The hash is of those elements, and the accepting flag and transitions of each
+    /// live state in that order, where all transitions to the (at most one) dead state are hashed the same.
+    pub(crate) fn minimal_language_fingerprint(&self) -> u64 {
+        let live = self.live_states();
+        let mut symbols = (0..self.alphabet.len())
+            .filter(|&elem| {
+                self.states
+                    .iter()
+                    .enumerate()
+                    .any(|(idx, state)| live[idx] && live[state.transitions[elem]])
+            })
+            .collect::<Vec<_>>();
+        symbols.sort_by(|&a, &b| self.alphabet[a].cmp(&self.alphabet[b]));
+
+        let mut hasher = Fnv64::new();
+        hasher.write_usize(symbols.len());
+        symbols
+            .iter()
+            .for_each(|&elem| hasher.write_str(&self.alphabet[elem]));
+        let mut order = vec![None; self.states.len()];
+        let mut queue = VecDeque::new();
+        if live[self.initial_state] {
+            order[self.initial_state] = Some(0);
+            queue.push_back(self.initial_state);
+        }
+        let mut numbered = queue.len();
+        while let Some(idx) = queue.pop_front() {
+            let state = &self.states[idx];
+            hasher.write_bool(state.accepting);
+            for &elem in &symbols {
+                let to = state.transitions[elem];
+                if !live[to] {
+                    // Dead states are hashed as 0, and live states as their number plus one
+                    hasher.write_usize(0);
+                    continue;
+                }
+                let number = *order[to].get_or_insert_with(|| {
+                    queue.push_back(to);
+                    numbered += 1;
+                    numbered - 1
+                });
+                hasher.write_usize(number + 1);
+            }
+        }
+        hasher.write_usize(numbered);
+        hasher.finish()
+    }
+
+    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton. Tables have
+    /// no notation for [priorities](DfaState::priority), so those are dropped, and the parsed DFA is only equal to
+    /// this DFA if none of its states has a priority.
+    pub fn to_table(&self) -> String {
+        self.gen_table(&TableStyle::default(), " ")
+    }
+
+    /// Generates a table of this DFA like [Dfa::to_table], but with the markers of the initial and accepting states
+    /// given by the [TableStyle]. The table may be parsed again by
+    /// [parser::dfa_with_config](crate::parser::dfa_with_config) with a [ParserConfig](crate::parser::ParserConfig)
+    /// containing those markers.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser::{self, ParserConfig, TableStyle};
+    ///
+    /// let dfa: Dfa = parser::dfa("   a\n→ * s t\n    t s").unwrap().try_into().unwrap();
+    /// let style = TableStyle {
+    ///     initial_marker: ">".to_string(),
+    ///     accepting_marker: "F".to_string(),
+    ///     ..TableStyle::default()
+    /// };
+    /// assert_eq!(dfa.to_table_with_style(&style), "      a \n> F s t \n    t s ");
+    /// ```
+    pub fn to_table_with_style(&self, style: &TableStyle) -> String {
+        self.gen_table(style, " ")
+    }
+
+    /// Generates a table of this DFA suitable for printing, which may be parsed again to this automaton. The arrow for
+    /// the initial state is "->"
     pub fn ascii_table(&self) -> String {
-        self.gen_table("->")
+        self.gen_table(&TableStyle::ascii(), " ")
+    }
+
+    /// Generates a table of this DFA suitable for printing, like [Dfa::to_table], but with `sep` between each column
+    /// instead of a single space. Note that the parser splits columns on whitespace, so a table generated with a
+    /// separator containing anything other than whitespace may not be parsed again.
+    pub fn to_table_with_separator(&self, sep: &str) -> String {
+        self.gen_table(&TableStyle::default(), sep)
+    }
+
+    /// Generates a table of this DFA where the columns are separated by `" | "`, which is easier to read for wide
+    /// tables. This is a convenience function for [Dfa::to_table_with_separator], and the table may not be parsed
+    /// again.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = "
+    ///        a  b
+    /// -> s0 s1 s0
+    ///  * s1 s1 s0";
+    /// let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// let table = dfa.to_table_pipe_separated();
+    /// assert_eq!(table.lines().nth(1), Some("→ |   | s0 | s1 | s0 | "));
+    /// ```
+    pub fn to_table_pipe_separated(&self) -> String {
+        self.to_table_with_separator(" | ")
     }
 
-    fn gen_table(&self, arrow: &str) -> String {
+    /// Generates the cells of the table given by [Dfa::to_table], row by row, without any padding. The first row is
+    /// the alphabet (after three empty cells), and each other row is a state, with the cells for the initial state
+    /// arrow and the accepting star (which are empty if the state isn't initial or accepting), the name and the
+    /// transitions. This is useful for rendering the table in some other way, such as in HTML.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///         a  b
+    ///   -> s0 s1 s0
+    ///    * s1 s1 s0
+    /// ").unwrap().try_into().unwrap();
+    /// let rows = dfa.to_table_rows();
+    /// assert_eq!(rows[0], ["", "", "", "a", "b"]);
+    /// assert_eq!(rows[1], ["→", "", "s0", "s1", "s0"]);
+    /// assert_eq!(rows[2], ["", "*", "s1", "s1", "s0"]);
+    /// ```
+    pub fn to_table_rows(&self) -> Vec<Vec<String>> {
+        self.table_cells(&TableStyle::default())
+            .into_iter()
+            .map(|row| row.into_iter().map(Cow::into_owned).collect())
+            .collect()
+    }
+
+    /// Generates an edge list of this DFA, like [Nfa::to_edge_list], which may be parsed again by
+    /// [parser::nfa_edges](crate::parser::nfa_edges) (as a NFA, which has no ε transitions)
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///         a  b
+    ///   -> s0 s1 s0
+    ///    * s1 s1 s0
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(
+    ///     dfa.to_edge_list(),
+    ///     "alphabet: a b\nstate s0 initial\nstate s1 accepting\ns0 -a-> s1\ns0 -b-> s0\ns1 -a-> s1\ns1 -b-> s0"
+    /// );
+    /// ```
+    pub fn to_edge_list(&self) -> String {
+        table::edge_list(
+            &self.alphabet,
+            self.states
+                .iter()
+                .map(|state| (&*state.name, state.initial, state.accepting)),
+            self.states.iter().enumerate().flat_map(|(from, state)| {
+                self.alphabet
+                    .iter()
+                    .zip(&state.transitions)
+                    .map(move |(elem, &to)| (from, Some(&**elem), to))
+            }),
+        )
+    }
+
+    /// Converts this DFA to a [ParsedDfaOwned] with the same table as [Dfa::to_table], which may be edited and
+    /// validated again by [TryInto::try_into] without writing and parsing the table. Just as with [Dfa::to_table],
+    /// the [priorities](DfaState::priority) of the states are dropped.
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///         a  b
+    ///   -> s0 s1 s0
+    ///    * s1 s1 s0
+    /// ").unwrap().try_into().unwrap();
+    /// let mut parsed = dfa.to_parsed();
+    /// parsed.set_accepting("s0", true).unwrap();
+    /// let edited: Dfa = parsed.try_into().unwrap();
+    /// assert!(edited.accepts(&[]));
+    /// assert_eq!(Dfa::try_from(dfa.to_parsed()).unwrap(), dfa);
+    /// ```
+    pub fn to_parsed(&self) -> ParsedDfaOwned {
+        ParsedDfaOwned {
+            head: self.alphabet.iter().map(|elem| elem.to_string()).collect(),
+            states: self
+                .states
+                .iter()
+                .map(|state| ParsedDfaStateOwned {
+                    name: state.name.to_string(),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state
+                        .transitions
+                        .iter()
+                        .map(|&to| self.states[to].name.to_string())
+                        .collect(),
+                })
+                .collect(),
+            declared_alphabet: None,
+        }
+    }
+
+    /// Serializes this DFA to JSON, in the format described in the [module-level documentation](crate::dfa)
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a  b
+    /// -> s0 s1 s0
+    ///  * s1 s1 s0").unwrap().try_into().unwrap();
+    /// let json = dfa.to_json_string().unwrap();
+    /// assert_eq!(Dfa::from_json_str(&json).unwrap().to_table(), dfa.to_table());
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a DFA from JSON, in the format described in the [module-level documentation](crate::dfa). Fails
+    /// if the JSON is malformed, or if the DFA is invalid for any reason a [DfaParseError](parse::DfaParseError) would
+    /// be given when parsing a table.
+    #[cfg(feature = "serde")]
+    pub fn from_json_str(s: &str) -> Result<Dfa, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    fn gen_table(&self, style: &TableStyle, sep: &str) -> String {
+        let cells = self.table_cells(style);
         let mut table = Table::default();
+        for row in &cells {
+            table.push_row(row.iter().map(|cell| cell as &str).collect());
+        }
+        table.to_string(sep)
+    }
+
+    /// Generates the cells of the table of this DFA, row by row, with the markers of the given style
+    fn table_cells<'a>(&'a self, style: &'a TableStyle) -> Vec<Vec<Cow<'a, str>>> {
+        let names = self
+            .states
+            .iter()
+            .map(|s| quote_cell(&s.name))
+            .collect::<Vec<_>>();
 
-        let mut alph = vec!["", "", ""];
-        alph.extend(self.alphabet.iter().map(|s| s as &str));
-        table.push_row(alph);
+        // The header of a table with an empty alphabet would be an empty line, which isn't parsed as a header, so the
+        // header of the state column is written instead
+        let corner = if self.alphabet.is_empty() {
+            "state"
+        } else {
+            ""
+        };
+        let mut alph = vec![Cow::Borrowed(""), Cow::Borrowed(""), Cow::Borrowed(corner)];
+        alph.extend(self.alphabet.iter().map(|s| quote_cell(s)));
+        let mut rows = vec![alph];
 
-        for DfaState {
-            name,
-            initial,
-            accepting,
-            transitions,
-        } in &self.states
-        {
-            let mut state = vec![
-                if *initial { arrow } else { "" },
-                if *accepting { "*" } else { "" },
-                name,
+        for (state, name) in self.states.iter().zip(&names) {
+            let mut row = vec![
+                Cow::Borrowed(if state.initial {
+                    style.initial_marker.as_str()
+                } else {
+                    ""
+                }),
+                Cow::Borrowed(if state.accepting {
+                    style.accepting_marker.as_str()
+                } else {
+                    ""
+                }),
+                name.clone(),
             ];
-            transitions
-                .iter()
-                .for_each(|&c| state.push(&self.states[c].name));
-            table.push_row(state);
+            row.extend(state.transitions.iter().map(|&c| names[c].clone()));
+            rows.push(row);
         }
-        table.to_string(" ")
+        rows
     }
 
     /// Checks if this DFA is equivalent to another DFA, that is, if they accept the same language.
@@ -901,45 +2897,372 @@ impl Dfa {
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
             return false;
         }
+        let translation = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet_index(elem).expect("the alphabets are equal"))
+            .collect::<Vec<_>>();
+        self.equivalent_translated(other, &translation)
+    }
 
+    /// Checks if this DFA is equivalent to another DFA after renaming the symbols of the other DFA, that is, if a word
+    /// is accepted by this DFA exactly when it is accepted by the other DFA with every symbol renamed by `mapping`.
+    /// The mapping consists of pairs of a symbol of the other DFA and the symbol of this DFA it is renamed to, and
+    /// needs to be a bijection between the alphabets, or a [MappingError] describing the problem is returned. The
+    /// other DFA isn't copied, instead the symbols are translated while comparing the DFAs.
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, MappingError};
+    ///
+    /// let parse = |table: &str| -> Dfa { dandy::parser::dfa(table).unwrap().try_into().unwrap() };
+    /// // Accepts the binary numbers with an even number of ones
+    /// let even_ones = parse("
+    ///         0  1
+    ///   → * e e  o
+    ///       o o  e
+    /// ");
+    /// // Accepts the words with an even number of b's
+    /// let even_bs = parse("
+    ///         a  b
+    ///   → * s s  t
+    ///       t t  s
+    /// ");
+    /// assert!(!even_ones.equivalent_to(&even_bs));
+    /// assert_eq!(even_ones.equivalent_to_under(&even_bs, &[("a", "0"), ("b", "1")]), Ok(true));
+    /// assert_eq!(even_ones.equivalent_to_under(&even_bs, &[("a", "1"), ("b", "0")]), Ok(false));
+    /// assert_eq!(
+    ///     even_ones.equivalent_to_under(&even_bs, &[("a", "0")]),
+    ///     Err(MappingError::Unmapped(vec!["b".to_string()]))
+    /// );
+    /// ```
+    pub fn equivalent_to_under(
+        &self,
+        other: &Dfa,
+        mapping: &[(&str, &str)],
+    ) -> Result<bool, MappingError> {
+        let translation = symbols::translation(&other.alphabet, &self.alphabet, mapping)?;
+        Ok(self.equivalent_translated(other, &translation))
+    }
+
+    /// Checks if this DFA is equivalent to another DFA, where the element with index `i` in the alphabet of this DFA
+    /// corresponds to the element with index `translation[i]` in the alphabet of the other DFA
+    fn equivalent_translated(&self, other: &Dfa, translation: &[usize]) -> bool {
         // initially, we explore the (pair of) initial states
-        let mut evaluators_to_explore = vec![(self.evaluator(), other.evaluator())];
-        let mut explored_states = HashSet::new();
-        explored_states.insert((
-            evaluators_to_explore[0].0.current_state_idx(),
-            evaluators_to_explore[0].1.current_state_idx(),
-        ));
+        let initial = (self.initial_state, other.initial_state);
+        let mut states_to_explore = vec![initial];
+        let mut explored_states = HashSet::from([initial]);
 
-        while let Some((s1, s2)) = evaluators_to_explore.pop() {
+        while let Some((s1, s2)) = states_to_explore.pop() {
+            let (s1, s2) = (&self.states[s1], &other.states[s2]);
             // we explore states s1 and s2
             // they must both be accepting or rejecting
-            if s1.is_accepting() != s2.is_accepting() {
+            if s1.accepting != s2.accepting {
                 return false;
             }
-            // for each char in alphabet, we step the evaluator. If we get new states, explore them!
-            for elem in self.alphabet.iter() {
-                let mut d1 = s1.clone();
-                d1.step(elem);
-                let mut d2 = s2.clone();
-                d2.step(elem);
-                if explored_states.insert((d1.current_state_idx(), d2.current_state_idx())) {
-                    evaluators_to_explore.push((d1, d2));
+            // for each element in the alphabet, we follow the transitions. If we get new states, explore them!
+            for (elem, &other_elem) in translation.iter().enumerate() {
+                let next = (s1.transitions[elem], s2.transitions[other_elem]);
+                if explored_states.insert(next) {
+                    states_to_explore.push(next);
                 }
             }
         }
         true
     }
 
+    /// Explores the pairs of states of this DFA and another DFA breadth-first, like [Dfa::equivalent_to], calling
+    /// `visitor` with each pair of states along with the shortest word leading to them, until a pair where exactly one
+    /// of the states is accepting is found. The elements of the alphabet are tried in sorted order, so the order in
+    /// which the pairs are visited doesn't depend on the column order of the DFAs. This is useful for explaining why
+    /// two DFAs are (or aren't) equivalent.
+    ///
+    /// This returns `Some(true)` if every reachable pair was visited without finding a mismatch, `Some(false)` if a
+    /// mismatching pair was visited (which is the last pair visited) or if the alphabets of the DFAs are unequal (in
+    /// which case no pairs are visited), and `None` if the visitor stopped the exploration by returning
+    /// [ControlFlow::Break].
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let parse = |table: &str| -> Dfa { dandy::parser::dfa(table).unwrap().try_into().unwrap() };
+    /// // Accepts the words ending with b
+    /// let ends_with_b = parse("
+    ///        a  b
+    ///   -> s s  t
+    ///    * t s  t
+    /// ");
+    /// // Accepts the words with at least one b
+    /// let has_b = parse("
+    ///        a  b
+    ///   -> x x  y
+    ///    * y y  y
+    /// ");
+    /// let mut words = vec![];
+    /// let result = ends_with_b.equivalence_trace(&has_b, |word, _, _| {
+    ///     words.push(word.concat());
+    ///     ControlFlow::Continue(())
+    /// });
+    /// assert_eq!(result, Some(false));
+    /// assert_eq!(words, ["", "b", "ba"]);
+    /// ```
+    pub fn equivalence_trace(
+        &self,
+        other: &Dfa,
+        mut visitor: impl FnMut(&[Rc<str>], &DfaState, &DfaState) -> ControlFlow<()>,
+    ) -> Option<bool> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return Some(false);
+        }
+        let mut order = (0..self.alphabet.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&idx| &self.alphabet[idx]);
+        let other_idx = order
+            .iter()
+            .map(|&idx| other.alphabet_index(&self.alphabet[idx]).unwrap())
+            .collect::<Vec<_>>();
+
+        // Every explored pair, and the explored pair and element (index in the alphabet of this DFA) each of them was
+        // first reached from, to be able to reconstruct the words
+        let mut explored = vec![(self.initial_state, other.initial_state)];
+        let mut reached_from: Vec<Option<(usize, usize)>> = vec![None];
+        let mut seen = HashSet::from([explored[0]]);
+        let mut queue = VecDeque::from([0]);
+        while let Some(pair_idx) = queue.pop_front() {
+            let (s1, s2) = explored[pair_idx];
+            let mut word = vec![];
+            let mut current = pair_idx;
+            while let Some((from, elem)) = reached_from[current] {
+                word.push(self.alphabet[elem].clone());
+                current = from;
+            }
+            word.reverse();
+
+            let (state1, state2) = (&self.states[s1], &other.states[s2]);
+            if visitor(&word, state1, state2).is_break() {
+                return None;
+            }
+            if state1.accepting != state2.accepting {
+                return Some(false);
+            }
+            for (&elem, &other_elem) in order.iter().zip(&other_idx) {
+                let pair = (state1.transitions[elem], state2.transitions[other_elem]);
+                if seen.insert(pair) {
+                    explored.push(pair);
+                    reached_from.push(Some((pair_idx, elem)));
+                    queue.push_back(explored.len() - 1);
+                }
+            }
+        }
+        Some(true)
+    }
+
+    /// Checks if this DFA is the same machine as another DFA up to renaming (and reordering) states, that is, if
+    /// there is a one-to-one mapping between their states preserving the initial state, the accepting states and
+    /// all transitions. The order of the alphabets doesn't matter, but the alphabets must be equal. Isomorphic DFAs
+    /// are always equivalent, but equivalent DFAs are often not isomorphic, for example if one of them isn't minimal.
+    ///
+    /// Since the initial states must be matched, and following the same element from matched states must lead to
+    /// matched states, the mapping of the reachable states is found by walking both DFAs in lockstep. Unreachable
+    /// states can't be matched that way, so if there are any, they are matched as described in [Nfa::isomorphic_to]
+    /// (and the DFAs are considered not to be isomorphic if that search gives up).
+    ///
+    /// ```
+    /// use dandy::dfa::Dfa;
+    /// use dandy::parser;
+    ///
+    /// let dfa: Dfa = parser::dfa("
+    ///        a b
+    ///   -> s t s
+    ///    * t t s
+    /// ").unwrap().try_into().unwrap();
+    /// let renamed: Dfa = parser::dfa("
+    ///        b a
+    ///    * x y x
+    ///   -> y y x
+    /// ").unwrap().try_into().unwrap();
+    /// assert!(dfa.isomorphic_to(&renamed));
+    /// ```
+    pub fn isomorphic_to(&self, other: &Dfa) -> bool {
+        if self.states.len() != other.states.len()
+            || !alphabet_equal(&self.alphabet, &other.alphabet)
+        {
+            return false;
+        }
+        let symbols = self
+            .alphabet
+            .iter()
+            .map(|elem| {
+                other
+                    .alphabet_index(elem)
+                    .expect("alphabets should be equal")
+            })
+            .collect::<Vec<_>>();
+
+        let mut this_to_other = vec![None; self.states.len()];
+        let mut other_to_this = vec![None; other.states.len()];
+        this_to_other[self.initial_state] = Some(other.initial_state);
+        other_to_this[other.initial_state] = Some(self.initial_state);
+        let mut to_explore = vec![(self.initial_state, other.initial_state)];
+        while let Some((s, o)) = to_explore.pop() {
+            if self.states[s].accepting != other.states[o].accepting {
+                return false;
+            }
+            for (idx, &other_idx) in symbols.iter().enumerate() {
+                let to_s = self.states[s].transitions[idx];
+                let to_o = other.states[o].transitions[other_idx];
+                match (this_to_other[to_s], other_to_this[to_o]) {
+                    (None, None) => {
+                        this_to_other[to_s] = Some(to_o);
+                        other_to_this[to_o] = Some(to_s);
+                        to_explore.push((to_s, to_o));
+                    }
+                    (Some(matched), _) if matched == to_o => {}
+                    _ => return false,
+                }
+            }
+        }
+
+        if this_to_other.iter().all(Option::is_some) {
+            true
+        } else {
+            self.clone()
+                .to_nfa()
+                .isomorphic_to(&other.clone().to_nfa())
+                .unwrap_or(false)
+        }
+    }
+
+    /// Applies the Unicode normalization form to the alphabet and the state names of this DFA, giving a DFA where
+    /// symbols typed in different (but canonically equivalent) ways are the same symbol. Words evaluated with the
+    /// normalized DFA should be normalized to the same form. Fails if two elements of the alphabet, or two state
+    /// names, are normalized to the same string, since they would be indistinguishable in the normalized DFA. The
+    /// normalized DFA of an already normalized DFA is the same DFA. Requires the `normalization` feature.
+    ///
+    /// ```
+    /// use dandy::dfa::{Dfa, NormalizationError, NormalizationForm};
+    ///
+    /// // '\u{e9}' is 'é' as a single character, which NFD decomposes into 'e' followed by a combining acute accent
+    /// let dfa: Dfa = dandy::parser::dfa("
+    ///        \u{e9} b
+    ///   → s  t      s
+    ///   * t  t      t
+    /// ").unwrap().try_into().unwrap();
+    /// let nfd = dfa.normalized(NormalizationForm::Nfd).unwrap();
+    /// assert!(nfd.accepts(&["e\u{301}"]));
+    /// assert!(!nfd.accepts(&["\u{e9}"]));
+    ///
+    /// let colliding: Dfa = dandy::parser::dfa("
+    ///      \u{e9} e\u{301}
+    ///   → s s      s
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(
+    ///     colliding.normalized(NormalizationForm::Nfc).unwrap_err(),
+    ///     NormalizationError::AlphabetCollision("\u{e9}".into(), "e\u{301}".into(), "\u{e9}".into())
+    /// );
+    /// ```
+    #[cfg(feature = "normalization")]
+    pub fn normalized(&self, form: NormalizationForm) -> Result<Dfa, NormalizationError> {
+        // Normalizes every string, failing with the given error if two of them collide
+        let normalize_all =
+            |strings: Vec<&Rc<str>>,
+             collision: fn(String, String, String) -> NormalizationError| {
+                let mut seen = HashMap::new();
+                strings
+                    .into_iter()
+                    .map(|string| {
+                        let normalized = form.normalize(string);
+                        if let Some(other) = seen.insert(normalized.clone(), string) {
+                            return Err(collision(
+                                other.to_string(),
+                                string.to_string(),
+                                normalized,
+                            ));
+                        }
+                        Ok(if **string == normalized {
+                            string.clone()
+                        } else {
+                            Rc::from(normalized)
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            };
+        let alphabet = normalize_all(
+            self.alphabet.iter().collect(),
+            NormalizationError::AlphabetCollision,
+        )?;
+        if let Some((elem, normalized)) = self
+            .alphabet
+            .iter()
+            .zip(&alphabet)
+            .find(|(_, normalized)| !util::valid_symbol(normalized))
+        {
+            return Err(NormalizationError::InvalidSymbol(
+                elem.to_string(),
+                normalized.to_string(),
+            ));
+        }
+        let names = normalize_all(
+            self.states.iter().map(|state| &state.name).collect(),
+            NormalizationError::StateCollision,
+        )?;
+
+        let mut dfa = self.clone();
+        dfa.alphabet = alphabet.into();
+        dfa.alphabet_lookup = AlphabetIndex::default();
+        dfa.states
+            .iter_mut()
+            .zip(names)
+            .for_each(|(state, name)| state.name = name);
+        dfa.provenance = Provenance::new(|| {
+            format!(
+                "{form:?} normalization of {}",
+                self.provenance.describe("DFA")
+            )
+        });
+        Ok(dfa)
+    }
+
     /// Gets the alphabet of this DFA
     pub fn alphabet(&self) -> &[Rc<str>] {
         &self.alphabet
     }
 
+    /// Gets the index of the given element in the alphabet of this DFA, or `None` if it isn't part of the alphabet.
+    /// The lookup table is built upon the first call and then reused, so repeated lookups are cheap.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let input = "
+    ///        a b
+    /// -> s   t s
+    ///  * t   t s
+    /// ";
+    /// let dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.alphabet_index("a"), Some(0));
+    /// assert_eq!(dfa.alphabet_index("b"), Some(1));
+    /// assert_eq!(dfa.alphabet_index("c"), None);
+    /// ```
+    pub fn alphabet_index(&self, symbol: &str) -> Option<usize> {
+        self.alphabet_lookup.get(&self.alphabet, symbol)
+    }
+
     /// Gets the states of this DFA
     pub fn states(&self) -> &[DfaState] {
         self.states.as_slice()
     }
 
+    /// Gets the number of states of this DFA, which is the same as `self.states().len()`
+    pub fn states_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Gets the size of the alphabet of this DFA, which is the same as `self.alphabet().len()`
+    pub fn alphabet_size(&self) -> usize {
+        self.alphabet.len()
+    }
+
     /// Gets the initial state of this DFA
     pub fn initial_state(&self) -> &DfaState {
         &self.states[self.initial_state]
@@ -949,4 +3272,144 @@ impl Dfa {
     pub fn initial_state_index(&self) -> usize {
         self.initial_state
     }
+
+    /// Gets the transition table of this DFA, where `table[state][elem]` is the index of the state the DFA moves to
+    /// from the state with index `state` upon seeing the element with index `elem` in the [alphabet](Dfa::alphabet).
+    /// Together with [Dfa::accepting_vector] and [Dfa::initial_state_vector], this is the textbook representation of
+    /// a DFA.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let even_as = "
+    ///        a b
+    /// -> * e o e
+    ///      o e o";
+    /// let dfa: Dfa = parser::dfa(even_as).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.transition_table(), vec![vec![1, 0], vec![0, 1]]);
+    /// assert_eq!(dfa.accepting_vector(), vec![true, false]);
+    /// assert_eq!(dfa.initial_state_vector(), vec![true, false]);
+    /// ```
+    pub fn transition_table(&self) -> Vec<Vec<usize>> {
+        self.states
+            .iter()
+            .map(|state| state.transitions.clone())
+            .collect()
+    }
+
+    /// Gets which states of this DFA are accepting, where `vector[state]` is true if the state with index `state` is
+    /// accepting
+    pub fn accepting_vector(&self) -> Vec<bool> {
+        self.states.iter().map(|state| state.accepting).collect()
+    }
+
+    /// Gets which state of this DFA is initial, where `vector[state]` is true if the state with index `state` is the
+    /// initial state. Exactly one element is true.
+    pub fn initial_state_vector(&self) -> Vec<bool> {
+        (0..self.states.len())
+            .map(|idx| idx == self.initial_state)
+            .collect()
+    }
+
+    /// Gets the number of transitions of this DFA. Since every state has exactly one transition for each element of
+    /// the alphabet, this is the number of states times the size of the alphabet.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = "
+    ///        a b c
+    /// -> * s s t s
+    ///      t t t t";
+    /// let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// assert_eq!(dfa.transition_count(), 6);
+    /// assert_eq!(dfa.accepting_state_count(), 1);
+    /// ```
+    pub fn transition_count(&self) -> usize {
+        self.states_count() * self.alphabet_size()
+    }
+
+    /// Gets the number of accepting states of this DFA
+    pub fn accepting_state_count(&self) -> usize {
+        self.states.iter().filter(|s| s.accepting).count()
+    }
+
+    /// Estimates the number of bytes this DFA occupies in memory, including the heap allocations it owns. The
+    /// estimate is deterministic, and is defined as the size of the [Dfa] struct itself, plus:
+    /// * The *capacity* (not length) of every `Vec`, since that is what is allocated, times the size of its elements,
+    /// * The length of every `Rc<str>` (the alphabet and the state names) plus the two reference counts next to it,
+    /// * The length of the alphabet slice plus the two reference counts next to it.
+    ///
+    /// Strings shared with other automata (for example the alphabet, after cloning a DFA) are counted in full for
+    /// every automaton, so this is an upper bound on the memory that would be freed by dropping this DFA. Overhead of
+    /// the allocator is not included.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::dfa::Dfa;
+    ///
+    /// let dfa = "
+    ///        a b
+    /// -> * s s t
+    ///      t t t";
+    /// let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    /// let product = dfa.union(&dfa).unwrap();
+    /// assert!(product.approx_memory_bytes() >= dfa.approx_memory_bytes());
+    /// ```
+    pub fn approx_memory_bytes(&self) -> usize {
+        let states = self
+            .states
+            .iter()
+            .map(|state| {
+                util::rc_str_bytes(&state.name)
+                    + state.transitions.capacity() * mem::size_of::<usize>()
+            })
+            .sum::<usize>();
+        mem::size_of::<Self>()
+            + util::alphabet_bytes(&self.alphabet)
+            + self.states.capacity() * mem::size_of::<DfaState>()
+            + states
+    }
+
+    /// Gets a description of how this DFA was created, for example "product of (parsed DFA, parsed DFA)", or `None`
+    /// if it was parsed from text. This is only available with the `provenance` feature, since recording it requires
+    /// some extra allocations for every operation creating a new automaton. Descriptions longer than 200 characters
+    /// (such as those of automata created by many operations in a row) are cut off with a `…`.
+    #[cfg(feature = "provenance")]
+    pub fn provenance(&self) -> Option<&str> {
+        self.provenance.get()
+    }
+}
+
+/// Joins the sorted names of a class of merged states into the name of the state they are merged into, see
+/// [Dfa::minimize_named]
+fn merged_name(names: &[Rc<str>]) -> String {
+    let mut name = names[0].to_string();
+    let mut len = name.chars().count();
+    for (idx, next) in names.iter().enumerate().skip(1) {
+        let next_len = next.chars().count() + 1;
+        if idx > 1 && len + next_len > MAX_MERGED_NAME_LEN {
+            name.push_str(&format!("{MERGED_NAME_SEPARATOR}…+{}", names.len() - idx));
+            break;
+        }
+        name.push(MERGED_NAME_SEPARATOR);
+        name.push_str(next);
+        len += next_len;
+    }
+    name
+}
+
+/// The highest of two priorities (the lowest value), where having a priority beats having none
+fn min_priority(p1: Option<u32>, p2: Option<u32>) -> Option<u32> {
+    match (p1, p2) {
+        (Some(p1), Some(p2)) => Some(p1.min(p2)),
+        _ => p1.or(p2),
+    }
+}
+
+/// The priority of a state if it is accepting
+fn accepting_priority(state: &DfaState) -> Option<u32> {
+    state.priority.filter(|_| state.accepting)
 }
@@ -0,0 +1,43 @@
+use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+
+/// The Unicode normalization forms which the alphabet and state names of a DFA can be normalized to, see
+/// [Dfa::normalized](crate::dfa::Dfa::normalized)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition, where `e` followed by a combining acute accent becomes `é`
+    Nfc,
+    /// Canonical decomposition, where `é` becomes `e` followed by a combining acute accent
+    Nfd,
+    /// Compatibility composition, which also replaces characters such as `ﬁ` by `fi` after composing them
+    Nfkc,
+    /// Compatibility decomposition, which replaces characters such as `ﬁ` by `fi` after decomposing them
+    Nfkd,
+}
+
+impl NormalizationForm {
+    pub(crate) fn normalize(self, s: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => s.nfc().collect(),
+            NormalizationForm::Nfd => s.nfd().collect(),
+            NormalizationForm::Nfkc => s.nfkc().collect(),
+            NormalizationForm::Nfkd => s.nfkd().collect(),
+        }
+    }
+}
+
+/// The reasons why the alphabet and state names of a DFA can't be normalized, see
+/// [Dfa::normalized](crate::dfa::Dfa::normalized)
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NormalizationError {
+    /// Two elements of the alphabet, which are both normalized to the third string
+    #[error("the elements '{0}' and '{1}' of the alphabet are both normalized to '{2}'")]
+    AlphabetCollision(String, String, String),
+    /// Two states, whose names are both normalized to the third string
+    #[error("the states '{0}' and '{1}' are both normalized to '{2}'")]
+    StateCollision(String, String, String),
+    /// An element of the alphabet which isn't a valid element once normalized, since it starts or ends with
+    /// whitespace
+    #[error("the element '{0}' of the alphabet is normalized to '{1}', which starts or ends with whitespace")]
+    InvalidSymbol(String, String),
+}
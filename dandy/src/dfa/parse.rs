@@ -1,5 +1,7 @@
 use crate::dfa::{Dfa, DfaState};
-use crate::parser::{ParsedDfa, ParsedDfaState};
+use crate::parser::{ParsedDfa, ParsedDfaOwned, ParsedDfaState};
+use crate::util::{valid_symbol, AlphabetIndex, Provenance, ReverseIndex, Version};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ops::Not;
 use std::rc::Rc;
@@ -8,17 +10,93 @@ use thiserror::Error;
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum DfaParseError<'a> {
     #[error("Wrong number of transitions for state '{0}': has {1} expected {2}")]
-    WrongNumberOfTransitions(&'a str, usize, usize),
+    WrongNumberOfTransitions(Cow<'a, str>, usize, usize),
     #[error("State '{1}' does not exist (in transition from state '{0}')")]
-    TransitionDoesNotExist(&'a str, &'a str),
+    TransitionDoesNotExist(Cow<'a, str>, Cow<'a, str>),
     #[error("There is no initial state")]
     MissingInitialState,
     #[error("There are two (or more) initial states")]
     MultipleInitialStates,
     #[error("'{0}' appears twice in the alphabet")]
-    DuplicateAlphabetSymbol(&'a str),
+    DuplicateAlphabetSymbol(Cow<'a, str>),
+    #[error("'{0}' is not a valid element of the alphabet, since it is empty or starts or ends with whitespace")]
+    InvalidAlphabetSymbol(Cow<'a, str>),
     #[error("State '{0}' defined multiple times")]
-    DuplicateStateDefinition(&'a str),
+    DuplicateStateDefinition(Cow<'a, str>),
+    #[error(
+        "The alphabet pragma declares the alphabet {0:?}, but the table has the alphabet {1:?}"
+    )]
+    AlphabetPragmaMismatch(Vec<Cow<'a, str>>, Vec<Cow<'a, str>>),
+}
+
+/// An owned version of [DfaParseError], which doesn't borrow from the parsed string. This is useful to propagate
+/// errors with `?` from functions that parse strings they own themselves, or to store errors for later use. Obtained
+/// by [DfaParseError::to_owned] or [From::from].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DfaParseErrorOwned {
+    #[error("Wrong number of transitions for state '{0}': has {1} expected {2}")]
+    WrongNumberOfTransitions(String, usize, usize),
+    #[error("State '{1}' does not exist (in transition from state '{0}')")]
+    TransitionDoesNotExist(String, String),
+    #[error("There is no initial state")]
+    MissingInitialState,
+    #[error("There are two (or more) initial states")]
+    MultipleInitialStates,
+    #[error("'{0}' appears twice in the alphabet")]
+    DuplicateAlphabetSymbol(String),
+    #[error("'{0}' is not a valid element of the alphabet, since it is empty or starts or ends with whitespace")]
+    InvalidAlphabetSymbol(String),
+    #[error("State '{0}' defined multiple times")]
+    DuplicateStateDefinition(String),
+    #[error(
+        "The alphabet pragma declares the alphabet {0:?}, but the table has the alphabet {1:?}"
+    )]
+    AlphabetPragmaMismatch(Vec<String>, Vec<String>),
+}
+
+impl DfaParseError<'_> {
+    /// Converts this error to an owned error, copying the borrowed parts of the parsed string
+    pub fn to_owned(&self) -> DfaParseErrorOwned {
+        use DfaParseError::*;
+        let owned = |elems: &[Cow<str>]| elems.iter().map(|elem| elem.to_string()).collect();
+        match self {
+            WrongNumberOfTransitions(state, has, expected) => {
+                DfaParseErrorOwned::WrongNumberOfTransitions(state.to_string(), *has, *expected)
+            }
+            TransitionDoesNotExist(from, to) => {
+                DfaParseErrorOwned::TransitionDoesNotExist(from.to_string(), to.to_string())
+            }
+            MissingInitialState => DfaParseErrorOwned::MissingInitialState,
+            MultipleInitialStates => DfaParseErrorOwned::MultipleInitialStates,
+            DuplicateAlphabetSymbol(symbol) => {
+                DfaParseErrorOwned::DuplicateAlphabetSymbol(symbol.to_string())
+            }
+            InvalidAlphabetSymbol(symbol) => {
+                DfaParseErrorOwned::InvalidAlphabetSymbol(symbol.to_string())
+            }
+            DuplicateStateDefinition(state) => {
+                DfaParseErrorOwned::DuplicateStateDefinition(state.to_string())
+            }
+            AlphabetPragmaMismatch(declared, actual) => {
+                DfaParseErrorOwned::AlphabetPragmaMismatch(owned(declared), owned(actual))
+            }
+        }
+    }
+}
+
+impl From<DfaParseError<'_>> for DfaParseErrorOwned {
+    fn from(value: DfaParseError<'_>) -> Self {
+        value.to_owned()
+    }
+}
+
+/// Validates an edited [ParsedDfaOwned] the same way as a [ParsedDfa]
+impl TryFrom<ParsedDfaOwned> for Dfa {
+    type Error = DfaParseErrorOwned;
+
+    fn try_from(value: ParsedDfaOwned) -> Result<Self, Self::Error> {
+        Dfa::try_from(value.as_parsed()).map_err(|e| e.to_owned())
+    }
 }
 
 impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
@@ -26,19 +104,40 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
 
     fn try_from(value: ParsedDfa<'a>) -> Result<Self, Self::Error> {
         use DfaParseError::*;
-        let ParsedDfa { head, states } = value;
+        let ParsedDfa {
+            head,
+            states,
+            declared_alphabet,
+        } = value;
 
         {
             let mut alphabet = HashSet::new();
             head.iter()
                 .try_for_each(|c| alphabet.insert(c).then_some(()).ok_or(c))
-                .map_err(|d| DuplicateAlphabetSymbol(d))?;
+                .map_err(|d| DuplicateAlphabetSymbol(d.clone()))?;
+        }
+        if let Some(symbol) = head.iter().find(|symbol| !valid_symbol(symbol)) {
+            return Err(InvalidAlphabetSymbol(symbol.clone()));
+        }
+
+        if let Some(declared) = declared_alphabet {
+            if declared.iter().copied().collect::<HashSet<_>>()
+                != head
+                    .iter()
+                    .map(|elem| elem.as_ref())
+                    .collect::<HashSet<_>>()
+            {
+                return Err(AlphabetPragmaMismatch(
+                    declared.into_iter().map(Cow::Borrowed).collect(),
+                    head,
+                ));
+            }
         }
 
         let state_name_map: HashMap<_, _> = states
             .iter()
             .enumerate()
-            .map(|(i, s)| (s.name, i))
+            .map(|(i, s)| (s.name.clone(), i))
             .collect();
 
         if state_name_map.len() != states.len() {
@@ -46,8 +145,8 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
             let mut seen = HashSet::new();
             let duplicate = states
                 .iter()
-                .find_map(|s| seen.insert(s.name).not().then_some(s.name))
-                .unwrap_or("<unknown>");
+                .find_map(|s| seen.insert(&s.name).not().then(|| s.name.clone()))
+                .unwrap_or(Cow::Borrowed("<unknown>"));
             return Err(DuplicateStateDefinition(duplicate));
         }
 
@@ -72,7 +171,7 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
 
             let mut new_transitions = Vec::with_capacity(head.len());
             for transition in transitions {
-                if let Some(idx) = state_name_map.get(transition) {
+                if let Some(idx) = state_name_map.get(transition.as_ref()) {
                     new_transitions.push(*idx);
                 } else {
                     return Err(TransitionDoesNotExist(name, transition)); // Target of transition does not exist
@@ -88,18 +187,23 @@ impl<'a> TryFrom<ParsedDfa<'a>> for Dfa {
             }
 
             new_states.push(DfaState {
-                name: Rc::from(name),
+                name: Rc::from(name.as_ref()),
                 initial,
                 accepting,
+                priority: None,
                 transitions: new_transitions,
             });
         }
 
         if let Some(initial_state) = initial_state {
             let dfa = Dfa {
-                alphabet: head.into_iter().map(Rc::from).collect(),
+                alphabet: head.iter().map(|elem| Rc::from(elem.as_ref())).collect(),
                 states: new_states,
                 initial_state,
+                alphabet_lookup: AlphabetIndex::default(),
+                version: Version::default(),
+                reverse_transitions: ReverseIndex::default(),
+                provenance: Provenance::none(),
             };
             Ok(dfa)
         } else {
@@ -0,0 +1,84 @@
+use crate::dfa::Dfa;
+use std::collections::VecDeque;
+
+/// The index in `other` of each element of the alphabet of `this`, where the alphabets are equal (not considering
+/// ordering)
+fn symbol_map(this: &Dfa, other: &Dfa) -> Vec<usize> {
+    this.alphabet
+        .iter()
+        .map(|elem| {
+            other
+                .alphabet_index(elem)
+                .expect("alphabets should be equal")
+        })
+        .collect()
+}
+
+/// Finds the states of `this` which are reached by some word accepted by `other`, in increasing order. These are
+/// found by exploring the pairs of states of `this` and `other` reachable from the pair of initial states.
+pub(crate) fn left_quotient_states(this: &Dfa, other: &Dfa) -> Vec<usize> {
+    let symbols = symbol_map(this, other);
+    let n = other.states.len();
+    let mut explored = vec![false; this.states.len() * n];
+    explored[this.initial_state * n + other.initial_state] = true;
+    let mut to_explore = vec![(this.initial_state, other.initial_state)];
+    let mut reached = vec![false; this.states.len()];
+
+    while let Some((s, o)) = to_explore.pop() {
+        if other.states[o].accepting {
+            reached[s] = true;
+        }
+        for (idx, &other_idx) in symbols.iter().enumerate() {
+            let to = (
+                this.states[s].transitions[idx],
+                other.states[o].transitions[other_idx],
+            );
+            if !explored[to.0 * n + to.1] {
+                explored[to.0 * n + to.1] = true;
+                to_explore.push(to);
+            }
+        }
+    }
+    (0..reached.len()).filter(|&s| reached[s]).collect()
+}
+
+/// Finds, for each state of `this`, if some word accepted by `other` leads from that state to an accepting state.
+/// This is found by exploring the pairs of states of `this` and `other` backwards, from the pairs of states where
+/// both are accepting, and checking which states of `this` are paired with the initial state of `other`.
+pub(crate) fn right_quotient_accepting(this: &Dfa, other: &Dfa) -> Vec<bool> {
+    let symbols = symbol_map(this, other);
+    let n = other.states.len();
+    let pairs = this.states.len() * n;
+    let mut predecessors = vec![vec![]; pairs];
+    for (s, state) in this.states.iter().enumerate() {
+        for (o, other_state) in other.states.iter().enumerate() {
+            for (idx, &other_idx) in symbols.iter().enumerate() {
+                let to = state.transitions[idx] * n + other_state.transitions[other_idx];
+                predecessors[to].push(s * n + o);
+            }
+        }
+    }
+
+    let mut leads_to_acceptance = vec![false; pairs];
+    let mut to_explore = VecDeque::new();
+    for (s, state) in this.states.iter().enumerate() {
+        for (o, other_state) in other.states.iter().enumerate() {
+            if state.accepting && other_state.accepting {
+                leads_to_acceptance[s * n + o] = true;
+                to_explore.push_back(s * n + o);
+            }
+        }
+    }
+    while let Some(pair) = to_explore.pop_front() {
+        for &from in &predecessors[pair] {
+            if !leads_to_acceptance[from] {
+                leads_to_acceptance[from] = true;
+                to_explore.push_back(from);
+            }
+        }
+    }
+
+    (0..this.states.len())
+        .map(|s| leads_to_acceptance[s * n + other.initial_state])
+        .collect()
+}
@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::rc::Rc;
+use thiserror::Error;
+
+/// Groups the indices of an alphabet of length `len` into classes of indices with equal columns, where the columns
+/// are given by `column`. The classes are ordered by their first index, and the indices of each class are in
+/// increasing order.
+pub(crate) fn classes<K: Eq + Hash>(len: usize, column: impl Fn(usize) -> K) -> Vec<Vec<usize>> {
+    let mut class_of = HashMap::new();
+    let mut classes: Vec<Vec<usize>> = vec![];
+    for idx in 0..len {
+        let class = *class_of.entry(column(idx)).or_insert_with(|| {
+            classes.push(vec![]);
+            classes.len() - 1
+        });
+        classes[class].push(idx);
+    }
+    classes
+}
+
+/// Gives the merges of the given classes of the alphabet, as pairs of each merged element and the element it was
+/// merged into (the first element of its class)
+pub(crate) fn merges(alphabet: &[Rc<str>], classes: &[Vec<usize>]) -> Vec<(Rc<str>, Rc<str>)> {
+    classes
+        .iter()
+        .flat_map(|class| {
+            let representative = &alphabet[class[0]];
+            class[1..]
+                .iter()
+                .map(|&idx| (alphabet[idx].clone(), representative.clone()))
+        })
+        .collect()
+}
+
+/// A translation of elements of an alphabet into other elements, made from the merges done by
+/// [Dfa::merge_equivalent_symbols](crate::dfa::Dfa::merge_equivalent_symbols) or
+/// [Nfa::merge_equivalent_symbols](crate::nfa::Nfa::merge_equivalent_symbols), such that words over the alphabet
+/// from before the merge can still be evaluated. Elements which weren't merged are translated into themselves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SymbolMap(HashMap<Rc<str>, Rc<str>>);
+
+impl SymbolMap {
+    /// Creates a map from pairs of merged elements and the elements they were merged into. The merges of multiple
+    /// calls to `merge_equivalent_symbols` may be given together, in which case elements are translated through
+    /// every merge.
+    pub fn new(merges: &[(Rc<str>, Rc<str>)]) -> Self {
+        Self(merges.iter().cloned().collect())
+    }
+
+    /// Translates an element into the element it was merged into, or gives the element back if it wasn't merged
+    pub fn translate<'a>(&'a self, mut symbol: &'a str) -> &'a str {
+        while let Some(merged_into) = self.0.get(symbol) {
+            symbol = merged_into;
+        }
+        symbol
+    }
+}
+
+/// An error in a renaming of the symbols of one alphabet into another, given to
+/// [Dfa::equivalent_to_under](crate::dfa::Dfa::equivalent_to_under) or
+/// [Nfa::equivalent_to_under](crate::nfa::Nfa::equivalent_to_under), which needs to be a bijection between the
+/// alphabets
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MappingError {
+    /// The symbols which are mapped more than once
+    #[error("the symbols {0:?} are mapped more than once")]
+    MappedTwice(Vec<String>),
+    /// The symbols which more than one symbol is mapped to
+    #[error("more than one symbol is mapped to each of the symbols {0:?}")]
+    NotInjective(Vec<String>),
+    /// The symbols which are mapped, but aren't in the alphabet of the renamed automaton
+    #[error("the mapped symbols {0:?} aren't in the alphabet")]
+    UnknownSource(Vec<String>),
+    /// The symbols which are mapped to, but aren't in the alphabet of the automaton compared to
+    #[error("the symbols {0:?} which are mapped to aren't in the alphabet compared to")]
+    UnknownTarget(Vec<String>),
+    /// The symbols of the renamed automaton which aren't mapped
+    #[error("the symbols {0:?} are unmapped")]
+    Unmapped(Vec<String>),
+    /// The symbols of the automaton compared to which no symbol is mapped to
+    #[error("no symbol is mapped to the symbols {0:?}")]
+    Unreached(Vec<String>),
+}
+
+/// Translates the indices of the `target` alphabet into indices of the `source` alphabet, through the `mapping` from
+/// symbols of `source` to symbols of `target`, checking that it is a bijection between the alphabets. The errors are
+/// checked in the order of the variants of [MappingError], and the symbols of each error are in the order of the
+/// mapping (or of the alphabet, for unmapped and unreached symbols).
+pub(crate) fn translation(
+    source: &[Rc<str>],
+    target: &[Rc<str>],
+    mapping: &[(&str, &str)],
+) -> Result<Vec<usize>, MappingError> {
+    let check = |symbols: Vec<String>, error: fn(Vec<String>) -> MappingError| {
+        if symbols.is_empty() {
+            Ok(())
+        } else {
+            Err(error(symbols))
+        }
+    };
+    let sources = || mapping.iter().map(|&(from, _)| from);
+    let targets = || mapping.iter().map(|&(_, to)| to);
+    check(duplicates(sources()), MappingError::MappedTwice)?;
+    check(duplicates(targets()), MappingError::NotInjective)?;
+    check(missing(sources(), source), MappingError::UnknownSource)?;
+    check(missing(targets(), target), MappingError::UnknownTarget)?;
+    let source_symbols = source.iter().map(AsRef::as_ref);
+    check(
+        missing(source_symbols, &sources().collect::<Vec<_>>()),
+        MappingError::Unmapped,
+    )?;
+    let target_symbols = target.iter().map(AsRef::as_ref);
+    check(
+        missing(target_symbols, &targets().collect::<Vec<_>>()),
+        MappingError::Unreached,
+    )?;
+
+    Ok(target
+        .iter()
+        .map(|elem| {
+            let (from, _) = mapping
+                .iter()
+                .find(|(_, to)| *to == elem.as_ref())
+                .expect("every symbol is reached");
+            source
+                .iter()
+                .position(|symbol| symbol.as_ref() == *from)
+                .expect("every mapped symbol is in the alphabet")
+        })
+        .collect())
+}
+
+/// The symbols occurring more than once, in the order of their second occurrences
+fn duplicates<'a>(symbols: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut duplicates = vec![];
+    for symbol in symbols {
+        if !seen.insert(symbol) && !duplicates.iter().any(|dup| dup == symbol) {
+            duplicates.push(symbol.to_string());
+        }
+    }
+    duplicates
+}
+
+/// The symbols which aren't in the alphabet, in order
+fn missing<'a>(
+    symbols: impl Iterator<Item = &'a str>,
+    alphabet: &[impl AsRef<str>],
+) -> Vec<String> {
+    symbols
+        .filter(|&symbol| !alphabet.iter().any(|elem| elem.as_ref() == symbol))
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,150 @@
+//! # Grading
+//! Comparing the language of a submitted DFA to the language of a specification (typically a regex converted to a
+//! minimal DFA), which is more informative than just checking their equivalence. [compare_language] tells whether the
+//! submission accepts exactly the specified language, too little of it, too much, or both, together with a word
+//! showing each problem:
+//! ```
+//! use dandy::dfa::Dfa;
+//! use dandy::grading::{self, LanguageRelation};
+//!
+//! let spec = dandy::parser::regex("a(a|b)*").unwrap().to_min_dfa();
+//! // Accepts words starting with 'a' and ending with 'b' (which is too restrictive)
+//! let submission: Dfa = dandy::parser::dfa("
+//!        a b
+//!   → s  t d
+//!     t  t u
+//!   * u  t u
+//!     d  d d
+//! ").unwrap().try_into().unwrap();
+//! assert_eq!(
+//!     grading::compare_language(&submission, &spec),
+//!     LanguageRelation::Subset { missing: "a".to_string() }
+//! );
+//! ```
+
+use crate::dfa::Dfa;
+use crate::regex::widen_alphabet;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+/// How the language of a submission relates to the language of a specification, given by [compare_language]. The
+/// witnesses are the shortest words (with the first one in the order of the alphabet among equally short words) showing
+/// that the languages differ, as the elements of the alphabet concatenated. Displaying a relation gives its
+/// [description](LanguageRelation::describe) followed by the witnesses, such as
+/// `subset (too restrictive), rejects "a"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageRelation {
+    /// The submission accepts exactly the language of the specification
+    Equal,
+    /// The submission accepts a strict subset of the language (it is too restrictive), where `missing` is accepted by
+    /// the specification but not by the submission
+    Subset { missing: String },
+    /// The submission accepts a strict superset of the language (it is too permissive), where `extra` is accepted by
+    /// the submission but not by the specification
+    Superset { extra: String },
+    /// Neither language is a subset of the other, where `missing` is accepted by the specification only and `extra`
+    /// by the submission only
+    Incomparable { missing: String, extra: String },
+}
+
+impl LanguageRelation {
+    /// A short description of this relation without the witnesses, such as `subset (too restrictive)`, which is the
+    /// same for all relations of the same kind (and may be used to count them)
+    pub fn describe(&self) -> &'static str {
+        match self {
+            LanguageRelation::Equal => "equal",
+            LanguageRelation::Subset { .. } => "subset (too restrictive)",
+            LanguageRelation::Superset { .. } => "superset (too permissive)",
+            LanguageRelation::Incomparable { .. } => "incomparable",
+        }
+    }
+
+    /// Checks if this relation is [LanguageRelation::Equal]
+    pub fn is_equal(&self) -> bool {
+        matches!(self, LanguageRelation::Equal)
+    }
+
+    /// The word accepted by the specification but not by the submission, if there is one
+    pub fn missing(&self) -> Option<&str> {
+        match self {
+            LanguageRelation::Subset { missing }
+            | LanguageRelation::Incomparable { missing, .. } => Some(missing),
+            _ => None,
+        }
+    }
+
+    /// The word accepted by the submission but not by the specification, if there is one
+    pub fn extra(&self) -> Option<&str> {
+        match self {
+            LanguageRelation::Superset { extra } | LanguageRelation::Incomparable { extra, .. } => {
+                Some(extra)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for LanguageRelation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.describe())?;
+        if let Some(missing) = self.missing() {
+            write!(f, ", rejects {missing:?}")?;
+        }
+        if let Some(extra) = self.extra() {
+            write!(f, ", accepts {extra:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the language of a submission to the language of a specification. If the alphabets differ, both DFAs are
+/// compared over the union of the alphabets, where words containing elements outside the alphabet of a DFA are
+/// rejected by it. This is the case for regexes in particular, whose alphabet only contains the graphemes appearing
+/// in them.
+pub fn compare_language(student: &Dfa, spec: &Dfa) -> LanguageRelation {
+    let (student, spec) = over_shared_alphabet(student, spec);
+    let missing = shortest_word(&spec, &student);
+    let extra = shortest_word(&student, &spec);
+    match (missing, extra) {
+        (None, None) => LanguageRelation::Equal,
+        (Some(missing), None) => LanguageRelation::Subset { missing },
+        (None, Some(extra)) => LanguageRelation::Superset { extra },
+        (Some(missing), Some(extra)) => LanguageRelation::Incomparable { missing, extra },
+    }
+}
+
+/// The shortest word accepted by `dfa` but not by `other`, which need to have equal alphabets
+fn shortest_word(dfa: &Dfa, other: &Dfa) -> Option<String> {
+    let difference = dfa
+        .difference(other)
+        .expect("the alphabets should be equal");
+    if !difference.has_reachable_accepting_state() {
+        return None;
+    }
+    let word = difference.to_nfa().word_components().next()?;
+    Some(word.iter().map(|elem| elem.as_ref()).collect())
+}
+
+/// Gives the DFAs over the union of their alphabets, where the elements only in the alphabet of one DFA lead to a dead
+/// state in the other one. The DFAs are borrowed if their alphabets are already equal.
+fn over_shared_alphabet<'a>(first: &'a Dfa, second: &'a Dfa) -> (Cow<'a, Dfa>, Cow<'a, Dfa>) {
+    let in_both = |a: &Dfa, b: &Dfa| a.alphabet().iter().all(|elem| b.alphabet().contains(elem));
+    if in_both(first, second) && in_both(second, first) {
+        return (Cow::Borrowed(first), Cow::Borrowed(second));
+    }
+    let mut alphabet = first.alphabet().to_vec();
+    alphabet.extend(
+        second
+            .alphabet()
+            .iter()
+            .filter(|elem| !first.alphabet().contains(elem))
+            .cloned(),
+    );
+    let widen = |dfa: &Dfa, alphabet: &[Rc<str>]| {
+        let mut nfa = dfa.clone().to_nfa();
+        widen_alphabet(&mut nfa, alphabet);
+        Cow::Owned(nfa.to_dfa())
+    };
+    (widen(first, &alphabet), widen(second, &alphabet))
+}
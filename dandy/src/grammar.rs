@@ -0,0 +1,290 @@
+//! # Grammars
+//! Right-linear grammars, which describe exactly the regular languages, just like finite automata. Every production of
+//! a right-linear grammar has a single nonterminal on its left-hand side, and a (possibly empty) sequence of terminals
+//! on its right-hand side, optionally followed by one nonterminal. A NFA is converted to a grammar by
+//! [Nfa::to_right_linear_grammar], where each state is a nonterminal, and converted back by [Grammar::to_nfa]:
+//! ```
+//! use dandy::grammar::{Grammar, GrammarSymbol};
+//! use dandy::nfa::Nfa;
+//!
+//! // Accepts words ending with "aab"
+//! let nfa: Nfa = dandy::parser::nfa("
+//!          a       b
+//!   → s1 {s1 s2} {s1}
+//!     s2 {s3}    {}
+//!     s3 {}      {s4}
+//!   * s4 {}      {}
+//! ").unwrap().try_into().unwrap();
+//! let grammar = nfa.to_right_linear_grammar();
+//! assert_eq!(grammar.start, "s1");
+//! assert_eq!(
+//!     grammar.to_string(),
+//!     "s1 -> a s1 | a s2 | b s1\ns2 -> a s3\ns3 -> b s4 | b\ns4 -> ε"
+//! );
+//! assert!(grammar.to_nfa().unwrap().equivalent_to(&nfa));
+//! ```
+
+use crate::nfa::{Nfa, NfaState};
+use crate::util::{valid_symbol, Provenance};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+use thiserror::Error;
+
+/// A right-linear grammar, given by its start symbol and its productions. Each production is a nonterminal and the
+/// sequence of symbols it may be replaced by, where an empty sequence is a production to ε. Several productions may
+/// have the same nonterminal on their left-hand side.
+///
+/// Displaying a grammar writes one line per nonterminal (the start symbol first, and then the others in the order
+/// their first production appears), with its alternatives separated by `|`, such as `A -> a B | b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    pub start: String,
+    pub productions: Vec<(String, Vec<GrammarSymbol>)>,
+}
+
+/// A symbol on the right-hand side of a production of a [Grammar]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GrammarSymbol {
+    /// An element of the alphabet
+    Terminal(Rc<str>),
+    /// A nonterminal, which is replaced using its productions
+    NonTerminal(String),
+}
+
+/// The reasons why a [Grammar] can't be converted to a NFA
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GrammarError {
+    /// The production with the given index has a nonterminal which isn't the last symbol of its right-hand side
+    #[error("production {index} is not right-linear, only its last symbol may be a nonterminal")]
+    NotRightLinear { index: usize },
+    /// A terminal of the grammar which isn't in the given alphabet
+    #[error("the terminal '{0}' is not in the alphabet")]
+    UnknownTerminal(Rc<str>),
+    /// A terminal of the grammar which can't be an element of an alphabet, since it is empty or starts or ends with
+    /// whitespace
+    #[error("the terminal '{0}' is empty or starts or ends with whitespace")]
+    InvalidTerminal(Rc<str>),
+    /// The given alphabet contains the same element more than once
+    #[error("the element '{0}' occurs more than once in the alphabet")]
+    DuplicateElement(Rc<str>),
+}
+
+impl Display for GrammarSymbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarSymbol::Terminal(terminal) => write!(f, "{terminal}"),
+            GrammarSymbol::NonTerminal(nonterminal) => write!(f, "{nonterminal}"),
+        }
+    }
+}
+
+impl Display for Grammar {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut nonterminals = vec![self.start.as_str()];
+        for (lhs, _) in &self.productions {
+            if !nonterminals.contains(&lhs.as_str()) {
+                nonterminals.push(lhs);
+            }
+        }
+        let lines = nonterminals
+            .into_iter()
+            .filter_map(|nonterminal| {
+                let alternatives = self
+                    .productions
+                    .iter()
+                    .filter(|(lhs, _)| lhs == nonterminal)
+                    .map(|(_, rhs)| match rhs.is_empty() {
+                        true => "ε".to_string(),
+                        false => rhs
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                    })
+                    .collect::<Vec<_>>();
+                (!alternatives.is_empty())
+                    .then(|| format!("{nonterminal} -> {}", alternatives.join(" | ")))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl Grammar {
+    /// Gets the terminals of this grammar, in the order they first appear in the productions
+    pub fn terminals(&self) -> Vec<Rc<str>> {
+        let mut terminals: Vec<Rc<str>> = vec![];
+        for symbol in self.productions.iter().flat_map(|(_, rhs)| rhs) {
+            if let GrammarSymbol::Terminal(terminal) = symbol {
+                if !terminals.contains(terminal) {
+                    terminals.push(terminal.clone());
+                }
+            }
+        }
+        terminals
+    }
+
+    /// Converts this grammar to a NFA whose alphabet is the terminals of the grammar, in the order they first appear.
+    /// See [Grammar::to_nfa_with_alphabet] for how the NFA is constructed.
+    pub fn to_nfa(&self) -> Result<Nfa, GrammarError> {
+        self.build_nfa(self.terminals())
+    }
+
+    /// Converts this grammar to a NFA with the given alphabet, which must contain every terminal of the grammar. Each
+    /// nonterminal becomes a state named after it (the start symbol being the initial state), which is accepting if it
+    /// has a production to ε. A production `A -> B` becomes an ε-move from `A` to `B`, and a production
+    /// `A -> a₁ … aₙ B` becomes a path reading `a₁ … aₙ` from `A` to `B`, through new intermediate states
+    /// if `n > 1`. Productions without a nonterminal, `A -> a₁ … aₙ`, instead end their paths in a new accepting
+    /// state `F` shared by all of them. New intermediate states are named after the nonterminal they come from, and new
+    /// states get as many `'` appended to their names as needed to make them unique.
+    ///
+    /// ```
+    /// use dandy::grammar::{Grammar, GrammarError, GrammarSymbol};
+    ///
+    /// let grammar = Grammar {
+    ///     start: "S".to_string(),
+    ///     productions: vec![
+    ///         ("S".to_string(), vec![GrammarSymbol::Terminal("a".into()), GrammarSymbol::NonTerminal("S".into())]),
+    ///         ("S".to_string(), vec![GrammarSymbol::Terminal("b".into())]),
+    ///     ],
+    /// };
+    /// let nfa = grammar.to_nfa_with_alphabet(&["b", "a"]).unwrap();
+    /// assert!(nfa.accepts(&["a", "a", "b"]));
+    /// assert!(!nfa.accepts(&["b", "a"]));
+    /// assert_eq!(grammar.to_nfa_with_alphabet(&["a"]).unwrap_err(), GrammarError::UnknownTerminal("b".into()));
+    /// ```
+    pub fn to_nfa_with_alphabet(&self, alphabet: &[&str]) -> Result<Nfa, GrammarError> {
+        let mut elements: Vec<Rc<str>> = Vec::with_capacity(alphabet.len());
+        for &elem in alphabet {
+            if elements.iter().any(|e| &**e == elem) {
+                return Err(GrammarError::DuplicateElement(Rc::from(elem)));
+            }
+            elements.push(Rc::from(elem));
+        }
+        if let Some(unknown) = self
+            .terminals()
+            .into_iter()
+            .find(|terminal| !elements.contains(terminal))
+        {
+            return Err(GrammarError::UnknownTerminal(unknown));
+        }
+        self.build_nfa(elements)
+    }
+
+    fn build_nfa(&self, alphabet: Vec<Rc<str>>) -> Result<Nfa, GrammarError> {
+        if let Some(invalid) = alphabet.iter().find(|elem| !valid_symbol(elem)) {
+            return Err(GrammarError::InvalidTerminal(invalid.clone()));
+        }
+        if let Some(index) = self.productions.iter().position(|(_, rhs)| {
+            rhs.iter()
+                .rev()
+                .skip(1)
+                .any(|symbol| matches!(symbol, GrammarSymbol::NonTerminal(_)))
+        }) {
+            return Err(GrammarError::NotRightLinear { index });
+        }
+
+        let mut nonterminals: Vec<&str> = vec![&self.start];
+        for (lhs, rhs) in &self.productions {
+            let last = match rhs.last() {
+                Some(GrammarSymbol::NonTerminal(nonterminal)) => Some(nonterminal.as_str()),
+                _ => None,
+            };
+            for nonterminal in [Some(lhs.as_str()), last].into_iter().flatten() {
+                if !nonterminals.contains(&nonterminal) {
+                    nonterminals.push(nonterminal);
+                }
+            }
+        }
+
+        let taken = nonterminals.iter().copied().collect::<HashSet<_>>();
+        let mut fresh_names = vec![];
+        let mut fresh = |base: &str| {
+            let mut name = base.to_string();
+            while taken.contains(name.as_str()) || fresh_names.contains(&name) {
+                name.push('\'');
+            }
+            fresh_names.push(name.clone());
+            Rc::from(name)
+        };
+        let new_state = |name: Rc<str>| NfaState {
+            name,
+            initial: false,
+            accepting: false,
+            epsilon_transitions: vec![],
+            transitions: vec![vec![]; alphabet.len()],
+        };
+        let index_of = |nonterminal: &str| {
+            nonterminals
+                .iter()
+                .position(|&n| n == nonterminal)
+                .expect("all nonterminals are collected")
+        };
+        let elem_index = |terminal: &Rc<str>| {
+            alphabet
+                .iter()
+                .position(|elem| elem == terminal)
+                .expect("the alphabet contains all terminals")
+        };
+
+        let mut states = nonterminals
+            .iter()
+            .map(|&nonterminal| new_state(Rc::from(nonterminal)))
+            .collect::<Vec<_>>();
+        states[0].initial = true;
+        let mut final_state = None;
+        for (lhs, rhs) in &self.productions {
+            let from = index_of(lhs);
+            let (terminals, target) = match rhs.split_last() {
+                None => {
+                    states[from].accepting = true;
+                    continue;
+                }
+                Some((GrammarSymbol::NonTerminal(nonterminal), terminals)) => {
+                    (terminals, Some(index_of(nonterminal)))
+                }
+                Some((GrammarSymbol::Terminal(_), _)) => (&rhs[..], None),
+            };
+            let target = target.unwrap_or_else(|| {
+                *final_state.get_or_insert_with(|| {
+                    let mut state = new_state(fresh("F"));
+                    state.accepting = true;
+                    states.push(state);
+                    states.len() - 1
+                })
+            });
+            let mut current = from;
+            for (i, symbol) in terminals.iter().enumerate() {
+                let GrammarSymbol::Terminal(terminal) = symbol else {
+                    unreachable!("only the last symbol may be a nonterminal");
+                };
+                let next = if i + 1 == terminals.len() {
+                    target
+                } else {
+                    states.push(new_state(fresh(lhs)));
+                    states.len() - 1
+                };
+                let targets = &mut states[current].transitions[elem_index(terminal)];
+                if !targets.contains(&next) {
+                    targets.push(next);
+                }
+                current = next;
+            }
+            if terminals.is_empty() && !states[current].epsilon_transitions.contains(&target) {
+                states[current].epsilon_transitions.push(target);
+            }
+        }
+
+        Ok(Nfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state: 0,
+            provenance: Provenance::new(|| {
+                format!("conversion from a grammar with start symbol {}", self.start)
+            }),
+            alphabet_lookup: Default::default(),
+            version: Default::default(),
+        })
+    }
+}
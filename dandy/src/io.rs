@@ -0,0 +1,339 @@
+//! # Reading automata from files
+//! [read_automaton] reads a DFA, NFA or regular expression from a file, and is meant to be used wherever a file
+//! written by hand (or exported from a spreadsheet) is loaded, such as in command line tools and benchmarks. Compared
+//! to reading the file and [parsing](crate::parser) it directly, it:
+//! - Decodes the file with [decode], which strips a UTF-8 byte order mark and accepts UTF-16 files,
+//! - Normalizes `\r\n` line endings to `\n`,
+//! - Reports the path of the file in every error, together with the line the parser stopped at (or, for tables that
+//!   parse but aren't valid automata, the line of the state at fault, if there is one).
+//!
+//! [read_automaton_lossy] also reads files which aren't valid UTF-8, replacing the invalid bytes, and gives back
+//! the decoding error as a warning. The functions locating errors in the parsed text ([parse_error_offset],
+//! [line_of], [dfa_error_slice] and [nfa_error_slice]) are public as well, for parsing text which isn't read from a
+//! file, such as in an editor.
+//!
+//! ```
+//! use dandy::io::{self, Automaton, AutomatonType};
+//! # use std::fs;
+//!
+//! let path = std::env::temp_dir().join("dandy-io-doctest.dfa");
+//! fs::write(&path, "\u{FEFF}   a  b\r\n-> s0 s1 s0\r\n * s1 s1 s1\r\n").unwrap();
+//! let Automaton::Dfa(dfa) = io::read_automaton(&path, AutomatonType::Dfa).unwrap() else {
+//!     panic!("read a DFA");
+//! };
+//! assert!(dfa.accepts_graphemes("ba"));
+//!
+//! fs::write(&path, "   a  b\n-> s0 s1 s0\n * s1 s1\n").unwrap();
+//! let error = io::read_automaton(&path, AutomatonType::Dfa).unwrap_err();
+//! assert_eq!(error.line(), Some(3));
+//! assert!(error.to_string().contains("dandy-io-doctest.dfa"));
+//! # fs::remove_file(&path).unwrap();
+//! ```
+
+use crate::dfa::parse::{DfaParseError, DfaParseErrorOwned};
+use crate::dfa::Dfa;
+use crate::nfa::parse::{NfaParseError, NfaParseErrorOwned};
+use crate::nfa::Nfa;
+use crate::parser::{self, decode, DecodeError, Detected};
+use crate::regex::Regex;
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The type of automaton to read with [read_automaton]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutomatonType {
+    Dfa,
+    Nfa,
+    Regex,
+    /// Detect the type from the contents of the file, see [parser::detect]
+    Auto,
+}
+
+impl Display for AutomatonType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AutomatonType::Dfa => write!(f, "DFA"),
+            AutomatonType::Nfa => write!(f, "NFA"),
+            AutomatonType::Regex => write!(f, "Regex"),
+            AutomatonType::Auto => write!(f, "automaton"),
+        }
+    }
+}
+
+/// An automaton read by [read_automaton]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Automaton {
+    Dfa(Dfa),
+    Nfa(Nfa),
+    /// A regular expression, along with the alphabet declared by its [alphabet pragma](parser#alphabet-pragma), if
+    /// it has one (which is checked to contain every element of the regular expression)
+    Regex(Regex, Option<Vec<String>>),
+}
+
+/// An error that occurred when reading an automaton with [read_automaton], which always names the file
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("Error loading file {}: {source}", .path.display())]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error loading file {}: {source}", .path.display())]
+    Decode { path: PathBuf, source: DecodeError },
+    #[error(
+        "Could not detect if {} contains a DFA, NFA or Regex, specify the type to read it",
+        .path.display()
+    )]
+    UnknownType { path: PathBuf },
+    /// The file couldn't be parsed, where `line` is the first line (starting from 1) the parser couldn't make sense
+    /// of, and `content` the rest of that line from where the parser stopped (which is empty if the parser expected
+    /// more input at the end of the file).
+    /// For DFAs and NFAs, `suggested` is the other kind of table if the file looks like one, see
+    /// [parser::suggest_type].
+    #[error("Error parsing {} on line {line} of {}: {}", .r#type, .path.display(), unexpected(.content))]
+    Parse {
+        path: PathBuf,
+        r#type: AutomatonType,
+        line: usize,
+        content: String,
+        suggested: Option<Detected>,
+    },
+    #[error("Error compiling DFA{}: {source}", location(.path, *.line))]
+    Dfa {
+        path: PathBuf,
+        line: Option<usize>,
+        source: DfaParseErrorOwned,
+    },
+    #[error("Error compiling NFA{}: {source}", location(.path, *.line))]
+    Nfa {
+        path: PathBuf,
+        line: Option<usize>,
+        source: NfaParseErrorOwned,
+    },
+    #[error(
+        "The Regex in {} contains elements not in the alphabet declared by its alphabet pragma {alphabet:?}",
+        .path.display()
+    )]
+    RegexAlphabet {
+        path: PathBuf,
+        alphabet: Vec<String>,
+    },
+}
+
+impl LoadError {
+    /// Gets the path of the file the error occurred in
+    pub fn path(&self) -> &Path {
+        match self {
+            LoadError::Read { path, .. }
+            | LoadError::Decode { path, .. }
+            | LoadError::UnknownType { path }
+            | LoadError::Parse { path, .. }
+            | LoadError::Dfa { path, .. }
+            | LoadError::Nfa { path, .. }
+            | LoadError::RegexAlphabet { path, .. } => path,
+        }
+    }
+
+    /// Gets the line (starting from 1) the error occurred on, if it is known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            LoadError::Parse { line, .. } => Some(*line),
+            LoadError::Dfa { line, .. } | LoadError::Nfa { line, .. } => *line,
+            _ => None,
+        }
+    }
+}
+
+/// Describes the content of the line a parse error occurred on
+fn unexpected(content: &str) -> String {
+    if content.is_empty() {
+        "unexpected end of file".to_string()
+    } else {
+        format!("unexpected {content:?}")
+    }
+}
+
+/// Describes where in the file an error occurred, if it is known
+fn location(path: &Path, line: Option<usize>) -> String {
+    line.map(|line| format!(" on line {line} of {}", path.display()))
+        .unwrap_or_default()
+}
+
+/// Reads the text of a file, decoding it with [decode] (which strips a UTF-8 byte order mark) and normalizing
+/// `\r\n` line endings to `\n`
+pub fn read_text(path: impl AsRef<Path>) -> Result<String, LoadError> {
+    read_text_inner(path.as_ref(), false).map(|(text, _)| text)
+}
+
+/// Reads the text of a file like [read_text], but if the file isn't valid UTF-8, the invalid bytes are replaced by
+/// `U+FFFD` and the error is given back as a warning
+fn read_text_inner(path: &Path, lossy: bool) -> Result<(String, Option<DecodeError>), LoadError> {
+    let bytes = fs::read(path).map_err(|source| LoadError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let (text, warning) = match decode(&bytes) {
+        Ok((text, _)) => (text.into_owned(), None),
+        Err(e @ DecodeError::InvalidUtf8 { .. }) if lossy => {
+            let rest = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&bytes);
+            (String::from_utf8_lossy(rest).into_owned(), Some(e))
+        }
+        Err(source) => {
+            return Err(LoadError::Decode {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+    let text = if text.contains("\r\n") {
+        text.replace("\r\n", "\n")
+    } else {
+        text
+    };
+    Ok((text, warning))
+}
+
+/// Reads and parses an automaton of the given type from a file. Files with [alphabet pragmas](parser#alphabet-pragma)
+/// are read like [parser::dfa_file], [parser::nfa_file] and [parser::regex_file] do. See the
+/// [module documentation](self) for how this differs from parsing the contents of the file directly.
+pub fn read_automaton(
+    path: impl AsRef<Path>,
+    r#type: AutomatonType,
+) -> Result<Automaton, LoadError> {
+    let path = path.as_ref();
+    let (text, _) = read_text_inner(path, false)?;
+    parse_automaton(path, &text, r#type)
+}
+
+/// Reads and parses an automaton like [read_automaton], but if the file isn't valid UTF-8, the invalid bytes are
+/// replaced by `U+FFFD` rather than giving an error. The decoding error is then given back as a warning, since the
+/// automaton probably isn't what the author of the file intended.
+pub fn read_automaton_lossy(
+    path: impl AsRef<Path>,
+    r#type: AutomatonType,
+) -> Result<(Automaton, Option<DecodeError>), LoadError> {
+    let path = path.as_ref();
+    let (text, warning) = read_text_inner(path, true)?;
+    parse_automaton(path, &text, r#type).map(|automaton| (automaton, warning))
+}
+
+/// Parses an automaton of the given type from the text of the file at `path`
+fn parse_automaton(path: &Path, text: &str, r#type: AutomatonType) -> Result<Automaton, LoadError> {
+    let r#type = match (r#type, parser::detect(text)) {
+        (AutomatonType::Auto, Detected::Dfa) => AutomatonType::Dfa,
+        (AutomatonType::Auto, Detected::Nfa) => AutomatonType::Nfa,
+        (AutomatonType::Auto, Detected::Regex) => AutomatonType::Regex,
+        (AutomatonType::Auto, Detected::Unknown) => {
+            return Err(LoadError::UnknownType {
+                path: path.to_path_buf(),
+            })
+        }
+        (r#type, _) => r#type,
+    };
+    let parse_error = |error: nom::error::Error<&str>, attempted: Option<Detected>| {
+        let offset = parse_error_offset(text, error.input);
+        let suggested =
+            attempted.and_then(|attempted| parser::suggest_type(error.input, attempted));
+        let line = text[..offset].matches('\n').count() + 1;
+        LoadError::Parse {
+            path: path.to_path_buf(),
+            r#type,
+            line,
+            content: text[offset..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            suggested,
+        }
+    };
+
+    match r#type {
+        AutomatonType::Dfa => {
+            let parsed = parser::dfa_file(text).map_err(|e| parse_error(e, Some(Detected::Dfa)))?;
+            let dfa = Dfa::try_from(parsed).map_err(|e| LoadError::Dfa {
+                path: path.to_path_buf(),
+                line: dfa_error_slice(&e).and_then(|slice| line_of(text, slice)),
+                source: e.into(),
+            })?;
+            Ok(Automaton::Dfa(dfa))
+        }
+        AutomatonType::Nfa => {
+            let parsed = parser::nfa_file(text).map_err(|e| parse_error(e, Some(Detected::Nfa)))?;
+            let nfa = Nfa::try_from(parsed).map_err(|e| LoadError::Nfa {
+                path: path.to_path_buf(),
+                line: nfa_error_slice(&e).and_then(|slice| line_of(text, slice)),
+                source: e.into(),
+            })?;
+            Ok(Automaton::Nfa(nfa))
+        }
+        AutomatonType::Regex => {
+            let (regex, alphabet) = parser::regex_file(text).map_err(|e| parse_error(e, None))?;
+            if let Some(alphabet) = &alphabet {
+                let elems = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+                if regex.clone().to_nfa_with_alphabet(&elems).is_err() {
+                    return Err(LoadError::RegexAlphabet {
+                        path: path.to_path_buf(),
+                        alphabet: alphabet.clone(),
+                    });
+                }
+            }
+            Ok(Automaton::Regex(regex, alphabet))
+        }
+        AutomatonType::Auto => unreachable!("The type is resolved"),
+    }
+}
+
+/// Finds the offset in `text` of the first non-whitespace character of the remaining input of a parse error, or of
+/// the end of the last line which isn't empty if there is no such character
+pub fn parse_error_offset(text: &str, remaining: &str) -> usize {
+    let offset = text.len() - remaining.len();
+    let skipped = remaining.len() - remaining.trim_start().len();
+    if offset + skipped < text.len() {
+        offset + skipped
+    } else {
+        text.trim_end().len()
+    }
+}
+
+/// Gets the line (starting from 1) of `slice` in `text`, or `None` if `slice` isn't a part of `text`
+pub fn line_of(text: &str, slice: &str) -> Option<usize> {
+    let start = (slice.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (start + slice.len() <= text.len()).then(|| text[..start].matches('\n').count() + 1)
+}
+
+/// Gets the part of the parsed text (such as a state name) which a [DfaParseError] is about, if there is one (names
+/// unescaped from quoted cells aren't part of the text)
+pub fn dfa_error_slice<'a>(error: &DfaParseError<'a>) -> Option<&'a str> {
+    match error {
+        DfaParseError::WrongNumberOfTransitions(state, ..)
+        | DfaParseError::TransitionDoesNotExist(_, state)
+        | DfaParseError::DuplicateAlphabetSymbol(state)
+        | DfaParseError::InvalidAlphabetSymbol(state)
+        | DfaParseError::DuplicateStateDefinition(state) => match state {
+            Cow::Borrowed(state) => Some(state),
+            Cow::Owned(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Gets the part of the parsed text (such as a state name) which a [NfaParseError] is about, if there is one (names
+/// unescaped from quoted cells aren't part of the text)
+pub fn nfa_error_slice<'a>(error: &NfaParseError<'a>) -> Option<&'a str> {
+    match error {
+        NfaParseError::WrongNumberOfTransitions(state, ..)
+        | NfaParseError::TransitionDoesNotExist(_, state)
+        | NfaParseError::DuplicateAlphabetSymbol(state)
+        | NfaParseError::InvalidAlphabetSymbol(state)
+        | NfaParseError::DuplicateStateDefinition(state) => match state {
+            Cow::Borrowed(state) => Some(state),
+            Cow::Owned(_) => None,
+        },
+        _ => None,
+    }
+}
@@ -99,6 +99,10 @@
 //! * [Checking whether two DFAs or two NFAs are equivalent](dfa::Dfa::equivalent_to)
 //! * Checking if a string is accepted by a [DFA](dfa::Dfa::accepts) or [NFA](nfa::Nfa::accepts)
 //! * [Step-by-step evaluation of a string](dfa::Dfa::evaluator)
+//! * [Saving](dfa::DfaEvaluator::checkpoint) and [resuming](dfa::Dfa::resume) a step-by-step evaluation, where the
+//!   checkpoints can be serialized with the `serde` feature
+//! * Serializing [DFAs](dfa::Dfa), [NFAs](nfa::Nfa) and [regexes](regex::Regex) with the `serde` feature, which also
+//!   provides conversions to and from JSON
 //! * [Identifying and removing unreachable states from a DFA](dfa::Dfa::unreachable_states)
 //! * [Identifying and merging non-distinguishable states from a DFA](dfa::Dfa::state_equivalence_classes)
 //! * [Minimizing a DFA](dfa::Dfa::minimize) (by executing the two above-mentioned steps)
@@ -106,19 +110,46 @@
 //!   [intersection](dfa::Dfa::intersection), [difference](dfa::Dfa::difference) and
 //!   [symmetric difference](dfa::Dfa::symmetric_difference) operations
 //! * [Product construction](nfa::Nfa::product_construction) for NFAs
-//! * [Enumerating all words](nfa::Nfa::words) accepted by a NFA
+//! * [Enumerating all words](nfa::Nfa::words) accepted by a NFA, optionally with bitpacked adjacency matrices
+//!   with the `fast-matrix` feature
 //! * [Removing epsilon moves](nfa::Nfa::remove_epsilon_moves) from a NFA
+//! * Converting NFAs to [right-linear grammars](nfa::Nfa::to_right_linear_grammar) and
+//!   [back](grammar::Grammar::to_nfa)
 //! * [Parsing regular expressions](parser::regex)
+//! * [Editing parsed tables](parser::ParsedDfaOwned) of DFAs and NFAs before they are validated, and converting
+//!   DFAs and NFAs [back](dfa::Dfa::to_parsed) to editable tables
+//! * [Reading automata from files](io::read_automaton), with errors naming the file and line
 //! * [Converting regular expressions to NFAs](regex::Regex::to_nfa)
+//! * [Asserting](testing) that two DFAs, NFAs or regexes are (or aren't) equivalent in tests, printing a
+//!   distinguishing word on failure
+//! * [Snapshot tests](testing#snapshots) of the tables of DFAs and NFAs with the `testing` feature, failing with a diff
+//!   when the formatting changes
+//! * [Grading](grading::compare_language) a DFA against a specification, telling whether it accepts too little or too
+//!   much of the language, with words showing why
+//! * [Aggregate statistics](analysis::corpus_report) over a corpus of words tested against a DFA or NFA, and
+//!   [how many states of an NFA are active](nfa::Nfa::active_state_profile) and
+//!   [which states and transitions of a DFA are used](dfa::Dfa::coverage) while reading them
+//! * Minimizing and comparing many DFAs on several threads with the `parallel` feature, in the `batch` module
+//! * [Unicode normalization](dfa::Dfa::normalized) of the alphabet and state names of a DFA with the
+//!   `normalization` feature, failing if two symbols or names would become the same
+//! * Recording how each DFA and NFA was created (for example by product or subset construction), called its
+//!   provenance, with the `provenance` feature
 //!
 //! See the documentation for [DFAs](dfa), [NFAs](nfa) and [Regular Expressions](regex) for more detailed
 //! information about each data type and their operations, together with some code examples
 
+pub mod analysis;
+#[cfg(feature = "parallel")]
+pub mod batch;
 pub mod dfa;
+pub mod grading;
+pub mod grammar;
+pub mod io;
 pub mod nfa;
 pub mod parser;
 pub mod regex;
 mod table;
+pub mod testing;
 #[cfg(test)]
 mod tests;
 mod util;
@@ -0,0 +1,178 @@
+use crate::nfa::Nfa;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Adds two counts of runs, where 2 means "two or more"
+fn add(a: u8, b: u8) -> u8 {
+    (a + b).min(2)
+}
+
+/// Checks if some word has at least two distinct accepting runs in the NFA, see
+/// [Nfa::is_ambiguous](crate::nfa::Nfa::is_ambiguous).
+///
+/// A run is split into the ε-moves from the initial state, followed by one step for each symbol of the word, where a
+/// step is a transition upon the symbol followed by any number of ε-moves. After counting the ways each step can be
+/// taken (between states which are reachable and can reach an accepting state, since no other state is part of any
+/// accepting run), the NFA is ambiguous if some step can be taken in two ways, or if two copies of the NFA reading
+/// the same word can be in different states and still both accept.
+pub(crate) fn is_ambiguous(nfa: &Nfa) -> bool {
+    let useful = useful_states(nfa);
+    if !useful[nfa.initial_state] {
+        return false;
+    }
+    let Some(epsilon_paths) = epsilon_paths(nfa, &useful) else {
+        // A useful state on an ε-cycle gives infinitely many runs
+        return true;
+    };
+
+    let initial = &epsilon_paths[nfa.initial_state];
+    if initial.values().any(|&count| count > 1) {
+        return true;
+    }
+    // steps[state][elem] are the states one step upon elem leads to from state, taken in exactly one way
+    let mut steps = vec![vec![vec![]; nfa.alphabet.len()]; nfa.states.len()];
+    for (from, state) in nfa
+        .states
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| useful[*idx])
+    {
+        for (elem, targets) in state.transitions.iter().enumerate() {
+            let mut counts = HashMap::new();
+            let targets = targets.iter().filter(|&&to| useful[to]);
+            for &to in targets.collect::<BTreeSet<_>>() {
+                for (&closure_state, &paths) in &epsilon_paths[to] {
+                    let count = counts.entry(closure_state).or_insert(0);
+                    *count = add(*count, paths);
+                }
+            }
+            if counts.values().any(|&count| count > 1) {
+                return true;
+            }
+            steps[from][elem] = counts.into_keys().collect();
+        }
+    }
+
+    // Explore the product of the NFA with itself, recording the reversed edges
+    let mut reached = HashSet::new();
+    let mut predecessors: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
+    let mut queue = vec![];
+    for &s1 in initial.keys() {
+        for &s2 in initial.keys() {
+            if reached.insert((s1, s2)) {
+                queue.push((s1, s2));
+            }
+        }
+    }
+    while let Some((s1, s2)) = queue.pop() {
+        for (targets1, targets2) in steps[s1].iter().zip(&steps[s2]) {
+            for &t1 in targets1 {
+                for &t2 in targets2 {
+                    predecessors.entry((t1, t2)).or_default().push((s1, s2));
+                    if reached.insert((t1, t2)) {
+                        queue.push((t1, t2));
+                    }
+                }
+            }
+        }
+    }
+
+    // Walk backwards from the pairs where both copies accept, looking for a pair where they are in different states
+    let mut queue = reached
+        .iter()
+        .copied()
+        .filter(|&(s1, s2)| nfa.states[s1].accepting && nfa.states[s2].accepting)
+        .collect::<Vec<_>>();
+    let mut productive = queue.iter().copied().collect::<HashSet<_>>();
+    while let Some(pair) = queue.pop() {
+        if pair.0 != pair.1 {
+            return true;
+        }
+        for &prev in predecessors.get(&pair).into_iter().flatten() {
+            if productive.insert(prev) {
+                queue.push(prev);
+            }
+        }
+    }
+    false
+}
+
+/// Finds the states which are both reachable from the initial state and can reach an accepting state
+fn useful_states(nfa: &Nfa) -> Vec<bool> {
+    let reachable = nfa.reachable_state_idx();
+    let mut predecessors = vec![vec![]; nfa.states.len()];
+    for (from, state) in nfa.states.iter().enumerate() {
+        for &to in state
+            .transitions
+            .iter()
+            .flatten()
+            .chain(&state.epsilon_transitions)
+        {
+            predecessors[to].push(from);
+        }
+    }
+    let mut productive = nfa.states.iter().map(|s| s.accepting).collect::<Vec<_>>();
+    let mut queue = (0..nfa.states.len())
+        .filter(|&idx| productive[idx])
+        .collect::<Vec<_>>();
+    while let Some(state) = queue.pop() {
+        for &prev in &predecessors[state] {
+            if !productive[prev] {
+                productive[prev] = true;
+                queue.push(prev);
+            }
+        }
+    }
+    (0..nfa.states.len())
+        .map(|idx| productive[idx] && reachable.contains(&idx))
+        .collect()
+}
+
+/// Counts the paths of ε-moves between useful states, such that `paths[from][to]` is the number of paths (up to 2)
+/// from `from` to `to`, including the empty path from every state to itself. This returns `None` if there is a
+/// cycle of ε-moves between useful states, in which case there are infinitely many paths.
+fn epsilon_paths(nfa: &Nfa, useful: &[bool]) -> Option<Vec<HashMap<usize, u8>>> {
+    let n = nfa.states.len();
+    let successors = (0..n)
+        .map(|idx| {
+            let targets = nfa.states[idx].epsilon_transitions.iter().copied();
+            let targets = targets.filter(|&to| useful[idx] && useful[to]);
+            targets.collect::<BTreeSet<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Order the states topologically (Kahn's algorithm), which fails if there is a cycle
+    let mut in_degree = vec![0; n];
+    successors
+        .iter()
+        .flatten()
+        .for_each(|&to| in_degree[to] += 1);
+    let mut queue = (0..n)
+        .filter(|&idx| in_degree[idx] == 0)
+        .collect::<Vec<_>>();
+    let mut order = vec![];
+    while let Some(state) = queue.pop() {
+        order.push(state);
+        for &to in &successors[state] {
+            in_degree[to] -= 1;
+            if in_degree[to] == 0 {
+                queue.push(to);
+            }
+        }
+    }
+    if order.len() != n {
+        return None;
+    }
+
+    let mut paths = vec![HashMap::new(); n];
+    for &state in order.iter().rev() {
+        let mut counts = HashMap::from([(state, 1)]);
+        for &to in &successors[state] {
+            for (&target, &count) in &paths[to] {
+                let total = counts.entry(target).or_insert(0);
+                *total = add(*total, count);
+            }
+        }
+        paths[state] = counts;
+    }
+    Some(paths)
+}
@@ -0,0 +1,79 @@
+use crate::nfa::Nfa;
+
+/// Checks if the language of `this` is a subset of the language of `other`, using the antichain algorithm. Both NFAs
+/// must have the same alphabet (not considering ordering).
+///
+/// The check explores pairs of a state of `this` and a set of states of `other` which both are reached on some
+/// word, looking for a pair where the state of `this` is accepting but none of the states of `other` is. A pair
+/// `(p, S)` is subsumed by a pair `(p, T)` if `T` is a subset of `S`, since any word leading to a counterexample from
+/// `(p, S)` also does so from `(p, T)`. Thus, for each state of `this`, only the minimal sets of `other` needs to be
+/// explored, which is what keeps this from determinizing `other` in the common case.
+pub(crate) fn included_in(this: &Nfa, other: &Nfa) -> bool {
+    // columns[j] is the column in `other` of the j:th element of the alphabet of `this`
+    let columns = this
+        .alphabet
+        .iter()
+        .map(|elem| {
+            other
+                .alphabet_index(elem)
+                .expect("Alphabets should be equal")
+        })
+        .collect::<Vec<_>>();
+    let this_closures = this.epsilon_closure_table();
+    let other_closures = other.epsilon_closure_table();
+
+    // antichain[p] is the minimal sets of states of `other` seen together with state `p` of `this`
+    let mut antichain: Vec<Vec<Vec<usize>>> = vec![vec![]; this.states.len()];
+    let mut to_explore = vec![];
+    let mut insert = |p: usize, set: Vec<usize>, to_explore: &mut Vec<(usize, Vec<usize>)>| {
+        if antichain[p].iter().any(|smaller| is_subset(smaller, &set)) {
+            return;
+        }
+        antichain[p].retain(|larger| !is_subset(&set, larger));
+        antichain[p].push(set.clone());
+        to_explore.push((p, set));
+    };
+
+    let initial = other_closures[other.initial_state].clone();
+    for &p in &this_closures[this.initial_state] {
+        insert(p, initial.clone(), &mut to_explore);
+    }
+
+    while let Some((p, set)) = to_explore.pop() {
+        if this.states[p].accepting && !set.iter().any(|&q| other.states[q].accepting) {
+            return false;
+        }
+        for (elem, &column) in columns.iter().enumerate() {
+            let next_set = closed_set(
+                set.iter()
+                    .flat_map(|&q| other.states[q].transitions[column].iter()),
+                &other_closures,
+            );
+            for &next in &this.states[p].transitions[elem] {
+                for &next in &this_closures[next] {
+                    insert(next, next_set.clone(), &mut to_explore);
+                }
+            }
+        }
+    }
+    true
+}
+
+/// The union of the closures of the given states, as a sorted `Vec`
+pub(crate) fn closed_set<'a>(
+    states: impl Iterator<Item = &'a usize>,
+    closures: &[Vec<usize>],
+) -> Vec<usize> {
+    let mut set = states
+        .flat_map(|&state| closures[state].iter().copied())
+        .collect::<Vec<_>>();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
+
+/// Checks if the sorted `Vec` `a` is a subset of the sorted `Vec` `b`
+fn is_subset(a: &[usize], b: &[usize]) -> bool {
+    let mut b = b.iter();
+    a.iter().all(|x| b.any(|y| y == x))
+}
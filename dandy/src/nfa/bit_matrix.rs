@@ -0,0 +1,85 @@
+use crate::nfa::words::NumBool::{self, *};
+use std::ops::{Index, Mul};
+
+/// A square matrix of booleans, packed 64 to a `u64` row by row, used in place of `DMatrix<NumBool>` for the
+/// adjacency matrices of the word enumeration when the `fast-matrix` feature is enabled. Multiplication is done with
+/// boolean semantics (`or` as addition and `and` as multiplication) just like for `NumBool`, but a whole word of the
+/// result row is computed with a single bitwise `or`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BitMatrix {
+    size: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl BitMatrix {
+    /// Creates a new matrix with `nrows` rows and `ncols` columns, where the element at `(row, col)` is given by `f`.
+    /// Only square matrices are supported, like the adjacency matrices they replace.
+    pub(crate) fn from_fn(
+        nrows: usize,
+        ncols: usize,
+        mut f: impl FnMut(usize, usize) -> NumBool,
+    ) -> Self {
+        assert_eq!(nrows, ncols, "BitMatrix only supports square matrices");
+        let words_per_row = nrows.div_ceil(64);
+        let mut bits = vec![0; nrows * words_per_row];
+        for row in 0..nrows {
+            for col in 0..ncols {
+                if f(row, col) == True {
+                    bits[row * words_per_row + col / 64] |= 1 << (col % 64);
+                }
+            }
+        }
+        Self {
+            size: nrows,
+            words_per_row,
+            bits,
+        }
+    }
+
+    fn row(&self, row: usize) -> &[u64] {
+        &self.bits[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    fn get(&self, row: usize, col: usize) -> bool {
+        self.row(row)[col / 64] & (1 << (col % 64)) != 0
+    }
+}
+
+impl Index<(usize, usize)> for BitMatrix {
+    type Output = NumBool;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.size && col < self.size,
+            "BitMatrix index out of bounds"
+        );
+        if self.get(row, col) {
+            &True
+        } else {
+            &False
+        }
+    }
+}
+
+impl Mul for &BitMatrix {
+    type Output = BitMatrix;
+
+    /// Row `i` of the product is the `or` of all rows `k` of `rhs` where `(i, k)` is set in `self`
+    fn mul(self, rhs: Self) -> Self::Output {
+        assert_eq!(self.size, rhs.size, "BitMatrix sizes should match");
+        let mut bits = vec![0; self.bits.len()];
+        for (row, out) in bits.chunks_mut(self.words_per_row.max(1)).enumerate() {
+            for k in (0..self.size).filter(|&k| self.get(row, k)) {
+                out.iter_mut()
+                    .zip(rhs.row(k))
+                    .for_each(|(out, rhs)| *out |= rhs);
+            }
+        }
+        BitMatrix {
+            size: self.size,
+            words_per_row: self.words_per_row,
+            bits,
+        }
+    }
+}
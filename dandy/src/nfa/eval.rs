@@ -1,12 +1,14 @@
+use crate::dfa::eval::CheckpointMismatch;
+use crate::dfa::SymbolMap;
 use crate::nfa::{Nfa, NfaState};
-use std::collections::{HashMap, HashSet};
-use std::iter;
-use std::rc::Rc;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::{iter, ptr};
+use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Clone, Debug)]
 pub struct NfaEvaluator<'a> {
     nfa: &'a Nfa,
-    rev_map: Rc<HashMap<&'a str, usize>>,
     current_states: HashSet<usize>,
     unknown_elem_seen: bool,
 }
@@ -31,6 +33,15 @@ impl<'a> NfaEvaluator<'a> {
         &self.current_states
     }
 
+    /// Gets the indices of the current states in ascending order, which is a canonical representation of the set of
+    /// current states, suitable as a key when caching results for sets of states. Evaluators of the same NFA are in
+    /// the same states if and only if their state keys are equal.
+    pub fn state_key(&self) -> Vec<usize> {
+        let mut states = self.current_states.iter().copied().collect::<Vec<_>>();
+        states.sort_unstable();
+        states
+    }
+
     pub fn step_all(&self) -> Vec<NfaEvaluator<'a>> {
         iter::repeat(self.clone())
             .zip(self.nfa.alphabet())
@@ -42,12 +53,12 @@ impl<'a> NfaEvaluator<'a> {
     }
 
     pub fn step(&mut self, elem: &str) -> Option<()> {
-        match self.rev_map.get(elem) {
+        match self.nfa.alphabet_index(elem) {
             None => {
                 self.unknown_elem_seen = true;
                 None
             }
-            Some(&idx) => {
+            Some(idx) => {
                 self.current_states = self
                     .current_states
                     .iter()
@@ -70,6 +81,51 @@ impl<'a> NfaEvaluator<'a> {
         }
     }
 
+    /// Steps this evaluator on an element after translating it with the given [SymbolMap], see
+    /// [Nfa::merge_equivalent_symbols]
+    pub fn step_mapped(&mut self, elem: &str, map: &SymbolMap) -> Option<()> {
+        self.step(map.translate(elem))
+    }
+
+    /// Steps this evaluator once for every grapheme of `s`, where every grapheme by itself is considered as an
+    /// element of the alphabet (see [Nfa::accepts_graphemes]). The graphemes are stepped as they are segmented, so
+    /// nothing is allocated, and the rest of `s` isn't segmented after a grapheme which isn't in the alphabet.
+    pub fn step_str(&mut self, s: &str) -> Option<()> {
+        s.graphemes(true).try_for_each(|g| self.step(g))
+    }
+
+    /// Saves the current state of this evaluator into a checkpoint, which can later be resumed with
+    /// [Nfa::resume]. The checkpoint doesn't borrow the NFA, but contains a [fingerprint](Nfa::fingerprint) of it
+    /// so that it can't be resumed against another NFA. Note that computing the fingerprint requires going through
+    /// the whole transition table of the NFA.
+    pub fn checkpoint(&self) -> NfaEvaluatorCheckpoint {
+        NfaEvaluatorCheckpoint {
+            states: self.state_key(),
+            unknown_elem_seen: self.unknown_elem_seen,
+            fingerprint: self.nfa.fingerprint(),
+        }
+    }
+
+    pub(crate) fn from_checkpoint(
+        nfa: &'a Nfa,
+        checkpoint: &NfaEvaluatorCheckpoint,
+    ) -> Result<Self, CheckpointMismatch> {
+        let fingerprint = nfa.fingerprint();
+        if checkpoint.fingerprint != fingerprint {
+            return Err(CheckpointMismatch::Fingerprint {
+                expected: fingerprint,
+                found: checkpoint.fingerprint,
+            });
+        }
+        if let Some(&state) = checkpoint.states.iter().find(|&&s| s >= nfa.states.len()) {
+            return Err(CheckpointMismatch::StateOutOfRange(state));
+        }
+        let mut evaluator = Self::from(nfa);
+        evaluator.current_states = checkpoint.states.iter().copied().collect();
+        evaluator.unknown_elem_seen = checkpoint.unknown_elem_seen;
+        Ok(evaluator)
+    }
+
     fn include_closure(&mut self) {
         let mut updated = true;
         let mut to_push = HashSet::new();
@@ -90,15 +146,8 @@ impl<'a> NfaEvaluator<'a> {
 
 impl<'a> From<&'a Nfa> for NfaEvaluator<'a> {
     fn from(value: &'a Nfa) -> Self {
-        let map = value
-            .alphabet
-            .iter()
-            .enumerate()
-            .map(|(idx, c)| (c as &str, idx))
-            .collect();
         let mut evaluator = Self {
             nfa: value,
-            rev_map: Rc::new(map),
             current_states: HashSet::new(),
             unknown_elem_seen: false,
         };
@@ -107,3 +156,50 @@ impl<'a> From<&'a Nfa> for NfaEvaluator<'a> {
         evaluator
     }
 }
+
+/// Two evaluators are equal if they evaluate the same NFA (the very same instance, not just an equal one), are in the
+/// same states and have both seen, or both not seen, an element not in the alphabet
+impl PartialEq for NfaEvaluator<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(self.nfa, other.nfa)
+            && self.unknown_elem_seen == other.unknown_elem_seen
+            && self.current_states == other.current_states
+    }
+}
+
+impl Eq for NfaEvaluator<'_> {}
+
+impl Hash for NfaEvaluator<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ptr::hash(self.nfa, state);
+        self.state_key().hash(state);
+        self.unknown_elem_seen.hash(state);
+    }
+}
+
+/// A snapshot of the state of a [NfaEvaluator], created by [NfaEvaluator::checkpoint], which can be resumed later
+/// (possibly on another machine) by [Nfa::resume]. With the `serde` feature enabled, checkpoints can be serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NfaEvaluatorCheckpoint {
+    states: Vec<usize>,
+    unknown_elem_seen: bool,
+    fingerprint: u64,
+}
+
+impl NfaEvaluatorCheckpoint {
+    /// Gets the indices of the states the evaluator was in, in ascending order
+    pub fn states_idx(&self) -> &[usize] {
+        &self.states
+    }
+
+    /// Checks if the evaluator had seen an element not in the alphabet
+    pub fn unknown_elem_seen(&self) -> bool {
+        self.unknown_elem_seen
+    }
+
+    /// Gets the fingerprint of the automaton the evaluator was evaluating
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
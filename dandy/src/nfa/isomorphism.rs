@@ -0,0 +1,217 @@
+use crate::nfa::Nfa;
+use std::collections::HashMap;
+
+/// The maximum number of candidate pairs of states tried by [isomorphic] before giving up
+pub(crate) const SEARCH_LIMIT: usize = 1_000_000;
+
+/// The transitions of a NFA by label, where the labels are the elements of the alphabet in some fixed order followed
+/// by ε, together with the reversed transitions
+struct Graph {
+    successors: Vec<Vec<Vec<usize>>>,
+    predecessors: Vec<Vec<Vec<usize>>>,
+}
+
+impl Graph {
+    /// Creates the graph of the NFA, where label `l` is element `order[l]` of the alphabet of the NFA
+    fn new(nfa: &Nfa, order: &[usize]) -> Self {
+        let n = nfa.states.len();
+        let labels = order.len() + 1;
+        let mut successors = vec![vec![vec![]; labels]; n];
+        let mut predecessors = vec![vec![vec![]; labels]; n];
+        for (from, state) in nfa.states.iter().enumerate() {
+            let targets = order
+                .iter()
+                .map(|&idx| &state.transitions[idx])
+                .chain([&state.epsilon_transitions]);
+            for (label, targets) in targets.enumerate() {
+                for &to in targets {
+                    successors[from][label].push(to);
+                    predecessors[to][label].push(from);
+                }
+            }
+        }
+        for lists in successors.iter_mut().chain(predecessors.iter_mut()) {
+            for list in lists {
+                list.sort_unstable();
+                list.dedup();
+            }
+        }
+        Self {
+            successors,
+            predecessors,
+        }
+    }
+}
+
+/// Checks if there is a bijection between the states of `this` and `other` preserving the initial state, accepting
+/// states and transitions, where the alphabets of the NFAs are equal (not considering ordering) and they have the
+/// same number of states. The states are first partitioned by color refinement, where two states get the same color
+/// if they have equally many transitions on each element to states of each color, after which a matching respecting
+/// the colors is searched for by backtracking. Returns `None` if more than `limit` candidate pairs were tried.
+pub(crate) fn isomorphic(this: &Nfa, other: &Nfa, limit: usize) -> Option<bool> {
+    let identity = (0..this.alphabet.len()).collect::<Vec<_>>();
+    let order = this
+        .alphabet
+        .iter()
+        .map(|elem| {
+            other
+                .alphabet_index(elem)
+                .expect("alphabets should be equal")
+        })
+        .collect::<Vec<_>>();
+    let a = Graph::new(this, &identity);
+    let b = Graph::new(other, &order);
+
+    let (colors_a, colors_b) = refine(this, other, &a, &b);
+    let mut histogram = HashMap::<usize, isize>::new();
+    for &color in &colors_a {
+        *histogram.entry(color).or_default() += 1;
+    }
+    for &color in &colors_b {
+        *histogram.entry(color).or_default() -= 1;
+    }
+    if histogram.values().any(|&count| count != 0) {
+        return Some(false);
+    }
+
+    // States with rare colors have few candidates, so they are matched first
+    let mut class_sizes = HashMap::<usize, usize>::new();
+    for &color in &colors_a {
+        *class_sizes.entry(color).or_default() += 1;
+    }
+    let mut order = (0..colors_a.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&s| (class_sizes[&colors_a[s]], s));
+    let mut search = Search {
+        a: &a,
+        b: &b,
+        colors_a: &colors_a,
+        colors_b: &colors_b,
+        a_to_b: vec![None; colors_a.len()],
+        b_to_a: vec![None; colors_b.len()],
+        tried: 0,
+        limit,
+    };
+    search.extend(&order)
+}
+
+/// Colors the states of both NFAs such that isomorphic states always get the same color, by refining the colors
+/// until they are stable. Colors are shared between the NFAs, so states of different NFAs with different colors can
+/// never be matched.
+fn refine(this: &Nfa, other: &Nfa, a: &Graph, b: &Graph) -> (Vec<usize>, Vec<usize>) {
+    let initial = |nfa: &Nfa| {
+        nfa.states
+            .iter()
+            .enumerate()
+            .map(|(idx, state)| {
+                vec![
+                    (idx == nfa.initial_state) as usize,
+                    state.accepting as usize,
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut ids = HashMap::new();
+    let mut colors_a = recolor(&mut ids, initial(this));
+    let mut colors_b = recolor(&mut ids, initial(other));
+    let mut count = ids.len();
+
+    loop {
+        let signatures = |graph: &Graph, colors: &[usize]| {
+            (0..colors.len())
+                .map(|s| {
+                    let mut signature = vec![colors[s]];
+                    for lists in [&graph.successors[s], &graph.predecessors[s]] {
+                        for list in lists {
+                            let mut neighbours =
+                                list.iter().map(|&t| colors[t]).collect::<Vec<_>>();
+                            neighbours.sort_unstable();
+                            signature.push(usize::MAX);
+                            signature.extend(neighbours);
+                        }
+                    }
+                    signature
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut ids = HashMap::new();
+        let new_a = recolor(&mut ids, signatures(a, &colors_a));
+        let new_b = recolor(&mut ids, signatures(b, &colors_b));
+        (colors_a, colors_b) = (new_a, new_b);
+        if ids.len() == count {
+            return (colors_a, colors_b);
+        }
+        count = ids.len();
+    }
+}
+
+/// Gives each distinct signature a color, sharing colors with signatures already seen
+fn recolor(ids: &mut HashMap<Vec<usize>, usize>, signatures: Vec<Vec<usize>>) -> Vec<usize> {
+    signatures
+        .into_iter()
+        .map(|signature| {
+            let next = ids.len();
+            *ids.entry(signature).or_insert(next)
+        })
+        .collect()
+}
+
+struct Search<'a> {
+    a: &'a Graph,
+    b: &'a Graph,
+    colors_a: &'a [usize],
+    colors_b: &'a [usize],
+    a_to_b: Vec<Option<usize>>,
+    b_to_a: Vec<Option<usize>>,
+    tried: usize,
+    limit: usize,
+}
+
+impl Search<'_> {
+    /// Matches the remaining states of `this` (in the given order) with states of `other`, giving `Some(true)` if
+    /// every state could be matched
+    fn extend(&mut self, remaining: &[usize]) -> Option<bool> {
+        let Some((&s, rest)) = remaining.split_first() else {
+            return Some(true);
+        };
+        for c in 0..self.colors_b.len() {
+            if self.colors_b[c] != self.colors_a[s] || self.b_to_a[c].is_some() {
+                continue;
+            }
+            self.tried += 1;
+            if self.tried > self.limit {
+                return None;
+            }
+            if !self.consistent(s, c) {
+                continue;
+            }
+            self.a_to_b[s] = Some(c);
+            self.b_to_a[c] = Some(s);
+            if self.extend(rest)? {
+                return Some(true);
+            }
+            self.a_to_b[s] = None;
+            self.b_to_a[c] = None;
+        }
+        Some(false)
+    }
+
+    /// Checks if matching `s` with `c` preserves all transitions between `s` and the states matched so far (including
+    /// `s` itself)
+    fn consistent(&self, s: usize, c: usize) -> bool {
+        let a_to_b = |t: usize| if t == s { Some(c) } else { self.a_to_b[t] };
+        let b_matched = |u: usize| u == c || self.b_to_a[u].is_some();
+        [
+            (&self.a.successors[s], &self.b.successors[c]),
+            (&self.a.predecessors[s], &self.b.predecessors[c]),
+        ]
+        .into_iter()
+        .all(|(lists_a, lists_b)| {
+            lists_a.iter().zip(lists_b).all(|(list_a, list_b)| {
+                let mut mapped = list_a.iter().filter_map(|&t| a_to_b(t)).collect::<Vec<_>>();
+                mapped.sort_unstable();
+                let matched = list_b.iter().copied().filter(|&u| b_matched(u));
+                mapped.into_iter().eq(matched)
+            })
+        })
+    }
+}
@@ -0,0 +1,70 @@
+use crate::nfa::parse::NfaParseErrorOwned;
+use crate::nfa::{Nfa, NfaState};
+use crate::util::{valid_symbol, AlphabetIndex, Provenance, Version};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// The serialized fields of a [Nfa], which are validated before being turned into a [Nfa] since the invariants of a
+/// [Nfa] can't be expressed in its serialized form
+#[derive(Deserialize)]
+pub(crate) struct NfaFields {
+    alphabet: Vec<Rc<str>>,
+    states: Vec<NfaState>,
+    initial_state: usize,
+}
+
+impl TryFrom<NfaFields> for Nfa {
+    type Error = NfaParseErrorOwned;
+
+    fn try_from(value: NfaFields) -> Result<Self, Self::Error> {
+        use NfaParseErrorOwned::*;
+        let NfaFields {
+            alphabet,
+            mut states,
+            initial_state,
+        } = value;
+
+        let mut seen = HashSet::new();
+        if let Some(symbol) = alphabet.iter().find(|&symbol| !seen.insert(symbol)) {
+            return Err(DuplicateAlphabetSymbol(symbol.to_string()));
+        }
+        if let Some(symbol) = alphabet.iter().find(|symbol| !valid_symbol(symbol)) {
+            return Err(InvalidAlphabetSymbol(symbol.to_string()));
+        }
+        let mut seen = HashSet::new();
+        if let Some(state) = states.iter().find(|&state| !seen.insert(&state.name)) {
+            return Err(DuplicateStateDefinition(state.name.to_string()));
+        }
+        if initial_state >= states.len() {
+            return Err(MissingInitialState);
+        }
+
+        for state in &states {
+            if state.transitions.len() != alphabet.len() {
+                return Err(WrongNumberOfTransitions(
+                    state.name.to_string(),
+                    state.transitions.len(),
+                    alphabet.len(),
+                ));
+            }
+            let targets = state.transitions.iter().chain([&state.epsilon_transitions]);
+            if let Some(to) = targets.flatten().find(|&&to| to >= states.len()) {
+                return Err(TransitionDoesNotExist(
+                    state.name.to_string(),
+                    to.to_string(),
+                ));
+            }
+        }
+        states[initial_state].initial = true;
+
+        Ok(Nfa {
+            alphabet: alphabet.into(),
+            states,
+            initial_state,
+            provenance: Provenance::none(),
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+        })
+    }
+}
@@ -0,0 +1,171 @@
+use crate::nfa::antichain::closed_set;
+use crate::nfa::Nfa;
+use std::collections::HashMap;
+use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The index of the initial set of states, which is never evicted
+const INITIAL: usize = 0;
+
+/// The smallest number of cached sets of states an evaluator may be limited to, which is the initial set, the current
+/// set and the set it steps to
+const MIN_MAX_STATES: usize = 3;
+
+/// An evaluator which determinizes an [Nfa] lazily, created by [Nfa::lazy_dfa_evaluator]. Every set of states (closed
+/// under ε-moves) reached is cached together with its transitions, which are computed the first time they are taken.
+/// Stepping on a cached transition is just a lookup, like stepping a DFA, so evaluating many words against the same
+/// NFA gets about as fast as with the DFA of [Nfa::to_dfa], while only the sets of states actually reached are ever
+/// computed. The cache is kept by [LazyDfaEvaluator::reset], so the evaluator should be reused between words.
+///
+/// Since the number of sets of states may grow exponentially, the cache may be limited with
+/// [LazyDfaEvaluator::with_max_states], in which case the least recently used set is evicted (together with all
+/// transitions to it) when another one is needed.
+#[derive(Clone, Debug)]
+pub struct LazyDfaEvaluator<'a> {
+    nfa: &'a Nfa,
+    /// The ε-closures of the states of the NFA, as sorted `Vec`s
+    closures: Vec<Vec<usize>>,
+    /// The cached sets of states of the NFA, as sorted indices
+    sets: Vec<Rc<[usize]>>,
+    /// The index in `sets` of every cached set
+    lookup: HashMap<Rc<[usize]>, usize>,
+    /// Whether every cached set contains an accepting state
+    accepting: Vec<bool>,
+    /// The transitions of the cached sets, where `transitions[set * alphabet length + elem]` is the set reached from
+    /// `set` on the element, if it has been computed (and the target hasn't been evicted)
+    transitions: Vec<Option<usize>>,
+    /// When every cached set was last stepped to, for evicting the least recently used one
+    last_used: Vec<u64>,
+    clock: u64,
+    max_states: usize,
+    /// The current set, or `None` if an element not in the alphabet has been seen
+    current: Option<usize>,
+}
+
+impl<'a> LazyDfaEvaluator<'a> {
+    pub(crate) fn new(nfa: &'a Nfa) -> Self {
+        let closures = nfa.epsilon_closure_table();
+        let initial: Rc<[usize]> = closures[nfa.initial_state].clone().into();
+        let mut evaluator = Self {
+            nfa,
+            closures,
+            sets: vec![],
+            lookup: HashMap::new(),
+            accepting: vec![],
+            transitions: vec![],
+            last_used: vec![],
+            clock: 0,
+            max_states: usize::MAX,
+            current: Some(INITIAL),
+        };
+        evaluator.insert(initial);
+        evaluator
+    }
+
+    /// Limits the number of cached sets of states to `max_states` (but at least 3), evicting the least recently used
+    /// set when another one is needed. If more sets than that are cached already, they are kept until evicted.
+    pub fn with_max_states(mut self, max_states: usize) -> Self {
+        self.max_states = max_states.max(MIN_MAX_STATES);
+        self
+    }
+
+    /// Steps this evaluator on an element. If the element isn't in the alphabet, this gives `None`, and the evaluator
+    /// rejects every word from then on (until it is [reset](LazyDfaEvaluator::reset)).
+    pub fn step(&mut self, elem: &str) -> Option<()> {
+        let (Some(current), Some(elem)) = (self.current, self.nfa.alphabet_index(elem)) else {
+            self.current = None;
+            return None;
+        };
+        let row = current * self.nfa.alphabet.len();
+        let next = match self.transitions[row + elem] {
+            Some(next) => next,
+            None => {
+                let set = closed_set(
+                    self.sets[current]
+                        .iter()
+                        .flat_map(|&state| &self.nfa.states[state].transitions[elem]),
+                    &self.closures,
+                );
+                let next = match self.lookup.get(set.as_slice()) {
+                    Some(&next) => next,
+                    None => self.insert(set.into()),
+                };
+                self.transitions[row + elem] = Some(next);
+                next
+            }
+        };
+        self.clock += 1;
+        self.last_used[next] = self.clock;
+        self.current = Some(next);
+        Some(())
+    }
+
+    /// Steps this evaluator once for every grapheme of `s`, where every grapheme by itself is considered as an
+    /// element of the alphabet, like [crate::nfa::NfaEvaluator::step_str]
+    pub fn step_str(&mut self, s: &str) -> Option<()> {
+        s.graphemes(true).try_for_each(|g| self.step(g))
+    }
+
+    /// Checks if the current set of states contains an accepting state (and no element outside the alphabet has been
+    /// seen)
+    pub fn is_accepting(&self) -> bool {
+        self.current.is_some_and(|current| self.accepting[current])
+    }
+
+    /// Resets this evaluator to the initial set of states, keeping the cached sets and transitions
+    pub fn reset(&mut self) {
+        self.current = Some(INITIAL);
+    }
+
+    /// Resets this evaluator and checks if it accepts the given string, where every grapheme is an element of the
+    /// alphabet, like [Nfa::accepts_graphemes]
+    pub fn accepts_graphemes(&mut self, s: &str) -> bool {
+        self.reset();
+        self.step_str(s).is_some() && self.is_accepting()
+    }
+
+    /// The number of sets of states currently cached, which is the number of states of the DFA discovered so far
+    /// (unless some have been evicted)
+    pub fn discovered_states(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Gets the indices of the current states in ascending order, like [crate::nfa::NfaEvaluator::state_key], or
+    /// `None` if an element not in the alphabet has been seen
+    pub fn current_states_idx(&self) -> Option<&[usize]> {
+        self.current.map(|current| self.sets[current].as_ref())
+    }
+
+    /// Caches a new set of states, evicting the least recently used set (other than the initial and the current one)
+    /// if the cache is full, and gives its index
+    fn insert(&mut self, set: Rc<[usize]>) -> usize {
+        let len = self.nfa.alphabet.len();
+        let accepting = set.iter().any(|&state| self.nfa.states[state].accepting);
+        let evicted = (self.sets.len() >= self.max_states)
+            .then(|| {
+                (0..self.sets.len())
+                    .filter(|&idx| idx != INITIAL && Some(idx) != self.current)
+                    .min_by_key(|&idx| self.last_used[idx])
+            })
+            .flatten();
+        let Some(idx) = evicted else {
+            self.lookup.insert(set.clone(), self.sets.len());
+            self.sets.push(set);
+            self.accepting.push(accepting);
+            self.transitions.extend((0..len).map(|_| None));
+            self.last_used.push(self.clock);
+            return self.sets.len() - 1;
+        };
+        self.lookup.remove(&self.sets[idx]);
+        self.transitions
+            .iter_mut()
+            .filter(|target| **target == Some(idx))
+            .for_each(|target| *target = None);
+        self.transitions[idx * len..(idx + 1) * len].fill(None);
+        self.lookup.insert(set.clone(), idx);
+        self.sets[idx] = set;
+        self.accepting[idx] = accepting;
+        self.last_used[idx] = self.clock;
+        idx
+    }
+}
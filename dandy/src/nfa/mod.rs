@@ -50,7 +50,8 @@
 //!
 //! ## Syntax
 //! The file format for NFAs is an UTF-8 encoded text file with more or less just a transition table.
-//! The first row of the file should contain all elements of the non-empty alphabet, space-separated. Then,
+//! The first row of the file should contain all elements of the alphabet, space-separated (an empty alphabet is
+//! written as just `ε`, or as `state` if there are no epsilon moves, see the [parser](crate::parser)). Then,
 //! there should be one row per state in the NFA (there must be at least one state), where each row contains
 //! these space-separated elements, in order:
 //! * Optionally `->` or `→`, if the state is the initial state
@@ -59,8 +60,8 @@
 //! * For each element of the alphabet specified in the header, in order, what states the Nfa transitions to from the
 //!   given state upon seeing that element, as a space-separated set encased in `{` and `}`
 //!
-//! `ε`, `eps`, `→`, `->` and `*` are reserved and may not be used as elements of the alphabet or names of
-//! states.
+//! `ε`, `eps`, `→`, `->` and `*` are reserved and may not be used as elements of the alphabet or names of states.
+//! Other tokens can be used (and reserved) instead by parsing with a [ParserConfig](crate::parser::ParserConfig).
 //!
 //! Additionally, these rules apply:
 //! * There must be exactly one (1) initial state
@@ -143,7 +144,10 @@
 //!
 //! Unlike for DFAs, there doesn't exist one unique minimized NFA which accepts the language of another NFA.
 //! Furthermore, no polynomial time algorithms are known (and none exists under the assumption `P != PSPACE`). This
-//! version of Dandy doesn't include a NFA minimization algorithm, but one may be added in the future.
+//! version of Dandy doesn't include a NFA minimization algorithm, but one may be added in the future. The states of a
+//! NFA can however be partitioned into classes of states accepting the same language in polynomial time by
+//! [Nfa::bisimulation_classes], and two NFAs may be compared the same way by [Nfa::bisimilar_to] and
+//! [Nfa::simulates], which are sound but incomplete alternatives to checking equivalence and inclusion of languages.
 //!
 //! The [Nfa] struct represents both NFAs with and without ε-moves, and one could check if the NFA has ε-moves by
 //! the [Nfa::has_epsilon_moves] method (which simply loops through the states and checks if any of them has any
@@ -210,6 +214,35 @@
 //! of two NFAs are infeasible. Dandy provides [Nfa::equivalent_to] which tracks all states reachable at the same time
 //! of the two provided NFAs, and if one is accepting while the other one is not, it rejects the NFAs as not equivalent.
 //!
+//! Since that effectively determinizes both NFAs, it may blow up. [Nfa::equivalent_to_antichain] instead checks that
+//! each language is a [subset](Nfa::subset_of_antichain) of the other using the antichain algorithm, which only
+//! explores the smallest sets of states the other NFA can be in, and is often much faster on such NFAs.
+//!
+//! A stricter, best-effort check is [Nfa::isomorphic_to], which checks if two NFAs are the same machine with states
+//! renamed.
+//!
+//! ### JSON
+//! With the `serde` feature enabled, NFAs can be serialized with any serde format, or to and from JSON directly with
+//! [Nfa::to_json_string] and [Nfa::from_json_str]. A NFA is represented just like a [DFA](crate::dfa), except that
+//! each transition is a list of state indices, and that each state also has a list of `epsilon_transitions`. This
+//! NFA accepting all words ending with an `a`
+//! ```text
+//!        ε  a       b
+//! -> s0 {}  {s0 s1} {s0}
+//!  * s1 {}  {}      {}
+//! ```
+//! is represented as:
+//! ```json
+//! {
+//!   "alphabet": ["a", "b"],
+//!   "states": [
+//!     { "name": "s0", "accepting": false, "epsilon_transitions": [], "transitions": [[0, 1], [0]] },
+//!     { "name": "s1", "accepting": true, "epsilon_transitions": [], "transitions": [[], []] }
+//!   ],
+//!   "initial_state": 0
+//! }
+//! ```
+//!
 //! ### Enumerating words
 //! An algorithm by [Margareta Ackerman and Jeffrey Shallit](https://maya-ackerman.com/wp-content/uploads/2018/09/Enumeration_AckermanShallit2.pdf)
 //! for enumerating words in the language of a NFA is implemented in Dandy. The word enumeration has two important
@@ -227,49 +260,101 @@
 //! * [Nfa::word_component_indices] with `impl Iterator<Item=Vec<usize>>` where each `usize` is the index to the
 //!   element in the alphabet
 //!
+//! To also get the accepting states the NFA ends up in on each word, for example to show which accepting state
+//! "fires" for each word in a table, use [Nfa::words_with_states].
+//!
 //! Due to the enumeration not skipping any words, iterating the words of a NFA and its complement will yield all
 //! strings of the alphabet.
 //!
+//! The [size hint](Iterator::size_hint) of these iterators, as well as the one of [Nfa::words_of_length], has an
+//! upper bound if and only if the NFA accepts finitely many words. The bound is computed by counting accepting paths,
+//! so it's exact for NFAs without nondeterminism and an overestimate otherwise.
+//!
 //! ### Additional operations
 //! In addition to the above-mentioned operations, you can:
-//! * [Get the alphabet](Nfa::alphabet) of the NFA,
+//! * [Get the alphabet](Nfa::alphabet) of the NFA, and [the index](Nfa::alphabet_index) of an element in it,
 //! * [Get the states](Nfa::states) and [initial state](Nfa::initial_state) of the NFA,
+//! * Get the [transition sets](Nfa::transition_sets) as plain vectors of indices,
 //! * [Convert it to a table](Nfa::to_table), possibly [in ascii-only](Nfa::ascii_table), both of which
 //!   can be parsed by Dandy into this very same NFA again,
+//! * [Convert it to a table with a custom column separator](Nfa::to_table_with_separator), such as
+//!   [pipes](Nfa::to_table_pipe_separated) for readability,
 //! * Find all [reachable](Nfa::reachable_states) and [non-reachable](Nfa::unreachable_states) states,
+//! * Find the [epsilon cycles](Nfa::epsilon_cycles) of the NFA,
+//! * Get a [smaller NFA](Nfa::to_minimized_nfa) by minimizing it as a DFA (which isn't a true NFA minimization),
+//! * [Reorder the alphabet](Nfa::with_alphabet_order), which changes the column order of the table and the order in
+//!   which words are enumerated,
+//! * Find the [classes of equivalent elements](Nfa::symbol_equivalence_classes) of the alphabet, and
+//!   [merge them](Nfa::merge_equivalent_symbols) while still [accepting the same words](Nfa::accepts_with_symbol_map),
+//! * Compute the [size of the minimal equivalent DFA](Nfa::minimal_dfa_size_bounded), giving up if the subset
+//!   construction gets too large,
+//! * Count the [states](Nfa::states_count), [alphabet elements](Nfa::alphabet_size),
+//!   [transitions](Nfa::transition_count) and [accepting states](Nfa::accepting_state_count), or
+//!   [estimate the memory footprint](Nfa::approx_memory_bytes) of the NFA,
+//! * Measure the nondeterminism of the NFA, by finding the [states with a choice](Nfa::nondeterministic_states),
+//!   the [largest transition set](Nfa::max_branching) and whether the NFA [is ambiguous](Nfa::is_ambiguous),
 //! * [Clone](Nfa::clone) it, which isn't super expensive since the alphabet and state names doesn't need new
 //!   allocations to be cloned (no strings at all are actually copied, just some `vec`s with `bool`s and `usize`s).
 //!   Note that since NFAs can have multiple transitions upon seeing each symbol, cloning a NFA inherently clones more
 //!   `vec`s and is more expensive than cloning a DFA.
 
-use crate::dfa::{Dfa, DfaState};
-use crate::nfa::words::{WordComponentIndices, WordComponents, Words};
-use crate::table::Table;
-use crate::util::alphabet_equal;
-pub use eval::NfaEvaluator;
-pub use parse::NfaParseError;
-use std::collections::{HashMap, HashSet};
+use crate::analysis::ActiveProfile;
+use crate::dfa::{symbols, CheckpointMismatch, Dfa, DfaState, MappingError, SymbolMap};
+use crate::grammar::{Grammar, GrammarSymbol};
+use crate::nfa::words::{WordComponentIndices, WordComponents, Words, WordsWithStates};
+use crate::parser::{ParsedNfaOwned, ParsedNfaStateOwned, TableStyle};
+use crate::table::{self, quote_cell, Table};
+use crate::util::{self, alphabet_equal, AlphabetIndex, Fnv64, Provenance, ReverseIndex, Version};
+pub use crate::util::{AlphabetDiff, BfsEvent, IndexRemap};
+pub use eval::{NfaEvaluator, NfaEvaluatorCheckpoint};
+pub use lazy::LazyDfaEvaluator;
+pub use parse::{NfaParseError, NfaParseErrorOwned};
+use simulation::WeakTransitions;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{self, Display, Formatter};
+use std::ops::ControlFlow;
 use std::rc::Rc;
 use std::{iter, mem};
+use thiserror::Error;
 use unicode_segmentation::UnicodeSegmentation;
 
+mod ambiguity;
+mod antichain;
+#[cfg(feature = "fast-matrix")]
+pub(crate) mod bit_matrix;
 pub mod eval;
+mod isomorphism;
+#[cfg(feature = "serde")]
+mod json;
+pub mod lazy;
 pub mod parse;
+mod simulation;
 pub mod words;
 
 /// A non-deterministic finite automata, denoted by its alphabet, states and the initial state
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "json::NfaFields"))]
 pub struct Nfa {
     pub(crate) alphabet: Rc<[Rc<str>]>,
     pub(crate) states: Vec<NfaState>,
     pub(crate) initial_state: usize,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) provenance: Provenance,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) alphabet_lookup: AlphabetIndex,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) version: Version,
 }
 
 /// A state in a NFA automata, which consists of its name, if it is the initial state or not, if it is accepting
 /// or not, any amount of epsilon transitions and any amount of transitions for each element in alphabet
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NfaState {
     pub(crate) name: Rc<str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) initial: bool,
     pub(crate) accepting: bool,
     pub(crate) epsilon_transitions: Vec<usize>,
@@ -304,6 +389,62 @@ impl NfaState {
     }
 }
 
+/// One of the two states making up a state of a [product construction](Nfa::product_construction_indexed), which is
+/// either a state of the NFA (by index) or no state at all if the NFA is in no state at that point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProductState<'a> {
+    /// The index of the state in its NFA, or `None` if the NFA is in no state
+    pub index: Option<usize>,
+    /// The state, or `None` if the NFA is in no state
+    pub state: Option<&'a NfaState>,
+}
+
+impl<'a> ProductState<'a> {
+    fn new(nfa: &'a Nfa, index: Option<usize>) -> Self {
+        Self {
+            index,
+            state: index.map(|idx| &nfa.states[idx]),
+        }
+    }
+
+    /// Gets the name of the state, or `None` if the NFA is in no state
+    pub fn name(&self) -> Option<&'a str> {
+        self.state.map(|state| state.name())
+    }
+
+    /// Checks if this is an accepting state (which is never the case if the NFA is in no state)
+    pub fn is_accepting(&self) -> bool {
+        self.state.is_some_and(|state| state.accepting)
+    }
+}
+
+/// The error returned when a subset construction would exceed the given maximum number of states, see
+/// [Nfa::minimal_dfa_size_bounded]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("The subset construction exceeded the bound of {max} states")]
+pub struct ExceededBound {
+    /// The maximum number of states which was exceeded
+    pub max: usize,
+}
+
+/// The error returned when the NFAs given to [Nfa::union_all] or [Nfa::intersect_all] don't all have the same
+/// alphabet (not considering ordering)
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("The alphabet of NFA {index} differs from the alphabet of the first NFA")]
+pub struct AlphabetMismatch {
+    /// The index of the first NFA whose alphabet differs from the alphabet of the first NFA
+    pub index: usize,
+    /// How the alphabet of the first NFA differs from the alphabet of that NFA
+    pub diff: AlphabetDiff,
+}
+
+impl Display for Nfa {
+    /// Formats the NFA as the table given by [Nfa::to_table]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_table())
+    }
+}
+
 impl Nfa {
     /// Constructs the intersection of two NFAs, that is, a new NFA that accepts exactly those strings that are accepted
     /// by either the first, the second NFA, or both. This returns `None` if and only if the alphabets of the two NFAs
@@ -313,6 +454,10 @@ impl Nfa {
     /// significant overhead. This function returns an Error with the two provided automatas if and only if the
     /// alphabets of the two automata differs (not considering ordering).
     ///
+    /// The alphabet of `self` always wins: the transitions of `other` are reordered to match the alphabet order of
+    /// `self`, so the column order of the table of the union, and the order in which its words are enumerated, follows
+    /// `self`. To get another order, see [Nfa::with_alphabet_order].
+    ///
     /// ```
     /// use dandy::parser;
     /// use dandy::nfa::Nfa;
@@ -342,6 +487,8 @@ impl Nfa {
     /// assert!(any.accepts_graphemes("bbabbaab"));
     /// assert!(any.accepts_graphemes("bbaabaab"));
     /// ```
+    // The error gives both NFAs back to the caller, so it is as large as it needs to be
+    #[allow(clippy::result_large_err)]
     pub fn union(mut self, mut other: Self) -> Result<Self, (Self, Self)> {
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
             return Err((self, other));
@@ -381,6 +528,7 @@ impl Nfa {
         other.remap_transitions(remapping);
 
         let b_init = remapping(other.initial_state).unwrap();
+        let other_provenance = other.provenance;
         self.states.extend(other.states);
 
         // Check uniqueness of names
@@ -389,8 +537,10 @@ impl Nfa {
             .iter()
             .map(|s| s.name.as_ref())
             .collect::<HashSet<_>>();
-        if names.len() != self.states.len() {
-            // Rename states
+        let new_state_name = if names.len() == self.states.len() {
+            Self::fresh_name_from_set("s_new", &names)
+        } else {
+            // Rename states, after which no state has the wanted name since they are all numbers
             let mut iter = 1..;
             self.states.iter_mut().for_each(|state| {
                 state.name = iter
@@ -398,10 +548,11 @@ impl Nfa {
                     .map(|i| Rc::from(i.to_string().as_str()))
                     .unwrap()
             });
-        }
+            Rc::from("s_new")
+        };
 
         let new_initial_state = NfaState {
-            name: self.fresh_name("s_new"),
+            name: new_state_name,
             initial: true,
             accepting: false,
             epsilon_transitions: vec![self.initial_state, b_init],
@@ -412,9 +563,201 @@ impl Nfa {
         self.states[b_init].initial = false;
         self.initial_state = self.states.len();
         self.states.push(new_initial_state);
+        self.provenance = Provenance::new(|| {
+            format!(
+                "union of ({}, {})",
+                self.provenance.describe("NFA"),
+                other_provenance.describe("NFA")
+            )
+        });
         Ok(self)
     }
 
+    /// Constructs the union of any number of NFAs, that is, a new NFA that accepts exactly those strings that are
+    /// accepted by at least one of the NFAs. Like [Nfa::union], a new initial state is added with epsilon transitions
+    /// to the initial states of all NFAs, but the states of all NFAs are moved into the new NFA at once, rather than
+    /// growing the union one NFA at a time as folding [Nfa::union] does. The alphabet of the first NFA wins, just like
+    /// the alphabet of `self` does in [Nfa::union], and if the names of the states aren't unique, they are numbered
+    /// instead.
+    ///
+    /// All alphabets are checked before anything is constructed, and an [AlphabetMismatch] is returned for the first
+    /// NFA whose alphabet differs from the alphabet of the first NFA (not considering ordering). The union of no NFAs
+    /// is a NFA with an empty alphabet and a single state which isn't accepting.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfas = ["a", "bb", "c*"]
+    ///     .into_iter()
+    ///     .map(|regex| parser::regex(regex).unwrap().to_nfa_with_alphabet(&["a", "b", "c"]).unwrap())
+    ///     .collect();
+    /// let any = Nfa::union_all(nfas).unwrap();
+    /// assert!(any.accepts_graphemes("a"));
+    /// assert!(any.accepts_graphemes("bb"));
+    /// assert!(any.accepts_graphemes("ccc"));
+    /// assert!(!any.accepts_graphemes("ab"));
+    ///
+    /// let mismatch = parser::regex("d").unwrap().to_nfa();
+    /// let error = Nfa::union_all(vec![any, mismatch]).unwrap_err();
+    /// assert_eq!(error.index, 1);
+    /// assert_eq!(error.diff.only_in_other, vec!["d".into()]);
+    /// ```
+    pub fn union_all(nfas: Vec<Nfa>) -> Result<Nfa, AlphabetMismatch> {
+        let Some(first) = nfas.first() else {
+            return Ok(Self::single_state(false));
+        };
+        Self::check_alphabets(&nfas)?;
+        let alphabet = first.alphabet.clone();
+        let descriptions = nfas
+            .iter()
+            .map(|nfa| nfa.provenance.clone())
+            .collect::<Vec<_>>();
+
+        let total = nfas.iter().map(|nfa| nfa.states.len()).sum::<usize>() + 1;
+        let mut states = Vec::with_capacity(total);
+        let mut initial_states = Vec::with_capacity(nfas.len());
+        for nfa in nfas {
+            let offset = states.len();
+            // columns[j] is the column of the j:th element of the alphabet of the first NFA in this NFA
+            let columns = alphabet
+                .iter()
+                .map(|elem| nfa.alphabet_index(elem).expect("Alphabets should be equal"))
+                .collect::<Vec<_>>();
+            let in_order = columns.iter().enumerate().all(|(j, &column)| j == column);
+            initial_states.push(nfa.initial_state + offset);
+            states.extend(nfa.states.into_iter().map(|mut state| {
+                let shift =
+                    |targets: &mut Vec<usize>| targets.iter_mut().for_each(|t| *t += offset);
+                shift(&mut state.epsilon_transitions);
+                state.transitions.iter_mut().for_each(shift);
+                if !in_order {
+                    state.transitions = columns
+                        .iter()
+                        .map(|&column| mem::take(&mut state.transitions[column]))
+                        .collect();
+                }
+                state.initial = false;
+                state
+            }));
+        }
+
+        let names = states
+            .iter()
+            .map(|s: &NfaState| s.name.as_ref())
+            .collect::<HashSet<_>>();
+        let new_state_name = if names.len() == states.len() {
+            Self::fresh_name_from_set("s_new", &names)
+        } else {
+            // Rename states, after which no state has the wanted name since they are all numbers
+            states
+                .iter_mut()
+                .zip(1..)
+                .for_each(|(state, i)| state.name = Rc::from(i.to_string().as_str()));
+            Rc::from("s_new")
+        };
+        let initial_state = states.len();
+        states.push(NfaState {
+            name: new_state_name,
+            initial: true,
+            accepting: false,
+            epsilon_transitions: initial_states,
+            transitions: vec![vec![]; alphabet.len()],
+        });
+
+        Ok(Nfa {
+            alphabet,
+            states,
+            initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::new(|| {
+                let operands = descriptions
+                    .iter()
+                    .map(|provenance| provenance.describe("NFA"))
+                    .collect::<Vec<_>>();
+                format!("union of ({})", operands.join(", "))
+            }),
+        })
+    }
+
+    /// Constructs the intersection of any number of NFAs, that is, a new NFA that accepts exactly those strings that
+    /// are accepted by all of the NFAs. The NFAs are intersected one at a time by [Nfa::intersection], stopping early
+    /// if an intermediate intersection has no accepting state, since it then accepts no strings and neither does the
+    /// intersection of it with the remaining NFAs. The alphabet of the first NFA wins.
+    ///
+    /// All alphabets are checked before anything is constructed, and an [AlphabetMismatch] is returned for the first
+    /// NFA whose alphabet differs from the alphabet of the first NFA (not considering ordering). The intersection of
+    /// no NFAs is a NFA with an empty alphabet and a single accepting state.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfas = ["(a|b)*a(a|b)*", "(a|b)*b(a|b)*", "(a|b)(a|b)(a|b)"]
+    ///     .into_iter()
+    ///     .map(|regex| parser::regex(regex).unwrap().to_nfa_with_alphabet(&["a", "b"]).unwrap())
+    ///     .collect();
+    /// let all = Nfa::intersect_all(nfas).unwrap();
+    /// assert!(all.accepts_graphemes("aab"));
+    /// assert!(all.accepts_graphemes("bba"));
+    /// assert!(!all.accepts_graphemes("aaa"));
+    /// assert!(!all.accepts_graphemes("ab"));
+    /// ```
+    pub fn intersect_all(nfas: Vec<Nfa>) -> Result<Nfa, AlphabetMismatch> {
+        Self::check_alphabets(&nfas)?;
+        let mut nfas = nfas.into_iter();
+        let Some(mut intersection) = nfas.next() else {
+            return Ok(Self::single_state(true));
+        };
+        for nfa in nfas {
+            // Without accepting states, neither this nor any later intersection accepts a string
+            if !intersection.states.iter().any(|state| state.accepting) {
+                break;
+            }
+            intersection = intersection
+                .intersection(&nfa)
+                .expect("Alphabets should be equal");
+        }
+        Ok(intersection)
+    }
+
+    /// Checks that all NFAs have the alphabet of the first NFA (not considering ordering)
+    fn check_alphabets(nfas: &[Nfa]) -> Result<(), AlphabetMismatch> {
+        let Some((first, rest)) = nfas.split_first() else {
+            return Ok(());
+        };
+        match rest
+            .iter()
+            .position(|nfa| !alphabet_equal(&first.alphabet, &nfa.alphabet))
+        {
+            Some(idx) => Err(AlphabetMismatch {
+                index: idx + 1,
+                diff: first.alphabet_diff(&rest[idx]),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// A NFA with an empty alphabet and a single state, which accepts the empty string if it is accepting and
+    /// otherwise nothing
+    fn single_state(accepting: bool) -> Self {
+        Nfa {
+            alphabet: Rc::from([]),
+            states: vec![NfaState {
+                name: Rc::from("s"),
+                initial: true,
+                accepting,
+                epsilon_transitions: vec![],
+                transitions: vec![],
+            }],
+            initial_state: 0,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::none(),
+        }
+    }
+
     /// Constructs the intersection of two NFAs, that is, a new NFA that accepts exactly those strings that are accepted
     /// by both the first and second NFAs. This returns `None` if and only if the alphabets of the two NFAs are unequal
     /// (not considering ordering). This is done by the product construction.
@@ -471,11 +814,60 @@ impl Nfa {
     /// constructions can only be used to calculate the union or intersection of two NFAs, since that doesn't include
     /// negation of any of the NFAs. However, for unions, there is a more efficient construction, see [Nfa::union].
     ///
-    /// If the alphabets of the provided automata differs, this function returns `None`.
+    /// If the alphabets of the provided automata differs, this function returns `None`. Just like [Nfa::union], the
+    /// constructed NFA has the alphabet of `self` in the same order, no matter the order of the alphabet of `other`.
+    /// This applies to [Nfa::intersection] as well. For access to the indices of the states and to veto transitions,
+    /// see [Nfa::product_construction_indexed].
     pub fn product_construction(
         &self,
         other: &Self,
         mut combinator: impl FnMut(Option<&NfaState>, Option<&NfaState>) -> bool,
+    ) -> Option<Self> {
+        self.product_construction_indexed(
+            other,
+            |s1, s2| combinator(s1.state, s2.state),
+            |_, _, _| true,
+        )
+    }
+
+    /// Constructs a new NFA from two NFAs using the product construction, just like [Nfa::product_construction], but
+    /// the `combinator` gets each pair of states as [ProductState]s, which includes the indices of the states. In
+    /// addition, every transition of the product on an element of the alphabet is first passed to `on_transition`,
+    /// together with the index of the element (in the alphabet of `self`), the pair of states it goes from and the
+    /// pair of states it goes to. If `on_transition` returns false, the transition is left out of the constructed
+    /// NFA, which allows for restricted products, such as only allowing some elements to be taken by one NFA alone.
+    /// Epsilon transitions are always kept. To keep every transition, pass `|_, _, _| true`.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa = "
+    ///         a    b
+    /// -> * s {s}  {t}
+    ///      t {}   {t}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// // Accept when the first NFA accepts while the second NFA is in state t
+    /// let product = nfa
+    ///     .product_construction_indexed(
+    ///         &nfa,
+    ///         |s1, s2| s1.is_accepting() && s2.name() == Some("t"),
+    ///         |_, _, _| true,
+    ///     )
+    ///     .unwrap();
+    /// // Since both NFAs move in lockstep, they are never in different states
+    /// assert!(product.words().next().is_none());
+    /// ```
+    pub fn product_construction_indexed(
+        &self,
+        other: &Self,
+        mut combinator: impl FnMut(ProductState, ProductState) -> bool,
+        mut on_transition: impl FnMut(
+            usize,
+            (ProductState, ProductState),
+            (ProductState, ProductState),
+        ) -> bool,
     ) -> Option<Self> {
         // If alphabets differ, we can't make a product construction
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
@@ -521,48 +913,44 @@ impl Nfa {
                         * s2.map_or(1, |s2| other.states[s2].transitions[other_elem].len()),
                 );
 
-                match (
+                let targets = match (
                     s1.filter(|&idx| !self.states[idx].transitions[elem].is_empty()),
                     s2.filter(|&idx| !other.states[idx].transitions[other_elem].is_empty()),
                 ) {
                     (Some(s1), Some(s2)) => {
                         let on_elem1 = &self.states[s1].transitions[elem];
                         let on_elem2 = &other.states[s2].transitions[other_elem];
-
-                        for &tr1 in on_elem1 {
-                            for &tr2 in on_elem2 {
-                                let states = (Some(tr1), Some(tr2));
-                                elem_transitions.push(states);
-                                if explored_states.insert(states) {
-                                    state_pairs_to_explore.push(states);
-                                }
-                            }
-                        }
+                        on_elem1
+                            .iter()
+                            .flat_map(|&tr1| {
+                                on_elem2.iter().map(move |&tr2| (Some(tr1), Some(tr2)))
+                            })
+                            .collect()
                     }
+                    (Some(s1), None) => self.states[s1].transitions[elem]
+                        .iter()
+                        .map(|&tr1| (Some(tr1), None))
+                        .collect(),
+                    (None, Some(s2)) => other.states[s2].transitions[other_elem]
+                        .iter()
+                        .map(|&tr2| (None, Some(tr2)))
+                        .collect(),
+                    (None, None) => vec![],
+                };
 
-                    (Some(s1), None) => {
-                        let on_elem1 = &self.states[s1].transitions[elem];
-                        for &tr1 in on_elem1 {
-                            let states = (Some(tr1), None);
-                            elem_transitions.push(states);
-                            if explored_states.insert(states) {
-                                state_pairs_to_explore.push(states);
-                            }
-                        }
+                for states in targets {
+                    let from = (ProductState::new(self, s1), ProductState::new(other, s2));
+                    let to = (
+                        ProductState::new(self, states.0),
+                        ProductState::new(other, states.1),
+                    );
+                    if !on_transition(elem, from, to) {
+                        continue;
                     }
-
-                    (None, Some(s2)) => {
-                        let on_elem2 = &other.states[s2].transitions[other_elem];
-                        for &tr2 in on_elem2 {
-                            let states = (None, Some(tr2));
-                            elem_transitions.push(states);
-                            if explored_states.insert(states) {
-                                state_pairs_to_explore.push(states);
-                            }
-                        }
+                    elem_transitions.push(states);
+                    if explored_states.insert(states) {
+                        state_pairs_to_explore.push(states);
                     }
-
-                    (None, None) => {}
                 }
 
                 transition_list.push(elem_transitions);
@@ -590,40 +978,29 @@ impl Nfa {
 
             state_data.push((
                 (s1, s2),
-                combinator(
-                    s1.map(|s1| &self.states[s1]),
-                    s2.map(|s2| &other.states[s2]),
-                ),
+                combinator(ProductState::new(self, s1), ProductState::new(other, s2)),
                 transition_list,
                 eps_transitions,
             ));
         }
 
-        // Try to generate new names for states
-        let names = {
-            let mut hm = HashSet::new();
-            let potential_names = explored_states
-                .iter()
-                .map_while(|(s1, s2)| {
-                    let combined_name: Rc<str> = Rc::from(format!(
-                        "({},{})",
-                        s1.map_or("none", |s1| &self.states[s1].name),
-                        s2.map_or("none", |s2| &other.states[s2].name)
-                    ));
-                    hm.insert(combined_name.clone())
-                        .then_some(((*s1, *s2), combined_name))
-                })
-                .collect::<HashMap<_, _>>();
-            if potential_names.len() < state_data.len() {
-                explored_states
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, (s1, s2))| ((*s1, *s2), Rc::from(format!("{idx}"))))
-                    .collect()
-            } else {
-                potential_names
-            }
-        };
+        // Try to generate new names for states, and if the names of some pairs collide, number the states in the
+        // order they were explored instead
+        let mut names = state_data
+            .iter()
+            .map(|((s1, s2), _, _, _)| {
+                Rc::from(format!(
+                    "({},{})",
+                    s1.map_or("none", |s1| &self.states[s1].name),
+                    s2.map_or("none", |s2| &other.states[s2].name)
+                ))
+            })
+            .collect::<Vec<Rc<str>>>();
+        if names.iter().collect::<HashSet<_>>().len() < names.len() {
+            names = (0..names.len())
+                .map(|idx| Rc::from(idx.to_string()))
+                .collect();
+        }
 
         let rev_state_idx_map = state_data
             .iter()
@@ -636,12 +1013,10 @@ impl Nfa {
 
         let states = state_data
             .into_iter()
+            .zip(names)
             .map(
-                |(states, accepting, transitions, epsilon_transitions)| NfaState {
-                    name: names
-                        .get(&states)
-                        .expect("All states should have a name")
-                        .clone(),
+                |((states, accepting, transitions, epsilon_transitions), name)| NfaState {
+                    name,
                     initial: states == (Some(q1), Some(q2)),
                     accepting,
                     transitions: transitions
@@ -672,15 +1047,264 @@ impl Nfa {
             alphabet: self.alphabet.clone(),
             states,
             initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::new(|| {
+                format!(
+                    "product of ({}, {})",
+                    self.provenance.describe("NFA"),
+                    other.provenance.describe("NFA")
+                )
+            }),
+        })
+    }
+
+    /// Creates a copy of this NFA with the alphabet in the given order, which must contain every element of the
+    /// alphabet exactly once. The transitions of every state are permuted accordingly, so the language of the NFA is
+    /// the same, but the column order of [Nfa::to_table] and the order in which [words are enumerated](Nfa::words)
+    /// follows the new order. Returns `None` if `order` is not a permutation of the alphabet.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa = "
+    ///         a   b
+    /// -> * s {s} {s}";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// let words = nfa.words().take(4).collect::<Vec<_>>();
+    /// assert_eq!(words, ["", "a", "b", "aa"]);
+    ///
+    /// let reordered = nfa.with_alphabet_order(&["b", "a"]).unwrap();
+    /// let words = reordered.words().take(4).collect::<Vec<_>>();
+    /// assert_eq!(words, ["", "b", "a", "bb"]);
+    /// assert!(reordered.equivalent_to(&nfa));
+    ///
+    /// assert!(nfa.with_alphabet_order(&["a"]).is_none());
+    /// assert!(nfa.with_alphabet_order(&["a", "a"]).is_none());
+    /// ```
+    pub fn with_alphabet_order(&self, order: &[&str]) -> Option<Nfa> {
+        if order.len() != self.alphabet.len() {
+            return None;
+        }
+        // permutation[i] is the index in the current alphabet of the i'th element of the new order
+        let permutation = order
+            .iter()
+            .map(|elem| self.alphabet_index(elem))
+            .collect::<Option<Vec<_>>>()?;
+        if permutation.iter().collect::<HashSet<_>>().len() != permutation.len() {
+            return None;
+        }
+
+        let alphabet = permutation
+            .iter()
+            .map(|&idx| self.alphabet[idx].clone())
+            .collect();
+        let states = self
+            .states
+            .iter()
+            .map(|state| NfaState {
+                transitions: permutation
+                    .iter()
+                    .map(|&idx| state.transitions[idx].clone())
+                    .collect(),
+                ..state.clone()
+            })
+            .collect();
+        Some(Nfa {
+            alphabet,
+            states,
+            initial_state: self.initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: self.provenance.clone(),
+        })
+    }
+
+    /// Gives the classes of equivalent elements of the alphabet, where two elements are equivalent if every state
+    /// transitions to the same set of states on both of them. The classes are ordered by their first element, and
+    /// the elements of each class are in the order of the alphabet. See [Dfa::symbol_equivalence_classes].
+    pub fn symbol_equivalence_classes(&self) -> Vec<Vec<Rc<str>>> {
+        self.symbol_equivalence_classes_idx()
+            .into_iter()
+            .map(|class| {
+                class
+                    .into_iter()
+                    .map(|idx| self.alphabet[idx].clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn symbol_equivalence_classes_idx(&self) -> Vec<Vec<usize>> {
+        symbols::classes(self.alphabet.len(), |idx| {
+            self.states
+                .iter()
+                .map(|state| {
+                    let mut targets = state.transitions[idx].clone();
+                    targets.sort_unstable();
+                    targets.dedup();
+                    targets
+                })
+                .collect::<Vec<_>>()
         })
     }
 
+    /// Merges every [class of equivalent elements](Nfa::symbol_equivalence_classes) of the alphabet into its first
+    /// element, removing the other elements from the alphabet. The words over the remaining alphabet are accepted just
+    /// like before. Returns pairs of every removed element and the element it was merged into, which can be made
+    /// into a [SymbolMap] to evaluate words over the previous alphabet, see [Nfa::accepts_with_symbol_map].
+    pub fn merge_equivalent_symbols(&mut self) -> Vec<(Rc<str>, Rc<str>)> {
+        self.version.bump();
+        let classes = self.symbol_equivalence_classes_idx();
+        let merges = symbols::merges(&self.alphabet, &classes);
+        if merges.is_empty() {
+            return merges;
+        }
+        self.alphabet = classes
+            .iter()
+            .map(|class| self.alphabet[class[0]].clone())
+            .collect();
+        for state in &mut self.states {
+            let mut transitions = mem::take(&mut state.transitions);
+            state.transitions = classes
+                .iter()
+                .map(|class| mem::take(&mut transitions[class[0]]))
+                .collect();
+        }
+        self.alphabet_lookup = AlphabetIndex::default();
+        merges
+    }
+
+    /// Finds the elements of the alphabet which no state has any transition on, in the order of the alphabet.
+    /// Removing these elements from the alphabet with [Nfa::prune_symbols] doesn't change which words over the
+    /// remaining elements are accepted. Unlike [Dfa::unused_symbols], elements with transitions that don't lead to
+    /// any accepting state aren't included, since every state of a DFA needs a transition on every element but NFAs
+    /// can simply leave them out.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let input = "
+    ///          a    b   c
+    /// -> s    {t}  {}  {}
+    ///  * t    {}   {t} {}
+    /// ";
+    /// let mut nfa: Nfa = parser::nfa(input).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.unused_symbols(), vec!["c".into()]);
+    /// assert_eq!(nfa.prune_symbols(), vec!["c".into()]);
+    /// assert_eq!(nfa.alphabet(), &["a".into(), "b".into()]);
+    /// assert!(nfa.accepts(&["a", "b", "b"]));
+    /// ```
+    pub fn unused_symbols(&self) -> Vec<Rc<str>> {
+        self.unused_symbols_idx()
+            .into_iter()
+            .map(|idx| self.alphabet[idx].clone())
+            .collect()
+    }
+
+    fn unused_symbols_idx(&self) -> Vec<usize> {
+        (0..self.alphabet.len())
+            .filter(|&elem| {
+                self.states
+                    .iter()
+                    .all(|state| state.transitions[elem].is_empty())
+            })
+            .collect()
+    }
+
+    /// Removes the [unused elements](Nfa::unused_symbols) from the alphabet, and the (empty) transitions on them from
+    /// every state, returning the removed elements. Just like [Dfa::prune_symbols], this changes the alphabet, so the
+    /// pruned NFA isn't [equivalent](Nfa::equivalent_to) to the original NFA, and only the words over the remaining
+    /// elements are accepted like before.
+    pub fn prune_symbols(&mut self) -> Vec<Rc<str>> {
+        self.version.bump();
+        let unused = self.unused_symbols_idx();
+        if unused.is_empty() {
+            return vec![];
+        }
+        let kept = (0..self.alphabet.len())
+            .filter(|elem| !unused.contains(elem))
+            .collect::<Vec<_>>();
+        let removed = unused
+            .iter()
+            .map(|&idx| self.alphabet[idx].clone())
+            .collect();
+        self.alphabet = kept.iter().map(|&idx| self.alphabet[idx].clone()).collect();
+        for state in &mut self.states {
+            let mut transitions = mem::take(&mut state.transitions);
+            state.transitions = kept
+                .iter()
+                .map(|&idx| mem::take(&mut transitions[idx]))
+                .collect();
+        }
+        self.alphabet_lookup = AlphabetIndex::default();
+        removed
+    }
+
+    /// Checks if this NFA accepts the given string, after translating every element with the given [SymbolMap].
+    /// This accepts the same words as before [merging equivalent elements](Nfa::merge_equivalent_symbols) if the map
+    /// is made from the merges.
+    pub fn accepts_with_symbol_map(&self, string: &[&str], map: &SymbolMap) -> bool {
+        let mut eval = self.evaluator();
+        for elem in string {
+            if eval.step_mapped(elem, map).is_none() {
+                return false;
+            }
+        }
+        eval.is_accepting()
+    }
+
     /// Optimizes this NFA by first removing all unreachable states and then removing all epsilon moves. This simply
     /// executes [Nfa::remove_unreachable_states] and then [Nfa::remove_epsilon_moves]. See documentation of those
-    /// functions for more information.
-    pub fn optimize(&mut self) {
-        self.remove_unreachable_states();
-        self.remove_epsilon_moves();
+    /// functions for more information. The returned [IndexRemap] maps the indices of the states before the call to
+    /// the indices after it.
+    pub fn optimize(&mut self) -> IndexRemap {
+        let reachable = self.remove_unreachable_states();
+        reachable.then(&self.remove_epsilon_moves())
+    }
+
+    /// Converts this NFA to a DFA, minimizes the DFA and converts it back to a NFA, which results in a NFA without
+    /// epsilon moves or unreachable states, accepting the same language as this NFA.
+    ///
+    /// Note that this is *not* a true NFA minimization. Finding a NFA with as few states as possible is PSPACE-hard,
+    /// and the result of this may have more states than needed. In practice, it often gives a smaller NFA than the
+    /// original, since redundant states are merged, but the subset construction may produce exponentially many
+    /// states (see [Nfa::to_dfa]), in which case the result will be larger than this NFA. To minimize this NFA in
+    /// place, see [Nfa::minimize_via_dfa].
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// // Accepts words ending with "a", but with redundant states
+    /// let ends_with_a = "
+    ///         ε       a    b
+    /// -> s0  {}      {s1} {s0}
+    ///    s1  {s0 s2} {}   {}
+    ///  * s2  {}      {}   {}
+    ///    s3  {}      {s2} {s3}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(ends_with_a).unwrap().try_into().unwrap();
+    /// let minimized = nfa.to_minimized_nfa();
+    /// assert!(minimized.equivalent_to(&nfa));
+    /// assert_eq!(minimized.states().len(), 2);
+    /// assert!(!minimized.has_epsilon_moves());
+    /// ```
+    pub fn to_minimized_nfa(&self) -> Nfa {
+        let mut dfa = self.to_dfa();
+        dfa.minimize();
+        dfa.to_nfa()
+    }
+
+    /// Minimizes this NFA in place by converting it to a minimized DFA and back, see [Nfa::to_minimized_nfa]. Note
+    /// that this is not a true NFA minimization, and in the worst case, the NFA gets exponentially larger.
+    pub fn minimize_via_dfa(&mut self) {
+        let mut version = self.version;
+        version.bump();
+        *self = self.to_minimized_nfa();
+        self.version = version;
     }
 
     /// Removes all epsilon moves from this NFA, and after this call returns, no state will have any epsilon moves and
@@ -699,6 +1323,17 @@ impl Nfa {
     /// that this procedure isn't a minimization of the NFA, nor does it remove unreachable states. See
     /// [Nfa::remove_unreachable_states] for removing unreachable states.
     ///
+    /// [Epsilon cycles](Nfa::epsilon_cycles) are handled like any other epsilon moves, since every state in a cycle
+    /// has the whole cycle in its epsilon closure. States in a cycle without any normal transitions are dead, even if
+    /// an accepting state is only reachable through the cycle, since the accepting state is part of the closure which
+    /// is inlined into every transition to the cycle. If the initial state is in a cycle, its epsilon closure has
+    /// more than one state, so a new initial state is added as described above, and the old initial state is only
+    /// kept if it has normal transitions (or is accepting).
+    ///
+    /// The remaining states keep their relative order, and the new initial state (if any) is placed last. The returned
+    /// [IndexRemap] maps the indices of the states before the call to the indices after it, where the new initial
+    /// state counts as the old initial state (which it takes the name of if the old initial state is removed).
+    ///
     /// ```
     /// use dandy::nfa::Nfa;
     /// use dandy::parser;
@@ -753,9 +1388,32 @@ impl Nfa {
     /// assert_eq!(nfa.states()[3].name(), "y");
     /// assert_eq!(nfa.states()[4].name(), "i"); // The new initial state is placed last
     /// ```
-    pub fn remove_epsilon_moves(&mut self) {
+    ///
+    /// The returned [IndexRemap] can be used to find where the states ended up, for example in the last example:
+    /// ```
+    /// # use dandy::nfa::Nfa;
+    /// # use dandy::parser;
+    /// # let branching_initial_state = "
+    /// #        eps        a       b       c
+    /// # -> i  {i0 i1 i2} {i0}    {i2}    {i0 i2}
+    /// #    i0 {i0}       {}      {}      {}
+    /// #    i1 {y}        {i0}    {i0}    {i}
+    /// #    i2 {}         {}      {i0}    {i0}
+    /// #    i3 {i1}       {i1}    {i1}    {i1}
+    /// #    x  {x}        {y}     {x}     {x}
+    /// #  * y  {}         {x}     {y}     {y}
+    /// # ";
+    /// let mut nfa: Nfa = parser::nfa(branching_initial_state).unwrap().try_into().unwrap();
+    /// let remap = nfa.remove_epsilon_moves();
+    /// assert_eq!(remap.old_to_new(2), Some(0)); // i1
+    /// assert_eq!(remap.old_to_new(1), None); // i0 is removed
+    /// assert_eq!(remap.old_to_new(0), Some(4)); // i is replaced by the new initial state
+    /// assert_eq!(remap.new_to_old(3), 6); // y
+    /// ```
+    pub fn remove_epsilon_moves(&mut self) -> IndexRemap {
+        self.version.bump();
         if !self.has_epsilon_moves() {
-            return;
+            return IndexRemap::identity(self.states.len());
         }
 
         // Pre-calculate all epsilon closures
@@ -769,11 +1427,11 @@ impl Nfa {
                 // On transition from a to b, transition from a to eps closure of b
                 *transition_set = transition_set
                     .iter()
-                    .fold(HashSet::new(), |mut set, transition| {
+                    .fold(BTreeSet::new(), |mut set, transition| {
                         set.extend(&closures[*transition]);
                         set
                     })
-                    .drain()
+                    .into_iter()
                     .collect();
             });
             state.epsilon_transitions.clear();
@@ -803,6 +1461,8 @@ impl Nfa {
 
         // Thirdly, figure out if we need a new initial state (which we would need if
         // our initial state has epsilon transitions to other than dead states)
+        let old_len = self.states.len();
+        let old_initial = self.initial_state;
         let init_closure = closures[self.initial_state]
             .iter()
             .copied()
@@ -813,7 +1473,6 @@ impl Nfa {
             // it as well!
 
             // Check if the old initial state is now dead, in that case we can re-use its name
-            let old_initial = self.initial_state;
             self.states[old_initial].initial = false;
             let old_state_dead = !self.states[old_initial].accepting
                 && self.states[old_initial]
@@ -852,11 +1511,11 @@ impl Nfa {
                 .map(|elem_idx| {
                     init_closure
                         .iter()
-                        .fold(HashSet::new(), |mut set, &state| {
+                        .fold(BTreeSet::new(), |mut set, &state| {
                             set.extend(self.states[state].transitions[elem_idx].iter().copied());
                             set
                         })
-                        .drain()
+                        .into_iter()
                         .filter(|i| !dead_states.contains(i))
                         .collect::<Vec<_>>()
                 })
@@ -883,25 +1542,30 @@ impl Nfa {
         });
 
         // Finally, remove all dead states
-        self.remove_states(dead_states.drain().collect());
+        let remap = self.remove_states(dead_states.drain().collect());
+        if remap.old_len() > old_len {
+            // The new initial state took over the role of the old one
+            remap.with_added(old_len, old_initial)
+        } else {
+            remap
+        }
     }
 
-    /// This function removes the states with indices in the vector from this NFA, changing the transition tables
+    /// This function removes the states with indices in the set from this NFA, changing the transition tables
     /// of the remaining states to the new state indices. There should not be any transitions to any of the states
     /// that are to be removed (except for in any of the states that are to be removed). If there is, transitions may be
     /// undefined after this call. If debug_assertions is enabled, such errors would cause a panic here, otherwise they
     /// would not immediately panic but other operations might panic at a later stage. The initial state cannot be
     /// removed and will cause a panic if attempted to.
-    fn remove_states(&mut self, mut to_remove: Vec<usize>) {
+    fn remove_states(&mut self, to_remove: BTreeSet<usize>) -> IndexRemap {
+        let old_len = self.states.len();
         let mut old_state_idx = (0..self.states.len()).collect::<Vec<_>>();
 
-        to_remove.sort();
-        if let Err(less_than) = to_remove.binary_search(&self.initial_state) {
-            // We removed "less than" states before the initial state: adjust
-            self.initial_state -= less_than;
-        } else {
+        if to_remove.contains(&self.initial_state) {
             panic!("Cannot remove initial state");
         }
+        // We removed some states before the initial state: adjust
+        self.initial_state -= to_remove.range(..self.initial_state).count();
 
         to_remove.iter().rev().for_each(|&idx| {
             self.states.remove(idx);
@@ -917,6 +1581,7 @@ impl Nfa {
             }
         };
         self.remap_transitions(map);
+        IndexRemap::from_kept(old_len, old_state_idx)
     }
 
     /// Remaps the transitions so that any transition and epsilon transition to n gets mapped to mapper(n)
@@ -935,25 +1600,42 @@ impl Nfa {
         })
     }
 
-    fn fresh_name(&mut self, wanted: &str) -> Rc<str> {
-        if self.states.iter().all(|s| s.name.as_ref() != wanted) {
+    /// Gets a name for a new state, which is `wanted` if no state has that name, and otherwise the smallest number
+    /// which isn't the name of any state
+    pub(crate) fn fresh_name(&self, wanted: &str) -> Rc<str> {
+        let names = self
+            .states
+            .iter()
+            .map(|s| s.name.as_ref())
+            .collect::<HashSet<_>>();
+        Self::fresh_name_from_set(wanted, &names)
+    }
+
+    /// Like [Nfa::fresh_name], but with the names of the states already collected, so that the states don't need to
+    /// be scanned for every number tried
+    fn fresh_name_from_set(wanted: &str, names: &HashSet<&str>) -> Rc<str> {
+        if !names.contains(wanted) {
             Rc::from(wanted)
         } else {
             (0..)
-                .map(|i| Rc::from(i.to_string().as_str()))
-                .find(|n| self.states.iter().all(|s| &s.name != n))
+                .map(|i| i.to_string())
+                .find(|n| !names.contains(n.as_str()))
+                .map(|n| Rc::from(n.as_str()))
                 .unwrap()
         }
     }
 
     /// Removes the unreachable states of this NFA, that is, all states that cannot be reached by any input to
-    /// the automata. See [Nfa::unreachable_states] to get the unreachable states
-    pub fn remove_unreachable_states(&mut self) {
-        let states = self.unreachable_state_idx().into_iter().collect();
-        self.remove_states(states);
+    /// the automata. See [Nfa::unreachable_states] to get the unreachable states. The remaining states keep their
+    /// relative order, and the returned [IndexRemap] maps the indices of the states before the call to the indices
+    /// after it.
+    pub fn remove_unreachable_states(&mut self) -> IndexRemap {
+        self.version.bump();
+        self.remove_states(self.unreachable_state_idx())
     }
 
-    /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata
+    /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, in the
+    /// order they appear in the automata
     pub fn unreachable_states(&self) -> Vec<&NfaState> {
         self.unreachable_state_idx()
             .into_iter()
@@ -962,8 +1644,8 @@ impl Nfa {
     }
 
     /// Finds the unreachable states, that is, all states that cannot be reached by any input to the automata, and
-    /// returns them as indices
-    pub fn unreachable_state_idx(&self) -> HashSet<usize> {
+    /// returns them as indices (in increasing order when iterated)
+    pub fn unreachable_state_idx(&self) -> BTreeSet<usize> {
         let reachables = self.reachable_state_idx();
         (0..self.states.len())
             .filter(|x| !reachables.contains(x))
@@ -979,7 +1661,8 @@ impl Nfa {
             .any(|idx| self.states[*idx].accepting)
     }
 
-    /// Finds the reachable states, that is, all states that can be reached by some input to the automata
+    /// Finds the reachable states, that is, all states that can be reached by some input to the automata, in the
+    /// order they appear in the automata
     pub fn reachable_states(&self) -> Vec<&NfaState> {
         self.reachable_state_idx()
             .into_iter()
@@ -988,13 +1671,13 @@ impl Nfa {
     }
 
     /// Finds the reachable states, that is, all states that can be reached by some input to the automata, and
-    /// returns them as indices
-    pub fn reachable_state_idx(&self) -> HashSet<usize> {
-        let mut reachables = HashSet::from([self.initial_state]);
-        let mut new_states = reachables.clone();
+    /// returns them as indices (in increasing order when iterated)
+    pub fn reachable_state_idx(&self) -> BTreeSet<usize> {
+        let mut reachables = BTreeSet::from([self.initial_state]);
+        let mut new_states = vec![self.initial_state];
         while !new_states.is_empty() {
             new_states = new_states
-                .drain()
+                .into_iter()
                 .flat_map(|state| {
                     // For each state, add all transitions and its epsilon closure
                     self.states[state]
@@ -1011,7 +1694,8 @@ impl Nfa {
     }
 
     /// Iterate over the words accepted by this NFA in lexicographic order (according to
-    /// the order of the alphabet). The words are represented by a `Vec` of indices of the
+    /// the order of the alphabet, which is the column order of [Nfa::to_table], see [Nfa::with_alphabet_order]
+    /// to change it). The words are represented by a `Vec` of indices of the
     /// elements, corresponding to the same element in the alphabet. For a `Vec` of `Rc<str>`s,
     /// see [Nfa::word_components], and for a `Vec` of element indices, see [Nfa::word_component_indices].
     /// Notably, this operation does not include a NFA-to-DFA conversion and doesn't suffer
@@ -1028,7 +1712,8 @@ impl Nfa {
     /// elements, corresponding to the same element in the alphabet. For a String
     /// representation, see [Nfa::words], and for a `Vec` of element indices, see [Nfa::word_component_indices].
     /// Notably, this operation does not include a NFA-to-DFA conversion and doesn't suffer
-    /// from exponential blowups.
+    /// from exponential blowups. To get the components as some other type than `Rc<str>` (such as
+    /// borrowed `&str`s), see [WordComponents::with_components].
     ///
     /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
     /// See [Nfa::remove_epsilon_moves]
@@ -1049,29 +1734,143 @@ impl Nfa {
         WordComponentIndices::new(self)
     }
 
-    /// Converts this NFA to a DFA using the subset construction.
-    /// Note that this is a somewhat expensive operation. The names of
-    /// the states in the resulting DFA are non-deterministic, named
-    /// sequentially from 0. The state named 0 is guaranteed to be the
-    /// initial state
-    pub fn to_dfa(&self) -> Dfa {
-        // Generator to generate sequential numbers to new states
-        let mut gen = 0usize..;
-        // Mapping set of old states to new sequential number
-        let mut map = HashMap::new();
-        // Set of sequential numbers which are accepting states
-        let mut accepting = HashSet::new();
-        // Evaluators to explore
-        let mut to_explore = vec![self.evaluator()];
-        // Transition tables for new states, indexed by sets
-        let mut transitions = HashMap::new();
-
-        {
-            // Pre-work, add init to tables
-            let key = Self::set_to_vec(to_explore[0].current_states_idx());
-            let n = gen.next().unwrap(); // 0
-            map.insert(key, n);
-            if to_explore[0].is_accepting() {
+    /// Iterate over the words of length exactly `k` accepted by this NFA in lexicographic order (according to
+    /// the order of the alphabet). Unlike filtering the words from [Nfa::words], this uses the adjacency matrices
+    /// of the NFA to go directly to the first word of length `k`, without visiting any shorter words first.
+    ///
+    /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
+    /// See [Nfa::remove_epsilon_moves]
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let ends_with_b = "
+    ///         a    b
+    /// -> s0 {s0} {s0 s1}
+    ///  * s1 {}   {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(ends_with_b).unwrap().try_into().unwrap();
+    /// let words = nfa.words_of_length(2).collect::<Vec<_>>();
+    /// assert_eq!(words, vec!["ab", "bb"]);
+    /// ```
+    pub fn words_of_length(&self, k: usize) -> impl Iterator<Item = String> + '_ {
+        WordComponentIndices::new(self)
+            .of_length(k)
+            .map(|components| {
+                components
+                    .iter()
+                    .map(|c| self.alphabet[*c].as_ref())
+                    .collect()
+            })
+    }
+
+    /// Iterate over the words of length at most `max_len` (in elements of the alphabet) accepted by this NFA, in the
+    /// same order as [Nfa::words]. Since the words are visited from the shortest ones, the iterator ends as soon as a
+    /// longer word is found, so this is finite even if the language isn't.
+    ///
+    /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
+    /// See [Nfa::remove_epsilon_moves]
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let mut nfa: Nfa = parser::regex("a*b").unwrap().to_nfa();
+    /// nfa.remove_epsilon_moves();
+    /// let words = nfa.words_up_to_length(3).collect::<Vec<_>>();
+    /// assert_eq!(words, vec!["b", "ab", "aab"]);
+    /// ```
+    pub fn words_up_to_length(&self, max_len: usize) -> impl Iterator<Item = String> + '_ {
+        WordComponentIndices::new(self)
+            .take_while(move |components| components.len() <= max_len)
+            .map(|components| {
+                components
+                    .iter()
+                    .map(|c| self.alphabet[*c].as_ref())
+                    .collect()
+            })
+    }
+
+    /// Iterate over the words accepted by this NFA in lexicographic order (according to the order of the alphabet),
+    /// like [Nfa::words], together with the names of the accepting states the NFA ends up in on each word. The states
+    /// are given in the order they have in this NFA, and there is always at least one of them.
+    ///
+    /// *NOTE:* Current implementation only works for NFAs without epsilon moves.
+    /// See [Nfa::remove_epsilon_moves]
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa = "
+    ///          a       b
+    /// -> s0 {s0 s1} {s0 s2}
+    ///  * s1 {}      {}
+    ///  * s2 {}      {s2}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// let words = nfa.words_with_states().take(3).collect::<Vec<_>>();
+    /// assert_eq!(words[0], ("a".to_string(), vec!["s1".into()]));
+    /// assert_eq!(words[1], ("b".to_string(), vec!["s2".into()]));
+    /// assert_eq!(words[2], ("aa".to_string(), vec!["s1".into()]));
+    /// let (word, states) = nfa.words_with_states().find(|(word, _)| word == "bb").unwrap();
+    /// assert_eq!(states, vec!["s2".into()]);
+    /// ```
+    pub fn words_with_states(&self) -> WordsWithStates<'_> {
+        WordsWithStates::new(self)
+    }
+
+    /// Converts this NFA to a DFA using the subset construction.
+    /// Note that this is a somewhat expensive operation. The states in
+    /// the resulting DFA are named sequentially from 0, in the order
+    /// they are found, so the same NFA always gives the same DFA. The
+    /// state named 0 is guaranteed to be the initial state
+    pub fn to_dfa(&self) -> Dfa {
+        self.to_dfa_bounded(usize::MAX)
+            .expect("An unbounded subset construction should always succeed")
+    }
+
+    /// Converts this NFA to a DFA using the subset construction, just like [Nfa::to_dfa], but gives up and
+    /// returns `None` if the resulting DFA would get more than `max_states` states. Since the subset construction
+    /// may produce exponentially many states, this can be used to fail early on inputs that would explode.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let third_last_is_a = "
+    ///         a        b
+    /// -> s0 {s0 s1} {s0}
+    ///    s1 {s2}    {s2}
+    ///    s2 {s3}    {s3}
+    ///  * s3 {}      {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(third_last_is_a).unwrap().try_into().unwrap();
+    /// assert!(nfa.to_dfa_bounded(4).is_none());
+    /// assert_eq!(nfa.to_dfa_bounded(8).unwrap().states().len(), 8);
+    /// ```
+    pub fn to_dfa_bounded(&self, max_states: usize) -> Option<Dfa> {
+        if max_states == 0 {
+            return None;
+        }
+        // Generator to generate sequential numbers to new states
+        let mut gen = 0usize..;
+        // Mapping set of old states to new sequential number
+        let mut map = HashMap::new();
+        // Set of sequential numbers which are accepting states
+        let mut accepting = HashSet::new();
+        // Evaluators to explore
+        let mut to_explore = vec![self.evaluator()];
+        // Transition tables for new states, indexed by sets
+        let mut transitions = HashMap::new();
+
+        {
+            // Pre-work, add init to tables
+            let key = to_explore[0].state_key();
+            let n = gen.next().unwrap(); // 0
+            map.insert(key, n);
+            if to_explore[0].is_accepting() {
                 accepting.insert(n);
             }
         }
@@ -1082,8 +1881,11 @@ impl Nfa {
             let mut tr = Vec::with_capacity(self.alphabet.len());
             for new_evaluator in eval.step_all() {
                 let is_accepting = new_evaluator.is_accepting();
-                let key = Self::set_to_vec(new_evaluator.current_states_idx());
+                let key = new_evaluator.state_key();
                 if !map.contains_key(&key) {
+                    if map.len() >= max_states {
+                        return None;
+                    }
                     to_explore.push(new_evaluator);
                 }
                 let x = map.entry(key).or_insert_with(|| gen.next().unwrap());
@@ -1093,7 +1895,7 @@ impl Nfa {
                 }
             }
 
-            transitions.insert(Self::set_to_vec(eval.current_states_idx()), tr);
+            transitions.insert(eval.state_key(), tr);
         }
 
         let sorted_keys = {
@@ -1103,24 +1905,123 @@ impl Nfa {
         };
 
         // We sort the keys to have a nice table later on. This may be wasteful but
-        // Self::set_to_vec sorts and converts sets to vecs anyways so nevermind
+        // NfaEvaluator::state_key sorts and converts sets to vecs anyways so nevermind
         let states = sorted_keys
             .into_iter()
             .map(|(key, &n)| DfaState {
                 name: Rc::from(n.to_string()),
                 initial: n == 0,
                 accepting: accepting.contains(&n),
+                priority: None,
                 transitions: transitions.remove(key).unwrap(),
             })
             .collect();
 
-        Dfa {
+        Some(Dfa {
             alphabet: self.alphabet.clone(), // Clone is cheap: alphabet is Rc<_>
             states,
             initial_state: 0, // We start at initial state and assign 0 from gen, so initial is 0
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+            provenance: Provenance::new(|| {
+                format!(
+                    "subset construction from {}",
+                    self.provenance.describe("NFA")
+                )
+            }),
+        })
+    }
+
+    /// Walks the sets of states of the subset construction of this NFA breadth-first, like [Dfa::bfs] walks the
+    /// states of a DFA, without constructing the DFA. `visit` is called with each set of states (as sorted indices,
+    /// after following ε-moves) when it is first found, until it returns [ControlFlow::Break] or all reachable sets
+    /// are found. The sets are numbered in the order they are found, which is the `state` (and `parent`) of the
+    /// [BfsEvent]. The empty set is visited as well, if some word leads to no state at all.
+    ///
+    /// ```
+    /// use dandy::nfa::{BfsEvent, Nfa};
+    /// use dandy::parser;
+    /// use std::ops::ControlFlow;
+    ///
+    /// let nfa = "
+    ///        ε    a    b
+    /// -> s0 {s1} {s0} {}
+    ///  * s1 {}   {}   {s1}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// let mut subsets = vec![];
+    /// nfa.bfs_subsets(|event, subset| {
+    ///     subsets.push((event, subset.to_vec()));
+    ///     ControlFlow::Continue(())
+    /// });
+    /// assert_eq!(subsets.len(), 3);
+    /// assert_eq!(subsets[0].1, vec![0, 1]);
+    /// assert_eq!(subsets[1], (BfsEvent { state: 1, parent: Some(0), symbol: Some(1), depth: 1 }, vec![1]));
+    /// assert_eq!(subsets[2], (BfsEvent { state: 2, parent: Some(1), symbol: Some(0), depth: 2 }, vec![]));
+    /// ```
+    pub fn bfs_subsets(&self, mut visit: impl FnMut(BfsEvent, &[usize]) -> ControlFlow<()>) {
+        let initial = self.evaluator();
+        let initial_event = BfsEvent {
+            state: 0,
+            parent: None,
+            symbol: None,
+            depth: 0,
+        };
+        let key = initial.state_key();
+        if visit(initial_event, &key).is_break() {
+            return;
+        }
+        let mut found = HashSet::from([key]);
+        let mut queue = VecDeque::from([(initial, 0, 0)]);
+        while let Some((eval, parent, depth)) = queue.pop_front() {
+            for (symbol, next) in eval.step_all().into_iter().enumerate() {
+                let key = next.state_key();
+                if found.contains(&key) {
+                    continue;
+                }
+                let state = found.len();
+                let event = BfsEvent {
+                    state,
+                    parent: Some(parent),
+                    symbol: Some(symbol),
+                    depth: depth + 1,
+                };
+                if visit(event, &key).is_break() {
+                    return;
+                }
+                found.insert(key);
+                queue.push_back((next, state, depth + 1));
+            }
         }
     }
 
+    /// Computes the number of states of the minimal DFA accepting the same language as this NFA. This runs the
+    /// subset construction [with a bound](Nfa::to_dfa_bounded) of `max` states, and gives an error as soon as the
+    /// bound is exceeded, so an NFA whose subset construction explodes doesn't exhaust the memory. Note that the
+    /// bound is on the DFA before minimization, which may be larger than the minimal DFA.
+    ///
+    /// ```
+    /// use dandy::nfa::{ExceededBound, Nfa};
+    /// use dandy::parser;
+    ///
+    /// let third_last_is_a = "
+    ///         a        b
+    /// -> s0 {s0 s1} {s0}
+    ///    s1 {s2}    {s2}
+    ///    s2 {s3}    {s3}
+    ///  * s3 {}      {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(third_last_is_a).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.minimal_dfa_size_bounded(8), Ok(8));
+    /// assert_eq!(nfa.minimal_dfa_size_bounded(4), Err(ExceededBound { max: 4 }));
+    /// ```
+    pub fn minimal_dfa_size_bounded(&self, max: usize) -> Result<usize, ExceededBound> {
+        self.to_dfa_bounded(max)
+            .map(|dfa| dfa.minimal_size())
+            .ok_or(ExceededBound { max })
+    }
+
     /// Checks if this automaton accepts the given string. This is equivalent to getting the
     /// evaluator, stepping it multiple times and checking if it is accepting
     pub fn accepts(&self, string: &[&str]) -> bool {
@@ -1135,9 +2036,8 @@ impl Nfa {
     /// elements with multiple graphemes, see [Nfa::graphemes_only]. A grapheme is defined to be
     /// one extended unicode grapheme cluster (which may consist of one or many code points).
     pub fn accepts_graphemes(&self, string: &str) -> bool {
-        let graphemes = string.graphemes(true).collect::<Vec<_>>();
         let mut eval = self.evaluator();
-        eval.step_multiple(&graphemes);
+        eval.step_str(string);
         eval.is_accepting()
     }
 
@@ -1157,11 +2057,177 @@ impl Nfa {
             .any(|state| !state.epsilon_transitions.is_empty())
     }
 
+    /// Finds the epsilon cycles of this NFA, that is, the sets of states which can all reach each other using epsilon
+    /// moves only (the non-trivial strongly connected components of the graph of epsilon moves). A single state is
+    /// only part of a cycle if it has an epsilon move to itself. Epsilon cycles are allowed, but they often indicate
+    /// a mistake, since all states in a cycle are equivalent. Each cycle is given as the indices of its states in
+    /// ascending order, and the cycles are ordered by their first state. See [Nfa::epsilon_cycle_names] to get the
+    /// names of the states instead.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let cycles = "
+    ///          ε       a
+    /// -> s0   {s1}    {s0}
+    ///    s1   {s2}    {}
+    ///  * s2   {s0 s3} {}
+    ///    s3   {s3}    {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(cycles).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.epsilon_cycles(), vec![vec![0, 1, 2], vec![3]]);
+    /// assert_eq!(nfa.epsilon_cycle_names(), vec![vec!["s0", "s1", "s2"], vec!["s3"]]);
+    /// assert!(nfa.has_epsilon_cycles());
+    /// ```
+    pub fn epsilon_cycles(&self) -> Vec<Vec<usize>> {
+        let closures = (0..self.states.len())
+            .filter_map(|idx| self.closure(idx))
+            .collect::<Vec<_>>();
+        let mut seen = vec![false; self.states.len()];
+        let mut cycles = vec![];
+        for idx in 0..self.states.len() {
+            if seen[idx] {
+                continue;
+            }
+            // The states in the same cycle are the ones reachable from this state which can also reach this state
+            let mut cycle = closures[idx]
+                .iter()
+                .copied()
+                .filter(|&other| closures[other].contains(&idx))
+                .collect::<Vec<_>>();
+            cycle.sort();
+            cycle.iter().for_each(|&state| seen[state] = true);
+            if cycle.len() > 1 || self.states[idx].epsilon_transitions.contains(&idx) {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    /// Finds the epsilon cycles of this NFA, just like [Nfa::epsilon_cycles], but gives the names of the states
+    pub fn epsilon_cycle_names(&self) -> Vec<Vec<&str>> {
+        self.epsilon_cycles()
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .map(|idx| self.states[idx].name())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Checks if this NFA has any epsilon cycles, see [Nfa::epsilon_cycles]
+    pub fn has_epsilon_cycles(&self) -> bool {
+        !self.epsilon_cycles().is_empty()
+    }
+
     /// Gets an evaluator, which is a struct that is used to evaluate strings with the automaton
     pub fn evaluator(&self) -> NfaEvaluator<'_> {
         self.into()
     }
 
+    /// Runs the evaluator on every input (where every grapheme is an element of the alphabet) and aggregates how
+    /// many states are active after each step, see [ActiveProfile]. The inputs are consumed one at a time and not
+    /// kept.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///          a     b
+    ///   → s  {s t}  {}
+    ///   * t  {}     {t}
+    /// ").unwrap().try_into().unwrap();
+    /// let profile = nfa.active_state_profile(["ab", "b"].into_iter().map(String::from));
+    /// assert_eq!(profile.steps[1].max, 2);
+    /// assert_eq!(profile.died, 1);
+    /// ```
+    pub fn active_state_profile(&self, inputs: impl Iterator<Item = String>) -> ActiveProfile {
+        let mut profile = ActiveProfile::default();
+        inputs.for_each(|input| profile.record(self, &input));
+        profile
+    }
+
+    /// Creates an evaluator which determinizes this NFA lazily, computing each set of states (and each transition
+    /// between them) the first time it is needed and caching it, see [LazyDfaEvaluator]. This is faster than
+    /// [Nfa::evaluator] when evaluating many words, without paying for the whole subset construction of
+    /// [Nfa::to_dfa] up front.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///          a      b
+    ///   → s0 {s0 s1} {s0}
+    ///     s1 {s2}    {s2}
+    ///   * s2 {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let mut eval = nfa.lazy_dfa_evaluator();
+    /// assert!(eval.accepts_graphemes("bab"));
+    /// assert!(!eval.accepts_graphemes("abb"));
+    /// assert!(eval.accepts_graphemes("aab"));
+    /// // {s0}, {s0 s1}, {s0 s2} and {s0 s1 s2}
+    /// assert_eq!(eval.discovered_states(), 4);
+    /// ```
+    pub fn lazy_dfa_evaluator(&self) -> LazyDfaEvaluator<'_> {
+        LazyDfaEvaluator::new(self)
+    }
+
+    /// Resumes an evaluator from a checkpoint created by [NfaEvaluator::checkpoint]. This fails if the checkpoint
+    /// was created by an evaluator of another NFA, which is detected by comparing [fingerprints](Nfa::fingerprint).
+    pub fn resume(
+        &self,
+        checkpoint: &NfaEvaluatorCheckpoint,
+    ) -> Result<NfaEvaluator<'_>, CheckpointMismatch> {
+        NfaEvaluator::from_checkpoint(self, checkpoint)
+    }
+
+    /// Gets the version of this NFA, which is 0 for a newly created NFA and is increased by every method modifying it
+    /// (such as [Nfa::remove_epsilon_moves] or [Nfa::minimize_via_dfa]), whether or not anything changed. Clones keep
+    /// the version, and the version doesn't affect equality. This is useful to detect that a NFA has been modified
+    /// since some data about it was computed.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let mut nfa: Nfa = parser::nfa("  a\n-> s {s}\n * t {t}").unwrap().try_into().unwrap();
+    /// let before = nfa.version();
+    /// nfa.remove_unreachable_states();
+    /// assert!(nfa.version() > before);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.version.get()
+    }
+
+    /// Computes a fingerprint of this NFA, which is a hash of its alphabet, its states (but not their names) and its
+    /// transition table. The fingerprint is stable across platforms, so it may be stored or sent to another machine.
+    /// Every transition is hashed as a set of targets, so the order in which the targets are listed doesn't matter, and
+    /// targets listed more than once are counted once.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv64::new();
+        let write_set = |hasher: &mut Fnv64, set: &[usize]| {
+            let set = set.iter().collect::<BTreeSet<_>>();
+            hasher.write_usize(set.len());
+            set.into_iter().for_each(|&to| hasher.write_usize(to));
+        };
+        hasher.write_usize(self.alphabet.len());
+        self.alphabet.iter().for_each(|elem| hasher.write_str(elem));
+        hasher.write_usize(self.states.len());
+        hasher.write_usize(self.initial_state);
+        self.states.iter().for_each(|state| {
+            hasher.write_bool(state.accepting);
+            write_set(&mut hasher, &state.epsilon_transitions);
+            state
+                .transitions
+                .iter()
+                .for_each(|to| write_set(&mut hasher, to));
+        });
+        hasher.finish()
+    }
+
     /// Gives the epsilon closure of a state, given the state index
     pub fn closure(&self, start: usize) -> Option<HashSet<usize>> {
         if start >= self.states.len() {
@@ -1183,52 +2249,316 @@ impl Nfa {
         Some(all)
     }
 
+    /// Gives the epsilon closures of all states, where the closure of the state with index `i` is at index `i`. Each
+    /// closure contains the indices of its states in ascending order, including the state itself.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    /// use dandy::parser;
+    ///
+    /// let nfa = "
+    ///          ε    a
+    /// -> s0   {s1} {s0}
+    ///    s1   {s2} {}
+    ///  * s2   {s1} {}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.epsilon_closure_table(), vec![vec![0, 1, 2], vec![1, 2], vec![1, 2]]);
+    /// ```
+    pub fn epsilon_closure_table(&self) -> Vec<Vec<usize>> {
+        (0..self.states.len())
+            .map(|idx| {
+                let mut closure = self
+                    .closure(idx)
+                    .expect("State should exist")
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                closure.sort_unstable();
+                closure
+            })
+            .collect()
+    }
+
     /// Generates a table of this NFA suitable for printing, which may be parsed again to this automaton
     pub fn to_table(&self) -> String {
-        self.gen_table("ε", "→")
+        self.gen_table(&TableStyle::default(), " ")
+    }
+
+    /// Generates a table of this NFA like [Nfa::to_table], but with the markers of the initial and accepting states and
+    /// the header of the epsilon column given by the [TableStyle]. The table may be parsed again by
+    /// [parser::nfa_with_config](crate::parser::nfa_with_config) with a [ParserConfig](crate::parser::ParserConfig)
+    /// containing those tokens.
+    pub fn to_table_with_style(&self, style: &TableStyle) -> String {
+        self.gen_table(style, " ")
     }
 
     /// Generates a table of this NFA suitable for printing, which may be parsed again to this automaton. The epsilon
     /// character is represented "eps" and the arrow for the initial state is "->"
     pub fn ascii_table(&self) -> String {
-        self.gen_table("eps", "->")
+        self.gen_table(&TableStyle::ascii(), " ")
     }
 
-    fn gen_table(&self, eps: &str, arrow: &str) -> String {
-        let mut table = Table::default();
+    /// Generates a table of this NFA suitable for printing, like [Nfa::to_table], but with `sep` between each column
+    /// instead of a single space. Note that the parser splits columns on whitespace, so a table generated with a
+    /// separator containing anything other than whitespace may not be parsed again.
+    pub fn to_table_with_separator(&self, sep: &str) -> String {
+        self.gen_table(&TableStyle::default(), sep)
+    }
+
+    /// Generates a table of this NFA where the columns are separated by `" | "`, which is easier to read for wide
+    /// tables. This is a convenience function for [Nfa::to_table_with_separator], and the table may not be parsed
+    /// again.
+    pub fn to_table_pipe_separated(&self) -> String {
+        self.to_table_with_separator(" | ")
+    }
+
+    /// Generates the cells of the table given by [Nfa::to_table], row by row, without any padding. The first row is
+    /// ε followed by the alphabet (after three empty cells), and each other row is a state, with the cells for the
+    /// initial state arrow and the accepting star (which are empty if the state isn't initial or accepting), the name
+    /// and the transition sets. This is useful for rendering the table in some other way, such as in HTML.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///         a       b
+    ///   -> s0 {s0 s1} {s0}
+    ///    * s1 {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let rows = nfa.to_table_rows();
+    /// assert_eq!(rows[0], ["", "", "", "ε", "a", "b"]);
+    /// assert_eq!(rows[1], ["→", "", "s0", "{}", "{s0 s1}", "{s0}"]);
+    /// assert_eq!(rows[2], ["", "*", "s1", "{}", "{}", "{}"]);
+    /// ```
+    pub fn to_table_rows(&self) -> Vec<Vec<String>> {
+        self.table_cells(&TableStyle::default())
+            .into_iter()
+            .map(|row| row.into_iter().map(Cow::into_owned).collect())
+            .collect()
+    }
+
+    /// Generates an edge list of this NFA, which is a line declaring the alphabet, a line for every state and a line
+    /// for every transition (see [parser::nfa_edges](crate::parser::nfa_edges) for the format). This is more compact
+    /// than [Nfa::to_table] for sparse NFAs, and may be parsed again to this automaton by
+    /// [parser::nfa_edges](crate::parser::nfa_edges).
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///         ε    a       b
+    ///   -> s0 {}   {s0 s1} {}
+    ///    * s1 {s0} {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let edges = nfa.to_edge_list();
+    /// assert_eq!(edges, "alphabet: a b\nstate s0 initial\nstate s1 accepting\ns0 -a-> s0\ns0 -a-> s1\ns1 -ε-> s0");
+    /// let parsed: Nfa = dandy::parser::nfa_edges(&edges).unwrap().try_into().unwrap();
+    /// assert_eq!(parsed.to_table(), nfa.to_table());
+    /// ```
+    pub fn to_edge_list(&self) -> String {
+        table::edge_list(
+            &self.alphabet,
+            self.states
+                .iter()
+                .map(|state| (&*state.name, state.initial, state.accepting)),
+            self.states.iter().enumerate().flat_map(|(from, state)| {
+                let eps = state
+                    .epsilon_transitions
+                    .iter()
+                    .map(move |&to| (from, None, to));
+                let elems = self.alphabet.iter().zip(&state.transitions).flat_map(
+                    move |(elem, targets)| targets.iter().map(move |&to| (from, Some(&**elem), to)),
+                );
+                eps.chain(elems)
+            }),
+        )
+    }
+
+    /// Converts this NFA to a [ParsedNfaOwned] with the same table as [Nfa::to_table] (including the ε column), which
+    /// may be edited and validated again by [TryInto::try_into] without writing and parsing the table
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///         a       b
+    ///   -> s0 {s0 s1} {s0}
+    ///    * s1 {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let mut parsed = nfa.to_parsed();
+    /// parsed.set_transition("s0", None, &["s1"]).unwrap();
+    /// let edited: Nfa = parsed.try_into().unwrap();
+    /// assert!(edited.accepts(&[]));
+    /// assert!(!nfa.accepts(&[]));
+    /// ```
+    pub fn to_parsed(&self) -> ParsedNfaOwned {
+        let names = |targets: &[usize]| {
+            targets
+                .iter()
+                .map(|&to| self.states[to].name.to_string())
+                .collect()
+        };
+        ParsedNfaOwned {
+            head: iter::once(None)
+                .chain(self.alphabet.iter().map(|elem| Some(elem.to_string())))
+                .collect(),
+            states: self
+                .states
+                .iter()
+                .map(|state| ParsedNfaStateOwned {
+                    name: state.name.to_string(),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: iter::once(names(&state.epsilon_transitions))
+                        .chain(state.transitions.iter().map(|targets| names(targets)))
+                        .collect(),
+                })
+                .collect(),
+            declared_alphabet: None,
+        }
+    }
 
-        let mut alph = vec!["", "", "", eps];
-        alph.extend(self.alphabet.iter().map(|s| s as &str));
-        table.push_row(alph);
+    /// Converts this NFA to a right-linear grammar generating its language, where each state is a nonterminal named
+    /// after it and the initial state is the start symbol. Each transition `A -a-> B` gives a production `A -> a B`,
+    /// and also `A -> a` if `B` is accepting, each accepting state `A` gives `A -> ε`, and each ε-move `A -ε-> B`
+    /// gives the unit production `A -> B`. To get a grammar without unit productions, use
+    /// [Nfa::remove_epsilon_moves] first. The productions are ordered by state, and then by element of the alphabet.
+    /// See the [grammar module](crate::grammar) for an example.
+    ///
+    /// ```
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = dandy::parser::nfa("
+    ///        ε    a    b
+    /// -> s0  {s1} {s0} {}
+    ///  * s1  {}   {}   {s1}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.to_right_linear_grammar().to_string(), "s0 -> s1 | a s0\ns1 -> b s1 | b | ε");
+    /// ```
+    pub fn to_right_linear_grammar(&self) -> Grammar {
+        let nonterminal = |idx: usize| self.states[idx].name.to_string();
+        let productions = self
+            .states
+            .iter()
+            .flat_map(|state| {
+                let lhs = state.name.to_string();
+                let units = state
+                    .epsilon_transitions
+                    .iter()
+                    .map(|&to| vec![GrammarSymbol::NonTerminal(nonterminal(to))]);
+                let moves =
+                    self.alphabet
+                        .iter()
+                        .zip(&state.transitions)
+                        .flat_map(|(elem, targets)| {
+                            let terminal = GrammarSymbol::Terminal(elem.clone());
+                            let ends = targets
+                                .iter()
+                                .any(|&to| self.states[to].accepting)
+                                .then(|| vec![terminal.clone()]);
+                            let steps = targets.iter().map(move |&to| {
+                                vec![
+                                    terminal.clone(),
+                                    GrammarSymbol::NonTerminal(nonterminal(to)),
+                                ]
+                            });
+                            steps.chain(ends)
+                        });
+                let empty = state.accepting.then(Vec::new);
+                units
+                    .chain(moves)
+                    .chain(empty)
+                    .map(move |rhs| (lhs.clone(), rhs))
+            })
+            .collect();
+        Grammar {
+            start: nonterminal(self.initial_state),
+            productions,
+        }
+    }
+
+    /// Serializes this NFA to JSON, in the format described in the [module-level documentation](crate::nfa)
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        ε    a       b
+    /// -> s0  {}   {s0 s1} {s0}
+    ///  * s1  {s0} {}      {}").unwrap().try_into().unwrap();
+    /// let json = nfa.to_json_string().unwrap();
+    /// assert_eq!(Nfa::from_json_str(&json).unwrap().to_table(), nfa.to_table());
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a NFA from JSON, in the format described in the [module-level documentation](crate::nfa). Fails
+    /// if the JSON is malformed, or if the NFA is invalid for any reason a [NfaParseError](parse::NfaParseError) would
+    /// be given when parsing a table.
+    #[cfg(feature = "serde")]
+    pub fn from_json_str(s: &str) -> Result<Nfa, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    fn gen_table(&self, style: &TableStyle, sep: &str) -> String {
+        let cells = self.table_cells(style);
+        let mut table = Table::default();
+        for row in &cells {
+            table.push_row(row.iter().map(|cell| cell as &str).collect());
+        }
+        table.to_string(sep)
+    }
 
-        let trans_strings = &self
+    /// Generates the cells of the table of this NFA, row by row, with the markers and epsilon header of the given
+    /// style
+    fn table_cells<'a>(&'a self, style: &'a TableStyle) -> Vec<Vec<Cow<'a, str>>> {
+        let names = self
             .states
             .iter()
-            .map(|state| {
+            .map(|s| quote_cell(&s.name))
+            .collect::<Vec<_>>();
+
+        let mut alph = vec![
+            Cow::Borrowed(""),
+            Cow::Borrowed(""),
+            Cow::Borrowed(""),
+            Cow::Borrowed(style.epsilon_token.as_str()),
+        ];
+        alph.extend(self.alphabet.iter().map(|s| quote_cell(s)));
+        let mut rows = vec![alph];
+
+        for (state, name) in self.states.iter().zip(&names) {
+            let mut row = vec![
+                Cow::Borrowed(if state.initial {
+                    style.initial_marker.as_str()
+                } else {
+                    ""
+                }),
+                Cow::Borrowed(if state.accepting {
+                    style.accepting_marker.as_str()
+                } else {
+                    ""
+                }),
+                name.clone(),
+            ];
+            row.extend(
                 iter::once(&state.epsilon_transitions)
                     .chain(&state.transitions)
                     .map(|trans| {
                         let s = trans
                             .iter()
-                            .map(|c| self.states[*c].name.clone())
+                            .map(|c| &names[*c] as &str)
                             .collect::<Vec<_>>()
                             .join(" ");
-                        format!("{{{s}}}")
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
-
-        for (idx, state) in self.states.iter().enumerate() {
-            let mut state = vec![
-                if state.initial { arrow } else { "" },
-                if state.accepting { "*" } else { "" },
-                &state.name,
-            ];
-            state.extend(trans_strings[idx].iter().map(|s| s as &str));
-            table.push_row(state);
+                        Cow::Owned(format!("{{{s}}}"))
+                    }),
+            );
+            rows.push(row);
         }
-        table.to_string(" ")
+        rows
     }
 
     /// Checks if this NFA is equivalent to another NFA, that is, if they accept the same language.
@@ -1239,13 +2569,36 @@ impl Nfa {
         if !alphabet_equal(&self.alphabet, &other.alphabet) {
             return false;
         }
+        let translation = self
+            .alphabet
+            .iter()
+            .map(|elem| other.alphabet_index(elem).expect("the alphabets are equal"))
+            .collect::<Vec<_>>();
+        self.equivalent_translated(other, &translation)
+    }
+
+    /// Checks if this NFA is equivalent to another NFA after renaming the symbols of the other NFA, like
+    /// [Dfa::equivalent_to_under]. The mapping consists of pairs of a symbol of the other NFA and the symbol of this
+    /// NFA it is renamed to, and needs to be a bijection between the alphabets, or a [MappingError] describing the
+    /// problem is returned.
+    pub fn equivalent_to_under(
+        &self,
+        other: &Nfa,
+        mapping: &[(&str, &str)],
+    ) -> Result<bool, MappingError> {
+        let translation = symbols::translation(&other.alphabet, &self.alphabet, mapping)?;
+        Ok(self.equivalent_translated(other, &translation))
+    }
 
+    /// Checks if this NFA is equivalent to another NFA, where the element with index `i` in the alphabet of this NFA
+    /// corresponds to the element with index `translation[i]` in the alphabet of the other NFA
+    fn equivalent_translated(&self, other: &Nfa, translation: &[usize]) -> bool {
         // initially, we explore the (pair of) initial states
         let mut evaluators_to_explore = vec![(self.evaluator(), other.evaluator())];
         let mut explored_states = HashSet::new();
         explored_states.insert((
-            Self::set_to_vec(evaluators_to_explore[0].0.current_states_idx()),
-            Self::set_to_vec(evaluators_to_explore[0].1.current_states_idx()),
+            evaluators_to_explore[0].0.state_key(),
+            evaluators_to_explore[0].1.state_key(),
         ));
 
         while let Some((s1, s2)) = evaluators_to_explore.pop() {
@@ -1255,15 +2608,12 @@ impl Nfa {
                 return false;
             }
             // for each char in alphabet, we step the evaluator. If we get new states, explore them!
-            for elem in self.alphabet.iter() {
+            for (elem, &other_elem) in self.alphabet.iter().zip(translation) {
                 let mut d1 = s1.clone();
                 d1.step(elem);
                 let mut d2 = s2.clone();
-                d2.step(elem);
-                if explored_states.insert((
-                    Self::set_to_vec(d1.current_states_idx()),
-                    Self::set_to_vec(d2.current_states_idx()),
-                )) {
+                d2.step(&other.alphabet[other_elem]);
+                if explored_states.insert((d1.state_key(), d2.state_key())) {
                     evaluators_to_explore.push((d1, d2));
                 }
             }
@@ -1271,11 +2621,257 @@ impl Nfa {
         true
     }
 
-    /// Converts a HashSet (which is not hashable) to a Vec (which is hashable) in a determenistic way
-    fn set_to_vec<T: Clone + Ord>(set: &HashSet<T>) -> Vec<T> {
-        let mut vec = set.iter().cloned().collect::<Vec<_>>();
-        vec.sort();
-        vec
+    /// Checks if the language of this NFA is a subset of the language of another NFA, that is, if every word accepted
+    /// by this NFA is accepted by the other NFA as well. This returns `None` if and only if the alphabets of the two
+    /// NFAs are unequal (not considering ordering).
+    ///
+    /// This uses the antichain algorithm, which avoids determinizing the other NFA in most cases, by only exploring
+    /// the smallest sets of states of the other NFA it can be in together with each state of this NFA. This is often
+    /// much faster than [Nfa::equivalent_to] on NFAs whose DFAs would be much larger.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let ends_with_ab = parser::regex("(a|b)*ab").unwrap().to_nfa();
+    /// let ends_with_b = parser::regex("(a|b)*b").unwrap().to_nfa();
+    /// assert_eq!(ends_with_ab.subset_of_antichain(&ends_with_b), Some(true));
+    /// assert_eq!(ends_with_b.subset_of_antichain(&ends_with_ab), Some(false));
+    /// ```
+    pub fn subset_of_antichain(&self, other: &Nfa) -> Option<bool> {
+        alphabet_equal(&self.alphabet, &other.alphabet).then(|| antichain::included_in(self, other))
+    }
+
+    /// Checks if this NFA is equivalent to another NFA, that is, if they accept the same language, by checking that
+    /// each language is a [subset](Nfa::subset_of_antichain) of the other. This returns `None` if and only if the
+    /// alphabets of the two NFAs are unequal (not considering ordering). This gives the same result as
+    /// [Nfa::equivalent_to], but is often much faster on NFAs whose DFAs would be much larger.
+    pub fn equivalent_to_antichain(&self, other: &Nfa) -> Option<bool> {
+        alphabet_equal(&self.alphabet, &other.alphabet)
+            .then(|| antichain::included_in(self, other) && antichain::included_in(other, self))
+    }
+
+    /// Checks if this NFA is the same machine as another NFA up to renaming (and reordering) states, that is, if
+    /// there is a one-to-one mapping between their states preserving the initial state, the accepting states and
+    /// all transitions. The order of the alphabets doesn't matter, but the alphabets must be equal. Isomorphic NFAs
+    /// are always equivalent, but equivalent NFAs are often not isomorphic.
+    ///
+    /// This is a best-effort check: the states are first partitioned by their transitions to each other, and then a
+    /// mapping is searched for by backtracking. This is fast for most NFAs, but may take exponential time for very
+    /// symmetric NFAs, so the search gives up and returns `None` after trying a million pairs of states.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        a       b
+    ///   -> s {s t}   {}
+    ///    * t {}      {s}
+    /// ").unwrap().try_into().unwrap();
+    /// let renamed: Nfa = parser::nfa("
+    ///        b   a
+    ///    * x {y} {}
+    ///   -> y {}  {y x}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.isomorphic_to(&renamed), Some(true));
+    /// ```
+    pub fn isomorphic_to(&self, other: &Nfa) -> Option<bool> {
+        if self.states.len() != other.states.len()
+            || !alphabet_equal(&self.alphabet, &other.alphabet)
+        {
+            return Some(false);
+        }
+        isomorphism::isomorphic(self, other, isomorphism::SEARCH_LIMIT)
+    }
+
+    /// Checks if this NFA simulates another NFA, that is, if every step the other NFA can take can be matched by a
+    /// step of this NFA on the same element, such that the states of this NFA keep simulating the states of the other
+    /// NFA and are accepting whenever those are. This returns `None` if and only if the alphabets of the two NFAs are
+    /// unequal (not considering ordering).
+    ///
+    /// The simulation is computed in polynomial time, as the greatest fixpoint of removing pairs of states which
+    /// violate the condition, starting from all pairs. ε-moves are taken into account by stepping on an element as
+    /// taking ε-moves, the element and then ε-moves again, and by considering a state accepting if an accepting state
+    /// is reachable by ε-moves.
+    ///
+    /// If this NFA simulates the other NFA, the language of the other NFA is a subset of the language of this NFA, but
+    /// not conversely: the language of `ab|ac` is a subset of the language of `a(b|c)` (they are equal), and
+    /// `a(b|c)` simulates `ab|ac`, but `ab|ac` doesn't simulate `a(b|c)`, since it has to choose between `b` and `c`
+    /// when stepping on `a`. Thus, `Some(false)` doesn't mean that the language isn't a subset, and
+    /// [Nfa::subset_of_antichain] should be used for an exact answer.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let late_choice: Nfa = parser::nfa("
+    ///        a   b   c
+    ///   -> s {t} {}  {}
+    ///      t {}  {f} {f}
+    ///    * f {}  {}  {}
+    /// ").unwrap().try_into().unwrap();
+    /// let early_choice: Nfa = parser::nfa("
+    ///         a       b   c
+    ///   -> s  {t1 t2} {}  {}
+    ///      t1 {}      {f} {}
+    ///      t2 {}      {}  {f}
+    ///    * f  {}      {}  {}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(late_choice.simulates(&early_choice), Some(true));
+    /// assert_eq!(early_choice.simulates(&late_choice), Some(false));
+    /// assert!(early_choice.equivalent_to(&late_choice));
+    /// ```
+    pub fn simulates(&self, other: &Nfa) -> Option<bool> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        let weak = WeakTransitions::disjoint_union(self, other);
+        let n = self.states.len() + other.states.len();
+        let other_initial = self.states.len() + other.initial_state;
+        Some(weak.simulation()[self.initial_state * n + other_initial])
+    }
+
+    /// Checks if this NFA is bisimilar to another NFA, that is, if their states can be related such that the initial
+    /// states are related, related states agree on accepting, and every step either NFA can take from a state can be
+    /// matched by a step of the other NFA on the same element from a related state, to a state related to the state
+    /// stepped to. This returns `None` if and only if the alphabets of the two NFAs are unequal (not considering
+    /// ordering). Like [Nfa::simulates], this is computed in polynomial time and takes ε-moves into account.
+    ///
+    /// Bisimilar NFAs are always [equivalent](Nfa::equivalent_to) (and simulate each other), but equivalent NFAs are
+    /// often not bisimilar, such as the NFAs of `a(b|c)` and `ab|ac` (see [Nfa::simulates]). Thus, this is a cheap
+    /// check which may prove that NFAs are equivalent, while `Some(false)` doesn't mean that they aren't. Isomorphic
+    /// NFAs are always bisimilar.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        a
+    ///   -> * s {s}
+    /// ").unwrap().try_into().unwrap();
+    /// let unrolled: Nfa = parser::nfa("
+    ///        a
+    ///   -> * s {t}
+    ///      * t {s t}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.bisimilar_to(&unrolled), Some(true));
+    /// ```
+    pub fn bisimilar_to(&self, other: &Nfa) -> Option<bool> {
+        if !alphabet_equal(&self.alphabet, &other.alphabet) {
+            return None;
+        }
+        let classes = WeakTransitions::disjoint_union(self, other).bisimulation();
+        Some(classes[self.initial_state] == classes[self.states.len() + other.initial_state])
+    }
+
+    /// Partitions the states of this NFA into the classes of the largest bisimulation of this NFA with itself (see
+    /// [Nfa::bisimilar_to]), where every state accepts the same language as the other states of its class. The
+    /// classes are sorted by their first states, and the states of each class are sorted. Merging the states of each
+    /// class into one state gives an equivalent NFA (see [Nfa::bisimulation_quotient]), so this may be used to reduce
+    /// the number of states of a NFA in polynomial time (but the result is not minimal in general, see the
+    /// [module-level documentation](crate::nfa)).
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        a       b
+    ///   -> s {t u}   {}
+    ///      t {}      {f}
+    ///      u {}      {g}
+    ///    * f {}      {}
+    ///    * g {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.bisimulation_classes(), vec![vec![0], vec![1, 2], vec![3, 4]]);
+    /// ```
+    pub fn bisimulation_classes(&self) -> Vec<Vec<usize>> {
+        let mut classes: Vec<Vec<usize>> = vec![];
+        for (state, class) in WeakTransitions::new(self)
+            .bisimulation()
+            .into_iter()
+            .enumerate()
+        {
+            if class == classes.len() {
+                classes.push(vec![]);
+            }
+            classes[class].push(state);
+        }
+        classes
+    }
+
+    /// Merges the states of every class of [Nfa::bisimulation_classes] into one state, giving an equivalent NFA
+    /// without ε-moves which is bisimilar to this NFA. Since bisimilar states only agree on their steps over ε-moves
+    /// (not on the ε-moves themselves), every merged state steps like the first state of its class does over ε-moves:
+    /// on an element, it steps to the classes of the states reachable from that state by taking ε-moves, the element
+    /// and then ε-moves again, and it is accepting if an accepting state is reachable from that state by ε-moves. Each
+    /// merged state is named after the first state of its class.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa: Nfa = parser::nfa("
+    ///        ε     a       b
+    ///   -> s {}    {t u}   {}
+    ///      t {}    {}      {f}
+    ///      u {t}   {}      {g}
+    ///    * f {}    {}      {}
+    ///    * g {}    {}      {}
+    /// ").unwrap().try_into().unwrap();
+    /// let quotient = nfa.bisimulation_quotient();
+    /// assert_eq!(quotient.states().len(), 3);
+    /// assert!(quotient.equivalent_to(&nfa));
+    /// assert_eq!(quotient.bisimilar_to(&nfa), Some(true));
+    /// ```
+    pub fn bisimulation_quotient(&self) -> Nfa {
+        let classes = self.bisimulation_classes();
+        let mut class_of = vec![0; self.states.len()];
+        for (class, states) in classes.iter().enumerate() {
+            for &state in states {
+                class_of[state] = class;
+            }
+        }
+        let closures = self.epsilon_closure_table();
+        let states = classes
+            .iter()
+            .map(|states| {
+                let closure = &closures[states[0]];
+                let step = |elem: usize| {
+                    let mut next = closure
+                        .iter()
+                        .flat_map(|&state| &self.states[state].transitions[elem])
+                        .flat_map(|&target| &closures[target])
+                        .map(|&target| class_of[target])
+                        .collect::<Vec<_>>();
+                    next.sort_unstable();
+                    next.dedup();
+                    next
+                };
+                NfaState {
+                    name: self.states[states[0]].name.clone(),
+                    initial: states.contains(&self.initial_state),
+                    accepting: closure.iter().any(|&state| self.states[state].accepting),
+                    epsilon_transitions: vec![],
+                    transitions: (0..self.alphabet.len()).map(step).collect(),
+                }
+            })
+            .collect();
+        Nfa {
+            alphabet: self.alphabet.clone(),
+            states,
+            initial_state: class_of[self.initial_state],
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::new(|| {
+                format!(
+                    "bisimulation quotient of {}",
+                    self.provenance.describe("NFA")
+                )
+            }),
+        }
     }
 
     /// Gets the alphabet of this NFA
@@ -1283,11 +2879,56 @@ impl Nfa {
         &self.alphabet
     }
 
+    /// Compares the alphabet of this NFA with the alphabet of another NFA, see [Dfa::alphabet_diff]
+    pub fn alphabet_diff(&self, other: &Nfa) -> AlphabetDiff {
+        AlphabetDiff::new(&self.alphabet, &other.alphabet)
+    }
+
+    /// Finds the graphemes appearing in the words of a corpus which aren't elements of the alphabet of this NFA, see
+    /// [Dfa::missing_symbols_for]
+    pub fn missing_symbols_for<'a>(
+        &self,
+        corpus: impl Iterator<Item = &'a str>,
+    ) -> BTreeSet<String> {
+        util::missing_graphemes(corpus, |grapheme| self.alphabet_index(grapheme))
+    }
+
+    /// Gets the index of the given element in the alphabet of this NFA, or `None` if it isn't part of the alphabet.
+    /// The lookup table is built upon the first call and then reused, so repeated lookups are cheap.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let input = "
+    ///        a   b
+    /// -> s   {t} {s}
+    ///  * t   {t} {s}
+    /// ";
+    /// let nfa: Nfa = parser::nfa(input).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.alphabet_index("a"), Some(0));
+    /// assert_eq!(nfa.alphabet_index("b"), Some(1));
+    /// assert_eq!(nfa.alphabet_index("c"), None);
+    /// ```
+    pub fn alphabet_index(&self, symbol: &str) -> Option<usize> {
+        self.alphabet_lookup.get(&self.alphabet, symbol)
+    }
+
     /// Gets the states of this NFA
     pub fn states(&self) -> &[NfaState] {
         self.states.as_slice()
     }
 
+    /// Gets the number of states of this NFA, which is the same as `self.states().len()`
+    pub fn states_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Gets the size of the alphabet of this NFA, which is the same as `self.alphabet().len()`
+    pub fn alphabet_size(&self) -> usize {
+        self.alphabet.len()
+    }
+
     /// Gets the initial state of this NFA
     pub fn initial_state(&self) -> &NfaState {
         &self.states[self.initial_state]
@@ -1297,4 +2938,175 @@ impl Nfa {
     pub fn initial_state_index(&self) -> usize {
         self.initial_state
     }
+
+    /// Gets the transition sets of this NFA, where `sets[state][elem]` is the indices of the states the NFA moves to
+    /// from the state with index `state` upon seeing the element with index `elem` in the [alphabet](Nfa::alphabet).
+    /// The epsilon transitions are not included, see [NfaState::epsilon_transitions] for those.
+    pub fn transition_sets(&self) -> Vec<Vec<Vec<usize>>> {
+        self.states
+            .iter()
+            .map(|state| state.transitions.clone())
+            .collect()
+    }
+
+    /// Gets the number of transitions of this NFA, which is the total size of all transition sets, including the
+    /// epsilon transitions. For only the epsilon transitions, see [Nfa::epsilon_transition_count].
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa = "
+    ///         ε   a     b
+    /// -> s   {t} {s t} {}
+    ///  * t   {}  {}    {s}";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// assert_eq!(nfa.transition_count(), 4);
+    /// assert_eq!(nfa.epsilon_transition_count(), 1);
+    /// assert_eq!(nfa.accepting_state_count(), 1);
+    /// ```
+    pub fn transition_count(&self) -> usize {
+        self.states
+            .iter()
+            .map(|state| state.transitions.iter().map(Vec::len).sum::<usize>())
+            .sum::<usize>()
+            + self.epsilon_transition_count()
+    }
+
+    /// Gets the number of epsilon transitions of this NFA, that is, the total size of all epsilon transition sets
+    pub fn epsilon_transition_count(&self) -> usize {
+        self.states
+            .iter()
+            .map(|state| state.epsilon_transitions.len())
+            .sum()
+    }
+
+    /// Gets the number of accepting states of this NFA
+    pub fn accepting_state_count(&self) -> usize {
+        self.states.iter().filter(|s| s.accepting).count()
+    }
+
+    /// Gets the size of the largest transition set of this NFA, over all states and elements of the alphabet
+    /// (including the epsilon transitions). This is 1 or less for NFAs converted from DFAs.
+    pub fn max_branching(&self) -> usize {
+        self.states
+            .iter()
+            .flat_map(|state| state.transitions.iter().chain([&state.epsilon_transitions]))
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Finds the states where this NFA has a nondeterministic choice, that is, the states with epsilon transitions or
+    /// with more than one transition upon some element of the alphabet, in the order they appear in the automata
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa = "
+    ///         ε   a     b
+    /// -> s   {}  {s t} {s}
+    ///    t   {u} {}    {}
+    ///  * u   {}  {}    {}";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// let names = nfa.nondeterministic_states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    /// assert_eq!(names, vec!["s", "t"]);
+    /// assert_eq!(nfa.max_branching(), 2);
+    /// ```
+    pub fn nondeterministic_states(&self) -> Vec<&NfaState> {
+        self.states
+            .iter()
+            .filter(|state| {
+                !state.epsilon_transitions.is_empty()
+                    || state.transitions.iter().any(|targets| targets.len() > 1)
+            })
+            .collect()
+    }
+
+    /// Checks if this NFA is ambiguous, that is, if some word has two or more distinct accepting runs. A run starts in
+    /// the initial state and takes one transition for each symbol of the word, with any number of epsilon transitions
+    /// before, between and after them. An unambiguous NFA may still be nondeterministic (see
+    /// [Nfa::nondeterministic_states]), but NFAs converted from DFAs are never ambiguous. If a state which is part of
+    /// some accepting run is on a cycle of epsilon transitions, there are infinitely many runs, so the NFA is
+    /// ambiguous.
+    ///
+    /// This is decided without any NFA-to-DFA conversion, by exploring the product of this NFA with itself for pairs
+    /// of different states that are both reachable and can reach an accepting state.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// // Accepts the words containing "ab", with one accepting run per occurrence of "ab"
+    /// let contains_ab = "
+    ///        a     b
+    /// -> s  {s t} {s}
+    ///    t  {}    {u}
+    ///  * u  {u}   {u}";
+    /// let contains_ab: Nfa = parser::nfa(contains_ab).unwrap().try_into().unwrap();
+    /// assert!(contains_ab.is_ambiguous()); // "abab" has two accepting runs
+    ///
+    /// // Accepts the words ending with "ab", with exactly one accepting run for each of them
+    /// let ends_with_ab = "
+    ///        a     b
+    /// -> s  {s t} {s}
+    ///    t  {}    {u}
+    ///  * u  {}    {}";
+    /// let ends_with_ab: Nfa = parser::nfa(ends_with_ab).unwrap().try_into().unwrap();
+    /// assert!(!ends_with_ab.is_ambiguous());
+    /// assert!(!ends_with_ab.nondeterministic_states().is_empty());
+    /// ```
+    pub fn is_ambiguous(&self) -> bool {
+        ambiguity::is_ambiguous(self)
+    }
+
+    /// Estimates the number of bytes this NFA occupies in memory, including the heap allocations it owns. This is
+    /// useful to warn before doing something expensive, such as [converting it to a DFA](Nfa::to_dfa). The estimate
+    /// is deterministic, and is defined the same way as for [Dfa::approx_memory_bytes]: the size of the [Nfa] struct
+    /// itself plus the *capacity* of every `Vec` (including every transition set) times the size of its elements, plus
+    /// the length and the reference counts of every `Rc<str>` and of the alphabet slice. Shared strings are counted in
+    /// full for every automaton.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::nfa::Nfa;
+    ///
+    /// let nfa = "
+    ///        a     b
+    /// -> * s {s t} {}
+    ///      t {}    {s}";
+    /// let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    /// let union = nfa.clone().union(nfa.clone()).unwrap();
+    /// assert!(union.approx_memory_bytes() > nfa.approx_memory_bytes());
+    /// ```
+    pub fn approx_memory_bytes(&self) -> usize {
+        let states = self
+            .states
+            .iter()
+            .map(|state| {
+                util::rc_str_bytes(&state.name)
+                    + state.epsilon_transitions.capacity() * mem::size_of::<usize>()
+                    + state.transitions.capacity() * mem::size_of::<Vec<usize>>()
+                    + state
+                        .transitions
+                        .iter()
+                        .map(|set| set.capacity() * mem::size_of::<usize>())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+        mem::size_of::<Self>()
+            + util::alphabet_bytes(&self.alphabet)
+            + self.states.capacity() * mem::size_of::<NfaState>()
+            + states
+    }
+
+    /// Gets a description of how this NFA was created, for example "conversion from parsed DFA", or `None` if it was
+    /// parsed from text. This is only available with the `provenance` feature, since recording it requires some extra
+    /// allocations for every operation creating a new automaton. Descriptions longer than 200 characters (such as
+    /// those of automata created by many operations in a row) are cut off with a `…`.
+    #[cfg(feature = "provenance")]
+    pub fn provenance(&self) -> Option<&str> {
+        self.provenance.get()
+    }
 }
@@ -1,5 +1,7 @@
 use crate::nfa::{Nfa, NfaState};
-use crate::parser::{NfaAlphabetEntry, ParsedNfa, ParsedNfaState};
+use crate::parser::{NfaAlphabetEntry, ParsedNfa, ParsedNfaOwned, ParsedNfaState};
+use crate::util::{valid_symbol, AlphabetIndex, Provenance, Version};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ops::Not;
 use std::rc::Rc;
@@ -8,17 +10,93 @@ use thiserror::Error;
 #[derive(Debug, Error, PartialEq)]
 pub enum NfaParseError<'a> {
     #[error("Wrong number of transitions for state '{0}': has {1} expected {2}")]
-    WrongNumberOfTransitions(&'a str, usize, usize),
+    WrongNumberOfTransitions(Cow<'a, str>, usize, usize),
     #[error("State '{1}' does not exist (in transition from state '{0}')")]
-    TransitionDoesNotExist(&'a str, &'a str),
+    TransitionDoesNotExist(Cow<'a, str>, Cow<'a, str>),
     #[error("There is no initial state")]
     MissingInitialState,
     #[error("There are two (or more) initial states")]
     MultipleInitialStates,
     #[error("'{0}' appears twice in the alphabet")]
-    DuplicateAlphabetSymbol(&'a str),
+    DuplicateAlphabetSymbol(Cow<'a, str>),
+    #[error("'{0}' is not a valid element of the alphabet, since it is empty or starts or ends with whitespace")]
+    InvalidAlphabetSymbol(Cow<'a, str>),
     #[error("State '{0}' defined multiple times")]
-    DuplicateStateDefinition(&'a str),
+    DuplicateStateDefinition(Cow<'a, str>),
+    #[error(
+        "The alphabet pragma declares the alphabet {0:?}, but the table has the alphabet {1:?}"
+    )]
+    AlphabetPragmaMismatch(Vec<Cow<'a, str>>, Vec<Cow<'a, str>>),
+}
+
+/// An owned version of [NfaParseError], which doesn't borrow from the parsed string. This is useful to propagate
+/// errors with `?` from functions that parse strings they own themselves, or to store errors for later use. Obtained
+/// by [NfaParseError::to_owned] or [From::from].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum NfaParseErrorOwned {
+    #[error("Wrong number of transitions for state '{0}': has {1} expected {2}")]
+    WrongNumberOfTransitions(String, usize, usize),
+    #[error("State '{1}' does not exist (in transition from state '{0}')")]
+    TransitionDoesNotExist(String, String),
+    #[error("There is no initial state")]
+    MissingInitialState,
+    #[error("There are two (or more) initial states")]
+    MultipleInitialStates,
+    #[error("'{0}' appears twice in the alphabet")]
+    DuplicateAlphabetSymbol(String),
+    #[error("'{0}' is not a valid element of the alphabet, since it is empty or starts or ends with whitespace")]
+    InvalidAlphabetSymbol(String),
+    #[error("State '{0}' defined multiple times")]
+    DuplicateStateDefinition(String),
+    #[error(
+        "The alphabet pragma declares the alphabet {0:?}, but the table has the alphabet {1:?}"
+    )]
+    AlphabetPragmaMismatch(Vec<String>, Vec<String>),
+}
+
+impl NfaParseError<'_> {
+    /// Converts this error to an owned error, copying the borrowed parts of the parsed string
+    pub fn to_owned(&self) -> NfaParseErrorOwned {
+        use NfaParseError::*;
+        let owned = |elems: &[Cow<str>]| elems.iter().map(|elem| elem.to_string()).collect();
+        match self {
+            WrongNumberOfTransitions(state, has, expected) => {
+                NfaParseErrorOwned::WrongNumberOfTransitions(state.to_string(), *has, *expected)
+            }
+            TransitionDoesNotExist(from, to) => {
+                NfaParseErrorOwned::TransitionDoesNotExist(from.to_string(), to.to_string())
+            }
+            MissingInitialState => NfaParseErrorOwned::MissingInitialState,
+            MultipleInitialStates => NfaParseErrorOwned::MultipleInitialStates,
+            DuplicateAlphabetSymbol(symbol) => {
+                NfaParseErrorOwned::DuplicateAlphabetSymbol(symbol.to_string())
+            }
+            InvalidAlphabetSymbol(symbol) => {
+                NfaParseErrorOwned::InvalidAlphabetSymbol(symbol.to_string())
+            }
+            DuplicateStateDefinition(state) => {
+                NfaParseErrorOwned::DuplicateStateDefinition(state.to_string())
+            }
+            AlphabetPragmaMismatch(declared, actual) => {
+                NfaParseErrorOwned::AlphabetPragmaMismatch(owned(declared), owned(actual))
+            }
+        }
+    }
+}
+
+impl From<NfaParseError<'_>> for NfaParseErrorOwned {
+    fn from(value: NfaParseError<'_>) -> Self {
+        value.to_owned()
+    }
+}
+
+/// Validates an edited [ParsedNfaOwned] the same way as a [ParsedNfa]
+impl TryFrom<ParsedNfaOwned> for Nfa {
+    type Error = NfaParseErrorOwned;
+
+    fn try_from(value: ParsedNfaOwned) -> Result<Self, Self::Error> {
+        Nfa::try_from(value.as_parsed()).map_err(|e| e.to_owned())
+    }
 }
 
 impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
@@ -26,7 +104,11 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
 
     fn try_from(value: ParsedNfa<'a>) -> Result<Self, Self::Error> {
         use NfaParseError::*;
-        let ParsedNfa { head, states } = value;
+        let ParsedNfa {
+            head,
+            states,
+            declared_alphabet,
+        } = value;
 
         let mut eps_idx = None;
         {
@@ -34,23 +116,52 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             head.iter()
                 .enumerate()
                 .try_for_each(|(idx, e)| match e {
-                    NfaAlphabetEntry::Element(c) => alphabet.insert(c).then_some(()).ok_or(c),
+                    NfaAlphabetEntry::Element(c) => {
+                        alphabet.insert(c).then_some(()).ok_or(c.clone())
+                    }
                     NfaAlphabetEntry::Eps => {
                         if eps_idx.is_some() {
-                            Err(&"ε")
+                            Err(Cow::Borrowed("ε"))
                         } else {
                             eps_idx = Some(idx);
                             Ok(())
                         }
                     }
                 })
-                .map_err(|e| DuplicateAlphabetSymbol(e))?
+                .map_err(DuplicateAlphabetSymbol)?
+        }
+        if let Some(symbol) = head.iter().find_map(|entry| match entry {
+            NfaAlphabetEntry::Element(symbol) => (!valid_symbol(symbol)).then(|| symbol.clone()),
+            NfaAlphabetEntry::Eps => None,
+        }) {
+            return Err(InvalidAlphabetSymbol(symbol));
+        }
+
+        if let Some(declared) = declared_alphabet {
+            let elements = head
+                .iter()
+                .filter_map(|entry| match entry {
+                    NfaAlphabetEntry::Element(elem) => Some(elem.clone()),
+                    NfaAlphabetEntry::Eps => None,
+                })
+                .collect::<Vec<_>>();
+            if declared.iter().copied().collect::<HashSet<_>>()
+                != elements
+                    .iter()
+                    .map(|elem| elem.as_ref())
+                    .collect::<HashSet<_>>()
+            {
+                return Err(AlphabetPragmaMismatch(
+                    declared.into_iter().map(Cow::Borrowed).collect(),
+                    elements,
+                ));
+            }
         }
 
         let state_name_map: HashMap<_, _> = states
             .iter()
             .enumerate()
-            .map(|(i, s)| (s.name, i))
+            .map(|(i, s)| (s.name.clone(), i))
             .collect();
 
         if state_name_map.len() != states.len() {
@@ -58,8 +169,8 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             let mut seen = HashSet::new();
             let duplicate = states
                 .iter()
-                .find_map(|s| seen.insert(s.name).not().then_some(s.name))
-                .unwrap_or("<unknown>");
+                .find_map(|s| seen.insert(&s.name).not().then(|| s.name.clone()))
+                .unwrap_or(Cow::Borrowed("<unknown>"));
             return Err(DuplicateStateDefinition(duplicate));
         }
 
@@ -84,11 +195,11 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
 
             let mut epsilon_transitions = None;
             let mut new_transitions = Vec::with_capacity(head.len());
-            for (idx, transition) in transitions.iter().enumerate() {
+            for (idx, transition) in transitions.into_iter().enumerate() {
                 let mut tr_idx = Vec::with_capacity(transition.len());
                 if Some(idx) == eps_idx {
                     for target in transition {
-                        if let Some(idx) = state_name_map.get(target) {
+                        if let Some(idx) = state_name_map.get(target.as_ref()) {
                             tr_idx.push(*idx);
                         } else {
                             return Err(TransitionDoesNotExist(name, target)); // Target of transition does not exist
@@ -97,7 +208,7 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
                     epsilon_transitions = Some(tr_idx);
                 } else {
                     for target in transition {
-                        if let Some(idx) = state_name_map.get(target) {
+                        if let Some(idx) = state_name_map.get(target.as_ref()) {
                             tr_idx.push(*idx);
                         } else {
                             return Err(TransitionDoesNotExist(name, target)); // Target of transition does not exist
@@ -116,7 +227,7 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
             }
 
             new_states.push(NfaState {
-                name: Rc::from(name),
+                name: Rc::from(name.as_ref()),
                 initial,
                 accepting,
                 epsilon_transitions: epsilon_transitions.unwrap_or_default(),
@@ -130,11 +241,14 @@ impl<'a> TryFrom<ParsedNfa<'a>> for Nfa {
                     .into_iter()
                     .filter_map(|s| match s {
                         NfaAlphabetEntry::Eps => None,
-                        NfaAlphabetEntry::Element(s) => Some(Rc::from(s)),
+                        NfaAlphabetEntry::Element(s) => Some(Rc::from(s.as_ref())),
                     })
                     .collect::<Rc<[_]>>(),
                 states: new_states,
                 initial_state,
+                alphabet_lookup: AlphabetIndex::default(),
+                version: Version::default(),
+                provenance: Provenance::none(),
             };
             Ok(dfa)
         } else {
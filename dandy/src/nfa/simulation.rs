@@ -0,0 +1,143 @@
+use crate::nfa::antichain::closed_set;
+use crate::nfa::Nfa;
+use std::collections::HashMap;
+
+/// The weak transitions of one or more NFAs, without ε-moves. A state steps on an element to every state reachable by
+/// taking ε-moves, the element and then ε-moves again, and it is accepting if an accepting state is reachable by
+/// ε-moves. The states of a state accept the same language as the states of the NFAs they come from, so
+/// (bi)simulations of these transitions are (bi)simulations of the NFAs which are insensitive to how ε-moves are
+/// placed.
+pub(crate) struct WeakTransitions {
+    accepting: Vec<bool>,
+    /// `successors[state][elem]` is the states reached from `state` on the element, sorted
+    successors: Vec<Vec<Vec<usize>>>,
+}
+
+impl WeakTransitions {
+    pub(crate) fn new(nfa: &Nfa) -> Self {
+        let mut weak = Self {
+            accepting: vec![],
+            successors: vec![],
+        };
+        weak.push(nfa, &(0..nfa.alphabet.len()).collect::<Vec<_>>());
+        weak
+    }
+
+    /// The weak transitions of two NFAs with equal alphabets (not considering ordering), where the states of `other`
+    /// come after the states of `this` and the elements are in the order of the alphabet of `this`
+    pub(crate) fn disjoint_union(this: &Nfa, other: &Nfa) -> Self {
+        let mut weak = Self::new(this);
+        let columns = this
+            .alphabet
+            .iter()
+            .map(|elem| {
+                other
+                    .alphabet_index(elem)
+                    .expect("Alphabets should be equal")
+            })
+            .collect::<Vec<_>>();
+        weak.push(other, &columns);
+        weak
+    }
+
+    /// Adds the states of a NFA, where `columns[j]` is the column in `nfa` of the j:th element
+    fn push(&mut self, nfa: &Nfa, columns: &[usize]) {
+        let offset = self.accepting.len();
+        let closures = nfa.epsilon_closure_table();
+        for closure in &closures {
+            self.accepting
+                .push(closure.iter().any(|&state| nfa.states[state].accepting));
+            self.successors.push(
+                columns
+                    .iter()
+                    .map(|&column| {
+                        closed_set(
+                            closure
+                                .iter()
+                                .flat_map(|&state| &nfa.states[state].transitions[column]),
+                            &closures,
+                        )
+                        .into_iter()
+                        .map(|state| state + offset)
+                        .collect()
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    /// Computes the largest simulation preorder, where `simulates[p * n + q]` (with `n` states) tells if `p`
+    /// simulates `q`, that is, if `p` is accepting whenever `q` is, and every step of `q` can be matched by a step of
+    /// `p` on the same element to a state simulating the state `q` stepped to. This is the greatest fixpoint, found by
+    /// starting from all pairs agreeing on accepting and removing pairs violating the condition until none does.
+    pub(crate) fn simulation(&self) -> Vec<bool> {
+        let n = self.accepting.len();
+        let mut simulates = (0..n * n)
+            .map(|idx| self.accepting[idx / n] || !self.accepting[idx % n])
+            .collect::<Vec<_>>();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for p in 0..n {
+                for q in 0..n {
+                    if simulates[p * n + q]
+                        && !self.successors[q].iter().zip(&self.successors[p]).all(
+                            |(q_next, p_next)| {
+                                q_next.iter().all(|&q_next| {
+                                    p_next.iter().any(|&p_next| simulates[p_next * n + q_next])
+                                })
+                            },
+                        )
+                    {
+                        simulates[p * n + q] = false;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        simulates
+    }
+
+    /// Computes the largest bisimulation, giving the class of every state. This is the coarsest partition where
+    /// states of the same class agree on accepting and step to the same classes on every element, found by
+    /// partitioning on accepting and then refining on the classes stepped to until the partition is stable. The
+    /// classes are numbered in the order of their first states.
+    pub(crate) fn bisimulation(&self) -> Vec<usize> {
+        let mut classes = self
+            .accepting
+            .iter()
+            .map(|&accepting| usize::from(accepting))
+            .collect::<Vec<_>>();
+        let mut count = 0;
+        loop {
+            let mut signatures = HashMap::new();
+            let refined = self
+                .successors
+                .iter()
+                .enumerate()
+                .map(|(state, successors)| {
+                    let signature = (
+                        classes[state],
+                        successors
+                            .iter()
+                            .map(|next| {
+                                let mut next = next.iter().map(|&q| classes[q]).collect::<Vec<_>>();
+                                next.sort_unstable();
+                                next.dedup();
+                                next
+                            })
+                            .collect::<Vec<_>>(),
+                    );
+                    let class = signatures.len();
+                    *signatures.entry(signature).or_insert(class)
+                })
+                .collect::<Vec<_>>();
+            // Refining never merges classes, so the partition is stable when no class is split
+            if signatures.len() == count {
+                return refined;
+            }
+            count = signatures.len();
+            classes = refined;
+        }
+    }
+}
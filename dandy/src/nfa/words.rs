@@ -1,12 +1,24 @@
+#[cfg(feature = "fast-matrix")]
+use crate::nfa::bit_matrix::BitMatrix;
 use crate::nfa::Nfa;
+#[cfg(not(feature = "fast-matrix"))]
 use nalgebra::DMatrix;
 use num_traits::{One, Zero};
+use std::borrow::Cow;
+use std::cell::OnceCell;
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign, Mul, MulAssign};
 use std::rc::Rc;
 use NumBool::*;
 
+/// The type of the adjacency matrices used for the word enumeration. With the `fast-matrix` feature, this is a
+/// bitpacked matrix, and otherwise a `nalgebra` matrix of `NumBool`s.
+#[cfg(not(feature = "fast-matrix"))]
+type AdjacencyMatrix = DMatrix<NumBool>;
+#[cfg(feature = "fast-matrix")]
+type AdjacencyMatrix = BitMatrix;
+
 /// An iterator visiting all words accepted by a NFA iteratively, returning them as [String]s. The
 /// iterator visits words in lexicographic order, according to the alphabet of the NFA.
 pub struct Words<'a> {
@@ -27,6 +39,10 @@ impl Iterator for Words<'_> {
                 })
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<'a> Words<'a> {
@@ -37,31 +53,109 @@ impl<'a> Words<'a> {
     }
 }
 
+/// An iterator visiting all words accepted by a NFA iteratively, returning them as [String]s
+/// together with the names of the accepting states the NFA ends up in on each word (in the order
+/// of the states of the NFA). The iterator visits words in lexicographic order, according to the
+/// alphabet of the NFA.
+pub struct WordsWithStates<'a> {
+    inner: WordComponentIndices<'a>,
+}
+
+impl Iterator for WordsWithStates<'_> {
+    type Item = (String, Vec<Rc<str>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.put_next();
+        let components = self.inner.last_word.as_ref()?;
+        let word = components
+            .iter()
+            .map(|c| self.inner.nfa.alphabet[*c].as_ref())
+            .collect();
+        let states = self
+            .inner
+            .final_states()
+            .into_iter()
+            .map(|idx| self.inner.nfa.states[idx].name.clone())
+            .collect();
+        Some((word, states))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> WordsWithStates<'a> {
+    /// Creates an iterator over the words accepted by the NFA, the same as [Nfa::words_with_states]
+    pub fn new(nfa: &'a Nfa) -> Self {
+        Self {
+            inner: WordComponentIndices::new(nfa),
+        }
+    }
+
+    /// Creates the iterator from a NFA it owns rather than borrows
+    pub(crate) fn owned(nfa: Nfa) -> WordsWithStates<'static> {
+        WordsWithStates {
+            inner: WordComponentIndices::from_cow(Cow::Owned(nfa)),
+        }
+    }
+}
+
 /// An iterator visiting all words accepted by a NFA iteratively, returning them as vectors of
-/// components (`Rc<str>`) for elements of the words. The iterator visits words in lexicographic
-/// order, according to the alphabet of the NFA.
-pub struct WordComponents<'a> {
+/// components for elements of the words. The iterator visits words in lexicographic order,
+/// according to the alphabet of the NFA.
+///
+/// By default, the components are the `Rc<str>`s of the alphabet of the NFA. Any other type can be
+/// built from the elements of the alphabet using [WordComponents::with_components], such as
+/// borrowed `&str`s which avoid cloning an `Rc` per element.
+pub struct WordComponents<'a, T = Rc<str>> {
     inner: WordComponentIndices<'a>,
+    alphabet: &'a [Rc<str>],
+    component: fn(&'a Rc<str>) -> T,
 }
 
-impl Iterator for WordComponents<'_> {
-    type Item = Vec<Rc<str>>;
+impl<T> Iterator for WordComponents<'_, T> {
+    type Item = Vec<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.put_next();
         self.inner.last_word.as_ref().map(|components| {
             components
                 .iter()
-                .map(|c| self.inner.nfa.alphabet[*c].clone())
+                .map(|c| (self.component)(&self.alphabet[*c]))
                 .collect()
         })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 impl<'a> WordComponents<'a> {
     pub fn new(nfa: &'a Nfa) -> Self {
+        Self::with_components(nfa, Rc::clone)
+    }
+}
+
+impl<'a, T> WordComponents<'a, T> {
+    /// Creates an iterator whose components are created from the elements of the alphabet of the
+    /// NFA by the given function.
+    ///
+    /// ```
+    /// # use dandy::nfa::words::WordComponents;
+    /// # use dandy::nfa::Nfa;
+    /// let mut nfa: Nfa = dandy::parser::regex("ab*").unwrap().to_nfa();
+    /// nfa.remove_epsilon_moves();
+    /// let words = WordComponents::with_components(&nfa, |elem| elem.as_ref());
+    /// let words: Vec<Vec<&str>> = words.take(2).collect();
+    /// assert_eq!(words, vec![vec!["a"], vec!["a", "b"]]);
+    /// ```
+    pub fn with_components(nfa: &'a Nfa, component: fn(&'a Rc<str>) -> T) -> Self {
         Self {
             inner: WordComponentIndices::new(nfa),
+            alphabet: &nfa.alphabet,
+            component,
         }
     }
 }
@@ -69,15 +163,21 @@ impl<'a> WordComponents<'a> {
 /// An iterator visiting all words accepted by a NFA iteratively, returning them as vectors of
 /// indices for elements of the words. The iterator visits words in lexicographic
 /// order, according to the alphabet of the NFA.
+///
+/// The [size hint](Iterator::size_hint) has no upper bound if the NFA accepts infinitely many
+/// words. Otherwise, its upper bound is the number of accepting paths through the NFA (minus the
+/// words already visited), which is exact for NFAs without nondeterminism.
 pub struct WordComponentIndices<'a> {
-    nfa: &'a Nfa,
-    adj_matrices: Vec<DMatrix<NumBool>>,
+    nfa: Cow<'a, Nfa>,
+    adj_matrices: Vec<AdjacencyMatrix>,
     final_states: HashSet<usize>,
     state_stack: Vec<HashSet<usize>>,
     #[allow(dead_code)] // Unused for now since we don't support NFAs with epsilon moves yet
     has_epsilon_moves: bool,
     has_failed: bool,
     last_word: Option<Vec<usize>>,
+    visited: usize,
+    word_bound: OnceCell<Option<usize>>,
 }
 
 impl Iterator for WordComponentIndices<'_> {
@@ -87,6 +187,14 @@ impl Iterator for WordComponentIndices<'_> {
         self.put_next();
         self.last_word.clone()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.has_failed {
+            return (0, Some(0));
+        }
+        let bound = self.word_bound.get_or_init(|| word_bound(&self.nfa));
+        (0, bound.map(|bound| bound.saturating_sub(self.visited)))
+    }
 }
 
 // Based on: http://maya-ackerman.com/wp-content/uploads/2018/09/Enumeration_AckermanShallit2.pdf
@@ -102,7 +210,7 @@ impl<'a> WordComponentIndices<'a> {
         if let Some(last) = self.last_word.clone() {
             len = last.len() + 1;
             if let Some(new) = self.next_word(last) {
-                self.last_word = Some(new);
+                self.visit(new);
                 return;
             }
         }
@@ -117,7 +225,7 @@ impl<'a> WordComponentIndices<'a> {
                     len += 1;
                 }
                 Some(w) => {
-                    self.last_word = Some(w);
+                    self.visit(w);
                     return;
                 }
             }
@@ -126,11 +234,22 @@ impl<'a> WordComponentIndices<'a> {
         self.has_failed = true;
     }
 
+    fn visit(&mut self, word: Vec<usize>) {
+        // The words are visited in increasing order (by length, and then lexicographically), so a word which isn't
+        // greater than the last one is either visited twice or out of order
+        if let Some(last) = &self.last_word {
+            debug_assert!(
+                (last.len(), last) < (word.len(), &word),
+                "the word {word:?} was visited after {last:?}"
+            );
+        }
+        self.last_word = Some(word);
+        self.visited += 1;
+    }
+
     fn next_word(&mut self, mut word: Vec<usize>) -> Option<Vec<usize>> {
         let WordComponentIndices {
-            nfa,
-            state_stack,
-            ..
+            nfa, state_stack, ..
         } = self;
         let n_complete = |n, from| {
             let mut s: HashSet<usize> = HashSet::new();
@@ -194,6 +313,32 @@ impl<'a> WordComponentIndices<'a> {
         None
     }
 
+    /// Gets the accepting states the NFA ends up in on the last visited word, in index order. The
+    /// top of the stack S is the set of states after all but the last element of that word (leaving
+    /// out states which can't reach an accepting state anyway), so the last element is stepped here.
+    fn final_states(&self) -> Vec<usize> {
+        let Some(word) = &self.last_word else {
+            return vec![];
+        };
+        let current_s = self
+            .state_stack
+            .last()
+            .expect("final_states: state stack should be nonempty");
+        let mut states = match word.last() {
+            None => current_s.iter().copied().collect::<HashSet<_>>(),
+            Some(&last) => current_s
+                .iter()
+                .flat_map(|u| self.nfa.states[*u].transitions[last].iter())
+                .copied()
+                .collect(),
+        }
+        .into_iter()
+        .filter(|idx| self.nfa.states[*idx].accepting)
+        .collect::<Vec<_>>();
+        states.sort_unstable();
+        states
+    }
+
     /// Gets the minimum word of size n, and updates the stack S
     fn min_word(&mut self, n: usize) -> Option<Vec<usize>> {
         self.generate_matrices_up_to(n);
@@ -242,7 +387,22 @@ impl<'a> WordComponentIndices<'a> {
         Some(ret)
     }
 
-    fn is_reachable_in_one_step(nfa: &'a Nfa, from: usize, to: usize, epsilon_moves: bool) -> bool {
+    /// Iterates over all words of length exactly `n` in lexicographic order. This starts directly at the minimal
+    /// word of length `n` using the adjacency matrices, without visiting any shorter words.
+    pub(crate) fn of_length(mut self, n: usize) -> WordsOfLength<'a> {
+        self.state_stack.clear();
+        self.state_stack
+            .push(HashSet::from([self.nfa.initial_state]));
+        let next = self.min_word(n);
+        let bound = accepting_paths(&self.nfa).nth(n).unwrap_or(0);
+        WordsOfLength {
+            inner: self,
+            next,
+            bound,
+        }
+    }
+
+    fn is_reachable_in_one_step(nfa: &Nfa, from: usize, to: usize, epsilon_moves: bool) -> bool {
         if epsilon_moves {
             nfa.closure(from)
                 .expect("'from' state should exist")
@@ -277,19 +437,80 @@ impl<'a> WordComponentIndices<'a> {
     /// with index i to state with index j using exactly one character from the alphabet. This means
     /// that state a is adjacent to state b if we can move from any state in the epsilon closure of
     /// a to any state whose epsilon closure includes b upon seeing one symbol from the alphabet.
-    fn generate_adjacency_matrix(nfa: &'a Nfa) -> DMatrix<NumBool> {
+    fn generate_adjacency_matrix(nfa: &Nfa) -> AdjacencyMatrix {
         let n = nfa.states.len();
         let eps = nfa.has_epsilon_moves();
-        DMatrix::from_fn(n, n, |from, to| {
+        AdjacencyMatrix::from_fn(n, n, |from, to| {
             Self::is_reachable_in_one_step(nfa, from, to, eps).into()
         })
     }
 
-    fn identity_matrix(n: usize) -> DMatrix<NumBool> {
-        DMatrix::from_fn(n, n, |x, y| (x == y).into())
+    /// Gets the alphabet of the NFA, which the indices of the visited words refer to
+    pub fn alphabet(&self) -> &[Rc<str>] {
+        &self.nfa.alphabet
+    }
+
+    fn identity_matrix(n: usize) -> AdjacencyMatrix {
+        AdjacencyMatrix::from_fn(n, n, |x, y| (x == y).into())
     }
 
     pub fn new(nfa: &'a Nfa) -> Self {
+        Self::from_cow(Cow::Borrowed(nfa))
+    }
+
+    /// Creates an iterator which continues right after the given word, as if it was the last word
+    /// visited. This allows for resuming the enumeration later on without keeping the iterator
+    /// around, by only saving the last word. Returns `None` if the word isn't accepted by the NFA
+    /// (or contains an index outside of the alphabet). Since the words visited before aren't
+    /// known, the upper bound of the [size hint](Iterator::size_hint) counts them as well.
+    ///
+    /// ```
+    /// # use dandy::nfa::words::WordComponentIndices;
+    /// # use dandy::nfa::Nfa;
+    /// let mut nfa: Nfa = dandy::parser::regex("a*b").unwrap().to_nfa();
+    /// nfa.remove_epsilon_moves();
+    /// let words = WordComponentIndices::new(&nfa).take(5).collect::<Vec<_>>();
+    /// let resumed = WordComponentIndices::resume_from(&nfa, &words[1]).unwrap();
+    /// assert_eq!(resumed.take(3).collect::<Vec<_>>(), &words[2..]);
+    /// ```
+    pub fn resume_from(nfa: &'a Nfa, last_word: &[usize]) -> Option<Self> {
+        if last_word.iter().any(|&idx| idx >= nfa.alphabet.len()) {
+            return None;
+        }
+        let mut indices = Self::new(nfa);
+        let n = last_word.len();
+        indices.generate_matrices_up_to(n);
+
+        // Rebuild the stack S just like min_word would have when visiting the word: the i:th set
+        // is the states after i elements, which can still reach an accepting state in n - i steps
+        let mut current_s = HashSet::from([nfa.initial_state]);
+        indices.state_stack.push(current_s.clone());
+        for (i, &elem) in last_word.iter().enumerate() {
+            let matrix = &indices.adj_matrices[n - i - 1];
+            current_s = current_s
+                .iter()
+                .flat_map(|u| nfa.states[*u].transitions[elem].iter())
+                .copied()
+                .filter(|v| {
+                    indices
+                        .final_states
+                        .iter()
+                        .any(|f| matrix[(*v, *f)] == True)
+                })
+                .collect();
+            if i != n - 1 {
+                indices.state_stack.push(current_s.clone());
+            }
+        }
+        if !current_s.iter().any(|s| indices.final_states.contains(s)) {
+            return None;
+        }
+
+        indices.last_word = Some(last_word.to_vec());
+        Some(indices)
+    }
+
+    fn from_cow(nfa: Cow<'a, Nfa>) -> Self {
         let final_states = nfa
             .states
             .iter()
@@ -300,17 +521,20 @@ impl<'a> WordComponentIndices<'a> {
         if has_epsilon_moves {
             unimplemented!("Words iterator for NFAs with epsilon moves is unimplemented");
         }
+        let adj_matrices = vec![
+            Self::identity_matrix(nfa.states.len()),
+            Self::generate_adjacency_matrix(&nfa),
+        ];
         Self {
             nfa,
-            adj_matrices: vec![
-                Self::identity_matrix(nfa.states.len()),
-                Self::generate_adjacency_matrix(nfa),
-            ],
+            adj_matrices,
             final_states,
             state_stack: vec![],
             has_epsilon_moves,
             has_failed: false,
             last_word: None,
+            visited: 0,
+            word_bound: OnceCell::new(),
         }
     }
 }
@@ -321,13 +545,88 @@ impl<'a> From<&'a Nfa> for WordComponentIndices<'a> {
     }
 }
 
+/// An iterator visiting all words of a fixed length accepted by a NFA, returning them as vectors
+/// of indices for elements of the words, created by [WordComponentIndices::of_length]. The upper
+/// bound of its [size hint](Iterator::size_hint) is the number of accepting paths of that length
+/// (minus the words already visited).
+pub(crate) struct WordsOfLength<'a> {
+    inner: WordComponentIndices<'a>,
+    next: Option<Vec<usize>>,
+    bound: usize,
+}
+
+impl Iterator for WordsOfLength<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let word = self.next.take()?;
+        self.next = self.inner.next_word(word.clone());
+        self.bound = self.bound.saturating_sub(1);
+        Some(word)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            Some(_) => (1, Some(self.bound.max(1))),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+/// Counts the accepting paths of each length through the NFA, by counting the number of paths
+/// from the initial state to each state one symbol at a time. Every accepted word has at least one
+/// accepting path, so the number of paths of length `k` (the `k`:th item) is an upper bound of the
+/// number of accepted words of length `k`, which is exact if the NFA has no nondeterminism. The
+/// counts saturate at `usize::MAX` instead of overflowing.
+fn accepting_paths(nfa: &Nfa) -> impl Iterator<Item = usize> + '_ {
+    let mut initial = vec![0; nfa.states.len()];
+    initial[nfa.initial_state] = 1;
+    std::iter::successors(Some(initial), |paths: &Vec<usize>| {
+        let mut next = vec![0usize; paths.len()];
+        for (from, &count) in paths.iter().enumerate().filter(|(_, &count)| count > 0) {
+            for targets in &nfa.states[from].transitions {
+                // The same target may be listed more than once, but it is still just one path
+                for (idx, &to) in targets.iter().enumerate() {
+                    if !targets[..idx].contains(&to) {
+                        next[to] = next[to].saturating_add(count);
+                    }
+                }
+            }
+        }
+        Some(next)
+    })
+    .map(|paths| {
+        paths
+            .iter()
+            .zip(&nfa.states)
+            .filter(|(_, state)| state.accepting)
+            .fold(0usize, |acc, (&count, _)| acc.saturating_add(count))
+    })
+}
+
+/// An upper bound of the number of words accepted by the NFA, or `None` if it accepts infinitely
+/// many words. With `n` states, the NFA accepts infinitely many words if and only if it accepts a
+/// word with a length between `n` and `2n` (since any longer accepting path can be pumped down into
+/// that range), and otherwise every accepted word is shorter than `n`.
+fn word_bound(nfa: &Nfa) -> Option<usize> {
+    let n = nfa.states.len();
+    let mut total = 0usize;
+    for (len, paths) in accepting_paths(nfa).take(2 * n).enumerate() {
+        if len >= n && paths > 0 {
+            return None;
+        }
+        total = total.saturating_add(paths);
+    }
+    Some(total)
+}
+
 /// A type equal to `bool` in terms of bit pattern and size, but implementing num traits like
 /// zero (false) and one (true), add (false + false = false, _ = true), mul (true * true = true,
 /// _ = false). This is to be able to have a matrix of bools, since matrix multiplication requires
 /// those implementations.
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum NumBool {
+pub(crate) enum NumBool {
     False = 0,
     True = 1,
 }
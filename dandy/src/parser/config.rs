@@ -0,0 +1,124 @@
+/// The tokens marking initial and accepting states and the epsilon column in DFA and NFA tables, used by
+/// [dfa_with_config](super::dfa_with_config) and [nfa_with_config](super::nfa_with_config). The tokens are reserved,
+/// so none of them may be used as a name of a state or an element of the alphabet. The default is the tokens of the
+/// [table format](super#format-for-dfas-and-nfas), that is, `->`/`→` for initial states, `*` for accepting states
+/// and `ε`/`eps` for the epsilon column.
+///
+/// ```
+/// use dandy::nfa::Nfa;
+/// use dandy::parser::{self, ParserConfig};
+///
+/// let config = ParserConfig {
+///     initial_markers: vec![">".to_string()],
+///     accepting_markers: vec!["F".to_string()],
+///     epsilon_tokens: vec!["λ".to_string()],
+/// };
+/// let nfa: Nfa = parser::nfa_with_config("
+///        λ    a
+///   > s  {t}  {s}
+///   F t  {}   {t}", &config).unwrap().try_into().unwrap();
+/// assert!(nfa.accepts(&[]));
+/// // The configured tokens are reserved, while the default ones aren't
+/// assert!(parser::nfa_with_config("   a\n> s {F}", &config).is_err());
+/// assert!(parser::nfa_with_config("   ε\n> * {*}", &config).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserConfig {
+    /// The tokens marking the initial state
+    pub initial_markers: Vec<String>,
+    /// The tokens marking accepting states
+    pub accepting_markers: Vec<String>,
+    /// The tokens in the alphabet row denoting the column of epsilon moves (only used when parsing NFAs)
+    pub epsilon_tokens: Vec<String>,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        let strings = |tokens: &[&str]| tokens.iter().map(|token| token.to_string()).collect();
+        Self {
+            initial_markers: strings(&["->", "→"]),
+            accepting_markers: strings(&["*"]),
+            epsilon_tokens: strings(&["ε", "eps"]),
+        }
+    }
+}
+
+impl ParserConfig {
+    /// Checks if `text` is one of the tokens, which may not be used as names of states or elements of the alphabet
+    pub(crate) fn is_reserved(&self, text: &str) -> bool {
+        self.epsilon_tokens
+            .iter()
+            .chain(&self.initial_markers)
+            .chain(&self.accepting_markers)
+            .any(|token| token == text)
+    }
+}
+
+/// The tokens used when generating tables with [Dfa::to_table_with_style](crate::dfa::Dfa::to_table_with_style)
+/// and [Nfa::to_table_with_style](crate::nfa::Nfa::to_table_with_style). To parse the tables again, the tokens need
+/// to be among the tokens of the [ParserConfig] used. The default is the tokens used by
+/// [Dfa::to_table](crate::dfa::Dfa::to_table) and [Nfa::to_table](crate::nfa::Nfa::to_table), and
+/// [TableStyle::ascii] gives the tokens used by [Dfa::ascii_table](crate::dfa::Dfa::ascii_table) and
+/// [Nfa::ascii_table](crate::nfa::Nfa::ascii_table).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableStyle {
+    /// The token marking the initial state
+    pub initial_marker: String,
+    /// The token marking accepting states
+    pub accepting_marker: String,
+    /// The header of the column of epsilon moves (only used for NFAs)
+    pub epsilon_token: String,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            initial_marker: "→".to_string(),
+            accepting_marker: "*".to_string(),
+            epsilon_token: "ε".to_string(),
+        }
+    }
+}
+
+impl TableStyle {
+    /// The ascii-only style, with `->` marking the initial state and `eps` as the header of the epsilon column
+    pub fn ascii() -> Self {
+        Self {
+            initial_marker: "->".to_string(),
+            accepting_marker: "*".to_string(),
+            epsilon_token: "eps".to_string(),
+        }
+    }
+}
+
+/// Options for parsing regular expressions with [regex_with_options](super::regex_with_options). The default is to
+/// parse the [standard syntax](super#format-for-regular-expressions), with at most [DEFAULT_MAX_DEPTH] levels of
+/// nesting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegexOptions {
+    /// Whether to parse extended regular expressions, where `&` is the intersection of the languages of its operands
+    /// and a prefix `~` or `¬` is the complement of the language of its operand. These characters are then reserved,
+    /// and need to be escaped to be matched literally. `&` binds tighter than `|` but looser than sequencing, and
+    /// `~`/`¬` applies to the next element of a sequence (including its `*` or `+`), so `~ab&c|d` is
+    /// `(((~a)b)&c)|d`. See [Regex::to_dfa](crate::regex::Regex::to_dfa) for how they are compiled.
+    pub extended: bool,
+    /// The maximum number of nested parentheses (and, in extended regexes, nested complements). The parser and the
+    /// conversion to a NFA don't recurse, but displaying, comparing and dropping regexes do, so much deeper nesting
+    /// would risk overflowing the stack, which aborts the process (or kills the whole wasm module). Instead, parsing
+    /// fails with [ErrorKind::TooLarge](nom::error::ErrorKind::TooLarge) at the parenthesis exceeding the limit.
+    pub max_depth: usize,
+}
+
+/// The default [RegexOptions::max_depth], which is far deeper than any handwritten regex, while the recursive
+/// operations on regexes this deep still fit well within the 1 MiB stack of wasm and the 2 MiB stack of spawned
+/// threads.
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+
+impl Default for RegexOptions {
+    fn default() -> Self {
+        Self {
+            extended: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
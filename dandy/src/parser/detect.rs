@@ -0,0 +1,91 @@
+use crate::parser::fa::without_comment;
+
+/// The kind of input [detect] guesses a string to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Detected {
+    /// A DFA table, with a single state in every transition cell
+    Dfa,
+    /// A NFA table, with sets of states (such as `{s₀ s₁}`) in the transition cells
+    Nfa,
+    /// A regular expression
+    Regex,
+    /// Nothing recognizable, such as an empty file
+    Unknown,
+}
+
+/// The characters which are operators in regexes (except for `\`, which is also common in other text)
+const REGEX_OPERATORS: [char; 7] = ['∅', 'ε', '|', '*', '+', '(', ')'];
+
+/// Guesses whether the input is a DFA table, a NFA table or a regular expression, using cheap heuristics which
+/// don't require the input to be valid:
+/// * Input with (at least) two lines which aren't empty or comments has the structure of a table. It is a NFA table
+///   if some transition cell is a set (contains `{` or `}`) or if the alphabet contains `ε` or `eps`, and otherwise
+///   a DFA table
+/// * Input with a single line is a regular expression if it parses as one or contains regex operators (such as `|`
+///   or `*`)
+///
+/// Everything else, such as empty input, is [Detected::Unknown]. This is useful to give a better error message when
+/// the input is parsed as the wrong kind, see [suggest_type].
+///
+/// ```
+/// use dandy::parser::{self, Detected};
+///
+/// assert_eq!(parser::detect("   a  b\n→ s s s\n"), Detected::Dfa);
+/// assert_eq!(parser::detect("   a   b\n→ s {s} {}\n"), Detected::Nfa);
+/// assert_eq!(parser::detect("(ab)*c"), Detected::Regex);
+/// assert_eq!(parser::detect("# just a comment"), Detected::Unknown);
+/// ```
+pub fn detect(input: &str) -> Detected {
+    let lines = content_lines(input).collect::<Vec<_>>();
+    match lines.as_slice() {
+        [] => Detected::Unknown,
+        [line] => {
+            if super::regex_file(input).is_ok() || line.contains(REGEX_OPERATORS) {
+                Detected::Regex
+            } else {
+                Detected::Unknown
+            }
+        }
+        [head, states @ ..] => {
+            let epsilon = head
+                .split_whitespace()
+                .any(|elem| ["ε", "eps"].contains(&elem.trim_matches('"')));
+            if epsilon || states.iter().any(|line| line.contains(['{', '}'])) {
+                Detected::Nfa
+            } else {
+                Detected::Dfa
+            }
+        }
+    }
+}
+
+/// Suggests another kind of table to parse the input as, given the remaining input where parsing it as `attempted`
+/// (either [Detected::Dfa] or [Detected::Nfa]) failed, which is the input of the parse error. This gives
+/// [Detected::Nfa] if parsing a DFA failed on input containing sets of states, and [Detected::Dfa] if parsing a NFA
+/// failed on input with no sets of states at all, and `None` otherwise.
+///
+/// ```
+/// use dandy::parser::{self, Detected};
+///
+/// let nfa = "   a   b\n→ s {s} {}\n";
+/// let error = parser::dfa(nfa).unwrap_err();
+/// assert_eq!(parser::suggest_type(error.input, Detected::Dfa), Some(Detected::Nfa));
+/// ```
+pub fn suggest_type(error_input: &str, attempted: Detected) -> Option<Detected> {
+    let mut lines = content_lines(error_input).peekable();
+    lines.peek()?;
+    let sets = lines.any(|line| line.contains(['{', '}']));
+    match attempted {
+        Detected::Dfa if sets => Some(Detected::Nfa),
+        Detected::Nfa if !sets => Some(Detected::Dfa),
+        _ => None,
+    }
+}
+
+/// Gives the lines of the input without comments, except for the lines which are empty without the comment
+fn content_lines(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .lines()
+        .map(|line| without_comment(line).trim())
+        .filter(|line| !line.is_empty())
+}
@@ -0,0 +1,152 @@
+use crate::parser::fa::{
+    alphabet_elem, cell_separator, cell_text, leading_empty_cells, space_comment_line, state_name,
+    trailing_empty_cells,
+};
+use crate::parser::{NfaAlphabetEntry, ParsedNfa, ParsedNfaState, ParserConfig};
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::line_ending;
+use nom::combinator::{map, map_opt, opt};
+use nom::error::{Error, ErrorKind};
+use nom::multi::{many0, many1, separated_list1};
+use nom::sequence::{delimited, pair, preceded, terminated, tuple};
+use nom::IResult;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// A line of an edge list after the alphabet
+enum EdgeListLine<'a> {
+    State {
+        name: Cow<'a, str>,
+        initial: bool,
+        accepting: bool,
+    },
+    /// A transition, where `elem` is `None` for ε
+    Edge {
+        from: Cow<'a, str>,
+        elem: Option<Cow<'a, str>>,
+        to: Cow<'a, str>,
+    },
+}
+
+pub(crate) fn full_edge_list<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, ParsedNfa<'a>> {
+    let (input, alphabet) = preceded(
+        many0(space_comment_line),
+        terminated(|i| alphabet_line(i, config), line_ending),
+    )(input)?;
+    let (input, lines) = terminated(
+        preceded(
+            many0(space_comment_line),
+            separated_list1(many1(space_comment_line), |i| {
+                // The start of every line is kept, for locating errors in edges
+                edge_list_line(i, config).map(|(rest, line)| (rest, (i, line)))
+            }),
+        ),
+        many0(space_comment_line),
+    )(input)?;
+
+    // The first column is ε, like in a table with an ε column
+    let head = std::iter::once(NfaAlphabetEntry::Eps)
+        .chain(alphabet.iter().cloned().map(NfaAlphabetEntry::Element))
+        .collect::<Vec<_>>();
+    let mut states = vec![];
+    let mut state_idx = HashMap::new();
+    for (_, line) in &lines {
+        if let EdgeListLine::State {
+            name,
+            initial,
+            accepting,
+        } = line
+        {
+            state_idx.entry(name.clone()).or_insert(states.len());
+            states.push(ParsedNfaState {
+                name: name.clone(),
+                initial: *initial,
+                accepting: *accepting,
+                transitions: vec![vec![]; head.len()],
+            });
+        }
+    }
+    for (at, line) in lines {
+        if let EdgeListLine::Edge { from, elem, to } = line {
+            let column = match elem {
+                None => Some(0),
+                Some(elem) => alphabet.iter().position(|e| *e == elem).map(|idx| idx + 1),
+            };
+            // Edges from undeclared states or on elements not in the alphabet have no place in the table, so they
+            // are errors at the line of the edge
+            let (Some(&state), Some(column)) = (state_idx.get(&from), column) else {
+                return Err(nom::Err::Failure(Error::new(at, ErrorKind::Verify)));
+            };
+            states[state].transitions[column].push(to);
+        }
+    }
+
+    let nfa = ParsedNfa {
+        head,
+        states,
+        declared_alphabet: None,
+    };
+    Ok((input, nfa))
+}
+
+fn alphabet_line<'a>(input: &'a str, config: &ParserConfig) -> IResult<&'a str, Vec<Cow<'a, str>>> {
+    delimited(
+        pair(leading_empty_cells, tag("alphabet:")),
+        many0(preceded(cell_separator, |i| alphabet_elem(i, config))),
+        trailing_empty_cells,
+    )(input)
+}
+
+fn edge_list_line<'a>(input: &'a str, config: &ParserConfig) -> IResult<&'a str, EdgeListLine<'a>> {
+    delimited(
+        leading_empty_cells,
+        alt((
+            map(
+                tuple((
+                    |i| state_name(i, config),
+                    preceded(cell_separator, |i| arrow(i, config)),
+                    preceded(cell_separator, |i| state_name(i, config)),
+                )),
+                |(from, elem, to)| EdgeListLine::Edge { from, elem, to },
+            ),
+            map(
+                tuple((
+                    preceded(pair(tag("state"), cell_separator), |i| {
+                        state_name(i, config)
+                    }),
+                    opt(preceded(cell_separator, tag("initial"))),
+                    opt(preceded(cell_separator, tag("accepting"))),
+                )),
+                |(name, initial, accepting)| EdgeListLine::State {
+                    name,
+                    initial: initial.is_some(),
+                    accepting: accepting.is_some(),
+                },
+            ),
+        )),
+        trailing_empty_cells,
+    )(input)
+}
+
+/// Parses the arrow of an edge, such as `-a->`, giving the element, or `None` for one of the epsilon tokens (such as
+/// `-ε->`)
+fn arrow<'a>(input: &'a str, config: &ParserConfig) -> IResult<&'a str, Option<Cow<'a, str>>> {
+    map_opt(cell_text, |cell| {
+        let elem = cell.strip_prefix('-')?.strip_suffix("->")?;
+        if elem.is_empty() {
+            None
+        } else if config.epsilon_tokens.iter().any(|token| token == elem) {
+            Some(None)
+        } else {
+            let range = 1..cell.len() - 2;
+            Some(Some(match cell {
+                Cow::Borrowed(cell) => Cow::Borrowed(&cell[range]),
+                Cow::Owned(cell) => Cow::Owned(cell[range].to_string()),
+            }))
+        }
+    })(input)
+}
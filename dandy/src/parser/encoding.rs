@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// An encoding detected by [decode]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Encoding::Utf8 => write!(f, "UTF-8"),
+            Encoding::Utf8Bom => write!(f, "UTF-8 with byte order mark"),
+            Encoding::Utf16Le => write!(f, "UTF-16LE"),
+            Encoding::Utf16Be => write!(f, "UTF-16BE"),
+        }
+    }
+}
+
+/// An error that occurred when decoding a file with [decode], naming the encoding the file was detected to have
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    #[error("The file is encoded as {0}, but has an odd number of bytes")]
+    OddLength(Encoding),
+    #[error(
+        "The file is encoded as {encoding}, but contains an invalid character at byte {position}"
+    )]
+    InvalidUtf16 { encoding: Encoding, position: usize },
+    #[error(
+        "The file is not valid {encoding} (invalid byte at position {position}), it may be saved as Latin-1 or \
+        another legacy encoding. Save it as UTF-8 instead"
+    )]
+    InvalidUtf8 { encoding: Encoding, position: usize },
+}
+
+/// Decodes the bytes of a file to a string, so that it can be [parsed](crate::parser). The encoding is detected
+/// from the byte order mark (BOM) if there is one, which is then stripped. UTF-8 and UTF-16 (both little and big
+/// endian) are supported. Files without a BOM are decoded as UTF-8, unless they contain `NUL` bytes only at every
+/// other position, in which case they are decoded as UTF-16 (which is how ASCII text looks like in UTF-16).
+///
+/// Since the bytes of a file in a legacy encoding such as Latin-1 can't be told apart from each other, such files
+/// (if they aren't valid UTF-8) give an error rather than being decoded into garbled text.
+///
+/// ```
+/// use dandy::parser;
+/// use dandy::parser::Encoding;
+///
+/// let utf16: Vec<u8> = [0xFF, 0xFE]
+///     .into_iter()
+///     .chain("a\n-> * s s".encode_utf16().flat_map(u16::to_le_bytes))
+///     .collect();
+/// let (text, encoding) = parser::decode(&utf16).unwrap();
+/// assert_eq!(text, "a\n-> * s s");
+/// assert_eq!(encoding, Encoding::Utf16Le);
+/// assert!(parser::dfa(&text).is_ok());
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<(Cow<'_, str>, Encoding), DecodeError> {
+    let (encoding, rest) = if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        (Encoding::Utf8Bom, rest)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        (Encoding::Utf16Le, rest)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        (Encoding::Utf16Be, rest)
+    } else {
+        (guess_without_bom(bytes), bytes)
+    };
+    // The position of errors should refer to the original bytes, including the BOM
+    let offset = bytes.len() - rest.len();
+
+    let text = match encoding {
+        Encoding::Utf8 | Encoding::Utf8Bom => Cow::Borrowed(std::str::from_utf8(rest).map_err(
+            |e| DecodeError::InvalidUtf8 {
+                encoding,
+                position: offset + e.valid_up_to(),
+            },
+        )?),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if rest.len() % 2 == 1 {
+                return Err(DecodeError::OddLength(encoding));
+            }
+            let units = rest.chunks_exact(2).map(|pair| {
+                let pair = [pair[0], pair[1]];
+                if encoding == Encoding::Utf16Le {
+                    u16::from_le_bytes(pair)
+                } else {
+                    u16::from_be_bytes(pair)
+                }
+            });
+            let mut text = String::with_capacity(rest.len() / 2);
+            let mut position = offset;
+            for c in char::decode_utf16(units) {
+                let c = c.map_err(|_| DecodeError::InvalidUtf16 { encoding, position })?;
+                position += c.len_utf16() * 2;
+                text.push(c);
+            }
+            Cow::Owned(text)
+        }
+    };
+    Ok((text, encoding))
+}
+
+/// Guesses the encoding of a file without a BOM. Tables are mostly ASCII, which in UTF-16 has a `NUL` byte as every
+/// other byte, while `NUL` bytes never appear in UTF-8 text.
+fn guess_without_bom(bytes: &[u8]) -> Encoding {
+    if bytes.len() % 2 == 1 || !bytes.contains(&0) {
+        return Encoding::Utf8;
+    }
+    let nul_at = |parity: usize| bytes.iter().skip(parity).step_by(2).any(|&b| b == 0);
+    match (nul_at(0), nul_at(1)) {
+        (false, true) => Encoding::Utf16Le,
+        (true, false) => Encoding::Utf16Be,
+        _ => Encoding::Utf8,
+    }
+}
@@ -1,56 +1,95 @@
-use crate::parser::{NfaAlphabetEntry, ParsedDfa, ParsedDfaState, ParsedNfa, ParsedNfaState};
+use crate::parser::{
+    NfaAlphabetEntry, ParsedDfa, ParsedDfaState, ParsedNfa, ParsedNfaState, ParserConfig,
+};
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_till1};
-use nom::character::complete::{line_ending, not_line_ending, space0, space1};
-use nom::combinator::{eof, map, opt, recognize, value, verify};
-use nom::multi::{many0, many1, separated_list0, separated_list1};
+use nom::character::complete::{line_ending, not_line_ending, satisfy, space0, space1};
+use nom::combinator::{eof, map, opt, peek, recognize, value, verify};
+use nom::error::{Error, ErrorKind};
+use nom::multi::{many0, many1, many_m_n, separated_list0, separated_list1};
 use nom::sequence::{delimited, pair, preceded, terminated, tuple};
 use nom::IResult;
+use std::borrow::Cow;
 
-pub(crate) fn full_nfa(input: &str) -> IResult<&str, ParsedNfa> {
-    map(
-        delimited(
-            many0(space_comment_line),
-            pair(
-                terminated(nfa_head, line_ending),
-                preceded(
-                    many0(space_comment_line),
-                    separated_list1(many1(space_comment_line), nfa_line),
-                ),
-            ),
+pub(crate) fn full_nfa<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, ParsedNfa<'a>> {
+    let (input, head) = preceded(
+        many0(space_comment_line),
+        terminated(|i| nfa_head(i, config), line_ending),
+    )(input)?;
+    let min_transitions = usize::from(
+        !matches!(head.as_slice(), [NfaAlphabetEntry::Element(elem)] if CORNER_HEADERS.contains(&&**elem)),
+    );
+    let (input, states) = terminated(
+        preceded(
             many0(space_comment_line),
+            separated_list1(many1(space_comment_line), |i| {
+                nfa_line(i, config, min_transitions)
+            }),
         ),
-        |(head, states)| ParsedNfa { head, states },
-    )(input)
+        many0(space_comment_line),
+    )(input)?;
+    let nfa = ParsedNfa {
+        head: without_corner_header(
+            head,
+            |entry| matches!(entry, NfaAlphabetEntry::Element(elem) if CORNER_HEADERS.contains(&&**elem)),
+            states.iter().map(|state| state.transitions.len()),
+        ),
+        states,
+        declared_alphabet: None,
+    };
+    Ok((input, nfa))
 }
 
-fn nfa_head(input: &str) -> IResult<&str, Vec<NfaAlphabetEntry>> {
+fn nfa_head<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, Vec<NfaAlphabetEntry<'a>>> {
     delimited(
-        space0,
+        leading_empty_cells,
         separated_list1(
-            space1,
+            cell_separator,
             alt((
-                map(alphabet_elem, NfaAlphabetEntry::Element),
-                value(NfaAlphabetEntry::Eps, eps),
+                value(
+                    NfaAlphabetEntry::Eps,
+                    quoted(|i| token(i, &config.epsilon_tokens)),
+                ),
+                map(|i| alphabet_elem(i, config), NfaAlphabetEntry::Element),
             )),
         ),
-        space_comment,
+        trailing_empty_cells,
     )(input)
 }
 
-fn nfa_line(input: &str) -> IResult<&str, ParsedNfaState> {
+fn nfa_line<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+    min_transitions: usize,
+) -> IResult<&'a str, ParsedNfaState<'a>> {
     map(
         delimited(
-            space0,
+            leading_empty_cells,
             tuple((
                 opt(
-                    terminated(arrow, space1), // note: to be more lenient, change this and accepting to space0
+                    terminated(
+                        quoted(|i| token(i, &config.initial_markers)),
+                        cell_separator,
+                    ), // note: to be more lenient, change this and accepting to space0
+                ),
+                opt(terminated(
+                    quoted(|i| token(i, &config.accepting_markers)),
+                    cell_separator,
+                )),
+                |i| state_name(i, config),
+                many_m_n(
+                    min_transitions,
+                    usize::MAX,
+                    preceded(cell_separator, quoted(|i| state_set(i, config))),
                 ),
-                opt(terminated(accepting, space1)),
-                terminated(state_name, space1),
-                separated_list1(space1, state_set),
             )),
-            space_comment,
+            trailing_empty_cells,
         ),
         |(initial, accepting, name, transitions)| ParsedNfaState {
             name,
@@ -61,44 +100,95 @@ fn nfa_line(input: &str) -> IResult<&str, ParsedNfaState> {
     )(input)
 }
 
-pub(crate) fn full_dfa(input: &str) -> IResult<&str, ParsedDfa> {
-    map(
-        delimited(
-            many0(space_comment_line),
-            pair(
-                terminated(dfa_head, line_ending),
-                preceded(
-                    many0(space_comment_line),
-                    separated_list1(many1(space_comment_line), dfa_line),
-                ),
-            ),
+pub(crate) fn full_dfa<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, ParsedDfa<'a>> {
+    let (input, head) = preceded(
+        many0(space_comment_line),
+        terminated(|i| dfa_head(i, config), line_ending),
+    )(input)?;
+    let min_transitions =
+        usize::from(!matches!(head.as_slice(), [elem] if CORNER_HEADERS.contains(&&**elem)));
+    let (input, states) = terminated(
+        preceded(
             many0(space_comment_line),
+            separated_list1(many1(space_comment_line), |i| {
+                dfa_line(i, config, min_transitions)
+            }),
         ),
-        |(head, states)| ParsedDfa { head, states },
-    )(input)
+        many0(space_comment_line),
+    )(input)?;
+    let dfa = ParsedDfa {
+        head: without_corner_header(
+            head,
+            |elem| CORNER_HEADERS.contains(&&**elem),
+            states.iter().map(|state| state.transitions.len()),
+        ),
+        states,
+        declared_alphabet: None,
+    };
+    Ok((input, dfa))
+}
+
+/// Headers of the state column which spreadsheets may put in the top-left corner of a table. If the header row is
+/// only such a header, the alphabet is empty, so the rows of the states may have no transitions at all (while there
+/// otherwise has to be at least one).
+const CORNER_HEADERS: [&str; 4] = ["state", "states", "δ", "Q"];
+
+/// Removes the first element of the alphabet if it is a header of the state column (such as `state`) rather than an
+/// element of the alphabet. This is only the case if every state has one transition less than there are elements
+/// in the alphabet, so an alphabet which actually contains such an element is left as-is. A header consisting of
+/// only such a header is how tables of automata with an empty alphabet are written.
+fn without_corner_header<T>(
+    mut head: Vec<T>,
+    is_corner_header: impl Fn(&T) -> bool,
+    mut transition_counts: impl Iterator<Item = usize>,
+) -> Vec<T> {
+    if !head.is_empty()
+        && is_corner_header(&head[0])
+        && transition_counts.all(|count| count == head.len() - 1)
+    {
+        head.remove(0);
+    }
+    head
 }
 
-fn dfa_head(input: &str) -> IResult<&str, Vec<&str>> {
+fn dfa_head<'a>(input: &'a str, config: &ParserConfig) -> IResult<&'a str, Vec<Cow<'a, str>>> {
     delimited(
-        space0,
-        separated_list1(space1, alphabet_elem),
-        space_comment,
+        leading_empty_cells,
+        separated_list1(cell_separator, |i| alphabet_elem(i, config)),
+        trailing_empty_cells,
     )(input)
 }
 
-fn dfa_line(input: &str) -> IResult<&str, ParsedDfaState> {
+fn dfa_line<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+    min_transitions: usize,
+) -> IResult<&'a str, ParsedDfaState<'a>> {
     map(
         delimited(
-            space0,
+            leading_empty_cells,
             tuple((
                 opt(
-                    terminated(arrow, space1), // note: to be more lenient, change this and accepting to space0
+                    terminated(
+                        quoted(|i| token(i, &config.initial_markers)),
+                        cell_separator,
+                    ), // note: to be more lenient, change this and accepting to space0
+                ),
+                opt(terminated(
+                    quoted(|i| token(i, &config.accepting_markers)),
+                    cell_separator,
+                )),
+                |i| state_name(i, config),
+                many_m_n(
+                    min_transitions,
+                    usize::MAX,
+                    preceded(cell_separator, |i| state_name(i, config)),
                 ),
-                opt(terminated(accepting, space1)),
-                terminated(state_name, space1),
-                separated_list1(space1, state_name),
             )),
-            space_comment,
+            trailing_empty_cells,
         ),
         |(initial, accepting, name, transitions)| ParsedDfaState {
             name,
@@ -109,41 +199,141 @@ fn dfa_line(input: &str) -> IResult<&str, ParsedDfaState> {
     )(input)
 }
 
-fn eps(input: &str) -> IResult<&str, ()> {
-    map(alt((tag("ε"), tag("eps"))), |_| ())(input)
+/// Parses one of the tokens (such as the markers of initial states), trying them in order
+fn token<'a>(input: &'a str, tokens: &[String]) -> IResult<&'a str, ()> {
+    for token in tokens {
+        if let Some(rest) = input.strip_prefix(token.as_str()) {
+            return Ok((rest, ()));
+        }
+    }
+    Err(nom::Err::Error(Error::new(input, ErrorKind::Tag)))
 }
 
-fn alphabet_elem(input: &str) -> IResult<&str, &str> {
-    verify(
-        take_till1(|c: char| c.is_whitespace() || "#{}".contains(c)),
-        |elem| !["ε", "eps", "→", "->", "*"].contains(&elem),
-    )(input)
+pub(super) fn alphabet_elem<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, Cow<'a, str>> {
+    verify(cell_text, |elem: &Cow<str>| !config.is_reserved(elem))(input)
 }
 
-fn state_set(input: &str) -> IResult<&str, Vec<&str>> {
+fn state_set<'a>(input: &'a str, config: &ParserConfig) -> IResult<&'a str, Vec<Cow<'a, str>>> {
     delimited(
         tag("{"),
-        delimited(space0, separated_list0(space1, state_name), space0),
+        delimited(
+            space0,
+            separated_list0(space1, |i| state_name(i, config)),
+            space0,
+        ),
         tag("}"),
     )(input)
 }
 
-fn state_name(input: &str) -> IResult<&str, &str> {
-    verify(
-        take_till1(|c: char| c.is_whitespace() || "#{}".contains(c)),
-        |elem| !["ε", "eps", "→", "->", "*"].contains(&elem),
+pub(super) fn state_name<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> IResult<&'a str, Cow<'a, str>> {
+    verify(cell_text, |elem: &Cow<str>| !config.is_reserved(elem))(input)
+}
+
+/// Parses the text of a cell. If it is surrounded by double quotes (as spreadsheets may export cells), the quotes
+/// are stripped, see [quoted_cell_text]. An empty quoted cell (`""`) is no cell at all, so it isn't parsed. Other
+/// cells are read as they are, even if they start with a double quote.
+pub(super) fn cell_text(input: &str) -> IResult<&str, Cow<'_, str>> {
+    alt((
+        quoted_cell_text,
+        map(
+            verify(take_till1(ends_cell), |text: &str| text != "\"\""),
+            Cow::Borrowed,
+        ),
+    ))(input)
+}
+
+/// Parses the text of a cell surrounded by double quotes, which may contain whitespace, `#`, `{` and `}` (but not
+/// line breaks), and double quotes written twice. This is how [crate::table::quote_cell] writes names and elements
+/// containing such characters or starting with a double quote. The text is only copied if it contains double quotes,
+/// which have to be unescaped.
+fn quoted_cell_text(input: &str) -> IResult<&str, Cow<'_, str>> {
+    map(
+        terminated(
+            delimited(
+                tag("\""),
+                recognize(many1(alt((
+                    take_till1(|c: char| c == '"' || c == '\n' || c == '\r'),
+                    tag("\"\""),
+                )))),
+                tag("\""),
+            ),
+            peek(alt((eof, recognize(satisfy(ends_cell))))),
+        ),
+        |text: &str| {
+            if text.contains('"') {
+                Cow::Owned(text.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(text)
+            }
+        },
     )(input)
 }
 
-fn accepting(input: &str) -> IResult<&str, ()> {
-    value((), tag("*"))(input)
+/// Removes the comment from a line of a table, if it has one. The line is split into cells the same way as when
+/// parsing the table, so a `#` in a quoted cell doesn't start a comment.
+pub(super) fn without_comment(line: &str) -> &str {
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with('#') {
+            break;
+        }
+        rest = match cell_text(rest) {
+            Ok((rest, _)) => rest,
+            // The braces of sets of states and empty quoted cells aren't cell text
+            Err(_) => &rest[rest.chars().next().map_or(0, char::len_utf8)..],
+        };
+    }
+    &line[..line.len() - rest.len()]
 }
 
-fn arrow(input: &str) -> IResult<&str, ()> {
-    map(alt((tag("->"), tag("→"))), |_| ())(input)
+/// Checks if a character ends the text of an unquoted cell
+fn ends_cell(c: char) -> bool {
+    c.is_whitespace() || "#{}".contains(c)
+}
+
+/// Parses a cell with `parser`, which may optionally be surrounded by double quotes (as spreadsheets may export
+/// cells)
+fn quoted<'a, O>(
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    move |input: &'a str| {
+        if let Some(rest) = input.strip_prefix('"') {
+            if let Ok((rest, output)) = terminated(&mut parser, tag("\""))(rest) {
+                return Ok((rest, output));
+            }
+        }
+        parser(input)
+    }
+}
+
+// Spreadsheets may export empty cells as `""`, which are skipped just as whitespace
+
+/// Parses whitespace between two cells, including any empty quoted cells in between
+pub(super) fn cell_separator(input: &str) -> IResult<&str, ()> {
+    value((), pair(space1, many0(pair(tag("\"\""), space1))))(input)
+}
+
+/// Parses the start of a line, which may contain whitespace and empty quoted cells
+pub(super) fn leading_empty_cells(input: &str) -> IResult<&str, ()> {
+    value((), pair(space0, many0(pair(tag("\"\""), space1))))(input)
+}
+
+/// Parses the end of a line, which may contain empty quoted cells before trailing whitespace and comments
+pub(super) fn trailing_empty_cells(input: &str) -> IResult<&str, ()> {
+    value(
+        (),
+        pair(many0(preceded(space1, tag("\"\""))), space_comment),
+    )(input)
 }
 
-fn space_comment_line(input: &str) -> IResult<&str, ()> {
+pub(super) fn space_comment_line(input: &str) -> IResult<&str, ()> {
     // We need to allow a space-only or comment-only line to end with either
     // a line ending or eof, but we need to consume *something* otherwise
     // many0(space_comment_line) will be in an endless loop at eof
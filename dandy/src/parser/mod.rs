@@ -43,6 +43,54 @@
 //! Any lines containing only whitespace are ignored, and if `#` appears on any line, that character and all subsequent
 //! characters on that line will be ignored (as a comment).
 //!
+//! To accept tables exported from spreadsheets (which are usually tab-separated), the parser is lenient with cells:
+//! - Any cell may be surrounded by double quotes (such as `"s₀"` or `"{s₁ s₂}"`), which are stripped
+//! - Quoted cells may contain whitespace, `#`, `{` and `}`, and double quotes written twice (such as `"a""b"` for
+//!   `a"b`), which is how [Dfa::to_table](crate::dfa::Dfa::to_table) and [Nfa::to_table](crate::nfa::Nfa::to_table)
+//!   write names and elements containing those characters or starting with a double quote. A cell starting with a
+//!   double quote which isn't such a quoted cell (such as `"a`) is read as it is. Elements of the alphabet may thus
+//!   contain whitespace, but they can't be empty or start or end with whitespace (such as `" a"`), since those are
+//!   too easily confused with other elements
+//! - Empty quoted cells (`""`) are ignored, just as whitespace, so empty columns are allowed anywhere
+//! - The top-left corner of the table may contain a header of the state column, which is one of `state`, `states`,
+//!   `δ` or `Q`. It is only treated as such if every state has one transition less than the number of elements in
+//!   the alphabet row, otherwise it is an element of the alphabet. Tables of automata with an empty alphabet (such
+//!   as the DFA of the regex `ε`) have no transition columns, so their alphabet row is only such a header
+//! - Lines may end with either `\n` or `\r\n`
+//!
+//! The tokens marking initial and accepting states and the epsilon column can be changed (such as to `>`, `F` and
+//! `λ`, as used by some courses) by parsing with [dfa_with_config] and [nfa_with_config] and a [ParserConfig].
+//! Tables using such tokens can be generated with [TableStyle].
+//!
+//! If it isn't known whether a file contains a DFA, a NFA or a regular expression, [detect] guesses it, and
+//! [suggest_type] helps explaining parse errors caused by parsing a NFA table as a DFA table or vice versa.
+//!
+//! The parsers take `&str`s, so files in other encodings than UTF-8 need to be decoded first. [decode] does that
+//! for UTF-8 and UTF-16 files, with or without a byte order mark.
+//!
+//! ### Edge lists
+//! Sparse NFAs, where most cells of the table are `{}`, may instead be written as an edge list, which is parsed by
+//! [nfa_edges] and generated by [Nfa::to_edge_list](crate::nfa::Nfa::to_edge_list) and
+//! [Dfa::to_edge_list](crate::dfa::Dfa::to_edge_list). It consists of:
+//! - One line containing `alphabet:` followed by the whitespace-separated elements of the alphabet
+//! - One line for each state, containing `state`, the name of the state, and then optionally `initial` and
+//!   optionally `accepting` (in that order)
+//! - One line for each transition, containing the name of the state it is from, an arrow containing the element (such
+//!   as `-a->`, or `-ε->` or `-eps->` for epsilon moves) and the name of the state it is to
+//!
+//! The lines of the states and transitions may come in any order, but the transitions must be from declared states and
+//! on elements of the alphabet. Comments, blank lines and quoted cells are allowed as in tables. This is the NFA with
+//! epsilon moves accepting `a*` or `b`:
+//! ```text
+//! alphabet: a b
+//! state s₀ initial
+//! state s₁ accepting
+//! state s₂ accepting
+//! s₀ -ε-> s₁
+//! s₀ -b-> s₂
+//! s₁ -a-> s₁  # loop
+//! ```
+//!
 //! ## Format for Regular Expressions
 //! There are eight reserved characters: `∅`, `ε`, `|`, `*`, `+`, `\`, `(` and `)`. Symbols distinct from them
 //! may be written as-is. To denote one of the reserved characters, you may escape it with a backslash `\`. Multiple
@@ -55,52 +103,118 @@
 //!
 //! Leading and trailing whitespace is ignored, but not whitespace within the expression itself.
 //!
+//! ### Extended regular expressions
+//! With [RegexOptions::extended], [regex_with_options] also parses the intersection operator `&` (binding tighter
+//! than `|` but looser than sequencing) and the prefix complement operators `~` and `¬`. `&`, `~` and `¬` are then
+//! reserved, and have to be escaped to be matched literally. Such regexes are compiled with
+//! [Regex::to_dfa], where the complement is taken with respect to the graphemes appearing in the whole regex. Note
+//! that whitespace is significant here as well, so there should be no spaces around `&`:
+//! ```
+//! use dandy::parser::{self, RegexOptions};
+//!
+//! let options = RegexOptions { extended: true, ..RegexOptions::default() };
+//! let contains_b = parser::regex_with_options("~(a*)&(a|b)*", &options).unwrap().to_dfa();
+//! assert!(contains_b.accepts_graphemes("aab"));
+//! assert!(!contains_b.accepts_graphemes("aaa"));
+//! // Without the option, & and ~ are matched literally
+//! assert!(parser::regex("~a&b").unwrap().to_nfa().accepts_graphemes("~a&b"));
+//! ```
+//!
+//! ## Alphabet pragma
+//! Files may start with a line declaring the alphabet, such as `#! alphabet a b c`, which is read by [dfa_file],
+//! [nfa_file] and [regex_file] (while [dfa], [nfa] and [regex] don't treat it specially). For regexes, the
+//! declared alphabet is the alphabet the regex is over, which may contain elements not appearing in the regex itself
+//! (see [Regex::to_nfa_with_alphabet]). For DFAs and NFAs, the pragma is an assertion that the table has exactly the
+//! declared alphabet (not considering ordering), and converting the parsed automaton fails otherwise. This catches
+//! missing or misspelled columns early:
+//! ```
+//! use dandy::dfa::{Dfa, parse::DfaParseError};
+//! use dandy::parser;
+//!
+//! let (regex, alphabet) = parser::regex_file("#! alphabet a b c\n(ab)+").unwrap();
+//! let nfa = regex.to_nfa_with_alphabet(&["a", "b", "c"]).unwrap();
+//! assert_eq!(alphabet, Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]));
+//! assert_eq!(nfa.alphabet().len(), 3);
+//!
+//! let dfa = parser::dfa_file("#! alphabet a b\n   a\n→ * s s").unwrap();
+//! let result: Result<Dfa, _> = dfa.try_into();
+//! assert_eq!(
+//!     result.unwrap_err(),
+//!     DfaParseError::AlphabetPragmaMismatch(vec!["a".into(), "b".into()], vec!["a".into()])
+//! );
+//! ```
+//!
 
+mod config;
+mod detect;
+mod edges;
+mod encoding;
 mod fa;
+mod owned;
+mod pragma;
 mod regex;
 
 use crate::regex::Regex;
-use nom::{combinator::all_consuming, error::Error, Finish};
+pub use config::{ParserConfig, RegexOptions, TableStyle, DEFAULT_MAX_DEPTH};
+pub use detect::{detect, suggest_type, Detected};
+pub use encoding::{decode, DecodeError, Encoding};
+use nom::combinator::{all_consuming, opt};
+use nom::{error::Error, Finish};
+pub use owned::{
+    ParsedDfaOwned, ParsedDfaStateOwned, ParsedEditError, ParsedNfaOwned, ParsedNfaStateOwned,
+};
+use std::borrow::Cow;
 
 #[derive(Debug)]
 pub struct ParsedNfa<'a> {
     pub(crate) head: Vec<NfaAlphabetEntry<'a>>,
     pub(crate) states: Vec<ParsedNfaState<'a>>,
+    pub(crate) declared_alphabet: Option<Vec<&'a str>>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum NfaAlphabetEntry<'a> {
-    Element(&'a str),
+    Element(Cow<'a, str>),
     Eps,
 }
 
 #[derive(Debug)]
 pub struct ParsedNfaState<'a> {
-    pub(crate) name: &'a str,
+    pub(crate) name: Cow<'a, str>,
     pub(crate) initial: bool,
     pub(crate) accepting: bool,
-    pub(crate) transitions: Vec<Vec<&'a str>>,
+    pub(crate) transitions: Vec<Vec<Cow<'a, str>>>,
 }
 
 #[derive(Debug)]
 pub struct ParsedDfa<'a> {
-    pub(crate) head: Vec<&'a str>,
+    pub(crate) head: Vec<Cow<'a, str>>,
     pub(crate) states: Vec<ParsedDfaState<'a>>,
+    pub(crate) declared_alphabet: Option<Vec<&'a str>>,
 }
 
 #[derive(Debug)]
 pub struct ParsedDfaState<'a> {
-    pub(crate) name: &'a str,
+    pub(crate) name: Cow<'a, str>,
     pub(crate) initial: bool,
     pub(crate) accepting: bool,
-    pub(crate) transitions: Vec<&'a str>,
+    pub(crate) transitions: Vec<Cow<'a, str>>,
 }
 
 /// Parses a DFA according to the format above. The whole string must be parsable, otherwise this function errors.
 /// Note that the result is a [ParsedDfa], which is not guaranteed to be a valid [crate::dfa::Dfa]. Use
 /// [TryInto::try_into] to convert a [ParsedDfa] to a [crate::dfa::Dfa].
-pub fn dfa(input: &str) -> Result<ParsedDfa, Error<&str>> {
-    all_consuming(fa::full_dfa)(input)
+pub fn dfa(input: &str) -> Result<ParsedDfa<'_>, Error<&str>> {
+    dfa_with_config(input, &ParserConfig::default())
+}
+
+/// Parses a DFA like [dfa], but with the markers of initial and accepting states given by the [ParserConfig]
+/// instead of the default ones
+pub fn dfa_with_config<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> Result<ParsedDfa<'a>, Error<&'a str>> {
+    all_consuming(|i| fa::full_dfa(i, config))(input)
         .finish()
         .map(|(_, dfa)| dfa)
 }
@@ -108,8 +222,27 @@ pub fn dfa(input: &str) -> Result<ParsedDfa, Error<&str>> {
 /// Parses a NFA according to the format above. The whole string must be parsable, otherwise this function errors.
 /// Note that the result is a [ParsedNfa], which is not guaranteed to be a valid [crate::nfa::Nfa]. Use
 /// [TryInto::try_into] to convert a [ParsedNfa] to a [crate::nfa::Nfa].
-pub fn nfa(input: &str) -> Result<ParsedNfa, Error<&str>> {
-    all_consuming(fa::full_nfa)(input)
+pub fn nfa(input: &str) -> Result<ParsedNfa<'_>, Error<&str>> {
+    nfa_with_config(input, &ParserConfig::default())
+}
+
+/// Parses a NFA like [nfa], but with the markers of initial and accepting states and the tokens of the epsilon
+/// column given by the [ParserConfig] instead of the default ones
+pub fn nfa_with_config<'a>(
+    input: &'a str,
+    config: &ParserConfig,
+) -> Result<ParsedNfa<'a>, Error<&'a str>> {
+    all_consuming(|i| fa::full_nfa(i, config))(input)
+        .finish()
+        .map(|(_, nfa)| nfa)
+}
+
+/// Parses a NFA from an [edge list](self#edge-lists), such as generated by
+/// [Nfa::to_edge_list](crate::nfa::Nfa::to_edge_list) and [Dfa::to_edge_list](crate::dfa::Dfa::to_edge_list). The
+/// whole string must be parsable, otherwise this function errors. Like [nfa], the result is a [ParsedNfa], which is
+/// converted to a [crate::nfa::Nfa] by [TryInto::try_into].
+pub fn nfa_edges(input: &str) -> Result<ParsedNfa<'_>, Error<&str>> {
+    all_consuming(|i| edges::full_edge_list(i, &ParserConfig::default()))(input)
         .finish()
         .map(|(_, nfa)| nfa)
 }
@@ -117,7 +250,44 @@ pub fn nfa(input: &str) -> Result<ParsedNfa, Error<&str>> {
 /// Parses a regular expression according to the format above. The whole string must be parsable, otherwise this
 /// function errors. All regexes that are successfully parsed by this function is guaranteed to be valid regexes.
 pub fn regex(input: &str) -> Result<Regex, Error<&str>> {
-    all_consuming(regex::full_regex)(input)
+    regex_with_options(input, &RegexOptions::default())
+}
+
+/// Parses a regular expression like [regex], but with the given [RegexOptions], such as to parse
+/// [extended regular expressions](self#extended-regular-expressions)
+pub fn regex_with_options<'a>(
+    input: &'a str,
+    options: &RegexOptions,
+) -> Result<Regex, Error<&'a str>> {
+    all_consuming(|i| regex::full_regex(i, options))(input)
         .finish()
         .map(|(_, regex)| regex)
 }
+
+/// Parses a DFA like [dfa], but if the first line is an [alphabet pragma](self#alphabet-pragma), the declared
+/// alphabet is checked against the alphabet of the table when converting the [ParsedDfa] to a [crate::dfa::Dfa].
+pub fn dfa_file(input: &str) -> Result<ParsedDfa<'_>, Error<&str>> {
+    let (input, declared_alphabet) = opt(pragma::alphabet_pragma)(input).finish()?;
+    let mut dfa = dfa(input)?;
+    dfa.declared_alphabet = declared_alphabet;
+    Ok(dfa)
+}
+
+/// Parses a NFA like [nfa], but if the first line is an [alphabet pragma](self#alphabet-pragma), the declared
+/// alphabet is checked against the alphabet of the table when converting the [ParsedNfa] to a [crate::nfa::Nfa].
+pub fn nfa_file(input: &str) -> Result<ParsedNfa<'_>, Error<&str>> {
+    let (input, declared_alphabet) = opt(pragma::alphabet_pragma)(input).finish()?;
+    let mut nfa = nfa(input)?;
+    nfa.declared_alphabet = declared_alphabet;
+    Ok(nfa)
+}
+
+/// Parses a regular expression like [regex], but if the first line is an [alphabet pragma](self#alphabet-pragma),
+/// the declared alphabet is returned as well, to be used with [Regex::to_nfa_with_alphabet].
+pub fn regex_file(input: &str) -> Result<(Regex, Option<Vec<String>>), Error<&str>> {
+    let (input, declared_alphabet) = opt(pragma::alphabet_pragma)(input).finish()?;
+    let regex = regex(input)?;
+    let declared_alphabet =
+        declared_alphabet.map(|elems| elems.into_iter().map(String::from).collect());
+    Ok((regex, declared_alphabet))
+}
@@ -0,0 +1,368 @@
+use crate::parser::{NfaAlphabetEntry, ParsedDfa, ParsedDfaState, ParsedNfa, ParsedNfaState};
+use std::borrow::Cow;
+use thiserror::Error;
+
+/// An owned version of [ParsedDfa], which doesn't borrow from the parsed string. Unlike a [ParsedDfa], it can be
+/// edited before it is validated by converting it to a [Dfa](crate::dfa::Dfa) with [TryInto::try_into], which checks
+/// the same things as converting a [ParsedDfa]. Obtained by [From::from] or by
+/// [Dfa::to_parsed](crate::dfa::Dfa::to_parsed).
+///
+/// ```
+/// use dandy::dfa::Dfa;
+/// use dandy::parser::ParsedDfaOwned;
+///
+/// let parsed: ParsedDfaOwned = dandy::parser::dfa("
+///        a  b
+///   → s0 s1 s0
+///   * s1 s1 s2
+/// ").unwrap().into();
+/// // s2 doesn't exist, which is fixed before validating the DFA
+/// let mut fixed = parsed.clone();
+/// fixed.set_transition("s1", "b", "s0").unwrap();
+/// assert!(Dfa::try_from(parsed).is_err());
+/// let dfa: Dfa = fixed.try_into().unwrap();
+/// assert!(dfa.accepts(&["a", "b", "a"]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedDfaOwned {
+    pub(crate) head: Vec<String>,
+    pub(crate) states: Vec<ParsedDfaStateOwned>,
+    pub(crate) declared_alphabet: Option<Vec<String>>,
+}
+
+/// A state of a [ParsedDfaOwned], whose transitions are the names of their target states in the order of the alphabet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedDfaStateOwned {
+    pub(crate) name: String,
+    pub(crate) initial: bool,
+    pub(crate) accepting: bool,
+    pub(crate) transitions: Vec<String>,
+}
+
+/// An owned version of [ParsedNfa], which can be edited before it is validated, just like [ParsedDfaOwned]. The
+/// columns of the table are given by [ParsedNfaOwned::head], where `None` is the ε column. Obtained by [From::from]
+/// or by [Nfa::to_parsed](crate::nfa::Nfa::to_parsed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedNfaOwned {
+    pub(crate) head: Vec<Option<String>>,
+    pub(crate) states: Vec<ParsedNfaStateOwned>,
+    pub(crate) declared_alphabet: Option<Vec<String>>,
+}
+
+/// A state of a [ParsedNfaOwned], whose transitions are the names of their target states in the order of the columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedNfaStateOwned {
+    pub(crate) name: String,
+    pub(crate) initial: bool,
+    pub(crate) accepting: bool,
+    pub(crate) transitions: Vec<Vec<String>>,
+}
+
+/// The reasons why editing a [ParsedDfaOwned] or [ParsedNfaOwned] may fail. Other mistakes, such as transitions to
+/// states that don't exist, are instead found when the automaton is validated.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ParsedEditError {
+    #[error("State '{0}' does not exist")]
+    UnknownState(String),
+    #[error("'{0}' is not in the alphabet")]
+    UnknownSymbol(String),
+    #[error("State '{0}' defined multiple times")]
+    DuplicateState(String),
+}
+
+impl From<ParsedDfa<'_>> for ParsedDfaOwned {
+    fn from(value: ParsedDfa<'_>) -> Self {
+        Self {
+            head: value.head.iter().map(|elem| elem.to_string()).collect(),
+            states: value
+                .states
+                .into_iter()
+                .map(|state| ParsedDfaStateOwned {
+                    name: state.name.to_string(),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state.transitions.iter().map(|to| to.to_string()).collect(),
+                })
+                .collect(),
+            declared_alphabet: owned_alphabet(value.declared_alphabet),
+        }
+    }
+}
+
+impl From<ParsedNfa<'_>> for ParsedNfaOwned {
+    fn from(value: ParsedNfa<'_>) -> Self {
+        Self {
+            head: value
+                .head
+                .iter()
+                .map(|entry| match entry {
+                    NfaAlphabetEntry::Element(elem) => Some(elem.to_string()),
+                    NfaAlphabetEntry::Eps => None,
+                })
+                .collect(),
+            states: value
+                .states
+                .into_iter()
+                .map(|state| ParsedNfaStateOwned {
+                    name: state.name.to_string(),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state
+                        .transitions
+                        .iter()
+                        .map(|set| set.iter().map(|to| to.to_string()).collect())
+                        .collect(),
+                })
+                .collect(),
+            declared_alphabet: owned_alphabet(value.declared_alphabet),
+        }
+    }
+}
+
+fn owned_alphabet(alphabet: Option<Vec<&str>>) -> Option<Vec<String>> {
+    alphabet.map(|alphabet| alphabet.iter().map(|elem| elem.to_string()).collect())
+}
+
+impl ParsedDfaStateOwned {
+    /// Gets the name of this state
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Checks if this state is marked as initial
+    pub fn is_initial(&self) -> bool {
+        self.initial
+    }
+
+    /// Checks if this state is marked as accepting
+    pub fn is_accepting(&self) -> bool {
+        self.accepting
+    }
+
+    /// Gets the names of the targets of the transitions of this state, in the order of the alphabet
+    pub fn transitions(&self) -> &[String] {
+        &self.transitions
+    }
+}
+
+impl ParsedNfaStateOwned {
+    /// Gets the name of this state
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Checks if this state is marked as initial
+    pub fn is_initial(&self) -> bool {
+        self.initial
+    }
+
+    /// Checks if this state is marked as accepting
+    pub fn is_accepting(&self) -> bool {
+        self.accepting
+    }
+
+    /// Gets the names of the targets of the transitions of this state, in the order of the columns
+    pub fn transitions(&self) -> &[Vec<String>] {
+        &self.transitions
+    }
+}
+
+impl ParsedDfaOwned {
+    /// Gets the alphabet, in the order of the columns of the table
+    pub fn alphabet(&self) -> &[String] {
+        &self.head
+    }
+
+    /// Gets the states, in the order of the rows of the table
+    pub fn states(&self) -> &[ParsedDfaStateOwned] {
+        &self.states
+    }
+
+    /// Sets the target of the transition from the state upon the element of the alphabet. The target doesn't need
+    /// to exist until the DFA is validated.
+    pub fn set_transition(
+        &mut self,
+        state: &str,
+        symbol: &str,
+        target: &str,
+    ) -> Result<(), ParsedEditError> {
+        let column = column(
+            self.head.iter().map(|elem| Some(elem.as_str())),
+            Some(symbol),
+        )?;
+        let state = self.state_mut(state)?;
+        // States with missing transitions are only reported when validating
+        if let Some(to) = state.transitions.get_mut(column) {
+            *to = target.to_string();
+        }
+        Ok(())
+    }
+
+    /// Marks the state as accepting or not accepting
+    pub fn set_accepting(&mut self, state: &str, accepting: bool) -> Result<(), ParsedEditError> {
+        self.state_mut(state)?.accepting = accepting;
+        Ok(())
+    }
+
+    /// Adds a non-initial state last in the table, with the names of the targets of its transitions in the order of
+    /// the alphabet
+    pub fn add_state(
+        &mut self,
+        name: &str,
+        accepting: bool,
+        transitions: &[&str],
+    ) -> Result<(), ParsedEditError> {
+        if self.states.iter().any(|state| state.name == name) {
+            return Err(ParsedEditError::DuplicateState(name.to_string()));
+        }
+        self.states.push(ParsedDfaStateOwned {
+            name: name.to_string(),
+            initial: false,
+            accepting,
+            transitions: transitions.iter().map(|to| to.to_string()).collect(),
+        });
+        Ok(())
+    }
+
+    fn state_mut(&mut self, name: &str) -> Result<&mut ParsedDfaStateOwned, ParsedEditError> {
+        self.states
+            .iter_mut()
+            .find(|state| state.name == name)
+            .ok_or_else(|| ParsedEditError::UnknownState(name.to_string()))
+    }
+
+    /// Borrows this DFA as a [ParsedDfa], to be validated like a parsed table
+    pub(crate) fn as_parsed(&self) -> ParsedDfa<'_> {
+        ParsedDfa {
+            head: self
+                .head
+                .iter()
+                .map(|elem| Cow::Borrowed(elem.as_str()))
+                .collect(),
+            states: self
+                .states
+                .iter()
+                .map(|state| ParsedDfaState {
+                    name: Cow::Borrowed(&state.name),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state
+                        .transitions
+                        .iter()
+                        .map(|to| Cow::Borrowed(to.as_str()))
+                        .collect(),
+                })
+                .collect(),
+            declared_alphabet: borrowed_alphabet(&self.declared_alphabet),
+        }
+    }
+}
+
+impl ParsedNfaOwned {
+    /// Gets the columns of the table, where `None` is the ε column
+    pub fn head(&self) -> &[Option<String>] {
+        &self.head
+    }
+
+    /// Gets the states, in the order of the rows of the table
+    pub fn states(&self) -> &[ParsedNfaStateOwned] {
+        &self.states
+    }
+
+    /// Sets the targets of the transitions from the state upon the element of the alphabet, or upon ε if `symbol`
+    /// is `None`. The targets don't need to exist until the NFA is validated.
+    pub fn set_transition(
+        &mut self,
+        state: &str,
+        symbol: Option<&str>,
+        targets: &[&str],
+    ) -> Result<(), ParsedEditError> {
+        let column = column(self.head.iter().map(Option::as_deref), symbol)?;
+        let state = self.state_mut(state)?;
+        // States with missing transitions are only reported when validating
+        if let Some(to) = state.transitions.get_mut(column) {
+            *to = targets.iter().map(|to| to.to_string()).collect();
+        }
+        Ok(())
+    }
+
+    /// Marks the state as accepting or not accepting
+    pub fn set_accepting(&mut self, state: &str, accepting: bool) -> Result<(), ParsedEditError> {
+        self.state_mut(state)?.accepting = accepting;
+        Ok(())
+    }
+
+    /// Adds a non-initial state last in the table, with the names of the targets of its transitions in the order of
+    /// the columns
+    pub fn add_state(
+        &mut self,
+        name: &str,
+        accepting: bool,
+        transitions: &[&[&str]],
+    ) -> Result<(), ParsedEditError> {
+        if self.states.iter().any(|state| state.name == name) {
+            return Err(ParsedEditError::DuplicateState(name.to_string()));
+        }
+        self.states.push(ParsedNfaStateOwned {
+            name: name.to_string(),
+            initial: false,
+            accepting,
+            transitions: transitions
+                .iter()
+                .map(|set| set.iter().map(|to| to.to_string()).collect())
+                .collect(),
+        });
+        Ok(())
+    }
+
+    fn state_mut(&mut self, name: &str) -> Result<&mut ParsedNfaStateOwned, ParsedEditError> {
+        self.states
+            .iter_mut()
+            .find(|state| state.name == name)
+            .ok_or_else(|| ParsedEditError::UnknownState(name.to_string()))
+    }
+
+    /// Borrows this NFA as a [ParsedNfa], to be validated like a parsed table
+    pub(crate) fn as_parsed(&self) -> ParsedNfa<'_> {
+        ParsedNfa {
+            head: self
+                .head
+                .iter()
+                .map(|entry| match entry {
+                    Some(elem) => NfaAlphabetEntry::Element(Cow::Borrowed(elem)),
+                    None => NfaAlphabetEntry::Eps,
+                })
+                .collect(),
+            states: self
+                .states
+                .iter()
+                .map(|state| ParsedNfaState {
+                    name: Cow::Borrowed(&state.name),
+                    initial: state.initial,
+                    accepting: state.accepting,
+                    transitions: state
+                        .transitions
+                        .iter()
+                        .map(|set| set.iter().map(|to| Cow::Borrowed(to.as_str())).collect())
+                        .collect(),
+                })
+                .collect(),
+            declared_alphabet: borrowed_alphabet(&self.declared_alphabet),
+        }
+    }
+}
+
+/// Finds the column of the symbol (where `None` is ε) among the columns of a table
+fn column<'a>(
+    mut head: impl Iterator<Item = Option<&'a str>>,
+    symbol: Option<&str>,
+) -> Result<usize, ParsedEditError> {
+    head.position(|column| column == symbol)
+        .ok_or_else(|| ParsedEditError::UnknownSymbol(symbol.unwrap_or("ε").to_string()))
+}
+
+fn borrowed_alphabet(alphabet: &Option<Vec<String>>) -> Option<Vec<&str>> {
+    alphabet
+        .as_ref()
+        .map(|alphabet| alphabet.iter().map(String::as_str).collect())
+}
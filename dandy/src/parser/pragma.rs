@@ -0,0 +1,19 @@
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_till1};
+use nom::character::complete::{line_ending, space0, space1};
+use nom::combinator::eof;
+use nom::multi::many0;
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::IResult;
+
+/// Parses an alphabet pragma, such as `#! alphabet a b c`, which is a line declaring the alphabet as
+/// whitespace-separated elements. The line ending is consumed as well.
+pub(crate) fn alphabet_pragma(input: &str) -> IResult<&str, Vec<&str>> {
+    terminated(
+        preceded(
+            tuple((space0, tag("#!"), space0, tag("alphabet"))),
+            many0(preceded(space1, take_till1(char::is_whitespace))),
+        ),
+        pair(space0, alt((line_ending, eof))),
+    )(input)
+}
@@ -1,35 +1,119 @@
+use crate::parser::RegexOptions;
 use crate::regex::{Regex, RegexChar, RegexTree};
 use nom::branch::alt;
 use nom::character::complete;
 use nom::character::complete::one_of;
 use nom::combinator::{fail, map, opt, value, verify};
-use nom::multi::{many1, separated_list1};
-use nom::sequence::{delimited, preceded};
+use nom::error::{Error, ErrorKind};
+use nom::sequence::preceded;
 use nom::{IResult, Parser};
 use std::hint::unreachable_unchecked;
 use std::rc::Rc;
 use unicode_segmentation::UnicodeSegmentation;
 
-pub(crate) fn full_regex(input: &str) -> IResult<&str, Regex> {
-    map(expression, |tree| Regex { tree })(input.trim()) //trim instead of delimited since otherwise trailing w.s. can be counted as tokens
+/// Parses a regular expression without recursion, so that deeply nested expressions can't overflow the stack. Every
+/// unclosed parenthesis has a [Level] on an explicit stack, and elements are parsed one at a time, where a finished
+/// element is added to the innermost level (after applying the complements preceding it). `depth` is the number of
+/// unclosed parentheses and pending complements, which is limited by `options.max_depth`.
+pub(crate) fn full_regex<'a>(input: &'a str, options: &RegexOptions) -> IResult<&'a str, Regex> {
+    // Trim instead of delimited, since otherwise trailing whitespace can be counted as tokens
+    let mut input = input.trim();
+    let mut stack = vec![Level::default()];
+    let mut depth = 0;
+    loop {
+        // Prefix operators and opening parentheses nest, and are followed by another element
+        if options.extended {
+            if let Ok((rest, _)) = one_of::<_, _, Error<&str>>("~¬")(input) {
+                check_depth(input, options, &mut depth)?;
+                stack.last_mut().expect("never empty").complements += 1;
+                input = rest;
+                continue;
+            }
+        }
+        if let Some(rest) = input.strip_prefix('(') {
+            check_depth(input, options, &mut depth)?;
+            stack.push(Level::default());
+            input = rest;
+            continue;
+        }
+        let (rest, mut element) = combinated_char(input, options)?;
+        input = rest;
+
+        // The element is finished, possibly along with the parentheses it closes
+        loop {
+            let level = stack.last_mut().expect("never empty");
+            for _ in 0..level.complements {
+                element = RegexTree::Complement(Box::new(element));
+            }
+            depth -= level.complements;
+            level.complements = 0;
+            level.sequence.push(element);
+            if let Some(rest) = input.strip_prefix('|') {
+                level.end_sequence();
+                level.end_intersection();
+                input = rest;
+            } else if let Some(rest) = input.strip_prefix('&').filter(|_| options.extended) {
+                level.end_sequence();
+                input = rest;
+            } else if let Some(rest) = input.strip_prefix(')').filter(|_| stack.len() > 1) {
+                let tree = stack.pop().expect("has more than one level").finish();
+                depth -= 1;
+                let (rest, kleene) = opt(one_of("+*"))(rest)?;
+                input = rest;
+                element = apply_kleene((tree, kleene));
+                continue;
+            } else if stack.len() == 1 && (input.is_empty() || input.starts_with(')')) {
+                let tree = stack.pop().expect("has one level").finish();
+                return Ok((input, Regex { tree }));
+            }
+            break;
+        }
+    }
 }
 
-fn expression(input: &str) -> IResult<&str, RegexTree> {
-    alternation(input)
+/// An unclosed parenthesis (or the whole expression) being parsed by [full_regex], with the finished alternatives
+/// and intersected sequences of it, the elements of the current sequence and the number of complements which apply
+/// to the next element
+#[derive(Default)]
+struct Level {
+    alternatives: Vec<RegexTree>,
+    intersection: Vec<RegexTree>,
+    sequence: Vec<RegexTree>,
+    complements: usize,
 }
 
-fn alternation(input: &str) -> IResult<&str, RegexTree> {
-    map(
-        separated_list1(complete::char('|'), sequence),
-        wrap_multiple(RegexTree::Alt),
-    )(input)
+impl Level {
+    fn end_sequence(&mut self) {
+        let sequence = std::mem::take(&mut self.sequence);
+        self.intersection
+            .push(wrap_multiple(RegexTree::Sequence)(sequence));
+    }
+
+    fn end_intersection(&mut self) {
+        let intersection = std::mem::take(&mut self.intersection);
+        self.alternatives
+            .push(wrap_multiple(RegexTree::Intersection)(intersection));
+    }
+
+    fn finish(mut self) -> RegexTree {
+        self.end_sequence();
+        self.end_intersection();
+        wrap_multiple(RegexTree::Alt)(self.alternatives)
+    }
 }
 
-fn sequence(input: &str) -> IResult<&str, RegexTree> {
-    map(
-        many1(alt((par_expr, combinated_char))),
-        wrap_multiple(RegexTree::Sequence),
-    )(input)
+/// Increases the depth when starting a nested expression (such as an opening parenthesis) at `input`, which fails
+/// without backtracking if `depth` is already at the maximum depth
+fn check_depth<'a>(
+    input: &'a str,
+    options: &RegexOptions,
+    depth: &mut usize,
+) -> Result<(), nom::Err<Error<&'a str>>> {
+    if *depth >= options.max_depth {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::TooLarge)));
+    }
+    *depth += 1;
+    Ok(())
 }
 
 fn wrap_multiple<T>(f: impl Fn(Vec<T>) -> T) -> impl Fn(Vec<T>) -> T {
@@ -42,16 +126,9 @@ fn wrap_multiple<T>(f: impl Fn(Vec<T>) -> T) -> impl Fn(Vec<T>) -> T {
     }
 }
 
-fn par_expr(input: &str) -> IResult<&str, RegexTree> {
+fn combinated_char<'a>(input: &'a str, options: &RegexOptions) -> IResult<&'a str, RegexTree> {
     map(
-        delimited(complete::char('('), expression, complete::char(')')).and(opt(one_of("+*"))),
-        apply_kleene,
-    )(input)
-}
-
-fn combinated_char(input: &str) -> IResult<&str, RegexTree> {
-    map(
-        map(regex_char, RegexTree::Char).and(opt(one_of("+*"))),
+        map(|i| regex_char(i, options), RegexTree::Char).and(opt(one_of("+*"))),
         apply_kleene,
     )(input)
 }
@@ -68,13 +145,18 @@ fn apply_kleene((to_combine, kleene): (RegexTree, Option<char>)) -> RegexTree {
     }
 }
 
-fn regex_char(input: &str) -> IResult<&str, RegexChar> {
-    alt((empty_lang, empty_str, escaped_char, normal_char))(input)
+fn regex_char<'a>(input: &'a str, options: &RegexOptions) -> IResult<&'a str, RegexChar> {
+    alt((empty_lang, empty_str, escaped_char, |i| {
+        normal_char(i, options)
+    }))(input)
 }
 
-fn normal_char(input: &str) -> IResult<&str, RegexChar> {
+fn normal_char<'a>(input: &'a str, options: &RegexOptions) -> IResult<&'a str, RegexChar> {
     verify(one_cluster, |rxc| match rxc {
-        RegexChar::Grapheme(c) => !is_reserved_char(c.chars().next().unwrap_or_default()),
+        RegexChar::Grapheme(c) => {
+            let c = c.chars().next().unwrap_or_default();
+            !(is_reserved_char(c) || options.extended && is_extended_operator(c))
+        }
         // Safety: mapped under one_char, it can only yield RegexChar::Char
         _ => unsafe { unreachable_unchecked() },
     })(input)
@@ -104,3 +186,8 @@ fn empty_lang(input: &str) -> IResult<&str, RegexChar> {
 fn is_reserved_char(char: char) -> bool {
     ['(', ')', '∅', 'ε', '|', '*', '+', '\\'].contains(&char)
 }
+
+/// Checks if the character is an operator of extended regexes, which is reserved only when parsing those
+fn is_extended_operator(char: char) -> bool {
+    ['&', '~', '¬'].contains(&char)
+}
@@ -0,0 +1,129 @@
+use super::{Regex, RegexChar, RegexTree};
+use std::ops::{BitOr, Mul};
+use std::rc::Rc;
+use thiserror::Error;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The error given by [Regex::grapheme] if the string isn't exactly one grapheme cluster
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Expected a single grapheme cluster, but got {0:?}")]
+pub struct NotAGrapheme(pub String);
+
+impl Regex {
+    /// A regex matching exactly the given string, that is, the sequence of its grapheme clusters. Reserved characters
+    /// such as `*` or `|` match themselves, since nothing is parsed. The empty string gives [Regex::epsilon].
+    ///
+    /// ```
+    /// use dandy::regex::Regex;
+    ///
+    /// let regex = Regex::literal("a*");
+    /// assert_eq!(regex.to_string(), "a\\*");
+    /// assert!(regex.to_nfa().accepts_graphemes("a*"));
+    /// ```
+    pub fn literal(s: &str) -> Regex {
+        let mut graphemes = s
+            .graphemes(true)
+            .map(|g| RegexTree::Char(RegexChar::Grapheme(Rc::from(g))))
+            .collect::<Vec<_>>();
+        let tree = match graphemes.len() {
+            0 => RegexTree::Char(RegexChar::Epsilon),
+            1 => graphemes.pop().unwrap(),
+            _ => RegexTree::Sequence(graphemes),
+        };
+        Regex { tree }
+    }
+
+    /// A regex matching the single grapheme cluster `g`, or an error if `g` is empty or consists of more than one
+    /// grapheme cluster
+    pub fn grapheme(g: &str) -> Result<Regex, NotAGrapheme> {
+        let mut graphemes = g.graphemes(true);
+        match (graphemes.next(), graphemes.next()) {
+            (Some(_), None) => Ok(Regex::literal(g)),
+            _ => Err(NotAGrapheme(g.to_string())),
+        }
+    }
+
+    /// The regex `∅`, matching no words at all
+    pub fn empty() -> Regex {
+        Regex {
+            tree: RegexTree::Char(RegexChar::Empty),
+        }
+    }
+
+    /// The regex `ε`, matching only the empty word
+    pub fn epsilon() -> Regex {
+        Regex {
+            tree: RegexTree::Char(RegexChar::Epsilon),
+        }
+    }
+
+    /// The concatenation of this regex and another one. Nested sequences are flattened, so `a.then(b).then(c)` and
+    /// `a.then(b.then(c))` give the same tree. Also available as `self * other`.
+    pub fn then(self, other: Regex) -> Regex {
+        let mut items = Vec::new();
+        for tree in [self.tree, other.tree] {
+            match tree {
+                RegexTree::Sequence(seq) => items.extend(seq),
+                tree => items.push(tree),
+            }
+        }
+        Regex {
+            tree: RegexTree::Sequence(items),
+        }
+    }
+
+    /// The alternation of this regex and another one. Nested alternations are flattened, so `a.or(b).or(c)` and
+    /// `a.or(b.or(c))` give the same tree. Also available as `self | other`.
+    pub fn or(self, other: Regex) -> Regex {
+        let mut items = Vec::new();
+        for tree in [self.tree, other.tree] {
+            match tree {
+                RegexTree::Alt(alt) => items.extend(alt),
+                tree => items.push(tree),
+            }
+        }
+        Regex {
+            tree: RegexTree::Alt(items),
+        }
+    }
+
+    /// The Kleene star of this regex, matching zero or more repetitions of it
+    pub fn star(self) -> Regex {
+        Regex {
+            tree: RegexTree::Repeat(Box::new(self.tree)),
+        }
+    }
+
+    /// The Kleene plus of this regex, matching one or more repetitions of it. Like in the parser, `r+` is
+    /// represented as `rr*`.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::Regex;
+    ///
+    /// let regex = Regex::literal("ab").plus().then(Regex::literal("c"));
+    /// assert_eq!(regex.to_string(), "ab(ab)*c");
+    /// assert!(regex.language_eq(&parser::regex("(ab)+c").unwrap()));
+    /// ```
+    pub fn plus(self) -> Regex {
+        self.clone().then(self.star())
+    }
+}
+
+impl Mul for Regex {
+    type Output = Regex;
+
+    /// Concatenation, see [Regex::then]
+    fn mul(self, rhs: Regex) -> Regex {
+        self.then(rhs)
+    }
+}
+
+impl BitOr for Regex {
+    type Output = Regex;
+
+    /// Alternation, see [Regex::or]
+    fn bitor(self, rhs: Regex) -> Regex {
+        self.or(rhs)
+    }
+}
@@ -10,15 +10,29 @@
 //! language, `ε` denotes the empty string, `|` denotes alternation, and `*`/`+` is Kleene star/plus (zero or more/one
 //! or more). Initial and trailing whitespace is ignored, but all whitespace within the expression is significant.
 //!
+//! [Extended regexes](crate::parser#extended-regular-expressions) may also contain intersections (`&`) and
+//! complements (`~` or `¬`), which are compiled with [Regex::to_dfa].
+//!
 //! Here are some examples:
 //! * `(ab)+` matches `ab`, `abab`, `ababab`, ...
 //! * `(ab)*` matches `(empty string)`, `ab`, `abab`, `ababab`, ...
 //! * `0*1(0+ε)` matches `1`, `10`, `0001` and all other strings containing the character `1` once
 //!
 //! ## Operations
-//! The only operation currently implemented is converting a Regular Expression to a NFA. From there, you can do lots
-//! of stuff, like optimizing it, encoding it to a table, enumerate all words in it, convert it to a DFA to take the
-//! symmetric difference to another regex or automata etc.
+//! The main operation is converting a Regular Expression to a NFA (optionally
+//! [with debug information](Regex::to_nfa_debug) about which part of the regex each state comes from, or
+//! [annotated](Regex::to_nfa_annotated) with the kind of part for visualizations). From there,
+//! you can do lots of stuff, like optimizing it, encoding it to a table, enumerate all words in it, convert it to a
+//! DFA to take the symmetric difference to another regex or automata etc.
+//!
+//! The most common pipeline, converting a regex to a minimal DFA, is available as [Regex::to_min_dfa], which
+//! optimizes the NFA before the subset construction.
+//!
+//! Regexes can also be compared by their languages: two regexes are [equal](Regex::language_eq) if they match the same
+//! language, and `r1 <= r2` if the language of `r1` is a [subset](Regex::subset_of) of the language of `r2`. Both
+//! comparisons convert the regexes to DFAs, which is expensive for large regexes. The syntax trees can be compared
+//! with [Regex::structurally_eq]. To group many regexes by their languages, compare their
+//! [language fingerprints](Regex::language_fingerprint) instead of comparing each pair of regexes.
 //!
 //! Here are some example usages of the regexes above:
 //! ```
@@ -50,26 +64,111 @@
 //! assert_eq!(words.next(), Some("01".to_string()));
 //! assert_eq!(words.next(), Some("10".to_string()));
 //! ```
+//!
+//! ## Building regexes
+//! Regexes can also be built programmatically, without any escaping, from [Regex::literal], [Regex::grapheme],
+//! [Regex::empty] and [Regex::epsilon], combined with [Regex::then] (or `*`), [Regex::or] (or `|`), [Regex::star] and
+//! [Regex::plus]:
+//! ```
+//! use dandy::regex::Regex;
+//! let regex = (Regex::literal("a") | Regex::literal("(")).star() * Regex::literal("b");
+//! assert_eq!(regex.to_string(), "((a|\\())*b");
+//! assert!(regex.to_nfa().accepts_graphemes("a(ab"));
+//! ```
+//!
+//! ## JSON
+//! With the `serde` feature enabled, regexes can be serialized with any serde format, or to and from JSON directly with
+//! [Regex::to_json_string] and [Regex::from_json_str]. A regex is represented by its syntax `tree`, where each node is
+//! an object with a single key: `Sequence`, `Alt` or `Intersection` with a list of nodes, `Repeat` or `Complement` with
+//! a single node, or `Char` with either `{"Grapheme": "..."}` or one of the strings `"Epsilon"` and `"Empty"`. The
+//! regex `a(b|ε)*` is represented as:
+//! ```json
+//! {
+//!   "tree": {
+//!     "Sequence": [
+//!       { "Char": { "Grapheme": "a" } },
+//!       { "Repeat": { "Alt": [{ "Char": { "Grapheme": "b" } }, { "Char": "Epsilon" }] } }
+//!     ]
+//!   }
+//! }
+//! ```
 
+use crate::dfa::Dfa;
 use crate::nfa::{Nfa, NfaState};
+use crate::util::{valid_symbol, AlphabetIndex, Provenance, Version};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::iter;
 use std::rc::Rc;
+use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+mod builder;
+
+pub use builder::NotAGrapheme;
+
+/// A regular expression. Note that two regexes are [equal](PartialEq) if they match the same language, and
+/// [ordered](PartialOrd) by language inclusion, which both require converting the regexes to DFAs. To compare the
+/// syntax trees of two regexes, use [Regex::structurally_eq].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Regex {
     pub tree: RegexTree,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegexTree {
     Sequence(Vec<RegexTree>),
     Alt(Vec<RegexTree>),
     Repeat(Box<RegexTree>),
     Char(RegexChar),
+    /// The intersection of the languages of the subtrees, only in
+    /// [extended regexes](crate::parser#extended-regular-expressions)
+    Intersection(Vec<RegexTree>),
+    /// The complement of the language of the subtree, only in
+    /// [extended regexes](crate::parser#extended-regular-expressions)
+    Complement(Box<RegexTree>),
+}
+
+impl RegexTree {
+    /// Checks if this tree contains an [intersection](RegexTree::Intersection) or a
+    /// [complement](RegexTree::Complement), which can only be compiled by [Regex::to_dfa]
+    pub fn is_extended(&self) -> bool {
+        match self {
+            RegexTree::Sequence(items) | RegexTree::Alt(items) => {
+                items.iter().any(RegexTree::is_extended)
+            }
+            RegexTree::Repeat(tree) => tree.is_extended(),
+            RegexTree::Char(_) => false,
+            RegexTree::Intersection(_) | RegexTree::Complement(_) => true,
+        }
+    }
+
+    /// Pushes the graphemes of this tree which aren't in `graphemes` to it, in the order they appear
+    fn collect_graphemes(&self, graphemes: &mut Vec<Rc<str>>) {
+        match self {
+            RegexTree::Sequence(items) | RegexTree::Alt(items) | RegexTree::Intersection(items) => {
+                items
+                    .iter()
+                    .for_each(|tree| tree.collect_graphemes(graphemes))
+            }
+            RegexTree::Repeat(tree) | RegexTree::Complement(tree) => {
+                tree.collect_graphemes(graphemes)
+            }
+            RegexTree::Char(RegexChar::Grapheme(g)) => {
+                if !graphemes.contains(g) {
+                    graphemes.push(g.clone());
+                }
+            }
+            RegexTree::Char(_) => {}
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegexChar {
     Grapheme(Rc<str>),
     Epsilon,
@@ -98,10 +197,161 @@ impl StateCounter {
 }
 
 impl Regex {
-    /// Converts this regular expression to a NFA. This is the only operation available to regular expressions.
+    /// Serializes this regular expression to JSON, in the format described in the
+    /// [module-level documentation](crate::regex)
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::Regex;
+    ///
+    /// let regex = parser::regex("a(b|ε)*").unwrap();
+    /// let json = regex.to_json_string().unwrap();
+    /// assert!(Regex::from_json_str(&json).unwrap().structurally_eq(&regex));
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a regular expression from JSON, in the format described in the
+    /// [module-level documentation](crate::regex)
+    #[cfg(feature = "serde")]
+    pub fn from_json_str(s: &str) -> Result<Regex, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    /// Converts this regular expression to a NFA.
     /// To check if a string is accepted by this regular expression, one should convert it to a NFA and then check
     /// using that NFA. Note that the resulting NFA may be quite large, so converting it to a DFA may optimize it.
+    ///
+    /// [Extended regexes](crate::parser#extended-regular-expressions) can't be converted by the Thompson
+    /// construction, so they are compiled with [Regex::to_dfa] and converted to a NFA from there.
     pub fn to_nfa(self) -> Nfa {
+        self.build_nfa(&mut None)
+    }
+
+    /// Converts this regular expression to a NFA over the given alphabet, which may contain elements not appearing in
+    /// the regex (which no words of the language contain, just as if they did appear but only in `∅` parts). The
+    /// alphabet of the NFA is in the given order. Gives an error if the regex contains elements not in the alphabet,
+    /// or if the alphabet contains duplicates or elements which are empty or start or end with whitespace (just as
+    /// parsed tables, since those are too easily confused with other elements). The alphabet may be declared by an
+    /// [alphabet pragma](crate::parser#alphabet-pragma) in regex files.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::RegexAlphabetError;
+    ///
+    /// let regex = parser::regex("(ab)+").unwrap();
+    /// let nfa = regex.clone().to_nfa_with_alphabet(&["a", "b", "c"]).unwrap();
+    /// assert_eq!(nfa.alphabet(), &["a".into(), "b".into(), "c".into()]);
+    /// assert!(nfa.accepts(&["a", "b"]));
+    /// assert!(!nfa.accepts(&["a", "b", "c"]));
+    /// let unknown = regex.clone().to_nfa_with_alphabet(&["a"]).unwrap_err();
+    /// assert_eq!(unknown, RegexAlphabetError::UnknownElement("b".into()));
+    /// let invalid = regex.to_nfa_with_alphabet(&["a", "b", ""]).unwrap_err();
+    /// assert_eq!(invalid, RegexAlphabetError::InvalidElement("".into()));
+    /// ```
+    pub fn to_nfa_with_alphabet(self, alphabet: &[&str]) -> Result<Nfa, RegexAlphabetError> {
+        let mut elements: Vec<Rc<str>> = Vec::with_capacity(alphabet.len());
+        for &elem in alphabet {
+            if !valid_symbol(elem) {
+                return Err(RegexAlphabetError::InvalidElement(Rc::from(elem)));
+            }
+            if elements.iter().any(|e| &**e == elem) {
+                return Err(RegexAlphabetError::DuplicateElement(Rc::from(elem)));
+            }
+            elements.push(Rc::from(elem));
+        }
+        let mut nfa = self.to_nfa();
+        if let Some(unknown) = nfa.alphabet.iter().find(|elem| !elements.contains(elem)) {
+            return Err(RegexAlphabetError::UnknownElement(unknown.clone()));
+        }
+        widen_alphabet(&mut nfa, &elements);
+        Ok(nfa
+            .with_alphabet_order(alphabet)
+            .expect("The alphabet contains exactly the elements of the NFA"))
+    }
+
+    /// Converts this regular expression to a NFA just like [Regex::to_nfa], but also returns a [DebugInfo] telling
+    /// which part of the regular expression each state of the NFA corresponds to. The NFA is identical to the one
+    /// returned by [Regex::to_nfa], so this is useful for understanding or debugging the conversion, but it requires
+    /// some extra allocations to keep track of the paths in the regex tree. No roles are recorded for
+    /// [extended regexes](crate::parser#extended-regular-expressions), which are compiled with [Regex::to_dfa].
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::StateRole;
+    ///
+    /// let regex = parser::regex("(a|b)*c").unwrap();
+    /// let (nfa, debug) = regex.clone().to_nfa_debug();
+    /// assert_eq!(nfa, regex.to_nfa());
+    /// assert_eq!(debug.roles().len(), nfa.states().len());
+    /// assert_eq!(debug.role(0), Some(&StateRole::Accepting));
+    /// assert_eq!(debug.role(1), Some(&StateRole::Initial));
+    ///
+    /// // The state which transitions on 'a' is the state entering that character in the tree
+    /// let a_state = nfa.states().iter().position(|s| !s.transitions()[0].is_empty()).unwrap();
+    /// assert_eq!(
+    ///     debug.role(a_state).unwrap().to_string(),
+    ///     "enter Sequence[0].Repeat.Alt[0].Char('a')"
+    /// );
+    /// ```
+    pub fn to_nfa_debug(self) -> (Nfa, DebugInfo) {
+        let mut recorder = Some(DebugRecorder {
+            path: vec![],
+            roles: vec![StateRole::Accepting, StateRole::Initial],
+        });
+        let nfa = self.build_nfa(&mut recorder);
+        let roles = recorder.map(|r| r.roles).unwrap_or_default();
+        (nfa, DebugInfo { roles })
+    }
+
+    /// Converts this regular expression to a NFA just like [Regex::to_nfa], but also returns what kind of part of the
+    /// Thompson construction each state of the NFA is, indexed by the index of the state. This is a coarser version
+    /// of the roles given by [Regex::to_nfa_debug], suitable for coloring the states when drawing the NFA. The states
+    /// of NFAs converted from [extended regexes](crate::parser#extended-regular-expressions) don't correspond to
+    /// parts of the regex, so they are all [RegexProvenance::Compiled]. When the NFA is
+    /// [optimized](Nfa::optimize) afterwards, the returned [IndexRemap](crate::nfa::IndexRemap) gives the provenance
+    /// of the remaining states.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// use dandy::regex::RegexProvenance;
+    ///
+    /// let regex = parser::regex("a(b|c)*").unwrap();
+    /// let (nfa, provenance) = regex.to_nfa_annotated();
+    /// assert_eq!(provenance.len(), nfa.states().len());
+    /// assert_eq!(provenance[0], RegexProvenance::Accepting);
+    /// assert_eq!(provenance[1], RegexProvenance::Initial);
+    /// assert_eq!(provenance[2], RegexProvenance::SequenceEntry);
+    /// assert_eq!(provenance[3], RegexProvenance::SequenceJoint { index: 0, depth: 0 });
+    /// assert_eq!(provenance[4], RegexProvenance::CharIncoming { grapheme: "a".into() });
+    /// assert!(provenance.contains(&RegexProvenance::RepeatEntry));
+    ///
+    /// let mut optimized = nfa.clone();
+    /// let remap = optimized.optimize();
+    /// let provenance = (0..remap.new_len())
+    ///     .map(|new| provenance[remap.new_to_old(new)].clone())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(provenance.len(), optimized.states().len());
+    /// ```
+    pub fn to_nfa_annotated(&self) -> (Nfa, Vec<RegexProvenance>) {
+        let (nfa, debug) = self.clone().to_nfa_debug();
+        let provenance = if debug.roles.is_empty() {
+            vec![RegexProvenance::Compiled; nfa.states.len()]
+        } else {
+            debug.roles.iter().map(RegexProvenance::of_role).collect()
+        };
+        (nfa, provenance)
+    }
+
+    /// Converts this regular expression to a NFA, recording the role of every state if `debug` is `Some`
+    fn build_nfa(self, debug: &mut Option<DebugRecorder>) -> Nfa {
+        if self.is_extended() {
+            *debug = None;
+            return self.to_dfa().to_nfa();
+        }
+        let provenance = Provenance::new(|| format!("Thompson construction from regex {self}"));
         // Final accepting state is 0
         // Initial state is 1
         let mut counter = StateCounter::new();
@@ -129,7 +379,8 @@ impl Regex {
         };
 
         let states = {
-            let mut tree_states = Self::tree_to_nfa(self.tree, &mut counter, &mut grapheme_idx, 0);
+            let mut tree_states =
+                Self::tree_to_nfa(self.tree, &mut counter, &mut grapheme_idx, 0, debug);
             let mut all_states = Vec::with_capacity(tree_states.len() + 2);
             all_states.push(accepting_state); // state 0
             all_states.push(initial_state); // state 1
@@ -151,14 +402,196 @@ impl Regex {
             alphabet,
             states,
             initial_state: 1,
+            provenance,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
         }
     }
 
-    /// *This is subject to change*
-    pub fn to_string(&self) -> String {
-        let mut acc = String::new();
-        Self::build_string(&self.tree, &mut acc);
-        acc
+    /// Converts this regular expression to a minimal DFA. This is the same as converting it to a NFA, then to a DFA
+    /// and minimizing it, but the NFA is [optimized](Nfa::optimize) before the subset construction, which usually
+    /// gives a much smaller DFA before minimization. The states of the DFA are named sequentially from 0 in
+    /// breadth-first order from the initial state, so equal regexes always give identical DFAs. To get the number of
+    /// states after each step, use [Regex::to_min_dfa_with_report].
+    pub fn to_min_dfa(&self) -> Dfa {
+        self.to_min_dfa_with_report().0
+    }
+
+    /// Converts this regular expression to a minimal DFA, like [Regex::to_min_dfa], but also supports
+    /// [extended regexes](crate::parser#extended-regular-expressions) with intersections and complements. These
+    /// can't be expressed in the Thompson construction, so the tree is compiled recursively: subtrees without
+    /// extended operators are compiled through a NFA as usual, while intersections, complements and everything
+    /// containing them are combined at the level of minimal DFAs (with the product construction, inversion,
+    /// [concatenation](Dfa::concatenate) and [star](Dfa::star)). All DFAs are over the graphemes appearing in the
+    /// whole regex, which is the alphabet complements are taken with respect to. Like for [Regex::to_min_dfa], the
+    /// states are named sequentially from 0 in breadth-first order.
+    ///
+    /// ```
+    /// use dandy::parser::{self, RegexOptions};
+    ///
+    /// let options = RegexOptions { extended: true, ..RegexOptions::default() };
+    /// // Words of a:s and b:s which contain both "aa" and "bb"
+    /// let regex = parser::regex_with_options("(a|b)*aa(a|b)*&(a|b)*bb(a|b)*", &options).unwrap();
+    /// let dfa = regex.to_dfa();
+    /// assert!(dfa.accepts_graphemes("bbaa"));
+    /// assert!(!dfa.accepts_graphemes("abab"));
+    /// // The complement of ε over the alphabet {a} is a+
+    /// let regex = parser::regex_with_options("~ε|a", &options).unwrap();
+    /// assert!(regex.to_dfa().equivalent_to(&parser::regex("a+").unwrap().to_min_dfa()));
+    /// ```
+    pub fn to_dfa(&self) -> Dfa {
+        if !self.is_extended() {
+            return self.to_min_dfa();
+        }
+        let mut alphabet = vec![];
+        self.tree.collect_graphemes(&mut alphabet);
+        let mut dfa = Self::tree_to_dfa(&self.tree, &alphabet);
+        dfa.renumber_states();
+        dfa.provenance = Provenance::new(|| format!("compiled from extended regex {self}"));
+        dfa
+    }
+
+    /// Checks if this regex contains an intersection or a complement, see [RegexTree::is_extended]
+    pub fn is_extended(&self) -> bool {
+        self.tree.is_extended()
+    }
+
+    /// Compiles a tree to a minimal DFA over the given alphabet, for [Regex::to_dfa]
+    fn tree_to_dfa(tree: &RegexTree, alphabet: &[Rc<str>]) -> Dfa {
+        let compile_all = |trees: &[RegexTree]| {
+            trees
+                .iter()
+                .map(|tree| Self::tree_to_dfa(tree, alphabet))
+                .collect::<Vec<_>>()
+        };
+        let mut dfa = match tree {
+            tree if !tree.is_extended() => {
+                let mut nfa = Regex { tree: tree.clone() }.to_nfa();
+                widen_alphabet(&mut nfa, alphabet);
+                nfa.optimize();
+                nfa.to_dfa()
+            }
+            RegexTree::Sequence(items) => compile_all(items)
+                .into_iter()
+                .reduce(|acc, dfa| acc.concatenate(&dfa).expect("alphabets should be equal"))
+                .expect("extended sequences should be non-empty"),
+            RegexTree::Alt(items) => {
+                Dfa::union_many(&compile_all(items).iter().collect::<Vec<_>>())
+                    .expect("alphabets should be equal")
+            }
+            RegexTree::Intersection(items) => {
+                Dfa::intersection_many(&compile_all(items).iter().collect::<Vec<_>>())
+                    .expect("alphabets should be equal")
+            }
+            RegexTree::Repeat(tree) => Self::tree_to_dfa(tree, alphabet).star(),
+            RegexTree::Complement(tree) => {
+                let mut dfa = Self::tree_to_dfa(tree, alphabet);
+                dfa.invert();
+                dfa
+            }
+            RegexTree::Char(_) => unreachable!("characters are never extended"),
+        };
+        dfa.minimize();
+        dfa
+    }
+
+    /// Converts this regular expression to a minimal DFA just like [Regex::to_min_dfa], but also returns a
+    /// [CompileReport] with the number of states after each step.
+    ///
+    /// ```
+    /// use dandy::parser;
+    ///
+    /// let regex = parser::regex("(a|b)*abb").unwrap();
+    /// let (dfa, report) = regex.to_min_dfa_with_report();
+    /// assert_eq!(dfa.states().len(), 4);
+    /// assert_eq!(report.min_dfa_states, 4);
+    /// assert!(report.nfa_states > report.min_dfa_states);
+    /// assert_eq!(dfa.initial_state().name(), "0");
+    /// ```
+    pub fn to_min_dfa_with_report(&self) -> (Dfa, CompileReport) {
+        let mut nfa = self.clone().to_nfa();
+        let nfa_states = nfa.states.len();
+        nfa.optimize();
+        let mut dfa = nfa.to_dfa();
+        let dfa_states = dfa.states().len();
+        dfa.minimize();
+        dfa.renumber_states();
+        let report = CompileReport {
+            nfa_states,
+            dfa_states,
+            min_dfa_states: dfa.states().len(),
+        };
+        (dfa, report)
+    }
+
+    /// Checks if this regex matches the same language as another regex. Unlike [Regex::structurally_eq], this
+    /// considers `a|b` and `b|a` equal. Both regexes are converted to DFAs over the union of their alphabets, so this
+    /// may take time exponential in the size of the regexes.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// let regex1 = parser::regex("(a|b)*").unwrap();
+    /// let regex2 = parser::regex("(a*b*)*").unwrap();
+    /// assert!(regex1.language_eq(&regex2));
+    /// assert!(!regex1.structurally_eq(&regex2));
+    /// ```
+    pub fn language_eq(&self, other: &Regex) -> bool {
+        let (this, other) = self.to_dfas_over_shared_alphabet(other);
+        this.equivalent_to(&other)
+    }
+
+    /// Computes a fingerprint of the language of this regex, which is equal for all regexes matching the same words,
+    /// so that many regexes can be grouped by their languages without comparing each pair of them. This is the
+    /// [language fingerprint](Dfa::language_fingerprint) of the minimal DFA of the regex, which is stable across runs
+    /// and platforms. Since it is a 64-bit hash, regexes with different languages may get the same fingerprint with a
+    /// tiny probability, so fingerprints being equal should be confirmed with [Regex::language_eq] if certainty is
+    /// needed.
+    ///
+    /// ```
+    /// use dandy::parser;
+    /// let regex1 = parser::regex("(ab)*a").unwrap();
+    /// let regex2 = parser::regex("a(ba)*").unwrap();
+    /// let regex3 = parser::regex("(ab)*").unwrap();
+    /// assert_eq!(regex1.language_fingerprint(), regex2.language_fingerprint());
+    /// assert_ne!(regex1.language_fingerprint(), regex3.language_fingerprint());
+    /// ```
+    pub fn language_fingerprint(&self) -> u64 {
+        self.to_min_dfa().minimal_language_fingerprint()
+    }
+
+    /// Checks if the language of this regex is a subset of the language of another regex, that is, if every word
+    /// matched by this regex is matched by the other regex as well. Both regexes are converted to DFAs over the union
+    /// of their alphabets, so this may take time exponential in the size of the regexes.
+    pub fn subset_of(&self, other: &Regex) -> bool {
+        let (this, other) = self.to_dfas_over_shared_alphabet(other);
+        !this
+            .difference(&other)
+            .expect("alphabets should be equal")
+            .has_reachable_accepting_state()
+    }
+
+    /// Checks if the syntax trees of this regex and another regex are equal. This is cheap, but regexes matching the
+    /// same language (such as `a|b` and `b|a`) may differ structurally, see [Regex::language_eq].
+    pub fn structurally_eq(&self, other: &Regex) -> bool {
+        self.tree == other.tree
+    }
+
+    /// Converts this regex and another regex to DFAs, where graphemes only in one of the regexes are added to the
+    /// alphabet of the other one (without any transitions), so that the DFAs can be compared
+    pub(crate) fn to_dfas_over_shared_alphabet(&self, other: &Regex) -> (Dfa, Dfa) {
+        let this = self.clone().to_nfa();
+        let other = other.clone().to_nfa();
+        let mut alphabet = this.alphabet.to_vec();
+        for elem in other.alphabet.iter() {
+            if !alphabet.contains(elem) {
+                alphabet.push(elem.clone());
+            }
+        }
+        let widen = |mut nfa: Nfa| {
+            widen_alphabet(&mut nfa, &alphabet);
+            nfa.to_dfa()
+        };
+        (widen(this), widen(other))
     }
 
     fn build_string(tree: &RegexTree, acc: &mut String) {
@@ -186,6 +619,21 @@ impl Regex {
                 acc.push(')');
                 acc.push('*');
             }
+            RegexTree::Intersection(items) => {
+                acc.push('(');
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        acc.push('&');
+                    }
+                    Self::build_string(item, acc);
+                }
+                acc.push(')');
+            }
+            RegexTree::Complement(tree) => {
+                acc.push_str("~(");
+                Self::build_string(tree, acc);
+                acc.push(')');
+            }
             RegexTree::Char(c) => match c {
                 RegexChar::Epsilon => {
                     acc.push('ε');
@@ -194,8 +642,10 @@ impl Regex {
                     acc.push('∅');
                 }
                 RegexChar::Grapheme(g) => {
-                    if g.len() == 1
-                        && ['(', ')', '∅', 'ε', '|', '*', '+', '\\']
+                    // The operators of extended regexes are escaped as well, so that the string can be parsed
+                    // either way
+                    if g.chars().count() == 1
+                        && ['(', ')', '∅', 'ε', '|', '*', '+', '\\', '&', '~', '¬']
                             .contains(&g.chars().next().unwrap())
                     {
                         acc.push('\\');
@@ -208,103 +658,485 @@ impl Regex {
         }
     }
 
-    /// We turn a tree to a NFA recursively. `counter` is used to get the number of the next state.
-    /// `char_idx` gives the index of a given character in the alphabet (and inserts the character
-    /// if it didn't exist already). `send_to` is the state that the subtree should transition to
-    /// if successful.
+    /// We turn a tree to a NFA by visiting its subtrees depth-first. `counter` is used to get the number of the next
+    /// state. `char_idx` gives the index of a given character in the alphabet (and inserts the character if it didn't
+    /// exist already). `send_to` is the state that the tree should transition to if successful. If `debug` is
+    /// `Some`, the role of every created state is recorded in it, in the order the states are numbered.
+    ///
+    /// The states are numbered in the order they are created, and since the tree may be arbitrarily deep, the
+    /// subtrees still to visit are kept in an explicit stack of [NfaFrame]s instead of recursing. The transitions of
+    /// a state which depend on later states are filled in when returning to its frame.
     fn tree_to_nfa(
         tree: RegexTree,
         counter: &mut StateCounter,
         grapheme_idx: &mut impl FnMut(Rc<str>) -> usize,
         send_to: usize,
+        debug: &mut Option<DebugRecorder>,
     ) -> Vec<NfaState> {
-        let incoming_state_idx = counter.next();
-        let mut incoming_state = NfaState {
-            name: Rc::from(incoming_state_idx.to_string()),
-            initial: false,
-            accepting: false,
-            epsilon_transitions: vec![],
-            transitions: vec![],
-        };
+        // The index of the first state, so that the state with index `idx` is `states[idx - first]`
+        let first = counter.peek();
+        let mut states: Vec<NfaState> = vec![];
+        let mut stack = vec![NfaFrame::Enter { tree, send_to }];
 
-        match tree {
-            RegexTree::Sequence(seq) => {
-                if seq.is_empty() {
-                    incoming_state.epsilon_transitions.push(send_to);
-                    vec![incoming_state]
-                } else {
-                    incoming_state.epsilon_transitions.push(counter.state + 1);
-                    let seq_len = seq.len();
-                    let mut states = seq
-                        .into_iter()
-                        .enumerate()
-                        .flat_map(|(idx, subtree)| {
-                            let after_state_idx = counter.next();
-                            let mut after_state = NfaState {
-                                name: Rc::from(after_state_idx.to_string()),
-                                initial: false,
-                                accepting: false,
-                                epsilon_transitions: vec![],
-                                transitions: vec![],
-                            };
-                            let new_states =
-                                Self::tree_to_nfa(subtree, counter, grapheme_idx, after_state_idx);
-                            if idx + 1 == seq_len {
-                                after_state.epsilon_transitions.push(send_to);
+        while let Some(frame) = stack.pop() {
+            match frame {
+                NfaFrame::Enter { tree, send_to } => {
+                    if let Some(recorder) = debug {
+                        let tree_ref = recorder.tree_ref(&tree);
+                        recorder.roles.push(StateRole::Enter(tree_ref));
+                    }
+                    let incoming_state_idx = counter.next();
+                    states.push(unconnected_state(incoming_state_idx));
+                    let incoming_state = states.last_mut().unwrap();
+
+                    match tree {
+                        RegexTree::Sequence(seq) => {
+                            if seq.is_empty() {
+                                incoming_state.epsilon_transitions.push(send_to);
                             } else {
-                                after_state.epsilon_transitions.push(counter.state + 1);
+                                incoming_state.epsilon_transitions.push(counter.state + 1);
+                                stack.push(NfaFrame::Sequence {
+                                    items: seq.into_iter().enumerate(),
+                                    send_to,
+                                    after_state: None,
+                                });
+                            }
+                        }
+                        RegexTree::Alt(alt) => stack.push(NfaFrame::Alt {
+                            items: alt.into_iter().enumerate(),
+                            incoming_state_idx,
+                            send_to,
+                            entered: false,
+                        }),
+                        RegexTree::Repeat(r) => {
+                            incoming_state.epsilon_transitions = vec![counter.peek(), send_to];
+                            if let Some(recorder) = debug {
+                                recorder.path.push(RegexTreeStep::Repeat);
+                            }
+                            stack.push(NfaFrame::PopPath);
+                            stack.push(NfaFrame::Enter {
+                                tree: *r,
+                                send_to: incoming_state_idx,
+                            });
+                        }
+                        RegexTree::Char(c) => match c {
+                            RegexChar::Grapheme(g) => {
+                                // If we only accept one char, make sure our incoming state
+                                // transition to outgoing state on that char only
+                                let cidx = grapheme_idx(g); // our character index
+
+                                // if we get index 1, we want {{}, {target}} in our transition table
+                                let mut transition_vec = vec![vec![]; cidx];
+                                transition_vec.push(vec![send_to]);
+                                incoming_state.transitions = transition_vec;
+                            }
+                            RegexChar::Epsilon => {
+                                // If we accept epsilon char, just transition to send to immediately
+                                incoming_state.epsilon_transitions = vec![send_to];
                             }
-                            iter::once(after_state).chain(new_states)
-                        })
-                        .collect::<Vec<_>>();
-                    let mut ret = vec![incoming_state];
-                    ret.append(&mut states);
-                    ret
+                            RegexChar::Empty => {}
+                        },
+                        RegexTree::Intersection(_) | RegexTree::Complement(_) => {
+                            unreachable!("extended regexes are compiled with Regex::to_dfa")
+                        }
+                    }
                 }
-            }
-            RegexTree::Alt(alt) => {
-                let mut additional = alt
-                    .into_iter()
-                    .flat_map(|tree| {
-                        incoming_state.epsilon_transitions.push(counter.peek());
-                        Self::tree_to_nfa(tree, counter, grapheme_idx, send_to)
-                    })
-                    .collect::<Vec<_>>();
-                let mut ret = Vec::with_capacity(1 + additional.len());
-                ret.push(incoming_state);
-                ret.append(&mut additional);
-                ret
-            }
-            RegexTree::Repeat(r) => {
-                incoming_state.epsilon_transitions = vec![counter.peek(), send_to];
-                let mut additional =
-                    Self::tree_to_nfa(*r, counter, grapheme_idx, incoming_state_idx);
-                let mut ret = Vec::with_capacity(additional.len() + 1);
-                ret.push(incoming_state);
-                ret.append(&mut additional);
-                ret
-            }
-            RegexTree::Char(c) => match c {
-                RegexChar::Grapheme(g) => {
-                    // If we only accept one char, make sure our incoming state
-                    // transition to outgoing state on that char only
-                    let cidx = grapheme_idx(g); // our character index
-
-                    // if we get index 1, we want {{}, {target}} in our transition table
-                    let mut transition_vec = vec![vec![]; cidx];
-                    transition_vec.push(vec![send_to]);
-                    incoming_state.transitions = transition_vec;
-                    vec![incoming_state]
+                NfaFrame::Sequence {
+                    mut items,
+                    send_to,
+                    after_state,
+                } => {
+                    // The previous element (if any) is done, so its after state can be connected to the next one
+                    if let Some(after_state_idx) = after_state {
+                        if let Some(recorder) = debug {
+                            recorder.path.pop();
+                        }
+                        let next = if items.len() == 0 {
+                            send_to
+                        } else {
+                            counter.state + 1
+                        };
+                        states[after_state_idx - first]
+                            .epsilon_transitions
+                            .push(next);
+                    }
+                    if let Some((idx, subtree)) = items.next() {
+                        if let Some(recorder) = debug {
+                            recorder.path.push(RegexTreeStep::Sequence(idx));
+                            let tree_ref = recorder.tree_ref(&subtree);
+                            recorder.roles.push(StateRole::Exit(tree_ref));
+                        }
+                        let after_state_idx = counter.next();
+                        states.push(unconnected_state(after_state_idx));
+                        stack.push(NfaFrame::Sequence {
+                            items,
+                            send_to,
+                            after_state: Some(after_state_idx),
+                        });
+                        stack.push(NfaFrame::Enter {
+                            tree: subtree,
+                            send_to: after_state_idx,
+                        });
+                    }
                 }
-                RegexChar::Epsilon => {
-                    // If we accept epsilon char, just transition to send to immediately
-                    incoming_state.epsilon_transitions = vec![send_to];
-                    vec![incoming_state]
+                NfaFrame::Alt {
+                    mut items,
+                    incoming_state_idx,
+                    send_to,
+                    entered,
+                } => {
+                    if entered {
+                        if let Some(recorder) = debug {
+                            recorder.path.pop();
+                        }
+                    }
+                    if let Some((idx, tree)) = items.next() {
+                        states[incoming_state_idx - first]
+                            .epsilon_transitions
+                            .push(counter.peek());
+                        if let Some(recorder) = debug {
+                            recorder.path.push(RegexTreeStep::Alt(idx));
+                        }
+                        stack.push(NfaFrame::Alt {
+                            items,
+                            incoming_state_idx,
+                            send_to,
+                            entered: true,
+                        });
+                        stack.push(NfaFrame::Enter { tree, send_to });
+                    }
                 }
-                RegexChar::Empty => {
-                    vec![incoming_state]
+                NfaFrame::PopPath => {
+                    if let Some(recorder) = debug {
+                        recorder.path.pop();
+                    }
                 }
+            }
+        }
+        states
+    }
+}
+
+/// A step of [Regex::tree_to_nfa] still to be done
+enum NfaFrame {
+    /// Create the states of a tree, which transitions to `send_to` when successful
+    Enter { tree: RegexTree, send_to: usize },
+    /// Create the states of the remaining elements of a sequence. `after_state` is the index of the state reached
+    /// after the previous element, if there is one, which is connected to the next element (or `send_to`) here.
+    Sequence {
+        items: iter::Enumerate<std::vec::IntoIter<RegexTree>>,
+        send_to: usize,
+        after_state: Option<usize>,
+    },
+    /// Create the states of the remaining alternatives, each of which the state with index `incoming_state_idx` has
+    /// an epsilon move to. `entered` is whether an alternative has been entered before.
+    Alt {
+        items: iter::Enumerate<std::vec::IntoIter<RegexTree>>,
+        incoming_state_idx: usize,
+        send_to: usize,
+        entered: bool,
+    },
+    /// Leave the subtree of a [RegexTree::Repeat] in the path of the [DebugRecorder]
+    PopPath,
+}
+
+/// A state named by its index, which is neither initial nor accepting and has no transitions (yet)
+fn unconnected_state(idx: usize) -> NfaState {
+    NfaState {
+        name: Rc::from(idx.to_string()),
+        initial: false,
+        accepting: false,
+        epsilon_transitions: vec![],
+        transitions: vec![],
+    }
+}
+
+/// The reasons why a [Regex] can't be converted to a NFA over a given alphabet by [Regex::to_nfa_with_alphabet]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RegexAlphabetError {
+    /// An element of the regex which isn't in the alphabet
+    #[error("the element '{0}' of the regex is not in the alphabet")]
+    UnknownElement(Rc<str>),
+    /// An element of the alphabet which is empty or starts or ends with whitespace
+    #[error("the element '{0}' is empty or starts or ends with whitespace")]
+    InvalidElement(Rc<str>),
+    /// The alphabet contains the same element more than once
+    #[error("the element '{0}' occurs more than once in the alphabet")]
+    DuplicateElement(Rc<str>),
+}
+
+/// Adds the elements of `alphabet` which aren't in the alphabet of the NFA to it, without any transitions on them
+pub(crate) fn widen_alphabet(nfa: &mut Nfa, alphabet: &[Rc<str>]) {
+    for elem in alphabet {
+        if !nfa.alphabet.contains(elem) {
+            nfa.alphabet = nfa.alphabet.iter().chain([elem]).cloned().collect();
+            nfa.states
+                .iter_mut()
+                .for_each(|state| state.transitions.push(vec![]));
+        }
+    }
+    nfa.alphabet_lookup = AlphabetIndex::default();
+}
+
+/// The number of states after each step of converting a regex to a minimal DFA, created by
+/// [Regex::to_min_dfa_with_report]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileReport {
+    /// The number of states of the NFA created from the regex, before it is optimized
+    pub nfa_states: usize,
+    /// The number of states of the DFA created from the optimized NFA, before it is minimized
+    pub dfa_states: usize,
+    /// The number of states of the minimized DFA
+    pub min_dfa_states: usize,
+}
+
+impl Display for CompileReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Compiled to {} NFA states, {} DFA states and {} minimal DFA states",
+            self.nfa_states, self.dfa_states, self.min_dfa_states
+        )
+    }
+}
+
+impl Display for Regex {
+    /// Formats the regex in the syntax accepted by the parser. *The exact output is subject to change*
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut acc = String::new();
+        Self::build_string(&self.tree, &mut acc);
+        f.write_str(&acc)
+    }
+}
+
+/// Regexes are equal if they match the same language, see [Regex::language_eq]. Note that this converts both regexes
+/// to DFAs, which may take time exponential in the size of the regexes, so comparing regexes with `==` is much more
+/// expensive than comparing their syntax trees with [Regex::structurally_eq].
+impl PartialEq for Regex {
+    /// Checks if the regexes match the same language, see [Regex::language_eq]
+    fn eq(&self, other: &Self) -> bool {
+        self.language_eq(other)
+    }
+}
+
+impl Eq for Regex {}
+
+impl PartialOrd for Regex {
+    /// Orders regexes by language inclusion, that is, `r1 <= r2` if and only if every word matched by `r1` is also
+    /// matched by `r2`. Regexes where neither language is a subset of the other are incomparable. Note that this
+    /// converts both regexes to DFAs, which may take time exponential in the size of the regexes.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (this, other) = self.to_dfas_over_shared_alphabet(other);
+        let subset = !this.difference(&other)?.has_reachable_accepting_state();
+        let superset = !other.difference(&this)?.has_reachable_accepting_state();
+        match (subset, superset) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Information about which part of a regular expression each state of a NFA corresponds to, as returned by
+/// [Regex::to_nfa_debug]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugInfo {
+    roles: Vec<StateRole>,
+}
+
+impl DebugInfo {
+    /// Gets the role of the state with the given index in the NFA, or `None` if there is no such state
+    pub fn role(&self, state_idx: usize) -> Option<&StateRole> {
+        self.roles.get(state_idx)
+    }
+
+    /// Gets the roles of all states, indexed by the index of the state in the NFA
+    pub fn roles(&self) -> &[StateRole] {
+        &self.roles
+    }
+}
+
+/// The role a state of a NFA converted from a regular expression has
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateRole {
+    /// The single accepting state of the NFA, which every complete match ends up in
+    Accepting,
+    /// The initial state of the NFA, which has an epsilon move to the state entering the whole tree
+    Initial,
+    /// The state entering the referenced subtree
+    Enter(RegexTreeRef),
+    /// The state reached after matching the referenced subtree, which is an element of a sequence
+    Exit(RegexTreeRef),
+}
+
+impl Display for StateRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StateRole::Accepting => write!(f, "accepting"),
+            StateRole::Initial => write!(f, "initial"),
+            StateRole::Enter(tree_ref) => write!(f, "enter {tree_ref}"),
+            StateRole::Exit(tree_ref) => write!(f, "exit {tree_ref}"),
+        }
+    }
+}
+
+/// A reference to a subtree of a [RegexTree], given by the path from the root of the tree down to the subtree, along
+/// with what kind of node the subtree is. It is displayed as the steps of the path followed by the node, for example
+/// `Alt[0].Sequence[2].Char('a')`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegexTreeRef {
+    path: Vec<RegexTreeStep>,
+    node: RegexNode,
+}
+
+impl RegexTreeRef {
+    /// Gets the steps from the root of the tree down to the subtree
+    pub fn path(&self) -> &[RegexTreeStep] {
+        &self.path
+    }
+
+    /// Gets the kind of node the subtree is
+    pub fn node(&self) -> &RegexNode {
+        &self.node
+    }
+}
+
+impl Display for RegexTreeRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.path.iter().try_for_each(|step| write!(f, "{step}."))?;
+        write!(f, "{}", self.node)
+    }
+}
+
+/// One step down a [RegexTree]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegexTreeStep {
+    /// Into the element with the given index of a [RegexTree::Sequence]
+    Sequence(usize),
+    /// Into the alternative with the given index of a [RegexTree::Alt]
+    Alt(usize),
+    /// Into the repeated subtree of a [RegexTree::Repeat]
+    Repeat,
+}
+
+impl Display for RegexTreeStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexTreeStep::Sequence(idx) => write!(f, "Sequence[{idx}]"),
+            RegexTreeStep::Alt(idx) => write!(f, "Alt[{idx}]"),
+            RegexTreeStep::Repeat => write!(f, "Repeat"),
+        }
+    }
+}
+
+/// The kind of a node in a [RegexTree], without its children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexNode {
+    Sequence,
+    Alt,
+    Repeat,
+    Char(RegexChar),
+    Intersection,
+    Complement,
+}
+
+impl Display for RegexNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexNode::Sequence => write!(f, "Sequence"),
+            RegexNode::Alt => write!(f, "Alt"),
+            RegexNode::Repeat => write!(f, "Repeat"),
+            RegexNode::Char(RegexChar::Grapheme(g)) => write!(f, "Char('{g}')"),
+            RegexNode::Char(RegexChar::Epsilon) => write!(f, "Char(ε)"),
+            RegexNode::Char(RegexChar::Empty) => write!(f, "Char(∅)"),
+            RegexNode::Intersection => write!(f, "Intersection"),
+            RegexNode::Complement => write!(f, "Complement"),
+        }
+    }
+}
+
+/// What kind of part of the Thompson construction a state of a NFA converted from a regular expression is, as given
+/// by [Regex::to_nfa_annotated]. Every subtree of the regex gets a state entering it, and every element of a
+/// sequence gets a state reached after matching it, which is joined to the next element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegexProvenance {
+    /// The initial state of the NFA, which has an epsilon move to the state entering the whole regex
+    Initial,
+    /// The single accepting state of the NFA, which every complete match ends up in
+    Accepting,
+    /// The state entering a sequence, which has an epsilon move to the state after its first element
+    SequenceEntry,
+    /// The state entering a sequence which is the alternative with the given index of an alternation
+    AltBranch { index: usize },
+    /// The state reached after matching the element with the given index of a sequence, which is nested in `depth`
+    /// other sequences, alternations or repetitions
+    SequenceJoint { index: usize, depth: usize },
+    /// The state entering an alternation, which has epsilon moves to each of its alternatives
+    AltEntry,
+    /// The state entering a Kleene star, which has epsilon moves to its subtree and out of the star, and which the
+    /// subtree returns to
+    RepeatEntry,
+    /// The state transitioning on a single grapheme
+    CharIncoming { grapheme: Rc<str> },
+    /// The state of an `ε`, which has an epsilon move out of it
+    EpsilonIncoming,
+    /// The state of an `∅`, which has no transitions at all
+    EmptyIncoming,
+    /// A state of a NFA compiled from an extended regex, which doesn't correspond to any part of the regex
+    Compiled,
+}
+
+impl RegexProvenance {
+    fn of_role(role: &StateRole) -> Self {
+        match role {
+            StateRole::Initial => RegexProvenance::Initial,
+            StateRole::Accepting => RegexProvenance::Accepting,
+            StateRole::Exit(tree_ref) => match tree_ref.path.split_last() {
+                Some((&RegexTreeStep::Sequence(index), outer)) => RegexProvenance::SequenceJoint {
+                    index,
+                    depth: outer.len(),
+                },
+                _ => unreachable!("exit states are only created for elements of sequences"),
+            },
+            StateRole::Enter(tree_ref) => match &tree_ref.node {
+                RegexNode::Sequence => match tree_ref.path.last() {
+                    Some(&RegexTreeStep::Alt(index)) => RegexProvenance::AltBranch { index },
+                    _ => RegexProvenance::SequenceEntry,
+                },
+                RegexNode::Alt => RegexProvenance::AltEntry,
+                RegexNode::Repeat => RegexProvenance::RepeatEntry,
+                RegexNode::Char(RegexChar::Grapheme(grapheme)) => RegexProvenance::CharIncoming {
+                    grapheme: grapheme.clone(),
+                },
+                RegexNode::Char(RegexChar::Epsilon) => RegexProvenance::EpsilonIncoming,
+                RegexNode::Char(RegexChar::Empty) => RegexProvenance::EmptyIncoming,
+                RegexNode::Intersection | RegexNode::Complement => RegexProvenance::Compiled,
             },
         }
     }
 }
+
+/// Keeps track of the current path in the tree and the roles of all states created so far during
+/// [Regex::to_nfa_debug]
+struct DebugRecorder {
+    path: Vec<RegexTreeStep>,
+    roles: Vec<StateRole>,
+}
+
+impl DebugRecorder {
+    fn tree_ref(&self, tree: &RegexTree) -> RegexTreeRef {
+        let node = match tree {
+            RegexTree::Sequence(_) => RegexNode::Sequence,
+            RegexTree::Alt(_) => RegexNode::Alt,
+            RegexTree::Repeat(_) => RegexNode::Repeat,
+            RegexTree::Char(c) => RegexNode::Char(c.clone()),
+            RegexTree::Intersection(_) => RegexNode::Intersection,
+            RegexTree::Complement(_) => RegexNode::Complement,
+        };
+        RegexTreeRef {
+            path: self.path.clone(),
+            node,
+        }
+    }
+}
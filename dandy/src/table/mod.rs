@@ -1,5 +1,7 @@
+use std::borrow::Cow;
 use std::cmp::max;
 use std::iter;
+use std::rc::Rc;
 
 #[derive(Default, Debug, Clone)]
 pub struct Table<'a> {
@@ -49,3 +51,46 @@ impl<'a> Table<'a> {
             .join("\n")
     }
 }
+
+/// Surrounds the text of a cell (a name of a state or an element of the alphabet) with double quotes if it contains
+/// whitespace, `#`, `{` or `}`, or starts with a double quote, so that the parser reads it as one cell again. Double
+/// quotes inside quoted cells are written twice. Line breaks can't be written in cells at all.
+pub fn quote_cell(text: &str) -> Cow<'_, str> {
+    if text.starts_with('"') || text.contains(|c: char| c.is_whitespace() || "#{}".contains(c)) {
+        Cow::Owned(format!("\"{}\"", text.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Generates the edge list of an automaton (the format of [parser::nfa_edges](crate::parser::nfa_edges)), given its
+/// alphabet, its states as their names and whether they are initial and accepting, and its edges as the indices of
+/// their source and target states and their element (where `None` is ε)
+pub(crate) fn edge_list<'a>(
+    alphabet: &[Rc<str>],
+    states: impl Iterator<Item = (&'a str, bool, bool)>,
+    edges: impl Iterator<Item = (usize, Option<&'a str>, usize)>,
+) -> String {
+    let mut names = vec![];
+    let mut lines = vec![iter::once("alphabet:".into())
+        .chain(alphabet.iter().map(|elem| quote_cell(elem)))
+        .collect::<Vec<_>>()
+        .join(" ")];
+    for (name, initial, accepting) in states {
+        let name = quote_cell(name);
+        let mut line = format!("state {name}");
+        if initial {
+            line.push_str(" initial");
+        }
+        if accepting {
+            line.push_str(" accepting");
+        }
+        lines.push(line);
+        names.push(name);
+    }
+    lines.extend(edges.map(|(from, elem, to)| {
+        let arrow = format!("-{}->", elem.unwrap_or("ε"));
+        format!("{} {} {}", names[from], quote_cell(&arrow), names[to])
+    }));
+    lines.join("\n")
+}
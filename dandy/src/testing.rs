@@ -0,0 +1,322 @@
+//! # Testing
+//! Assertions for comparing the languages of automata in tests. [assert_equivalent](crate::assert_equivalent) and
+//! [assert_not_equivalent](crate::assert_not_equivalent) work like [assert_eq] and [assert_ne], but compare
+//! [DFAs](crate::dfa::Dfa), [NFAs](crate::nfa::Nfa) or [regexes](crate::regex::Regex) by language, and when they
+//! fail, they print both automata and (if there is one) a word accepted by only one of them:
+//! ```
+//! use dandy::{assert_equivalent, assert_not_equivalent};
+//! use dandy::dfa::Dfa;
+//!
+//! let odd_as: Dfa = dandy::parser::dfa("
+//!        a  b
+//!   -> s1 s2 s1
+//!    * s2 s1 s2
+//! ").unwrap().try_into().unwrap();
+//! let mut minimized = odd_as.clone();
+//! minimized.minimize();
+//! assert_equivalent!(odd_as, minimized);
+//!
+//! let mut even_as = odd_as.clone();
+//! even_as.invert();
+//! assert_not_equivalent!(odd_as, even_as, "inverting should change the language");
+//! ```
+//!
+//! ## Snapshots
+//! With the `testing` feature, [assert_table_snapshot](crate::assert_table_snapshot) and
+//! [assert_ascii_table_snapshot](crate::assert_ascii_table_snapshot) compare the table of a DFA or NFA with a snapshot
+//! stored in a file, to catch unintended changes to the formatting of tables. A missing snapshot is written by the
+//! assertion, and `UPDATE_SNAPSHOTS=1` overwrites snapshots which don't match. Otherwise, the assertion panics with a
+//! unified diff between the snapshot and the table.
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::regex::Regex;
+use std::fmt::Display;
+#[cfg(any(test, feature = "testing"))]
+use std::{env, fs, path::Path};
+
+/// Automata whose languages can be compared by the assertions in this module
+pub trait Equivalence: Display {
+    /// Checks if this automaton accepts the same language as another automaton
+    fn equivalent(&self, other: &Self) -> bool;
+
+    /// Finds a word accepted by exactly one of this automaton and another automaton, together with whether it is
+    /// accepted by this automaton. This gives `None` if there is no such word or if the automata can't be compared
+    /// (for example because their alphabets are unequal).
+    fn distinguishing_word(&self, other: &Self) -> Option<(String, bool)>;
+}
+
+impl Equivalence for Dfa {
+    fn equivalent(&self, other: &Self) -> bool {
+        self.equivalent_to(other)
+    }
+
+    fn distinguishing_word(&self, other: &Self) -> Option<(String, bool)> {
+        let word = self
+            .symmetric_difference(other)?
+            .to_nfa()
+            .word_components()
+            .next()?;
+        let word = word.iter().map(|elem| elem.as_ref()).collect::<Vec<_>>();
+        Some((word.concat(), self.accepts(&word)))
+    }
+}
+
+impl Equivalence for Nfa {
+    fn equivalent(&self, other: &Self) -> bool {
+        self.equivalent_to(other)
+    }
+
+    fn distinguishing_word(&self, other: &Self) -> Option<(String, bool)> {
+        self.to_dfa().distinguishing_word(&other.to_dfa())
+    }
+}
+
+impl Equivalence for Regex {
+    fn equivalent(&self, other: &Self) -> bool {
+        self.language_eq(other)
+    }
+
+    fn distinguishing_word(&self, other: &Self) -> Option<(String, bool)> {
+        let (this, other) = self.to_dfas_over_shared_alphabet(other);
+        this.distinguishing_word(&other)
+    }
+}
+
+/// Checks if two automata are equivalent (if `expected` is true) or not equivalent (if `expected` is false), and if
+/// not, gives a message describing the failure with both automata and a distinguishing word, if there is one. This
+/// is used by [assert_equivalent](crate::assert_equivalent) and [assert_not_equivalent](crate::assert_not_equivalent).
+pub fn check_equivalence<A: Equivalence>(
+    left: &A,
+    right: &A,
+    expected: bool,
+) -> Result<(), String> {
+    if left.equivalent(right) == expected {
+        return Ok(());
+    }
+    let mut message = if expected {
+        "assertion failed: automata are not equivalent".to_string()
+    } else {
+        "assertion failed: automata are equivalent".to_string()
+    };
+    message.push_str(&format!("\n left:\n{left}\n right:\n{right}"));
+    if let Some((word, accepted_by_left)) = left.distinguishing_word(right) {
+        let side = if accepted_by_left { "left" } else { "right" };
+        message.push_str(&format!(
+            "\n distinguishing word: {word:?} (accepted by {side} only)"
+        ));
+    }
+    Err(message)
+}
+
+/// Asserts that two automata ([Dfa](crate::dfa::Dfa)s, [Nfa](crate::nfa::Nfa)s or [Regex](crate::regex::Regex)es)
+/// are equivalent, that is, accept the same language. On failure, this panics with both automata and a word
+/// accepted by only one of them, if there is one. Like [assert_eq], a custom message may be given after the automata.
+///
+/// ```should_panic
+/// use dandy::assert_equivalent;
+///
+/// let a = dandy::parser::regex("a*").unwrap();
+/// let b = dandy::parser::regex("a+").unwrap();
+/// // Panics with the distinguishing word "" (accepted by left only)
+/// assert_equivalent!(a, b);
+/// ```
+#[macro_export]
+macro_rules! assert_equivalent {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_equivalence(&$left, &$right, true) {
+            panic!("{}", message);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Err(message) = $crate::testing::check_equivalence(&$left, &$right, true) {
+            panic!("{}\n message: {}", message, format_args!($($arg)+));
+        }
+    };
+}
+
+/// Asserts that two automata ([Dfa](crate::dfa::Dfa)s, [Nfa](crate::nfa::Nfa)s or [Regex](crate::regex::Regex)es)
+/// are not equivalent, that is, that there is some word accepted by only one of them. On failure, this panics with
+/// both automata. Like [assert_ne], a custom message may be given after the automata.
+#[macro_export]
+macro_rules! assert_not_equivalent {
+    ($left:expr, $right:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_equivalence(&$left, &$right, false) {
+            panic!("{}", message);
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        if let Err(message) = $crate::testing::check_equivalence(&$left, &$right, false) {
+            panic!("{}\n message: {}", message, format_args!($($arg)+));
+        }
+    };
+}
+
+/// The environment variable which makes the snapshot assertions overwrite snapshots which don't match when set to `1`
+#[cfg(any(test, feature = "testing"))]
+const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// The number of unchanged lines shown around the changed lines of a snapshot diff
+#[cfg(any(test, feature = "testing"))]
+const DIFF_CONTEXT: usize = 3;
+
+/// Compares `actual` with the snapshot stored at `path`, writing the snapshot (and the directories it is in) if it
+/// doesn't exist yet or if the environment variable `UPDATE_SNAPSHOTS` is `1`. Otherwise, if they differ, this gives a
+/// message with a unified diff from the snapshot to `actual`. Line endings of the snapshot are read as `\n`, so a
+/// snapshot checked out with `\r\n` line endings still matches. This is used by
+/// [assert_table_snapshot](crate::assert_table_snapshot) and
+/// [assert_ascii_table_snapshot](crate::assert_ascii_table_snapshot).
+#[cfg(any(test, feature = "testing"))]
+pub fn check_snapshot(actual: &str, path: &Path) -> Result<(), String> {
+    let update = env::var(UPDATE_SNAPSHOTS).is_ok_and(|value| value == "1");
+    if update || !path.exists() {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+        }
+        return fs::write(path, actual)
+            .map_err(|e| format!("failed to write snapshot {}: {e}", path.display()));
+    }
+    let expected = fs::read_to_string(path)
+        .map_err(|e| format!("failed to read snapshot {}: {e}", path.display()))?
+        .replace("\r\n", "\n");
+    if expected == actual {
+        return Ok(());
+    }
+    let diff = unified_diff(&expected, actual);
+    let diff = if diff.is_empty() {
+        "(the snapshot and the output only differ in the trailing newline)\n".to_string()
+    } else {
+        diff
+    };
+    Err(format!(
+        "snapshot {path} doesn't match the output (rerun with {UPDATE_SNAPSHOTS}=1 to update it)\n\
+         --- {path}\n+++ output\n{diff}",
+        path = path.display()
+    ))
+}
+
+/// Gives the hunks of a unified diff from `old` to `new` by lines, found from their longest common subsequence, or an
+/// empty string if their lines are equal
+#[cfg(any(test, feature = "testing"))]
+pub(crate) fn unified_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    // Every line of the diff, with the numbers of the old and new lines before it
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        let line = if i < old.len() && j < new.len() && old[i] == new[j] {
+            (' ', old[i])
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            ('-', old[i])
+        } else {
+            ('+', new[j])
+        };
+        lines.push((line, i, j));
+        match line.0 {
+            ' ' => (i, j) = (i + 1, j + 1),
+            '-' => i += 1,
+            _ => j += 1,
+        }
+    }
+
+    let changes = (0..lines.len())
+        .filter(|&idx| lines[idx].0 .0 != ' ')
+        .collect::<Vec<_>>();
+    let mut diff = String::new();
+    let mut next = 0;
+    while next < changes.len() {
+        // Changes with at most twice the context between them are shown in the same hunk
+        let first = changes[next];
+        while next + 1 < changes.len() && changes[next + 1] - changes[next] <= 2 * DIFF_CONTEXT + 1
+        {
+            next += 1;
+        }
+        let hunk =
+            first.saturating_sub(DIFF_CONTEXT)..(changes[next] + DIFF_CONTEXT + 1).min(lines.len());
+        next += 1;
+
+        let (_, old_start, new_start) = lines[hunk.start];
+        let old_len = lines[hunk.clone()]
+            .iter()
+            .filter(|((kind, _), _, _)| *kind != '+')
+            .count();
+        let new_len = lines[hunk.clone()]
+            .iter()
+            .filter(|((kind, _), _, _)| *kind != '-')
+            .count();
+        // Empty ranges are given by the line before them, as in other unified diffs
+        let range = |start: usize, len: usize| {
+            format!("{},{len}", if len == 0 { start } else { start + 1 })
+        };
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_len),
+            range(new_start, new_len)
+        ));
+        for ((kind, line), _, _) in &lines[hunk] {
+            diff.push_str(&format!("{kind}{line}\n"));
+        }
+    }
+    diff
+}
+
+/// Asserts that the [table](crate::dfa::Dfa::to_table) of a DFA or NFA matches the snapshot stored in a file, whose
+/// path is relative to the root of the package being tested (where its `Cargo.toml` is). The snapshot is written if it
+/// doesn't exist yet, or if the environment variable `UPDATE_SNAPSHOTS` is `1`. Otherwise, this panics with a unified
+/// diff from the snapshot to the table if they differ. Requires the `testing` feature.
+///
+/// ```no_run
+/// use dandy::assert_table_snapshot;
+/// use dandy::dfa::Dfa;
+///
+/// let dfa: Dfa = dandy::parser::dfa("
+///        a  b
+///   -> s1 s2 s1
+///    * s2 s1 s2
+/// ").unwrap().try_into().unwrap();
+/// assert_table_snapshot!(dfa, "tests/snapshots/odd_as.txt");
+/// ```
+#[cfg(any(test, feature = "testing"))]
+#[macro_export]
+macro_rules! assert_table_snapshot {
+    ($automaton:expr, $path:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_snapshot(
+            &$automaton.to_table(),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        ) {
+            panic!("{}", message);
+        }
+    };
+}
+
+/// Asserts that the [ASCII table](crate::dfa::Dfa::ascii_table) of a DFA or NFA matches the snapshot stored in a file,
+/// just like [assert_table_snapshot](crate::assert_table_snapshot) does for the table. Requires the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+#[macro_export]
+macro_rules! assert_ascii_table_snapshot {
+    ($automaton:expr, $path:expr $(,)?) => {
+        if let Err(message) = $crate::testing::check_snapshot(
+            &$automaton.ascii_table(),
+            &::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        ) {
+            panic!("{}", message);
+        }
+    };
+}
+
+#[cfg(any(test, feature = "testing"))]
+pub use crate::{assert_ascii_table_snapshot, assert_table_snapshot};
@@ -1,12 +1,14 @@
-use crate::dfa::{Dfa, DfaState};
-use crate::nfa::{Nfa, NfaState};
+use crate::dfa::{BfsEvent, Dfa, DfaState, TransitionMismatch};
+use crate::nfa::{IndexRemap, Nfa, NfaState};
+use crate::util::{AlphabetIndex, Provenance, ReverseIndex, Version};
 use crate::*;
 use ::regex::Regex as LibRegex;
 use proptest::prelude::*;
 use rand::prelude::*;
-use std::collections::HashSet;
-use std::ops::RangeInclusive;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::{ControlFlow, RangeInclusive};
 use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
 
 struct MultipleCounterIter {
     state: Vec<usize>,
@@ -75,6 +77,2666 @@ fn test_subset_construction() {
     assert!(dfa.equivalent_to(&converted));
 }
 
+/// Compiles a DFA from a string it owns, which requires an owned error to be able to use `?`
+fn compile_owned_dfa(source: String) -> Result<Dfa, dfa::DfaParseErrorOwned> {
+    let parsed = parser::dfa(&source).unwrap();
+    Ok(parsed.try_into()?)
+}
+
+/// Compiles a NFA from a string it owns, which requires an owned error to be able to use `?`
+fn compile_owned_nfa(source: String) -> Result<Nfa, nfa::NfaParseErrorOwned> {
+    let parsed = parser::nfa(&source).unwrap();
+    Ok(parsed.try_into()?)
+}
+
+#[test]
+fn owned_parse_errors() {
+    let source = "  a b\n-> s s t";
+    let borrowed = Dfa::try_from(parser::dfa(source).unwrap()).unwrap_err();
+    let owned = compile_owned_dfa(source.to_string()).unwrap_err();
+    assert_eq!(owned, borrowed.to_owned());
+    assert_eq!(owned.to_string(), borrowed.to_string());
+    assert_eq!(
+        owned,
+        dfa::DfaParseErrorOwned::TransitionDoesNotExist("s".to_string(), "t".to_string())
+    );
+    let boxed: Box<dyn std::error::Error> = Box::new(owned);
+    assert_eq!(
+        boxed.to_string(),
+        "State 't' does not exist (in transition from state 's')"
+    );
+
+    let source = "  a b\n s {s} {s}";
+    let borrowed = Nfa::try_from(parser::nfa(source).unwrap()).unwrap_err();
+    let owned = compile_owned_nfa(source.to_string()).unwrap_err();
+    assert_eq!(owned, nfa::NfaParseErrorOwned::MissingInitialState);
+    assert_eq!(owned.to_string(), borrowed.to_string());
+}
+
+#[cfg(feature = "provenance")]
+#[test]
+fn provenance() {
+    let nfa: Nfa = parser::nfa(include_str!("../tests/test_files/nfa1.nfa"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(nfa.provenance(), None);
+    let dfa = nfa.to_dfa();
+    assert_eq!(
+        dfa.provenance(),
+        Some("subset construction from parsed NFA")
+    );
+    let product = dfa.intersection(&dfa).unwrap();
+    assert_eq!(
+        product.provenance(),
+        Some(
+            "product of (DFA created by subset construction from parsed NFA, \
+            DFA created by subset construction from parsed NFA)"
+        )
+    );
+    // Provenance doesn't affect equality
+    let reparsed: Dfa = parser::dfa(&dfa.to_table()).unwrap().try_into().unwrap();
+    assert_eq!(reparsed.provenance(), None);
+    assert_eq!(reparsed, dfa);
+    let regex_nfa = parser::regex("a|b").unwrap().to_nfa();
+    assert_eq!(
+        regex_nfa.provenance(),
+        Some("Thompson construction from regex (a|b)")
+    );
+    assert_eq!(regex_nfa, parser::regex("a|b").unwrap().to_nfa());
+
+    // Descriptions don't grow without bound when many operations are applied in a row
+    let union = (0..50).fold(dfa.clone(), |acc, _| acc.union(&dfa).unwrap());
+    let provenance = union.provenance().unwrap();
+    assert!(provenance.starts_with("product of (DFA created by product of"));
+    assert!(provenance.ends_with('…'));
+    assert_eq!(
+        provenance.chars().count(),
+        crate::util::MAX_PROVENANCE_LEN + 1
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_checkpoint() {
+    let dfa: Dfa = parser::dfa(include_str!("../tests/test_files/eq_to_nfa1.dfa"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let mut evaluator = dfa.evaluator();
+    evaluator.step(&dfa.alphabet()[0]);
+    let checkpoint = evaluator.checkpoint();
+
+    let json = serde_json::to_string(&checkpoint).unwrap();
+    let deserialized = serde_json::from_str(&json).unwrap();
+    assert_eq!(checkpoint, deserialized);
+    let resumed = dfa.resume(&deserialized).unwrap();
+    assert_eq!(resumed.current_state_idx(), evaluator.current_state_idx());
+}
+
+#[test]
+fn closest_accepted_odd_as() {
+    let dfa: Dfa = parser::dfa(
+        "
+           a b
+    -> e   o e
+     * o   e o",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let (word, distance) = dfa.closest_accepted_graphemes("aa", 3).unwrap();
+    assert_eq!(distance, 1);
+    assert!(["a", "aaa", "aab", "aba", "baa", "ab", "ba"].contains(&word.as_str()));
+    assert!(dfa.accepts_graphemes(&word));
+    assert_eq!(dfa.closest_accepted_graphemes("aa", 0), None);
+    // Elements outside of the alphabet have to be substituted or deleted
+    let (word, distance) = dfa.closest_accepted_graphemes("ax", 3).unwrap();
+    assert_eq!(distance, 1);
+    assert!(["a", "ab", "ba"].contains(&word.as_str()));
+}
+
+#[test]
+fn structural_diff_pinpoints_edited_transition() {
+    let dfa: Dfa = parser::dfa(
+        "
+           a  b  c
+    -> s0  s1 s0 s3
+       s1  s1 s2 s3
+     * s2  s1 s0 s3
+       s3  s3 s3 s3",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    // Rename every state and change the c-transition of s1
+    let mut edited = dfa.clone();
+    edited
+        .states
+        .iter_mut()
+        .for_each(|state| state.name = Rc::from(state.name.replace('s', "q")));
+    edited.states[1].transitions[2] = 2;
+
+    let diff = dfa.structural_diff(&edited);
+    assert!(!diff.is_identical());
+    assert_eq!(
+        diff.transition_mismatches,
+        [TransitionMismatch {
+            from_self: "s1".into(),
+            from_other: "q1".into(),
+            symbol: "c".into(),
+            to_self: "s3".into(),
+            to_other: "q2".into(),
+            acceptance_differs: true,
+        }]
+    );
+    assert_eq!(diff.matched.len(), 4);
+    assert!(diff.acceptance_mismatches.is_empty());
+    assert!(diff.unmatched_in_self.is_empty() && diff.unmatched_in_other.is_empty());
+    assert!(diff
+        .to_string()
+        .contains("Transition from s1/q1 on c leads to s3 and q2"));
+
+    // Removing an element of the alphabet is also reported
+    let smaller: Dfa = parser::dfa(
+        "
+           a  b
+    -> s0  s1 s0
+       s1  s1 s2
+     * s2  s1 s0",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let diff = dfa.structural_diff(&smaller);
+    assert_eq!(diff.alphabet_only_in_self, [Rc::from("c")]);
+    assert!(diff.alphabet_only_in_other.is_empty());
+    assert!(diff.transition_mismatches.is_empty());
+    assert_eq!(diff.unmatched_in_self, [Rc::from("s3")]);
+}
+
+#[test]
+fn spreadsheet_corner_header() {
+    // Exported from a spreadsheet with the initial/accepting markers and state names in their own columns
+    let dfa = "\t\tstate\ta\tb\n->\t\ts0\ts1\ts0\n\t\ts1\ts1\ts2\n\t*\ts2\ts1\ts0\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert!(dfa.accepts_graphemes("bab"));
+
+    let nfa = "δ\tε\ta\tb\n->\ts0\t{}\t{s0 s1}\t{s0}\n*\ts1\t{}\t{}\t{}\n";
+    let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    assert_eq!(nfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert!(nfa.accepts_graphemes("ba"));
+
+    // If every state has a transition on it, it is an element of the alphabet rather than a header
+    let dfa = "\tQ\ta\n->\ts\ts\ts\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("Q"), Rc::from("a")]);
+}
+
+#[test]
+fn spreadsheet_quoted_cells() {
+    // Exported from a spreadsheet which quotes every cell
+    let dfa = "\"\"\t\"\"\t\"a\"\t\"b\"\n\"->\"\t\"\"\t\"s0\"\t\"s1\"\t\"s0\"\n\"\"\t\"*\"\t\"s1\"\t\"s1\"\t\"s0\"\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert_eq!(dfa.states()[0].name(), "s0");
+    assert!(dfa.accepts_graphemes("ba"));
+    assert!(!dfa.accepts_graphemes("ab"));
+
+    let nfa = "\"states\"\t\"a\"\t\"b\"\n\"->\"\t\"s0\"\t\"{s0 s1}\"\t\"{s0}\"\n\"*\"\t\"s1\"\t\"{}\"\t\"{}\"\n";
+    let nfa: Nfa = parser::nfa(nfa).unwrap().try_into().unwrap();
+    assert_eq!(nfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert!(nfa.accepts_graphemes("ba"));
+
+    // Quotes which don't surround a cell are still part of the name
+    let dfa = "a\n-> s\" \"s\"\"\n * \"s\"\" \"s\"\"\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.states()[0].name(), "s\"");
+    assert_eq!(dfa.states()[1].name(), "\"s\"\"");
+
+    // Double quotes written twice in quoted cells are one double quote
+    let dfa = "\"a b\"\n-> \"s\"\"\"\"\" \"s\"\"\"\"\"\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("a b")]);
+    assert_eq!(dfa.states()[0].name(), "s\"\"");
+}
+
+#[test]
+fn spreadsheet_crlf_and_trailing_columns() {
+    // Exported from a spreadsheet on Windows, where the table is narrower than the sheet
+    let dfa = "state\ta\tb\t\t\r\n->\ts0\ts1\ts0\t\t\r\n*\ts1\ts1\ts0\t\t\r\n\t\t\t\t\r\n";
+    let dfa: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert_eq!(dfa.states().len(), 2);
+    assert!(dfa.accepts_graphemes("ba"));
+
+    // The same, but with empty cells exported as quotes
+    let dfa = "\"state\",\"a\",\"b\",\"\"\r\n".replace(',', "\t")
+        + &"\"->\",\"s0\",\"s1\",\"s0\",\"\"\r\n".replace(',', "\t")
+        + &"\"*\",\"s1\",\"s1\",\"s0\",\"\"\r\n".replace(',', "\t");
+    let dfa: Dfa = parser::dfa(&dfa).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet(), [Rc::from("a"), Rc::from("b")]);
+    assert!(dfa.accepts_graphemes("ba"));
+}
+
+#[test]
+fn remove_epsilon_moves_accepting_through_cycle() {
+    // f is only reachable through the cycle s1 -> s2 -> s1
+    let nfa: Nfa = parser::nfa(
+        "
+           ε       a    b
+    -> s0  {}      {s1} {s0}
+       s1  {s2}    {}   {}
+       s2  {s1 f}  {}   {}
+     * f   {}      {}   {f}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(nfa.epsilon_cycles(), vec![vec![1, 2]]);
+
+    let mut no_eps = nfa.clone();
+    no_eps.remove_epsilon_moves();
+    assert!(!no_eps.has_epsilon_moves());
+    assert!(!no_eps.has_epsilon_cycles());
+    assert!(no_eps.equivalent_to(&nfa));
+    // s1 and s2 only have epsilon moves, so they are dead after the epsilon closures are inlined
+    let names = no_eps.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["s0", "f"]);
+    assert!(no_eps.accepts_graphemes("a"));
+    assert!(no_eps.accepts_graphemes("babb"));
+    assert!(!no_eps.accepts_graphemes("b"));
+}
+
+#[test]
+fn remove_epsilon_moves_initial_in_cycle() {
+    // The initial state is in a cycle with s1, which has the only transitions
+    let nfa: Nfa = parser::nfa(
+        "
+           ε     a    b
+    -> i   {s1}  {}   {}
+       s1  {i}   {f}  {s1}
+     * f   {f}   {}   {i}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(nfa.epsilon_cycle_names(), vec![vec!["i", "s1"], vec!["f"]]);
+
+    let mut no_eps = nfa.clone();
+    no_eps.remove_epsilon_moves();
+    assert!(!no_eps.has_epsilon_moves());
+    assert!(no_eps.equivalent_to(&nfa));
+    // A new initial state is created, which takes the name of the old initial state since it became dead
+    let initial = no_eps.initial_state();
+    assert_eq!(initial.name(), "i");
+    assert!(!initial.is_accepting());
+    assert_eq!(
+        no_eps.states().iter().filter(|s| s.name() == "i").count(),
+        1
+    );
+    for word in ["a", "ba", "bba", "aba", "abba"] {
+        assert!(no_eps.accepts_graphemes(word), "{word} should be accepted");
+    }
+    for word in ["", "b", "ab", "aa"] {
+        assert!(!no_eps.accepts_graphemes(word), "{word} should be rejected");
+    }
+
+    // If the initial state is accepting, it isn't dead, so the new initial state gets a new name
+    let accepting_initial: Nfa = parser::nfa(
+        "
+            ε     a    b
+    -> * i  {s1}  {}   {}
+         s1 {i}   {s1} {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut no_eps = accepting_initial.clone();
+    no_eps.remove_epsilon_moves();
+    assert!(no_eps.equivalent_to(&accepting_initial));
+    assert!(no_eps.initial_state().is_accepting());
+    assert!(no_eps.accepts_graphemes(""));
+    assert!(no_eps.accepts_graphemes("aaa"));
+    assert!(!no_eps.accepts_graphemes("b"));
+}
+
+#[test]
+fn decode_encodings() {
+    use crate::parser::{DecodeError, Encoding};
+
+    // "→ s₀" (with two non-ASCII characters) in each encoding
+    let text = "→ s₀";
+    let cases: [(&[u8], Encoding); 5] = [
+        (b"\xE2\x86\x92 s\xE2\x82\x80", Encoding::Utf8),
+        (b"\xEF\xBB\xBF\xE2\x86\x92 s\xE2\x82\x80", Encoding::Utf8Bom),
+        (
+            b"\xFF\xFE\x92\x21\x20\x00\x73\x00\x80\x20",
+            Encoding::Utf16Le,
+        ),
+        (
+            b"\xFE\xFF\x21\x92\x00\x20\x00\x73\x20\x80",
+            Encoding::Utf16Be,
+        ),
+        (b"\x92\x21\x20\x00\x73\x00\x80\x20", Encoding::Utf16Le),
+    ];
+    for (bytes, encoding) in cases {
+        assert_eq!(parser::decode(bytes).unwrap(), (text.into(), encoding));
+    }
+
+    // "sö" in Latin-1
+    assert_eq!(
+        parser::decode(b"s\xF6"),
+        Err(DecodeError::InvalidUtf8 {
+            encoding: Encoding::Utf8,
+            position: 1
+        })
+    );
+    // A lone surrogate
+    assert_eq!(
+        parser::decode(b"\xFF\xFE\x73\x00\x00\xD8"),
+        Err(DecodeError::InvalidUtf16 {
+            encoding: Encoding::Utf16Le,
+            position: 4
+        })
+    );
+    assert_eq!(
+        parser::decode(b"\xFE\xFF\x00"),
+        Err(DecodeError::OddLength(Encoding::Utf16Be))
+    );
+}
+
+#[test]
+fn regex_language_comparisons() {
+    let regex = |s| parser::regex(s).unwrap();
+    let alt = regex("a|b");
+    let flipped = regex("b|a");
+    assert!(alt.language_eq(&flipped));
+    assert!(!alt.structurally_eq(&flipped));
+    assert_eq!(alt, flipped);
+    assert!(alt.structurally_eq(&regex("a|b")));
+
+    // Regexes over different alphabets can still be compared
+    let star = regex("(a|b)*");
+    assert!(regex("a").subset_of(&star));
+    assert!(!regex("c").subset_of(&star));
+    assert!(regex("a") < alt);
+    assert!(alt < star);
+    assert!(star >= regex("(ab)*"));
+    assert_eq!(
+        star.partial_cmp(&regex("(a*b*)*")),
+        Some(std::cmp::Ordering::Equal)
+    );
+    assert_eq!(regex("a").partial_cmp(&regex("b")), None);
+    assert_eq!(
+        regex("∅").partial_cmp(&regex("b")),
+        Some(std::cmp::Ordering::Less)
+    );
+    assert_ne!(regex("a"), regex("b"));
+}
+
+#[test]
+fn isomorphism_ignores_state_names() {
+    let dfa: Dfa = parser::dfa(
+        "
+          a  b
+    -> s0 s1 s0
+       s1 s1 s2
+     * s2 s1 s0
+       s3 s3 s2",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    // Same machine with states renamed and reordered, and the columns swapped
+    let renamed: Dfa = parser::dfa(
+        "
+           b  a
+       q3  q1 q3
+     * q1  q2 q0
+    -> q2  q2 q0
+       q0  q1 q0",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert!(dfa.isomorphic_to(&renamed));
+    assert!(renamed.isomorphic_to(&dfa));
+
+    // Moving the unreachable transition makes them differ, even though they are still equivalent
+    let mut edited = renamed.clone();
+    edited.states[0].transitions[0] = 0;
+    assert!(dfa.equivalent_to(&edited));
+    assert!(!dfa.isomorphic_to(&edited));
+
+    // A DFA is equivalent to its minimized version, but not isomorphic to it unless it is already minimal
+    let mut minimized = dfa.clone();
+    minimized.minimize();
+    assert!(dfa.equivalent_to(&minimized));
+    assert!(!dfa.isomorphic_to(&minimized));
+    let mut reachable = dfa.clone();
+    reachable.remove_unreachable_states();
+    assert!(reachable.isomorphic_to(&minimized));
+
+    let nfa: Nfa = parser::nfa(
+        "
+           ε    a       b
+    -> s0  {}   {s0 s1} {s0}
+       s1  {s2} {}      {s2}
+     * s2  {}   {}      {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let renamed: Nfa = parser::nfa(
+        "
+           b     ε    a
+     * x   {}    {}   {}
+    -> y   {y}   {}   {z y}
+       z   {x}   {x}  {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(nfa.isomorphic_to(&renamed), Some(true));
+    let mut edited = renamed.clone();
+    edited.states[2].epsilon_transitions.clear();
+    assert_eq!(nfa.isomorphic_to(&edited), Some(false));
+    assert_eq!(nfa.isomorphic_to(&nfa.to_minimized_nfa()), Some(false));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_round_trip() {
+    let dfa: Dfa = parser::dfa(
+        "
+           a  b
+        -> s0 s1 s0
+         * s1 s1 s0",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let json = dfa.to_json_string().unwrap();
+    assert_eq!(
+        json,
+        r#"{"alphabet":["a","b"],"states":[{"name":"s0","accepting":false,"transitions":[1,0]},{"name":"s1","accepting":true,"transitions":[1,0]}],"initial_state":0}"#
+    );
+    let deserialized = Dfa::from_json_str(&json).unwrap();
+    assert_eq!(deserialized, dfa);
+    assert!(deserialized.states()[0].is_initial());
+    assert_eq!(deserialized.alphabet_index("b"), Some(1));
+
+    let nfa = dfa.to_nfa();
+    let json = nfa.to_json_string().unwrap();
+    assert!(json.contains(
+        r#"{"name":"s0","accepting":false,"epsilon_transitions":[],"transitions":[[1],[0]]}"#
+    ));
+    assert_eq!(Nfa::from_json_str(&json).unwrap(), nfa);
+
+    let regex = parser::regex("a(b|ε)*").unwrap();
+    let json = regex.to_json_string().unwrap();
+    assert_eq!(
+        json,
+        r#"{"tree":{"Sequence":[{"Char":{"Grapheme":"a"}},{"Repeat":{"Alt":[{"Char":{"Grapheme":"b"}},{"Char":"Epsilon"}]}}]}}"#
+    );
+    assert!(regex::Regex::from_json_str(&json)
+        .unwrap()
+        .structurally_eq(&regex));
+
+    // Invalid automata are rejected just like when parsing tables
+    let invalid = [
+        r#"{"alphabet":["a","a"],"states":[{"name":"s0","accepting":false,"transitions":[0,0]}],"initial_state":0}"#,
+        r#"{"alphabet":["a"],"states":[{"name":"s0","accepting":false,"transitions":[1]}],"initial_state":0}"#,
+        r#"{"alphabet":["a"],"states":[{"name":"s0","accepting":false,"transitions":[0,0]}],"initial_state":0}"#,
+        r#"{"alphabet":["a"],"states":[{"name":"s0","accepting":false,"transitions":[0]}],"initial_state":1}"#,
+    ];
+    for json in invalid {
+        assert!(Dfa::from_json_str(json).is_err(), "{json}");
+    }
+    let error = Nfa::from_json_str(
+        r#"{"alphabet":[],"states":[{"name":"s0","accepting":false,"epsilon_transitions":[2],"transitions":[]}],"initial_state":0}"#,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("State '2' does not exist"));
+}
+
+#[test]
+fn words_of_length_ends_with_aab() {
+    let nfa: Nfa = parser::nfa(
+        "
+            a       b
+    ->  s1 {s1 s2} {s1}
+        s2 {s3}    {}
+        s3 {}      {s4}
+      * s4 {}      {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let expected = nfa
+        .words()
+        .take_while(|w| w.len() <= 4)
+        .filter(|w| w.len() == 4)
+        .collect::<Vec<_>>();
+    assert_eq!(expected, ["aaab", "baab"]);
+    assert_eq!(nfa.words_of_length(4).collect::<Vec<_>>(), expected);
+
+    // Borrowed components give the same words as the default Rc<str> components
+    let borrowed = nfa::words::WordComponents::with_components(&nfa, |elem| elem.as_ref());
+    let owned = nfa.word_components();
+    for (borrowed, owned) in borrowed.zip(owned).take(20) {
+        assert!(borrowed
+            .into_iter()
+            .eq(owned.iter().map(|elem| elem.as_ref())));
+    }
+}
+
+#[test]
+fn product_construction_synchronized_on_a() {
+    let first: Nfa = parser::nfa(
+        "
+           a   b
+    -> p  {q} {p}
+     * q  {}  {q}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let second: Nfa = parser::nfa(
+        "
+           a   b
+    -> x  {y} {}
+     * y  {}  {y}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let a = first.alphabet_index("a").unwrap();
+    // Both NFAs have to take every a together, while b may be taken by the first NFA alone
+    let product = first
+        .product_construction_indexed(
+            &second,
+            |s1, s2| s1.is_accepting() && (s2.is_accepting() || s2.index.is_none()),
+            |elem, _, (to1, to2)| elem != a || (to1.index.is_some() && to2.index.is_some()),
+        )
+        .unwrap();
+    let expected: Nfa = parser::nfa(
+        "
+                a       b
+    -> (p,x)    {(q,y)} {(p,none)}
+     * (q,y)    {}      {(q,y)}
+       (p,none) {}      {(p,none)}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(product.isomorphic_to(&expected), Some(true));
+    assert!(!product.accepts_graphemes("ba"));
+
+    // Without vetoing any transitions, the first NFA can take a alone as well
+    let unrestricted = first
+        .product_construction_indexed(
+            &second,
+            |s1, s2| s1.is_accepting() && (s2.is_accepting() || s2.index.is_none()),
+            |_, _, _| true,
+        )
+        .unwrap();
+    assert!(unrestricted.accepts_graphemes("ba"));
+}
+
+#[test]
+fn accepts_graphemes_multi_code_point_graphemes() {
+    // "e\u{301}" is an e with a combining acute accent, and the family is an emoji ZWJ sequence
+    let family = "\u{1F469}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+    let table = format!(
+        "
+               e\u{301} {family}
+        -> * s  t        s
+             t  s        t"
+    );
+    let dfa: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+    let nfa = dfa.clone().to_nfa();
+    for (input, accepted) in [
+        ("", true),
+        ("e\u{301}", false),
+        ("e\u{301}e\u{301}", true),
+        (&format!("{family}e\u{301}{family}e\u{301}"), true),
+        (&format!("e\u{301}{family}"), false),
+        ("e", false),
+        ("\u{1F469}", false),
+    ] {
+        let graphemes = input.graphemes(true).collect::<Vec<_>>();
+        assert_eq!(dfa.accepts(&graphemes), accepted, "{input}");
+        assert_eq!(dfa.accepts_graphemes(input), accepted, "{input}");
+        assert_eq!(nfa.accepts_graphemes(input), accepted, "{input}");
+    }
+
+    // Stepping stops at the first grapheme which isn't in the alphabet
+    let mut eval = dfa.evaluator();
+    assert!(eval.step_str(&format!("e\u{301}x{family}")).is_none());
+    assert!(eval.current_state().is_none());
+    assert!(!eval.is_accepting());
+    let mut eval = nfa.evaluator();
+    assert!(eval.step_str("xe\u{301}").is_none());
+    assert!(!eval.is_accepting());
+}
+
+#[test]
+fn merge_equivalent_digits() {
+    // Classifies a number as even or odd, where all even digits and all odd digits behave the same
+    let mut dfa: Dfa = parser::dfa(
+        "
+              0    1    2    3    4    5    6    7    8    9
+    -> s      even odd  even odd  even odd  even odd  even odd
+     * even   even odd  even odd  even odd  even odd  even odd
+       odd    even odd  even odd  even odd  even odd  even odd",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let original = dfa.clone();
+    let classes = dfa.symbol_equivalence_classes();
+    let digits = |digits: &[&str]| digits.iter().map(|&d| Rc::from(d)).collect::<Vec<_>>();
+    assert_eq!(
+        classes,
+        [
+            digits(&["0", "2", "4", "6", "8"]),
+            digits(&["1", "3", "5", "7", "9"])
+        ]
+    );
+
+    let merges = dfa.merge_equivalent_symbols();
+    assert_eq!(merges.len(), 8);
+    assert_eq!(dfa.alphabet(), &digits(&["0", "1"])[..]);
+    assert_eq!(dfa.alphabet_index("1"), Some(1));
+    assert_eq!(dfa.alphabet_index("3"), None);
+    let map = dfa::SymbolMap::new(&merges);
+    for number in ["", "0", "7", "42", "1234567890", "98765", "2468", "13579"] {
+        let number = number.graphemes(true).collect::<Vec<_>>();
+        assert_eq!(
+            dfa.accepts_with_symbol_map(&number, &map),
+            original.accepts(&number)
+        );
+    }
+    // Elements which aren't in the original alphabet still aren't accepted
+    assert!(!dfa.accepts_with_symbol_map(&["2", "x"], &map));
+    // Nothing is merged a second time
+    assert!(dfa.merge_equivalent_symbols().is_empty());
+
+    let mut nfa = original.to_nfa();
+    let nfa_map = dfa::SymbolMap::new(&nfa.merge_equivalent_symbols());
+    assert_eq!(nfa_map, map);
+    assert!(nfa.accepts_with_symbol_map(&["3", "6"], &nfa_map));
+    assert!(!nfa.accepts_with_symbol_map(&["6", "3"], &nfa_map));
+}
+
+#[test]
+fn display_and_equivalence_assertions() {
+    let regex = parser::regex("a(b|c)*").unwrap();
+    let reparsed = parser::regex(&regex.to_string()).unwrap();
+    crate::assert_equivalent!(regex, reparsed);
+
+    let nfa = regex.to_nfa();
+    assert_eq!(nfa.to_string(), nfa.to_table());
+    let dfa = nfa.to_dfa();
+    assert_eq!(format!("{dfa}"), dfa.to_table());
+
+    let mut inverted = dfa.clone();
+    inverted.invert();
+    crate::assert_not_equivalent!(dfa, inverted);
+    let message = testing::check_equivalence(&dfa, &inverted, true).unwrap_err();
+    assert!(message.contains(&dfa.to_table()));
+    assert!(message.contains(&inverted.to_table()));
+    assert!(message.ends_with("distinguishing word: \"\" (accepted by right only)"));
+}
+
+#[test]
+fn nfa_ambiguity() {
+    let parse = |table: &str| -> Nfa { parser::nfa(table).unwrap().try_into().unwrap() };
+    // Ends with "aab", where the last three symbols must be read by s2, s3 and s4
+    let ends_with_aab = parse(
+        "
+           a       b
+    -> s1 {s1 s2} {s1}
+       s2 {s3}    {}
+       s3 {}      {s4}
+     * s4 {}      {}",
+    );
+    assert!(!ends_with_aab.is_ambiguous());
+    assert_eq!(ends_with_aab.max_branching(), 2);
+    assert_eq!(ends_with_aab.nondeterministic_states().len(), 1);
+
+    // Two ε-paths to the accepting state
+    let two_paths = parse(
+        "
+         ε     a
+    -> s {t u} {}
+       t {v}   {}
+       u {v}   {}
+     * v {}    {}",
+    );
+    assert!(two_paths.is_ambiguous());
+
+    // An ε-cycle on an accepting run gives infinitely many runs, but not if it isn't part of any accepting run
+    let cycle = "
+         ε   a
+    -> s {}  {t}
+     * t {}  {}
+       u {v} {}
+       v {u} {}";
+    assert!(!parse(cycle).is_ambiguous());
+    assert!(parse(&cycle.replace("-> s {}", "-> s {s}")).is_ambiguous());
+
+    // The words of odd length, either as one loop or as (aa)*a and a(aa)*, which both accept "a"
+    let odd = parse(
+        "
+         a
+    -> s {t x}
+     * t {s}
+       x {y}
+     * y {x}",
+    );
+    assert!(odd.is_ambiguous());
+    let mut unique_odd = odd.clone();
+    unique_odd.states[3].accepting = false;
+    assert!(!unique_odd.is_ambiguous());
+}
+
+#[test]
+fn table_reparse_quotes_special_characters() {
+    let mut dfa: Dfa = parser::dfa(
+        "
+           a  b
+        -> s  s  t
+         * t  s  t",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    // Without quoting, "#" would start a comment, and "{t}" and "a b" would be parsed as a set and two cells. Double
+    // quotes are written twice in quoted cells, and names starting with one have to be quoted to not be stripped.
+    dfa.alphabet = Rc::from([Rc::from("#"), Rc::from("\"a b\"")]);
+    dfa.alphabet_lookup = Default::default();
+    dfa.states[0].name = Rc::from("\"s\"");
+    dfa.states[1].name = Rc::from("{t}");
+    let table = dfa.to_table();
+    let header = table.lines().next().unwrap().trim();
+    assert!(header.starts_with("\"#\" ") && header.ends_with(" \"\"\"a b\"\"\""));
+    let parsed: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+    assert_eq!(dfa, parsed);
+    assert!(parsed.accepts(&["#", "\"a b\""]));
+
+    let nfa = dfa.to_nfa();
+    let parsed: Nfa = parser::nfa(&nfa.to_table()).unwrap().try_into().unwrap();
+    assert_eq!(nfa, parsed);
+}
+
+#[test]
+fn merge_nondistinguishable_states_except_protected() {
+    // Both s1 and s2 accept the words with an even number of a:s
+    let mut dfa: Dfa = parser::dfa(
+        "
+           a  b
+        -> i  s1 s2
+         * s1 t  s1
+         * s2 t  s2
+           t  s1 t",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let original = dfa.clone();
+    let merges = dfa.merge_nondistinguishable_states_except(&["s1", "s2"]);
+    assert!(merges.is_empty());
+    assert_eq!(dfa.states().len(), 4);
+
+    let merges = dfa.merge_nondistinguishable_states_except(&["s2"]);
+    assert_eq!(merges, vec![(Rc::from("s1"), Rc::from("s2"))]);
+    assert_eq!(dfa.states().len(), 3);
+    assert_eq!(dfa.states()[1].name(), "s2");
+    crate::assert_equivalent!(dfa, original);
+}
+
+#[test]
+fn minimal_sizes() {
+    let odd_as: Dfa = parser::dfa(
+        "
+           a  b
+    -> s1 s2 s1
+     * s2 s3 s2
+       s3 s4 s3
+     * s4 s1 s4",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(odd_as.minimal_size(), 2);
+    assert_eq!(odd_as.states().len(), 4);
+
+    // The 8th last symbol is an a, for which the subset construction has 2^8 states
+    let n = 8;
+    let mut table = "a b\n-> s0 {s0 s1} {s0}\n".to_string();
+    for i in 1..n {
+        table.push_str(&format!("s{i} {{s{0}}} {{s{0}}}\n", i + 1));
+    }
+    table.push_str(&format!("* s{n} {{}} {{}}\n"));
+    let nfa: Nfa = parser::nfa(&table).unwrap().try_into().unwrap();
+    assert_eq!(
+        nfa.minimal_dfa_size_bounded(100),
+        Err(crate::nfa::ExceededBound { max: 100 })
+    );
+    assert_eq!(nfa.minimal_dfa_size_bounded(1 << n), Ok(1 << n));
+}
+
+#[test]
+fn evaluator_state_keys() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |value: &dyn Fn(&mut DefaultHasher)| {
+        let mut hasher = DefaultHasher::new();
+        value(&mut hasher);
+        hasher.finish()
+    };
+
+    // Ends with b, where s0 is reached by "a" and "ba"
+    let nfa: Nfa = parser::nfa(
+        "
+            a    b
+    -> s0 {s0} {s0 s1}
+     * s1 {}   {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut e1 = nfa.evaluator();
+    e1.step_str("ab");
+    let mut e2 = nfa.evaluator();
+    e2.step_str("bbab");
+    assert_eq!(e1.state_key(), vec![0, 1]);
+    assert_eq!(e1, e2);
+    assert_eq!(hash(&|h| e1.hash(h)), hash(&|h| e2.hash(h)));
+    e2.step("a");
+    assert_ne!(e1, e2);
+    let copy = nfa.clone();
+    let mut e3 = copy.evaluator();
+    e3.step_str("ab");
+    assert_eq!(e1.state_key(), e3.state_key());
+    assert_ne!(e1, e3);
+
+    let dfa = nfa.to_dfa();
+    let mut d1 = dfa.evaluator();
+    d1.step_str("ab");
+    let mut d2 = dfa.evaluator();
+    d2.step_str("aabbab");
+    assert_eq!(d1, d2);
+    assert_eq!(hash(&|h| d1.hash(h)), hash(&|h| d2.hash(h)));
+    let other = dfa.clone();
+    let mut d3 = other.evaluator();
+    d3.step_str("ab");
+    assert_ne!(d1, d3);
+    d1.step("c");
+    d2.step_str("ac");
+    assert_eq!(d1.state_key(), None);
+    assert_eq!(d1, d2);
+    assert_eq!(hash(&|h| d1.hash(h)), hash(&|h| d2.hash(h)));
+}
+
+#[test]
+fn alphabet_pragma() {
+    use crate::regex::RegexAlphabetError;
+    let (regex, alphabet) = parser::regex_file("#! alphabet a b c\n(ab)+").unwrap();
+    let alphabet = alphabet.unwrap();
+    let alphabet = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+    let nfa = regex.to_nfa_with_alphabet(&alphabet).unwrap();
+    assert_eq!(nfa.alphabet(), &["a".into(), "b".into(), "c".into()]);
+    assert!(nfa.accepts_graphemes("abab"));
+    assert!(!nfa.accepts_graphemes("abc"));
+
+    // Without a pragma, or with # just being part of the regex
+    let (regex, alphabet) = parser::regex_file("(ab)+").unwrap();
+    assert!(alphabet.is_none());
+    assert_eq!(regex.to_nfa().alphabet().len(), 2);
+    let (regex, alphabet) = parser::regex_file("#!a").unwrap();
+    assert!(alphabet.is_none());
+    assert!(regex.to_nfa().accepts_graphemes("#!a"));
+    let (regex, _) = parser::regex_file("#! alphabet a\nab").unwrap();
+    assert_eq!(
+        regex.clone().to_nfa_with_alphabet(&["a"]).unwrap_err(),
+        RegexAlphabetError::UnknownElement("b".into())
+    );
+    assert_eq!(
+        regex
+            .clone()
+            .to_nfa_with_alphabet(&["a", "b", "a"])
+            .unwrap_err(),
+        RegexAlphabetError::DuplicateElement("a".into())
+    );
+    // Elements which tables and pragmas reject are rejected here as well
+    for invalid in ["", " c", "c\t"] {
+        assert_eq!(
+            regex
+                .clone()
+                .to_nfa_with_alphabet(&["a", "b", invalid])
+                .unwrap_err(),
+            RegexAlphabetError::InvalidElement(invalid.into())
+        );
+    }
+    assert!(regex.to_nfa_with_alphabet(&["a", "b", "c d"]).is_ok());
+
+    let table = "#! alphabet a b\n      a b\n→ * s s s\n";
+    let dfa: Dfa = parser::dfa_file(table).unwrap().try_into().unwrap();
+    assert_eq!(dfa.alphabet().len(), 2);
+    let dfa: Result<Dfa, _> = parser::dfa_file("#! alphabet a b c\n  a b\n→ s s s")
+        .unwrap()
+        .try_into();
+    let error = dfa.unwrap_err();
+    assert_eq!(
+        error,
+        crate::dfa::DfaParseError::AlphabetPragmaMismatch(
+            vec!["a".into(), "b".into(), "c".into()],
+            vec!["a".into(), "b".into()]
+        )
+    );
+    assert_eq!(
+        error.to_string(),
+        r#"The alphabet pragma declares the alphabet ["a", "b", "c"], but the table has the alphabet ["a", "b"]"#
+    );
+    // Plain parsing treats the pragma as a comment
+    let dfa: Result<Dfa, _> = parser::dfa("#! alphabet a b c\n  a b\n→ s s s")
+        .unwrap()
+        .try_into();
+    assert!(dfa.is_ok());
+
+    let nfa: Result<Nfa, _> = parser::nfa_file("#! alphabet b a\n  ε a b\n→ * s {} {s} {s}")
+        .unwrap()
+        .try_into();
+    assert!(nfa.is_ok());
+    let nfa: Result<Nfa, _> = parser::nfa_file("#! alphabet a\n  ε a b\n→ * s {} {s} {s}")
+        .unwrap()
+        .try_into();
+    assert_eq!(
+        nfa.unwrap_err(),
+        crate::nfa::NfaParseError::AlphabetPragmaMismatch(
+            vec!["a".into()],
+            vec!["a".into(), "b".into()]
+        )
+    );
+}
+
+#[test]
+fn detect_input_type() {
+    use crate::parser::{detect, suggest_type, Detected};
+    let dfa = "
+           a  b
+    -> s1 s2 s1  # comment with {braces}
+     * s2 s1 s2";
+    let nfa = "
+           a    b
+    -> s1 {s2} {}
+     * s2 {}   {s1 s2}";
+    let epsilon_nfa = "# alphabet\n  eps a\n→ s s s";
+    assert_eq!(detect(dfa), Detected::Dfa);
+    assert_eq!(detect(nfa), Detected::Nfa);
+    assert_eq!(detect(epsilon_nfa), Detected::Nfa);
+    assert_eq!(detect("(a|b)*abb"), Detected::Regex);
+    assert_eq!(detect("#! alphabet a b c\n(ab)+"), Detected::Regex);
+    assert_eq!(detect("\n  \n# comment"), Detected::Unknown);
+    assert_eq!(detect("ab)"), Detected::Regex);
+    // A # in a quoted cell doesn't start a comment hiding the sets after it
+    assert_eq!(detect("     a\n-> \"s #1\" {}\n"), Detected::Nfa);
+
+    let error = parser::dfa(nfa).unwrap_err();
+    assert_eq!(
+        suggest_type(error.input, Detected::Dfa),
+        Some(Detected::Nfa)
+    );
+    let error = parser::nfa(dfa).unwrap_err();
+    assert_eq!(
+        suggest_type(error.input, Detected::Nfa),
+        Some(Detected::Dfa)
+    );
+    // A typo in a NFA table doesn't suggest another type
+    let error = parser::nfa("   a   b\n→ s {s} {s\n").unwrap_err();
+    assert_eq!(suggest_type(error.input, Detected::Nfa), None);
+    let error = parser::dfa("   a b\n→ s s s\n→").unwrap_err();
+    assert_eq!(suggest_type(error.input, Detected::Dfa), None);
+}
+
+#[test]
+fn regex_builder() {
+    use crate::regex::{NotAGrapheme, Regex};
+    let built = (Regex::literal("a") * Regex::literal("b")).plus() * Regex::grapheme("c").unwrap();
+    let parsed = parser::regex("(ab)+c").unwrap();
+    assert!(built
+        .clone()
+        .to_nfa()
+        .equivalent_to(&parsed.clone().to_nfa()));
+    let reparsed = parser::regex(&built.to_string()).unwrap();
+    assert!(reparsed.structurally_eq(&built));
+
+    // Nested sequences and alternations are flattened
+    let abc = Regex::literal("a").then(Regex::literal("b").then(Regex::literal("c")));
+    assert!(abc.structurally_eq(&Regex::literal("abc")));
+    let alt = Regex::literal("a") | (Regex::literal("b") | Regex::epsilon());
+    assert!(alt.structurally_eq(&parser::regex("a|b|ε").unwrap()));
+
+    // Reserved characters and multi-codepoint clusters need no escaping
+    let reserved = Regex::literal("(|)") | Regex::literal("e\u{301}*") | Regex::empty();
+    let reparsed = parser::regex(&reserved.to_string()).unwrap();
+    assert!(reparsed.structurally_eq(&reserved));
+    let nfa = reserved.to_nfa();
+    assert!(nfa.accepts_graphemes("(|)"));
+    assert!(nfa.accepts_graphemes("e\u{301}*"));
+    assert!(!nfa.accepts_graphemes("e"));
+
+    assert!(Regex::grapheme("e\u{301}").is_ok());
+    assert_eq!(
+        Regex::grapheme("ab").unwrap_err(),
+        NotAGrapheme("ab".to_string())
+    );
+    assert!(Regex::grapheme("").is_err());
+}
+
+#[test]
+fn language_fingerprints() {
+    let fingerprint = |regex: &str| parser::regex(regex).unwrap().language_fingerprint();
+    assert_eq!(fingerprint("(ab)*a"), fingerprint("a(ba)*"));
+    assert_eq!(fingerprint("a|b"), fingerprint("b|a"));
+    assert_eq!(fingerprint("(a|b)*"), fingerprint("(a*b*)*"));
+    // Symbols which are never part of an accepted word don't matter
+    assert_eq!(fingerprint("a|∅b"), fingerprint("a"));
+    assert_eq!(fingerprint("∅"), fingerprint("∅c"));
+
+    assert_ne!(fingerprint("(ab)*a"), fingerprint("(ab)*"));
+    assert_ne!(fingerprint("a"), fingerprint("b"));
+    assert_ne!(fingerprint("ε"), fingerprint("∅"));
+    assert_ne!(fingerprint("a*"), fingerprint("a+"));
+
+    // The fingerprint is stable, so it may never change
+    assert_eq!(fingerprint("(ab)*a"), 1420814643181007820);
+
+    let dfa: Dfa = parser::dfa("  a b\n-> * s t s\n t s t")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(dfa.language_fingerprint(), fingerprint("(b|ab*a)*"));
+}
+
+#[test]
+fn automaton_versions() {
+    let input = "
+         a  b
+    → s0 s1 s0
+    * s1 s2 s1
+    * s2 s1 s2
+      s3 s3 s3
+    ";
+    let mut dfa: Dfa = parser::dfa(input).unwrap().try_into().unwrap();
+    assert_eq!(dfa.version(), 0);
+    let original = dfa.clone();
+    let version = dfa.evaluator().automaton().version();
+    dfa.minimize();
+    assert!(dfa.version() > version);
+    assert_eq!(original.version(), 0);
+    // Modifying without changing anything still bumps the version, which doesn't affect equality
+    let mut inverted_twice = original.clone();
+    inverted_twice.invert();
+    inverted_twice.invert();
+    assert_eq!(inverted_twice.version(), 2);
+    assert_eq!(inverted_twice, original);
+    assert_eq!(inverted_twice.clone().version(), 2);
+    let mut pruned = original.clone();
+    assert!(pruned.prune_symbols().is_empty());
+    assert_eq!(pruned.version(), 1);
+
+    let mut nfa = original.to_nfa();
+    assert_eq!(nfa.version(), 0);
+    nfa.minimize_via_dfa();
+    assert_eq!(nfa.version(), 1);
+    nfa.remove_epsilon_moves();
+    assert_eq!(nfa.version(), 2);
+    assert!(nfa.prune_symbols().is_empty());
+    assert_eq!(nfa.version(), 3);
+}
+
+#[test]
+fn read_automaton_from_files() {
+    use crate::io::{self, Automaton, AutomatonType, LoadError};
+    use std::fs;
+
+    let dir = std::env::temp_dir();
+    let path = |name: &str| dir.join(format!("dandy-{}-{name}", std::process::id()));
+    let table = "   a  b\n-> s0 s1 s0\n * s1 s1 s1\n";
+    let expected: Dfa = parser::dfa(table).unwrap().try_into().unwrap();
+
+    let bom = path("bom.dfa");
+    fs::write(&bom, format!("\u{FEFF}{table}")).unwrap();
+    let crlf = path("crlf.dfa");
+    fs::write(&crlf, table.replace('\n', "\r\n")).unwrap();
+    for file in [&bom, &crlf] {
+        let Automaton::Dfa(dfa) = io::read_automaton(file, AutomatonType::Auto).unwrap() else {
+            panic!("{} should be detected as a DFA", file.display());
+        };
+        assert_eq!(dfa, expected);
+        fs::remove_file(file).unwrap();
+    }
+
+    // Truncated in the middle of a set, which can't be parsed
+    let truncated = path("truncated.nfa");
+    fs::write(&truncated, "   a    b\r\n-> s0 {s0} {s1}\r\n * s1 {s1").unwrap();
+    let error = io::read_automaton(&truncated, AutomatonType::Nfa).unwrap_err();
+    assert!(
+        matches!(error, LoadError::Parse { line: 3, .. }),
+        "{error:?}"
+    );
+    assert!(error
+        .to_string()
+        .contains(&format!("on line 3 of {}", truncated.display())));
+
+    // Truncated at the end of a line, which parses but lacks a transition
+    fs::write(&truncated, "   a    b\n-> s0 {s0} {s1}\n * s1 {s1}\n").unwrap();
+    let error = io::read_automaton(&truncated, AutomatonType::Nfa).unwrap_err();
+    assert_eq!(error.line(), Some(3));
+    assert_eq!(error.path(), truncated);
+
+    // Invalid UTF-8 (here in a comment) is only read lossily
+    fs::write(
+        &truncated,
+        b"   a    b  # caf\xE9\n-> s0 {s0} {s1}\n * s1 {s1} {}\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        io::read_automaton(&truncated, AutomatonType::Nfa),
+        Err(LoadError::Decode { .. })
+    ));
+    let (nfa, warning) = io::read_automaton_lossy(&truncated, AutomatonType::Nfa).unwrap();
+    fs::remove_file(&truncated).unwrap();
+    assert!(matches!(nfa, Automaton::Nfa(_)));
+    assert!(warning.is_some());
+}
+
+#[test]
+fn localized_table_tokens() {
+    let config = parser::ParserConfig {
+        initial_markers: vec![">".to_string()],
+        accepting_markers: vec!["F".to_string()],
+        epsilon_tokens: vec!["λ".to_string()],
+    };
+    let style = parser::TableStyle {
+        initial_marker: ">".to_string(),
+        accepting_marker: "F".to_string(),
+        epsilon_token: "λ".to_string(),
+    };
+    let input = "
+         λ     a     b
+    >   s0 {s1}  {s0}  {}
+      F s1 {}    {s1}  {s0 s1}
+    ";
+    let nfa: Nfa = parser::nfa_with_config(input, &config)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(nfa.accepts(&[]));
+    assert!(nfa.accepts_graphemes("ab"));
+    let table = nfa.to_table_with_style(&style);
+    assert_eq!(
+        table,
+        "       λ    a    b \n>   s0 {s1} {s0} {} \n  F s1 {}   {s1} {s0 s1} "
+    );
+    let reparsed: Nfa = parser::nfa_with_config(&table, &config)
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(reparsed, nfa);
+    // The default tokens aren't understood with this config, and the configured ones are reserved
+    assert!(parser::nfa_with_config(&nfa.to_table(), &config).is_err());
+    assert!(parser::nfa(&table).is_err());
+    assert!(parser::dfa_with_config("   a\n> s λ\n  λ s s", &config).is_err());
+
+    // The default config and style give the same results as before
+    let dfa = "
+           a  b
+    -> * s0 s1 s0
+         s1 s0 s1";
+    let parsed: Dfa = parser::dfa(dfa).unwrap().try_into().unwrap();
+    let configured: Dfa = parser::dfa_with_config(dfa, &parser::ParserConfig::default())
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(parsed, configured);
+    assert_table_snapshot!(parsed, "tests/snapshots/default_style.txt");
+    assert_eq!(
+        parsed.to_table_with_style(&parser::TableStyle::default()),
+        parsed.to_table()
+    );
+    assert_eq!(
+        parsed.to_table_with_style(&parser::TableStyle::ascii()),
+        parsed.ascii_table()
+    );
+    let nfa = parsed.to_nfa();
+    assert_eq!(
+        nfa.to_table_with_style(&parser::TableStyle::default()),
+        nfa.to_table()
+    );
+    assert_eq!(
+        nfa.to_table_with_style(&parser::TableStyle::ascii()),
+        nfa.ascii_table()
+    );
+}
+
+#[test]
+fn extended_regex_operators() {
+    let options = parser::RegexOptions {
+        extended: true,
+        ..parser::RegexOptions::default()
+    };
+    let regex = parser::regex_with_options("~(a*)&(a|b)*", &options).unwrap();
+    assert!(regex.is_extended());
+    let dfa = regex.to_dfa();
+    let nfa = regex.clone().to_nfa();
+    // Brute force all words over {a, b} of length up to 8
+    let mut words = vec![String::new()];
+    for _ in 0..8 {
+        let longer = words
+            .iter()
+            .flat_map(|word| ["a", "b"].map(|elem| format!("{word}{elem}")))
+            .collect::<Vec<_>>();
+        words.extend(longer);
+    }
+    for word in &words {
+        assert_eq!(dfa.accepts_graphemes(word), word.contains('b'), "{word}");
+        assert_eq!(nfa.accepts_graphemes(word), word.contains('b'), "{word}");
+    }
+    assert_eq!(dfa.states().len(), 2);
+
+    // The string representation can be parsed again
+    let reparsed = parser::regex_with_options(&regex.to_string(), &options).unwrap();
+    assert!(reparsed.structurally_eq(&regex));
+
+    // Precedence: & binds looser than sequencing, tighter than |, and ~ only applies to the next element
+    let regex = parser::regex_with_options("~ab*&ab|c", &options).unwrap();
+    assert_eq!(regex.to_string(), "((~(a)(b)*&ab)|c)");
+    let dfa = regex.to_dfa();
+    assert!(dfa.accepts_graphemes("ab"));
+    assert!(dfa.accepts_graphemes("c"));
+    assert!(!dfa.accepts_graphemes("abb"));
+    assert!(!dfa.accepts_graphemes("b"));
+
+    // Without the option, the operators are matched literally, and they are escaped when displayed
+    let literal = parser::regex("~a&¬").unwrap();
+    assert!(!literal.is_extended());
+    assert_eq!(literal.to_string(), "\\~a\\&\\¬");
+    let escaped = parser::regex_with_options(&literal.to_string(), &options).unwrap();
+    assert!(escaped.structurally_eq(&literal));
+    assert!(parser::regex_with_options("a&", &options).is_err());
+}
+
+#[test]
+fn deeply_nested_regexes() {
+    let nested =
+        |depth: usize, inner: &str| format!("{}{inner}{}", "(".repeat(depth), ")*".repeat(depth));
+
+    // Nesting within the limit is parsed and compiled as usual
+    let regex = parser::regex(&nested(parser::DEFAULT_MAX_DEPTH, "a")).unwrap();
+    let nfa = regex.clone().to_nfa();
+    assert!(nfa.accepts_graphemes("aaa"));
+    assert!(nfa.accepts_graphemes(""));
+    // Regexes this deep can still be displayed and compared, which recurses
+    assert!(parser::regex(&regex.to_string())
+        .unwrap()
+        .structurally_eq(&regex));
+
+    // Deeper nesting gives an error at the parenthesis exceeding the limit instead of overflowing the stack
+    let input = format!("{}a{}", "(".repeat(5000), ")".repeat(5000));
+    let error = parser::regex(&input).unwrap_err();
+    assert_eq!(error.code, nom::error::ErrorKind::TooLarge);
+    assert_eq!(error.input.len(), input.len() - parser::DEFAULT_MAX_DEPTH);
+    let options = parser::RegexOptions {
+        max_depth: 3,
+        ..parser::RegexOptions::default()
+    };
+    assert!(parser::regex_with_options("((((a))))", &options).is_err());
+    assert!(parser::regex_with_options("(((a)))", &options).is_ok());
+    let extended = parser::RegexOptions {
+        extended: true,
+        ..options
+    };
+    assert!(parser::regex_with_options("~~~~a", &extended).is_err());
+    assert!(parser::regex_with_options("~(~(a))", &extended).is_err());
+    assert!(parser::regex_with_options("~~a", &extended).is_ok());
+
+    // Trees deeper than the parser allows can be built programmatically, and are converted without recursion
+    let mut regex = regex::Regex::literal("a");
+    for _ in 0..500 {
+        regex = regex
+            .or(regex::Regex::literal("b"))
+            .then(regex::Regex::literal("c"));
+    }
+    let nfa = regex.to_nfa();
+    assert!(nfa.accepts_graphemes(&format!("b{}", "c".repeat(500))));
+    assert!(!nfa.accepts_graphemes(&format!("a{}", "c".repeat(499))));
+}
+
+#[test]
+fn corpus_report_counts() {
+    // Words over {a, b} ending with a
+    let dfa: Dfa = parser::dfa("   a b\n→ s t s\n* t t s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let corpus = "a\nb\nba\nab\naa\n\nbba\nxa\naxx\n\nåyx\nbab";
+    let report = analysis::corpus_report(&dfa, corpus.lines());
+    let counts = |accepted, rejected| analysis::LengthCounts { accepted, rejected };
+    assert_eq!(report.length_counts(0), counts(0, 2));
+    assert_eq!(report.length_counts(1), counts(1, 1));
+    assert_eq!(report.length_counts(2), counts(2, 2));
+    assert_eq!(report.length_counts(3), counts(1, 3));
+    assert_eq!(report.length_counts(4), counts(0, 0));
+    assert_eq!(report.total(), counts(4, 8));
+    assert_eq!(report.empty_lines, 2);
+    assert_eq!(report.lines_with_unknown_symbols, 3);
+    assert_eq!(report.unknown_symbol_occurrences(), 6);
+    assert_eq!(report.top_unknown_symbols(2), vec![("x", 4), ("y", 1)]);
+    assert_eq!(
+        report.top_unknown_symbols(5),
+        vec![("x", 4), ("y", 1), ("å", 1)]
+    );
+    // Reports of parts of the corpus can be merged
+    let mut merged = analysis::corpus_report(&dfa, corpus.lines().take(4));
+    merged.merge(&analysis::corpus_report(&dfa, corpus.lines().skip(4)));
+    assert_eq!(merged, report);
+    // NFAs give the same report, and reports can be extended line by line
+    let nfa = dfa.to_nfa();
+    let mut nfa_report = analysis::corpus_report(&nfa, corpus.lines().take(6));
+    corpus
+        .lines()
+        .skip(6)
+        .for_each(|line| nfa_report.record(&nfa, line));
+    assert_eq!(nfa_report, report);
+    assert_eq!(
+        report.to_string(),
+        "length accepted rejected\n\
+         0      0        2\n\
+         1      1        1\n\
+         2      2        2\n\
+         3      1        3\n\
+         total  4        8\n\
+         empty lines: 2\n\
+         unknown symbols: 6 occurrence(s) in 3 line(s), most common: \"x\" (4), \"y\" (1), \"å\" (1)"
+    );
+}
+
+/// Checks that the DFA and NFA round-trip through their tables, that minimizing the DFA keeps its language and that
+/// the NFA and DFA enumerate exactly the given words (up to `limit` words)
+fn check_tiny_automaton(nfa: &Nfa, words: &[&str], limit: usize) {
+    let dfa = nfa.to_dfa();
+    assert_eq!(dfa.alphabet(), nfa.alphabet());
+    assert!(dfa.initial_state().is_initial());
+    let mut minimized = dfa.clone();
+    minimized.minimize();
+    assert!(minimized.equivalent_to(&dfa));
+    assert!(minimized.clone().to_nfa().equivalent_to(nfa));
+    for dfa in [&dfa, &minimized] {
+        let table = dfa.to_table();
+        let parsed: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+        assert_eq!(parsed.to_table(), table);
+        assert!(parsed.equivalent_to(dfa));
+    }
+    let table = nfa.to_table();
+    let parsed: Nfa = parser::nfa(&table).unwrap().try_into().unwrap();
+    assert_eq!(parsed.to_table(), table);
+    assert!(parsed.equivalent_to(nfa));
+    // Words can only be enumerated without epsilon moves
+    let mut without_epsilon = nfa.clone();
+    without_epsilon.remove_epsilon_moves();
+    assert_eq!(
+        without_epsilon.words().take(limit).collect::<Vec<_>>(),
+        words
+    );
+    assert_eq!(
+        dfa.clone().to_nfa().words().take(limit).collect::<Vec<_>>(),
+        words
+    );
+}
+
+#[test]
+fn tiny_automata() {
+    // A single non-accepting state without transitions, so every transition of the DFA goes to the empty subset
+    let nfa: Nfa = parser::nfa("   a\n→ s {}").unwrap().try_into().unwrap();
+    check_tiny_automaton(&nfa, &[], 3);
+    assert_eq!(nfa.to_dfa().states_count(), 2);
+    let mut dfa = nfa.to_dfa();
+    dfa.minimize();
+    assert_eq!(dfa.states_count(), 1);
+
+    // A single accepting state looping on the only symbol
+    let nfa: Nfa = parser::nfa("   a\n→ * s {s}").unwrap().try_into().unwrap();
+    check_tiny_automaton(&nfa, &["", "a", "aa"], 3);
+    let dfa: Dfa = parser::dfa("   a\n→ * s s").unwrap().try_into().unwrap();
+    check_tiny_automaton(&dfa.to_nfa(), &["", "a", "aa"], 3);
+
+    // The initial state has no outgoing transitions at all, even though other states do
+    let nfa: Nfa = parser::nfa("   a b\n→ * s {} {}\n  t {s} {t}")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    check_tiny_automaton(&nfa, &[""], 3);
+
+    // Regexes without any symbols give automata with empty alphabets, whose tables only have the state column
+    for (regex, words) in [("ε", &[""][..]), ("∅", &[]), ("ε*|∅", &[""])] {
+        let nfa = parser::regex(regex).unwrap().to_nfa();
+        assert!(nfa.alphabet().is_empty());
+        check_tiny_automaton(&nfa, words, 3);
+        let mut dfa = nfa.to_dfa();
+        dfa.minimize();
+        assert_eq!(dfa.states_count(), 1);
+        assert_eq!(dfa.accepts(&[]), !words.is_empty());
+    }
+    let dfa = parser::regex("ε").unwrap().to_nfa().to_dfa();
+    assert_table_snapshot!(dfa, "tests/snapshots/empty_alphabet.txt");
+    let empty: Dfa = parser::dfa("state\n→ * s").unwrap().try_into().unwrap();
+    assert!(empty.equivalent_to(&dfa));
+    let empty: Nfa = parser::nfa("state\n→ s").unwrap().try_into().unwrap();
+    assert!(empty.alphabet().is_empty());
+    assert!(!empty.accepts(&[]));
+    // A corner header is only removed if the states have no transitions,
+    assert!(parser::dfa("state\n→ * s s")
+        .unwrap()
+        .try_into()
+        .map(|_: Dfa| ())
+        .is_ok());
+    // and states may only lack transitions if the alphabet is empty
+    assert!(parser::dfa("a\n→ * s").is_err());
+    assert!(parser::nfa("ε\n→ * s").is_err());
+}
+
+#[test]
+fn extended_regex_annotation() {
+    let options = parser::RegexOptions {
+        extended: true,
+        ..parser::RegexOptions::default()
+    };
+    let regex = parser::regex_with_options("~(a*)&(a|b)*", &options).unwrap();
+    let (nfa, provenance) = regex.to_nfa_annotated();
+    assert_eq!(provenance.len(), nfa.states().len());
+    assert!(provenance
+        .iter()
+        .all(|p| *p == regex::RegexProvenance::Compiled));
+    // Alternatives which are sequences are branches, while other alternatives are entered as themselves
+    let (_, provenance) = parser::regex("ab|c|d*").unwrap().to_nfa_annotated();
+    assert!(provenance.contains(&regex::RegexProvenance::AltEntry));
+    assert!(provenance.contains(&regex::RegexProvenance::AltBranch { index: 0 }));
+    assert!(!provenance.contains(&regex::RegexProvenance::SequenceEntry));
+    assert!(provenance.contains(&regex::RegexProvenance::SequenceJoint { index: 1, depth: 1 }));
+    assert!(provenance.contains(&regex::RegexProvenance::RepeatEntry));
+    let (_, provenance) = parser::regex("ε∅").unwrap().to_nfa_annotated();
+    assert!(provenance.contains(&regex::RegexProvenance::EpsilonIncoming));
+    assert!(provenance.contains(&regex::RegexProvenance::EmptyIncoming));
+}
+
+#[test]
+fn grading_relations() {
+    use crate::grading::{compare_language, LanguageRelation};
+
+    let spec = parser::regex("a(a|b)*").unwrap().to_min_dfa();
+    let relation =
+        |regex: &str| compare_language(&parser::regex(regex).unwrap().to_min_dfa(), &spec);
+    assert_eq!(relation("a(b|a)*|aa"), LanguageRelation::Equal);
+    assert_eq!(
+        relation("a(ba)*"),
+        LanguageRelation::Subset {
+            missing: "aa".to_string()
+        }
+    );
+    assert_eq!(
+        relation("(a|b)*"),
+        LanguageRelation::Superset {
+            extra: "".to_string()
+        }
+    );
+    assert_eq!(
+        relation("b(a|b)*|a"),
+        LanguageRelation::Incomparable {
+            missing: "aa".to_string(),
+            extra: "b".to_string()
+        }
+    );
+    // Symbols outside the alphabet of the specification are never accepted by it
+    let relation = relation("a(a|b|c)*");
+    assert_eq!(relation.extra(), Some("ac"));
+    assert_eq!(relation.missing(), None);
+    assert_eq!(
+        relation.to_string(),
+        "superset (too permissive), accepts \"ac\""
+    );
+
+    // The comparison doesn't depend on the order of the alphabet
+    let student: Dfa = parser::dfa("   b a\n→ s d t\n* t t t\n  d d d")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(compare_language(&student, &spec).is_equal());
+}
+
+#[test]
+fn remove_epsilon_moves_remap() {
+    // The branching_initial_state example of Nfa::remove_epsilon_moves
+    let nfa: Nfa = parser::nfa(
+        "
+           eps        a       b       c
+    -> i  {i0 i1 i2} {i0}    {i2}    {i0 i2}
+       i0 {i0}       {}      {}      {}
+       i1 {y}        {i0}    {i0}    {i}
+       i2 {}         {}      {i0}    {i0}
+       i3 {i1}       {i1}    {i1}    {i1}
+       x  {x}        {y}     {x}     {x}
+     * y  {}         {x}     {y}     {y}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut no_eps = nfa.clone();
+    let remap = no_eps.remove_epsilon_moves();
+    assert_eq!(remap.old_len(), 7);
+    assert_eq!(remap.new_len(), 5);
+    let new_idx = |name: &str| no_eps.states().iter().position(|s| s.name() == name);
+    for (old, state) in nfa.states().iter().enumerate() {
+        // The new initial state takes the name of the removed initial state i
+        assert_eq!(
+            remap.old_to_new(old),
+            new_idx(state.name()),
+            "{}",
+            state.name()
+        );
+    }
+    for (new, state) in no_eps.states().iter().enumerate() {
+        assert_eq!(nfa.states()[remap.new_to_old(new)].name(), state.name());
+    }
+    assert_eq!(remap.old_to_new(7), None);
+
+    // If the old initial state is kept, the new initial state still maps back to it
+    let accepting_initial: Nfa = parser::nfa(
+        "
+            ε     a    b
+    -> * i  {s1}  {}   {}
+         s1 {i}   {s1} {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut no_eps = accepting_initial.clone();
+    let remap = no_eps.remove_epsilon_moves();
+    let names = no_eps.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["i", "s1", "s_new"]);
+    assert_eq!(remap.old_to_new(0), Some(0));
+    assert_eq!(remap.old_to_new(1), Some(1));
+    assert_eq!(remap.new_to_old(2), 0);
+
+    // Without epsilon moves, nothing changes
+    assert_eq!(
+        no_eps.clone().remove_epsilon_moves(),
+        IndexRemap::identity(3)
+    );
+}
+
+#[test]
+fn equivalence_under_mapping() {
+    use crate::dfa::MappingError;
+
+    // The binary even/odd DFA of the documentation of the dfa module
+    let binary: Dfa = parser::dfa("       0   1\n-> even even odd\n * odd  even odd")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let letters: Dfa = parser::dfa("    b a\n-> s t s\n * t t s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(!binary.equivalent_to(&letters));
+    assert_eq!(
+        binary.equivalent_to_under(&letters, &[("a", "0"), ("b", "1")]),
+        Ok(true)
+    );
+    assert_eq!(
+        binary.equivalent_to_under(&letters, &[("b", "0"), ("a", "1")]),
+        Ok(false)
+    );
+    let nfa = |dfa: &Dfa| dfa.clone().to_nfa();
+    assert_eq!(
+        nfa(&binary).equivalent_to_under(&nfa(&letters), &[("a", "0"), ("b", "1")]),
+        Ok(true)
+    );
+    assert_eq!(
+        nfa(&binary).equivalent_to_under(&nfa(&letters), &[("b", "0"), ("a", "1")]),
+        Ok(false)
+    );
+
+    let error =
+        |mapping: &[(&str, &str)]| binary.equivalent_to_under(&letters, mapping).unwrap_err();
+    assert_eq!(
+        error(&[("a", "0")]),
+        MappingError::Unmapped(vec!["b".to_string()])
+    );
+    assert_eq!(
+        error(&[("a", "0")]).to_string(),
+        "the symbols [\"b\"] are unmapped"
+    );
+    assert_eq!(
+        error(&[("a", "0"), ("b", "0")]),
+        MappingError::NotInjective(vec!["0".to_string()])
+    );
+    assert_eq!(
+        error(&[("a", "0"), ("a", "1")]),
+        MappingError::MappedTwice(vec!["a".to_string()])
+    );
+    assert_eq!(
+        error(&[("a", "0"), ("b", "1"), ("c", "2")]),
+        MappingError::UnknownSource(vec!["c".to_string()])
+    );
+    assert_eq!(
+        error(&[("a", "0"), ("b", "2")]),
+        MappingError::UnknownTarget(vec!["2".to_string()])
+    );
+    let ternary: Dfa = parser::dfa("     0 1 2\n-> * s s s s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        ternary.equivalent_to_under(&letters, &[("a", "0"), ("b", "1")]),
+        Err(MappingError::Unreached(vec!["2".to_string()]))
+    );
+}
+
+#[test]
+fn minimize_named_odd_as() {
+    let odd_as = "
+           a  b
+    -> s1 s2 s1
+     * s2 s3 s2
+       s3 s4 s3
+     * s4 s1 s4
+       s5 s5 s5
+    ";
+    let original: Dfa = parser::dfa(odd_as).unwrap().try_into().unwrap();
+    let mut dfa = original.clone();
+    let merges = dfa.minimize_named();
+    let names = dfa.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["s1=s3", "s2=s4"]);
+    assert_eq!(
+        merges.into_iter().collect::<Vec<_>>(),
+        [
+            ("s1".into(), vec!["s3".into()]),
+            ("s2".into(), vec!["s4".into()])
+        ]
+    );
+    assert!(dfa.equivalent_to(&original));
+
+    let table = dfa.to_table();
+    let parsed: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+    assert_eq!(parsed, dfa);
+}
+
+#[test]
+fn minimize_named_fallbacks() {
+    // a and b are non-distinguishable, but their joined name is taken
+    let mut dfa: Dfa = parser::dfa("     x   y\n→ a   b   a=b\n  b   a   a=b\n* a=b a=b a=b")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    dfa.minimize_named();
+    let names = dfa.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["a=b'", "a=b"]);
+
+    // The name of a large class is truncated
+    let mut table = "   x\n".to_string();
+    for i in 0..20 {
+        table += &format!(
+            "{} state{i} state{}\n",
+            if i == 0 { "→ *" } else { "*" },
+            (i + 1) % 20
+        );
+    }
+    let mut dfa: Dfa = parser::dfa(&table).unwrap().try_into().unwrap();
+    let merges = dfa.minimize_named();
+    assert_eq!(dfa.states()[0].name(), "state0=state1=state10=state11=…+16");
+    assert_eq!(merges["state0"].len(), 19);
+    let parsed: Dfa = parser::dfa(&dfa.to_table()).unwrap().try_into().unwrap();
+    assert_eq!(parsed, dfa);
+}
+
+#[test]
+fn nfa_active_state_profile() {
+    let nfa: Nfa = parser::nfa(
+        "
+           a      b
+    → s  {s t}  {t}
+      t  {u}    {}
+    * u  {}     {u}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    // The active sets have sizes 1 2 3 2 for aab, 1 1 0 for bb (which dies) and 1 1 1 for ba
+    let inputs = ["aab", "bb", "ba"].into_iter().map(String::from);
+    let profile = nfa.active_state_profile(inputs);
+    let stats = |min, max, total, count| analysis::SizeStats {
+        min,
+        max,
+        total,
+        count,
+    };
+    assert_eq!(
+        profile.steps,
+        [
+            stats(1, 1, 3, 3),
+            stats(1, 2, 4, 3),
+            stats(0, 3, 4, 3),
+            stats(2, 2, 2, 1)
+        ]
+    );
+    assert_eq!(profile.overall, stats(0, 3, 13, 10));
+    assert_eq!(profile.steps[1].mean(), 4.0 / 3.0);
+    assert_eq!((profile.inputs, profile.died), (3, 1));
+    assert_eq!(profile.mean_death_depth(), Some(2.0));
+    // Profiles of parts of the inputs can be merged
+    let mut merged = nfa.active_state_profile(["bb"].into_iter().map(String::from));
+    merged.merge(&nfa.active_state_profile(["aab", "ba"].into_iter().map(String::from)));
+    assert_eq!(merged, profile);
+    assert_eq!(
+        profile.to_string(),
+        "step    inputs min max mean
+0       3      1   1   1.00
+1       3      1   2   1.33
+2       3      0   3   1.33
+3       1      2   2   2.00
+overall 10     0   3   1.30
+died: 1 of 3 input(s), after 2.00 step(s) on average"
+    );
+}
+
+#[test]
+fn nfa_edge_list_fixture() {
+    let table: Nfa = parser::nfa(include_str!("../tests/test_files/nfa1.nfa"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let edges: Nfa = parser::nfa_edges(include_str!("../tests/test_files/nfa1.edges"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(edges, table);
+    assert_eq!(table.to_edge_list().lines().count(), 1 + 6 + 16);
+}
+
+#[test]
+fn nfa_edge_list_errors() {
+    // Edges from undeclared states and on elements outside the alphabet are parse errors at their line
+    let error = parser::nfa_edges("alphabet: a\nstate s initial\nt -a-> s").unwrap_err();
+    assert_eq!(error.input, "t -a-> s");
+    let error = parser::nfa_edges("alphabet: a\nstate s initial\ns -b-> s\n").unwrap_err();
+    assert_eq!(error.input, "s -b-> s\n");
+    assert!(parser::nfa_edges("alphabet: a\nstate s initial\ns --> s").is_err());
+    assert!(parser::nfa_edges("state s initial\n").is_err());
+    // Edges to undeclared states are found when converting, as for tables
+    let parsed = parser::nfa_edges("alphabet: a\nstate s initial\ns -a-> t").unwrap();
+    assert_eq!(
+        Nfa::try_from(parsed).unwrap_err(),
+        nfa::NfaParseError::TransitionDoesNotExist("s".into(), "t".into())
+    );
+    // Quoted names and elements, and NFAs without alphabets
+    let nfa: Nfa = parser::nfa_edges(
+        "alphabet: \"a b\"\nstate \"s 0\" initial accepting\n\"s 0\" \"-a b->\" \"s 0\"",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert!(nfa.accepts(&["a b", "a b"]));
+    let nfa: Nfa = parser::nfa_edges("alphabet:\nstate s initial accepting")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(nfa.to_edge_list(), "alphabet:\nstate s initial accepting");
+}
+
+#[test]
+fn nfa_simulation_and_bisimulation() {
+    let late_choice: Nfa = parser::nfa(
+        "
+           a   b   c
+      -> s {t} {}  {}
+         t {}  {f} {f}
+       * f {}  {}  {}
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let early_choice: Nfa = parser::nfa(
+        "
+            a       b   c
+      -> s  {t1 t2} {}  {}
+         t1 {}      {f} {}
+         t2 {}      {}  {f}
+       * f  {}      {}  {}
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    // Equivalent but not bisimilar
+    assert!(late_choice.equivalent_to(&early_choice));
+    assert_eq!(late_choice.bisimilar_to(&early_choice), Some(false));
+    assert_eq!(late_choice.simulates(&early_choice), Some(true));
+    assert_eq!(early_choice.simulates(&late_choice), Some(false));
+
+    // Bisimilar (and thus equivalent), where the ε-moves and the order of the alphabet differ
+    let with_eps: Nfa = parser::nfa(
+        "
+            ε    c    b       a
+      -> s  {t}  {}   {}      {}
+         t  {}   {}   {}      {u v}
+         u  {}   {f}  {f}     {}
+         v  {w}  {}   {}      {}
+         w  {}   {f}  {f}     {}
+       * f  {}   {}   {}      {}
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert!(late_choice.equivalent_to(&with_eps));
+    assert_eq!(late_choice.bisimilar_to(&with_eps), Some(true));
+    assert_eq!(with_eps.bisimilar_to(&late_choice), Some(true));
+    assert_eq!(
+        with_eps.bisimulation_classes(),
+        vec![vec![0, 1], vec![2, 3, 4], vec![5]]
+    );
+
+    let other_alphabet = parser::regex("a(b|d)").unwrap().to_nfa();
+    assert_eq!(late_choice.simulates(&other_alphabet), None);
+    assert_eq!(late_choice.bisimilar_to(&other_alphabet), None);
+}
+
+#[test]
+fn dfa_alphabet_diff() {
+    let dfa = |table: &str| -> Dfa { parser::dfa(table).unwrap().try_into().unwrap() };
+    let abc = dfa("
+           a b c
+    -> * s s s s");
+    let cba = dfa("
+           c b a
+    -> * s s s s");
+    let abd = dfa("
+           a b d
+    -> * s s s s");
+    let diff = abc.alphabet_diff(&cba);
+    assert!(diff.is_equal());
+    assert_eq!(diff.shared, vec!["a".into(), "b".into(), "c".into()]);
+    assert!(abc.equivalent_to(&cba));
+
+    let diff = abc.alphabet_diff(&abd);
+    assert!(!diff.is_equal());
+    assert_eq!(diff.shared, vec!["a".into(), "b".into()]);
+    assert_eq!(diff.only_in_self, vec!["c".into()]);
+    assert_eq!(diff.only_in_other, vec!["d".into()]);
+    let reversed = abd.alphabet_diff(&abc);
+    assert_eq!(reversed.only_in_self, diff.only_in_other);
+    assert_eq!(reversed.only_in_other, diff.only_in_self);
+    assert!(!abc.equivalent_to(&abd));
+}
+
+#[test]
+fn dfa_missing_symbols_for_multi_grapheme_symbols() {
+    // "e\u{301}" is a single grapheme of two code points, while "ab" is an element of two graphemes
+    let table = "
+           e\u{301} ab c
+    -> * s  s        s  s";
+    let dfa: Dfa = parser::dfa(table).unwrap().try_into().unwrap();
+    let missing = |corpus: &[&str]| dfa.missing_symbols_for(corpus.iter().copied());
+    assert!(missing(&["", "c", "e\u{301}cc"]).is_empty());
+    assert_eq!(
+        missing(&["e\u{301}", "e"]).into_iter().collect::<Vec<_>>(),
+        vec!["e"]
+    );
+    assert_eq!(
+        missing(&["abc", "ba"]).into_iter().collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        missing(&["c\u{1F600}\u{1F600}"])
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["\u{1F600}"]
+    );
+}
+
+#[test]
+fn longest_match_with_priority() {
+    let alphabet = ('a'..='z').map(String::from).collect::<Vec<_>>();
+    let alphabet = alphabet.iter().map(String::as_str).collect::<Vec<_>>();
+    let lexer = |regex: &str, priority: u32| {
+        let regex = parser::regex(regex).unwrap();
+        let mut dfa = regex.to_nfa_with_alphabet(&alphabet).unwrap().to_dfa();
+        dfa.set_priority(Some(priority));
+        dfa
+    };
+    let letter = alphabet.join("|");
+    let keyword = lexer("if", 0);
+    let identifier = lexer(&format!("({letter})({letter})*"), 1);
+
+    let mut tokens = keyword.union(&identifier).unwrap();
+    let matched = |dfa: &Dfa, input: &str| {
+        dfa.longest_match_with_priority(input)
+            .map(|(len, priority, _)| (len, priority))
+    };
+    for dfa in [&tokens, &identifier.union(&keyword).unwrap()] {
+        assert_eq!(matched(dfa, "if"), Some((2, 0)));
+        assert_eq!(matched(dfa, "iff"), Some((3, 1)));
+        assert_eq!(matched(dfa, "i"), Some((1, 1)));
+        assert_eq!(matched(dfa, "if x"), Some((2, 0)));
+        assert_eq!(matched(dfa, "iffy(x)"), Some((4, 1)));
+        assert_eq!(matched(dfa, " if"), None);
+    }
+
+    // The priorities survive minimization, which doesn't merge the keyword into the identifiers
+    tokens.minimize();
+    assert_eq!(matched(&tokens, "if"), Some((2, 0)));
+    assert_eq!(matched(&tokens, "ab"), Some((2, 1)));
+    assert_eq!(tokens.states().len(), 4);
+
+    // Without priorities, the states accepting "if" and "ab" are merged
+    let mut plain = tokens.clone();
+    plain.set_priority(None);
+    plain.minimize();
+    assert_eq!(plain.states().len(), 2);
+    assert_eq!(matched(&plain, "if"), Some((2, u32::MAX)));
+
+    let last_wins = keyword
+        .union_with_priority(&identifier, |p1, p2| p2.or(p1))
+        .unwrap();
+    assert_eq!(matched(&last_wins, "if"), Some((2, 1)));
+    let intersection = keyword.intersection(&identifier).unwrap();
+    assert_eq!(matched(&intersection, "iff"), Some((2, 0)));
+
+    // Minimizing on other threads keeps the priorities as well
+    #[cfg(feature = "parallel")]
+    {
+        let tokens = vec![keyword.union(&identifier).unwrap(); 4];
+        let sequential = batch::minimize_all_with_jobs(tokens.clone(), 1);
+        let parallel = batch::minimize_all_with_jobs(tokens, 4);
+        for (sequential, parallel) in sequential.iter().zip(&parallel) {
+            assert_eq!(parallel.states().len(), sequential.states().len());
+            assert_eq!(parallel.states().len(), 4);
+            for input in ["if", "iff", "ab"] {
+                assert_eq!(matched(parallel, input), matched(sequential, input));
+            }
+        }
+    }
+}
+
+#[test]
+fn bfs_access_words() {
+    // The DFA of the example of Dfa::bfs, with its columns and states reordered
+    let dfa: Dfa = parser::dfa(
+        "
+           b  a
+        s4 s4 s4
+     -> s0 s2 s1
+      * s3 s3 s3
+        s2 s2 s3
+        s1 s3 s1
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let mut events = vec![];
+    dfa.bfs(|event| {
+        events.push(event);
+        ControlFlow::Continue(())
+    });
+    // The symbols are followed in the order of the alphabet, so b before a
+    let names = events
+        .iter()
+        .map(|event| dfa.states()[event.state].name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["s0", "s2", "s1", "s3"]);
+    assert_eq!(
+        events.iter().map(|event| event.depth).collect::<Vec<_>>(),
+        vec![0, 1, 1, 2]
+    );
+
+    let tree = dfa.bfs_tree();
+    let access_word = |mut state: usize| {
+        let mut word = vec![];
+        while let Some((parent, symbol)) = tree[state] {
+            word.push(dfa.alphabet()[symbol].as_ref());
+            state = parent;
+        }
+        word.reverse();
+        word.concat()
+    };
+    let words = (0..dfa.states().len())
+        .map(|state| (dfa.states()[state].name(), access_word(state)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        words,
+        vec![
+            ("s4", String::new()),
+            ("s0", String::new()),
+            ("s3", "ba".to_string()),
+            ("s2", "b".to_string()),
+            ("s1", "a".to_string())
+        ]
+    );
+    assert_eq!(tree[0], None);
+
+    // Breaking stops the search
+    let mut visited = 0;
+    dfa.bfs(|_| {
+        visited += 1;
+        ControlFlow::Break(())
+    });
+    assert_eq!(visited, 1);
+}
+
+#[test]
+fn duplicate_targets_enumerate_once() {
+    // The parser keeps targets listed more than once, which must not make the enumeration visit a word twice
+    let duplicated: Nfa = parser::nfa(
+        "
+           a          b
+    -> s0 {s1 s1}    {s0 s0}
+     * s1 {s1 s0 s1} {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(duplicated.states()[0].transitions()[0], vec![1, 1]);
+    let deduplicated: Nfa = parser::nfa(
+        "
+           a       b
+    -> s0 {s1}    {s0}
+     * s1 {s0 s1} {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+
+    let words = duplicated.words().take(200).collect::<Vec<_>>();
+    assert_eq!(words.iter().collect::<HashSet<_>>().len(), words.len());
+    assert_eq!(words, deduplicated.words().take(200).collect::<Vec<_>>());
+    for k in 0..6 {
+        let of_length = duplicated.words_of_length(k);
+        assert_eq!(
+            of_length.size_hint(),
+            deduplicated.words_of_length(k).size_hint()
+        );
+    }
+    let up_to_3 = duplicated.words_up_to_length(3).collect::<Vec<_>>();
+    assert_eq!(up_to_3, vec!["a", "aa", "ba", "aaa", "baa", "bba"]);
+}
+
+#[test]
+fn constructions_are_deterministic() {
+    // Every run parses the automata again, so that every hash map and hash set gets a new random seed
+    let nfa = || -> Nfa {
+        parser::nfa(include_str!("../tests/test_files/nfa1.nfa"))
+            .unwrap()
+            .try_into()
+            .unwrap()
+    };
+    let dfa = || -> Dfa {
+        parser::dfa(include_str!("../tests/test_files/eq_to_nfa1.dfa"))
+            .unwrap()
+            .try_into()
+            .unwrap()
+    };
+    // The names of the pairs ("p,q","r") and ("p","q,r") of the product collide
+    let colliding = || -> (Dfa, Dfa) {
+        let d1 = "   a b\n-> p,q p p,q\n * p p,q p";
+        let d2 = "   a b\n-> r q,r r\n * q,r r r";
+        (
+            parser::dfa(d1).unwrap().try_into().unwrap(),
+            parser::dfa(d2).unwrap().try_into().unwrap(),
+        )
+    };
+    let regex = || parser::regex("(a|b)*a(a|b)(a|b)|ba*b").unwrap().to_nfa();
+
+    let constructions: [(&str, &dyn Fn() -> String); 11] = [
+        ("to_dfa", &|| nfa().to_dfa().to_table()),
+        ("regex to_dfa", &|| regex().to_dfa().to_table()),
+        ("product", &|| {
+            let accepting = |s1: &DfaState, s2: &DfaState| s1.is_accepting() != s2.is_accepting();
+            let product = dfa().product_construction(&nfa().to_dfa(), accepting);
+            product.unwrap().to_table()
+        }),
+        ("colliding product", &|| {
+            let (d1, d2) = colliding();
+            d1.intersection(&d2).unwrap().to_table()
+        }),
+        ("union", &|| {
+            let (d1, d2) = colliding();
+            d1.union(&d2).unwrap().union(&dfa()).unwrap().to_table()
+        }),
+        ("nfa product", &|| {
+            let (d1, d2) = colliding();
+            let product = d1.to_nfa().intersection(&d2.to_nfa()).unwrap();
+            product.to_table()
+        }),
+        ("union_many", &|| {
+            let (d1, d2) = colliding();
+            Dfa::union_many(&[&d1, &d2, &d1]).unwrap().to_table()
+        }),
+        ("remove_epsilon_moves", &|| {
+            let mut nfa = nfa();
+            nfa.remove_epsilon_moves();
+            nfa.to_table()
+        }),
+        ("regex remove_epsilon_moves", &|| {
+            let mut nfa = regex();
+            nfa.remove_epsilon_moves();
+            nfa.to_table()
+        }),
+        ("minimize", &|| {
+            let mut dfa = dfa();
+            dfa.minimize();
+            dfa.to_table()
+        }),
+        ("regex minimize", &|| {
+            let mut dfa = regex().to_dfa();
+            dfa.minimize();
+            dfa.to_table()
+        }),
+    ];
+    for (name, construction) in constructions {
+        let first = construction();
+        for _ in 1..20 {
+            assert_eq!(construction(), first, "{name} is not deterministic");
+        }
+    }
+}
+
+#[test]
+fn right_linear_grammar_of_textbook_nfa() {
+    use crate::grammar::{Grammar, GrammarError, GrammarSymbol};
+    let nfa: Nfa = parser::nfa(
+        "
+           a       b
+    → s1 {s1 s2} {s1}
+      s2 {s3}    {}
+      s3 {}      {s4}
+    * s4 {}      {}
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let t = |s: &str| GrammarSymbol::Terminal(Rc::from(s));
+    let n = |s: &str| GrammarSymbol::NonTerminal(s.to_string());
+    let p = |lhs: &str, rhs: Vec<GrammarSymbol>| (lhs.to_string(), rhs);
+    // S → aS | aA | bS, A → aB, B → bC | b, C → ε
+    let textbook = Grammar {
+        start: "s1".to_string(),
+        productions: vec![
+            p("s1", vec![t("a"), n("s1")]),
+            p("s1", vec![t("a"), n("s2")]),
+            p("s1", vec![t("b"), n("s1")]),
+            p("s2", vec![t("a"), n("s3")]),
+            p("s3", vec![t("b"), n("s4")]),
+            p("s3", vec![t("b")]),
+            p("s4", vec![]),
+        ],
+    };
+    let grammar = nfa.to_right_linear_grammar();
+    assert_eq!(grammar, textbook);
+    assert_eq!(grammar.terminals(), [Rc::from("a"), Rc::from("b")]);
+    assert!(textbook.to_nfa().unwrap().equivalent_to(&nfa));
+
+    // Productions with several terminals, unit productions and terminals only
+    let grammar = Grammar {
+        start: "S".to_string(),
+        productions: vec![
+            p("S", vec![t("a"), t("a"), t("b"), n("S")]),
+            p("S", vec![n("A")]),
+            p("A", vec![t("b"), t("a")]),
+            p("A", vec![t("a")]),
+        ],
+    };
+    let converted = grammar.to_nfa().unwrap();
+    let names = converted
+        .states()
+        .iter()
+        .map(|s| s.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["S", "A", "S'", "S''", "F", "A'"]);
+    let regex = parser::regex("(aab)*(ba|a)").unwrap().to_nfa();
+    assert!(converted.equivalent_to(&regex));
+
+    let mut left_linear = grammar.clone();
+    left_linear.productions.push(p("A", vec![n("S"), t("a")]));
+    assert_eq!(
+        left_linear.to_nfa().unwrap_err(),
+        GrammarError::NotRightLinear { index: 4 }
+    );
+    assert_eq!(
+        grammar.to_nfa_with_alphabet(&["a", "b", "a"]).unwrap_err(),
+        GrammarError::DuplicateElement(Rc::from("a"))
+    );
+    let widened = grammar.to_nfa_with_alphabet(&["c", "b", "a"]).unwrap();
+    assert_eq!(widened.alphabet().len(), 3);
+    assert!(!widened.accepts(&["c"]));
+}
+
+#[test]
+fn coverage_of_tiny_corpus() {
+    let dfa: Dfa = parser::dfa(
+        "
+           a  b
+    → s0   s1 s0
+      s1   s2 s0
+    * s2   s2 s2
+      dead s0 dead
+    ",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    // "" visits s0; "ab" visits s0 s1 s0; "aab" visits s0 s1 s2 s2; "bxa" visits s0 s0 and stops at x
+    let coverage = dfa.coverage(["", "ab", "aab", "bxa"]);
+    assert_eq!(coverage.inputs, 4);
+    assert_eq!(coverage.state_visits, [6, 2, 2, 0]);
+    assert_eq!(coverage.transition_counts, [[2, 1], [1, 1], [0, 1], [0, 0]]);
+    assert_eq!(coverage.visits("s2"), Some(2));
+    assert_eq!(coverage.visits("s3"), None);
+    assert_eq!(coverage.unvisited_states(), ["dead"]);
+    assert_eq!(
+        coverage.untaken_transitions(),
+        [("s2", "a"), ("dead", "a"), ("dead", "b")]
+    );
+    assert_eq!(
+        coverage.to_string(),
+        "visited 3 of 4 state(s) and took 5 of 8 transition(s) over 4 input(s)\nunvisited states: dead\n\
+         untaken transitions from visited states: s2 -a->"
+    );
+
+    // The annotated table still parses to the same DFA
+    let annotated = coverage.annotate_table(&dfa.to_table());
+    assert!(annotated
+        .lines()
+        .nth(3)
+        .unwrap()
+        .ends_with("# 2 visit(s), untaken: a"));
+    assert!(annotated.lines().nth(4).unwrap().ends_with("# 0 visit(s)"));
+    let reparsed: Dfa = parser::dfa(&annotated).unwrap().try_into().unwrap();
+    assert_eq!(reparsed.to_table(), dfa.to_table());
+
+    let mut merged = dfa.coverage(["", "ab"]);
+    merged.merge(&dfa.coverage(["aab", "bxa"]));
+    assert_eq!(merged, coverage);
+}
+
+#[test]
+#[should_panic(expected = "merged coverages must be of the same DFA")]
+fn coverages_of_permuted_alphabets_are_not_merged() {
+    let dfa = |table: &str| -> Dfa { parser::dfa(table).unwrap().try_into().unwrap() };
+    let ab = dfa("     a  b\n→ s0 s1 s0\n* s1 s1 s1");
+    let ba = dfa("     b  a\n→ s0 s0 s1\n* s1 s1 s1");
+    assert!(ab.equivalent_to(&ba));
+    let mut coverage = ab.coverage(["ab"]);
+    coverage.merge(&ba.coverage(["ab"]));
+}
+
+#[test]
+fn invalid_alphabet_symbols_are_rejected() {
+    use crate::dfa::DfaParseError;
+    use crate::grammar::{Grammar, GrammarError, GrammarSymbol};
+    use crate::nfa::NfaParseError;
+    // Quoted cells may contain whitespace, but not start or end with it
+    let dfa = parser::dfa("   a \"b c\"\n→ s s s").unwrap();
+    assert!(Dfa::try_from(dfa).is_ok());
+    let dfa = parser::dfa("   a \" a\"\n→ s s s").unwrap();
+    assert_eq!(
+        Dfa::try_from(dfa).unwrap_err(),
+        DfaParseError::InvalidAlphabetSymbol(" a".into())
+    );
+    let nfa = parser::nfa("   ε  \"a \"\n→ s {} {}").unwrap();
+    assert_eq!(
+        Nfa::try_from(nfa).unwrap_err(),
+        NfaParseError::InvalidAlphabetSymbol("a ".into())
+    );
+
+    let grammar = Grammar {
+        start: "S".to_string(),
+        productions: vec![("S".to_string(), vec![GrammarSymbol::Terminal(Rc::from(""))])],
+    };
+    assert_eq!(
+        grammar.to_nfa().unwrap_err(),
+        GrammarError::InvalidTerminal(Rc::from(""))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn empty_symbols_are_rejected_in_json() {
+    use crate::dfa::DfaParseErrorOwned;
+    let json = r#"{"alphabet":["a",""],"states":[{"name":"s0","accepting":false,"transitions":[0,0]}],"initial_state":0}"#;
+    let error = Dfa::from_json_str(json).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains(&DfaParseErrorOwned::InvalidAlphabetSymbol(String::new()).to_string()),
+        "{error}"
+    );
+    let json = r#"{"alphabet":[""],"states":[{"name":"s0","accepting":false,"epsilon_transitions":[],"transitions":[[]]}],"initial_state":0}"#;
+    assert!(Nfa::from_json_str(json).is_err());
+}
+
+#[cfg(feature = "normalization")]
+#[test]
+fn normalization_detects_collisions() {
+    use crate::dfa::{NormalizationError, NormalizationForm};
+    // "é" as one character (NFC) and as "e" followed by a combining acute accent (NFD)
+    let (nfc, nfd) = ("\u{e9}", "e\u{301}");
+    let dfa: Dfa = parser::dfa(&format!("   {nfc} a\n→ s t s\n* t t t"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let normalized = dfa.normalized(NormalizationForm::Nfd).unwrap();
+    assert_eq!(normalized.alphabet()[0].as_ref(), nfd);
+    assert!(normalized.accepts(&[nfd]));
+    assert_eq!(normalized.normalized(NormalizationForm::Nfc).unwrap(), dfa);
+
+    let colliding: Dfa = parser::dfa(&format!("   {nfc} {nfd}\n→ s s s"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    for form in [NormalizationForm::Nfc, NormalizationForm::Nfd] {
+        assert_eq!(
+            colliding.normalized(form).unwrap_err(),
+            NormalizationError::AlphabetCollision(
+                nfc.to_string(),
+                nfd.to_string(),
+                form.normalize(nfc)
+            )
+        );
+    }
+    let colliding: Dfa = parser::dfa(&format!("   a\n→ {nfd} {nfc}\n  {nfc} {nfd}"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        colliding.normalized(NormalizationForm::Nfc).unwrap_err(),
+        NormalizationError::StateCollision(nfd.to_string(), nfc.to_string(), nfc.to_string())
+    );
+    // The compatibility forms replace the ligature "ﬁ" by "fi"
+    let ligature: Dfa = parser::dfa("   \u{fb01} fi\n→ s s s")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert!(ligature.normalized(NormalizationForm::Nfc).is_ok());
+    assert!(matches!(
+        ligature.normalized(NormalizationForm::Nfkc),
+        Err(NormalizationError::AlphabetCollision(..))
+    ));
+}
+
+#[test]
+fn patching_parsed_dfa_changes_exactly_one_cell() {
+    use crate::dfa::DfaParseErrorOwned;
+    use crate::parser::{ParsedDfaOwned, ParsedEditError};
+    let source = "
+           a  b
+      → s0 s1 s0
+      * s1 s1 s2
+        s2 s2 s2
+    ";
+    let parsed: ParsedDfaOwned = parser::dfa(source).unwrap().into();
+    let original: Dfa = parser::dfa(source).unwrap().try_into().unwrap();
+    assert_eq!(Dfa::try_from(parsed.clone()).unwrap(), original);
+
+    let mut patched = parsed.clone();
+    patched.set_transition("s1", "b", "s0").unwrap();
+    let patched: Dfa = patched.try_into().unwrap();
+    let (before, after) = (original.to_table_rows(), patched.to_table_rows());
+    let differing = before
+        .iter()
+        .flatten()
+        .zip(after.iter().flatten())
+        .filter(|(b, a)| b != a)
+        .collect::<Vec<_>>();
+    assert_eq!(differing, [(&"s2".to_string(), &"s0".to_string())]);
+    assert_eq!(after[2], ["", "*", "s1", "s1", "s0"]);
+
+    let mut edited = parsed.clone();
+    assert_eq!(
+        edited.set_transition("s3", "a", "s0"),
+        Err(ParsedEditError::UnknownState("s3".to_string()))
+    );
+    assert_eq!(
+        edited.set_transition("s0", "c", "s0"),
+        Err(ParsedEditError::UnknownSymbol("c".to_string()))
+    );
+    assert_eq!(
+        edited.add_state("s2", false, &["s2", "s2"]),
+        Err(ParsedEditError::DuplicateState("s2".to_string()))
+    );
+    assert_eq!(edited, parsed);
+    edited.set_transition("s0", "a", "s3").unwrap();
+    assert_eq!(
+        Dfa::try_from(edited.clone()).unwrap_err(),
+        DfaParseErrorOwned::TransitionDoesNotExist("s0".to_string(), "s3".to_string())
+    );
+    edited.add_state("s3", true, &["s3", "s0"]).unwrap();
+    edited.set_accepting("s1", false).unwrap();
+    let edited: Dfa = edited.try_into().unwrap();
+    assert!(edited.accepts(&["a", "b", "a"]));
+    assert!(!edited.accepts(&["a", "b", "b"]));
+    assert_eq!(edited.states().len(), 4);
+}
+
+#[test]
+fn patching_parsed_nfa() {
+    use crate::nfa::NfaParseErrorOwned;
+    use crate::parser::{ParsedEditError, ParsedNfaOwned};
+    let parsed: ParsedNfaOwned = parser::nfa(
+        "
+              a       b
+        → s0 {s0 s1} {s0}
+        * s1 {}      {}
+        ",
+    )
+    .unwrap()
+    .into();
+    assert_eq!(
+        parsed.clone().set_transition("s0", None, &["s1"]),
+        Err(ParsedEditError::UnknownSymbol("ε".to_string()))
+    );
+    let nfa: Nfa = parsed.try_into().unwrap();
+    let mut edited = nfa.to_parsed();
+    edited.set_transition("s0", None, &["s1"]).unwrap();
+    edited
+        .set_transition("s1", Some("b"), &["s1", "s2"])
+        .unwrap();
+    assert_eq!(
+        Nfa::try_from(edited.clone()).unwrap_err(),
+        NfaParseErrorOwned::TransitionDoesNotExist("s1".to_string(), "s2".to_string())
+    );
+    edited.add_state("s2", true, &[&[], &[], &[]]).unwrap();
+    let edited: Nfa = edited.try_into().unwrap();
+    assert!(edited.accepts(&[]));
+    assert!(edited.accepts(&["b"]));
+    assert!(!nfa.accepts(&["b"]));
+    assert_eq!(
+        Nfa::try_from(nfa.to_parsed()).unwrap().to_table(),
+        nfa.to_table()
+    );
+}
+
+#[test]
+fn nfa_union_all_edge_cases() {
+    let empty_union = Nfa::union_all(vec![]).unwrap();
+    assert!(empty_union.alphabet().is_empty());
+    assert!(!empty_union.accepts_graphemes(""));
+    let empty_intersection = Nfa::intersect_all(vec![]).unwrap();
+    assert!(empty_intersection.accepts_graphemes(""));
+
+    // The NFAs share state names, so the states of the union are numbered
+    let nfa: Nfa = parser::nfa("a b\n-> s {t} {}\n * t {} {t}")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let reordered: Nfa = parser::nfa("b a\n-> * s {s} {}")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let union = Nfa::union_all(vec![nfa.clone(), reordered.clone(), nfa.clone()]).unwrap();
+    assert_eq!(union.alphabet(), nfa.alphabet());
+    let names = union.states().iter().map(|s| s.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["1", "2", "3", "4", "5", "s_new"]);
+    assert!(union.accepts_graphemes("abb"));
+    assert!(union.accepts_graphemes("bbb"));
+    assert!(!union.accepts_graphemes("ba"));
+
+    let mismatch: Nfa = parser::nfa("a c\n-> * s {s} {s}")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let error = Nfa::intersect_all(vec![nfa.clone(), reordered, mismatch]).unwrap_err();
+    assert_eq!(error.index, 2);
+    assert_eq!(error.diff.only_in_self, vec!["b".into()]);
+    assert_eq!(error.diff.only_in_other, vec!["c".into()]);
+}
+
+#[test]
+fn prune_symbols_changes_alphabet() {
+    let table = "
+           a b c
+    -> s   t d d
+     * t   d t d
+       d   d d d";
+    let dfa: Dfa = parser::dfa(table).unwrap().try_into().unwrap();
+    let mut pruned = dfa.clone();
+    assert_eq!(pruned.prune_symbols(), vec!["c".into()]);
+    assert_eq!(pruned.prune_symbols(), vec![]);
+    assert!(!pruned.equivalent_to(&dfa));
+    assert!(pruned.intersection(&dfa).is_none());
+    assert_eq!(pruned.alphabet_diff(&dfa).only_in_other, vec!["c".into()]);
+    for word in [&["a"][..], &["a", "b", "b"], &["b"], &[]] {
+        assert_eq!(pruned.accepts(word), dfa.accepts(word));
+    }
+
+    // Transitions to the dead state are still transitions in a NFA, so only symbols without transitions are unused
+    let mut nfa = dfa.clone().to_nfa();
+    assert!(nfa.unused_symbols().is_empty());
+    let mut trimmed: Nfa = parser::nfa("a b c\n-> s {t} {} {}\n * t {} {t} {}")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(trimmed.prune_symbols(), vec!["c".into()]);
+    assert_eq!(nfa.prune_symbols(), vec![]);
+    assert!(!trimmed.equivalent_to(&nfa));
+    assert!(trimmed.accepts(&["a", "b"]));
+}
+
+#[test]
+fn table_snapshots() {
+    let nfa: Nfa = parser::nfa(include_str!("../tests/test_files/nfa1.nfa"))
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let dfa = nfa.to_dfa();
+    let mut minimized = dfa.clone();
+    minimized.minimize();
+    assert_table_snapshot!(nfa, "tests/snapshots/nfa1.txt");
+    assert_ascii_table_snapshot!(nfa, "tests/snapshots/nfa1_ascii.txt");
+    assert_table_snapshot!(dfa, "tests/snapshots/nfa1_dfa.txt");
+    assert_ascii_table_snapshot!(dfa, "tests/snapshots/nfa1_dfa_ascii.txt");
+    assert_table_snapshot!(minimized, "tests/snapshots/nfa1_minimized.txt");
+
+    // The examples of Nfa::union, where the union gets a new initial state
+    let ends_with_aab: Nfa = parser::nfa(
+        "
+                a       b
+        ->  s1 {s1 s2} {s1}
+            s2 {s3}    {}
+            s3 {}      {s4}
+          * s4 {}      {}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let contains_babb: Nfa = parser::nfa(
+        "
+                a    b
+        ->  s1 {s1} {s1 s2}
+            s2 {s3} {}
+            s3 {}   {s4}
+            s4 {}   {s5}
+          * s5 {s5} {s5}",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    let union = ends_with_aab.union(contains_babb).unwrap();
+    assert_table_snapshot!(union, "tests/snapshots/union.txt");
+    assert_ascii_table_snapshot!(union, "tests/snapshots/union_ascii.txt");
+
+    // The snapshotted tables are parsed to the same automata again
+    for nfa in [&nfa, &union] {
+        let parsed: Nfa = parser::nfa(&nfa.ascii_table()).unwrap().try_into().unwrap();
+        assert_eq!(&parsed, nfa);
+    }
+    for dfa in [&dfa, &minimized] {
+        let parsed: Dfa = parser::dfa(&dfa.ascii_table()).unwrap().try_into().unwrap();
+        assert_eq!(&parsed, dfa);
+    }
+}
+
+#[test]
+fn snapshot_diffs() {
+    let path = std::env::temp_dir()
+        .join(format!("dandy-snapshots-{}", std::process::id()))
+        .join("table.txt");
+    // A missing snapshot is written, and then matches
+    testing::check_snapshot("a\nb", &path).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\nb");
+    testing::check_snapshot("a\nb", &path).unwrap();
+    std::fs::write(&path, "a\r\nb").unwrap();
+    testing::check_snapshot("a\nb", &path).unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    let old = (1..=12)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let new = old.replace("2\n", "two\n").replace("\n12", "");
+    assert_eq!(
+        testing::unified_diff(&old, &new),
+        "@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n@@ -9,4 +9,3 @@\n 9\n 10\n 11\n-12\n"
+    );
+    assert_eq!(testing::unified_diff("", "a"), "@@ -0,0 +1,1 @@\n+a\n");
+    assert_eq!(testing::unified_diff("a\nb", "a\nb\n"), "");
+}
+
+/// The edit distance between two words, computed with the textbook dynamic programming algorithm
+fn edit_distance(a: &[&str], b: &[&str]) -> usize {
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let sub = diag + usize::from(x != y);
+            diag = row[j + 1];
+            row[j + 1] = sub.min(row[j] + 1).min(diag + 1);
+        }
+    }
+    row[b.len()]
+}
+
 proptest! {
     /// Tests that a DFA can be turned into a table with dfa.to_table() and then be
     /// parsed to the *very same* DFA again (not just equivalent)
@@ -84,13 +2746,24 @@ proptest! {
         assert_eq!(dfa, parsed_dfa);
     }
 
-    /// Tests that a DFA can be minimized and is then still equivalent to the original DFA
+    /// Tests that a table of a DFA separated by other whitespace than a single space can be parsed to the
+    /// very same DFA again
+    #[test]
+    fn dfa_table_separator_reparse(dfa in dfa(20, 20), sep in "[ \t]{1,4}") {
+        let parsed_dfa: Dfa = parser::dfa(&dfa.to_table_with_separator(&sep)).unwrap().try_into().unwrap();
+        assert_eq!(dfa, parsed_dfa);
+    }
+
+    /// Tests that a DFA can be minimized and is then still equivalent to the original DFA, and that it is minimal
+    /// exactly when minimizing it doesn't remove any states
     #[test]
     fn dfa_minimize_eq(dfa in dfa(25, 25)) { // This size is adequate, larger size takes too long time
         let mut minimized_dfa = dfa.clone();
         minimized_dfa.minimize();
-        assert!(minimized_dfa.equivalent_to(&dfa), "Minimized DFA should be equivalent to original");
-        assert!(dfa.equivalent_to(&minimized_dfa), "Original DFA should be equivalent to original");
+        assert!(minimized_dfa.is_minimal());
+        assert_eq!(dfa.is_minimal(), dfa.states().len() == minimized_dfa.states().len());
+        crate::assert_equivalent!(minimized_dfa, dfa, "Minimized DFA should be equivalent to original");
+        crate::assert_equivalent!(dfa, minimized_dfa, "Original DFA should be equivalent to original");
     }
 
     /// Tests that a DFA can be turned into an NFA and then turned back again to a DFA
@@ -98,8 +2771,8 @@ proptest! {
     #[test]
     fn dfa_to_nfa_to_dfa(dfa in dfa(50, 50)) {
         let converted = dfa.clone().to_nfa().to_dfa();
-        assert!(dfa.equivalent_to(&converted), "DFA should be equivalent to DFA->NFA->DFA");
-        assert!(converted.equivalent_to(&dfa), "DFA->NFA->DFA should be equivalent to DFA");
+        crate::assert_equivalent!(dfa, converted, "DFA should be equivalent to DFA->NFA->DFA");
+        crate::assert_equivalent!(converted, dfa, "DFA->NFA->DFA should be equivalent to DFA");
     }
 
 
@@ -111,13 +2784,64 @@ proptest! {
         assert_eq!(nfa, parsed_nfa);
     }
 
+    /// Tests that every NFA simulates and is bisimilar to itself, and that simulation implies inclusion of the
+    /// languages and bisimulation implies equivalence
+    #[test]
+    fn nfa_simulation_soundness(
+        nfa1 in fixed_alphabet_nfa(6, 'a'..='b', 2),
+        nfa2 in fixed_alphabet_nfa(6, 'a'..='b', 2),
+    ) {
+        assert_eq!(nfa1.bisimilar_to(&nfa1), Some(true));
+        assert_eq!(nfa1.simulates(&nfa1), Some(true));
+        if nfa1.bisimilar_to(&nfa2).unwrap() {
+            assert!(nfa1.equivalent_to(&nfa2));
+            assert_eq!(nfa1.simulates(&nfa2), Some(true));
+            assert_eq!(nfa2.simulates(&nfa1), Some(true));
+        }
+        if nfa1.simulates(&nfa2).unwrap() {
+            assert_eq!(nfa2.subset_of_antichain(&nfa1), Some(true));
+        }
+    }
+
+    /// Tests that merging the states of every bisimulation class gives an equivalent NFA, which is bisimilar to the
+    /// original NFA
+    #[test]
+    fn nfa_bisimulation_classes_merge(nfa in fixed_alphabet_nfa(8, 'a'..='b', 2)) {
+        let merged = nfa.bisimulation_quotient();
+        assert_eq!(merged.states.len(), nfa.bisimulation_classes().len());
+        assert!(merged.equivalent_to(&nfa));
+        assert_eq!(merged.bisimilar_to(&nfa), Some(true));
+    }
+
+    /// Tests that the edge list of a NFA can be parsed to the very same NFA again
+    #[test]
+    fn nfa_edge_list_reparse(nfa in nfa(50, 50)) {
+        let parsed_nfa: Nfa = parser::nfa_edges(&nfa.to_edge_list()).unwrap().try_into().unwrap();
+        assert_eq!(nfa, parsed_nfa);
+    }
+
+    /// Tests that the edge list of a DFA is parsed to the NFA of that DFA
+    #[test]
+    fn dfa_edge_list_reparse(dfa in dfa(50, 50)) {
+        let parsed_nfa: Nfa = parser::nfa_edges(&dfa.to_edge_list()).unwrap().try_into().unwrap();
+        assert_eq!(dfa.to_nfa(), parsed_nfa);
+    }
+
+    /// Tests that a table of a NFA separated by other whitespace than a single space can be parsed to the
+    /// very same NFA again
+    #[test]
+    fn nfa_table_separator_reparse(nfa in nfa(20, 20), sep in "[ \t]{1,4}") {
+        let parsed_nfa: Nfa = parser::nfa(&nfa.to_table_with_separator(&sep)).unwrap().try_into().unwrap();
+        assert_eq!(nfa, parsed_nfa);
+    }
+
     /// Tests that a NFA can be turned into an DFA and then turned back again to a NFA
     /// while still being equivalent to the original NFA
     #[test]
     fn nfa_to_dfa_to_nfa(nfa in nfa(25, 25)) {
         let converted = nfa.to_dfa().to_nfa();
-        assert!(nfa.equivalent_to(&converted), "NFA should be equivalent to NFA->DFA->NFA");
-        assert!(converted.equivalent_to(&nfa), "NFA->DFA->NFA should be equivalent to NFA");
+        crate::assert_equivalent!(nfa, converted, "NFA should be equivalent to NFA->DFA->NFA");
+        crate::assert_equivalent!(converted, nfa, "NFA->DFA->NFA should be equivalent to NFA");
     }
 
     #[test]
@@ -140,6 +2864,99 @@ proptest! {
         }
     }
 
+    #[test]
+    fn dfa_concatenate_and_star(
+        dfa1 in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        dfa2 in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        tests in prop::collection::vec(("[a-c]{0,6}", "[a-c]{0,6}"), 50)
+    ) {
+        let concatenation = dfa1.concatenate(&dfa2).unwrap();
+        let star = dfa1.star();
+        assert!(star.accepts_graphemes(""));
+        for (w1, w2) in tests.iter() {
+            if dfa1.accepts_graphemes(w1) && dfa2.accepts_graphemes(w2) {
+                assert!(concatenation.accepts_graphemes(&format!("{w1}{w2}")));
+            }
+            // A word is in the concatenation iff some split of it is accepted by the DFAs
+            let word = format!("{w1}{w2}");
+            let split = (0..=word.len())
+                .any(|i| dfa1.accepts_graphemes(&word[..i]) && dfa2.accepts_graphemes(&word[i..]));
+            assert_eq!(concatenation.accepts_graphemes(&word), split);
+            // A word is in the star iff it can be split into words accepted by the DFA
+            let mut splits = vec![false; word.len() + 1];
+            splits[0] = true;
+            for end in 1..=word.len() {
+                splits[end] = (0..end).any(|start| splits[start] && dfa1.accepts_graphemes(&word[start..end]));
+            }
+            assert_eq!(star.accepts_graphemes(&word), splits[word.len()]);
+        }
+    }
+
+    #[test]
+    fn bounded_product_construction(
+        dfa1 in fixed_alphabet_dfa(51, 'a'..='c', ('a'..='c').count()),
+        dfa2 in fixed_alphabet_dfa(51, 'a'..='c', ('a'..='c').count()),
+    ) {
+        let xor = |s1: &crate::dfa::DfaState, s2: &crate::dfa::DfaState| s1.is_accepting() != s2.is_accepting();
+        let unbounded = dfa1.product_construction(&dfa2, xor).unwrap();
+
+        match dfa1.product_construction_bounded(&dfa2, xor, 10, None).unwrap() {
+            Ok(product) => {
+                assert!(product.states().len() <= 10);
+                assert_eq!(product.states().len(), unbounded.states().len());
+                assert!(product.equivalent_to(&unbounded));
+            }
+            Err(e) => {
+                assert!(unbounded.states().len() > 10);
+                assert!(e.explored <= 10);
+                assert_eq!(e.theoretical_max, Some(dfa1.states().len() * dfa2.states().len()));
+            }
+        }
+
+        let mut reports = vec![];
+        let max = dfa1.states().len() * dfa2.states().len();
+        let product = dfa1
+            .product_construction_bounded(&dfa2, xor, max, Some(&mut |explored| reports.push(explored)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(product.states().len(), unbounded.states().len());
+        assert!(product.equivalent_to(&unbounded));
+        let interval = crate::dfa::PRODUCT_PROGRESS_INTERVAL;
+        let expected = (1..=product.states().len() / interval).map(|n| n * interval).collect::<Vec<_>>();
+        assert_eq!(reports, expected);
+    }
+
+    #[test]
+    fn language_fingerprint_of_equivalent_dfas(dfa in dfa(20, 5)) {
+        let converted = dfa.clone().to_nfa().to_dfa();
+        assert_eq!(dfa.language_fingerprint(), converted.language_fingerprint());
+    }
+
+    #[test]
+    fn preimage_matches_transitions(mut dfa in dfa(20, 5), targets in prop::collection::hash_set(0..20usize, 0..5)) {
+        let check = |dfa: &Dfa| {
+            let targets = targets.iter().map(|t| t % dfa.states().len()).collect::<HashSet<_>>();
+            for (elem, symbol) in dfa.alphabet().iter().enumerate() {
+                let expected = (0..dfa.states().len())
+                    .filter(|&from| targets.contains(&dfa.states()[from].transitions()[elem]))
+                    .collect::<HashSet<_>>();
+                assert_eq!(dfa.preimage(&targets, symbol), expected);
+            }
+            for to in 0..dfa.states().len() {
+                let mut expected = (0..dfa.alphabet().len())
+                    .flat_map(|elem| (0..dfa.states().len()).map(move |from| (from, elem)))
+                    .filter(|&(from, elem)| dfa.states()[from].transitions()[elem] == to)
+                    .collect::<Vec<_>>();
+                expected.sort_by_key(|&(from, elem)| (elem, from));
+                assert_eq!(dfa.predecessors(to), expected);
+            }
+        };
+        check(&dfa);
+        // The reversed transitions are rebuilt after modifying the DFA
+        dfa.minimize();
+        check(&dfa);
+    }
+
     #[test]
     fn nfa_binary_ops(
         // This takes a really long time to run, so we reduce the size of NFAs tested and amount of test cases
@@ -209,6 +3026,12 @@ proptest! {
         let mut no_unr_states = nfa.clone();
         no_unr_states.remove_unreachable_states();
         assert!(nfa.equivalent_to(&no_unr_states));
+
+        // The reachable states keep their relative order
+        let reachable = nfa.reachable_states().into_iter().map(|state| state.name()).collect::<Vec<_>>();
+        let remaining = no_unr_states.states().iter().map(|state| state.name()).collect::<Vec<_>>();
+        assert_eq!(reachable, remaining);
+        assert_eq!(nfa.reachable_state_idx().len() + nfa.unreachable_state_idx().len(), nfa.states().len());
     }
 
     #[test]
@@ -259,6 +3082,112 @@ proptest! {
         }
     }
 
+    #[test]
+    fn nfa_words_of_length(
+        dfa in fixed_alphabet_dfa(10, 'a'..='c', ('a'..='c').count())
+    ) {
+        let nfa = dfa.to_nfa();
+        let expected = nfa.words()
+            .take_while(|w| w.len() <= 5)
+            .filter(|w| w.len() == 5)
+            .collect::<Vec<_>>();
+        assert_eq!(nfa.words_of_length(5).count(), expected.len());
+        assert_eq!(nfa.words_of_length(5).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn dfa_count_words(
+        dfa in fixed_alphabet_dfa(10, 'a'..='c', ('a'..='c').count()),
+        n in 0usize..6
+    ) {
+        let nfa = dfa.clone().to_nfa();
+        let counted = dfa.count_words_up_to_length(n).unwrap();
+        let enumerated = nfa.word_components().take_while(|w| w.len() <= n).count();
+        assert_eq!(counted, enumerated as u64);
+
+        let exactly_n = nfa.word_components()
+            .take_while(|w| w.len() <= n)
+            .filter(|w| w.len() == n)
+            .count();
+        assert_eq!(dfa.count_words_of_length(n), exactly_n as u64);
+    }
+
+    #[test]
+    fn dfa_checkpoint_resume(
+        dfa in fixed_alphabet_dfa(20, 'a'..='f', ('a'..='f').count()),
+        prefix in "[a-f]*",
+        suffix in "[a-f]*"
+    ) {
+        let mut evaluator = dfa.evaluator();
+        prefix.graphemes(true).for_each(|g| { evaluator.step(g); });
+        let checkpoint = evaluator.checkpoint();
+
+        let mut resumed = dfa.resume(&checkpoint).unwrap();
+        suffix.graphemes(true).for_each(|g| { resumed.step(g); });
+        assert_eq!(resumed.is_accepting(), dfa.accepts_graphemes(&format!("{prefix}{suffix}")));
+
+        let mut inverted = dfa.clone();
+        inverted.invert();
+        assert!(inverted.resume(&checkpoint).is_err());
+    }
+
+    #[test]
+    fn nfa_checkpoint_resume(
+        nfa in fixed_alphabet_nfa(10, 'a'..='f', ('a'..='f').count()),
+        prefix in "[a-f]*",
+        suffix in "[a-f]*"
+    ) {
+        let mut evaluator = nfa.evaluator();
+        prefix.graphemes(true).for_each(|g| { evaluator.step(g); });
+        let checkpoint = evaluator.checkpoint();
+
+        let mut resumed = nfa.resume(&checkpoint).unwrap();
+        suffix.graphemes(true).for_each(|g| { resumed.step(g); });
+        assert_eq!(resumed.is_accepting(), nfa.accepts_graphemes(&format!("{prefix}{suffix}")));
+
+        let mut other = nfa.clone();
+        other.states[0].accepting = !other.states[0].accepting;
+        assert!(other.resume(&checkpoint).is_err());
+
+        // The order of the targets of a transition doesn't change the fingerprint
+        let mut reordered = nfa.clone();
+        reordered.states.iter_mut().for_each(|state| {
+            state.epsilon_transitions.reverse();
+            state.transitions.iter_mut().for_each(|targets| targets.reverse());
+        });
+        assert_eq!(reordered.fingerprint(), nfa.fingerprint());
+        assert!(reordered.resume(&checkpoint).is_ok());
+    }
+
+    /// Tests that the lazily determinized evaluator accepts the same words as the NFA, also when only a few sets of
+    /// states may be cached, and that it is in the same states as the NFA evaluator
+    #[test]
+    fn nfa_lazy_dfa_evaluator(
+        nfa in fixed_alphabet_nfa(10, 'a'..='c', ('a'..='c').count()),
+        words in prop::collection::vec("[a-d]{0,12}", 30)
+    ) {
+        let mut lazy = nfa.lazy_dfa_evaluator();
+        let mut bounded = nfa.lazy_dfa_evaluator().with_max_states(3);
+        for word in &words {
+            let accepted = nfa.accepts_graphemes(word);
+            assert_eq!(lazy.accepts_graphemes(word), accepted);
+            assert_eq!(bounded.accepts_graphemes(word), accepted);
+            assert!(bounded.discovered_states() <= 3);
+
+            let mut eval = nfa.evaluator();
+            lazy.reset();
+            for g in word.graphemes(true) {
+                let known = eval.step(g).is_some();
+                assert_eq!(lazy.step(g).is_some(), known);
+                if !known {
+                    break;
+                }
+                assert_eq!(lazy.current_states_idx(), Some(eval.state_key().as_slice()));
+            }
+        }
+        assert!(lazy.discovered_states() <= nfa.to_dfa().states().len());
+    }
+
     #[test]
     fn regex(
         regex_str in random_regex("[a-z]"),
@@ -279,6 +3208,869 @@ proptest! {
         })
     }
 
+    /// Tests that extended regexes with intersections and complements match the same words as the corresponding
+    /// combination of library regexes
+    #[test]
+    fn extended_regex(
+        regex1 in random_regex("[a-c]"),
+        regex2 in random_regex("[a-c]"),
+        tests in prop::collection::vec("[a-c]*", 20)
+    ) {
+        let options = parser::RegexOptions { extended: true, ..parser::RegexOptions::default() };
+        let regex = parser::regex_with_options(&format!("({regex1})&~({regex2})"), &options).unwrap();
+        let dfa = regex.to_dfa();
+        let lib_regex1 = LibRegex::new(&format!("^({regex1})$")).unwrap();
+        let lib_regex2 = LibRegex::new(&format!("^({regex2})$")).unwrap();
+
+        // The complement is taken over the characters of the regex
+        let accepted_chars = format!("{regex1}{regex2}").chars().collect::<HashSet<_>>();
+        tests.iter().for_each(|test| {
+            let s = test.chars().filter(|c| accepted_chars.contains(c)).collect::<String>();
+            assert_eq!(dfa.accepts_graphemes(&s), lib_regex1.is_match(&s) && !lib_regex2.is_match(&s));
+        })
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn batch_is_deterministic(dfas in prop::collection::vec(dfa(10, 4), 0..30)) {
+        let sequential = batch::minimize_all_with_jobs(dfas.clone(), 1);
+        for jobs in [2, 3, 8] {
+            let parallel = batch::minimize_all_with_jobs(dfas.clone(), jobs);
+            prop_assert_eq!(parallel.len(), sequential.len());
+            for (parallel, sequential) in parallel.iter().zip(&sequential) {
+                prop_assert_eq!(parallel.to_table(), sequential.to_table());
+                prop_assert!(parallel.equivalent_to(sequential));
+            }
+        }
+        if let Some(reference) = dfas.first() {
+            let expected = dfas.iter().map(|dfa| reference.equivalent_to(dfa)).collect::<Vec<_>>();
+            prop_assert!(expected[0]);
+            for jobs in [1, 2, 3, 8] {
+                prop_assert_eq!(&batch::equivalence_all_with_jobs(reference, &dfas, jobs), &expected);
+                prop_assert_eq!(&batch::equivalence_all_with_jobs(reference, &sequential, jobs), &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn dfa_minimize_remap(dfa in fixed_alphabet_dfa(10, 'a'..='c', ('a'..='c').count())) {
+        let mut minimized = dfa.clone();
+        let remap = minimized.minimize();
+        prop_assert_eq!(remap.old_len(), dfa.states().len());
+        prop_assert_eq!(remap.new_len(), minimized.states().len());
+        let reachable = dfa.reachable_state_idx();
+        for (old, state) in dfa.states().iter().enumerate() {
+            let Some(new) = remap.old_to_new(old) else {
+                prop_assert!(!reachable.contains(&old));
+                continue;
+            };
+            // Every reachable state maps to the state it was merged into, which behaves the same
+            let new_state = &minimized.states()[new];
+            prop_assert_eq!(state.is_accepting(), new_state.is_accepting());
+            for (elem, &to) in state.transitions().iter().enumerate() {
+                prop_assert_eq!(remap.old_to_new(to), Some(new_state.transitions()[elem]));
+            }
+        }
+        for (new, state) in minimized.states().iter().enumerate() {
+            let old = remap.new_to_old(new);
+            prop_assert_eq!(dfa.states()[old].name(), state.name());
+            prop_assert_eq!(remap.old_to_new(old), Some(new));
+        }
+        prop_assert_eq!(
+            remap.old_to_new(dfa.initial_state_index()),
+            Some(minimized.initial_state_index())
+        );
+    }
+
+    #[test]
+    fn nfa_optimize_remap(nfa in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count())) {
+        let mut optimized = nfa.clone();
+        let remap = optimized.optimize();
+        prop_assert_eq!(remap.new_len(), optimized.states().len());
+        for (new, state) in optimized.states().iter().enumerate() {
+            let old = remap.new_to_old(new);
+            if state.name() == nfa.states()[old].name() {
+                prop_assert_eq!(remap.old_to_new(old), Some(new));
+            } else {
+                // Only a new initial state may have a new name, and it maps back to the kept old initial state
+                prop_assert!(state.is_initial());
+                prop_assert_eq!(old, nfa.initial_state_index());
+                prop_assert!(remap.old_to_new(old).is_some_and(|kept| kept != new));
+            }
+        }
+        for (old, state) in nfa.states().iter().enumerate() {
+            if let Some(new) = remap.old_to_new(old) {
+                prop_assert!(
+                    optimized.states()[new].name() == state.name() || optimized.states()[new].is_initial()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dfa_closest_accepted(
+        dfa in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        word in "[a-d]{0,4}",
+        max_edits in 0usize..3
+    ) {
+        let word = word.graphemes(true).collect::<Vec<_>>();
+        let found = dfa.closest_accepted(&word, max_edits);
+        // Any word within max_edits of `word` is at most max_edits longer than it
+        let expected = dfa.clone().to_nfa().word_components()
+            .take_while(|w| w.len() <= word.len() + max_edits)
+            .map(|w| edit_distance(&w.iter().map(|e| e.as_ref()).collect::<Vec<_>>(), &word))
+            .filter(|&d| d <= max_edits)
+            .min();
+        assert_eq!(found.as_ref().map(|(_, d)| *d), expected);
+        if let Some((closest, distance)) = found {
+            let closest = closest.iter().map(|e| e.as_ref()).collect::<Vec<_>>();
+            assert!(dfa.accepts(&closest));
+            assert_eq!(edit_distance(&closest, &word), distance);
+        }
+    }
+
+    #[test]
+    fn regex_to_min_dfa(regex_str in random_regex("[a-e]")) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let nfa = regex.clone().to_nfa();
+        let mut naive = nfa.to_dfa();
+        naive.minimize();
+
+        let (dfa, report) = regex.to_min_dfa_with_report();
+        assert!(dfa.equivalent_to(&naive));
+        assert_eq!(report.nfa_states, nfa.states().len());
+        assert_eq!(report.min_dfa_states, naive.states().len());
+        assert_eq!(report.min_dfa_states, dfa.states().len());
+        assert!(report.min_dfa_states <= report.dfa_states);
+        assert_eq!(dfa.initial_state_index(), 0);
+        assert!(dfa.states().iter().enumerate().all(|(idx, s)| s.name() == idx.to_string()));
+        assert_eq!(regex.to_min_dfa(), dfa);
+    }
+
+    #[test]
+    fn regex_to_nfa_debug(regex_str in random_regex("[a-e]")) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let (nfa, debug) = regex.clone().to_nfa_debug();
+        assert_eq!(&nfa, &regex.to_nfa());
+        assert_eq!(debug.roles().len(), nfa.states().len());
+        // Every state transitioning on a character is the state entering that character in the tree
+        for (idx, state) in nfa.states().iter().enumerate() {
+            for (elem, targets) in state.transitions().iter().enumerate() {
+                if !targets.is_empty() {
+                    let Some(regex::StateRole::Enter(tree_ref)) = debug.role(idx) else {
+                        panic!("State {idx} transitions on an element but does not enter a subtree");
+                    };
+                    let expected = regex::RegexNode::Char(regex::RegexChar::Grapheme(nfa.alphabet()[elem].clone()));
+                    assert_eq!(tree_ref.node(), &expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regex_to_nfa_annotated(regex_str in random_regex("[a-e]")) {
+        let regex = parser::regex(&regex_str).unwrap();
+        let (nfa, provenance) = regex.to_nfa_annotated();
+        assert_eq!(&nfa, &regex.clone().to_nfa());
+        assert_eq!(provenance.len(), nfa.states().len());
+        // The character states are exactly the states with transitions, each only on its own grapheme, and there is
+        // at least one of them for every distinct grapheme of the regex
+        let mut graphemes = HashSet::new();
+        for (state, provenance) in nfa.states().iter().zip(&provenance) {
+            let elems = state
+                .transitions()
+                .iter()
+                .enumerate()
+                .filter(|(_, targets)| !targets.is_empty())
+                .map(|(elem, _)| nfa.alphabet()[elem].clone())
+                .collect::<Vec<_>>();
+            match provenance {
+                regex::RegexProvenance::CharIncoming { grapheme } => {
+                    assert_eq!(elems.len(), 1);
+                    assert_eq!(&elems[0], grapheme);
+                    graphemes.insert(grapheme.clone());
+                }
+                _ => assert!(elems.is_empty()),
+            }
+        }
+        assert_eq!(graphemes, nfa.alphabet().iter().cloned().collect());
+        assert_eq!(provenance.iter().filter(|p| **p == regex::RegexProvenance::Initial).count(), 1);
+        assert_eq!(provenance.iter().filter(|p| **p == regex::RegexProvenance::Accepting).count(), 1);
+    }
+
+    #[test]
+    fn nfa_alphabet_reorder(
+        nfa in fixed_alphabet_nfa(8, 'a'..='d', ('a'..='d').count()),
+        order in Just(vec!["a", "b", "c", "d"]).prop_shuffle()
+    ) {
+        let mut nfa = nfa;
+        nfa.remove_epsilon_moves();
+        let reordered = nfa.with_alphabet_order(&order).unwrap();
+        assert!(reordered.equivalent_to(&nfa));
+        assert_eq!(reordered.alphabet().iter().map(|e| e.as_ref()).collect::<Vec<_>>(), order);
+
+        // Words are enumerated in shortlex order according to the respective alphabets
+        let rank = |nfa: &Nfa, word: &[Rc<str>]| {
+            let idx = word.iter().map(|e| nfa.alphabet().iter().position(|a| a == e).unwrap()).collect::<Vec<_>>();
+            (word.len(), idx)
+        };
+        let before = nfa.word_components().take(30).collect::<Vec<_>>();
+        let after = reordered.word_components().take(30).collect::<Vec<_>>();
+        assert!(before.windows(2).all(|w| rank(&nfa, &w[0]) < rank(&nfa, &w[1])));
+        assert!(after.windows(2).all(|w| rank(&reordered, &w[0]) < rank(&reordered, &w[1])));
+        // The same words are enumerated (up to the length where both enumerations are complete)
+        let max_len = before.last().map_or(0, Vec::len).min(after.last().map_or(0, Vec::len));
+        let complete = |words: &[Vec<Rc<str>>]| {
+            words.iter().filter(|w| w.len() < max_len).cloned().collect::<HashSet<_>>()
+        };
+        assert_eq!(complete(&before), complete(&after));
+    }
+
+    #[test]
+    fn nfa_memory_grows_with_states(
+        a in fixed_alphabet_nfa(10, 'a'..='c', ('a'..='c').count()),
+        b in fixed_alphabet_nfa(10, 'a'..='c', ('a'..='c').count())
+    ) {
+        // Adding a state makes the NFA strictly larger
+        let mut larger = a.clone();
+        let mut bytes = larger.approx_memory_bytes();
+        for idx in 0..5 {
+            larger.states.push(NfaState {
+                name: Rc::from(format!("new{idx}")),
+                initial: false,
+                accepting: false,
+                epsilon_transitions: vec![0],
+                transitions: vec![vec![]; larger.alphabet.len()],
+            });
+            let new_bytes = larger.approx_memory_bytes();
+            assert!(new_bytes > bytes);
+            bytes = new_bytes;
+        }
+
+        // Prefix the names of b so that the union doesn't need to rename any states
+        let mut b = b;
+        b.states.iter_mut().for_each(|state| state.name = Rc::from(format!("b_{}", state.name)));
+        // Cloning drops any excess capacity of the generated vectors, so the union is compared to the clones
+        let (a, b) = (a.clone(), b.clone());
+        let union = a.clone().union(b.clone()).unwrap();
+        assert!(union.approx_memory_bytes() > a.approx_memory_bytes());
+        assert!(union.approx_memory_bytes() > b.approx_memory_bytes());
+        // The new initial state has epsilon transitions to both initial states
+        assert_eq!(union.transition_count(), a.transition_count() + b.transition_count() + 2);
+        assert_eq!(
+            union.epsilon_transition_count(),
+            a.epsilon_transition_count() + b.epsilon_transition_count() + 2
+        );
+        assert_eq!(union.accepting_state_count(), a.accepting_state_count() + b.accepting_state_count());
+    }
+
+    #[test]
+    fn dfa_transition_count(dfa in dfa(20, 10)) {
+        assert_eq!(dfa.transition_count(), dfa.states().iter().map(|s| s.transitions().len()).sum::<usize>());
+        let nfa = dfa.clone().to_nfa();
+        assert_eq!(nfa.transition_count(), dfa.transition_count());
+        assert_eq!(nfa.epsilon_transition_count(), 0);
+        assert_eq!(nfa.accepting_state_count(), dfa.accepting_state_count());
+        assert_eq!(dfa.states_count(), dfa.states().len());
+        assert_eq!(dfa.alphabet_size(), dfa.alphabet().len());
+        assert_eq!(nfa.states_count(), dfa.states_count());
+        assert_eq!(nfa.alphabet_size(), dfa.alphabet_size());
+    }
+
+    #[test]
+    fn alphabet_index(dfa in dfa(20, 10), nfa in nfa(20, 10)) {
+        for (idx, elem) in dfa.alphabet().iter().enumerate() {
+            assert_eq!(dfa.alphabet_index(elem), Some(idx));
+        }
+        for (idx, elem) in nfa.alphabet().iter().enumerate() {
+            assert_eq!(nfa.alphabet_index(elem), Some(idx));
+        }
+        // Elements of the alphabet never contain whitespace, so this is never part of them
+        assert_eq!(dfa.alphabet_index("not an element"), None);
+        assert_eq!(nfa.alphabet_index("not an element"), None);
+
+        // Converting keeps the alphabet (and the lookup table) intact
+        let converted = dfa.clone().to_nfa();
+        for (idx, elem) in dfa.alphabet().iter().enumerate() {
+            assert_eq!(converted.alphabet_index(elem), Some(idx));
+        }
+    }
+
+    #[test]
+    fn dfa_structural_diff_with_itself(mut dfa in dfa(20, 10)) {
+        dfa.remove_unreachable_states();
+        let diff = dfa.structural_diff(&dfa.clone());
+        assert!(diff.is_identical());
+        assert_eq!(diff.matched.len(), dfa.states().len());
+        assert!(diff.matched.iter().all(|(a, b)| a == b));
+    }
+
+    #[test]
+    fn nfa_minimize_via_dfa(nfa in nfa(10, 4)) {
+        let minimized = nfa.to_minimized_nfa();
+        assert!(minimized.equivalent_to(&nfa));
+        assert!(!minimized.has_epsilon_moves());
+
+        let mut in_place = nfa.clone();
+        in_place.minimize_via_dfa();
+        assert_eq!(in_place.states().len(), minimized.states().len());
+        assert!(in_place.equivalent_to(&nfa));
+    }
+
+    #[test]
+    fn dfa_product_many(
+        dfa1 in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        dfa2 in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        dfa3 in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count())
+    ) {
+        let dfas = [&dfa1, &dfa2, &dfa3];
+        let union = Dfa::union_many(&dfas).unwrap();
+        assert!(union.equivalent_to(&dfa1.union(&dfa2).unwrap().union(&dfa3).unwrap()));
+        let intersection = Dfa::intersection_many(&dfas).unwrap();
+        assert!(intersection.equivalent_to(&dfa1.intersection(&dfa2).unwrap().intersection(&dfa3).unwrap()));
+
+        let two_of_three = Dfa::product_many(&dfas, |states| {
+            states.iter().filter(|s| s.is_accepting()).count() >= 2
+        }).unwrap();
+        let alphabet = dfa1.alphabet().iter().map(|elem| elem.as_ref()).collect::<Vec<_>>();
+        for word in MultipleCounterIter::new(5, alphabet.len() - 1) {
+            let word = word.into_iter().map(|idx| alphabet[idx]).collect::<Vec<_>>();
+            let accepted = dfas.iter().filter(|dfa| dfa.accepts(&word)).count();
+            assert_eq!(two_of_three.accepts(&word), accepted >= 2);
+        }
+    }
+
+    /// Tests that the union and intersection of many NFAs are equivalent to folding the pairwise constructions
+    #[test]
+    fn nfa_union_all_and_intersect_all(nfas in prop::collection::vec(fixed_alphabet_nfa(5, 'a'..='b', 2), 1..5)) {
+        let (first, rest) = nfas.split_first().unwrap();
+        let union = Nfa::union_all(nfas.clone()).unwrap();
+        let folded = rest.iter().fold(first.clone(), |union, nfa| union.union(nfa.clone()).unwrap());
+        assert_eq!(union.states().len(), folded.states().len() - (nfas.len() - 1) + 1);
+        assert!(union.equivalent_to(&folded));
+
+        let intersection = Nfa::intersect_all(nfas.clone()).unwrap();
+        let folded = rest.iter().fold(first.clone(), |intersection, nfa| intersection.intersection(nfa).unwrap());
+        assert!(intersection.equivalent_to(&folded));
+    }
+
+    /// Tests that pruning the unused symbols of a DFA keeps the words over the remaining symbols, and that no
+    /// accepted word contains a pruned symbol
+    #[test]
+    fn dfa_prune_symbols(dfa in fixed_alphabet_dfa(6, 'a'..='c', ('a'..='c').count())) {
+        let mut pruned = dfa.clone();
+        let removed = pruned.prune_symbols();
+        assert_eq!(removed, dfa.unused_symbols());
+        assert!(pruned.unused_symbols().is_empty());
+        assert_eq!(pruned.alphabet().len() + removed.len(), dfa.alphabet().len());
+
+        let alphabet = dfa.alphabet().iter().map(|elem| elem.as_ref()).collect::<Vec<_>>();
+        for word in MultipleCounterIter::new(4, alphabet.len() - 1) {
+            let word = word.into_iter().map(|idx| alphabet[idx]).collect::<Vec<_>>();
+            if word.iter().any(|elem| removed.iter().any(|r| r.as_ref() == *elem)) {
+                assert!(!dfa.accepts(&word));
+            } else {
+                assert_eq!(pruned.accepts(&word), dfa.accepts(&word));
+            }
+        }
+    }
+
+    #[test]
+    fn dfa_bfs_tree_gives_shortest_words(dfa in fixed_alphabet_dfa(8, 'a'..='b', ('a'..='b').count())) {
+        let tree = dfa.bfs_tree();
+        let reachable = dfa.reachable_state_idx();
+        let mut depths = vec![None; dfa.states().len()];
+        dfa.bfs(|event| {
+            depths[event.state] = Some(event.depth);
+            ControlFlow::Continue(())
+        });
+
+        // The states reached by words of each length, for finding the first length reaching each state
+        let mut shortest = vec![None; dfa.states().len()];
+        let mut layer = BTreeSet::from([dfa.initial_state_index()]);
+        for len in 0..=dfa.states().len() {
+            for &state in &layer {
+                shortest[state].get_or_insert(len);
+            }
+            layer = layer
+                .iter()
+                .flat_map(|&state| dfa.states()[state].transitions().iter().copied())
+                .collect();
+        }
+
+        for state in 0..dfa.states().len() {
+            assert_eq!(reachable.contains(&state), shortest[state].is_some());
+            assert_eq!(depths[state], shortest[state]);
+            let mut word = vec![];
+            let mut current = state;
+            while let Some((parent, symbol)) = tree[current] {
+                word.push(dfa.alphabet()[symbol].as_ref());
+                current = parent;
+            }
+            if !reachable.contains(&state) {
+                assert!(word.is_empty());
+                continue;
+            }
+            word.reverse();
+            assert_eq!(Some(word.len()), shortest[state]);
+            let mut eval = dfa.evaluator();
+            eval.step_multiple(&word);
+            assert_eq!(eval.current_state_idx(), state);
+        }
+    }
+
+    #[test]
+    fn nfa_bfs_subsets(nfa in fixed_alphabet_nfa(6, 'a'..='b', ('a'..='b').count())) {
+        let mut subsets: Vec<(BfsEvent, Vec<usize>)> = vec![];
+        nfa.bfs_subsets(|event, subset| {
+            subsets.push((event, subset.to_vec()));
+            ControlFlow::Continue(())
+        });
+        assert_eq!(subsets.len(), nfa.to_dfa().states().len());
+        for (idx, (event, subset)) in subsets.iter().enumerate() {
+            assert_eq!(event.state, idx);
+            // Following the parents gives a word leading to the same set of states
+            let mut word = vec![];
+            let mut current = *event;
+            while let (Some(parent), Some(symbol)) = (current.parent, current.symbol) {
+                word.push(nfa.alphabet()[symbol].as_ref());
+                current = subsets[parent].0;
+            }
+            word.reverse();
+            assert_eq!(word.len(), event.depth);
+            let mut eval = nfa.evaluator();
+            eval.step_multiple(&word);
+            assert_eq!(&eval.state_key(), subset);
+        }
+    }
+
+    #[test]
+    fn nfa_duplicate_targets_words(
+        nfa in fixed_alphabet_nfa(6, 'a'..='b', ('a'..='b').count()),
+        max_len in 0usize..6
+    ) {
+        let mut nfa = nfa;
+        nfa.remove_epsilon_moves();
+        let mut duplicated = nfa.clone();
+        for transitions in duplicated.states.iter_mut().flat_map(|state| &mut state.transitions) {
+            let copy = transitions.clone();
+            transitions.extend(copy.into_iter().rev());
+        }
+        let words = duplicated.words_up_to_length(max_len).collect::<Vec<_>>();
+        assert_eq!(words.iter().collect::<HashSet<_>>().len(), words.len());
+        assert_eq!(words, nfa.words_up_to_length(max_len).collect::<Vec<_>>());
+        assert_eq!(duplicated.words().size_hint(), nfa.words().size_hint());
+        assert!(words.iter().all(|word| word.graphemes(true).count() <= max_len));
+    }
+
+    #[test]
+    fn nfa_right_linear_grammar_round_trip(
+        nfa in fixed_alphabet_nfa(6, 'a'..='b', ('a'..='b').count())
+    ) {
+        let alphabet = nfa.alphabet().iter().map(|elem| &**elem).collect::<Vec<_>>();
+        let converted = nfa.to_right_linear_grammar().to_nfa_with_alphabet(&alphabet).unwrap();
+        assert!(converted.equivalent_to(&nfa));
+        let mut without_epsilon = nfa.clone();
+        without_epsilon.remove_epsilon_moves();
+        let grammar = without_epsilon.to_right_linear_grammar();
+        assert!(grammar
+            .productions
+            .iter()
+            .all(|(_, rhs)| !matches!(rhs[..], [grammar::GrammarSymbol::NonTerminal(_)])));
+        assert!(grammar.to_nfa_with_alphabet(&alphabet).unwrap().equivalent_to(&nfa));
+    }
+
+    #[test]
+    fn dfa_coverage_merge(
+        dfa in fixed_alphabet_dfa(8, 'a'..='b', ('a'..='b').count()),
+        words in prop::collection::vec("[a-c]{0,8}", 0..20),
+        split in 0usize..20
+    ) {
+        let split = split.min(words.len());
+        let coverage = dfa.coverage(words.iter().map(String::as_str));
+        let mut merged = dfa.coverage(words[..split].iter().map(String::as_str));
+        merged.merge(&dfa.coverage(words[split..].iter().map(String::as_str)));
+        prop_assert_eq!(&merged, &coverage);
+        // Every visit except the first of each input comes from taking a transition
+        let visits = coverage.state_visits.iter().sum::<usize>();
+        let taken = coverage.transition_counts.iter().flatten().sum::<usize>();
+        prop_assert_eq!(visits, taken + words.len());
+        let never_visits = |word: &String, name: &str| {
+            let mut eval = dfa.evaluator();
+            // The evaluation stops at the first grapheme which isn't in the alphabet
+            word.graphemes(true)
+                .map_while(|g| eval.step(g).map(|state| state.name() != name))
+                .all(|unvisited| unvisited)
+        };
+        for name in coverage.unvisited_states() {
+            prop_assert!(words.iter().all(|word| never_visits(word, name)));
+        }
+    }
+
+    #[test]
+    fn parsed_round_trip(
+        dfa in fixed_alphabet_dfa(6, 'a'..='b', 2),
+        nfa in fixed_alphabet_nfa(6, 'a'..='b', 2)) {
+        prop_assert_eq!(Dfa::try_from(dfa.to_parsed()).unwrap(), dfa.clone());
+        let parsed: parser::ParsedDfaOwned = parser::dfa(&dfa.to_table()).unwrap().into();
+        prop_assert_eq!(parsed, dfa.to_parsed());
+        prop_assert_eq!(Nfa::try_from(nfa.to_parsed()).unwrap().to_table(), nfa.to_table());
+        let parsed: parser::ParsedNfaOwned = parser::nfa(&nfa.to_table()).unwrap().into();
+        prop_assert_eq!(parsed, nfa.to_parsed());
+    }
+
+    #[test]
+    fn nfa_words_size_hint(
+        dfa in fixed_alphabet_dfa(6, 'a'..='b', ('a'..='b').count()),
+        k in 0usize..6
+    ) {
+        // Since the NFA has no nondeterminism, the number of accepting paths is exactly the number of words
+        let nfa = dfa.clone().to_nfa();
+        let n = nfa.states().len();
+        let infinite = (n..2 * n).any(|len| nfa.words_of_length(len).next().is_some());
+        let mut words = nfa.words();
+        match words.size_hint() {
+            (_, None) => assert!(infinite),
+            (_, Some(bound)) => {
+                assert!(!infinite);
+                words.next();
+                assert_eq!(words.size_hint().1, Some(bound.saturating_sub(1)));
+                assert_eq!(nfa.words().count(), bound);
+            }
+        }
+
+        let of_length = nfa.words_of_length(k);
+        let expected = dfa.count_words_of_length(k) as usize;
+        assert_eq!(of_length.size_hint().1, Some(expected));
+        assert_eq!(of_length.count(), expected);
+    }
+
+    #[test]
+    fn nfa_words_resume_from(
+        mut nfa in fixed_alphabet_nfa(5, 'a'..='c', ('a'..='c').count()),
+        k in 0usize..10
+    ) {
+        use crate::nfa::words::WordComponentIndices;
+        nfa.remove_epsilon_moves();
+        let words = WordComponentIndices::new(&nfa).take(20).collect::<Vec<_>>();
+        for (idx, word) in words.iter().enumerate().skip(k) {
+            let resumed = WordComponentIndices::resume_from(&nfa, word).unwrap();
+            assert_eq!(resumed.take(20 - idx - 1).collect::<Vec<_>>(), &words[idx + 1..]);
+        }
+
+        // Words which aren't accepted can't be resumed from
+        for word in MultipleCounterIter::new(3, nfa.alphabet().len() - 1) {
+            let elems = word.iter().map(|&idx| nfa.alphabet()[idx].as_ref()).collect::<Vec<_>>();
+            let resumed = WordComponentIndices::resume_from(&nfa, &word);
+            assert_eq!(resumed.is_some(), nfa.accepts(&elems));
+        }
+        assert!(WordComponentIndices::resume_from(&nfa, &[3]).is_none());
+    }
+
+    #[test]
+    fn words_with_states(
+        mut nfa in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count()),
+        dfa in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count())
+    ) {
+        nfa.remove_epsilon_moves();
+        for (word, states) in nfa.words_with_states().take(50) {
+            let mut eval = nfa.evaluator();
+            word.chars().for_each(|c| { eval.step(&c.to_string()); });
+            let mut expected = eval.current_states_idx().iter().copied().collect::<Vec<_>>();
+            expected.sort_unstable();
+            let expected = expected.into_iter()
+                .map(|idx| &nfa.states()[idx])
+                .filter(|state| state.is_accepting())
+                .map(|state| state.name())
+                .collect::<Vec<_>>();
+            assert!(!states.is_empty());
+            assert_eq!(states.iter().map(|s| s.as_ref()).collect::<Vec<_>>(), expected);
+        }
+
+        for (word, state) in dfa.words_with_states().take(50) {
+            let mut eval = dfa.evaluator();
+            word.chars().for_each(|c| { eval.step(&c.to_string()); });
+            assert!(eval.is_accepting());
+            assert_eq!(eval.current_state().unwrap().name(), state.as_ref());
+        }
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn dfa_normalization_keeps_normalized_dfas(
+        dfa in fixed_alphabet_dfa(8, 'a'..='c', ('a'..='c').count()),
+        words in prop::collection::vec("[a-c]{0,8}", 0..10)
+    ) {
+        use crate::dfa::{NormalizationError, NormalizationForm};
+        let forms = [NormalizationForm::Nfc, NormalizationForm::Nfd, NormalizationForm::Nfkc, NormalizationForm::Nfkd];
+        for form in forms {
+            // The alphabet is already normalized, but the generated state names may not be
+            let normalized = match dfa.normalized(form) {
+                Ok(normalized) => normalized,
+                Err(error) => {
+                    prop_assert!(matches!(error, NormalizationError::StateCollision(..)));
+                    continue;
+                }
+            };
+            prop_assert_eq!(normalized.normalized(form).unwrap(), normalized.clone());
+            prop_assert_eq!(normalized.alphabet(), dfa.alphabet());
+            for word in &words {
+                let mut eval = dfa.evaluator();
+                let mut normalized_eval = normalized.evaluator();
+                for grapheme in word.graphemes(true) {
+                    let state = eval.step(grapheme).map(|state| form.normalize(state.name()));
+                    prop_assert_eq!(normalized_eval.step(grapheme).map(|state| state.name().to_string()), state);
+                }
+                prop_assert_eq!(normalized.accepts_graphemes(word), dfa.accepts_graphemes(word));
+            }
+        }
+    }
+
+    #[cfg(feature = "fast-matrix")]
+    #[test]
+    fn bit_matrix_mul(
+        (n, a, b) in (1usize..80).prop_flat_map(|n| (
+            Just(n),
+            prop::collection::vec(any::<bool>(), n * n),
+            prop::collection::vec(any::<bool>(), n * n)
+        ))
+    ) {
+        use crate::nfa::bit_matrix::BitMatrix;
+        use crate::nfa::words::NumBool;
+        use nalgebra::DMatrix;
+
+        let bit_a = BitMatrix::from_fn(n, n, |r, c| a[r * n + c].into());
+        let bit_b = BitMatrix::from_fn(n, n, |r, c| b[r * n + c].into());
+        let dense_a = DMatrix::<NumBool>::from_fn(n, n, |r, c| a[r * n + c].into());
+        let dense_b = DMatrix::<NumBool>::from_fn(n, n, |r, c| b[r * n + c].into());
+        let bit = &bit_a * &bit_b;
+        let dense = &dense_a * &dense_b;
+        for r in 0..n {
+            for c in 0..n {
+                assert_eq!(bit[(r, c)], dense[(r, c)]);
+            }
+        }
+    }
+
+    #[test]
+    fn nfa_antichain(
+        nfa1 in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count()),
+        nfa2 in fixed_alphabet_nfa(8, 'a'..='c', ('a'..='c').count())
+    ) {
+        assert_eq!(nfa1.equivalent_to_antichain(&nfa2), Some(nfa1.equivalent_to(&nfa2)));
+        let difference = nfa1.to_dfa().difference(&nfa2.to_dfa()).unwrap();
+        assert_eq!(nfa1.subset_of_antichain(&nfa2), Some(!difference.has_reachable_accepting_state()));
+
+        let determinized = nfa1.to_dfa().to_nfa();
+        assert_eq!(nfa1.equivalent_to_antichain(&determinized), Some(true));
+        let union = nfa1.clone().union(nfa2.clone()).unwrap();
+        assert_eq!(nfa1.subset_of_antichain(&union), Some(true));
+    }
+
+    #[test]
+    fn isomorphic_to_reversed_copy(mut dfa in dfa(20, 10), nfa in nfa(10, 4)) {
+        let reverse = |idx: usize, len: usize| len - 1 - idx;
+
+        let mut reversed = dfa.clone();
+        let len = reversed.states.len();
+        reversed.states.reverse();
+        reversed.states.iter_mut().for_each(|state| {
+            state.transitions.iter_mut().for_each(|to| *to = reverse(*to, len));
+        });
+        reversed.initial_state = reverse(reversed.initial_state, len);
+        assert!(dfa.isomorphic_to(&reversed));
+        dfa.remove_unreachable_states();
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        assert_eq!(dfa.isomorphic_to(&minimized), dfa.states().len() == minimized.states().len());
+
+        let mut reversed = nfa.clone();
+        let len = reversed.states.len();
+        reversed.states.reverse();
+        reversed.states.iter_mut().for_each(|state| {
+            state
+                .transitions
+                .iter_mut()
+                .chain([&mut state.epsilon_transitions])
+                .flatten()
+                .for_each(|to| *to = reverse(*to, len));
+        });
+        reversed.initial_state = reverse(reversed.initial_state, len);
+        assert_eq!(nfa.isomorphic_to(&reversed), Some(true));
+    }
+
+    /// Compares the quotients to their definitions, over all words of length at most 5. A shortest word `u` showing
+    /// that a word is in a quotient is shorter than the number of pairs of states, so those are all checked.
+    #[test]
+    fn dfa_quotients(
+        dfa1 in fixed_alphabet_dfa(3, 'a'..='b', 2),
+        dfa2 in fixed_alphabet_dfa(3, 'a'..='b', 2)
+    ) {
+        let left = dfa1.left_quotient(&dfa2).unwrap();
+        let right = dfa1.right_quotient(&dfa2).unwrap();
+        let words = |max_len| {
+            MultipleCounterIter::new(max_len, 1)
+                .map(|word| word.into_iter().map(|idx| dfa1.alphabet()[idx].clone()).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        let accepts = |dfa: &Dfa, word: &[Rc<str>]| dfa.accepts(&word.iter().map(|s| s.as_ref()).collect::<Vec<_>>());
+        let witnesses = words(dfa1.states().len() * dfa2.states().len())
+            .into_iter()
+            .filter(|u| accepts(&dfa2, u))
+            .collect::<Vec<_>>();
+        for w in words(5) {
+            let in_left = witnesses.iter().any(|u| accepts(&dfa1, &[u.as_slice(), &w].concat()));
+            let in_right = witnesses.iter().any(|u| accepts(&dfa1, &[w.as_slice(), u].concat()));
+            let w = w.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
+            assert_eq!(left.accepts(&w), in_left, "left quotient on {w:?}");
+            assert_eq!(right.accepts(&w), in_right, "right quotient on {w:?}");
+        }
+    }
+
+    /// Tests that merging equivalent elements of the alphabet keeps the accepted words (over the original alphabet)
+    /// through the symbol map
+    #[test]
+    fn merge_equivalent_symbols(
+        dfa in fixed_alphabet_dfa(3, 'a'..='d', 4),
+        nfa in fixed_alphabet_nfa(3, 'a'..='d', 4)
+    ) {
+        let mut merged_dfa = dfa.clone();
+        let dfa_map = dfa::SymbolMap::new(&merged_dfa.merge_equivalent_symbols());
+        assert_eq!(merged_dfa.alphabet().len(), dfa.symbol_equivalence_classes().len());
+        let mut merged_nfa = nfa.clone();
+        let nfa_map = dfa::SymbolMap::new(&merged_nfa.merge_equivalent_symbols());
+        assert_eq!(merged_nfa.alphabet().len(), nfa.symbol_equivalence_classes().len());
+        for word in MultipleCounterIter::new(4, 3) {
+            let dfa_word = word.iter().map(|&idx| dfa.alphabet()[idx].as_ref()).collect::<Vec<_>>();
+            assert_eq!(merged_dfa.accepts_with_symbol_map(&dfa_word, &dfa_map), dfa.accepts(&dfa_word));
+            let nfa_word = word.iter().map(|&idx| nfa.alphabet()[idx].as_ref()).collect::<Vec<_>>();
+            assert_eq!(merged_nfa.accepts_with_symbol_map(&nfa_word, &nfa_map), nfa.accepts(&nfa_word));
+        }
+    }
+
+    /// Tests that DFAs are never ambiguous as NFAs, and that a word with two accepting runs (found by counting the
+    /// runs of every short word) makes a NFA without epsilon moves ambiguous
+    #[test]
+    fn nfa_ambiguity_runs(
+        dfa in fixed_alphabet_dfa(6, 'a'..='b', 2),
+        nfa in fixed_alphabet_nfa(5, 'a'..='b', 2)
+    ) {
+        let dfa_as_nfa = dfa.to_nfa();
+        prop_assert!(!dfa_as_nfa.is_ambiguous());
+        prop_assert!(dfa_as_nfa.max_branching() <= 1);
+        prop_assert!(dfa_as_nfa.nondeterministic_states().is_empty());
+
+        let mut nfa = nfa;
+        nfa.states.iter_mut().for_each(|state| state.epsilon_transitions.clear());
+        let has_ambiguous_word = MultipleCounterIter::new(6, 1).any(|word| {
+            let mut runs = vec![0usize; nfa.states.len()];
+            runs[nfa.initial_state] = 1;
+            for elem in word {
+                let mut next = vec![0; nfa.states.len()];
+                for (state, count) in runs.iter().enumerate() {
+                    for &to in &nfa.states[state].transitions[elem] {
+                        next[to] += count;
+                    }
+                }
+                runs = next;
+            }
+            let accepting = nfa.states.iter().zip(runs).filter(|(s, _)| s.accepting);
+            accepting.map(|(_, count)| count).sum::<usize>() > 1
+        });
+        if has_ambiguous_word {
+            prop_assert!(nfa.is_ambiguous());
+        }
+    }
+
+    /// Tests that the equivalence trace agrees with equivalent_to, and that each word leads to the visited states
+    #[test]
+    fn dfa_equivalence_trace(
+        dfa1 in fixed_alphabet_dfa(8, 'a'..='c', 3),
+        dfa2 in fixed_alphabet_dfa(8, 'a'..='c', 3)
+    ) {
+        let mut visited = HashSet::new();
+        let result = dfa1.equivalence_trace(&dfa2, |word, s1, s2| {
+            let word = word.iter().map(|elem| elem.as_ref()).collect::<Vec<_>>();
+            let mut eval1 = dfa1.evaluator();
+            eval1.step_multiple(&word);
+            let mut eval2 = dfa2.evaluator();
+            eval2.step_multiple(&word);
+            assert_eq!(eval1.current_state(), Some(s1));
+            assert_eq!(eval2.current_state(), Some(s2));
+            assert!(visited.insert((s1.name().to_string(), s2.name().to_string())));
+            std::ops::ControlFlow::Continue(())
+        });
+        prop_assert_eq!(result, Some(dfa1.equivalent_to(&dfa2)));
+        prop_assert_eq!(dfa1.equivalence_trace(&dfa2, |_, _, _| std::ops::ControlFlow::Break(())), None);
+    }
+
+    /// Tests that merging non-distinguishable states except some protected states keeps the protected states and the
+    /// language, and that protecting no states merges as many states as minimizing
+    #[test]
+    fn dfa_merge_except_protected(dfa in dfa(25, 10), protect in prop::collection::vec(any::<bool>(), 25)) {
+        let protected = dfa
+            .states()
+            .iter()
+            .zip(&protect)
+            .filter(|(_, &protect)| protect)
+            .map(|(state, _)| state.name())
+            .collect::<Vec<_>>();
+        let mut merged = dfa.clone();
+        let merges = merged.merge_nondistinguishable_states_except(&protected);
+        crate::assert_equivalent!(merged, dfa);
+        prop_assert_eq!(merged.states().len() + merges.len(), dfa.states().len());
+        for name in &protected {
+            prop_assert!(merged.states().iter().any(|state| state.name() == *name));
+        }
+
+        let mut unprotected = dfa.clone();
+        unprotected.merge_nondistinguishable_states_except(&[]);
+        unprotected.remove_unreachable_states();
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        prop_assert_eq!(unprotected.states().len(), minimized.states().len());
+    }
+
+    /// Tests that the rows of cells of a table are the cells of each line of the table
+    #[test]
+    fn table_rows(dfa in dfa(20, 20), nfa in nfa(20, 20)) {
+        let words = |text: &str| text.split_whitespace().map(str::to_string).collect::<Vec<_>>();
+        for (table, rows) in [(dfa.to_table(), dfa.to_table_rows()), (nfa.to_table(), nfa.to_table_rows())] {
+            prop_assert_eq!(table.lines().count(), rows.len());
+            for (line, row) in table.lines().zip(&rows) {
+                prop_assert_eq!(words(line), words(&row.join(" ")));
+            }
+        }
+    }
+
+    #[test]
+    fn minimal_size_matches_minimize(dfa in dfa(20, 5), nfa in nfa(8, 3)) {
+        let mut minimized = dfa.clone();
+        minimized.minimize();
+        prop_assert_eq!(dfa.minimal_size(), minimized.states().len());
+        let mut subset = nfa.to_dfa();
+        subset.minimize();
+        prop_assert_eq!(nfa.minimal_dfa_size_bounded(usize::MAX), Ok(subset.states().len()));
+    }
+
+    #[test]
+    fn transition_tables(dfa in dfa(20, 10)) {
+        let table = dfa.transition_table();
+        let accepting = dfa.accepting_vector();
+        let initial = dfa.initial_state_vector();
+        for (idx, state) in dfa.states().iter().enumerate() {
+            assert_eq!(table[idx], state.transitions());
+            assert_eq!(accepting[idx], state.is_accepting());
+            assert_eq!(initial[idx], idx == dfa.initial_state_index());
+        }
+        let nfa = dfa.clone().to_nfa();
+        let sets = nfa.transition_sets();
+        assert_eq!(sets.len(), table.len());
+        for (sets, row) in sets.iter().zip(&table) {
+            assert_eq!(sets, &row.iter().map(|&to| vec![to]).collect::<Vec<_>>());
+        }
+    }
+
     #[test]
     fn regex_parse(regex_str in random_regex("[a-zε∅]")) {
         let parse1 = parser::regex(&regex_str).unwrap();
@@ -322,7 +4114,10 @@ prop_compose! {
         Nfa {
             alphabet,
             states,
-            initial_state
+            initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::none()
         }
     }
 }
@@ -358,7 +4153,10 @@ prop_compose! {
         Nfa {
             alphabet: alphabet.iter().map(|entry| Rc::from(entry.as_str())).collect(),
             states,
-            initial_state
+            initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            provenance: Provenance::none()
         }
     }
 }
@@ -382,6 +4180,7 @@ prop_compose! {
                 name: Rc::from(state_name.as_str()),
                 initial: idx == initial_state,
                 accepting,
+                priority: None,
                 transitions
             }
         ).collect();
@@ -393,7 +4192,11 @@ prop_compose! {
         Dfa {
             alphabet,
             states,
-            initial_state
+            initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+            provenance: Provenance::none()
         }
     }
 }
@@ -418,6 +4221,7 @@ prop_compose! {
                 name: Rc::from(state_name.as_str()),
                 initial: idx == initial_state,
                 accepting,
+                priority: None,
                 transitions
             }
         ).collect();
@@ -425,7 +4229,11 @@ prop_compose! {
         Dfa {
             alphabet: alphabet.iter().map(|entry| Rc::from(entry.as_str())).collect(),
             states,
-            initial_state
+            initial_state,
+            alphabet_lookup: AlphabetIndex::default(),
+            version: Version::default(),
+            reverse_transitions: ReverseIndex::default(),
+            provenance: Provenance::none()
         }
     }
 }
@@ -477,7 +4285,7 @@ prop_compose! {
 
 prop_compose! {
     fn state_names(count: usize)
-        (names in filtered_set(count, r"[^\s#{}]+", &["ε", "eps", "→", "->", "*"]))
+        (names in filtered_set(count, r#"([^\s]|[#{}"])+"#, &["ε", "eps", "→", "->", "*"]))
     -> HashSet<String> {
         names
     }
@@ -493,7 +4301,7 @@ prop_compose! {
 
 prop_compose! {
     fn alphabet_elems(count: usize)
-        (names in filtered_set(count, r"[^\s#{}]+", &["ε", "eps", "→", "->", "*"]))
+        (names in filtered_set(count, r#"([^\s]|[#{}"])+"#, &["ε", "eps", "→", "->", "*"]))
     -> HashSet<String> {
         names
     }
@@ -1,13 +1,379 @@
-use std::collections::HashSet;
+use std::cell::OnceCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::mem;
 use std::rc::Rc;
+use unicode_segmentation::UnicodeSegmentation;
 
 #[inline]
 pub fn alphabet_equal(a: &[Rc<str>], b: &[Rc<str>]) -> bool {
-    if a.len() != b.len() {
-        return false;
+    a.len() == b.len() && AlphabetDiff::new(a, b).is_equal()
+}
+
+/// Checks if a string may be an element of an alphabet. The empty string would match at every position of a word, and
+/// elements starting or ending with whitespace are too easily confused with the same elements without it, so both are
+/// rejected when automata are validated. Whitespace inside elements is allowed, since tables quote such elements.
+pub fn valid_symbol(symbol: &str) -> bool {
+    !symbol.is_empty() && symbol.trim() == symbol
+}
+
+/// Finds the graphemes of the words of a corpus which aren't elements of an alphabet, given the index of an element in
+/// the alphabet, see [Dfa::missing_symbols_for](crate::dfa::Dfa::missing_symbols_for)
+pub fn missing_graphemes<'a>(
+    corpus: impl Iterator<Item = &'a str>,
+    index: impl Fn(&str) -> Option<usize>,
+) -> BTreeSet<String> {
+    corpus
+        .flat_map(|word| word.graphemes(true))
+        .filter(|grapheme| index(grapheme).is_none())
+        .map(str::to_string)
+        .collect()
+}
+
+/// How the alphabets of two automata differ, as given by [Dfa::alphabet_diff](crate::dfa::Dfa::alphabet_diff). All
+/// three lists are sorted, and the order of the alphabets themselves doesn't matter.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AlphabetDiff {
+    /// The elements which are in both alphabets
+    pub shared: Vec<Rc<str>>,
+    /// The elements which are only in the alphabet of the first automaton
+    pub only_in_self: Vec<Rc<str>>,
+    /// The elements which are only in the alphabet of the second automaton
+    pub only_in_other: Vec<Rc<str>>,
+}
+
+impl AlphabetDiff {
+    pub(crate) fn new(this: &[Rc<str>], other: &[Rc<str>]) -> Self {
+        let this_set = this.iter().collect::<HashSet<_>>();
+        let other_set = other.iter().collect::<HashSet<_>>();
+        let sorted = |elems: Vec<&Rc<str>>| {
+            let mut elems = elems.into_iter().cloned().collect::<Vec<_>>();
+            elems.sort();
+            elems
+        };
+        Self {
+            shared: sorted(this_set.intersection(&other_set).copied().collect()),
+            only_in_self: sorted(this_set.difference(&other_set).copied().collect()),
+            only_in_other: sorted(other_set.difference(&this_set).copied().collect()),
+        }
+    }
+
+    /// Checks if the alphabets contain the same elements, that is, if no element is only in one of them
+    pub fn is_equal(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty()
+    }
+}
+
+/// A state found by a breadth-first search from the initial state, as given to the visitor of [Dfa::bfs] (and
+/// [Nfa::bfs_subsets](crate::nfa::Nfa::bfs_subsets), where `state` and `parent` are the numbers of sets of states in
+/// the order they were found).
+///
+/// [Dfa::bfs]: crate::dfa::Dfa::bfs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BfsEvent {
+    /// The index of the state which was found
+    pub state: usize,
+    /// The index of the state it was found from, which is `None` for the initial state
+    pub parent: Option<usize>,
+    /// The index of the element of the alphabet on which the parent transitions to the state, which is `None` for the
+    /// initial state
+    pub symbol: Option<usize>,
+    /// The length of the shortest words reaching the state
+    pub depth: usize,
+}
+
+/// A 64-bit FNV-1a hasher. Unlike the hashers of the standard library, the output of this hasher is stable across
+/// platforms and Rust versions, so it can be used for fingerprints that are stored or sent to other machines.
+pub struct Fnv64(u64);
+
+impl Fnv64 {
+    pub fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    pub fn write_usize(&mut self, n: usize) {
+        // Always hash as 64 bits, to get the same result on 32-bit platforms (such as wasm)
+        self.write_bytes(&(n as u64).to_le_bytes());
+    }
+
+    pub fn write_bool(&mut self, b: bool) {
+        self.write_bytes(&[b as u8]);
+    }
+
+    pub fn write_str(&mut self, s: &str) {
+        // Length prefix, so that ["ab", "c"] and ["a", "bc"] differ
+        self.write_usize(s.len());
+        self.write_bytes(s.as_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The maximum number of characters of a [Provenance] description, before it is cut off
+#[cfg(feature = "provenance")]
+pub const MAX_PROVENANCE_LEN: usize = 200;
+
+#[cfg(feature = "provenance")]
+fn truncate_provenance(mut description: String) -> String {
+    if let Some((idx, _)) = description.char_indices().nth(MAX_PROVENANCE_LEN) {
+        description.truncate(idx);
+        description.push('…');
+    }
+    description
+}
+
+/// A description of how an automaton was created, such as "subset construction from parsed NFA". The provenance is
+/// only recorded if the `provenance` feature is enabled, otherwise this is a zero-sized type and recording a
+/// provenance does nothing. Two provenances always compare equal, since automata with the same structure are equal
+/// no matter how they were created. The description is stored as a `Rc<String>` rather than a `Rc<str>` to keep
+/// the provenance one pointer wide, so that it adds as little as possible to the size of the automata.
+#[derive(Clone, Debug, Default)]
+pub struct Provenance(#[cfg(feature = "provenance")] Option<Rc<String>>);
+
+impl Provenance {
+    /// The provenance of an automaton parsed from text
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Records a provenance. The description is only computed if the `provenance` feature is enabled. Descriptions
+    /// longer than `MAX_PROVENANCE_LEN` characters are cut off with a `…`, since the descriptions of automata created
+    /// by many operations in a row would otherwise grow with every operation.
+    #[allow(unused_variables)]
+    pub fn new(description: impl FnOnce() -> String) -> Self {
+        #[cfg(feature = "provenance")]
+        return Self(Some(Rc::new(truncate_provenance(description()))));
+        #[cfg(not(feature = "provenance"))]
+        return Self();
+    }
+
+    #[cfg(feature = "provenance")]
+    pub fn get(&self) -> Option<&str> {
+        self.0.as_deref().map(String::as_str)
+    }
+
+    /// Describes an automaton of the given kind ("DFA" or "NFA") with this provenance, to be used as part of the
+    /// provenance of another automaton
+    #[allow(unused_variables)]
+    pub fn describe(&self, kind: &str) -> String {
+        #[cfg(feature = "provenance")]
+        if let Some(provenance) = &self.0 {
+            return format!("{kind} created by {provenance}");
+        }
+        format!("parsed {kind}")
+    }
+}
+
+/// A counter of the modifications of an automaton, which is increased by every method mutating it. Just as
+/// [Provenance], two versions always compare equal, since automata with the same structure are equal no matter how
+/// many times they have been modified.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Version(u64);
+
+impl Version {
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    pub fn bump(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Version {}
+
+/// The approximate number of bytes allocated for a `Rc<str>`, that is, the string itself and the two reference
+/// counts stored next to it
+pub fn rc_str_bytes(s: &Rc<str>) -> usize {
+    2 * mem::size_of::<usize>() + s.len()
+}
+
+/// The approximate number of bytes allocated for an alphabet, which is the slice of `Rc<str>`s (with its reference
+/// counts) and every element of the alphabet
+pub fn alphabet_bytes(alphabet: &Rc<[Rc<str>]>) -> usize {
+    2 * mem::size_of::<usize>()
+        + mem::size_of_val(alphabet.as_ref())
+        + alphabet.iter().map(rc_str_bytes).sum::<usize>()
+}
+
+/// A lookup table from the elements of an alphabet to their indices, which is built the first time it is used. The
+/// table is owned by the automaton, and needs to be replaced by a new table whenever the alphabet of the automaton
+/// changes (which only happens when merging elements of the alphabet). Just as [Provenance], two lookup tables always
+/// compare equal, since they only depend on the alphabet. The table is kept behind a `Rc`, so that it is shared between
+/// clones and keeps the automata small.
+#[derive(Clone, Debug, Default)]
+pub struct AlphabetIndex(OnceCell<Rc<HashMap<Rc<str>, usize>>>);
+
+impl AlphabetIndex {
+    /// Gets the index of `symbol` in `alphabet`, building the lookup table if needed. `alphabet` must be the same
+    /// alphabet every time this is called.
+    pub fn get(&self, alphabet: &[Rc<str>], symbol: &str) -> Option<usize> {
+        self.0
+            .get_or_init(|| {
+                Rc::new(
+                    alphabet
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, elem)| (elem.clone(), idx))
+                        .collect(),
+                )
+            })
+            .get(symbol)
+            .copied()
+    }
+}
+
+impl PartialEq for AlphabetIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for AlphabetIndex {}
+
+/// The transitions of a DFA reversed, where `index[to][elem]` are the states transitioning to the state `to` on the
+/// element `elem` of the alphabet, in increasing order. Just as [AlphabetIndex], the index is built the first time it
+/// is used, is shared between clones, needs to be replaced by a new index whenever the transitions of the automaton
+/// change, and always compares equal.
+#[derive(Clone, Debug, Default)]
+pub struct ReverseIndex(OnceCell<Rc<Vec<Vec<Vec<usize>>>>>);
+
+impl ReverseIndex {
+    /// Gets the index, building it with `build` if needed. `build` must give the same index every time this is called.
+    pub fn get_or_init(&self, build: impl FnOnce() -> Vec<Vec<Vec<usize>>>) -> &[Vec<Vec<usize>>] {
+        self.0.get_or_init(|| Rc::new(build()))
+    }
+}
+
+impl PartialEq for ReverseIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
     }
+}
+
+impl Eq for ReverseIndex {}
 
-    let set1 = a.iter().collect::<HashSet<_>>();
-    let set2 = b.iter().collect::<HashSet<_>>();
-    set1 == set2
+impl PartialEq for Provenance {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Provenance {}
+
+/// How the indices of the states of an automaton changed when states were removed from it, returned by for example
+/// [Nfa::remove_epsilon_moves](crate::nfa::Nfa::remove_epsilon_moves) and [Dfa::minimize](crate::dfa::Dfa::minimize).
+/// The remaining states keep their relative order, so this is mostly useful for keeping indices of states from before
+/// the call (such as a highlighted state in a UI, or the provenance of the states of a NFA created from a regex) in
+/// sync with the automaton.
+///
+/// States merged into another state map to the state they were merged into, so several old indices may map to the
+/// same new index, in which case the new index maps back to the state which was kept. A state added to take over
+/// the role of another state (such as the new initial state added when removing epsilon moves) maps back to that
+/// state, and is mapped to from it if that state was removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndexRemap {
+    /// The new index of every old state, or `None` if it was removed
+    old_to_new: Vec<Option<usize>>,
+    /// The old index of every new state
+    new_to_old: Vec<usize>,
+}
+
+impl IndexRemap {
+    /// The remap of an automaton with `len` states where no state changed index
+    pub fn identity(len: usize) -> Self {
+        Self {
+            old_to_new: (0..len).map(Some).collect(),
+            new_to_old: (0..len).collect(),
+        }
+    }
+
+    /// Creates the remap where the state at index `new` is the old state `new_to_old[new]`, and where the other of
+    /// the `old_len` old states were removed. `new_to_old` must be strictly increasing.
+    pub(crate) fn from_kept(old_len: usize, new_to_old: Vec<usize>) -> Self {
+        let mut old_to_new = vec![None; old_len];
+        for (new, &old) in new_to_old.iter().enumerate() {
+            old_to_new[old] = Some(new);
+        }
+        Self {
+            old_to_new,
+            new_to_old,
+        }
+    }
+
+    /// Maps every old state which is a key of `merged` to where its value ended up, where the values must be kept
+    pub(crate) fn with_merged(mut self, merged: &HashMap<usize, usize>) -> Self {
+        for (&old, &into) in merged {
+            self.old_to_new[old] = self.old_to_new[into];
+        }
+        self
+    }
+
+    /// Replaces the old state `added`, which was added by the operation (and thus wasn't there before it), by the
+    /// state `like` whose role it took over. The old indices must be the ones before `added` was added, so `added` is
+    /// the last old index.
+    pub(crate) fn with_added(mut self, added: usize, like: usize) -> Self {
+        debug_assert_eq!(added + 1, self.old_to_new.len());
+        let new = self.old_to_new.pop().flatten();
+        if let Some(new) = new {
+            self.new_to_old[new] = like;
+            if self.old_to_new[like].is_none() {
+                self.old_to_new[like] = Some(new);
+            }
+        }
+        self
+    }
+
+    /// The new index of the state which had the index `old` before the operation, or `None` if it was removed (or if
+    /// there was no such state)
+    pub fn old_to_new(&self, old: usize) -> Option<usize> {
+        self.old_to_new.get(old).copied().flatten()
+    }
+
+    /// The index before the operation of the state which now has the index `new`. This panics if there is no state
+    /// with that index.
+    pub fn new_to_old(&self, new: usize) -> usize {
+        self.new_to_old[new]
+    }
+
+    /// The number of states before the operation
+    pub fn old_len(&self) -> usize {
+        self.old_to_new.len()
+    }
+
+    /// The number of states after the operation
+    pub fn new_len(&self) -> usize {
+        self.new_to_old.len()
+    }
+
+    /// Combines this remap with a remap of a later operation, giving the remap from the indices before this operation
+    /// to the indices after the later one
+    pub fn then(&self, next: &IndexRemap) -> IndexRemap {
+        IndexRemap {
+            old_to_new: self
+                .old_to_new
+                .iter()
+                .map(|new| new.and_then(|new| next.old_to_new(new)))
+                .collect(),
+            new_to_old: next
+                .new_to_old
+                .iter()
+                .map(|&new| self.new_to_old[new])
+                .collect(),
+        }
+    }
 }